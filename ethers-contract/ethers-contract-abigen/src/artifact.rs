@@ -0,0 +1,290 @@
+//! Lazily parsed, memory-mapped contract artifacts.
+//!
+//! Compiler artifacts often contain the AST, source maps and metadata of a contract, which are
+//! much larger than the ABI and bytecode most tools need. [`ArtifactReader`] maps the file into
+//! memory and only parses the sections which are requested.
+
+use ethers_core::{abi::Abi, types::Bytes};
+use eyre::{Context, Result};
+use memmap2::Mmap;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// A section of a contract artifact
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArtifactSection {
+    /// The JSON ABI
+    Abi,
+    /// The creation bytecode
+    Bytecode,
+    /// The runtime bytecode
+    DeployedBytecode,
+    /// The storage layout, as emitted by solc
+    StorageLayout,
+}
+
+/// The top level sections of an artifact, which borrow their unparsed JSON from the file
+#[derive(Default, Deserialize)]
+struct RawSections<'a> {
+    #[serde(borrow, default)]
+    abi: Option<&'a RawValue>,
+    #[serde(borrow, default, alias = "byteCode", alias = "bin")]
+    bytecode: Option<&'a RawValue>,
+    #[serde(
+        borrow,
+        default,
+        rename = "deployedBytecode",
+        alias = "deployedbytecode",
+        alias = "runtimeBin",
+        alias = "runtimebin"
+    )]
+    deployed_bytecode: Option<&'a RawValue>,
+    #[serde(borrow, default, rename = "storageLayout")]
+    storage_layout: Option<&'a RawValue>,
+    /// The nested bytecode of solc's standard JSON output
+    #[serde(borrow, default)]
+    evm: Option<&'a RawValue>,
+}
+
+/// The bytecode sections of the `evm` object of solc's standard JSON output
+#[derive(Deserialize)]
+struct RawEvmSections<'a> {
+    #[serde(borrow, default)]
+    bytecode: Option<&'a RawValue>,
+    #[serde(borrow, default, rename = "deployedBytecode")]
+    deployed_bytecode: Option<&'a RawValue>,
+}
+
+/// Bytecode, either as a hex string or as an object with the hex string in `object`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawBytecode {
+    Object { object: Bytes },
+    Bytes(Bytes),
+}
+
+/// A memory-mapped contract artifact, whose sections are parsed on demand.
+///
+/// Supports the artifacts of solc, Foundry, Hardhat and Truffle, and plain JSON ABI arrays.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_contract_abigen::ArtifactReader;
+///
+/// # fn foo() -> eyre::Result<()> {
+/// let artifact = ArtifactReader::open("out/Greeter.sol/Greeter.json")?;
+/// // only the ABI is parsed, the AST and source maps are skipped
+/// let abi = artifact.abi()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ArtifactReader {
+    path: PathBuf,
+    map: Mmap,
+}
+
+impl ArtifactReader {
+    /// Maps the artifact at `path` into memory, without parsing it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        // SAFETY: the map is read only, artifacts which are modified while they are read may
+        // produce invalid JSON, which is rejected when parsing
+        #[allow(unsafe_code)]
+        let map = unsafe { Mmap::map(&file) }
+            .wrap_err_with(|| format!("failed to map {}", path.display()))?;
+        Ok(Self { path: path.to_path_buf(), map })
+    }
+
+    /// Returns the path of the artifact.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the name of the contract, i.e. the file name up to the first `.`.
+    pub fn contract_name(&self) -> Option<&str> {
+        self.path.file_name()?.to_str()?.split('.').next().filter(|name| !name.is_empty())
+    }
+
+    /// Returns the contents of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Returns the unparsed JSON of `section`, or `None` if the artifact does not contain it.
+    ///
+    /// The whole file is returned as the ABI of plain JSON ABI arrays.
+    pub fn section(&self, section: ArtifactSection) -> Result<Option<&str>> {
+        if self.is_abi_array() {
+            let abi = std::str::from_utf8(&self.map)?;
+            return Ok(Some(abi).filter(|_| section == ArtifactSection::Abi))
+        }
+
+        let sections = self.sections()?;
+        let raw = match section {
+            ArtifactSection::Abi => sections.abi,
+            ArtifactSection::StorageLayout => sections.storage_layout,
+            ArtifactSection::Bytecode | ArtifactSection::DeployedBytecode => {
+                let top_level = if section == ArtifactSection::Bytecode {
+                    sections.bytecode
+                } else {
+                    sections.deployed_bytecode
+                };
+                match (top_level, sections.evm) {
+                    (Some(raw), _) => Some(raw),
+                    (None, Some(evm)) => {
+                        let evm: RawEvmSections<'_> = serde_json::from_str(evm.get())?;
+                        if section == ArtifactSection::Bytecode {
+                            evm.bytecode
+                        } else {
+                            evm.deployed_bytecode
+                        }
+                    }
+                    (None, None) => None,
+                }
+            }
+        };
+        Ok(raw.map(RawValue::get).filter(|raw| *raw != "null"))
+    }
+
+    /// Parses the ABI of the artifact.
+    pub fn abi(&self) -> Result<Abi> {
+        let abi = self
+            .section(ArtifactSection::Abi)?
+            .ok_or_else(|| eyre::eyre!("artifact {} has no abi", self.path.display()))?;
+        serde_json::from_str(abi)
+            .wrap_err_with(|| format!("failed to parse the abi of {}", self.path.display()))
+    }
+
+    /// Parses the creation bytecode of the artifact, if it is not empty.
+    pub fn bytecode(&self) -> Result<Option<Bytes>> {
+        self.parse_bytecode(ArtifactSection::Bytecode)
+    }
+
+    /// Parses the runtime bytecode of the artifact, if it is not empty.
+    pub fn deployed_bytecode(&self) -> Result<Option<Bytes>> {
+        self.parse_bytecode(ArtifactSection::DeployedBytecode)
+    }
+
+    /// Parses the storage layout of the artifact.
+    pub fn storage_layout(&self) -> Result<Option<serde_json::Value>> {
+        self.section(ArtifactSection::StorageLayout)?
+            .map(serde_json::from_str)
+            .transpose()
+            .wrap_err_with(|| {
+                format!("failed to parse the storage layout of {}", self.path.display())
+            })
+    }
+
+    /// Returns an artifact JSON which only contains the ABI and bytecode sections, which is all
+    /// that [`Abigen`](crate::Abigen) needs.
+    ///
+    /// Files which are not JSON objects with an ABI, e.g. human readable ABIs, are returned
+    /// unchanged.
+    pub fn to_abigen_json(&self) -> Result<String> {
+        if self.is_abi_array() || self.sections().map_or(true, |sections| sections.abi.is_none()) {
+            return Ok(std::str::from_utf8(&self.map)?.to_string())
+        }
+
+        let mut json = String::from("{");
+        for (key, section) in [
+            ("abi", ArtifactSection::Abi),
+            ("bytecode", ArtifactSection::Bytecode),
+            ("deployedBytecode", ArtifactSection::DeployedBytecode),
+        ] {
+            if let Some(raw) = self.section(section)? {
+                if json.len() > 1 {
+                    json.push(',');
+                }
+                json.push_str(&format!("{key:?}:{raw}"));
+            }
+        }
+        json.push('}');
+        Ok(json)
+    }
+
+    fn parse_bytecode(&self, section: ArtifactSection) -> Result<Option<Bytes>> {
+        let Some(raw) = self.section(section)? else { return Ok(None) };
+        let bytecode = match serde_json::from_str(raw)
+            .wrap_err_with(|| format!("failed to parse the bytecode of {}", self.path.display()))?
+        {
+            RawBytecode::Object { object } => object,
+            RawBytecode::Bytes(bytes) => bytes,
+        };
+        Ok(Some(bytecode).filter(|bytecode| !bytecode.is_empty()))
+    }
+
+    fn sections(&self) -> Result<RawSections<'_>> {
+        serde_json::from_slice(&self.map)
+            .wrap_err_with(|| format!("failed to parse artifact {}", self.path.display()))
+    }
+
+    fn is_abi_array(&self) -> bool {
+        self.map.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/solidity-contracts").join(name)
+    }
+
+    #[test]
+    fn reads_sections() {
+        // Foundry artifact with an AST
+        let artifact = ArtifactReader::open(contract("seaport_1_0.json")).unwrap();
+        assert_eq!(artifact.contract_name(), Some("seaport_1_0"));
+        assert!(artifact.abi().unwrap().functions.contains_key("fulfillOrder"));
+        assert!(artifact.bytecode().unwrap().is_some());
+        assert!(artifact.deployed_bytecode().unwrap().is_some());
+        assert_eq!(artifact.storage_layout().unwrap(), None);
+
+        let json = artifact.to_abigen_json().unwrap();
+        assert!(json.len() < artifact.as_bytes().len());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value.as_object().unwrap().len(), 3);
+
+        // `bin` bytecode and plain ABI arrays
+        let artifact = ArtifactReader::open(contract("StructContract.json")).unwrap();
+        assert!(artifact.bytecode().unwrap().is_some());
+        assert_eq!(artifact.deployed_bytecode().unwrap(), None);
+
+        let artifact = ArtifactReader::open(contract("console.json")).unwrap();
+        assert!(!artifact.abi().unwrap().functions.is_empty());
+        assert_eq!(artifact.bytecode().unwrap(), None);
+        assert_eq!(artifact.to_abigen_json().unwrap().as_bytes(), artifact.as_bytes());
+    }
+
+    #[test]
+    fn reads_standard_json_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Counter.json");
+        std::fs::write(
+            &path,
+            r#"{"abi":[],"evm":{"bytecode":{"object":"6001"},"deployedBytecode":{"object":""}},"storageLayout":{"storage":[],"types":null}}"#,
+        )
+        .unwrap();
+
+        let artifact = ArtifactReader::open(&path).unwrap();
+        assert_eq!(artifact.bytecode().unwrap(), Some(vec![0x60, 0x01].into()));
+        assert_eq!(artifact.deployed_bytecode().unwrap(), None);
+        assert_eq!(
+            artifact.storage_layout().unwrap(),
+            Some(serde_json::json!({"storage": [], "types": null}))
+        );
+        assert_eq!(
+            artifact.to_abigen_json().unwrap(),
+            r#"{"abi":[],"bytecode":{"object":"6001"},"deployedBytecode":{"object":""}}"#
+        );
+    }
+}
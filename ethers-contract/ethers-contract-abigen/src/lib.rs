@@ -17,6 +17,11 @@
 #[path = "test/macros.rs"]
 mod test_macros;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod artifact;
+#[cfg(not(target_arch = "wasm32"))]
+pub use artifact::{ArtifactReader, ArtifactSection};
+
 pub mod contract;
 pub use contract::structs::InternalStructs;
 
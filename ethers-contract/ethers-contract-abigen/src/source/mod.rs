@@ -9,7 +9,7 @@ pub use online::Explorer;
 
 use crate::util;
 use eyre::{Error, Result};
-use std::{env, fs, path::PathBuf, str::FromStr};
+use std::{env, path::PathBuf, str::FromStr};
 
 /// A source of an Ethereum smart contract's ABI.
 ///
@@ -153,9 +153,15 @@ impl Source {
 
     /// Retrieves the source JSON of the artifact this will either read the JSON from the file
     /// system or retrieve a contract ABI from the network depending on the source type.
+    ///
+    /// Local artifacts are read with an [`ArtifactReader`](crate::ArtifactReader), which skips
+    /// all sections but the ABI and bytecode.
     pub fn get(&self) -> Result<String> {
         match self {
-            Self::Local(path) => Ok(fs::read_to_string(path)?),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Local(path) => crate::ArtifactReader::open(path)?.to_abigen_json(),
+            #[cfg(target_arch = "wasm32")]
+            Self::Local(path) => Ok(std::fs::read_to_string(path)?),
             Self::String(abi) => Ok(abi.clone()),
 
             #[cfg(all(feature = "online", not(target_arch = "wasm32")))]
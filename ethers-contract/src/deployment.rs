@@ -0,0 +1,275 @@
+//! Idempotent, resumable deployment scripts.
+
+use crate::{factory::Deployer, ContractError, ContractInstance, FunctionCall};
+use ethers_core::{
+    abi::Detokenize,
+    types::{Address, TxHash, U64},
+};
+use ethers_providers::{Middleware, ProviderError};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fs,
+    future::Future,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// A contract that was deployed by a [`Deployment`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployedContract {
+    /// The address of the contract
+    pub address: Address,
+    /// The hash of the deployment transaction
+    pub tx_hash: TxHash,
+    /// The block the contract was deployed in
+    pub block_number: Option<U64>,
+}
+
+/// The completed steps of a [`Deployment`] on a single network.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentState {
+    /// The chain id of the network
+    pub chain_id: u64,
+    /// The deployed contracts, by step name
+    #[serde(default)]
+    pub contracts: BTreeMap<String, DeployedContract>,
+    /// The hashes of the sent transactions, by step name
+    #[serde(default)]
+    pub calls: BTreeMap<String, TxHash>,
+    /// The names of the contracts that were verified
+    #[serde(default)]
+    pub verified: BTreeSet<String>,
+}
+
+/// Thrown when a step of a [`Deployment`] fails
+#[derive(Error, Debug)]
+pub enum DeploymentError<M: Middleware> {
+    /// Thrown when deploying or calling a contract fails
+    #[error(transparent)]
+    ContractError(#[from] ContractError<M>),
+    /// Thrown when waiting for a transaction fails
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+    /// Thrown when reading or writing the state file fails
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Thrown when the state file can not be (de)serialized
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    /// Thrown when the state file belongs to a different network
+    #[error("state file is for chain {expected}, but the client is connected to chain {actual}")]
+    ChainIdMismatch {
+        /// The chain id of the state file
+        expected: u64,
+        /// The chain id of the client
+        actual: u64,
+    },
+    /// Thrown when verifying a contract that was not deployed
+    #[error("contract `{0}` was not deployed")]
+    UnknownContract(String),
+    /// Thrown when a transaction was dropped or reverted
+    #[error("transaction of step `{0}` failed")]
+    TransactionFailed(String),
+    /// Thrown when verifying a contract fails
+    #[error("verification of `{name}` failed: {source}")]
+    Verification {
+        /// The name of the contract
+        name: String,
+        /// The error returned by the verifier
+        source: Box<dyn Error + Send + Sync>,
+    },
+}
+
+/// `ContractDeployment` is a [`Deployment`] with an [`Arc`] middleware, matching
+/// [`ContractFactory`](crate::ContractFactory).
+pub type ContractDeployment<M> = Deployment<Arc<M>, M>;
+
+/// A deployment script whose steps are recorded in a per-network state file.
+///
+/// Every step has a unique name. Steps that completed in a previous run are skipped, so an
+/// interrupted script can simply be run again.
+///
+/// ```no_run
+/// use ethers_contract::{deployment::ContractDeployment, ContractFactory};
+/// use ethers_core::{abi::Abi, types::Bytes};
+/// use ethers_providers::{Http, Provider};
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo(abi: Abi, bytecode: Bytes) -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Arc::new(Provider::<Http>::try_from("http://localhost:8545")?);
+/// // the state is stored at `deployments/<chain id>.json`
+/// let mut deployment = ContractDeployment::load(client.clone(), "deployments").await?;
+///
+/// let factory = ContractFactory::new(abi, bytecode, client);
+/// let token = deployment.deploy("Token", factory.deploy(())?).await?;
+/// deployment.call("mint", token.method::<_, ()>("mint", ())?).await?;
+/// deployment
+///     .verify("Token", |contract| async move {
+///         // submit the sources of `contract.address` to a block explorer
+///         Ok::<_, std::io::Error>(())
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Deployment<B, M> {
+    client: B,
+    path: PathBuf,
+    state: DeploymentState,
+    _m: PhantomData<M>,
+}
+
+impl<B, M> Deployment<B, M>
+where
+    B: Borrow<M> + Clone,
+    M: Middleware,
+{
+    /// Loads the state of the network the client is connected to from `dir/<chain id>.json`.
+    ///
+    /// A new state is started if the file does not exist yet.
+    pub async fn load(client: B, dir: impl AsRef<Path>) -> Result<Self, DeploymentError<M>> {
+        let chain_id = client
+            .borrow()
+            .get_chainid()
+            .await
+            .map_err(ContractError::from_middleware_error)?
+            .as_u64();
+        let path = dir.as_ref().join(format!("{chain_id}.json"));
+        Self::load_file(client, path, chain_id)
+    }
+
+    fn load_file(client: B, path: PathBuf, chain_id: u64) -> Result<Self, DeploymentError<M>> {
+        let state = if path.exists() {
+            let state: DeploymentState = serde_json::from_slice(&fs::read(&path)?)?;
+            if state.chain_id != chain_id {
+                return Err(DeploymentError::ChainIdMismatch {
+                    expected: state.chain_id,
+                    actual: chain_id,
+                })
+            }
+            state
+        } else {
+            DeploymentState { chain_id, ..Default::default() }
+        };
+        Ok(Self { client, path, state, _m: PhantomData })
+    }
+
+    /// Returns the recorded state.
+    pub fn state(&self) -> &DeploymentState {
+        &self.state
+    }
+
+    /// Returns the path of the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the address of the contract deployed in the step `name`.
+    pub fn address(&self, name: &str) -> Option<Address> {
+        self.state.contracts.get(name).map(|contract| contract.address)
+    }
+
+    /// Deploys a contract, unless it was already deployed by a step with the same name.
+    ///
+    /// Either way, an instance of the contract is returned.
+    pub async fn deploy(
+        &mut self,
+        name: impl Into<String>,
+        deployer: Deployer<B, M>,
+    ) -> Result<ContractInstance<B, M>, DeploymentError<M>> {
+        let name = name.into();
+        if let Some(contract) = self.state.contracts.get(&name) {
+            return Ok(ContractInstance::new(
+                contract.address,
+                deployer.abi().clone(),
+                self.client.clone(),
+            ))
+        }
+
+        let (contract, receipt) = deployer.send_with_receipt().await?;
+        self.state.contracts.insert(
+            name,
+            DeployedContract {
+                address: contract.address(),
+                tx_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+            },
+        );
+        self.persist()?;
+        Ok(contract)
+    }
+
+    /// Sends the transaction of a contract call and waits for it to be mined, unless a step with
+    /// the same name was already completed.
+    ///
+    /// Returns the hash of the transaction.
+    pub async fn call<D: Detokenize>(
+        &mut self,
+        name: impl Into<String>,
+        call: FunctionCall<B, M, D>,
+    ) -> Result<TxHash, DeploymentError<M>> {
+        let name = name.into();
+        if let Some(tx_hash) = self.state.calls.get(&name) {
+            return Ok(*tx_hash)
+        }
+
+        let receipt = call.send().await?.await?;
+        let Some(receipt) = receipt.filter(|receipt| receipt.status != Some(U64::zero())) else {
+            return Err(DeploymentError::TransactionFailed(name))
+        };
+        self.state.calls.insert(name, receipt.transaction_hash);
+        self.persist()?;
+        Ok(receipt.transaction_hash)
+    }
+
+    /// Runs `verify` for the contract deployed in the step `name`, unless it was already verified.
+    ///
+    /// The verifier is typically a block explorer client submitting the contract's sources.
+    pub async fn verify<F, Fut, E>(
+        &mut self,
+        name: impl Into<String>,
+        verify: F,
+    ) -> Result<(), DeploymentError<M>>
+    where
+        F: FnOnce(DeployedContract) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let name = name.into();
+        if self.state.verified.contains(&name) {
+            return Ok(())
+        }
+        let contract = *self
+            .state
+            .contracts
+            .get(&name)
+            .ok_or_else(|| DeploymentError::UnknownContract(name.clone()))?;
+
+        match verify(contract).await {
+            Ok(()) => {
+                self.state.verified.insert(name);
+                self.persist()
+            }
+            Err(err) => Err(DeploymentError::Verification { name, source: err.into() }),
+        }
+    }
+
+    /// Writes the state file, via a temporary file so it is never left corrupted.
+    fn persist(&self) -> Result<(), DeploymentError<M>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&self.state)?)?;
+        fs::rename(tmp, &self.path)?;
+        Ok(())
+    }
+}
@@ -19,6 +19,9 @@ pub use error::{ContractRevert, EthError};
 mod factory;
 pub use factory::{ContractDeployer, ContractDeploymentTx, ContractFactory, DeploymentTxFactory};
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod deployment;
+
 mod event;
 pub use event::{parse_log, EthEvent, Event};
 
@@ -27,6 +30,8 @@ pub use log::{decode_logs, EthLogDecode, LogMeta};
 
 pub mod stream;
 
+pub mod proxy;
+
 #[cfg(feature = "abigen")]
 #[cfg_attr(docsrs, doc(cfg(feature = "abigen")))]
 mod multicall;
@@ -35,6 +40,7 @@ mod multicall;
 pub use multicall::{
     constants::{MULTICALL_ADDRESS, MULTICALL_SUPPORTED_CHAIN_IDS},
     contract as multicall_contract,
+    erc7412::{Erc7412Error, FeeRequired, OracleDataRequired, ERC7412_MAX_ATTEMPTS},
     error::MulticallError,
     Call, Multicall, MulticallContract, MulticallVersion,
 };
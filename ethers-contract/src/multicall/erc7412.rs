@@ -0,0 +1,267 @@
+//! [ERC-7412](https://eips.ethereum.org/EIPS/eip-7412) off-chain data retrieval.
+//!
+//! Contracts implementing ERC-7412 revert with [`OracleDataRequired`] when they need off-chain
+//! data, like a fresh price, that has not been submitted to the oracle contract yet. The caller
+//! fetches the data off-chain and submits it with `fulfillOracleQuery(bytes)` in the same
+//! transaction, before the call that required it.
+
+use super::{error::MulticallError, Call, Multicall, MulticallVersion};
+use crate::{ContractError, EthError};
+use ethers_core::{
+    abi::{HumanReadableParser, Token},
+    types::{Address, Bytes, U256},
+};
+use ethers_providers::{Middleware, PendingTransaction};
+use std::future::Future;
+
+/// The maximum number of times the batch is simulated while resolving oracle queries.
+pub const ERC7412_MAX_ATTEMPTS: usize = 16;
+
+/// Thrown by ERC-7412 contracts when `oracle_query` has to be fulfilled by `oracle_contract`
+/// before the call can succeed.
+#[derive(Clone, Debug, PartialEq, Eq, EthError)]
+#[etherror(name = "OracleDataRequired", abi = "OracleDataRequired(address,bytes)")]
+pub struct OracleDataRequired {
+    /// The contract which verifies and stores the off-chain data
+    pub oracle_contract: Address,
+    /// The oracle specific query for the required data
+    pub oracle_query: Bytes,
+}
+
+/// Thrown by `fulfillOracleQuery` when the oracle charges a fee for verifying the data.
+#[derive(Clone, Debug, PartialEq, Eq, EthError)]
+#[etherror(name = "FeeRequired", abi = "FeeRequired(uint256)")]
+pub struct FeeRequired {
+    /// The fee, in wei
+    pub fee_amount: U256,
+}
+
+/// Errors when resolving the oracle queries of a [`Multicall`]
+#[derive(Debug, thiserror::Error)]
+pub enum Erc7412Error<M: Middleware, E> {
+    /// Simulating or sending the batch failed
+    #[error(transparent)]
+    Multicall(#[from] MulticallError<M>),
+    /// The off-chain data could not be fetched
+    #[error("failed to fetch oracle data: {0}")]
+    Resolver(E),
+    /// The batch still required oracle data after [`ERC7412_MAX_ATTEMPTS`] simulations
+    #[error("oracle data still required after {0} attempts")]
+    TooManyAttempts(usize),
+    /// Only [`MulticallVersion::Multicall3`] allows individual calls to fail and attach value
+    #[error("ERC-7412 requires Multicall3, got {0:?}")]
+    UnsupportedVersion(MulticallVersion),
+}
+
+impl<M: Middleware> Multicall<M> {
+    /// Simulates the batch and prepends the `fulfillOracleQuery` calls it requires, until the
+    /// simulation no longer reverts with [`OracleDataRequired`] or [`FeeRequired`].
+    ///
+    /// `resolver` is called with the oracle contract and the query of every required
+    /// [`OracleDataRequired`], and returns the signed off-chain data which is submitted to the
+    /// oracle. Fees are attached as the value of the call which required them, so the batch has to
+    /// be sent by an account which can pay for them.
+    ///
+    /// Returns the number of calls prepended to the batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Erc7412Error`] if the simulation fails for any other reason than ERC-7412
+    /// errors of calls which are not allowed to fail, or if the `resolver` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use ethers_core::types::{Address, Bytes};
+    /// # use ethers_providers::{Provider, Http};
+    /// # use ethers_contract::Multicall;
+    /// # use std::convert::TryFrom;
+    /// #
+    /// # let client = Provider::<Http>::try_from("http://localhost:8545")?;
+    /// # async fn fetch_price_update(oracle: Address, query: Bytes) -> Result<Bytes, std::io::Error> { todo!() }
+    /// #
+    /// let mut multicall = Multicall::new(client, None).await?;
+    /// // add the calls that read prices...
+    /// multicall.fulfill_oracle_queries(|oracle, query| fetch_price_update(oracle, query)).await?;
+    /// let _tx_receipt = multicall.send().await?.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fulfill_oracle_queries<F, Fut, E>(
+        &mut self,
+        mut resolver: F,
+    ) -> Result<usize, Erc7412Error<M, E>>
+    where
+        F: FnMut(Address, Bytes) -> Fut,
+        Fut: Future<Output = Result<Bytes, E>>,
+    {
+        if self.version != MulticallVersion::Multicall3 {
+            return Err(Erc7412Error::UnsupportedVersion(self.version))
+        }
+
+        let mut prepended = 0;
+        for _ in 0..ERC7412_MAX_ATTEMPTS {
+            // every call may fail in the simulation, so that the revert data of the first
+            // failing call is returned instead of Multicall3's generic error
+            let mut simulation = self.clone();
+            simulation.calls.iter_mut().for_each(|call| call.allow_failure = true);
+            let results = simulation
+                .as_aggregate_3_value()
+                .call()
+                .await
+                .map_err(|err| Erc7412Error::Multicall(MulticallError::ContractError(err)))?;
+
+            let failure = self
+                .calls
+                .iter()
+                .zip(results)
+                .enumerate()
+                .find(|(_, (call, result))| !result.success && !call.allow_failure);
+            let Some((index, (_, result))) = failure else { return Ok(prepended) };
+
+            if let Some(required) = OracleDataRequired::decode_with_selector(&result.return_data) {
+                let data = resolver(required.oracle_contract, required.oracle_query)
+                    .await
+                    .map_err(Erc7412Error::Resolver)?;
+                self.calls.insert(0, fulfill_oracle_query(required.oracle_contract, data));
+                prepended += 1;
+            } else if let Some(FeeRequired { fee_amount }) =
+                FeeRequired::decode_with_selector(&result.return_data)
+            {
+                let call = &mut self.calls[index];
+                if call.value >= fee_amount {
+                    return Err(revert(result.return_data))
+                }
+                call.value = fee_amount;
+            } else {
+                return Err(revert(result.return_data))
+            }
+        }
+
+        Err(Erc7412Error::TooManyAttempts(ERC7412_MAX_ATTEMPTS))
+    }
+
+    /// Fulfills the oracle queries of the batch with `resolver` and sends it, see
+    /// [`fulfill_oracle_queries`](Self::fulfill_oracle_queries).
+    pub async fn send_with_oracle_data<F, Fut, E>(
+        &mut self,
+        resolver: F,
+    ) -> Result<PendingTransaction<'_, M::Provider>, Erc7412Error<M, E>>
+    where
+        F: FnMut(Address, Bytes) -> Fut,
+        Fut: Future<Output = Result<Bytes, E>>,
+    {
+        self.fulfill_oracle_queries(resolver).await?;
+        self.send().await.map_err(Erc7412Error::Multicall)
+    }
+}
+
+fn revert<M: Middleware, E>(data: Bytes) -> Erc7412Error<M, E> {
+    Erc7412Error::Multicall(MulticallError::ContractError(ContractError::Revert(data)))
+}
+
+/// Returns the call which submits the off-chain `data` to the `oracle`.
+fn fulfill_oracle_query(oracle: Address, data: Bytes) -> Call {
+    let function =
+        HumanReadableParser::parse_function("function fulfillOracleQuery(bytes) external payable")
+            .expect("valid function signature");
+    let data = function.encode_input(&[Token::Bytes(data.to_vec())]).expect("valid input");
+    Call { target: oracle, data: data.into(), value: U256::zero(), allow_failure: false, function }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multicall::contract::Result as MulticallResult;
+    use ethers_core::abi::{encode, Function, StateMutability, Tokenizable};
+    use ethers_providers::Provider;
+
+    fn results(results: Vec<(bool, Bytes)>) -> Bytes {
+        let results = results
+            .into_iter()
+            .map(|(success, return_data)| MulticallResult { success, return_data })
+            .collect::<Vec<_>>();
+        encode(&[results.into_token()]).into()
+    }
+
+    fn revert_data<E: EthError>(err: E) -> Bytes {
+        [&E::selector()[..], &err.encode()].concat().into()
+    }
+
+    fn price_call() -> Call {
+        #[allow(deprecated)]
+        let function = Function {
+            name: "getPrice".to_string(),
+            inputs: vec![],
+            outputs: vec![],
+            constant: None,
+            state_mutability: StateMutability::View,
+        };
+        Call {
+            target: Address::repeat_byte(1),
+            data: Bytes::from_static(&[0x98, 0xd5, 0xfd, 0xca]),
+            value: U256::zero(),
+            allow_failure: false,
+            function,
+        }
+    }
+
+    #[tokio::test]
+    async fn prepends_oracle_fulfillment() {
+        let (provider, mock) = Provider::mocked();
+        let mut multicall =
+            Multicall::new(provider, Some(Address::repeat_byte(0xca))).await.unwrap();
+        multicall.calls.push(price_call());
+
+        let oracle = Address::repeat_byte(0x0a);
+        let required = OracleDataRequired { oracle_contract: oracle, oracle_query: vec![1].into() };
+        // responses are returned last in, first out
+        mock.push::<Bytes, Bytes>(results(vec![
+            (true, Bytes::new()),
+            (true, Bytes::new()),
+            (true, vec![7].into()),
+        ]))
+        .unwrap();
+        mock.push::<Bytes, Bytes>(results(vec![
+            (false, revert_data(FeeRequired { fee_amount: 5.into() })),
+            (false, revert_data(required.clone())),
+        ]))
+        .unwrap();
+        mock.push::<Bytes, Bytes>(results(vec![(false, revert_data(required))])).unwrap();
+
+        let prepended = multicall
+            .fulfill_oracle_queries(|oracle_contract, query| async move {
+                assert_eq!(oracle_contract, oracle);
+                assert_eq!(query, Bytes::from(vec![1]));
+                Ok::<_, std::convert::Infallible>(Bytes::from(vec![0xaa]))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(prepended, 1);
+        assert_eq!(multicall.calls.len(), 2);
+        let fulfillment = &multicall.calls[0];
+        assert_eq!(fulfillment.target, oracle);
+        assert_eq!(fulfillment.value, U256::from(5));
+        assert_eq!(&fulfillment.data[..4], &ethers_core::utils::id("fulfillOracleQuery(bytes)"));
+    }
+
+    #[tokio::test]
+    async fn surfaces_other_reverts() {
+        let (provider, mock) = Provider::mocked();
+        let mut multicall =
+            Multicall::new(provider, Some(Address::repeat_byte(0xca))).await.unwrap();
+        multicall.calls.push(price_call());
+
+        mock.push::<Bytes, Bytes>(results(vec![(false, vec![1, 2, 3].into())])).unwrap();
+        let err = multicall
+            .fulfill_oracle_queries(|_, _| async {
+                Ok::<_, std::convert::Infallible>(Bytes::new())
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Erc7412Error::Multicall(err) if err.is_revert()));
+        assert_eq!(multicall.calls.len(), 1);
+    }
+}
@@ -18,6 +18,8 @@ use contract::{
 
 pub mod constants;
 
+pub mod erc7412;
+
 /// Type alias for `Result<T, MulticallError<M>>`
 pub type Result<T, M> = StdResult<T, error::MulticallError<M>>;
 
@@ -386,8 +388,15 @@ impl<M: Middleware> Multicall<M> {
             TypedTransaction::Legacy(tx) => (tx.to, tx.data, tx.value),
             TypedTransaction::Eip2930(tx) => (tx.tx.to, tx.tx.data, tx.tx.value),
             TypedTransaction::Eip1559(tx) => (tx.to, tx.data, tx.value),
+            TypedTransaction::Eip4844(tx) => (tx.tx.to, tx.tx.data, tx.tx.value),
             #[cfg(feature = "optimism")]
             TypedTransaction::OptimismDeposited(tx) => (tx.tx.to, tx.tx.data, tx.tx.value),
+            #[cfg(feature = "celo")]
+            TypedTransaction::Cip42(tx) => (tx.tx.to, tx.tx.data, tx.tx.value),
+            #[cfg(feature = "celo")]
+            TypedTransaction::Cip64(tx) => (tx.tx.to, tx.tx.data, tx.tx.value),
+            #[cfg(feature = "zksync")]
+            TypedTransaction::ZkSync(tx) => (tx.tx.to, tx.tx.data, tx.tx.value),
         };
         if data.is_none() && !call.function.outputs.is_empty() {
             return self
@@ -0,0 +1,351 @@
+//! Administration of [EIP-1967](https://eips.ethereum.org/EIPS/eip-1967) proxies and Safe
+//! modules.
+//!
+//! The helpers check that the target of an operation implements the expected interface before
+//! returning the call, since upgrading a proxy to a contract which is not an implementation, or
+//! enabling a module on a contract which is not a Safe, can brick the proxy or silently do
+//! nothing.
+
+use crate::{AbiError, BaseContract, Contract, ContractCall, ContractError, Lazy};
+use ethers_core::{
+    abi::parse_abi,
+    types::{Address, Bytes, H256},
+};
+use ethers_providers::Middleware;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// The storage slot of the implementation of an EIP-1967 proxy,
+/// `keccak256("eip1967.proxy.implementation") - 1`
+pub const EIP1967_IMPLEMENTATION_SLOT: H256 = H256([
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+]);
+
+/// The storage slot of the admin of an EIP-1967 proxy, `keccak256("eip1967.proxy.admin") - 1`
+pub const EIP1967_ADMIN_SLOT: H256 = H256([
+    0xb5, 0x31, 0x27, 0x68, 0x4a, 0x56, 0x8b, 0x31, 0x73, 0xae, 0x13, 0xb9, 0xf8, 0xa6, 0x01, 0x6e,
+    0x24, 0x3e, 0x63, 0xb6, 0xe8, 0xee, 0x11, 0x78, 0xd6, 0xa7, 0x17, 0x85, 0x0b, 0x5d, 0x61, 0x03,
+]);
+
+static PROXY_ABI: Lazy<BaseContract> = Lazy::new(|| {
+    parse_abi(&[
+        "function upgradeTo(address newImplementation)",
+        "function upgradeToAndCall(address newImplementation, bytes data)",
+        "function changeAdmin(address newAdmin)",
+        "function proxiableUUID() view returns (bytes32)",
+    ])
+    .expect("valid proxy abi")
+    .into()
+});
+
+static SAFE_ABI: Lazy<BaseContract> = Lazy::new(|| {
+    parse_abi(&[
+        "function enableModule(address module)",
+        "function isModuleEnabled(address module) view returns (bool)",
+    ])
+    .expect("valid safe abi")
+    .into()
+});
+
+/// The upgrade pattern of an EIP-1967 proxy
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProxyKind {
+    /// OpenZeppelin's `TransparentUpgradeableProxy`, which is upgraded by its admin
+    Transparent,
+    /// A UUPS ([EIP-1822](https://eips.ethereum.org/EIPS/eip-1822)) proxy, which is upgraded
+    /// through its implementation
+    Uups,
+}
+
+/// Thrown when a proxy operation fails its safety checks
+#[derive(Debug, Error)]
+pub enum ProxyError<M: Middleware> {
+    /// Thrown when querying the chain or encoding the call fails
+    #[error(transparent)]
+    ContractError(#[from] ContractError<M>),
+    /// Thrown when the proxy, implementation or module has no code
+    #[error("{0:?} has no code")]
+    NoCode(Address),
+    /// Thrown when the new implementation of a UUPS proxy does not return the EIP-1967
+    /// implementation slot from `proxiableUUID()`, so that it could not be upgraded again
+    #[error("{0:?} is not a UUPS implementation")]
+    NotProxiable(Address),
+    /// Thrown when the proxy is already using the implementation
+    #[error("{0:?} is already the implementation of the proxy")]
+    SameImplementation(Address),
+    /// Thrown when the new admin is the zero address, which would lock the proxy
+    #[error("the admin of a proxy can not be the zero address")]
+    ZeroAdmin,
+    /// Thrown when the operation is not supported by the kind of proxy
+    #[error("{0} is not supported by {1:?} proxies")]
+    Unsupported(&'static str, ProxyKind),
+    /// Thrown when the target of a module operation does not implement `isModuleEnabled`
+    #[error("{0:?} is not a Safe")]
+    NotASafe(Address),
+    /// Thrown when the module is already enabled
+    #[error("module {0:?} is already enabled")]
+    ModuleEnabled(Address),
+}
+
+impl<M: Middleware> From<AbiError> for ProxyError<M> {
+    fn from(err: AbiError) -> Self {
+        ProxyError::ContractError(err.into())
+    }
+}
+
+/// Builds the calls which upgrade an EIP-1967 proxy or change its admin, after checking the
+/// targets of the calls.
+///
+/// The calls are sent to the proxy, so they have to be sent by the admin of a
+/// [`ProxyKind::Transparent`] proxy, or by the account the implementation of a
+/// [`ProxyKind::Uups`] proxy authorizes.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_contract::proxy::{ProxyAdmin, ProxyKind};
+/// use ethers_core::types::Address;
+/// use ethers_providers::{Http, Provider};
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Arc::new(Provider::<Http>::try_from("http://localhost:8545")?);
+/// # let (proxy, new_implementation) = (Address::random(), Address::random());
+/// let admin = ProxyAdmin::new(proxy, ProxyKind::Uups, client);
+/// // fails if the new implementation can not be upgraded again
+/// let call = admin.upgrade_to(new_implementation).await?;
+/// let _receipt = call.send().await?.await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ProxyAdmin<M> {
+    proxy: Contract<M>,
+    kind: ProxyKind,
+}
+
+impl<M: Middleware> ProxyAdmin<M> {
+    /// Creates the admin of the `kind` proxy at `proxy`.
+    pub fn new(proxy: Address, kind: ProxyKind, client: Arc<M>) -> Self {
+        Self { proxy: Contract::new(proxy, PROXY_ABI.clone(), client), kind }
+    }
+
+    /// Returns the address of the proxy.
+    pub fn address(&self) -> Address {
+        self.proxy.address()
+    }
+
+    /// Returns the kind of the proxy.
+    pub fn kind(&self) -> ProxyKind {
+        self.kind
+    }
+
+    /// Returns the current implementation of the proxy.
+    pub async fn implementation(&self) -> Result<Address, ProxyError<M>> {
+        self.read_slot(EIP1967_IMPLEMENTATION_SLOT).await
+    }
+
+    /// Returns the admin of the proxy, which is the zero address for most UUPS proxies.
+    pub async fn admin(&self) -> Result<Address, ProxyError<M>> {
+        self.read_slot(EIP1967_ADMIN_SLOT).await
+    }
+
+    /// Returns the call which upgrades the proxy to `implementation`.
+    ///
+    /// Fails if the implementation has no code or is already used by the proxy, or if it is not
+    /// a UUPS implementation when upgrading a UUPS proxy.
+    pub async fn upgrade_to(
+        &self,
+        implementation: Address,
+    ) -> Result<ContractCall<M, ()>, ProxyError<M>> {
+        self.check_implementation(implementation).await?;
+        Ok(self.proxy.method("upgradeTo", implementation)?)
+    }
+
+    /// Returns the call which upgrades the proxy to `implementation` and calls it with `data`,
+    /// e.g. to initialize a new version.
+    ///
+    /// Performs the same checks as [`ProxyAdmin::upgrade_to`].
+    pub async fn upgrade_to_and_call(
+        &self,
+        implementation: Address,
+        data: Bytes,
+    ) -> Result<ContractCall<M, ()>, ProxyError<M>> {
+        self.check_implementation(implementation).await?;
+        Ok(self.proxy.method("upgradeToAndCall", (implementation, data))?)
+    }
+
+    /// Returns the call which transfers the administration of a transparent proxy to
+    /// `new_admin`.
+    ///
+    /// Fails for UUPS proxies, whose implementation manages the access, and if `new_admin` is the
+    /// zero address.
+    pub async fn change_admin(
+        &self,
+        new_admin: Address,
+    ) -> Result<ContractCall<M, ()>, ProxyError<M>> {
+        if self.kind != ProxyKind::Transparent {
+            return Err(ProxyError::Unsupported("changeAdmin", self.kind))
+        }
+        if new_admin.is_zero() {
+            return Err(ProxyError::ZeroAdmin)
+        }
+        ensure_code(self.proxy.client_ref(), self.address()).await?;
+        Ok(self.proxy.method("changeAdmin", new_admin)?)
+    }
+
+    async fn check_implementation(&self, implementation: Address) -> Result<(), ProxyError<M>> {
+        let client = self.proxy.client_ref();
+        ensure_code(client, self.address()).await?;
+        ensure_code(client, implementation).await?;
+        if self.implementation().await? == implementation {
+            return Err(ProxyError::SameImplementation(implementation))
+        }
+
+        if self.kind == ProxyKind::Uups {
+            let uuid =
+                self.proxy.at(implementation).method::<_, H256>("proxiableUUID", ())?.call().await;
+            match uuid {
+                Ok(slot) if slot == EIP1967_IMPLEMENTATION_SLOT => {}
+                Err(err @ ContractError::MiddlewareError { .. }) |
+                Err(err @ ContractError::ProviderError { .. }) => return Err(err.into()),
+                _ => return Err(ProxyError::NotProxiable(implementation)),
+            }
+        }
+        Ok(())
+    }
+
+    async fn read_slot(&self, slot: H256) -> Result<Address, ProxyError<M>> {
+        let value = self
+            .proxy
+            .client_ref()
+            .get_storage_at(self.address(), slot, None)
+            .await
+            .map_err(ContractError::from_middleware_error)?;
+        Ok(Address::from(value))
+    }
+}
+
+/// Returns the call which enables `module` on the Safe at `safe`.
+///
+/// Safes only accept the call from themselves, so it has to be executed as a Safe transaction
+/// with the [calldata](crate::FunctionCall::calldata) of the returned call.
+///
+/// Fails if `module` has no code, if `safe` does not implement `isModuleEnabled` or if the module
+/// is already enabled.
+pub async fn enable_safe_module<M: Middleware>(
+    safe: Address,
+    module: Address,
+    client: Arc<M>,
+) -> Result<ContractCall<M, ()>, ProxyError<M>> {
+    ensure_code(&*client, module).await?;
+    let safe = Contract::new(safe, SAFE_ABI.clone(), client);
+    match safe.method::<_, bool>("isModuleEnabled", module)?.call().await {
+        Ok(false) => Ok(safe.method("enableModule", module)?),
+        Ok(true) => Err(ProxyError::ModuleEnabled(module)),
+        Err(err @ ContractError::MiddlewareError { .. }) |
+        Err(err @ ContractError::ProviderError { .. }) => Err(err.into()),
+        Err(_) => Err(ProxyError::NotASafe(safe.address())),
+    }
+}
+
+async fn ensure_code<M: Middleware>(client: &M, address: Address) -> Result<(), ProxyError<M>> {
+    let code =
+        client.get_code(address, None).await.map_err(ContractError::from_middleware_error)?;
+    if code.is_empty() {
+        return Err(ProxyError::NoCode(address))
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::{abi::AbiEncode, utils::keccak256};
+    use ethers_providers::{MockProvider, Provider};
+
+    fn slot(name: &str) -> H256 {
+        let mut slot = keccak256(name);
+        // the preimages of the slots end in non-zero bytes
+        slot[31] -= 1;
+        H256(slot)
+    }
+
+    fn client() -> (Arc<Provider<MockProvider>>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        (Arc::new(provider), mock)
+    }
+
+    #[test]
+    fn eip1967_slots() {
+        assert_eq!(EIP1967_IMPLEMENTATION_SLOT, slot("eip1967.proxy.implementation"));
+        assert_eq!(EIP1967_ADMIN_SLOT, slot("eip1967.proxy.admin"));
+    }
+
+    #[tokio::test]
+    async fn checks_uups_implementation() {
+        let (proxy, current, next) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        let code = Bytes::from(vec![0x60, 0x00]);
+
+        // responses are returned last in, first out
+        let (client, mock) = client();
+        mock.push::<Bytes, Bytes>(Bytes::from(EIP1967_IMPLEMENTATION_SLOT.encode())).unwrap();
+        mock.push(H256::from(current)).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        let admin = ProxyAdmin::new(proxy, ProxyKind::Uups, client);
+        let call = admin.upgrade_to(next).await.unwrap();
+        assert_eq!(call.tx.to_addr(), Some(&proxy));
+        assert_eq!(call.calldata().unwrap()[4..], next.encode()[..]);
+
+        mock.push::<Bytes, Bytes>(Bytes::from(H256::zero().encode())).unwrap();
+        mock.push(H256::from(current)).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        assert!(
+            matches!(admin.upgrade_to(next).await, Err(ProxyError::NotProxiable(a)) if a == next)
+        );
+
+        mock.push(H256::from(next)).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        mock.push::<Bytes, Bytes>(code).unwrap();
+        assert!(matches!(admin.upgrade_to(next).await, Err(ProxyError::SameImplementation(_))));
+
+        mock.push::<Bytes, Bytes>(Bytes::new()).unwrap();
+        mock.push::<Bytes, Bytes>(Bytes::from(vec![0x60, 0x00])).unwrap();
+        assert!(matches!(admin.upgrade_to(next).await, Err(ProxyError::NoCode(a)) if a == next));
+
+        assert!(matches!(
+            admin.change_admin(Address::repeat_byte(4)).await,
+            Err(ProxyError::Unsupported(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_safe_modules() {
+        let (safe, module) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let code = Bytes::from(vec![0x60, 0x00]);
+
+        let (client, mock) = client();
+        mock.push::<Bytes, Bytes>(Bytes::from(false.encode())).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        let call = enable_safe_module(safe, module, client.clone()).await.unwrap();
+        assert_eq!(call.tx.to_addr(), Some(&safe));
+
+        mock.push::<Bytes, Bytes>(Bytes::from(true.encode())).unwrap();
+        mock.push::<Bytes, Bytes>(code.clone()).unwrap();
+        assert!(matches!(
+            enable_safe_module(safe, module, client.clone()).await,
+            Err(ProxyError::ModuleEnabled(_))
+        ));
+
+        mock.push::<Bytes, Bytes>(Bytes::new()).unwrap();
+        mock.push::<Bytes, Bytes>(code).unwrap();
+        assert!(matches!(
+            enable_safe_module(safe, module, client).await,
+            Err(ProxyError::NotASafe(a)) if a == safe
+        ));
+    }
+}
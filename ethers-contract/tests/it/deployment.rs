@@ -0,0 +1,44 @@
+use crate::common::*;
+use ethers_contract::{
+    deployment::{ContractDeployment, DeploymentState},
+    ContractFactory,
+};
+use ethers_core::{abi::Abi, types::Bytes, utils::Anvil};
+use ethers_providers::{Http, Middleware, Provider};
+use std::{path::Path, sync::Arc};
+
+async fn run(
+    client: Arc<Provider<Http>>,
+    dir: &Path,
+    abi: Abi,
+    bytecode: Bytes,
+) -> DeploymentState {
+    let mut deployment = ContractDeployment::load(client.clone(), dir).await.unwrap();
+    let factory = ContractFactory::new(abi, bytecode, client);
+    let contract = deployment
+        .deploy("SimpleStorage", factory.deploy("initial value".to_string()).unwrap())
+        .await
+        .unwrap();
+    let call = contract.method::<_, ()>("setValue", "hi".to_string()).unwrap();
+    deployment.call("setValue", call).await.unwrap();
+    deployment.verify("SimpleStorage", |_| async { Ok::<_, std::io::Error>(()) }).await.unwrap();
+    deployment.state().clone()
+}
+
+#[tokio::test]
+async fn deployment_skips_completed_steps() {
+    let (abi, bytecode) = get_contract("SimpleStorage.json");
+    let anvil = Anvil::new().spawn();
+    let client = connect(&anvil, 0);
+    let sender = anvil.addresses()[0];
+    let dir = tempfile::tempdir().unwrap();
+
+    let first = run(client.clone(), dir.path(), abi.clone(), bytecode.clone()).await;
+    let nonce = client.get_transaction_count(sender, None).await.unwrap();
+    let second = run(client.clone(), dir.path(), abi, bytecode).await;
+
+    assert_eq!(first, second);
+    assert!(second.verified.contains("SimpleStorage"));
+    assert_eq!(client.get_transaction_count(sender, None).await.unwrap(), nonce);
+    assert!(dir.path().join(format!("{}.json", anvil.chain_id())).exists());
+}
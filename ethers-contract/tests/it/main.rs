@@ -14,3 +14,6 @@ mod common;
 
 #[cfg(all(not(target_arch = "wasm32"), not(feature = "celo")))]
 mod contract;
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "celo")))]
+mod deployment;
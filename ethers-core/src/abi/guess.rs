@@ -0,0 +1,196 @@
+//! Best-effort interface recovery for unverified contracts.
+
+use crate::{
+    abi::{Abi, AbiParser, Function},
+    types::Selector,
+    utils::{disassemble, id},
+};
+use std::collections::BTreeMap;
+
+/// A source of known function signatures, keyed by their selector.
+pub trait SignatureDatabase {
+    /// Returns all known signatures for the given selector, most likely first.
+    fn function_signatures(&self, selector: Selector) -> Vec<String>;
+}
+
+impl<T: SignatureDatabase + ?Sized> SignatureDatabase for &T {
+    fn function_signatures(&self, selector: Selector) -> Vec<String> {
+        (**self).function_signatures(selector)
+    }
+}
+
+/// An in-memory [`SignatureDatabase`].
+///
+/// This can be populated from local signature lists or with the results of a remote lookup.
+///
+/// # Example
+///
+/// ```
+/// use ethers_core::abi::{LocalSignatureDatabase, SignatureDatabase};
+///
+/// let db = LocalSignatureDatabase::from_signatures(["transfer(address,uint256)"]);
+/// assert_eq!(db.function_signatures([0xa9, 0x05, 0x9c, 0xbb]), ["transfer(address,uint256)"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LocalSignatureDatabase {
+    functions: BTreeMap<Selector, Vec<String>>,
+}
+
+impl LocalSignatureDatabase {
+    /// Creates an empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a database from a list of function signatures like `transfer(address,uint256)`.
+    pub fn from_signatures<I, S>(signatures: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut db = Self::new();
+        db.extend(signatures);
+        db
+    }
+
+    /// Adds the signature to the database, keyed by its computed selector.
+    pub fn insert(&mut self, signature: impl Into<String>) {
+        let signature = signature.into();
+        self.insert_with_selector(id(&signature), signature);
+    }
+
+    /// Adds the signature for the given selector.
+    ///
+    /// Signatures that do not hash to `selector` are ignored.
+    pub fn insert_with_selector(&mut self, selector: Selector, signature: impl Into<String>) {
+        let signature = signature.into();
+        if id(&signature) != selector {
+            return
+        }
+        let entry = self.functions.entry(selector).or_default();
+        if !entry.contains(&signature) {
+            entry.push(signature);
+        }
+    }
+
+    /// Adds all signatures to the database.
+    pub fn extend<I, S>(&mut self, signatures: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for signature in signatures {
+            self.insert(signature);
+        }
+    }
+
+    /// Returns the number of distinct selectors in the database.
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Returns `true` if the database contains no signatures.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+}
+
+impl SignatureDatabase for LocalSignatureDatabase {
+    fn function_signatures(&self, selector: Selector) -> Vec<String> {
+        self.functions.get(&selector).cloned().unwrap_or_default()
+    }
+}
+
+/// The interface recovered from a contract's bytecode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GuessedInterface {
+    /// The recovered ABI, containing one function per resolved selector.
+    ///
+    /// The functions have no outputs and are assumed to be `nonpayable`, since neither can be
+    /// recovered from a selector.
+    pub abi: Abi,
+    /// All selectors found in the dispatch table, in order of appearance.
+    pub selectors: Vec<Selector>,
+    /// Selectors that could not be resolved.
+    pub unknown: Vec<Selector>,
+    /// Selectors with more than one candidate signature. The first candidate was used in `abi`.
+    pub ambiguous: BTreeMap<Selector, Vec<String>>,
+}
+
+/// Extracts the selectors from the dispatch table of `code` and resolves them with `db`.
+///
+/// See [`Disassembly::selectors`](crate::utils::Disassembly::selectors) for how selectors are
+/// detected.
+pub fn guess_interface(code: impl AsRef<[u8]>, db: impl SignatureDatabase) -> GuessedInterface {
+    let selectors = disassemble(code).selectors();
+    let mut interface = GuessedInterface { selectors: selectors.clone(), ..Default::default() };
+
+    for selector in selectors {
+        let mut candidates: Vec<_> = db
+            .function_signatures(selector)
+            .into_iter()
+            .filter(|signature| id(signature) == selector)
+            .collect();
+        candidates.dedup();
+
+        let Some(function) = candidates.iter().find_map(|sig| parse_signature(sig)) else {
+            interface.unknown.push(selector);
+            continue
+        };
+        interface.abi.functions.entry(function.name.clone()).or_default().push(function);
+        if candidates.len() > 1 {
+            interface.ambiguous.insert(selector, candidates);
+        }
+    }
+
+    interface
+}
+
+fn parse_signature(signature: &str) -> Option<Function> {
+    AbiParser::default().parse_function(signature).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::FunctionExt;
+    use hex_literal::hex;
+
+    // PUSH1 0xe0 SHR DUP1 PUSH4 0xa9059cbb EQ PUSH2 0x0030 JUMPI
+    // DUP1 PUSH4 0x70a08231 EQ PUSH2 0x0040 JUMPI
+    const DISPATCHER: [u8; 25] = hex!("60e01c8063a9059cbb1461003057806370a082311461004057");
+
+    #[test]
+    fn ignores_mismatched_signatures() {
+        let mut db = LocalSignatureDatabase::new();
+        db.insert_with_selector(hex!("a9059cbb"), "approve(address,uint256)");
+        assert!(db.is_empty());
+    }
+
+    #[test]
+    fn guesses_interface() {
+        let db = LocalSignatureDatabase::from_signatures([
+            "transfer(address,uint256)",
+            "balanceOf(address)",
+            "totalSupply()",
+        ]);
+
+        let interface = guess_interface(DISPATCHER, &db);
+        assert_eq!(interface.selectors, [hex!("a9059cbb"), hex!("70a08231")]);
+        assert!(interface.unknown.is_empty());
+        assert!(interface.ambiguous.is_empty());
+
+        let transfer = interface.abi.function("transfer").unwrap();
+        assert_eq!(transfer.abi_signature(), "transfer(address,uint256)");
+        assert!(interface.abi.function("balanceOf").is_ok());
+        assert!(interface.abi.function("totalSupply").is_err());
+    }
+
+    #[test]
+    fn reports_unknown_selectors() {
+        let db = LocalSignatureDatabase::from_signatures(["balanceOf(address)"]);
+        let interface = guess_interface(DISPATCHER, db);
+        assert_eq!(interface.unknown, [hex!("a9059cbb")]);
+        assert_eq!(interface.abi.functions().count(), 1);
+    }
+}
@@ -31,6 +31,12 @@ pub use raw::{AbiObject, Component, Item, JsonAbi, RawAbi};
 mod packed;
 pub use packed::{encode_packed, EncodePackedError};
 
+mod registry;
+pub use registry::{AbiRegistry, DecodedEvent, DecodedFunction};
+
+mod guess;
+pub use guess::{guess_interface, GuessedInterface, LocalSignatureDatabase, SignatureDatabase};
+
 mod sealed {
     use ethabi::{Event, Function};
 
@@ -0,0 +1,301 @@
+//! A registry of known events and functions for decoding logs and calldata of arbitrary
+//! contracts.
+
+use crate::{
+    abi::{parse_abi, Abi, Event, Function, LogParam, RawLog, Token},
+    types::{Address, Log, Selector, H256},
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// The events of the ERC-20, ERC-721 and ERC-1155 token standards.
+const TOKEN_EVENTS: &[&str] = &[
+    // ERC-20
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    // ERC-721
+    "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+    "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+    // ERC-721 and ERC-1155
+    "event ApprovalForAll(address indexed owner, address indexed operator, bool approved)",
+    // ERC-1155
+    "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+    "event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values)",
+];
+
+/// The functions of the ERC-20, ERC-721 and ERC-1155 token standards that move or approve tokens.
+const TOKEN_FUNCTIONS: &[&str] = &[
+    // ERC-20, `transferFrom` and `approve` share their selectors with ERC-721
+    "function transfer(address to, uint256 value)",
+    "function transferFrom(address from, address to, uint256 value)",
+    "function approve(address spender, uint256 value)",
+    // ERC-721
+    "function safeTransferFrom(address from, address to, uint256 tokenId)",
+    "function safeTransferFrom(address from, address to, uint256 tokenId, bytes data)",
+    // ERC-721 and ERC-1155
+    "function setApprovalForAll(address operator, bool approved)",
+    // ERC-1155
+    "function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data)",
+    "function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] values, bytes data)",
+];
+
+/// Known events, keyed by their topic, and functions, keyed by their selector, used to decode
+/// logs and calldata without knowing the ABI of the contract up front.
+///
+/// Events and functions registered for a specific contract with [`AbiRegistry::insert_contract`]
+/// take precedence over those registered for all contracts. Events that share a signature but
+/// differ in which parameters are indexed, like the `Transfer` events of ERC-20 and ERC-721, are
+/// told apart by the number of topics of the log.
+///
+/// # Example
+///
+/// ```
+/// use ethers_core::{abi::AbiRegistry, types::Log};
+///
+/// let registry = AbiRegistry::with_token_standards();
+/// # let log = Log::default();
+/// if let Some(event) = registry.decode_log(&log) {
+///     println!("{}: {:?}", event.name, event.params);
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AbiRegistry {
+    events: BTreeMap<H256, Vec<Event>>,
+    contracts: HashMap<Address, BTreeMap<H256, Vec<Event>>>,
+    functions: BTreeMap<Selector, Vec<Function>>,
+    contract_functions: HashMap<Address, BTreeMap<Selector, Vec<Function>>>,
+}
+
+impl AbiRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the events and the transfer and approval functions of the ERC-20,
+    /// ERC-721 and ERC-1155 token standards.
+    pub fn with_token_standards() -> Self {
+        let mut registry = Self::new();
+        registry.insert_abi(&parse_abi(TOKEN_EVENTS).expect("token events are valid"));
+        registry.insert_abi(&parse_abi(TOKEN_FUNCTIONS).expect("token functions are valid"));
+        registry
+    }
+
+    /// Registers `event` for all contracts.
+    pub fn insert_event(&mut self, event: Event) {
+        insert(&mut self.events, event);
+    }
+
+    /// Registers `function` for all contracts.
+    pub fn insert_function(&mut self, function: Function) {
+        insert_function(&mut self.functions, function);
+    }
+
+    /// Registers all events and functions of `abi` for all contracts.
+    pub fn insert_abi(&mut self, abi: &Abi) {
+        for event in abi.events() {
+            self.insert_event(event.clone());
+        }
+        for function in abi.functions() {
+            self.insert_function(function.clone());
+        }
+    }
+
+    /// Registers all events and functions of `abi` for the contract at `address`.
+    pub fn insert_contract(&mut self, address: Address, abi: &Abi) {
+        let events = self.contracts.entry(address).or_default();
+        for event in abi.events() {
+            insert(events, event.clone());
+        }
+        let functions = self.contract_functions.entry(address).or_default();
+        for function in abi.functions() {
+            insert_function(functions, function.clone());
+        }
+    }
+
+    /// Returns the events registered for `topic`, those of the contract at `address` first.
+    pub fn events(&self, address: Address, topic: H256) -> impl Iterator<Item = &Event> + '_ {
+        let contract = self.contracts.get(&address).and_then(|events| events.get(&topic));
+        contract.into_iter().chain(self.events.get(&topic)).flatten()
+    }
+
+    /// Decodes `log` with the first matching registered event.
+    ///
+    /// Returns `None` for anonymous logs and logs of unknown events.
+    pub fn decode_log(&self, log: &Log) -> Option<DecodedEvent> {
+        let topic = *log.topics.first()?;
+        self.events(log.address, topic).find_map(|event| {
+            let raw = RawLog { topics: log.topics.clone(), data: log.data.to_vec() };
+            let decoded = event.parse_log(raw).ok()?;
+            Some(DecodedEvent { name: event.name.clone(), params: decoded.params })
+        })
+    }
+
+    /// Returns the functions registered for `selector`, those of the contract at `address` first.
+    pub fn functions(
+        &self,
+        address: Address,
+        selector: Selector,
+    ) -> impl Iterator<Item = &Function> + '_ {
+        let contract =
+            self.contract_functions.get(&address).and_then(|functions| functions.get(&selector));
+        contract.into_iter().chain(self.functions.get(&selector)).flatten()
+    }
+
+    /// Decodes the calldata `data` of a call to the contract at `to` with the first matching
+    /// registered function.
+    ///
+    /// Returns `None` if the calldata is shorter than a selector, the function is unknown or the
+    /// arguments can not be decoded.
+    pub fn decode_call(&self, to: Address, data: &[u8]) -> Option<DecodedFunction> {
+        let selector: Selector = data.get(..4)?.try_into().ok()?;
+        self.functions(to, selector).find_map(|function| {
+            let tokens = function.decode_input(&data[4..]).ok()?;
+            let params = function
+                .inputs
+                .iter()
+                .zip(tokens)
+                .map(|(input, value)| LogParam { name: input.name.clone(), value })
+                .collect();
+            let types: Vec<_> =
+                function.inputs.iter().map(|input| input.kind.to_string()).collect();
+            let signature = format!("{}({})", function.name, types.join(","));
+            Some(DecodedFunction { name: function.name.clone(), signature, params })
+        })
+    }
+
+    /// Returns `true` if no events or functions are registered.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() &&
+            self.contracts.is_empty() &&
+            self.functions.is_empty() &&
+            self.contract_functions.is_empty()
+    }
+}
+
+fn insert(events: &mut BTreeMap<H256, Vec<Event>>, event: Event) {
+    if event.anonymous {
+        return
+    }
+    let entry = events.entry(event.signature()).or_default();
+    if !entry.contains(&event) {
+        entry.push(event);
+    }
+}
+
+fn insert_function(functions: &mut BTreeMap<Selector, Vec<Function>>, function: Function) {
+    let entry = functions.entry(function.short_signature()).or_default();
+    if !entry.contains(&function) {
+        entry.push(function);
+    }
+}
+
+/// An event decoded by an [`AbiRegistry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedEvent {
+    /// The name of the event
+    pub name: String,
+    /// The decoded parameters, in the order of the event's inputs
+    pub params: Vec<LogParam>,
+}
+
+impl DecodedEvent {
+    /// Returns the value of the parameter called `name`.
+    pub fn param(&self, name: &str) -> Option<&Token> {
+        self.params.iter().find(|param| param.name == name).map(|param| &param.value)
+    }
+}
+
+/// A function call decoded by an [`AbiRegistry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedFunction {
+    /// The name of the function
+    pub name: String,
+    /// The signature of the function, e.g. `transfer(address,uint256)`
+    pub signature: String,
+    /// The decoded arguments, in the order of the function's inputs
+    pub params: Vec<LogParam>,
+}
+
+impl DecodedFunction {
+    /// Returns the value of the argument called `name`.
+    pub fn param(&self, name: &str) -> Option<&Token> {
+        self.params.iter().find(|param| param.name == name).map(|param| &param.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{abi::encode, types::U256};
+
+    fn topic(address: Address) -> H256 {
+        address.into()
+    }
+
+    #[test]
+    fn decodes_erc20_and_erc721_transfers() {
+        let registry = AbiRegistry::with_token_standards();
+        let signature: H256 = crate::utils::keccak256("Transfer(address,address,uint256)").into();
+        let (from, to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        let erc20 = Log {
+            topics: vec![signature, topic(from), topic(to)],
+            data: encode(&[Token::Uint(100.into())]).into(),
+            ..Default::default()
+        };
+        let event = registry.decode_log(&erc20).unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(event.param("value"), Some(&Token::Uint(100.into())));
+
+        let erc721 = Log {
+            topics: vec![signature, topic(from), topic(to), H256::from_low_u64_be(7)],
+            ..Default::default()
+        };
+        let event = registry.decode_log(&erc721).unwrap();
+        assert_eq!(event.param("tokenId"), Some(&Token::Uint(U256::from(7))));
+        assert_eq!(event.param("to"), Some(&Token::Address(to)));
+    }
+
+    #[test]
+    fn prefers_contract_events() {
+        let mut registry = AbiRegistry::with_token_standards();
+        let address = Address::repeat_byte(0xaa);
+        let abi = parse_abi(&[
+            "event Transfer(address indexed sender, address indexed receiver, uint256 amount)",
+        ])
+        .unwrap();
+        registry.insert_contract(address, &abi);
+
+        let signature: H256 = crate::utils::keccak256("Transfer(address,address,uint256)").into();
+        let mut log = Log {
+            address,
+            topics: vec![signature, H256::zero(), H256::zero()],
+            data: encode(&[Token::Uint(1.into())]).into(),
+            ..Default::default()
+        };
+        assert!(registry.decode_log(&log).unwrap().param("amount").is_some());
+
+        log.address = Address::zero();
+        assert!(registry.decode_log(&log).unwrap().param("value").is_some());
+
+        log.topics[0] = H256::zero();
+        assert!(registry.decode_log(&log).is_none());
+    }
+
+    #[test]
+    fn decodes_token_calls() {
+        let registry = AbiRegistry::with_token_standards();
+        let to = Address::repeat_byte(2);
+        let transfer = crate::utils::id("transfer(address,uint256)");
+        let data = [&transfer[..], &encode(&[Token::Address(to), Token::Uint(5.into())])].concat();
+
+        let call = registry.decode_call(Address::zero(), &data).unwrap();
+        assert_eq!(call.name, "transfer");
+        assert_eq!(call.signature, "transfer(address,uint256)");
+        assert_eq!(call.param("to"), Some(&Token::Address(to)));
+        assert_eq!(call.param("value"), Some(&Token::Uint(5.into())));
+
+        assert!(registry.decode_call(Address::zero(), &transfer).is_none());
+        assert!(registry.decode_call(Address::zero(), &[0xde, 0xad]).is_none());
+    }
+}
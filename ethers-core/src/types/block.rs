@@ -94,6 +94,18 @@ pub struct Block<TX> {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg(not(feature = "celo"))]
     pub withdrawals: Option<Vec<Withdrawal>>,
+    /// Blob gas used by the block's transactions (if past Cancun)
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "blobGasUsed")]
+    #[cfg(not(feature = "celo"))]
+    pub blob_gas_used: Option<U256>,
+    /// Blob gas above the target accumulated by previous blocks (if past Cancun)
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "excessBlobGas")]
+    #[cfg(not(feature = "celo"))]
+    pub excess_blob_gas: Option<U256>,
+    /// Root of the parent beacon block (if past Cancun)
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "parentBeaconBlockRoot")]
+    #[cfg(not(feature = "celo"))]
+    pub parent_beacon_block_root: Option<H256>,
 
     #[cfg(feature = "celo")]
     #[cfg_attr(docsrs, doc(cfg(feature = "celo")))]
@@ -140,6 +152,14 @@ pub const ELASTICITY_MULTIPLIER: U256 = U256([2u64, 0, 0, 0]);
 #[cfg(not(feature = "celo"))]
 pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: U256 = U256([8u64, 0, 0, 0]);
 
+// ref <https://eips.ethereum.org/EIPS/eip-4844>
+#[cfg(not(feature = "celo"))]
+pub const TARGET_BLOB_GAS_PER_BLOCK: u64 = 393_216;
+#[cfg(not(feature = "celo"))]
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+#[cfg(not(feature = "celo"))]
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3_338_477;
+
 impl<TX> Block<TX> {
     /// The target gas usage as per EIP-1559
     #[cfg(not(feature = "celo"))]
@@ -199,6 +219,97 @@ impl<TX> Block<TX> {
         let secs = self.timestamp.as_u64() as i64;
         Ok(Utc.timestamp_opt(secs, 0).unwrap())
     }
+
+    /// Computes the hash of the block header from its fields.
+    ///
+    /// Unlike [`Self::hash`], which is reported by the node, the computed hash can be compared
+    /// against a trusted block hash to check that the header fields, e.g. the
+    /// [`Self::state_root`], are genuine.
+    ///
+    /// Returns `None` for pending blocks.
+    #[cfg(not(feature = "celo"))]
+    pub fn header_hash(&self) -> Option<H256> {
+        let mut rlp = rlp::RlpStream::new();
+        rlp.begin_unbounded_list();
+        rlp.append(&self.parent_hash);
+        rlp.append(&self.uncles_hash);
+        rlp.append(&self.author?);
+        rlp.append(&self.state_root);
+        rlp.append(&self.transactions_root);
+        rlp.append(&self.receipts_root);
+        rlp.append(&self.logs_bloom?);
+        rlp.append(&self.difficulty);
+        rlp.append(&self.number?);
+        rlp.append(&self.gas_limit);
+        rlp.append(&self.gas_used);
+        rlp.append(&self.timestamp);
+        rlp.append(&self.extra_data.0);
+        rlp.append(&self.mix_hash?);
+        rlp.append(&self.nonce?);
+
+        // fields added by later forks, each implies all previous ones
+        let optional = [
+            self.base_fee_per_gas.map(|fee| rlp::encode(&fee)),
+            self.withdrawals_root.map(|root| rlp::encode(&root)),
+            self.blob_gas_used.map(|gas| rlp::encode(&gas)),
+            self.excess_blob_gas.map(|gas| rlp::encode(&gas)),
+            self.parent_beacon_block_root.map(|root| rlp::encode(&root)),
+        ];
+        for field in optional.iter().map_while(Option::as_ref) {
+            rlp.append_raw(field, 1);
+        }
+
+        rlp.finalize_unbounded_list();
+        Some(H256(crate::utils::keccak256(rlp.out())))
+    }
+
+    /// Returns `true` if the block has the fields added by Shanghai, i.e. withdrawals.
+    #[cfg(not(feature = "celo"))]
+    pub fn is_shanghai(&self) -> bool {
+        self.withdrawals_root.is_some()
+    }
+
+    /// Returns `true` if the block has the fields added by Cancun, i.e. blob gas accounting.
+    #[cfg(not(feature = "celo"))]
+    pub fn is_cancun(&self) -> bool {
+        self.excess_blob_gas.is_some()
+    }
+
+    /// Returns the base fee per unit of blob gas of the block, as per EIP-4844.
+    ///
+    /// Returns `None` for blocks before Cancun.
+    #[cfg(not(feature = "celo"))]
+    pub fn blob_base_fee(&self) -> Option<U256> {
+        Some(fake_exponential(
+            MIN_BLOB_BASE_FEE.into(),
+            self.excess_blob_gas?,
+            BLOB_BASE_FEE_UPDATE_FRACTION.into(),
+        ))
+    }
+
+    /// Returns the excess blob gas of the next block, as per EIP-4844.
+    ///
+    /// Returns `None` for blocks before Cancun.
+    #[cfg(not(feature = "celo"))]
+    pub fn next_block_excess_blob_gas(&self) -> Option<U256> {
+        let total = self.excess_blob_gas?.saturating_add(self.blob_gas_used.unwrap_or_default());
+        Some(total.saturating_sub(TARGET_BLOB_GAS_PER_BLOCK.into()))
+    }
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using a Taylor expansion, as per
+/// EIP-4844.
+#[cfg(not(feature = "celo"))]
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut output = U256::zero();
+    let mut accum = factor.saturating_mul(denominator);
+    let mut i = U256::one();
+    while !accum.is_zero() {
+        output = output.saturating_add(accum);
+        accum = accum.saturating_mul(numerator) / denominator.saturating_mul(i);
+        i += U256::one();
+    }
+    output / denominator
 }
 
 impl Block<TxHash> {
@@ -230,6 +341,9 @@ impl Block<TxHash> {
                 base_fee_per_gas,
                 withdrawals_root,
                 withdrawals,
+                blob_gas_used,
+                excess_blob_gas,
+                parent_beacon_block_root,
                 other,
                 ..
             } = self;
@@ -257,6 +371,9 @@ impl Block<TxHash> {
                 base_fee_per_gas,
                 withdrawals_root,
                 withdrawals,
+                blob_gas_used,
+                excess_blob_gas,
+                parent_beacon_block_root,
                 transactions,
                 other,
             }
@@ -338,6 +455,9 @@ impl From<Block<Transaction>> for Block<TxHash> {
                 base_fee_per_gas,
                 withdrawals_root,
                 withdrawals,
+                blob_gas_used,
+                excess_blob_gas,
+                parent_beacon_block_root,
                 other,
             } = full;
             Block {
@@ -364,6 +484,9 @@ impl From<Block<Transaction>> for Block<TxHash> {
                 base_fee_per_gas,
                 withdrawals_root,
                 withdrawals,
+                blob_gas_used,
+                excess_blob_gas,
+                parent_beacon_block_root,
                 transactions: transactions.iter().map(|tx| tx.hash).collect(),
                 other,
             }
@@ -926,7 +1049,54 @@ mod tests {
           "uncles": []
         }
               );
-        let _block: Block<TxHash> = serde_json::from_value(json).unwrap();
+        let block: Block<TxHash> = serde_json::from_value(json).unwrap();
+        assert_eq!(block.header_hash(), block.hash);
+    }
+
+    #[test]
+    fn cancun_block() {
+        let json = serde_json::json!({
+          "number": "0x1286a1b",
+          "hash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+          "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+          "miner": "0x0000000000000000000000000000000000000003",
+          "logsBloom": null,
+          "gasLimit": "0x1c9c380",
+          "gasUsed": "0x0",
+          "timestamp": "0x65f1b057",
+          "baseFeePerGas": "0x7",
+          "withdrawalsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+          "withdrawals": [],
+          "blobGasUsed": "0xc0000",
+          "excessBlobGas": "0x3c0000",
+          "parentBeaconBlockRoot": "0x0000000000000000000000000000000000000000000000000000000000000004",
+          "transactions": [],
+          "uncles": []
+        });
+        let block: Block<TxHash> = serde_json::from_value(json.clone()).unwrap();
+        assert!(block.is_shanghai() && block.is_cancun());
+        assert_eq!(block.blob_gas_used, Some(0xc0000.into()));
+        assert_eq!(block.parent_beacon_block_root, Some(H256::from_low_u64_be(4)));
+        assert!(block.other.is_empty());
+        // 3_932_160 excess blob gas
+        assert_eq!(block.blob_base_fee(), Some(3.into()));
+        assert_eq!(block.next_block_excess_blob_gas(), Some((0x3c0000 + 0xc0000 - 393_216).into()));
+
+        let serialized = serde_json::to_value(&block).unwrap();
+        assert_eq!(serialized["excessBlobGas"], json["excessBlobGas"]);
+
+        // pre-Cancun blocks have none of the fields
+        let block: Block<TxHash> = serde_json::from_value(serde_json::json!({
+          "number": "0x1", "hash": null, "logsBloom": null, "transactions": []
+        }))
+        .unwrap();
+        assert!(!block.is_shanghai() && !block.is_cancun());
+        assert_eq!(block.blob_base_fee(), None);
+        assert!(serde_json::to_value(&block).unwrap().get("blobGasUsed").is_none());
+
+        let block =
+            Block::<TxHash> { excess_blob_gas: Some(100_000_000.into()), ..Default::default() };
+        assert_eq!(block.blob_base_fee(), Some(10_203_769_476_395u64.into()));
     }
 }
 
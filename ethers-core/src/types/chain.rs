@@ -1,8 +1,9 @@
-use super::{U128, U256, U512, U64};
+use super::{Address, H256, U128, U256, U512, U64};
 use serde::{Deserialize, Serialize, Serializer};
 use std::{
     convert::{TryFrom, TryInto},
     fmt,
+    sync::RwLock,
     time::Duration,
 };
 use strum::{AsRefStr, EnumCount, EnumIter, EnumString, EnumVariantNames};
@@ -26,6 +27,9 @@ pub type ParseChainError = TryFromPrimitiveError<Chain>;
 //      More info: <https://serde.rs/variant-attrs.html>
 //     - Add a test at the bottom of the file
 
+/// Explorer URLs registered with [`Chain::set_explorer_url`], taking precedence over the defaults.
+static EXPLORER_URLS: RwLock<Vec<(Chain, String)>> = RwLock::new(Vec::new());
+
 // We don't derive Serialize because it is manually implemented using AsRef<str> and it would
 // break a lot of things since Serialize is `kebab-case` vs Deserialize `snake_case`.
 // This means that the Chain type is not "round-trippable", because the Serialize and Deserialize
@@ -62,14 +66,17 @@ pub enum Chain {
     Goerli = 5,
     Kovan = 42,
     Sepolia = 11155111,
+    Holesky = 17000,
 
     Optimism = 10,
     OptimismKovan = 69,
     OptimismGoerli = 420,
+    OptimismSepolia = 11155420,
 
     Arbitrum = 42161,
     ArbitrumTestnet = 421611,
     ArbitrumGoerli = 421613,
+    ArbitrumSepolia = 421614,
     ArbitrumNova = 42170,
 
     Cronos = 25,
@@ -87,6 +94,8 @@ pub enum Chain {
     Poa = 99,
     Sokol = 77,
 
+    Scroll = 534352,
+    ScrollSepolia = 534351,
     ScrollAlphaTestnet = 534353,
 
     Metis = 1088,
@@ -151,9 +160,22 @@ pub enum Chain {
 
     Boba = 288,
 
+    Base = 8453,
     BaseGoerli = 84531,
+    BaseSepolia = 84532,
 
+    Linea = 59144,
     LineaTestnet = 59140,
+    LineaSepolia = 59141,
+
+    Mantle = 5000,
+    MantleSepolia = 5003,
+
+    Blast = 81457,
+    BlastSepolia = 168587773,
+
+    Mode = 34443,
+    ModeSepolia = 919,
 
     #[strum(to_string = "zksync")]
     #[serde(alias = "zksync")]
@@ -161,6 +183,9 @@ pub enum Chain {
     #[strum(to_string = "zksync-testnet")]
     #[serde(alias = "zksync_testnet")]
     ZkSyncTestnet = 280,
+    #[strum(to_string = "zksync-sepolia")]
+    #[serde(alias = "zksync_sepolia")]
+    ZkSyncSepolia = 300,
 }
 
 // === impl Chain ===
@@ -277,9 +302,12 @@ impl Chain {
         use Chain::*;
 
         let ms = match self {
-            Mainnet => 12_000,
-            Arbitrum | ArbitrumTestnet | ArbitrumGoerli | ArbitrumNova => 1_300,
-            Optimism | OptimismGoerli => 2_000,
+            Mainnet | Holesky => 12_000,
+            Arbitrum | ArbitrumTestnet | ArbitrumGoerli | ArbitrumSepolia | ArbitrumNova => 1_300,
+            Optimism | OptimismGoerli | OptimismSepolia => 2_000,
+            Base | BaseSepolia | Blast | BlastSepolia | Mode | ModeSepolia => 2_000,
+            Mantle | MantleSepolia | Linea | LineaSepolia => 2_000,
+            ZkSync | ZkSyncSepolia => 1_000,
             Polygon | PolygonMumbai => 2_100,
             Moonbeam | Moonriver => 12_500,
             BinanceSmartChain | BinanceSmartChainTestnet => 3_000,
@@ -293,11 +321,11 @@ impl Chain {
             Dev | AnvilHardhat => 200,
             Celo | CeloAlfajores | CeloBaklava => 5_000,
             FilecoinHyperspaceTestnet | FilecoinMainnet => 30_000,
-            ScrollAlphaTestnet => 3_000,
+            Scroll | ScrollSepolia | ScrollAlphaTestnet => 3_000,
             // Explicitly exhaustive. See NB above.
             Morden | Ropsten | Rinkeby | Goerli | Kovan | XDai | Chiado | Sepolia | Moonbase |
             MoonbeamDev | OptimismKovan | Poa | Sokol | Rsk | EmeraldTestnet | Boba |
-            BaseGoerli | ZkSync | ZkSyncTestnet | PolygonZkEvm | PolygonZkEvmTestnet | Metis |
+            BaseGoerli | ZkSyncTestnet | PolygonZkEvm | PolygonZkEvmTestnet | Metis |
             LineaTestnet => return None,
         };
 
@@ -335,6 +363,7 @@ impl Chain {
             Boba |
             ZkSync |
             ZkSyncTestnet |
+            ZkSyncSepolia |
             BaseGoerli |
             PolygonZkEvm |
             PolygonZkEvmTestnet => true,
@@ -343,17 +372,32 @@ impl Chain {
             Mainnet |
             Goerli |
             Sepolia |
+            Holesky |
             Optimism |
             OptimismGoerli |
+            OptimismSepolia |
             Polygon |
             PolygonMumbai |
             Avalanche |
             AvalancheFuji |
             Arbitrum |
             ArbitrumGoerli |
+            ArbitrumSepolia |
             ArbitrumNova |
             FilecoinMainnet |
+            Base |
+            BaseSepolia |
+            Scroll |
+            ScrollSepolia |
+            Linea |
             LineaTestnet |
+            LineaSepolia |
+            Mantle |
+            MantleSepolia |
+            Blast |
+            BlastSepolia |
+            Mode |
+            ModeSepolia |
             FilecoinHyperspaceTestnet => false,
 
             // Unknown / not applicable, default to false for backwards compatibility
@@ -364,17 +408,78 @@ impl Chain {
         }
     }
 
+    /// Returns whether the chain accepts EIP-1559 transactions, i.e. the inverse of
+    /// [`is_legacy`](Chain::is_legacy).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::Chain;
+    ///
+    /// assert!(Chain::Base.supports_eip1559());
+    /// assert!(!Chain::ZkSync.supports_eip1559());
+    /// ```
+    pub const fn supports_eip1559(&self) -> bool {
+        !self.is_legacy()
+    }
+
     /// Returns whether the chain supports the `PUSH0` opcode or not.
     ///
     /// For more information, see EIP-3855:
     /// `<https://eips.ethereum.org/EIPS/eip-3855>`
     pub const fn supports_push0(&self) -> bool {
         match self {
-            Chain::Mainnet | Chain::Goerli | Chain::Sepolia => true,
+            Chain::Mainnet | Chain::Goerli | Chain::Sepolia | Chain::Holesky => true,
             _ => false,
         }
     }
 
+    /// Returns the symbol of the chain's native currency, which pays for gas.
+    ///
+    /// Testnets return the symbol of their mainnet's currency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::Chain;
+    ///
+    /// assert_eq!(Chain::Mainnet.native_currency_symbol(), "ETH");
+    /// assert_eq!(Chain::Base.native_currency_symbol(), "ETH");
+    /// assert_eq!(Chain::Mantle.native_currency_symbol(), "MNT");
+    /// ```
+    pub const fn native_currency_symbol(&self) -> &'static str {
+        use Chain::*;
+
+        match self {
+            Mainnet | Morden | Ropsten | Rinkeby | Goerli | Kovan | Sepolia | Holesky |
+            Optimism | OptimismKovan | OptimismGoerli | OptimismSepolia | Arbitrum |
+            ArbitrumTestnet | ArbitrumGoerli | ArbitrumSepolia | ArbitrumNova | Base |
+            BaseGoerli | BaseSepolia | Scroll | ScrollSepolia | ScrollAlphaTestnet | Linea |
+            LineaTestnet | LineaSepolia | ZkSync | ZkSyncTestnet | ZkSyncSepolia | Blast |
+            BlastSepolia | Mode | ModeSepolia | PolygonZkEvm | PolygonZkEvmTestnet | Boba |
+            Aurora | AuroraTestnet | Dev | AnvilHardhat => "ETH",
+            Mantle | MantleSepolia => "MNT",
+            Polygon | PolygonMumbai => "POL",
+            BinanceSmartChain | BinanceSmartChainTestnet => "BNB",
+            Avalanche | AvalancheFuji => "AVAX",
+            Fantom | FantomTestnet => "FTM",
+            Cronos | CronosTestnet => "CRO",
+            XDai | Chiado => "xDAI",
+            Celo | CeloAlfajores | CeloBaklava => "CELO",
+            Moonbeam => "GLMR",
+            Moonriver => "MOVR",
+            Moonbase | MoonbeamDev => "DEV",
+            Metis => "METIS",
+            Rsk => "RBTC",
+            Poa | Sokol => "POA",
+            Evmos | EvmosTestnet => "EVMOS",
+            Oasis => "OAC",
+            Emerald | EmeraldTestnet => "ROSE",
+            FilecoinMainnet | FilecoinHyperspaceTestnet => "FIL",
+            Canto | CantoTestnet => "CANTO",
+        }
+    }
+
     /// Returns the chain's blockchain explorer and its API (Etherscan and Etherscan-like) URLs.
     ///
     /// Returns `(API_URL, BASE_URL)`
@@ -404,6 +509,7 @@ impl Chain {
             Rinkeby => ("https://api-rinkeby.etherscan.io/api", "https://rinkeby.etherscan.io"),
             Goerli => ("https://api-goerli.etherscan.io/api", "https://goerli.etherscan.io"),
             Sepolia => ("https://api-sepolia.etherscan.io/api", "https://sepolia.etherscan.io"),
+            Holesky => ("https://api-holesky.etherscan.io/api", "https://holesky.etherscan.io"),
 
             Polygon => ("https://api.polygonscan.com/api", "https://polygonscan.com"),
             PolygonMumbai => {
@@ -434,6 +540,10 @@ impl Chain {
                 "https://api-kovan-optimistic.etherscan.io/api",
                 "https://kovan-optimistic.etherscan.io",
             ),
+            OptimismSepolia => (
+                "https://api-sepolia-optimistic.etherscan.io/api",
+                "https://sepolia-optimism.etherscan.io",
+            ),
 
             Fantom => ("https://api.ftmscan.com/api", "https://ftmscan.com"),
             FantomTestnet => ("https://api-testnet.ftmscan.com/api", "https://testnet.ftmscan.com"),
@@ -448,6 +558,9 @@ impl Chain {
                 ("https://api-testnet.arbiscan.io/api", "https://testnet.arbiscan.io")
             }
             ArbitrumGoerli => ("https://api-goerli.arbiscan.io/api", "https://goerli.arbiscan.io"),
+            ArbitrumSepolia => {
+                ("https://api-sepolia.arbiscan.io/api", "https://sepolia.arbiscan.io")
+            }
             ArbitrumNova => ("https://api-nova.arbiscan.io/api", "https://nova.arbiscan.io/"),
 
             Cronos => ("https://api.cronoscan.com/api", "https://cronoscan.com"),
@@ -464,6 +577,10 @@ impl Chain {
                 ("https://blockscout.com/xdai/mainnet/api", "https://blockscout.com/xdai/mainnet")
             }
 
+            Scroll => ("https://api.scrollscan.com/api", "https://scrollscan.com"),
+            ScrollSepolia => {
+                ("https://api-sepolia.scrollscan.com/api", "https://sepolia.scrollscan.com")
+            }
             ScrollAlphaTestnet => {
                 ("https://blockscout.scroll.io/api", "https://blockscout.scroll.io/")
             }
@@ -520,7 +637,9 @@ impl Chain {
 
             Boba => ("https://api.bobascan.com/api", "https://bobascan.com"),
 
+            Base => ("https://api.basescan.org/api", "https://basescan.org"),
             BaseGoerli => ("https://api-goerli.basescan.org/api", "https://goerli.basescan.org"),
+            BaseSepolia => ("https://api-sepolia.basescan.org/api", "https://sepolia.basescan.org"),
 
             ZkSync => {
                 ("https://zksync2-mainnet-explorer.zksync.io/", "https://explorer.zksync.io/")
@@ -529,9 +648,34 @@ impl Chain {
                 "https://zksync2-testnet-explorer.zksync.dev/",
                 "https://goerli.explorer.zksync.io/",
             ),
+            ZkSyncSepolia => (
+                "https://block-explorer-api.sepolia.zksync.dev/api",
+                "https://sepolia.explorer.zksync.io",
+            ),
+
+            Linea => ("https://api.lineascan.build/api", "https://lineascan.build"),
             LineaTestnet => {
                 ("https://explorer.goerli.linea.build/api", "https://explorer.goerli.linea.build/")
             }
+            LineaSepolia => {
+                ("https://api-sepolia.lineascan.build/api", "https://sepolia.lineascan.build")
+            }
+
+            Mantle => ("https://explorer.mantle.xyz/api", "https://explorer.mantle.xyz"),
+            MantleSepolia => {
+                ("https://explorer.sepolia.mantle.xyz/api", "https://explorer.sepolia.mantle.xyz")
+            }
+
+            Blast => ("https://api.blastscan.io/api", "https://blastscan.io"),
+            BlastSepolia => {
+                ("https://api-sepolia.blastscan.io/api", "https://sepolia.blastscan.io")
+            }
+
+            Mode => ("https://explorer.mode.network/api", "https://explorer.mode.network"),
+            ModeSepolia => (
+                "https://sepolia.explorer.mode.network/api",
+                "https://sepolia.explorer.mode.network",
+            ),
 
             AnvilHardhat | Dev | Morden | MoonbeamDev | FilecoinMainnet => {
                 // this is explicitly exhaustive so we don't forget to add new urls when adding a
@@ -563,14 +707,17 @@ impl Chain {
             Kovan |
             Rinkeby |
             Goerli |
+            Holesky |
             Optimism |
             OptimismGoerli |
             OptimismKovan |
+            OptimismSepolia |
             BinanceSmartChain |
             BinanceSmartChainTestnet |
             Arbitrum |
             ArbitrumTestnet |
             ArbitrumGoerli |
+            ArbitrumSepolia |
             ArbitrumNova |
             Cronos |
             CronosTestnet |
@@ -589,7 +736,17 @@ impl Chain {
 
             Moonbeam | Moonbase | MoonbeamDev | Moonriver => "MOONSCAN_API_KEY",
 
-            Canto | CantoTestnet => "BLOCKSCOUT_API_KEY",
+            Canto | CantoTestnet | Mantle | MantleSepolia | Mode | ModeSepolia => {
+                "BLOCKSCOUT_API_KEY"
+            }
+
+            Base | BaseSepolia => "BASESCAN_API_KEY",
+
+            Scroll | ScrollSepolia => "SCROLLSCAN_API_KEY",
+
+            Linea | LineaSepolia => "LINEASCAN_API_KEY",
+
+            Blast | BlastSepolia => "BLASTSCAN_API_KEY",
 
             Boba => "BOBASCAN_API_KEY",
 
@@ -611,6 +768,7 @@ impl Chain {
             Dev |
             ZkSync |
             ZkSyncTestnet |
+            ZkSyncSepolia |
             FilecoinMainnet |
             LineaTestnet |
             FilecoinHyperspaceTestnet => return None,
@@ -634,6 +792,100 @@ impl Chain {
     pub fn etherscan_api_key(&self) -> Option<String> {
         self.etherscan_api_key_name().and_then(|name| std::env::var(name).ok())
     }
+
+    /// Overrides the blockchain explorer base URL of the chain for the whole process, or restores
+    /// the default if `url` is `None`.
+    ///
+    /// This affects [`explorer_url`](Chain::explorer_url) and the URL helpers built on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::Chain;
+    ///
+    /// Chain::AnvilHardhat.set_explorer_url(Some("http://localhost:5100/"));
+    /// assert_eq!(Chain::AnvilHardhat.explorer_url().as_deref(), Some("http://localhost:5100"));
+    /// assert_eq!(
+    ///     Chain::AnvilHardhat.explorer_block_url(1).as_deref(),
+    ///     Some("http://localhost:5100/block/1")
+    /// );
+    /// ```
+    pub fn set_explorer_url(&self, url: Option<impl Into<String>>) {
+        let mut urls = EXPLORER_URLS.write().unwrap_or_else(|err| err.into_inner());
+        urls.retain(|(chain, _)| chain != self);
+        if let Some(url) = url {
+            urls.push((*self, url.into().trim_end_matches('/').to_string()));
+        }
+    }
+
+    /// Returns the chain's blockchain explorer base URL, without a trailing slash.
+    ///
+    /// This is the URL registered with [`set_explorer_url`](Chain::set_explorer_url), or the
+    /// base URL of [`etherscan_urls`](Chain::etherscan_urls) otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::Chain;
+    ///
+    /// assert_eq!(Chain::Mainnet.explorer_url().as_deref(), Some("https://etherscan.io"));
+    /// assert_eq!(Chain::Dev.explorer_url(), None);
+    /// ```
+    pub fn explorer_url(&self) -> Option<String> {
+        let urls = EXPLORER_URLS.read().unwrap_or_else(|err| err.into_inner());
+        urls.iter()
+            .find(|(chain, _)| chain == self)
+            .map(|(_, url)| url.clone())
+            .or_else(|| self.etherscan_urls().map(|(_, url)| url.to_string()))
+    }
+
+    /// Returns the blockchain explorer URL of the transaction with the given hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::{Chain, H256};
+    ///
+    /// assert_eq!(
+    ///     Chain::Mainnet.explorer_tx_url(H256::zero()).as_deref(),
+    ///     Some("https://etherscan.io/tx/0x0000000000000000000000000000000000000000000000000000000000000000")
+    /// );
+    /// ```
+    pub fn explorer_tx_url(&self, hash: H256) -> Option<String> {
+        self.explorer_url().map(|url| format!("{url}/tx/{hash:?}"))
+    }
+
+    /// Returns the blockchain explorer URL of the given address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::{Address, Chain};
+    ///
+    /// assert_eq!(
+    ///     Chain::Polygon.explorer_address_url(Address::zero()).as_deref(),
+    ///     Some("https://polygonscan.com/address/0x0000000000000000000000000000000000000000")
+    /// );
+    /// ```
+    pub fn explorer_address_url(&self, address: Address) -> Option<String> {
+        self.explorer_url().map(|url| format!("{url}/address/{address:?}"))
+    }
+
+    /// Returns the blockchain explorer URL of the block with the given number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ethers_core::types::Chain;
+    ///
+    /// assert_eq!(
+    ///     Chain::Mainnet.explorer_block_url(17_000_000).as_deref(),
+    ///     Some("https://etherscan.io/block/17000000")
+    /// );
+    /// ```
+    pub fn explorer_block_url(&self, number: u64) -> Option<String> {
+        self.explorer_url().map(|url| format!("{url}/block/{number}"))
+    }
 }
 
 #[cfg(test)]
@@ -688,6 +940,7 @@ mod tests {
             (AnvilHardhat, &["anvil", "hardhat"]),
             (AvalancheFuji, &["fuji"]),
             (ZkSync, &["zksync"]),
+            (ZkSyncSepolia, &["zksync-sepolia"]),
         ];
 
         for &(chain, aliases) in ALIASES {
@@ -699,6 +952,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn l2_metadata() {
+        use Chain::*;
+
+        for chain in [Base, Scroll, Linea, ZkSync, Mantle, Blast, Mode] {
+            assert!(chain.average_blocktime_hint().is_some(), "{chain}");
+            assert!(chain.explorer_url().is_some(), "{chain}");
+        }
+        assert_eq!(Chain::try_from(8453u64).unwrap(), Base);
+        assert_eq!("blast-sepolia".parse::<Chain>().unwrap(), BlastSepolia);
+        assert_eq!(Mode.native_currency_symbol(), "ETH");
+        assert!(Scroll.supports_eip1559() && !ZkSyncSepolia.supports_eip1559());
+    }
+
     #[test]
     fn serde_to_string_match() {
         for chain in Chain::iter() {
@@ -0,0 +1,289 @@
+use super::Chain;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+/// The URL of the [chainid.network](https://chainid.network) chain list.
+pub const CHAINS_JSON_URL: &str = "https://chainid.network/chains.json";
+
+/// Snapshot of chainid.network entries for chains that are not (yet) [`Chain`] variants.
+const BUNDLED_CHAINS_JSON: &str = include_str!("../../data/chains.json");
+
+/// An error thrown when loading a [`ChainRegistry`].
+#[derive(Debug, Error)]
+pub enum ChainRegistryError {
+    /// The chain list is not a JSON array.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The chain list could not be fetched.
+    #[cfg(feature = "online")]
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// The native currency of a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NativeCurrency {
+    /// The currency name, e.g. `Ether`.
+    pub name: String,
+    /// The currency symbol, e.g. `ETH`.
+    pub symbol: String,
+    /// The number of decimals of the currency.
+    pub decimals: u8,
+}
+
+/// A blockchain explorer of a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainExplorer {
+    /// The explorer name.
+    pub name: String,
+    /// The explorer base URL.
+    pub url: String,
+    /// The URL standard the explorer follows, e.g. `EIP3091`.
+    #[serde(default)]
+    pub standard: String,
+}
+
+/// A chain entry, in the format used by [chainid.network](https://chainid.network/chains.json).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainInfo {
+    /// The chain name, e.g. `Ethereum Mainnet`.
+    pub name: String,
+    /// The chain family, e.g. `ETH`.
+    #[serde(default)]
+    pub chain: String,
+    /// The RPC endpoints. These may contain `${VAR}` placeholders for API keys.
+    #[serde(default)]
+    pub rpc: Vec<String>,
+    /// The native currency of the chain.
+    pub native_currency: NativeCurrency,
+    /// The chain's website.
+    #[serde(default, rename = "infoURL", skip_serializing_if = "Option::is_none")]
+    pub info_url: Option<String>,
+    /// The chain's short name, e.g. `eth`.
+    #[serde(default)]
+    pub short_name: String,
+    /// The EIP-155 chain ID.
+    pub chain_id: u64,
+    /// The network ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_id: Option<u64>,
+    /// The blockchain explorers of the chain.
+    #[serde(default)]
+    pub explorers: Vec<ChainExplorer>,
+}
+
+impl ChainInfo {
+    /// Returns the [`Chain`] variant with this entry's chain ID, if any.
+    pub fn chain(&self) -> Option<Chain> {
+        Chain::try_from(self.chain_id).ok()
+    }
+
+    /// Returns the base URL of the chain's first blockchain explorer, without a trailing slash.
+    pub fn explorer_url(&self) -> Option<&str> {
+        self.explorers.first().map(|explorer| explorer.url.trim_end_matches('/'))
+    }
+
+    /// Returns the RPC endpoints which can be used without an API key.
+    pub fn public_rpc_urls(&self) -> impl Iterator<Item = &str> {
+        self.rpc.iter().map(String::as_str).filter(|url| !url.contains("${"))
+    }
+}
+
+impl From<Chain> for ChainInfo {
+    fn from(chain: Chain) -> Self {
+        let symbol = chain.native_currency_symbol();
+        let explorers = chain
+            .explorer_url()
+            .map(|url| {
+                let name = url.split("://").last().unwrap_or(&url).to_string();
+                ChainExplorer { name, url, standard: "EIP3091".to_string() }
+            })
+            .into_iter()
+            .collect();
+        Self {
+            name: chain.to_string(),
+            chain: symbol.to_string(),
+            rpc: Vec::new(),
+            native_currency: NativeCurrency {
+                name: symbol.to_string(),
+                symbol: symbol.to_string(),
+                decimals: 18,
+            },
+            info_url: None,
+            short_name: chain.to_string(),
+            chain_id: chain as u64,
+            network_id: Some(chain as u64),
+            explorers,
+        }
+    }
+}
+
+/// A registry of chain metadata, keyed by chain ID.
+///
+/// This resolves the name, native currency, and RPC and explorer endpoints of chains which are not
+/// [`Chain`] variants, using the [chainid.network](https://chainid.network) chain list.
+///
+/// # Examples
+///
+/// ```
+/// use ethers_core::types::ChainRegistry;
+///
+/// let registry = ChainRegistry::bundled();
+/// let zora = registry.get(7777777).unwrap();
+/// assert_eq!(zora.name, "Zora");
+/// assert_eq!(zora.native_currency.symbol, "ETH");
+/// assert_eq!(zora.explorer_url(), Some("https://explorer.zora.energy"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainRegistry {
+    chains: BTreeMap<u64, ChainInfo>,
+}
+
+impl ChainRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with all [`Chain`] variants and the bundled chain list snapshot.
+    pub fn bundled() -> Self {
+        let mut registry: Self = Chain::iter().map(ChainInfo::from).collect();
+        let snapshot = Self::from_json(BUNDLED_CHAINS_JSON).expect("bundled chain list is valid");
+        registry.extend(snapshot.chains.into_values());
+        registry
+    }
+
+    /// Parses a registry from a chainid.network formatted JSON array.
+    ///
+    /// Malformed entries are skipped, so that a single bad entry in the upstream list does not
+    /// make the whole list unusable.
+    pub fn from_json(s: &str) -> Result<Self, ChainRegistryError> {
+        let entries: Vec<serde_json::Value> = serde_json::from_str(s)?;
+        Ok(entries.into_iter().filter_map(|entry| serde_json::from_value(entry).ok()).collect())
+    }
+
+    /// Fetches the chain list from [`CHAINS_JSON_URL`].
+    #[cfg(feature = "online")]
+    pub async fn fetch() -> Result<Self, ChainRegistryError> {
+        let json = reqwest::get(CHAINS_JSON_URL).await?.error_for_status()?.text().await?;
+        Self::from_json(&json)
+    }
+
+    /// Fetches the chain list from [`CHAINS_JSON_URL`] and merges it into this registry,
+    /// replacing existing entries with the same chain ID.
+    #[cfg(feature = "online")]
+    pub async fn refresh(&mut self) -> Result<(), ChainRegistryError> {
+        let fetched = Self::fetch().await?;
+        self.extend(fetched.chains.into_values());
+        Ok(())
+    }
+
+    /// Inserts an entry, returning the previous entry with the same chain ID, if any.
+    pub fn insert(&mut self, info: ChainInfo) -> Option<ChainInfo> {
+        self.chains.insert(info.chain_id, info)
+    }
+
+    /// Returns the entry of the given chain ID.
+    pub fn get(&self, chain_id: u64) -> Option<&ChainInfo> {
+        self.chains.get(&chain_id)
+    }
+
+    /// Returns the entry with the given short name, e.g. `zora`.
+    pub fn get_by_short_name(&self, short_name: &str) -> Option<&ChainInfo> {
+        self.chains.values().find(|info| info.short_name.eq_ignore_ascii_case(short_name))
+    }
+
+    /// Returns the name of the given chain ID.
+    pub fn name(&self, chain_id: u64) -> Option<&str> {
+        self.get(chain_id).map(|info| info.name.as_str())
+    }
+
+    /// Returns an iterator over all entries, ordered by chain ID.
+    pub fn iter(&self) -> impl Iterator<Item = &ChainInfo> {
+        self.chains.values()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    /// Returns `true` if the registry has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+}
+
+impl Extend<ChainInfo> for ChainRegistry {
+    fn extend<I: IntoIterator<Item = ChainInfo>>(&mut self, iter: I) {
+        for info in iter {
+            self.insert(info);
+        }
+    }
+}
+
+impl FromIterator<ChainInfo> for ChainRegistry {
+    fn from_iter<I: IntoIterator<Item = ChainInfo>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        registry.extend(iter);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chain_list() {
+        let registry = ChainRegistry::from_json(
+            r#"[
+                {
+                    "name": "Ethereum Mainnet",
+                    "chain": "ETH",
+                    "icon": "ethereum",
+                    "rpc": ["https://mainnet.infura.io/v3/${INFURA_API_KEY}", "https://cloudflare-eth.com"],
+                    "features": [{ "name": "EIP155" }, { "name": "EIP1559" }],
+                    "nativeCurrency": { "name": "Ether", "symbol": "ETH", "decimals": 18 },
+                    "infoURL": "https://ethereum.org",
+                    "shortName": "eth",
+                    "chainId": 1,
+                    "networkId": 1,
+                    "slip44": 60,
+                    "explorers": [{ "name": "etherscan", "url": "https://etherscan.io/", "standard": "EIP3091" }]
+                },
+                { "name": "Broken", "chainId": "not a number" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(registry.len(), 1);
+        let mainnet = registry.get(1).unwrap();
+        assert_eq!(mainnet.chain(), Some(Chain::Mainnet));
+        assert_eq!(mainnet.info_url.as_deref(), Some("https://ethereum.org"));
+        assert_eq!(mainnet.explorer_url(), Some("https://etherscan.io"));
+        assert_eq!(mainnet.public_rpc_urls().collect::<Vec<_>>(), ["https://cloudflare-eth.com"]);
+        assert_eq!(registry.get_by_short_name("ETH"), Some(mainnet));
+
+        assert!(ChainRegistry::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn bundled() {
+        let registry = ChainRegistry::bundled();
+
+        for chain in Chain::iter() {
+            assert_eq!(registry.get(chain as u64).and_then(ChainInfo::chain), Some(chain));
+        }
+        let mantle = registry.get(Chain::Mantle as u64).unwrap();
+        assert_eq!(mantle.native_currency.symbol, "MNT");
+
+        let amoy = registry.get(80002).unwrap();
+        assert_eq!(amoy.chain(), None);
+        assert_eq!(amoy.native_currency.symbol, "POL");
+        assert_eq!(registry.name(7777777), Some("Zora"));
+    }
+}
@@ -1,11 +1,23 @@
 use crate::types::{serde_helpers::deserialize_stringified_numeric, U256};
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// The response of `eth_feeHistory`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FeeHistory {
+    /// The base fee per gas of each block of the range, followed by the base fee of the block
+    /// after the range.
     pub base_fee_per_gas: Vec<U256>,
+    /// The ratio of gas used to the gas limit of each block of the range
     pub gas_used_ratio: Vec<f64>,
+    /// The base fee per blob gas of each block of the range, followed by the base fee of the
+    /// block after the range. Empty before Cancun.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub base_fee_per_blob_gas: Vec<U256>,
+    /// The ratio of blob gas used to the maximum blob gas of each block of the range. Empty
+    /// before Cancun.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blob_gas_used_ratio: Vec<f64>,
     #[serde(deserialize_with = "deserialize_stringified_numeric")]
     /// oldestBlock is returned as an unsigned integer up to geth v1.10.6. From
     /// geth v1.10.7, this has been updated to return in the hex encoded form.
@@ -17,3 +29,47 @@ pub struct FeeHistory {
     #[serde(default)]
     pub reward: Vec<Vec<U256>>,
 }
+
+impl FeeHistory {
+    /// Returns the base fee per gas of the block after the range, i.e. of the pending block if the
+    /// range ends with the latest block.
+    pub fn next_base_fee_per_gas(&self) -> Option<U256> {
+        self.base_fee_per_gas.last().copied()
+    }
+
+    /// Returns the base fee per blob gas of the block after the range, if past Cancun.
+    pub fn next_base_fee_per_blob_gas(&self) -> Option<U256> {
+        self.base_fee_per_blob_gas.last().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_fee_history() {
+        let history: FeeHistory = serde_json::from_value(serde_json::json!({
+            "oldestBlock": "0x1286a1a",
+            "baseFeePerGas": ["0x7", "0x8", "0x9"],
+            "gasUsedRatio": [0.5, 0.75],
+            "baseFeePerBlobGas": ["0x1", "0x1", "0x2"],
+            "blobGasUsedRatio": [0.0, 1.0],
+            "reward": [["0x1"], ["0x2"]]
+        }))
+        .unwrap();
+        assert_eq!(history.next_base_fee_per_gas(), Some(9.into()));
+        assert_eq!(history.next_base_fee_per_blob_gas(), Some(2.into()));
+
+        // pre-Cancun responses, which may also miss the rewards
+        let history: FeeHistory = serde_json::from_value(serde_json::json!({
+            "oldestBlock": 10762137,
+            "baseFeePerGas": ["0x7"],
+            "gasUsedRatio": []
+        }))
+        .unwrap();
+        assert_eq!(history.oldest_block, 10762137.into());
+        assert_eq!(history.next_base_fee_per_blob_gas(), None);
+        assert!(history.reward.is_empty());
+    }
+}
@@ -12,6 +12,70 @@ use std::ops::{Range, RangeFrom, RangeTo};
 
 pub type BloomFilter = Vec<Option<Bloom>>;
 
+/// Log bloom helpers for [`Bloom`].
+///
+/// Blooms can have false positives but no false negatives, so these can be used to cheaply skip
+/// blocks which can't contain a log before calling `eth_getLogs`.
+///
+/// # Examples
+///
+/// ```
+/// use ethers_core::types::{Address, Bloom, BloomExt, Log, H256};
+///
+/// let log = Log {
+///     address: Address::repeat_byte(1),
+///     topics: vec![H256::repeat_byte(2)],
+///     ..Default::default()
+/// };
+/// let bloom = Bloom::from_logs([&log]);
+/// assert!(bloom.contains_address(&log.address));
+/// assert!(bloom.contains_topic(&log.topics[0]));
+/// assert!(!bloom.contains_address(&Address::repeat_byte(3)));
+/// ```
+pub trait BloomExt {
+    /// Creates a bloom which contains the addresses and topics of all the given logs.
+    fn from_logs<'a, I>(logs: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Log>;
+
+    /// Adds the address and topics of the log to the bloom.
+    fn accrue_log(&mut self, log: &Log);
+
+    /// Returns `true` if the bloom possibly contains the address.
+    fn contains_address(&self, address: &Address) -> bool;
+
+    /// Returns `true` if the bloom possibly contains the topic.
+    fn contains_topic(&self, topic: &H256) -> bool;
+}
+
+impl BloomExt for Bloom {
+    fn from_logs<'a, I>(logs: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Log>,
+    {
+        let mut bloom = Bloom::zero();
+        for log in logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+
+    fn accrue_log(&mut self, log: &Log) {
+        self.accrue(BloomInput::Raw(log.address.as_bytes()));
+        for topic in &log.topics {
+            self.accrue(BloomInput::Raw(topic.as_bytes()));
+        }
+    }
+
+    fn contains_address(&self, address: &Address) -> bool {
+        self.contains_input(BloomInput::Raw(address.as_bytes()))
+    }
+
+    fn contains_topic(&self, topic: &H256) -> bool {
+        self.contains_input(BloomInput::Raw(topic.as_bytes()))
+    }
+}
+
 /// A single topic
 pub type Topic = ValueOrArray<Option<H256>>;
 
@@ -372,6 +436,34 @@ impl Filter {
     pub fn has_topics(&self) -> bool {
         self.topics.iter().any(|t| t.is_some())
     }
+
+    /// Returns `true` if a block with the given logs bloom possibly contains logs matching the
+    /// filter's address and topics, and `false` if it certainly does not.
+    ///
+    /// The block range of the filter is not checked.
+    pub fn matches_bloom(&self, bloom: &Bloom) -> bool {
+        let matches_address = match &self.address {
+            None => true,
+            Some(ValueOrArray::Value(address)) => bloom.contains_address(address),
+            Some(ValueOrArray::Array(addresses)) => {
+                addresses.is_empty() ||
+                    addresses.iter().any(|address| bloom.contains_address(address))
+            }
+        };
+
+        matches_address &&
+            self.topics().all(|topic| match topic {
+                ValueOrArray::Value(topic) => {
+                    topic.as_ref().map_or(true, |topic| bloom.contains_topic(topic))
+                }
+                ValueOrArray::Array(topics) => {
+                    topics.is_empty() ||
+                        topics.iter().any(|topic| {
+                            topic.as_ref().map_or(true, |topic| bloom.contains_topic(topic))
+                        })
+                }
+            })
+    }
 }
 
 impl Serialize for Filter {
@@ -1211,4 +1303,32 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn can_match_bloom() {
+        let (address, topic0, topic1) =
+            (Address::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(3));
+        let logs = [
+            Log { address, topics: vec![topic0, topic1], ..Default::default() },
+            Log { address: Address::repeat_byte(4), ..Default::default() },
+        ];
+        let bloom = Bloom::from_logs(&logs);
+        assert_eq!(Bloom::from_logs(&logs[..1]), build_bloom(address, topic0, topic1));
+        assert!(bloom.contains_address(&logs[1].address));
+        assert!(bloom.contains_topic(&topic1));
+
+        let unknown_address = Address::repeat_byte(5);
+        let unknown_topic = H256::repeat_byte(6);
+        assert!(Filter::new().matches_bloom(&bloom));
+        assert!(Filter::new().address(address).topic0(topic0).topic1(topic1).matches_bloom(&bloom));
+        assert!(Filter::new().address(vec![unknown_address, address]).matches_bloom(&bloom));
+        assert!(Filter::new().address(Vec::<Address>::new()).matches_bloom(&bloom));
+        assert!(Filter::new().topic0(vec![unknown_topic, topic0]).matches_bloom(&bloom));
+        assert!(Filter::new()
+            .topic0(ValueOrArray::Array(vec![Some(unknown_topic), None]))
+            .matches_bloom(&bloom));
+        assert!(!Filter::new().address(unknown_address).matches_bloom(&bloom));
+        assert!(!Filter::new().address(address).topic1(unknown_topic).matches_bloom(&bloom));
+        assert!(!Filter::new().topic2(vec![unknown_topic]).matches_bloom(&bloom));
+    }
 }
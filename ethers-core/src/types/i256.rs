@@ -377,6 +377,34 @@ impl I256 {
     pub fn to_little_endian(&self, bytes: &mut [u8]) {
         self.0.to_little_endian(bytes)
     }
+
+    /// Creates an integer from its two's complement representation as a big-endian byte array.
+    #[inline(always)]
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        Self(U256::from_big_endian(&bytes))
+    }
+
+    /// Creates an integer from its two's complement representation as a little-endian byte array.
+    #[inline(always)]
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        Self(U256::from_little_endian(&bytes))
+    }
+
+    /// Returns the two's complement representation of `self` as a big-endian byte array.
+    #[inline(always)]
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        self.0.to_big_endian(&mut bytes);
+        bytes
+    }
+
+    /// Returns the two's complement representation of `self` as a little-endian byte array.
+    #[inline(always)]
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        self.0.to_little_endian(&mut bytes);
+        bytes
+    }
 }
 
 // ops impl
@@ -445,6 +473,20 @@ impl I256 {
         self.into_sign_and_abs().1
     }
 
+    /// Computes the absolute difference between `self` and `other`.
+    ///
+    /// This function always returns the correct answer without overflow or panics by returning an
+    /// unsigned integer.
+    #[inline(always)]
+    #[must_use]
+    pub fn abs_diff(self, other: Self) -> U256 {
+        if self < other {
+            other.0.overflowing_sub(self.0).0
+        } else {
+            self.0.overflowing_sub(other.0).0
+        }
+    }
+
     /// Negates self, overflowing if this is equal to the minimum value.
     ///
     /// Returns a tuple of the negated version of self along with a boolean indicating whether an
@@ -754,6 +796,19 @@ impl I256 {
         }
     }
 
+    /// Saturating integer remainder. Computes `self % rhs`, returning `0` for `MIN % -1` instead of
+    /// overflowing.
+    ///
+    /// # Panics
+    ///
+    /// If `rhs` is 0.
+    #[inline(always)]
+    #[track_caller]
+    #[must_use]
+    pub fn saturating_rem(self, rhs: Self) -> Self {
+        self.overflowing_rem(rhs).0
+    }
+
     /// Wrapping (modular) remainder. Computes `self % rhs`, wrapping around at the boundary of the
     /// type.
     ///
@@ -1180,7 +1235,7 @@ macro_rules! impl_conversions {
                 #[inline(always)]
                 fn try_from(value: I256) -> Result<$u, Self::Error> {
                     if value.is_negative() || value > I256::from(<$u>::MAX) {
-                        return Err(TryFromBigIntError);
+                        return Err(TryFromBigIntError)
                     }
 
                     Ok(value.$actual_low_u() as $u)
@@ -1193,7 +1248,7 @@ macro_rules! impl_conversions {
                 #[inline(always)]
                 fn try_from(value: I256) -> Result<$i, Self::Error> {
                     if value < I256::from(<$i>::MIN) || value > I256::from(<$i>::MAX) {
-                        return Err(TryFromBigIntError);
+                        return Err(TryFromBigIntError)
                     }
 
                     Ok(value.$actual_low_i() as $i)
@@ -2156,4 +2211,172 @@ mod tests {
         assert_twos_complement!(i128, u128);
         assert_twos_complement!(isize, usize);
     }
+
+    #[test]
+    fn byte_conversions() {
+        let mut bytes = [0xff; 32];
+        assert_eq!(I256::from_be_bytes(bytes), I256::minus_one());
+        bytes[0] = 0x80;
+        assert_eq!(I256::from_le_bytes(bytes), I256::minus_one() - I256::from(0x7f));
+        assert_eq!(I256::MIN.to_be_bytes()[0], 0x80);
+        assert_eq!(I256::MIN.to_le_bytes()[31], 0x80);
+    }
+
+    #[test]
+    fn abs_diff() {
+        assert_eq!(I256::MIN.abs_diff(I256::MAX), U256::MAX);
+        assert_eq!(I256::MAX.abs_diff(I256::MIN), U256::MAX);
+        assert_eq!(I256::from(-3).abs_diff(I256::from(4)), U256::from(7));
+        assert_eq!(I256::from(-3).abs_diff(I256::from(-3)), U256::zero());
+    }
+
+    mod proptests {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Maps `i128` to the most significant half of an `I256`, so that overflows happen at the
+        /// same operands for both types.
+        fn scaled(value: i128) -> I256 {
+            I256::from(value) << 128usize
+        }
+
+        fn saturated(positive: bool) -> I256 {
+            if positive {
+                I256::MAX
+            } else {
+                I256::MIN
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn add_sub(a: i128, b: i128) {
+                let (x, y) = (I256::from(a), I256::from(b));
+                if let Some(c) = a.checked_add(b) {
+                    prop_assert_eq!(x.checked_add(y), Some(I256::from(c)));
+                }
+                if let Some(c) = a.checked_sub(b) {
+                    prop_assert_eq!(x.checked_sub(y), Some(I256::from(c)));
+                }
+
+                let (x, y) = (scaled(a), scaled(b));
+                let (c, overflow) = a.overflowing_add(b);
+                prop_assert_eq!(x.overflowing_add(y), (scaled(c), overflow));
+                prop_assert_eq!(x.checked_add(y), a.checked_add(b).map(scaled));
+                prop_assert_eq!(x.wrapping_add(y), scaled(a.wrapping_add(b)));
+                prop_assert_eq!(
+                    x.saturating_add(y),
+                    a.checked_add(b).map_or_else(|| saturated(a > 0), scaled)
+                );
+
+                let (c, overflow) = a.overflowing_sub(b);
+                prop_assert_eq!(x.overflowing_sub(y), (scaled(c), overflow));
+                prop_assert_eq!(x.checked_sub(y), a.checked_sub(b).map(scaled));
+                prop_assert_eq!(x.wrapping_sub(y), scaled(a.wrapping_sub(b)));
+                prop_assert_eq!(
+                    x.saturating_sub(y),
+                    a.checked_sub(b).map_or_else(|| saturated(a >= 0), scaled)
+                );
+            }
+
+            #[test]
+            fn mul(a: i128, b: i128) {
+                let (x, y) = (I256::from(a), I256::from(b));
+                if let Some(c) = a.checked_mul(b) {
+                    prop_assert_eq!(x.checked_mul(y), Some(I256::from(c)));
+                }
+
+                let x = scaled(a);
+                let (c, overflow) = a.overflowing_mul(b);
+                prop_assert_eq!(x.overflowing_mul(y), (scaled(c), overflow));
+                prop_assert_eq!(x.checked_mul(y), a.checked_mul(b).map(scaled));
+                prop_assert_eq!(x.wrapping_mul(y), scaled(a.wrapping_mul(b)));
+                prop_assert_eq!(
+                    x.saturating_mul(y),
+                    a.checked_mul(b).map_or_else(|| saturated((a < 0) == (b < 0)), scaled)
+                );
+            }
+
+            #[test]
+            fn div_rem(a: i128, b: i128) {
+                let (x, y) = (I256::from(a), I256::from(b));
+                prop_assert_eq!(x.checked_div(y), a.checked_div(b).map(I256::from).or_else(|| {
+                    // `i128::MIN / -1` overflows `i128`, but not `I256`.
+                    (b == -1).then(|| -x)
+                }));
+                prop_assert_eq!(x.checked_rem(y), a.checked_rem(b).map(I256::from).or_else(|| {
+                    (b == -1).then(I256::zero)
+                }));
+                if let Some(c) = a.checked_div_euclid(b) {
+                    prop_assert_eq!(x.checked_div_euclid(y), Some(I256::from(c)));
+                }
+                if let Some(c) = a.checked_rem_euclid(b) {
+                    prop_assert_eq!(x.checked_rem_euclid(y), Some(I256::from(c)));
+                }
+                if b != 0 {
+                    let (x, y) = (scaled(a), scaled(b));
+                    let quotient = a.checked_div(b).map(I256::from);
+                    prop_assert_eq!(x.wrapping_div(y), quotient.unwrap_or(I256::MIN));
+                    prop_assert_eq!(x.saturating_div(y), quotient.unwrap_or(I256::MAX));
+                    prop_assert_eq!(x.wrapping_rem(y), scaled(a.wrapping_rem(b)));
+                    prop_assert_eq!(x.saturating_rem(y), scaled(a.wrapping_rem(b)));
+                }
+            }
+
+            #[test]
+            fn pow(a: i64, exp in 0u32..8) {
+                let x = I256::from(a);
+                prop_assert_eq!(x.checked_pow(exp).and_then(|c| i128::try_from(c).ok()),
+                    i128::from(a).checked_pow(exp));
+
+                let (c, overflow) = i128::from(a).overflowing_pow(exp);
+                if !overflow {
+                    prop_assert_eq!(x.pow(exp), I256::from(c));
+                    prop_assert_eq!(x.wrapping_pow(exp), I256::from(c));
+                    prop_assert_eq!(x.saturating_pow(exp), I256::from(c));
+                }
+            }
+
+            #[test]
+            fn abs_neg(a: i128) {
+                let x = I256::from(a);
+                prop_assert_eq!(x.unsigned_abs(), U256::from(a.unsigned_abs()));
+                prop_assert_eq!(x.checked_abs(), Some(I256::from(a.unsigned_abs())));
+                prop_assert_eq!(x.checked_neg().map(|neg| neg + x), Some(I256::zero()));
+
+                let x = scaled(a);
+                prop_assert_eq!(x.overflowing_abs(), (scaled(a.wrapping_abs()), a == i128::MIN));
+                prop_assert_eq!(x.checked_abs(), a.checked_abs().map(scaled));
+                prop_assert_eq!(x.wrapping_abs(), scaled(a.wrapping_abs()));
+                prop_assert_eq!(x.overflowing_neg(), (scaled(a.wrapping_neg()), a == i128::MIN));
+                prop_assert_eq!(x.checked_neg(), a.checked_neg().map(scaled));
+                prop_assert_eq!(x.wrapping_neg(), scaled(a.wrapping_neg()));
+            }
+
+            #[test]
+            fn conversions(a: i128, b: u128) {
+                let x = I256::from(a);
+                prop_assert_eq!(i128::try_from(x).ok(), Some(a));
+                prop_assert_eq!(x.low_i128(), a);
+                prop_assert_eq!(u128::try_from(x).ok(), u128::try_from(a).ok());
+                prop_assert_eq!(I256::from_dec_str(&a.to_string()).ok(), Some(x));
+                prop_assert_eq!(x.to_string(), a.to_string());
+
+                let bytes = x.to_be_bytes();
+                let extension = if a < 0 { 0xff } else { 0 };
+                prop_assert!(bytes[..16].iter().all(|byte| *byte == extension));
+                let low = a.to_be_bytes();
+                prop_assert_eq!(&bytes[16..], &low[..]);
+                prop_assert_eq!(I256::from_be_bytes(bytes), x);
+                prop_assert_eq!(I256::from_le_bytes(x.to_le_bytes()), x);
+                prop_assert_eq!(I256::from_raw(x.into_raw()), x);
+                prop_assert_eq!(U256::try_from(x).ok(), u128::try_from(a).ok().map(U256::from));
+
+                let y = I256::from(b);
+                prop_assert_eq!(u128::try_from(y).ok(), Some(b));
+                prop_assert_eq!(i128::try_from(y).ok(), i128::try_from(b).ok());
+                prop_assert_eq!(I256::try_from(U256::from(b)).ok(), Some(y));
+            }
+        }
+    }
 }
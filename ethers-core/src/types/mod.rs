@@ -15,6 +15,7 @@ pub mod transaction;
 pub use transaction::{
     eip1559::Eip1559TransactionRequest,
     eip2930::Eip2930TransactionRequest,
+    eip4844::Eip4844TransactionRequest,
     request::TransactionRequest,
     response::{Transaction, TransactionReceipt},
 };
@@ -64,6 +65,9 @@ pub use trace::*;
 mod chain;
 pub use chain::*;
 
+mod chain_registry;
+pub use chain_registry::*;
+
 mod proof;
 
 pub use proof::*;
@@ -71,6 +75,9 @@ pub use proof::*;
 mod fee;
 pub use fee::*;
 
+mod private_transaction;
+pub use private_transaction::*;
+
 mod other;
 pub use other::OtherFields;
 
@@ -0,0 +1,127 @@
+use crate::types::{Bytes, TxHash, U64};
+use serde::{Deserialize, Serialize};
+
+/// Options of a private transaction sent with `eth_sendPrivateTransaction`, as supported by
+/// Flashbots Protect and most block builders.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivateTransactionOptions {
+    /// The highest block number in which the transaction should be included. The endpoint stops
+    /// trying to include the transaction after this block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block_number: Option<U64>,
+    /// How the transaction should be shared with builders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferences: Option<PrivateTransactionPreferences>,
+}
+
+impl PrivateTransactionOptions {
+    /// Creates options with the defaults of the endpoint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the highest block number in which the transaction should be included.
+    #[must_use]
+    pub fn max_block_number<T: Into<U64>>(mut self, block: T) -> Self {
+        self.max_block_number = Some(block.into());
+        self
+    }
+
+    /// Enables fast mode, which shares the transaction with all registered builders to speed up
+    /// its inclusion.
+    #[must_use]
+    pub fn fast(mut self) -> Self {
+        self.preferences.get_or_insert_with(Default::default).fast = true;
+        self
+    }
+
+    /// Only shares the transaction with the given builders.
+    #[must_use]
+    pub fn builders<T: Into<String>>(mut self, builders: impl IntoIterator<Item = T>) -> Self {
+        let preferences = self.preferences.get_or_insert_with(Default::default);
+        preferences.privacy.get_or_insert_with(Default::default).builders =
+            builders.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Only shares the given hints about the transaction, e.g. `calldata` or `logs`, with
+    /// searchers.
+    #[must_use]
+    pub fn hints<T: Into<String>>(mut self, hints: impl IntoIterator<Item = T>) -> Self {
+        let preferences = self.preferences.get_or_insert_with(Default::default);
+        preferences.privacy.get_or_insert_with(Default::default).hints =
+            hints.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Sharing preferences of a private transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivateTransactionPreferences {
+    /// Whether the transaction is shared with all registered builders
+    #[serde(default)]
+    pub fast: bool,
+    /// What is shared about the transaction and with whom
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacyPreferences>,
+}
+
+/// Privacy preferences of a private transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyPreferences {
+    /// The hints about the transaction that are shared with searchers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<String>,
+    /// The builders the transaction is shared with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builders: Vec<String>,
+}
+
+/// The parameters of `eth_sendPrivateTransaction`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivateTransactionRequest {
+    /// The signed transaction
+    pub tx: Bytes,
+    /// The options of the private transaction
+    #[serde(flatten)]
+    pub options: PrivateTransactionOptions,
+}
+
+/// The parameters of `eth_cancelPrivateTransaction`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelPrivateTransactionRequest {
+    /// The hash of the private transaction to cancel
+    pub tx_hash: TxHash,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_private_transaction_request() {
+        let request = PrivateTransactionRequest {
+            tx: Bytes::from(vec![0x02, 0xf8]),
+            options: PrivateTransactionOptions::new()
+                .max_block_number(100u64)
+                .fast()
+                .builders(["flashbots"]),
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "tx": "0x02f8",
+                "maxBlockNumber": "0x64",
+                "preferences": { "fast": true, "privacy": { "builders": ["flashbots"] } }
+            })
+        );
+
+        let request = PrivateTransactionRequest {
+            tx: Bytes::from(vec![0x02]),
+            options: PrivateTransactionOptions::new(),
+        };
+        assert_eq!(serde_json::to_value(&request).unwrap(), serde_json::json!({ "tx": "0x02" }));
+    }
+}
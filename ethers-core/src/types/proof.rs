@@ -1,5 +1,11 @@
-use crate::types::{Address, Bytes, H256, U256, U64};
+use crate::{
+    types::{Address, Bytes, H256, U256, U64},
+    utils::keccak256,
+};
+use rlp::{Rlp, RlpStream};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct StorageProof {
@@ -8,6 +14,29 @@ pub struct StorageProof {
     pub value: U256,
 }
 
+impl StorageProof {
+    /// Verifies the storage proof against the storage root of an account.
+    ///
+    /// On success, [`Self::value`] is proven to be stored at [`Self::key`], where slots that are
+    /// not in the storage trie hold zero.
+    pub fn verify(&self, storage_hash: H256) -> Result<(), ProofError> {
+        // accounts which do not exist are reported with a zero storage hash
+        let value = if storage_hash.is_zero() {
+            None
+        } else {
+            verify_trie_proof(storage_hash, &keccak256(self.key), &self.proof)?
+        };
+        let proven = match value {
+            Some(value) => Rlp::new(&value).as_val()?,
+            None => U256::zero(),
+        };
+        if proven != self.value {
+            return Err(ProofError::ValueMismatch)
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EIP1186ProofResponse {
@@ -20,6 +49,205 @@ pub struct EIP1186ProofResponse {
     pub storage_proof: Vec<StorageProof>,
 }
 
+impl EIP1186ProofResponse {
+    /// Verifies the account proof and all storage proofs against the state root of a block, and
+    /// returns the proven state of the account.
+    pub fn verify(&self, state_root: H256) -> Result<VerifiedAccount, ProofError> {
+        self.verify_account_proof(state_root)?;
+        let storage = self
+            .storage_proof
+            .iter()
+            .map(|proof| {
+                proof.verify(self.storage_hash)?;
+                Ok((proof.key, proof.value))
+            })
+            .collect::<Result<_, ProofError>>()?;
+        Ok(VerifiedAccount {
+            address: self.address,
+            nonce: self.nonce,
+            balance: self.balance,
+            code_hash: self.code_hash,
+            storage_hash: self.storage_hash,
+            storage,
+        })
+    }
+
+    /// Verifies the account proof against the state root of a block.
+    ///
+    /// On success, the nonce, balance, storage hash and code hash of the response are proven to be
+    /// the state of [`Self::address`] at that block. Accounts that do not exist must be reported
+    /// as empty accounts.
+    pub fn verify_account_proof(&self, state_root: H256) -> Result<(), ProofError> {
+        let key = keccak256(self.address);
+        match verify_trie_proof(state_root, &key, &self.account_proof)? {
+            Some(account) => {
+                let mut expected = RlpStream::new_list(4);
+                expected
+                    .append(&self.nonce)
+                    .append(&self.balance)
+                    .append(&self.storage_hash)
+                    .append(&self.code_hash);
+                if account != expected.out().as_ref() {
+                    return Err(ProofError::ValueMismatch)
+                }
+            }
+            None => {
+                let empty_code = [H256::zero(), H256(keccak256(b""))];
+                let empty_storage = [H256::zero(), H256(keccak256(rlp::NULL_RLP))];
+                if !self.nonce.is_zero() ||
+                    !self.balance.is_zero() ||
+                    !empty_code.contains(&self.code_hash) ||
+                    !empty_storage.contains(&self.storage_hash)
+                {
+                    return Err(ProofError::ValueMismatch)
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The state of an account, proven by [`EIP1186ProofResponse::verify`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VerifiedAccount {
+    /// The address of the account
+    pub address: Address,
+    /// The nonce of the account
+    pub nonce: U64,
+    /// The balance of the account
+    pub balance: U256,
+    /// The hash of the code of the account
+    pub code_hash: H256,
+    /// The root of the storage trie of the account
+    pub storage_hash: H256,
+    /// The values of the proven storage slots
+    pub storage: BTreeMap<H256, U256>,
+}
+
+/// Thrown when a Merkle-Patricia trie proof is invalid
+#[derive(Debug, Error)]
+pub enum ProofError {
+    /// Thrown when a node of the proof can not be decoded
+    #[error(transparent)]
+    Rlp(#[from] rlp::DecoderError),
+    /// Thrown when the proof ends before reaching a value
+    #[error("proof is missing a node")]
+    MissingNode,
+    /// Thrown when the hash of a node does not match the reference in its parent
+    #[error("node hash mismatch: expected {expected:?}, got {actual:?}")]
+    NodeHashMismatch {
+        /// The hash referenced by the parent node, or the root
+        expected: H256,
+        /// The hash of the node in the proof
+        actual: H256,
+    },
+    /// Thrown when a node is neither a branch, extension nor leaf node
+    #[error("invalid trie node")]
+    InvalidNode,
+    /// Thrown when the proven value does not match the value in the response
+    #[error("proven value does not match the response")]
+    ValueMismatch,
+}
+
+/// Verifies a Merkle-Patricia trie proof for `key` against the trie `root`.
+///
+/// `key` is the full path in the trie, e.g. the hashed address for account proofs. Returns the
+/// value stored at `key`, or `None` if the proof shows that `key` is not in the trie.
+pub fn verify_trie_proof(
+    root: H256,
+    key: &[u8],
+    proof: &[Bytes],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    if root == H256(keccak256(rlp::NULL_RLP)) {
+        return Ok(None)
+    }
+
+    let nibbles: Vec<u8> = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect();
+    let mut path = nibbles.as_slice();
+    let mut nodes = proof.iter();
+    let mut next = NodeRef::Hash(root);
+
+    loop {
+        let node = match next {
+            NodeRef::Hash(expected) => {
+                let node = nodes.next().ok_or(ProofError::MissingNode)?;
+                let actual = H256(keccak256(node));
+                if actual != expected {
+                    return Err(ProofError::NodeHashMismatch { expected, actual })
+                }
+                node.to_vec()
+            }
+            NodeRef::Inline(node) => node,
+        };
+
+        let rlp = Rlp::new(&node);
+        match rlp.item_count()? {
+            17 => {
+                let Some((&nibble, rest)) = path.split_first() else {
+                    let value = rlp.at(16)?.data()?;
+                    return Ok((!value.is_empty()).then(|| value.to_vec()))
+                };
+                path = rest;
+                match NodeRef::decode(&rlp.at(nibble as usize)?)? {
+                    Some(child) => next = child,
+                    None => return Ok(None),
+                }
+            }
+            2 => {
+                let (is_leaf, node_path) = decode_hex_prefix(rlp.at(0)?.data()?)?;
+                if is_leaf {
+                    let value = rlp.at(1)?.data()?;
+                    return Ok((path == node_path.as_slice()).then(|| value.to_vec()))
+                }
+                if !path.starts_with(&node_path) {
+                    return Ok(None)
+                }
+                path = &path[node_path.len()..];
+                match NodeRef::decode(&rlp.at(1)?)? {
+                    Some(child) => next = child,
+                    None => return Err(ProofError::InvalidNode),
+                }
+            }
+            _ => return Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+/// A reference to a child node, either by hash or embedded if its encoding is shorter than 32
+/// bytes
+enum NodeRef {
+    Hash(H256),
+    Inline(Vec<u8>),
+}
+
+impl NodeRef {
+    fn decode(rlp: &Rlp) -> Result<Option<Self>, ProofError> {
+        if rlp.is_list() {
+            return Ok(Some(Self::Inline(rlp.as_raw().to_vec())))
+        }
+        match rlp.data()? {
+            [] => Ok(None),
+            hash if hash.len() == 32 => Ok(Some(Self::Hash(H256::from_slice(hash)))),
+            _ => Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+/// Decodes the hex-prefix encoded path of a leaf or extension node into its nibbles
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(bool, Vec<u8>), ProofError> {
+    let (&first, rest) = encoded.split_first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode)
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Ok((flag & 2 == 2, nibbles))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,4 +257,109 @@ mod tests {
         serde_json::from_str::<EIP1186ProofResponse>(include_str!("../../testdata/proof.json"))
             .unwrap();
     }
+
+    /// Builds a trie with a single account, so the root node is a leaf node
+    fn single_account_proof(response: &EIP1186ProofResponse) -> (H256, Bytes) {
+        let mut path = vec![0x20];
+        path.extend_from_slice(&keccak256(response.address));
+
+        let mut account = RlpStream::new_list(4);
+        account
+            .append(&response.nonce)
+            .append(&response.balance)
+            .append(&response.storage_hash)
+            .append(&response.code_hash);
+
+        let mut leaf = RlpStream::new_list(2);
+        leaf.append(&path).append(&account.out().to_vec());
+        let leaf = Bytes::from(leaf.out().to_vec());
+        (H256(keccak256(&leaf)), leaf)
+    }
+
+    #[test]
+    fn verifies_account_proof() {
+        let mut response = EIP1186ProofResponse {
+            address: Address::repeat_byte(0x11),
+            balance: U256::exp10(18),
+            code_hash: H256(keccak256(b"")),
+            nonce: 7u64.into(),
+            storage_hash: H256(keccak256(rlp::NULL_RLP)),
+            ..Default::default()
+        };
+        let (root, leaf) = single_account_proof(&response);
+        response.account_proof = vec![leaf.clone()];
+        response.verify_account_proof(root).unwrap();
+
+        // a different balance is not proven by the same nodes
+        let mut forged = response.clone();
+        forged.balance += U256::one();
+        assert!(matches!(forged.verify_account_proof(root), Err(ProofError::ValueMismatch)));
+
+        // neither is a different root
+        assert!(matches!(
+            response.verify_account_proof(H256::repeat_byte(0x01)),
+            Err(ProofError::NodeHashMismatch { .. })
+        ));
+
+        // another address is proven to be absent
+        let mut absent = EIP1186ProofResponse {
+            address: Address::repeat_byte(0x22),
+            account_proof: vec![leaf],
+            ..Default::default()
+        };
+        absent.verify_account_proof(root).unwrap();
+        absent.balance = U256::one();
+        assert!(matches!(absent.verify_account_proof(root), Err(ProofError::ValueMismatch)));
+    }
+
+    /// Builds a storage trie with a single slot, so the root node is a leaf node
+    fn single_slot_proof(key: H256, value: U256) -> (H256, Bytes) {
+        let mut path = vec![0x20];
+        path.extend_from_slice(&keccak256(key));
+
+        let mut leaf = RlpStream::new_list(2);
+        leaf.append(&path).append(&rlp::encode(&value).to_vec());
+        let leaf = Bytes::from(leaf.out().to_vec());
+        (H256(keccak256(&leaf)), leaf)
+    }
+
+    #[test]
+    fn verifies_storage_proofs() {
+        let key = H256::from_low_u64_be(3);
+        let (storage_hash, leaf) = single_slot_proof(key, 42u64.into());
+        let mut response = EIP1186ProofResponse {
+            address: Address::repeat_byte(0x11),
+            code_hash: H256(keccak256(b"")),
+            storage_hash,
+            storage_proof: vec![
+                StorageProof { key, proof: vec![leaf.clone()], value: 42u64.into() },
+                StorageProof { key: H256::zero(), proof: vec![leaf], value: U256::zero() },
+            ],
+            ..Default::default()
+        };
+        let (root, account) = single_account_proof(&response);
+        response.account_proof = vec![account];
+
+        let verified = response.verify(root).unwrap();
+        assert_eq!(verified.storage[&key], 42u64.into());
+        assert_eq!(verified.storage[&H256::zero()], U256::zero());
+
+        // a different value is not proven by the same nodes
+        response.storage_proof[1].value = U256::one();
+        assert!(matches!(response.verify(root), Err(ProofError::ValueMismatch)));
+
+        // slots of accounts without storage hold zero
+        let empty = StorageProof { key, ..Default::default() };
+        empty.verify(H256(keccak256(rlp::NULL_RLP))).unwrap();
+        empty.verify(H256::zero()).unwrap();
+    }
+
+    #[test]
+    fn decodes_hex_prefix() {
+        assert_eq!(decode_hex_prefix(&[0x00, 0x12]).unwrap(), (false, vec![1, 2]));
+        assert_eq!(decode_hex_prefix(&[0x13, 0x45]).unwrap(), (false, vec![3, 4, 5]));
+        assert_eq!(decode_hex_prefix(&[0x20]).unwrap(), (true, vec![]));
+        assert_eq!(decode_hex_prefix(&[0x3f]).unwrap(), (true, vec![0x0f]));
+        assert!(decode_hex_prefix(&[0x40]).is_err());
+    }
 }
@@ -1,7 +1,7 @@
 //! Some convenient serde helpers
 
 use crate::types::{BlockNumber, U256, U64};
-use serde::{Deserialize, Deserializer};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use std::{
     convert::{TryFrom, TryInto},
     str::FromStr,
@@ -162,6 +162,51 @@ where
     }
 }
 
+/// Supports parsing numbers as decimal or hex strings or as JSON numbers, treating `null` as zero.
+///
+/// Combine with `#[serde(default)]` to also accept a missing field.
+pub fn deserialize_lenient_numeric<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<StringifiedNumeric>::deserialize(deserializer)?
+        .map_or(Ok(U256::zero()), |num| num.try_into().map_err(serde::de::Error::custom))
+}
+
+/// Supports parsing ethereum-types U64 as decimal or hex strings or as JSON numbers, treating
+/// `null` as zero.
+///
+/// Combine with `#[serde(default)]` to also accept a missing field.
+pub fn deserialize_lenient_eth_u64<'de, D>(deserializer: D) -> Result<U64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<StringifiedNumeric>::deserialize(deserializer)?
+        .map_or(Ok(U64::zero()), |num| num.try_into().map_err(serde::de::Error::custom))
+}
+
+/// Supports parsing u64 as decimal or hex strings or as JSON numbers, treating `null` as zero.
+///
+/// Combine with `#[serde(default)]` to also accept a missing field.
+pub fn deserialize_lenient_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let num = deserialize_lenient_numeric(deserializer)?;
+    num.try_into().map_err(serde::de::Error::custom)
+}
+
+/// Deserializes `null` as the default value of `T`.
+///
+/// Combine with `#[serde(default)]` to also accept a missing field.
+pub fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 /// Helper type to deserialize sequence of numbers
 #[derive(Deserialize)]
 #[serde(untagged)]
@@ -295,10 +340,125 @@ where
     Ok(num)
 }
 
+/// Wrapper which opts a response type into lenient deserialization, for RPCs which return
+/// nonconforming JSON.
+///
+/// Before deserializing `T`, integer JSON numbers are converted to hex quantities and `null` object
+/// members are dropped, so that they deserialize as `None` or fall back to their
+/// `#[serde(default)]`. This is meant for types which encode all their integers as quantities,
+/// like blocks, transactions, receipts and logs.
+///
+/// ```
+/// use ethers_core::types::{serde_helpers::Lenient, Log, U64};
+///
+/// let Lenient(log): Lenient<Log> = serde_json::from_value(serde_json::json!({
+///     "address": "0x0000000000000000000000000000000000000000",
+///     "topics": [],
+///     "data": "0x",
+///     "blockNumber": 17000000,
+///     "logIndex": null,
+/// }))
+/// .unwrap();
+/// assert_eq!(log.block_number, Some(U64::from(17_000_000)));
+/// assert_eq!(log.log_index, None);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Lenient<T>(pub T);
+
+impl<T> Lenient<T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_lenient(deserializer).map(Lenient)
+    }
+}
+
+/// Deserializes `T` leniently, see [`Lenient`].
+pub fn deserialize_lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let mut value = serde_json::Value::deserialize(deserializer)?;
+    normalize_lenient(&mut value);
+    serde_json::from_value(value).map_err(serde::de::Error::custom)
+}
+
+fn normalize_lenient(value: &mut serde_json::Value) {
+    use serde_json::Value;
+
+    match value {
+        Value::Number(num) => {
+            // negative and fractional numbers are left as is
+            if let Ok(num) = U256::from_dec_str(&num.to_string()) {
+                *value = Value::String(format!("0x{num:x}"));
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(normalize_lenient),
+        Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(normalize_lenient);
+        }
+        _ => {}
+    }
+}
+
+/// (De)serializes a [`Signature`](crate::types::Signature) as the hex string of its 64 byte
+/// [EIP-2098](https://eips.ethereum.org/EIPS/eip-2098) compact representation.
+///
+/// Deserialization also accepts regular 65 byte signatures.
+///
+/// ```
+/// use ethers_core::types::Signature;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Permit {
+///     #[serde(with = "ethers_core::types::serde_helpers::compact_signature")]
+///     signature: Signature,
+/// }
+/// ```
+pub mod compact_signature {
+    use crate::types::{Bytes, Signature};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::convert::TryFrom;
+
+    /// Serializes the signature in its compact representation
+    pub fn serialize<S>(signature: &Signature, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Bytes::from(signature.to_compact().to_vec()).serialize(serializer)
+    }
+
+    /// Deserializes a compact or regular signature
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Signature, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = Bytes::deserialize(deserializer)?;
+        match bytes.len() {
+            65 => Signature::try_from(bytes.as_ref()),
+            _ => Signature::from_compact(&bytes),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::U256;
+    use crate::types::{Signature, U256};
+    use serde::Serialize;
 
     #[test]
     fn test_deserialize_string_chain_id() {
@@ -317,6 +477,98 @@ mod tests {
         assert_eq!(domain.chain_id, Some(137u64.into()));
     }
 
+    #[test]
+    fn compact_signature_roundtrip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Signed {
+            #[serde(with = "compact_signature")]
+            signature: Signature,
+        }
+
+        let signed = Signed { signature: Signature { r: 1.into(), s: 2.into(), v: 28 } };
+        let json = serde_json::to_value(&signed).unwrap();
+        let expected = format!("0x{}01{}{}02", "00".repeat(31), "80", "00".repeat(30));
+        assert_eq!(json["signature"], expected);
+        assert_eq!(serde_json::from_value::<Signed>(json).unwrap(), signed);
+
+        // regular signatures are accepted too
+        let json = serde_json::json!({ "signature": format!("0x{}", signed.signature) });
+        assert_eq!(serde_json::from_value::<Signed>(json).unwrap(), signed);
+    }
+
+    #[test]
+    fn deserialize_lenient_fields() {
+        #[derive(Debug, Deserialize, Eq, PartialEq)]
+        struct TestValues {
+            #[serde(deserialize_with = "deserialize_lenient_numeric")]
+            value: U256,
+            #[serde(default, deserialize_with = "deserialize_lenient_eth_u64")]
+            number: U64,
+            #[serde(deserialize_with = "deserialize_lenient_u64")]
+            nonce: u64,
+            #[serde(default, deserialize_with = "deserialize_null_default")]
+            names: Vec<String>,
+        }
+
+        let values: TestValues = serde_json::from_value(serde_json::json!({
+            "value": "0x2a",
+            "number": "42",
+            "nonce": 42,
+            "names": ["a"]
+        }))
+        .unwrap();
+        assert_eq!(
+            values,
+            TestValues {
+                value: 42.into(),
+                number: 42.into(),
+                nonce: 42,
+                names: vec!["a".to_string()]
+            }
+        );
+
+        let values: TestValues = serde_json::from_value(serde_json::json!({
+            "value": null,
+            "nonce": "0x2a",
+            "names": null
+        }))
+        .unwrap();
+        assert_eq!(
+            values,
+            TestValues { value: 0.into(), number: 0.into(), nonce: 42, names: vec![] }
+        );
+
+        assert!(serde_json::from_value::<TestValues>(serde_json::json!({
+            "value": "forty-two",
+            "nonce": 42
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn deserialize_lenient_response() {
+        use crate::types::{Log, U64};
+
+        let json = serde_json::json!([{
+            "address": "0x0000000000000000000000000000000000000000",
+            "topics": [],
+            "data": "0x",
+            "blockHash": null,
+            "blockNumber": 17000000,
+            "transactionIndex": "0x1",
+            "logIndex": 42,
+            "removed": false
+        }]);
+        assert!(serde_json::from_value::<Vec<Log>>(json.clone()).is_err());
+
+        let logs = serde_json::from_value::<Lenient<Vec<Log>>>(json).unwrap().into_inner();
+        assert_eq!(logs[0].block_hash, None);
+        assert_eq!(logs[0].block_number, Some(U64::from(17_000_000)));
+        assert_eq!(logs[0].transaction_index, Some(U64::one()));
+        assert_eq!(logs[0].log_index, Some(U256::from(42)));
+        assert_eq!(logs[0].removed, Some(false));
+    }
+
     // <https://github.com/gakonst/ethers-rs/issues/2353>
     #[test]
     fn deserialize_stringified() {
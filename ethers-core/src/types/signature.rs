@@ -36,6 +36,39 @@ pub enum SignatureError {
     /// Error in recovering public key from signature
     #[error("Public key recovery error")]
     RecoveryError,
+    /// Invalid length, EIP-2098 compact signatures are 64 bytes
+    #[error("invalid compact signature length, got {0}, expected 64")]
+    InvalidCompactLength(usize),
+    /// The `s` value is in the upper half of the curve order, which
+    /// [EIP-2](https://eips.ethereum.org/EIPS/eip-2) forbids
+    #[error("non-canonical signature, s is in the upper half of the curve order")]
+    HighS,
+    /// The `v` value is not a valid recovery id
+    #[error("invalid signature v value {0}")]
+    InvalidV(u64),
+}
+
+/// The order of the secp256k1 curve
+const SECP256K1N: U256 =
+    U256([0xBFD25E8CD0364141, 0xBAAEDCE6AF48A03B, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF]);
+
+/// Half the order of the secp256k1 curve, the largest `s` value of a canonical signature
+const SECP256K1N_HALF: U256 =
+    U256([0xDFE92F46681B20A0, 0x5D576E7357A4501D, 0xFFFFFFFFFFFFFFFF, 0x7FFFFFFFFFFFFFFF]);
+
+/// How signature recovery handles malleable signatures, see [`Signature::recover_with_policy`].
+///
+/// For every signature `(r, s, v)` the signature `(r, n - s, v')` with the other parity `v'`
+/// recovers the same signer. [EIP-2](https://eips.ethereum.org/EIPS/eip-2) only allows the one
+/// with `s` in the lower half of the curve order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MalleabilityPolicy {
+    /// Signatures with a high `s` are normalized, and `v` is accepted as `0`, `1`, `27`, `28` or
+    /// an [EIP-155](https://eips.ethereum.org/EIPS/eip-155) value
+    #[default]
+    Lenient,
+    /// Signatures with a high `s`, or a `v` other than `27` or `28`, are rejected
+    Strict,
 }
 
 /// Recovery message data.
@@ -51,6 +84,37 @@ pub enum RecoveryMessage {
     Hash(H256),
 }
 
+/// A way of hashing a payload before signing it, see [`Signature::diagnose`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HashingScheme {
+    /// The payload is the signed 32 byte hash
+    RawHash,
+    /// The keccak256 hash of the payload was signed
+    Keccak256,
+    /// The payload was signed as an EIP-191 personal message
+    PersonalMessage,
+    /// The `0x` prefixed hex encoding of the 32 byte payload was signed as an EIP-191 personal
+    /// message
+    PersonalMessageHex,
+    /// The keccak256 hash of the payload was signed as an EIP-191 personal message
+    PersonalMessageKeccak256,
+    /// The payload is EIP-712 typed data in JSON, which was signed as such
+    TypedData,
+}
+
+/// A combination of hashing scheme and `v` value for which a signature recovers the expected
+/// signer, see [`Signature::diagnose`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignatureDiagnosis {
+    /// How the payload was hashed
+    pub scheme: HashingScheme,
+    /// The signed hash
+    pub hash: H256,
+    /// Whether the signer only recovers with the other `v` parity, or the `v` of the signature is
+    /// invalid
+    pub flipped_v: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy, Hash)]
 /// An ECDSA signature
 pub struct Signature {
@@ -87,8 +151,10 @@ impl Signature {
 
     /// Recovers the Ethereum address which was used to sign the given message.
     ///
-    /// Recovery signature data uses 'Electrum' notation, this means the `v`
-    /// value is expected to be either `27` or `28`.
+    /// `message` is hashed as an [EIP-191](https://eips.ethereum.org/EIPS/eip-191) personal
+    /// message unless it is a [`RecoveryMessage::Hash`], e.g. an [`H256`]. The `v` value may be
+    /// `27` or `28`, the raw parity `0` or `1`, or an
+    /// [EIP-155](https://eips.ethereum.org/EIPS/eip-155) value.
     pub fn recover<M>(&self, message: M) -> Result<Address, SignatureError>
     where
         M: Into<RecoveryMessage>,
@@ -114,11 +180,114 @@ impl Signature {
         Ok(Address::from_slice(&hash[12..]))
     }
 
+    /// Verifies that signature on `message` was produced by `address`, handling malleable
+    /// signatures according to `policy`.
+    pub fn verify_with_policy<M, A>(
+        &self,
+        message: M,
+        address: A,
+        policy: MalleabilityPolicy,
+    ) -> Result<(), SignatureError>
+    where
+        M: Into<RecoveryMessage>,
+        A: Into<Address>,
+    {
+        let address = address.into();
+        let recovered = self.recover_with_policy(message, policy)?;
+        if recovered != address {
+            return Err(SignatureError::VerificationError(address, recovered))
+        }
+
+        Ok(())
+    }
+
+    /// Recovers the Ethereum address which was used to sign the given message, handling malleable
+    /// signatures according to `policy`.
+    ///
+    /// ```
+    /// use ethers_core::types::{MalleabilityPolicy, Signature};
+    /// # use std::str::FromStr;
+    ///
+    /// let signature = Signature::from_str(
+    ///     "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+    /// ).unwrap();
+    /// let malleated = signature.malleate();
+    ///
+    /// assert_eq!(
+    ///     malleated.recover_with_policy("Some data", MalleabilityPolicy::Lenient).unwrap(),
+    ///     signature.recover("Some data").unwrap()
+    /// );
+    /// assert!(malleated.recover_with_policy("Some data", MalleabilityPolicy::Strict).is_err());
+    /// ```
+    pub fn recover_with_policy<M>(
+        &self,
+        message: M,
+        policy: MalleabilityPolicy,
+    ) -> Result<Address, SignatureError>
+    where
+        M: Into<RecoveryMessage>,
+    {
+        match policy {
+            MalleabilityPolicy::Lenient => self.normalize()?.recover(message),
+            MalleabilityPolicy::Strict => {
+                self.check_canonical()?;
+                self.recover(message)
+            }
+        }
+    }
+
+    /// Returns `true` if `s` is in the lower half of the curve order.
+    pub fn is_low_s(&self) -> bool {
+        self.s <= SECP256K1N_HALF
+    }
+
+    /// Returns an error if the signature has a high `s`, or a `v` other than `27` or `28`.
+    pub fn check_canonical(&self) -> Result<(), SignatureError> {
+        if self.v != 27 && self.v != 28 {
+            return Err(SignatureError::InvalidV(self.v))
+        }
+        if !self.is_low_s() {
+            return Err(SignatureError::HighS)
+        }
+        Ok(())
+    }
+
+    /// Returns the canonical form of the signature, with a low `s` and a `v` of `27` or `28`,
+    /// which recovers the same signer.
+    pub fn normalize(&self) -> Result<Self, SignatureError> {
+        let signature = Self { v: 27 + self.recovery_id()?.to_byte() as u64, ..*self };
+        Ok(if signature.is_low_s() { signature } else { signature.malleate() })
+    }
+
+    /// Returns the signature with `s` in the lower half of the curve order, flipping the parity
+    /// of `v` if `s` was high.
+    ///
+    /// Unlike [`Signature::normalize`], the encoding of `v` is kept, e.g. an
+    /// [EIP-155](https://eips.ethereum.org/EIPS/eip-155) `v` stays one. Contracts like
+    /// OpenZeppelin's `ECDSA` reject signatures with a high `s`.
+    pub fn normalize_s(&self) -> Self {
+        if self.is_low_s() {
+            *self
+        } else {
+            self.malleate()
+        }
+    }
+
+    /// Returns the other signature with the same `r` which recovers the same signer, with `s`
+    /// replaced by `n - s` and the parity of `v` flipped.
+    pub fn malleate(&self) -> Self {
+        let v = match normalize_recovery_id(self.v) {
+            0 => self.v + 1,
+            1 => self.v - 1,
+            _ => self.v,
+        };
+        Self { r: self.r, s: SECP256K1N.saturating_sub(self.s), v }
+    }
+
     /// Recovers the ethereum address which was used to sign a given EIP712
     /// typed data payload.
     ///
-    /// Recovery signature data uses 'Electrum' notation, this means the `v`
-    /// value is expected to be either `27` or `28`.
+    /// Accepts the same `v` values as [`Signature::recover`].
     pub fn recover_typed_data<T>(&self, payload: &T) -> Result<Address, SignatureError>
     where
         T: super::transaction::eip712::Eip712,
@@ -127,6 +296,87 @@ impl Signature {
         self.recover(encoded)
     }
 
+    /// Verifies that signature on the EIP712 typed data `payload` was produced by `address`
+    pub fn verify_typed_data<T, A>(&self, payload: &T, address: A) -> Result<(), SignatureError>
+    where
+        T: super::transaction::eip712::Eip712,
+        A: Into<Address>,
+    {
+        let address = address.into();
+        let recovered = self.recover_typed_data(payload)?;
+        if recovered != address {
+            return Err(SignatureError::VerificationError(address, recovered))
+        }
+
+        Ok(())
+    }
+
+    /// Tries all plausible ways in which `payload` may have been hashed and `v` may have been
+    /// encoded, and returns the combinations for which the signature recovers `signer`.
+    ///
+    /// This is meant for debugging signatures produced by other tools. The payload is tried as a
+    /// raw 32 byte hash, hashed with keccak256, as an EIP-191 personal message of itself, of its
+    /// hex encoding or of its keccak256 hash, and as EIP-712 typed data if it is JSON. Each hash
+    /// is tried with both `v` parities. An empty result means the signature was not produced by
+    /// `signer` for this payload.
+    ///
+    /// ```
+    /// use ethers_core::types::{HashingScheme, Signature};
+    /// # use std::str::FromStr;
+    ///
+    /// let signature = Signature::from_str(
+    ///     "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+    /// ).unwrap();
+    /// let signer = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".parse().unwrap();
+    ///
+    /// let matches = signature.diagnose(signer, b"Some data");
+    /// assert_eq!(matches[0].scheme, HashingScheme::PersonalMessage);
+    /// assert!(!matches[0].flipped_v);
+    /// ```
+    pub fn diagnose(&self, signer: Address, payload: &[u8]) -> Vec<SignatureDiagnosis> {
+        let mut hashes = Vec::new();
+        if payload.len() == 32 {
+            hashes.push((HashingScheme::RawHash, H256::from_slice(payload)));
+            hashes.push((
+                HashingScheme::PersonalMessageHex,
+                hash_message(format!("0x{}", hex::encode(payload))),
+            ));
+        }
+        hashes.push((HashingScheme::Keccak256, H256(crate::utils::keccak256(payload))));
+        hashes.push((HashingScheme::PersonalMessage, hash_message(payload)));
+        hashes.push((
+            HashingScheme::PersonalMessageKeccak256,
+            hash_message(crate::utils::keccak256(payload)),
+        ));
+        if let Ok(typed_data) =
+            serde_json::from_slice::<super::transaction::eip712::TypedData>(payload)
+        {
+            use super::transaction::eip712::Eip712;
+            if let Ok(hash) = typed_data.encode_eip712() {
+                hashes.push((HashingScheme::TypedData, H256(hash)));
+            }
+        }
+
+        let v = match normalize_recovery_id(self.v) {
+            id @ (0 | 1) => Some(27 + id as u64),
+            _ => None,
+        };
+        let mut matches = Vec::new();
+        for (scheme, hash) in hashes {
+            for candidate in [27, 28] {
+                let signature = Signature { v: candidate, ..*self };
+                if signature.recover(hash).ok() == Some(signer) {
+                    matches.push(SignatureDiagnosis {
+                        scheme,
+                        hash,
+                        flipped_v: v != Some(candidate),
+                    });
+                }
+            }
+        }
+        matches
+    }
+
     /// Retrieves the recovery signature.
     fn as_signature(&self) -> Result<(RecoverableSignature, RecoveryId), SignatureError> {
         let recovery_id = self.recovery_id()?;
@@ -146,7 +396,42 @@ impl Signature {
     /// Retrieve the recovery ID.
     pub fn recovery_id(&self) -> Result<RecoveryId, SignatureError> {
         let standard_v = normalize_recovery_id(self.v);
-        Ok(RecoveryId::from_byte(standard_v).expect("normalized recovery id always valid"))
+        RecoveryId::from_byte(standard_v).ok_or(SignatureError::InvalidV(self.v))
+    }
+
+    /// Encodes the signature in the 64 byte [EIP-2098](https://eips.ethereum.org/EIPS/eip-2098)
+    /// compact representation `r || yParity << 255 | s`.
+    ///
+    /// The compact form can only hold an `s` in the lower half of the curve order, so signatures
+    /// with a high `s` are normalized with [`Self::normalize_s`] first. The encoding recovers the
+    /// same signer, but decodes to the low-s twin of such a signature.
+    pub fn to_compact(&self) -> [u8; 64] {
+        let signature = self.normalize_s();
+        let mut compact = [0u8; 64];
+        signature.r.to_big_endian(&mut compact[..32]);
+        signature.s.to_big_endian(&mut compact[32..]);
+        if normalize_recovery_id(signature.v) == 1 {
+            compact[32] |= 0x80;
+        }
+        compact
+    }
+
+    /// Decodes a signature from its 64 byte [EIP-2098](https://eips.ethereum.org/EIPS/eip-2098)
+    /// compact representation. The returned signature has a `v` of 27 or 28.
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.len() != 64 {
+            return Err(SignatureError::InvalidCompactLength(bytes.len()))
+        }
+        let y_parity = bytes[32] >> 7;
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&bytes[32..]);
+        s[0] &= 0x7f;
+
+        Ok(Self {
+            r: U256::from_big_endian(&bytes[..32]),
+            s: U256::from_big_endian(&s),
+            v: 27 + y_parity as u64,
+        })
     }
 
     /// Copies and serializes `self` into a new `Vec` with the recovery id included
@@ -298,6 +583,42 @@ impl From<H256> for RecoveryMessage {
 mod tests {
     use super::*;
 
+    #[test]
+    fn compact_signature() {
+        // test vectors from EIP-2098
+        for (r, s, v, y_parity_and_s) in [
+            (
+                "68a020a209d3d56c46f38cc50a33f704f4a9a10a59377f8dd762ac66910e9b90",
+                "7e865ad05c4035ab5792787d4a0297a43617ae897930a6fe4d822b8faea52064",
+                27,
+                "7e865ad05c4035ab5792787d4a0297a43617ae897930a6fe4d822b8faea52064",
+            ),
+            (
+                "9328da16089fcba9bececa81663203989f2df5fe1faa6291a45381c81bd17f76",
+                "139c6d6b623b42da56557e5e734a43dc83345ddfadec52cbe24d0cc64f550793",
+                28,
+                "939c6d6b623b42da56557e5e734a43dc83345ddfadec52cbe24d0cc64f550793",
+            ),
+        ] {
+            let sig = Signature { r: r.parse().unwrap(), s: s.parse().unwrap(), v };
+            let compact = sig.to_compact();
+            assert_eq!(hex::encode(compact), format!("{r}{y_parity_and_s}"));
+            assert_eq!(Signature::from_compact(&compact).unwrap(), sig);
+
+            // EIP-155 signatures are encoded by their parity
+            let eip155 = Signature { v: v + 10, ..sig };
+            assert_eq!(eip155.to_compact(), compact);
+
+            // high-s signatures are encoded by their low-s twin
+            assert_eq!(sig.malleate().to_compact(), compact);
+        }
+
+        assert!(matches!(
+            Signature::from_compact(&[0u8; 65]),
+            Err(SignatureError::InvalidCompactLength(65))
+        ));
+    }
+
     #[test]
     fn recover_web3_signature() {
         // test vector taken from:
@@ -311,6 +632,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verify_typed_data() {
+        use crate::types::transaction::eip712::{Eip712, TypedData};
+        use k256::ecdsa::SigningKey;
+
+        let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [{ "name": "name", "type": "string" }],
+                "Mail": [{ "name": "contents", "type": "string" }]
+            },
+            "primaryType": "Mail",
+            "domain": { "name": "Ether Mail" },
+            "message": { "contents": "Hello, Bob!" }
+        }))
+        .unwrap();
+        let hash = typed_data.encode_eip712().unwrap();
+
+        let key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let signer = crate::utils::secret_key_to_address(&key);
+        let (sig, recovery_id) = key.sign_prehash_recoverable(&hash).unwrap();
+        let bytes = sig.to_bytes();
+        let signature = Signature {
+            r: U256::from_big_endian(&bytes[..32]),
+            s: U256::from_big_endian(&bytes[32..]),
+            v: 27 + recovery_id.to_byte() as u64,
+        };
+
+        assert_eq!(signature.recover_typed_data(&typed_data).unwrap(), signer);
+        signature.verify_typed_data(&typed_data, signer).unwrap();
+        signature.verify(H256::from(hash), signer).unwrap();
+
+        // EIP-155 and raw parity values recover the same signer
+        for v in [recovery_id.to_byte() as u64, 37 + recovery_id.to_byte() as u64] {
+            Signature { v, ..signature }.verify_typed_data(&typed_data, signer).unwrap();
+        }
+        assert!(matches!(
+            signature.verify_typed_data(&typed_data, Address::zero()),
+            Err(SignatureError::VerificationError(_, recovered)) if recovered == signer
+        ));
+    }
+
     #[test]
     fn signature_from_str() {
         let s1 = Signature::from_str(
@@ -323,4 +685,77 @@ mod tests {
 
         assert_eq!(s1, s2);
     }
+
+    #[test]
+    fn malleability_policy() {
+        let signature = Signature::from_str(
+            "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+        ).unwrap();
+        let signer = Address::from_str("2c7536E3605D9C16a7a3D7b1898e529396a65c23").unwrap();
+        assert!(signature.is_low_s());
+        signature.check_canonical().unwrap();
+        assert_eq!(signature.normalize().unwrap(), signature);
+
+        let malleated = signature.malleate();
+        assert!(!malleated.is_low_s());
+        assert_eq!(malleated.v, 27);
+        assert_eq!(malleated.malleate(), signature);
+        assert_eq!(malleated.normalize().unwrap(), signature);
+        assert_eq!(malleated.normalize_s(), signature);
+        assert_eq!(signature.normalize_s(), signature);
+        assert!(matches!(malleated.check_canonical(), Err(SignatureError::HighS)));
+        malleated.verify_with_policy("Some data", signer, MalleabilityPolicy::Lenient).unwrap();
+        assert!(matches!(
+            malleated.recover_with_policy("Some data", MalleabilityPolicy::Strict),
+            Err(SignatureError::HighS)
+        ));
+
+        // raw parity and EIP-155 values are only accepted by the lenient policy
+        for v in [1, 38] {
+            let signature = Signature { v, ..signature };
+            assert_eq!(signature.normalize().unwrap().v, 28);
+            assert_eq!(signature.malleate().normalize_s(), signature);
+            assert!(matches!(
+                signature.verify_with_policy("Some data", signer, MalleabilityPolicy::Strict),
+                Err(SignatureError::InvalidV(_))
+            ));
+        }
+        let lower = Signature { v: 0, ..signature.malleate() };
+        assert_eq!(lower.normalize().unwrap(), Signature { v: 28, ..signature });
+
+        let invalid = Signature { v: 5, ..signature };
+        assert!(matches!(invalid.recover("Some data"), Err(SignatureError::InvalidV(5))));
+        assert!(invalid.recover_with_policy("Some data", MalleabilityPolicy::Lenient).is_err());
+    }
+
+    #[test]
+    fn diagnose_signature() {
+        let signature = Signature::from_str(
+            "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+        ).unwrap();
+        let signer = Address::from_str("2c7536E3605D9C16a7a3D7b1898e529396a65c23").unwrap();
+        let hash = hash_message("Some data");
+
+        let matches = signature.diagnose(signer, b"Some data");
+        assert_eq!(
+            matches,
+            vec![SignatureDiagnosis {
+                scheme: HashingScheme::PersonalMessage,
+                hash,
+                flipped_v: false
+            }]
+        );
+
+        // the signed hash itself, with an EIP-155 and a wrong v
+        let eip155 = Signature { v: 38, ..signature };
+        let matches = eip155.diagnose(signer, hash.as_bytes());
+        assert_eq!(matches[0].scheme, HashingScheme::RawHash);
+        assert!(!matches[0].flipped_v);
+        let flipped = Signature { v: 0, ..signature };
+        assert!(flipped.diagnose(signer, hash.as_bytes())[0].flipped_v);
+        let invalid = Signature { v: 5, ..signature };
+        assert!(invalid.diagnose(signer, hash.as_bytes())[0].flipped_v);
+
+        assert!(signature.diagnose(Address::zero(), b"Some data").is_empty());
+    }
 }
@@ -146,7 +146,8 @@ pub enum ActionType {
     Call,
     /// Contract creation.
     Create,
-    /// Contract suicide.
+    /// Contract suicide, which Reth reports as `selfdestruct`.
+    #[serde(alias = "selfdestruct")]
     Suicide,
     /// A block reward.
     Reward,
@@ -224,6 +225,21 @@ pub struct Create {
     pub gas: U256,
     /// Initialization code
     pub init: Bytes,
+    /// The opcode which created the contract, only reported by some clients
+    #[serde(default, rename = "creationMethod", skip_serializing_if = "Option::is_none")]
+    pub creation_method: Option<CreationMethod>,
+}
+
+/// The opcode which created a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreationMethod {
+    /// `CREATE`, or a contract creation transaction
+    Create,
+    /// `CREATE2`
+    Create2,
+    /// `EOFCREATE`
+    EofCreate,
 }
 
 /// Suicide
@@ -469,6 +485,56 @@ mod tests {
         "type": "reward"
     }"#;
 
+    const EXAMPLE_TRACE_RETH: &str = r#"[{
+        "action": {
+            "from": "0xd1220a0cf47c7b9be7a2e6ba89f429762e7b9adb",
+            "gas": "0x63ab9",
+            "init": "0x",
+            "value": "0x0",
+            "creationMethod": "create2"
+        },
+        "blockHash": "0x6474a53a9ebf72d306a1406ec12ded12e210b6c3141b4373bfb3a3cea987dfb8",
+        "blockNumber": 988775,
+        "result": {
+            "address": "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+            "code": "0x",
+            "gasUsed": "0x4b419"
+        },
+        "subtraces": 1,
+        "traceAddress": [],
+        "transactionHash": "0x342c284238149db221f9d87db87f90ffad7ac0aac57c0c480142f4c21b63f652",
+        "transactionPosition": 1,
+        "type": "create"
+    }, {
+        "action": {
+            "address": "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+            "refundAddress": "0xd1220a0cf47c7b9be7a2e6ba89f429762e7b9adb",
+            "balance": "0x0"
+        },
+        "blockHash": "0x6474a53a9ebf72d306a1406ec12ded12e210b6c3141b4373bfb3a3cea987dfb8",
+        "blockNumber": 988775,
+        "result": null,
+        "subtraces": 0,
+        "traceAddress": [0],
+        "transactionHash": "0x342c284238149db221f9d87db87f90ffad7ac0aac57c0c480142f4c21b63f652",
+        "transactionPosition": 1,
+        "type": "selfdestruct"
+    }]"#;
+
+    #[test]
+    fn test_deserialize_reth_trace() {
+        let traces: Vec<Trace> = serde_json::from_str(EXAMPLE_TRACE_RETH).unwrap();
+        match &traces[0].action {
+            Action::Create(create) => {
+                assert_eq!(create.creation_method, Some(CreationMethod::Create2))
+            }
+            action => panic!("expected a create action, got {action:?}"),
+        }
+        assert!(matches!(traces[0].result, Some(Res::Create(_))));
+        assert_eq!(traces[1].action_type, ActionType::Suicide);
+        assert!(matches!(traces[1].action, Action::Suicide(_)));
+    }
+
     #[test]
     fn test_deserialize_trace() {
         let _trace: Trace = serde_json::from_str(EXAMPLE_TRACE_CALL).unwrap();
@@ -12,7 +12,7 @@ pub use self::{
 use crate::types::{
     serde_helpers::deserialize_stringified_numeric, Address, Bytes, H256, U256, U64,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::BTreeMap;
 
@@ -44,6 +44,9 @@ pub struct StructLog {
     pub pc: u64,
     #[serde(default, rename = "refund", skip_serializing_if = "Option::is_none")]
     pub refund_counter: Option<u64>,
+    /// The return data of the last call, if `enableReturnData` is set
+    #[serde(default, rename = "returnData", skip_serializing_if = "Option::is_none")]
+    pub return_data: Option<Bytes>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub stack: Option<Vec<U256>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -107,6 +110,20 @@ pub enum GethTrace {
     Unknown(Value),
 }
 
+impl GethTrace {
+    /// Converts the trace into the frame of a tracer, e.g. a [`CallFrame`] for the `callTracer`.
+    ///
+    /// Unlike matching on [`GethTrace::Known`], this also converts traces which deserialized into
+    /// another frame with the same shape, or which could not be deserialized into a known frame.
+    pub fn try_into_frame<T: DeserializeOwned>(self) -> Result<T, serde_json::Error> {
+        let value = match self {
+            GethTrace::Known(frame) => serde_json::to_value(frame)?,
+            GethTrace::Unknown(value) => value,
+        };
+        serde_json::from_value(value)
+    }
+}
+
 impl From<GethTraceResult> for GethTrace {
     fn from(value: GethTraceResult) -> Self {
         match value {
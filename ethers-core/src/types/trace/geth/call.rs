@@ -1,6 +1,6 @@
 use crate::types::{
     serde_helpers::{deserialize_stringified_numeric, deserialize_stringified_numeric_opt},
-    Address, Bytes, NameOrAddress, H256, U256,
+    Address, Bytes, NameOrAddress, H256, U256, U64,
 };
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +27,9 @@ pub struct CallFrame {
     pub output: Option<Bytes>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// The reason string of a call which reverted with `Error(string)`
+    #[serde(default, rename = "revertReason", skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub calls: Option<Vec<CallFrame>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -41,6 +44,9 @@ pub struct CallLogFrame {
     pub topics: Option<Vec<H256>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub data: Option<Bytes>,
+    /// The number of subcalls of the frame which were made before the log was emitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<U64>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -88,4 +94,35 @@ mod tests {
         let trace: CallFrame = serde_json::from_str(WITH_LOG).unwrap();
         let _logs = trace.logs.unwrap();
     }
+
+    #[test]
+    fn test_deserialize_reverted_call_trace() {
+        let trace: GethTrace = serde_json::from_value(serde_json::json!({
+            "type": "CALL",
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "value": "0x0",
+            "gas": "0x5208",
+            "gasUsed": "0x5208",
+            "input": "0x",
+            "output": "0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000046e6f706500000000000000000000000000000000000000000000000000000000",
+            "error": "execution reverted",
+            "revertReason": "nope",
+            "logs": [{
+                "address": "0x0000000000000000000000000000000000000002",
+                "topics": [],
+                "data": "0x",
+                "position": "0x0"
+            }]
+        }))
+        .unwrap();
+        assert!(matches!(trace, GethTrace::Known(GethTraceFrame::CallTracer(_))));
+
+        let frame: CallFrame = trace.try_into_frame().unwrap();
+        assert_eq!(frame.revert_reason.as_deref(), Some("nope"));
+        assert_eq!(frame.logs.unwrap()[0].position, Some(U64::zero()));
+
+        let unknown = GethTrace::Unknown(serde_json::json!({ "foo": 1 }));
+        assert!(unknown.try_into_frame::<CallFrame>().is_err());
+    }
 }
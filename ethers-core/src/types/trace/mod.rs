@@ -135,6 +135,9 @@ pub struct VMOperation {
     /// The opcode of the executed instruction
     #[serde(rename = "op")]
     pub op: ExecutedInstruction,
+    /// The position of the instruction in the trace, e.g. `0-1`, only reported by some clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idx: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
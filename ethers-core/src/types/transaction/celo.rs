@@ -0,0 +1,345 @@
+//! Celo transactions that pay their fees in an ERC-20 fee currency, see
+//! [CIP-42](https://github.com/celo-org/celo-proposals/blob/master/CIPs/cip-0042.md) and
+//! [CIP-64](https://github.com/celo-org/celo-proposals/blob/master/CIPs/cip-0064.md).
+
+use super::{
+    decode_to, eip1559::Eip1559TransactionRequest, eip2718::TypedTransaction, normalize_v, rlp_opt,
+};
+use crate::types::{
+    Address, Bytes, NameOrAddress, Signature, SignatureError, Transaction, U256, U64,
+};
+use rlp::{Decodable, DecoderError, RlpStream};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The EIP-2718 type of CIP-42 transactions
+pub const CIP42_TX_TYPE: u8 = 0x7c;
+
+/// The EIP-2718 type of CIP-64 transactions
+pub const CIP64_TX_TYPE: u8 = 0x7b;
+
+/// CIP-42 transactions have 12 fields
+const CIP42_NUM_TX_FIELDS: usize = 12;
+
+/// CIP-64 transactions have 10 fields
+const CIP64_NUM_TX_FIELDS: usize = 10;
+
+/// An error involving a Celo transaction request.
+#[derive(Debug, Error)]
+pub enum CeloRequestError {
+    /// When decoding a transaction request from RLP
+    #[error(transparent)]
+    DecodingError(#[from] rlp::DecoderError),
+    /// When recovering the address from a signature
+    #[error(transparent)]
+    RecoveryError(#[from] SignatureError),
+}
+
+/// A CIP-42 transaction: an EIP-1559 transaction whose fees are paid in `fee_currency`, with an
+/// optional fee paid to the full node that relayed the transaction.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct Cip42TransactionRequest {
+    #[serde(flatten)]
+    pub tx: Eip1559TransactionRequest,
+
+    /// The currency fees are paid in (None for native currency)
+    #[serde(rename = "feeCurrency", skip_serializing_if = "Option::is_none")]
+    pub fee_currency: Option<Address>,
+
+    /// Gateway fee recipient (None for no gateway fee paid)
+    #[serde(rename = "gatewayFeeRecipient", skip_serializing_if = "Option::is_none")]
+    pub gateway_fee_recipient: Option<Address>,
+
+    /// Gateway fee amount (None for no gateway fee paid)
+    #[serde(rename = "gatewayFee", skip_serializing_if = "Option::is_none")]
+    pub gateway_fee: Option<U256>,
+}
+
+impl Cip42TransactionRequest {
+    pub fn new(
+        tx: Eip1559TransactionRequest,
+        fee_currency: Option<Address>,
+        gateway_fee_recipient: Option<Address>,
+        gateway_fee: Option<U256>,
+    ) -> Self {
+        Self { tx, fee_currency, gateway_fee_recipient, gateway_fee }
+    }
+
+    /// Sets the `fee_currency` field in the transaction to the provided value
+    #[must_use]
+    pub fn fee_currency<T: Into<Address>>(mut self, fee_currency: T) -> Self {
+        self.fee_currency = Some(fee_currency.into());
+        self
+    }
+
+    /// Sets the `gateway_fee_recipient` field in the transaction to the provided value
+    #[must_use]
+    pub fn gateway_fee_recipient<T: Into<Address>>(mut self, gateway_fee_recipient: T) -> Self {
+        self.gateway_fee_recipient = Some(gateway_fee_recipient.into());
+        self
+    }
+
+    /// Sets the `gateway_fee` field in the transaction to the provided value
+    #[must_use]
+    pub fn gateway_fee<T: Into<U256>>(mut self, gateway_fee: T) -> Self {
+        self.gateway_fee = Some(gateway_fee.into());
+        self
+    }
+
+    /// Hashes the transaction's data with the provided chain id
+    pub fn rlp(&self) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(CIP42_NUM_TX_FIELDS);
+        self.rlp_base(&mut rlp);
+        rlp.out().freeze().into()
+    }
+
+    /// Produces the RLP encoding of the transaction with the provided signature
+    pub fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_unbounded_list();
+        self.rlp_base(&mut rlp);
+        append_signature(&mut rlp, self.tx.chain_id, signature);
+        rlp.finalize_unbounded_list();
+        rlp.out().freeze().into()
+    }
+
+    pub(crate) fn rlp_base(&self, rlp: &mut RlpStream) {
+        let tx = &self.tx;
+        rlp_opt(rlp, &tx.chain_id);
+        rlp_opt(rlp, &tx.nonce);
+        rlp_opt(rlp, &tx.max_priority_fee_per_gas);
+        rlp_opt(rlp, &tx.max_fee_per_gas);
+        rlp_opt(rlp, &tx.gas);
+        rlp_opt(rlp, &self.fee_currency);
+        rlp_opt(rlp, &self.gateway_fee_recipient);
+        rlp_opt(rlp, &self.gateway_fee);
+        rlp_opt(rlp, &tx.to.as_ref());
+        rlp_opt(rlp, &tx.value);
+        rlp_opt(rlp, &tx.data.as_ref().map(|d| d.as_ref()));
+        rlp.append(&tx.access_list);
+    }
+
+    /// Decodes fields of the request starting at the RLP offset passed. Increments the offset for
+    /// each element parsed.
+    pub fn decode_base_rlp(rlp: &rlp::Rlp, offset: &mut usize) -> Result<Self, DecoderError> {
+        let mut tx = Eip1559TransactionRequest::new();
+        tx.chain_id = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.nonce = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.max_priority_fee_per_gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.max_fee_per_gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        let fee_currency = decode_to(rlp, offset)?;
+        let gateway_fee_recipient = decode_to(rlp, offset)?;
+        let gateway_fee = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.to = decode_to(rlp, offset)?.map(NameOrAddress::Address);
+        tx.value = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        let data = rlp::Rlp::new(rlp.at(*offset)?.as_raw()).data()?;
+        tx.data = match data.len() {
+            0 => None,
+            _ => Some(Bytes::from(data.to_vec())),
+        };
+        *offset += 1;
+        tx.access_list = rlp.val_at(*offset)?;
+        *offset += 1;
+        Ok(Self { tx, fee_currency, gateway_fee_recipient, gateway_fee })
+    }
+
+    /// Decodes the given RLP into a transaction, attempting to decode its signature as well.
+    pub fn decode_signed_rlp(rlp: &rlp::Rlp) -> Result<(Self, Signature), CeloRequestError> {
+        let mut offset = 0;
+        let mut txn = Self::decode_base_rlp(rlp, &mut offset)?;
+        let sig = decode_signature(rlp, offset)?;
+        txn.tx.from = Some(sig.recover(TypedTransaction::Cip42(txn.clone()).sighash())?);
+        Ok((txn, sig))
+    }
+}
+
+impl Decodable for Cip42TransactionRequest {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        Self::decode_base_rlp(rlp, &mut 0)
+    }
+}
+
+/// Get a Cip42TransactionRequest from a Transaction
+impl From<&Transaction> for Cip42TransactionRequest {
+    fn from(tx: &Transaction) -> Cip42TransactionRequest {
+        Cip42TransactionRequest {
+            tx: tx.into(),
+            fee_currency: tx.fee_currency,
+            gateway_fee_recipient: tx.gateway_fee_recipient,
+            gateway_fee: tx.gateway_fee,
+        }
+    }
+}
+
+/// A CIP-64 transaction: an EIP-1559 transaction whose fees are paid in `fee_currency`.
+///
+/// CIP-64 replaces CIP-42 and drops its gateway fee.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct Cip64TransactionRequest {
+    #[serde(flatten)]
+    pub tx: Eip1559TransactionRequest,
+
+    /// The currency fees are paid in (None for native currency)
+    #[serde(rename = "feeCurrency", skip_serializing_if = "Option::is_none")]
+    pub fee_currency: Option<Address>,
+}
+
+impl Cip64TransactionRequest {
+    pub fn new(tx: Eip1559TransactionRequest, fee_currency: Option<Address>) -> Self {
+        Self { tx, fee_currency }
+    }
+
+    /// Sets the `fee_currency` field in the transaction to the provided value
+    #[must_use]
+    pub fn fee_currency<T: Into<Address>>(mut self, fee_currency: T) -> Self {
+        self.fee_currency = Some(fee_currency.into());
+        self
+    }
+
+    /// Hashes the transaction's data with the provided chain id
+    pub fn rlp(&self) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(CIP64_NUM_TX_FIELDS);
+        self.rlp_base(&mut rlp);
+        rlp.out().freeze().into()
+    }
+
+    /// Produces the RLP encoding of the transaction with the provided signature
+    pub fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_unbounded_list();
+        self.rlp_base(&mut rlp);
+        append_signature(&mut rlp, self.tx.chain_id, signature);
+        rlp.finalize_unbounded_list();
+        rlp.out().freeze().into()
+    }
+
+    pub(crate) fn rlp_base(&self, rlp: &mut RlpStream) {
+        self.tx.rlp_base(rlp);
+        rlp_opt(rlp, &self.fee_currency);
+    }
+
+    /// Decodes fields of the request starting at the RLP offset passed. Increments the offset for
+    /// each element parsed.
+    pub fn decode_base_rlp(rlp: &rlp::Rlp, offset: &mut usize) -> Result<Self, DecoderError> {
+        let tx = Eip1559TransactionRequest::decode_base_rlp(rlp, offset)?;
+        let fee_currency = decode_to(rlp, offset)?;
+        Ok(Self { tx, fee_currency })
+    }
+
+    /// Decodes the given RLP into a transaction, attempting to decode its signature as well.
+    pub fn decode_signed_rlp(rlp: &rlp::Rlp) -> Result<(Self, Signature), CeloRequestError> {
+        let mut offset = 0;
+        let mut txn = Self::decode_base_rlp(rlp, &mut offset)?;
+        let sig = decode_signature(rlp, offset)?;
+        txn.tx.from = Some(sig.recover(TypedTransaction::Cip64(txn.clone()).sighash())?);
+        Ok((txn, sig))
+    }
+}
+
+impl Decodable for Cip64TransactionRequest {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        Self::decode_base_rlp(rlp, &mut 0)
+    }
+}
+
+/// Get a Cip64TransactionRequest from a Transaction
+impl From<&Transaction> for Cip64TransactionRequest {
+    fn from(tx: &Transaction) -> Cip64TransactionRequest {
+        Cip64TransactionRequest { tx: tx.into(), fee_currency: tx.fee_currency }
+    }
+}
+
+/// Appends the y-parity, `r` and `s` of the signature, like for EIP-1559 transactions
+fn append_signature(rlp: &mut RlpStream, chain_id: Option<U64>, signature: &Signature) {
+    // if the chain_id is none we assume mainnet and choose one
+    let chain_id = chain_id.unwrap_or_else(U64::one);
+    rlp.append(&normalize_v(signature.v, chain_id));
+    rlp.append(&signature.r);
+    rlp.append(&signature.s);
+}
+
+fn decode_signature(rlp: &rlp::Rlp, mut offset: usize) -> Result<Signature, DecoderError> {
+    let v = rlp.val_at(offset)?;
+    offset += 1;
+    let r = rlp.val_at(offset)?;
+    offset += 1;
+    let s = rlp.val_at(offset)?;
+    Ok(Signature { r, s, v })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{k256::ecdsa::SigningKey, types::H256, utils::secret_key_to_address};
+
+    fn sign(key: &SigningKey, hash: H256) -> Signature {
+        let (signature, recovery_id) = key.sign_prehash_recoverable(hash.as_bytes()).unwrap();
+        Signature {
+            r: U256::from_big_endian(&signature.r().to_bytes()),
+            s: U256::from_big_endian(&signature.s().to_bytes()),
+            v: u8::from(recovery_id) as u64,
+        }
+    }
+
+    fn eip1559_tx() -> Eip1559TransactionRequest {
+        Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .value(1_000u64)
+            .data(vec![0xab, 0xcd])
+            .nonce(3)
+            .gas(100_000)
+            .max_fee_per_gas(5_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .chain_id(44787)
+    }
+
+    #[test]
+    fn roundtrips_signed_cip64() {
+        let key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+        let fee_currency = Address::repeat_byte(0xcc);
+        let tx: TypedTransaction =
+            Cip64TransactionRequest::new(eip1559_tx(), Some(fee_currency)).into();
+        assert_eq!(tx.rlp()[0], CIP64_TX_TYPE);
+
+        let signature = sign(&key, tx.sighash());
+        let encoded = tx.rlp_signed(&signature);
+        let (decoded, decoded_signature) =
+            TypedTransaction::decode_signed(&rlp::Rlp::new(&encoded)).unwrap();
+
+        assert_eq!(decoded_signature, signature);
+        assert_eq!(decoded.from(), Some(&secret_key_to_address(&key)));
+        assert_eq!(decoded.fee_currency(), Some(&fee_currency));
+        assert_eq!(decoded.sighash(), tx.sighash());
+    }
+
+    #[test]
+    fn roundtrips_signed_cip42() {
+        let key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+        let tx: TypedTransaction = Cip42TransactionRequest::new(eip1559_tx(), None, None, None)
+            .fee_currency(Address::repeat_byte(0xcc))
+            .gateway_fee_recipient(Address::repeat_byte(0xdd))
+            .gateway_fee(21u64)
+            .into();
+        assert_eq!(tx.rlp()[0], CIP42_TX_TYPE);
+
+        let signature = sign(&key, tx.sighash());
+        let encoded = tx.rlp_signed(&signature);
+        let (decoded, _) = TypedTransaction::decode_signed(&rlp::Rlp::new(&encoded)).unwrap();
+
+        let TypedTransaction::Cip42(decoded) = decoded else { panic!("not a CIP-42 transaction") };
+        assert_eq!(decoded.gateway_fee, Some(21u64.into()));
+        assert_eq!(decoded.gateway_fee_recipient, Some(Address::repeat_byte(0xdd)));
+        assert_eq!(decoded.tx.from, Some(secret_key_to_address(&key)));
+        assert_eq!(decoded.tx.data, Some(vec![0xab, 0xcd].into()));
+    }
+}
@@ -0,0 +1,331 @@
+//! Receipts with decoded logs and a summary of the token movements they record
+use crate::{
+    abi::{AbiRegistry, DecodedEvent, Token},
+    types::{Address, Log, TransactionReceipt, U256},
+};
+use std::fmt;
+
+/// A log together with its decoded event, if the event is known.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedLog {
+    /// The raw log
+    pub log: Log,
+    /// The decoded event, or `None` if the registry does not know the event
+    pub event: Option<DecodedEvent>,
+}
+
+/// The logs of a [`TransactionReceipt`], decoded with an [`AbiRegistry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedReceipt {
+    /// The decoded logs, in the order of the receipt
+    pub logs: Vec<DecodedLog>,
+}
+
+impl DecodedReceipt {
+    /// Decodes the logs of `receipt` with the events known to `registry`.
+    pub fn new(receipt: &TransactionReceipt, registry: &AbiRegistry) -> Self {
+        let logs = receipt
+            .logs
+            .iter()
+            .map(|log| DecodedLog { log: log.clone(), event: registry.decode_log(log) })
+            .collect();
+        Self { logs }
+    }
+
+    /// Summarizes the token transfers and approvals of the receipt.
+    ///
+    /// Only events of the ERC-20, ERC-721 and ERC-1155 standards, as registered by
+    /// [`AbiRegistry::with_token_standards`], are taken into account.
+    pub fn summary(&self) -> ReceiptSummary {
+        let mut summary = ReceiptSummary::default();
+        for log in &self.logs {
+            if let Some(event) = &log.event {
+                summary.add(log.log.address, event);
+            }
+        }
+        summary
+    }
+}
+
+/// The token transfers and approvals recorded in a receipt, in the order of its logs.
+///
+/// The [`Display`](fmt::Display) implementation renders one line per transfer or approval.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReceiptSummary {
+    /// The tokens moved
+    pub transfers: Vec<TokenTransfer>,
+    /// The approvals granted or revoked
+    pub approvals: Vec<TokenApproval>,
+}
+
+impl ReceiptSummary {
+    /// Returns `true` if no tokens were moved or approved.
+    pub fn is_empty(&self) -> bool {
+        self.transfers.is_empty() && self.approvals.is_empty()
+    }
+
+    fn add(&mut self, token: Address, event: &DecodedEvent) {
+        let address = |name: &str| event.param(name).cloned().and_then(Token::into_address);
+        let uint = |name: &str| event.param(name).cloned().and_then(Token::into_uint);
+        let uints =
+            |name: &str| {
+                event.param(name).cloned().and_then(Token::into_array).map(|tokens| {
+                    tokens.into_iter().filter_map(Token::into_uint).collect::<Vec<_>>()
+                })
+            };
+
+        match event.name.as_str() {
+            "Transfer" => {
+                let (Some(from), Some(to)) = (address("from"), address("to")) else { return };
+                let amount = match (uint("value"), uint("tokenId")) {
+                    (Some(value), _) => TokenAmount::Fungible(value),
+                    (_, Some(id)) => TokenAmount::NonFungible(id),
+                    _ => return,
+                };
+                self.transfers.push(TokenTransfer { token, from, to, amount });
+            }
+            "TransferSingle" => {
+                let (Some(from), Some(to), Some(id), Some(value)) =
+                    (address("from"), address("to"), uint("id"), uint("value"))
+                else {
+                    return
+                };
+                let amount = TokenAmount::MultiToken { id, value };
+                self.transfers.push(TokenTransfer { token, from, to, amount });
+            }
+            "TransferBatch" => {
+                let (Some(from), Some(to), Some(ids), Some(values)) =
+                    (address("from"), address("to"), uints("ids"), uints("values"))
+                else {
+                    return
+                };
+                for (id, value) in ids.into_iter().zip(values) {
+                    let amount = TokenAmount::MultiToken { id, value };
+                    self.transfers.push(TokenTransfer { token, from, to, amount });
+                }
+            }
+            "Approval" => {
+                let Some(owner) = address("owner") else { return };
+                let approval = match (address("spender"), uint("value"), address("approved")) {
+                    (Some(spender), Some(value), _) => (spender, ApprovalScope::Amount(value)),
+                    (_, _, Some(approved)) => match uint("tokenId") {
+                        Some(id) => (approved, ApprovalScope::Token(id)),
+                        None => return,
+                    },
+                    _ => return,
+                };
+                let (spender, scope) = approval;
+                self.approvals.push(TokenApproval { token, owner, spender, scope });
+            }
+            "ApprovalForAll" => {
+                let (Some(owner), Some(spender)) = (address("owner"), address("operator")) else {
+                    return
+                };
+                let Some(Token::Bool(approved)) = event.param("approved").cloned() else { return };
+                let scope = ApprovalScope::All(approved);
+                self.approvals.push(TokenApproval { token, owner, spender, scope });
+            }
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for ReceiptSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for transfer in &self.transfers {
+            writeln!(f, "{transfer}")?;
+        }
+        for approval in &self.approvals {
+            writeln!(f, "{approval}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The amount of a [`TokenTransfer`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenAmount {
+    /// An amount of an ERC-20 token, in its smallest unit
+    Fungible(U256),
+    /// An ERC-721 token, by its id
+    NonFungible(U256),
+    /// An amount of an ERC-1155 token
+    MultiToken {
+        /// The id of the token
+        id: U256,
+        /// The amount of the token
+        value: U256,
+    },
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenAmount::Fungible(value) => write!(f, "{value}"),
+            TokenAmount::NonFungible(id) => write!(f, "#{id}"),
+            TokenAmount::MultiToken { id, value } => write!(f, "{value} of #{id}"),
+        }
+    }
+}
+
+/// Tokens moved by a transfer event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenTransfer {
+    /// The token contract
+    pub token: Address,
+    /// The sender, or the zero address for mints
+    pub from: Address,
+    /// The recipient, or the zero address for burns
+    pub to: Address,
+    /// The tokens moved
+    pub amount: TokenAmount,
+}
+
+impl fmt::Display for TokenTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transfer {} of {:?} from {:?} to {:?}",
+            self.amount, self.token, self.from, self.to
+        )
+    }
+}
+
+/// What a [`TokenApproval`] allows the spender to move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApprovalScope {
+    /// Up to the amount of an ERC-20 token, zero revokes the approval
+    Amount(U256),
+    /// The ERC-721 token with the id
+    Token(U256),
+    /// All tokens of the owner if `true`, none if `false`
+    All(bool),
+}
+
+impl fmt::Display for ApprovalScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalScope::Amount(value) if *value == U256::MAX => f.write_str("unlimited"),
+            ApprovalScope::Amount(value) => write!(f, "{value}"),
+            ApprovalScope::Token(id) => write!(f, "#{id}"),
+            ApprovalScope::All(true) => f.write_str("all tokens"),
+            ApprovalScope::All(false) => f.write_str("no tokens"),
+        }
+    }
+}
+
+/// An approval granted or revoked by an approval event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenApproval {
+    /// The token contract
+    pub token: Address,
+    /// The owner of the tokens
+    pub owner: Address,
+    /// The account allowed to move the tokens
+    pub spender: Address,
+    /// What the spender is allowed to move
+    pub scope: ApprovalScope,
+}
+
+impl fmt::Display for TokenApproval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "approve {:?} to spend {} of {:?} owned by {:?}",
+            self.spender, self.scope, self.token, self.owner
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{abi::encode, types::H256, utils::keccak256};
+
+    fn log(token: Address, signature: &str, topics: &[H256], data: &[Token]) -> Log {
+        let mut all = vec![H256::from(keccak256(signature))];
+        all.extend_from_slice(topics);
+        Log { address: token, topics: all, data: encode(data).into(), ..Default::default() }
+    }
+
+    #[test]
+    fn summarizes_token_events() {
+        let token = Address::repeat_byte(0x70);
+        let (owner, spender) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let receipt = TransactionReceipt {
+            logs: vec![
+                log(
+                    token,
+                    "Approval(address,address,uint256)",
+                    &[owner.into(), spender.into()],
+                    &[Token::Uint(U256::MAX)],
+                ),
+                log(
+                    token,
+                    "Transfer(address,address,uint256)",
+                    &[owner.into(), spender.into()],
+                    &[Token::Uint(100.into())],
+                ),
+                log(token, "Unknown()", &[], &[]),
+            ],
+            ..Default::default()
+        };
+
+        let decoded = receipt.decode_logs(&AbiRegistry::with_token_standards());
+        assert_eq!(decoded.logs.len(), 3);
+        assert!(decoded.logs[2].event.is_none());
+
+        let summary = decoded.summary();
+        assert_eq!(
+            summary.transfers,
+            [TokenTransfer {
+                token,
+                from: owner,
+                to: spender,
+                amount: TokenAmount::Fungible(100.into())
+            }]
+        );
+        assert_eq!(
+            summary.approvals,
+            [TokenApproval { token, owner, spender, scope: ApprovalScope::Amount(U256::MAX) }]
+        );
+        assert_eq!(
+            summary.to_string(),
+            format!(
+                "transfer 100 of {token:?} from {owner:?} to {spender:?}\n\
+                 approve {spender:?} to spend unlimited of {token:?} owned by {owner:?}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn summarizes_batch_transfers() {
+        let token = Address::repeat_byte(0x11);
+        let (operator, to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let ids = Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]);
+        let values = Token::Array(vec![Token::Uint(10.into()), Token::Uint(20.into())]);
+        let receipt = TransactionReceipt {
+            logs: vec![log(
+                token,
+                "TransferBatch(address,address,address,uint256[],uint256[])",
+                &[operator.into(), H256::zero(), to.into()],
+                &[ids, values],
+            )],
+            ..Default::default()
+        };
+
+        let summary = receipt.decode_logs(&AbiRegistry::with_token_standards()).summary();
+        let amounts: Vec<_> = summary.transfers.iter().map(|transfer| transfer.amount).collect();
+        assert_eq!(
+            amounts,
+            [
+                TokenAmount::MultiToken { id: 1.into(), value: 10.into() },
+                TokenAmount::MultiToken { id: 2.into(), value: 20.into() },
+            ]
+        );
+        assert!(summary
+            .transfers
+            .iter()
+            .all(|transfer| transfer.from.is_zero() && transfer.to == to));
+    }
+}
@@ -0,0 +1,189 @@
+//! EIP-2612 permits, which approve ERC-20 allowances with a signature instead of a transaction
+use crate::{
+    abi::{encode, Token},
+    types::{
+        transaction::eip712::{EIP712Domain, Eip712, Eip712Error},
+        Address, Bytes, Signature, U256,
+    },
+    utils::{id, keccak256},
+};
+
+/// The type hash of `Permit(address owner,address spender,uint256 value,uint256 nonce,uint256
+/// deadline)`
+pub const PERMIT_TYPEHASH: [u8; 32] = [
+    110, 113, 237, 174, 18, 177, 185, 127, 77, 31, 96, 55, 15, 239, 16, 16, 95, 162, 250, 174, 1,
+    38, 17, 74, 22, 156, 100, 132, 93, 97, 38, 201,
+];
+
+/// The EIP-712 payload of an ERC-20 `permit` call, allowing `spender` to spend `value` of the
+/// `owner`'s tokens.
+///
+/// Nonces are sequential, a permit is only valid for the owner's current `nonces(owner)` of the
+/// token. Tokens with DAI-style permits, which allow or revoke unlimited allowances, use a
+/// different type and are not supported.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_core::types::{
+///     transaction::{eip2612::Permit, eip712::EIP712Domain},
+///     Address,
+/// };
+///
+/// let usdc = EIP712Domain {
+///     name: Some("USD Coin".to_string()),
+///     version: Some("2".to_string()),
+///     chain_id: Some(1.into()),
+///     verifying_contract: Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".parse().unwrap()),
+///     salt: None,
+/// };
+/// let (owner, spender) = (Address::repeat_byte(1), Address::repeat_byte(2));
+/// let permit = Permit::new(usdc, owner, spender, 1_000_000u64, 0u64).deadline(1_700_000_000u64);
+/// // `permit` implements `Eip712` and can be signed with `Signer::sign_typed_data`
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permit {
+    /// The domain of the token contract
+    pub domain: EIP712Domain,
+    /// The holder of the tokens, which signs the permit
+    pub owner: Address,
+    /// The account allowed to spend the tokens
+    pub spender: Address,
+    /// The allowance, in the token's smallest unit
+    pub value: U256,
+    /// The owner's current nonce of the token
+    pub nonce: U256,
+    /// The unix timestamp until which the permit is valid
+    pub deadline: U256,
+}
+
+impl Permit {
+    /// Creates a permit that never expires.
+    pub fn new<T: Into<U256>, N: Into<U256>>(
+        domain: EIP712Domain,
+        owner: Address,
+        spender: Address,
+        value: T,
+        nonce: N,
+    ) -> Self {
+        Self {
+            domain,
+            owner,
+            spender,
+            value: value.into(),
+            nonce: nonce.into(),
+            deadline: U256::MAX,
+        }
+    }
+
+    /// Sets the unix timestamp until which the permit is valid, inclusive.
+    #[must_use]
+    pub fn deadline<T: Into<U256>>(mut self, deadline: T) -> Self {
+        self.deadline = deadline.into();
+        self
+    }
+
+    /// Returns the calldata that submits the permit signed by `owner` to the token contract.
+    pub fn calldata(&self, signature: &Signature) -> Bytes {
+        let v = if signature.v >= 27 { signature.v } else { signature.v + 27 };
+        let mut data = id("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)").to_vec();
+        data.extend(encode(&[
+            Token::Address(self.owner),
+            Token::Address(self.spender),
+            Token::Uint(self.value),
+            Token::Uint(self.deadline),
+            Token::Uint(v.into()),
+            Token::Uint(signature.r),
+            Token::Uint(signature.s),
+        ]));
+        data.into()
+    }
+}
+
+impl Eip712 for Permit {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(self.domain.clone())
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(PERMIT_TYPEHASH)
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(PERMIT_TYPEHASH.to_vec()),
+            Token::Address(self.owner),
+            Token::Address(self.spender),
+            Token::Uint(self.value),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::eip712::TypedData;
+
+    fn permit() -> Permit {
+        let domain = EIP712Domain {
+            name: Some("USD Coin".to_string()),
+            version: Some("2".to_string()),
+            chain_id: Some(1.into()),
+            verifying_contract: Some(Address::repeat_byte(0xa0)),
+            salt: None,
+        };
+        Permit::new(domain, Address::repeat_byte(1), Address::repeat_byte(2), 100, 7).deadline(20)
+    }
+
+    #[test]
+    fn matches_typed_data() {
+        let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" },
+                ],
+                "Permit": [
+                    { "name": "owner", "type": "address" },
+                    { "name": "spender", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "nonce", "type": "uint256" },
+                    { "name": "deadline", "type": "uint256" },
+                ],
+            },
+            "primaryType": "Permit",
+            "domain": {
+                "name": "USD Coin",
+                "version": "2",
+                "chainId": 1,
+                "verifyingContract": "0xa0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0",
+            },
+            "message": {
+                "owner": "0x0101010101010101010101010101010101010101",
+                "spender": "0x0202020202020202020202020202020202020202",
+                "value": 100,
+                "nonce": 7,
+                "deadline": 20,
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(permit().encode_eip712().unwrap(), typed_data.encode_eip712().unwrap());
+    }
+
+    #[test]
+    fn encodes_calldata() {
+        let signature = Signature { r: 1.into(), s: 2.into(), v: 1 };
+        let calldata = permit().calldata(&signature);
+        assert_eq!(&calldata[..4], [0xd5, 0x05, 0xac, 0xcf]);
+        assert_eq!(calldata.len(), 4 + 7 * 32);
+        // v is normalized to 27 or 28
+        assert_eq!(calldata[4 + 5 * 32 - 1], 28);
+    }
+}
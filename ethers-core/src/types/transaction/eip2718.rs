@@ -1,11 +1,13 @@
 use super::{
     eip1559::{Eip1559RequestError, Eip1559TransactionRequest},
     eip2930::{AccessList, Eip2930RequestError, Eip2930TransactionRequest},
+    eip4844::{Eip4844RequestError, Eip4844TransactionRequest, BLOB_TX_TYPE},
     request::RequestError,
 };
 use crate::{
     types::{
-        Address, Bytes, NameOrAddress, Signature, Transaction, TransactionRequest, H256, U256, U64,
+        Address, Bytes, NameOrAddress, Signature, SignatureError, Transaction, TransactionRequest,
+        H256, U256, U64,
     },
     utils::keccak256,
 };
@@ -18,12 +20,28 @@ use super::optimism_deposited::{
     OptimismDepositedRequestError, OptimismDepositedTransactionRequest,
 };
 
+#[cfg(feature = "celo")]
+use super::celo::{
+    CeloRequestError, Cip42TransactionRequest, Cip64TransactionRequest, CIP42_TX_TYPE,
+    CIP64_TX_TYPE,
+};
+
+#[cfg(feature = "zksync")]
+use super::zksync::{ZkSyncRequestError, ZkSyncTransactionRequest, ZKSYNC_TX_TYPE};
+
 /// The TypedTransaction enum represents all Ethereum transaction types.
 ///
 /// Its variants correspond to specific allowed transactions:
 /// 1. Legacy (pre-EIP2718) [`TransactionRequest`]
 /// 2. EIP2930 (state access lists) [`Eip2930TransactionRequest`]
 /// 3. EIP1559 [`Eip1559TransactionRequest`]
+/// 4. EIP4844 (blob transactions) [`Eip4844TransactionRequest`]
+///
+/// With the `celo` feature, Celo's CIP-42 and CIP-64 transactions, which pay fees in an ERC-20 fee
+/// currency, are supported as well.
+///
+/// With the `zksync` feature, zkSync Era's EIP-712 transactions, which can be sponsored by a
+/// paymaster, are supported as well.
 ///
 /// To support Kovan and other non-London-compatbile networks, please enable
 /// the `legacy` crate feature. This will disable the `type` flag in the
@@ -42,10 +60,25 @@ pub enum TypedTransaction {
     // 0x02
     #[serde(rename = "0x02")]
     Eip1559(Eip1559TransactionRequest),
+    // 0x03
+    #[serde(rename = "0x03")]
+    Eip4844(Eip4844TransactionRequest),
     // 0x7E
     #[cfg(feature = "optimism")]
     #[serde(rename = "0x7E")]
     OptimismDeposited(OptimismDepositedTransactionRequest),
+    // 0x7C
+    #[cfg(feature = "celo")]
+    #[serde(rename = "0x7c")]
+    Cip42(Cip42TransactionRequest),
+    // 0x7B
+    #[cfg(feature = "celo")]
+    #[serde(rename = "0x7b")]
+    Cip64(Cip64TransactionRequest),
+    // 0x71
+    #[cfg(feature = "zksync")]
+    #[serde(rename = "0x71")]
+    ZkSync(ZkSyncTransactionRequest),
 }
 
 /// An error involving a typed transaction request.
@@ -60,10 +93,21 @@ pub enum TypedTransactionError {
     /// When decoding a signed Eip2930 transaction
     #[error(transparent)]
     Eip2930Error(#[from] Eip2930RequestError),
+    /// When decoding a signed Eip4844 transaction
+    #[error(transparent)]
+    Eip4844Error(#[from] Eip4844RequestError),
     /// When decoding a signed Optimism Deposited transaction
     #[cfg(feature = "optimism")]
     #[error(transparent)]
     OptimismDepositedError(#[from] OptimismDepositedRequestError),
+    /// When decoding a signed Celo CIP-42 or CIP-64 transaction
+    #[cfg(feature = "celo")]
+    #[error(transparent)]
+    CeloError(#[from] CeloRequestError),
+    /// When decoding a signed zkSync transaction
+    #[cfg(feature = "zksync")]
+    #[error(transparent)]
+    ZkSyncError(#[from] ZkSyncRequestError),
     /// Error decoding the transaction type from the transaction's RLP encoding
     #[error(transparent)]
     TypeDecodingError(#[from] rlp::DecoderError),
@@ -97,8 +141,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.from.as_ref(),
             Eip2930(inner) => inner.tx.from.as_ref(),
             Eip1559(inner) => inner.from.as_ref(),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.from.as_ref(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.from.as_ref(),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.from.as_ref(),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.from.as_ref(),
         }
     }
 
@@ -107,8 +157,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.from = Some(from),
             Eip2930(inner) => inner.tx.from = Some(from),
             Eip1559(inner) => inner.from = Some(from),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.from = Some(from),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.from = Some(from),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.from = Some(from),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.from = Some(from),
         };
         self
     }
@@ -118,8 +174,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.to.as_ref(),
             Eip2930(inner) => inner.tx.to.as_ref(),
             Eip1559(inner) => inner.to.as_ref(),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.to.as_ref(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.to.as_ref(),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.to.as_ref(),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.to.as_ref(),
         }
     }
 
@@ -133,8 +195,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.to = Some(to),
             Eip2930(inner) => inner.tx.to = Some(to),
             Eip1559(inner) => inner.to = Some(to),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.to = Some(to),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.to = Some(to),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.to = Some(to),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.to = Some(to),
         };
         self
     }
@@ -144,8 +212,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.nonce.as_ref(),
             Eip2930(inner) => inner.tx.nonce.as_ref(),
             Eip1559(inner) => inner.nonce.as_ref(),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.nonce.as_ref(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.nonce.as_ref(),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.nonce.as_ref(),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.nonce.as_ref(),
         }
     }
 
@@ -155,8 +229,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.nonce = Some(nonce),
             Eip2930(inner) => inner.tx.nonce = Some(nonce),
             Eip1559(inner) => inner.nonce = Some(nonce),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.nonce = Some(nonce),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.nonce = Some(nonce),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.nonce = Some(nonce),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.nonce = Some(nonce),
         };
         self
     }
@@ -166,8 +246,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.value.as_ref(),
             Eip2930(inner) => inner.tx.value.as_ref(),
             Eip1559(inner) => inner.value.as_ref(),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.value.as_ref(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.value.as_ref(),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.value.as_ref(),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.value.as_ref(),
         }
     }
 
@@ -177,8 +263,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.value = Some(value),
             Eip2930(inner) => inner.tx.value = Some(value),
             Eip1559(inner) => inner.value = Some(value),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.value = Some(value),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.value = Some(value),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.value = Some(value),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.value = Some(value),
         };
         self
     }
@@ -188,8 +280,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.gas.as_ref(),
             Eip2930(inner) => inner.tx.gas.as_ref(),
             Eip1559(inner) => inner.gas.as_ref(),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.gas.as_ref(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.gas.as_ref(),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.gas.as_ref(),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.gas.as_ref(),
         }
     }
 
@@ -198,8 +296,14 @@ impl TypedTransaction {
             Legacy(inner) => &mut inner.gas,
             Eip2930(inner) => &mut inner.tx.gas,
             Eip1559(inner) => &mut inner.gas,
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => &mut inner.gas,
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => &mut inner.tx.gas,
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => &mut inner.gas,
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => &mut inner.gas,
         }
     }
 
@@ -209,8 +313,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.gas = Some(gas),
             Eip2930(inner) => inner.tx.gas = Some(gas),
             Eip1559(inner) => inner.gas = Some(gas),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.gas = Some(gas),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.gas = Some(gas),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.gas = Some(gas),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.gas = Some(gas),
         };
         self
     }
@@ -227,8 +337,32 @@ impl TypedTransaction {
                     (max_fee, None) => max_fee,
                 }
             }
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => {
+                match (inner.max_fee_per_gas, inner.max_priority_fee_per_gas) {
+                    (Some(max_fee), Some(_)) => Some(max_fee),
+                    (None, prio_fee) => prio_fee,
+                    (max_fee, None) => max_fee,
+                }
+            }
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.gas_price,
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => {
+                match (inner.max_fee_per_gas, inner.max_priority_fee_per_gas) {
+                    (Some(max_fee), Some(_)) => Some(max_fee),
+                    (None, prio_fee) => prio_fee,
+                    (max_fee, None) => max_fee,
+                }
+            }
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => {
+                match (inner.max_fee_per_gas, inner.max_priority_fee_per_gas) {
+                    (Some(max_fee), Some(_)) => Some(max_fee),
+                    (None, prio_fee) => prio_fee,
+                    (max_fee, None) => max_fee,
+                }
+            }
         }
     }
 
@@ -241,8 +375,23 @@ impl TypedTransaction {
                 inner.max_fee_per_gas = Some(gas_price);
                 inner.max_priority_fee_per_gas = Some(gas_price);
             }
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => {
+                inner.max_fee_per_gas = Some(gas_price);
+                inner.max_priority_fee_per_gas = Some(gas_price);
+            }
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.gas_price = Some(gas_price),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => {
+                inner.max_fee_per_gas = Some(gas_price);
+                inner.max_priority_fee_per_gas = Some(gas_price);
+            }
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => {
+                inner.max_fee_per_gas = Some(gas_price);
+                inner.max_priority_fee_per_gas = Some(gas_price);
+            }
         };
         self
     }
@@ -252,8 +401,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.chain_id,
             Eip2930(inner) => inner.tx.chain_id,
             Eip1559(inner) => inner.chain_id,
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.chain_id,
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.chain_id,
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.chain_id,
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.chain_id,
         }
     }
 
@@ -263,8 +418,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.chain_id = Some(chain_id),
             Eip2930(inner) => inner.tx.chain_id = Some(chain_id),
             Eip1559(inner) => inner.chain_id = Some(chain_id),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.chain_id = Some(chain_id),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.chain_id = Some(chain_id),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.chain_id = Some(chain_id),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.chain_id = Some(chain_id),
         };
         self
     }
@@ -274,8 +435,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.data.as_ref(),
             Eip2930(inner) => inner.tx.data.as_ref(),
             Eip1559(inner) => inner.data.as_ref(),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.data.as_ref(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.data.as_ref(),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.data.as_ref(),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.data.as_ref(),
         }
     }
 
@@ -284,8 +451,14 @@ impl TypedTransaction {
             Legacy(_) => None,
             Eip2930(inner) => Some(&inner.access_list),
             Eip1559(inner) => Some(&inner.access_list),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => Some(&inner.access_list),
             #[cfg(feature = "optimism")]
             OptimismDeposited(_) => None,
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => Some(&inner.access_list),
+            #[cfg(feature = "zksync")]
+            ZkSync(_) => None,
         }
     }
 
@@ -294,8 +467,14 @@ impl TypedTransaction {
             Legacy(_) => {}
             Eip2930(inner) => inner.access_list = access_list,
             Eip1559(inner) => inner.access_list = access_list,
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.access_list = access_list,
             #[cfg(feature = "optimism")]
             OptimismDeposited(_) => {}
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.access_list = access_list,
+            #[cfg(feature = "zksync")]
+            ZkSync(_) => {}
         };
         self
     }
@@ -305,8 +484,14 @@ impl TypedTransaction {
             Legacy(inner) => inner.data = Some(data),
             Eip2930(inner) => inner.tx.data = Some(data),
             Eip1559(inner) => inner.data = Some(data),
+            Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => inner.data = Some(data),
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => inner.tx.data = Some(data),
+            #[cfg(feature = "celo")]
+            Cip42(Cip42TransactionRequest { tx: inner, .. }) |
+            Cip64(Cip64TransactionRequest { tx: inner, .. }) => inner.data = Some(data),
+            #[cfg(feature = "zksync")]
+            ZkSync(ZkSyncTransactionRequest { tx: inner, .. }) => inner.data = Some(data),
         };
         self
     }
@@ -325,15 +510,51 @@ impl TypedTransaction {
                 encoded.extend_from_slice(&[0x2]);
                 encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
             }
+            Eip4844(inner) => {
+                encoded.extend_from_slice(&[BLOB_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
+            }
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => {
                 encoded.extend_from_slice(&[0x7E]);
                 encoded.extend_from_slice(inner.rlp().as_ref());
             }
+            #[cfg(feature = "celo")]
+            Cip42(inner) => {
+                encoded.extend_from_slice(&[CIP42_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
+            }
+            #[cfg(feature = "celo")]
+            Cip64(inner) => {
+                encoded.extend_from_slice(&[CIP64_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
+            }
+            #[cfg(feature = "zksync")]
+            ZkSync(inner) => {
+                encoded.extend_from_slice(&[ZKSYNC_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
+            }
         };
         encoded.into()
     }
 
+    /// Produces the encoding of the signed transaction which is broadcast with
+    /// `eth_sendRawTransaction`.
+    ///
+    /// This is the same as [`TypedTransaction::rlp_signed`], except for EIP-4844 transactions with
+    /// a sidecar, whose blobs, commitments and proofs are wrapped around the signed
+    /// transaction.
+    pub fn rlp_network(&self, signature: &Signature) -> Bytes {
+        match self {
+            Eip4844(inner) => {
+                let mut encoded = vec![BLOB_TX_TYPE];
+                encoded.extend_from_slice(inner.rlp_network(signature).as_ref());
+                encoded.into()
+            }
+            _ => self.rlp_signed(signature),
+        }
+    }
+
     pub fn rlp(&self) -> Bytes {
         let mut encoded = vec![];
         match self {
@@ -348,29 +569,58 @@ impl TypedTransaction {
                 encoded.extend_from_slice(&[0x2]);
                 encoded.extend_from_slice(inner.rlp().as_ref());
             }
+            Eip4844(inner) => {
+                encoded.extend_from_slice(&[BLOB_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp().as_ref());
+            }
             #[cfg(feature = "optimism")]
             OptimismDeposited(inner) => {
                 encoded.extend_from_slice(&[0x7E]);
                 encoded.extend_from_slice(inner.rlp().as_ref());
             }
+            #[cfg(feature = "celo")]
+            Cip42(inner) => {
+                encoded.extend_from_slice(&[CIP42_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp().as_ref());
+            }
+            #[cfg(feature = "celo")]
+            Cip64(inner) => {
+                encoded.extend_from_slice(&[CIP64_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp().as_ref());
+            }
+            #[cfg(feature = "zksync")]
+            ZkSync(inner) => {
+                encoded.extend_from_slice(&[ZKSYNC_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp().as_ref());
+            }
         };
 
         encoded.into()
     }
 
     /// Hashes the transaction's data. Does not double-RLP encode
+    ///
+    /// zkSync transactions are signed as EIP-712 typed data, their sighash is the typed data hash.
     pub fn sighash(&self) -> H256 {
+        #[cfg(feature = "zksync")]
+        if let ZkSync(inner) = self {
+            return inner.sighash()
+        }
         let encoded = self.rlp();
         keccak256(encoded).into()
     }
 
-    /// Max cost of the transaction
+    /// Max cost of the transaction, including the blob gas of EIP-4844 transactions
     pub fn max_cost(&self) -> Option<U256> {
         let gas_limit = self.gas();
         let gas_price = self.gas_price();
-        match (gas_limit, gas_price) {
-            (Some(gas_limit), Some(gas_price)) => Some(gas_limit * gas_price),
-            _ => None,
+        let cost = match (gas_limit, gas_price) {
+            (Some(gas_limit), Some(gas_price)) => gas_limit * gas_price,
+            _ => return None,
+        };
+        match self {
+            Eip4844(inner) => Some(cost + inner.blob_gas() * inner.max_fee_per_blob_gas?),
+            _ => Some(cost),
         }
     }
 
@@ -404,15 +654,67 @@ impl TypedTransaction {
             let decoded_request = Eip1559TransactionRequest::decode_signed_rlp(&rest)?;
             return Ok((Self::Eip1559(decoded_request.0), decoded_request.1))
         }
+        if first == BLOB_TX_TYPE {
+            // EIP-4844 (0x03), either signed or in its network representation
+            let decoded_request = Eip4844TransactionRequest::decode_signed_rlp(&rest)?;
+            return Ok((Self::Eip4844(decoded_request.0), decoded_request.1))
+        }
         #[cfg(feature = "optimism")]
         if first == 0x7E {
             // Optimism Deposited (0x7E)
             let decoded_request = OptimismDepositedTransactionRequest::decode_signed_rlp(&rest)?;
             return Ok((Self::OptimismDeposited(decoded_request.0), decoded_request.1))
         }
+        #[cfg(feature = "celo")]
+        if first == CIP42_TX_TYPE {
+            // Celo CIP-42 (0x7c)
+            let decoded_request = Cip42TransactionRequest::decode_signed_rlp(&rest)?;
+            return Ok((Self::Cip42(decoded_request.0), decoded_request.1))
+        }
+        #[cfg(feature = "celo")]
+        if first == CIP64_TX_TYPE {
+            // Celo CIP-64 (0x7b)
+            let decoded_request = Cip64TransactionRequest::decode_signed_rlp(&rest)?;
+            return Ok((Self::Cip64(decoded_request.0), decoded_request.1))
+        }
+        #[cfg(feature = "zksync")]
+        if first == ZKSYNC_TX_TYPE {
+            // zkSync EIP-712 (0x71)
+            let decoded_request = ZkSyncTransactionRequest::decode_signed_rlp(&rest)?;
+            return Ok((Self::ZkSync(decoded_request.0), decoded_request.1))
+        }
 
         Err(rlp::DecoderError::Custom("invalid tx type").into())
     }
+
+    /// Decodes a signed transaction of any type from its raw bytes, e.g. as returned by
+    /// `eth_getRawTransactionByHash` or submitted to a relay, and recovers its sender.
+    ///
+    /// Typed transactions which are wrapped in an RLP string, as in block bodies, are unwrapped
+    /// first.
+    pub fn decode_signed_raw(
+        raw: impl AsRef<[u8]>,
+    ) -> Result<(Self, Signature), TypedTransactionError> {
+        let raw = raw.as_ref();
+        let rlp = rlp::Rlp::new(raw);
+        // legacy transactions are RLP lists and typed transactions start with their type, so a
+        // string prefix means that the transaction is wrapped
+        if matches!(raw.first(), Some(0x80..=0xbf)) {
+            return Self::decode_signed(&rlp::Rlp::new(rlp.data()?))
+        }
+        Self::decode_signed(&rlp)
+    }
+
+    /// Recovers the sender of the transaction from its signature.
+    ///
+    /// Optimism deposit transactions are not signed, their sender is the `from` field.
+    pub fn recover_from(&self, signature: &Signature) -> Result<Address, SignatureError> {
+        #[cfg(feature = "optimism")]
+        if let OptimismDeposited(inner) = self {
+            return inner.tx.from.ok_or(SignatureError::RecoveryError)
+        }
+        signature.recover(self.sighash())
+    }
 }
 
 /// Get a TypedTransaction directly from a rlp encoded byte stream
@@ -435,11 +737,30 @@ impl Decodable for TypedTransaction {
                 // EIP-1559 (0x02)
                 Ok(Self::Eip1559(Eip1559TransactionRequest::decode(&rest)?))
             }
+            Some(x) if x == U64::from(BLOB_TX_TYPE) => {
+                // EIP-4844 (0x03)
+                Ok(Self::Eip4844(Eip4844TransactionRequest::decode(&rest)?))
+            }
             #[cfg(feature = "optimism")]
             Some(x) if x == U64::from(0x7E) => {
                 // Optimism Deposited (0x7E)
                 Ok(Self::OptimismDeposited(OptimismDepositedTransactionRequest::decode(&rest)?))
             }
+            #[cfg(feature = "celo")]
+            Some(x) if x == U64::from(CIP42_TX_TYPE) => {
+                // Celo CIP-42 (0x7c)
+                Ok(Self::Cip42(Cip42TransactionRequest::decode(&rest)?))
+            }
+            #[cfg(feature = "celo")]
+            Some(x) if x == U64::from(CIP64_TX_TYPE) => {
+                // Celo CIP-64 (0x7b)
+                Ok(Self::Cip64(Cip64TransactionRequest::decode(&rest)?))
+            }
+            #[cfg(feature = "zksync")]
+            Some(x) if x == U64::from(ZKSYNC_TX_TYPE) => {
+                // zkSync EIP-712 (0x71)
+                Ok(Self::ZkSync(ZkSyncTransactionRequest::decode(&rest)?))
+            }
             _ => {
                 // Legacy (0x00)
                 // use the original rlp
@@ -467,6 +788,12 @@ impl From<Eip1559TransactionRequest> for TypedTransaction {
     }
 }
 
+impl From<Eip4844TransactionRequest> for TypedTransaction {
+    fn from(src: Eip4844TransactionRequest) -> TypedTransaction {
+        TypedTransaction::Eip4844(src)
+    }
+}
+
 #[cfg(feature = "optimism")]
 impl From<OptimismDepositedTransactionRequest> for TypedTransaction {
     fn from(src: OptimismDepositedTransactionRequest) -> TypedTransaction {
@@ -474,6 +801,27 @@ impl From<OptimismDepositedTransactionRequest> for TypedTransaction {
     }
 }
 
+#[cfg(feature = "celo")]
+impl From<Cip42TransactionRequest> for TypedTransaction {
+    fn from(src: Cip42TransactionRequest) -> TypedTransaction {
+        TypedTransaction::Cip42(src)
+    }
+}
+
+#[cfg(feature = "celo")]
+impl From<Cip64TransactionRequest> for TypedTransaction {
+    fn from(src: Cip64TransactionRequest) -> TypedTransaction {
+        TypedTransaction::Cip64(src)
+    }
+}
+
+#[cfg(feature = "zksync")]
+impl From<ZkSyncTransactionRequest> for TypedTransaction {
+    fn from(src: ZkSyncTransactionRequest) -> TypedTransaction {
+        TypedTransaction::ZkSync(src)
+    }
+}
+
 impl From<&Transaction> for TypedTransaction {
     fn from(tx: &Transaction) -> TypedTransaction {
         match tx.transaction_type {
@@ -487,12 +835,35 @@ impl From<&Transaction> for TypedTransaction {
                 let request: Eip1559TransactionRequest = tx.into();
                 request.into()
             }
+            // EIP-4844 (0x03)
+            Some(x) if x == U64::from(BLOB_TX_TYPE) => {
+                let request: Eip4844TransactionRequest = tx.into();
+                request.into()
+            }
             #[cfg(feature = "optimism")]
             // Optimism Deposited (0x7E)
             Some(x) if x == U64::from(0x7E) => {
                 let request: OptimismDepositedTransactionRequest = tx.into();
                 request.into()
             }
+            #[cfg(feature = "celo")]
+            // Celo CIP-42 (0x7c)
+            Some(x) if x == U64::from(CIP42_TX_TYPE) => {
+                let request: Cip42TransactionRequest = tx.into();
+                request.into()
+            }
+            #[cfg(feature = "celo")]
+            // Celo CIP-64 (0x7b)
+            Some(x) if x == U64::from(CIP64_TX_TYPE) => {
+                let request: Cip64TransactionRequest = tx.into();
+                request.into()
+            }
+            #[cfg(feature = "zksync")]
+            // zkSync EIP-712 (0x71)
+            Some(x) if x == U64::from(ZKSYNC_TX_TYPE) => {
+                let request: ZkSyncTransactionRequest = tx.into();
+                request.into()
+            }
             // Legacy (0x00)
             _ => {
                 let request: TransactionRequest = tx.into();
@@ -521,6 +892,12 @@ impl TypedTransaction {
             _ => None,
         }
     }
+    pub fn as_eip4844_ref(&self) -> Option<&Eip4844TransactionRequest> {
+        match self {
+            Eip4844(tx) => Some(tx),
+            _ => None,
+        }
+    }
     #[cfg(feature = "optimism")]
     pub fn as_optimism_deposited_ref(&self) -> Option<&OptimismDepositedTransactionRequest> {
         match self {
@@ -528,6 +905,13 @@ impl TypedTransaction {
             _ => None,
         }
     }
+    #[cfg(feature = "zksync")]
+    pub fn as_zksync_ref(&self) -> Option<&ZkSyncTransactionRequest> {
+        match self {
+            ZkSync(tx) => Some(tx),
+            _ => None,
+        }
+    }
 
     pub fn as_legacy_mut(&mut self) -> Option<&mut TransactionRequest> {
         match self {
@@ -547,6 +931,12 @@ impl TypedTransaction {
             _ => None,
         }
     }
+    pub fn as_eip4844_mut(&mut self) -> Option<&mut Eip4844TransactionRequest> {
+        match self {
+            Eip4844(tx) => Some(tx),
+            _ => None,
+        }
+    }
     #[cfg(feature = "optimism")]
     pub fn as_optimism_deposited_mut(
         &mut self,
@@ -556,12 +946,52 @@ impl TypedTransaction {
             _ => None,
         }
     }
+    #[cfg(feature = "zksync")]
+    pub fn as_zksync_mut(&mut self) -> Option<&mut ZkSyncTransactionRequest> {
+        match self {
+            ZkSync(tx) => Some(tx),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "celo")]
+impl TypedTransaction {
+    /// Returns the currency the transaction's fees are paid in, `None` for the native currency.
+    pub fn fee_currency(&self) -> Option<&Address> {
+        match self {
+            Legacy(inner) => inner.fee_currency.as_ref(),
+            Cip42(inner) => inner.fee_currency.as_ref(),
+            Cip64(inner) => inner.fee_currency.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the currency the transaction's fees are paid in.
+    ///
+    /// EIP-1559 transactions are turned into CIP-64 transactions, EIP-2930 transactions can not pay
+    /// fees in another currency and are left as is.
+    pub fn set_fee_currency(&mut self, fee_currency: Address) -> &mut Self {
+        match self {
+            Legacy(inner) => inner.fee_currency = Some(fee_currency),
+            Eip1559(inner) => {
+                *self = Cip64(Cip64TransactionRequest::new(inner.clone(), Some(fee_currency)))
+            }
+            Cip42(inner) => inner.fee_currency = Some(fee_currency),
+            Cip64(inner) => inner.fee_currency = Some(fee_currency),
+            _ => {}
+        };
+        self
+    }
 }
 
 impl TypedTransaction {
     fn into_eip1559(self) -> Eip1559TransactionRequest {
         match self {
             Eip1559(tx) => tx,
+            Eip4844(tx) => tx.tx,
+            #[cfg(feature = "zksync")]
+            ZkSync(tx) => tx.tx,
             _ => Eip1559TransactionRequest {
                 from: self.from().copied(),
                 to: self.to().cloned(),
@@ -607,8 +1037,20 @@ impl TypedTransaction {
                 #[cfg_attr(docsrs, doc(cfg(feature = "celo")))]
                 gateway_fee: None,
             },
+            Eip4844(tx) => tx.tx.into(),
             #[cfg(feature = "optimism")]
             OptimismDeposited(tx) => tx.tx,
+            #[cfg(feature = "celo")]
+            Cip42(tx) => TransactionRequest {
+                fee_currency: tx.fee_currency,
+                gateway_fee_recipient: tx.gateway_fee_recipient,
+                gateway_fee: tx.gateway_fee,
+                ..tx.tx.into()
+            },
+            #[cfg(feature = "celo")]
+            Cip64(tx) => TransactionRequest { fee_currency: tx.fee_currency, ..tx.tx.into() },
+            #[cfg(feature = "zksync")]
+            ZkSync(tx) => tx.tx.into(),
         }
     }
 }
@@ -648,8 +1090,15 @@ impl TypedTransaction {
                 },
                 access_list,
             },
+            Eip4844(_) => Eip2930TransactionRequest { tx: self.into_legacy(), access_list },
             #[cfg(feature = "optimism")]
             OptimismDeposited(tx) => Eip2930TransactionRequest { tx: tx.tx, access_list },
+            #[cfg(feature = "celo")]
+            Cip42(_) | Cip64(_) => {
+                Eip2930TransactionRequest { tx: self.into_legacy(), access_list }
+            }
+            #[cfg(feature = "zksync")]
+            ZkSync(_) => Eip2930TransactionRequest { tx: self.into_legacy(), access_list },
         }
     }
 }
@@ -795,6 +1244,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_signed_raw() {
+        let raw = hex::decode("02f899018085602b94278b85b2f7a17de88302cf5c940aa7420c43b8c1a7b165d216948870c8ecfe1ee18802c68af0bb140000a46ecd23060000000000000000000000000000000000000000000000000000000000000002c080a0c5f35bf1cc6ab13053e33b1af7400c267be17218aeadcdb4ae3eefd4795967e8a04f6871044dd6368aea8deecd1c29f55b5531020f5506502e3f79ad457051bc4a").unwrap();
+        let (tx, signature) = TypedTransaction::decode_signed_raw(&raw).unwrap();
+        assert_eq!(tx.recover_from(&signature).ok().as_ref(), tx.from());
+        assert_eq!(
+            tx.hash(&signature),
+            H256::from_str("0x206e4c71335333f8658e995cc0c4ee54395d239acb08587ab8e5409bfdd94a6f")
+                .unwrap()
+        );
+
+        // as found in block bodies
+        let wrapped = rlp::encode(&raw);
+        assert_eq!(TypedTransaction::decode_signed_raw(wrapped).unwrap(), (tx, signature));
+
+        assert!(TypedTransaction::decode_signed_raw([]).is_err());
+    }
+
     #[cfg(not(feature = "celo"))]
     #[test]
     fn test_eip155_decode() {
@@ -0,0 +1,282 @@
+//! EIP-3009 transfers with authorization, as implemented by USDC and other stablecoins
+use crate::{
+    abi::{encode, Token},
+    types::{
+        transaction::eip712::{EIP712Domain, Eip712, Eip712Error},
+        Address, Bytes, Signature, H256, U256,
+    },
+    utils::{id, keccak256},
+};
+use std::ops::Deref;
+
+/// The type hash of `TransferWithAuthorization(address from,address to,uint256 value,uint256
+/// validAfter,uint256 validBefore,bytes32 nonce)`
+pub const TRANSFER_WITH_AUTHORIZATION_TYPEHASH: [u8; 32] = [
+    124, 124, 108, 219, 103, 161, 135, 67, 244, 158, 198, 250, 155, 53, 245, 13, 82, 237, 5, 203,
+    237, 76, 197, 146, 225, 59, 68, 80, 28, 26, 34, 103,
+];
+
+/// The type hash of `ReceiveWithAuthorization(address from,address to,uint256 value,uint256
+/// validAfter,uint256 validBefore,bytes32 nonce)`
+pub const RECEIVE_WITH_AUTHORIZATION_TYPEHASH: [u8; 32] = [
+    208, 153, 204, 152, 239, 113, 16, 122, 97, 108, 79, 15, 148, 31, 4, 195, 34, 216, 226, 84, 254,
+    38, 179, 198, 102, 141, 184, 122, 174, 65, 61, 232,
+];
+
+/// The parameters of an EIP-3009 authorization, allowing anyone holding the signature of `from` to
+/// move `value` tokens to `to` between `valid_after` and `valid_before`.
+///
+/// Nonces are random 32 byte values instead of sequential counters, so that authorizations can be
+/// created and submitted in any order. Each nonce can only be used once per `from` account.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_core::types::{
+///     transaction::{eip3009::TransferAuthorization, eip712::EIP712Domain},
+///     Address,
+/// };
+///
+/// let usdc = EIP712Domain {
+///     name: Some("USD Coin".to_string()),
+///     version: Some("2".to_string()),
+///     chain_id: Some(1.into()),
+///     verifying_contract: Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".parse().unwrap()),
+///     salt: None,
+/// };
+/// let (from, to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+/// let transfer = TransferAuthorization::new(usdc, from, to, 1_000_000u64)
+///     .valid_between(0u64, 1_700_000_000u64)
+///     .transfer();
+/// // `transfer` implements `Eip712` and can be signed with `Signer::sign_typed_data`
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferAuthorization {
+    /// The domain of the token contract
+    pub domain: EIP712Domain,
+    /// The account the tokens are moved from, which signs the authorization
+    pub from: Address,
+    /// The recipient of the tokens
+    pub to: Address,
+    /// The amount of tokens, in the token's smallest unit
+    pub value: U256,
+    /// The unix timestamp after which the authorization is valid
+    pub valid_after: U256,
+    /// The unix timestamp before which the authorization is valid
+    pub valid_before: U256,
+    /// The unique nonce of the authorization
+    pub nonce: H256,
+}
+
+impl TransferAuthorization {
+    /// Creates an authorization with a random nonce that is valid immediately and never expires.
+    pub fn new<T: Into<U256>>(domain: EIP712Domain, from: Address, to: Address, value: T) -> Self {
+        Self {
+            domain,
+            from,
+            to,
+            value: value.into(),
+            valid_after: U256::zero(),
+            valid_before: U256::MAX,
+            nonce: H256(rand::random()),
+        }
+    }
+
+    /// Sets the unix timestamps after and before which the authorization is valid, both
+    /// exclusive.
+    #[must_use]
+    pub fn valid_between<A: Into<U256>, B: Into<U256>>(mut self, after: A, before: B) -> Self {
+        self.valid_after = after.into();
+        self.valid_before = before.into();
+        self
+    }
+
+    /// Sets the nonce of the authorization.
+    #[must_use]
+    pub fn nonce<T: Into<H256>>(mut self, nonce: T) -> Self {
+        self.nonce = nonce.into();
+        self
+    }
+
+    /// Returns the payload for `transferWithAuthorization`, which can be submitted by anyone.
+    pub fn transfer(self) -> TransferWithAuthorization {
+        TransferWithAuthorization(self)
+    }
+
+    /// Returns the payload for `receiveWithAuthorization`, which can only be submitted by the
+    /// recipient. This prevents front-running the transfer when it is part of a larger call.
+    pub fn receive(self) -> ReceiveWithAuthorization {
+        ReceiveWithAuthorization(self)
+    }
+
+    fn struct_hash(&self, type_hash: [u8; 32]) -> [u8; 32] {
+        keccak256(encode(&[
+            Token::FixedBytes(type_hash.to_vec()),
+            Token::Address(self.from),
+            Token::Address(self.to),
+            Token::Uint(self.value),
+            Token::Uint(self.valid_after),
+            Token::Uint(self.valid_before),
+            Token::FixedBytes(self.nonce.as_bytes().to_vec()),
+        ]))
+    }
+
+    fn calldata(&self, signature: &str, sig: &Signature) -> Bytes {
+        let v = if sig.v >= 27 { sig.v } else { sig.v + 27 };
+        let mut data = id(signature).to_vec();
+        data.extend(encode(&[
+            Token::Address(self.from),
+            Token::Address(self.to),
+            Token::Uint(self.value),
+            Token::Uint(self.valid_after),
+            Token::Uint(self.valid_before),
+            Token::FixedBytes(self.nonce.as_bytes().to_vec()),
+            Token::Uint(v.into()),
+            Token::Uint(sig.r),
+            Token::Uint(sig.s),
+        ]));
+        data.into()
+    }
+}
+
+macro_rules! impl_authorization {
+    ($name:ident, $type_hash:ident, $function:literal) => {
+        impl $name {
+            /// Returns the calldata that submits the authorization signed by `from` to the token
+            /// contract.
+            pub fn calldata(&self, signature: &Signature) -> Bytes {
+                self.0.calldata($function, signature)
+            }
+
+            /// Returns the authorization parameters.
+            pub fn into_inner(self) -> TransferAuthorization {
+                self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = TransferAuthorization;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl Eip712 for $name {
+            type Error = Eip712Error;
+
+            fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+                Ok(self.0.domain.clone())
+            }
+
+            fn type_hash() -> Result<[u8; 32], Self::Error> {
+                Ok($type_hash)
+            }
+
+            fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+                Ok(self.0.struct_hash($type_hash))
+            }
+        }
+    };
+}
+
+/// The EIP-712 payload of a `transferWithAuthorization` call
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferWithAuthorization(TransferAuthorization);
+
+impl_authorization!(
+    TransferWithAuthorization,
+    TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
+    "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)"
+);
+
+/// The EIP-712 payload of a `receiveWithAuthorization` call
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReceiveWithAuthorization(TransferAuthorization);
+
+impl_authorization!(
+    ReceiveWithAuthorization,
+    RECEIVE_WITH_AUTHORIZATION_TYPEHASH,
+    "receiveWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::eip712::TypedData;
+
+    fn authorization() -> TransferAuthorization {
+        let domain = EIP712Domain {
+            name: Some("USD Coin".to_string()),
+            version: Some("2".to_string()),
+            chain_id: Some(1.into()),
+            verifying_contract: Some(Address::repeat_byte(0xa0)),
+            salt: None,
+        };
+        TransferAuthorization::new(domain, Address::repeat_byte(1), Address::repeat_byte(2), 100)
+            .valid_between(10, 20)
+            .nonce(H256::repeat_byte(3))
+    }
+
+    fn typed_data(primary_type: &str) -> TypedData {
+        serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" },
+                ],
+                primary_type: [
+                    { "name": "from", "type": "address" },
+                    { "name": "to", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "validAfter", "type": "uint256" },
+                    { "name": "validBefore", "type": "uint256" },
+                    { "name": "nonce", "type": "bytes32" },
+                ],
+            },
+            "primaryType": primary_type,
+            "domain": {
+                "name": "USD Coin",
+                "version": "2",
+                "chainId": 1,
+                "verifyingContract": "0xa0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0",
+            },
+            "message": {
+                "from": "0x0101010101010101010101010101010101010101",
+                "to": "0x0202020202020202020202020202020202020202",
+                "value": 100,
+                "validAfter": 10,
+                "validBefore": 20,
+                "nonce": "0x0303030303030303030303030303030303030303030303030303030303030303",
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_typed_data() {
+        assert_eq!(
+            authorization().transfer().encode_eip712().unwrap(),
+            typed_data("TransferWithAuthorization").encode_eip712().unwrap()
+        );
+        assert_eq!(
+            authorization().receive().encode_eip712().unwrap(),
+            typed_data("ReceiveWithAuthorization").encode_eip712().unwrap()
+        );
+    }
+
+    #[test]
+    fn encodes_calldata() {
+        let signature = Signature { r: 1.into(), s: 2.into(), v: 0 };
+        let calldata = authorization().transfer().calldata(&signature);
+        assert_eq!(&calldata[..4], [0xe3, 0xee, 0x16, 0x0e]);
+        assert_eq!(calldata.len(), 4 + 9 * 32);
+        // v is normalized to 27 or 28
+        assert_eq!(calldata[4 + 7 * 32 - 1], 27);
+
+        let calldata = authorization().receive().calldata(&signature);
+        assert_eq!(&calldata[..4], [0xef, 0x55, 0xbe, 0xc6]);
+    }
+}
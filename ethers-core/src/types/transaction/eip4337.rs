@@ -0,0 +1,307 @@
+//! ERC-4337 user operations
+use crate::{
+    abi::{encode, Token},
+    types::{Address, Bytes, H160, H256, U256},
+    utils::keccak256,
+};
+use serde::{Deserialize, Serialize};
+
+/// The v0.6 `EntryPoint` contract, deployed at the same address on all chains.
+pub const ENTRY_POINT_V06: Address = H160([
+    0x5f, 0xf1, 0x37, 0xd4, 0xb0, 0xfd, 0xcd, 0x49, 0xdc, 0xa3, 0x0c, 0x7c, 0xf5, 0x7e, 0x57, 0x8a,
+    0x02, 0x6d, 0x27, 0x89,
+]);
+
+/// The v0.7 `EntryPoint` contract, deployed at the same address on all chains.
+pub const ENTRY_POINT_V07: Address = H160([
+    0x00, 0x00, 0x00, 0x00, 0x71, 0x72, 0x7d, 0xe2, 0x2e, 0x5e, 0x9d, 0x8b, 0xaf, 0x0e, 0xda, 0xc6,
+    0xf3, 0x7d, 0xa0, 0x32,
+]);
+
+/// An ERC-4337 user operation, in the layout of the `EntryPoint` version it is sent to.
+///
+/// Serializes to the JSON-RPC representation of the bundler API.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UserOperation {
+    /// A user operation for the v0.6 `EntryPoint`
+    V06(UserOperationV06),
+    /// A user operation for the v0.7 `EntryPoint`
+    V07(UserOperationV07),
+}
+
+impl UserOperation {
+    /// Returns the account sending the operation.
+    pub fn sender(&self) -> Address {
+        match self {
+            Self::V06(op) => op.sender,
+            Self::V07(op) => op.sender,
+        }
+    }
+
+    /// Returns the signature of the operation.
+    pub fn signature(&self) -> &Bytes {
+        match self {
+            Self::V06(op) => &op.signature,
+            Self::V07(op) => &op.signature,
+        }
+    }
+
+    /// Sets the signature of the operation.
+    pub fn set_signature(&mut self, signature: impl Into<Bytes>) {
+        match self {
+            Self::V06(op) => op.signature = signature.into(),
+            Self::V07(op) => op.signature = signature.into(),
+        }
+    }
+
+    /// Returns the hash of the operation as computed by `EntryPoint.getUserOpHash`, which is
+    /// what accounts sign.
+    pub fn user_op_hash(&self, entry_point: Address, chain_id: u64) -> H256 {
+        let packed_hash = match self {
+            Self::V06(op) => op.packed_hash(),
+            Self::V07(op) => op.packed_hash(),
+        };
+        keccak256(encode(&[
+            Token::FixedBytes(packed_hash.to_vec()),
+            Token::Address(entry_point),
+            Token::Uint(chain_id.into()),
+        ]))
+        .into()
+    }
+}
+
+impl From<UserOperationV06> for UserOperation {
+    fn from(op: UserOperationV06) -> Self {
+        Self::V06(op)
+    }
+}
+
+impl From<UserOperationV07> for UserOperation {
+    fn from(op: UserOperationV07) -> Self {
+        Self::V07(op)
+    }
+}
+
+/// A user operation in the layout of the v0.6 `EntryPoint`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationV06 {
+    /// The account sending the operation
+    pub sender: Address,
+    /// The anti-replay nonce of the account
+    pub nonce: U256,
+    /// The factory address and calldata deploying the account, or empty if it is deployed
+    pub init_code: Bytes,
+    /// The calldata of the account's execution call
+    pub call_data: Bytes,
+    /// The gas limit of the execution call
+    pub call_gas_limit: U256,
+    /// The gas limit of the verification step
+    pub verification_gas_limit: U256,
+    /// The gas paid for the bundler's overhead
+    pub pre_verification_gas: U256,
+    /// The maximum fee per gas, as in EIP-1559
+    pub max_fee_per_gas: U256,
+    /// The maximum priority fee per gas, as in EIP-1559
+    pub max_priority_fee_per_gas: U256,
+    /// The paymaster address and its data, or empty if the account pays
+    pub paymaster_and_data: Bytes,
+    /// The signature validated by the account
+    pub signature: Bytes,
+}
+
+impl UserOperationV06 {
+    fn packed_hash(&self) -> [u8; 32] {
+        keccak256(encode(&[
+            Token::Address(self.sender),
+            Token::Uint(self.nonce),
+            Token::FixedBytes(keccak256(&self.init_code).to_vec()),
+            Token::FixedBytes(keccak256(&self.call_data).to_vec()),
+            Token::Uint(self.call_gas_limit),
+            Token::Uint(self.verification_gas_limit),
+            Token::Uint(self.pre_verification_gas),
+            Token::Uint(self.max_fee_per_gas),
+            Token::Uint(self.max_priority_fee_per_gas),
+            Token::FixedBytes(keccak256(&self.paymaster_and_data).to_vec()),
+        ]))
+    }
+}
+
+/// A user operation in the layout of the v0.7 `EntryPoint`.
+///
+/// This is the unpacked form used by bundlers, which the `EntryPoint` receives as a
+/// `PackedUserOperation`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationV07 {
+    /// The account sending the operation
+    pub sender: Address,
+    /// The anti-replay nonce of the account
+    pub nonce: U256,
+    /// The factory deploying the account, or `None` if it is deployed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory: Option<Address>,
+    /// The calldata of the factory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory_data: Option<Bytes>,
+    /// The calldata of the account's execution call
+    pub call_data: Bytes,
+    /// The gas limit of the execution call
+    pub call_gas_limit: U256,
+    /// The gas limit of the verification step
+    pub verification_gas_limit: U256,
+    /// The gas paid for the bundler's overhead
+    pub pre_verification_gas: U256,
+    /// The maximum fee per gas, as in EIP-1559
+    pub max_fee_per_gas: U256,
+    /// The maximum priority fee per gas, as in EIP-1559
+    pub max_priority_fee_per_gas: U256,
+    /// The paymaster, or `None` if the account pays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster: Option<Address>,
+    /// The gas limit of the paymaster's validation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster_verification_gas_limit: Option<U256>,
+    /// The gas limit of the paymaster's post-operation call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster_post_op_gas_limit: Option<U256>,
+    /// The data of the paymaster
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster_data: Option<Bytes>,
+    /// The signature validated by the account
+    pub signature: Bytes,
+}
+
+impl UserOperationV07 {
+    /// Returns the packed `initCode`, the factory address followed by its calldata.
+    pub fn init_code(&self) -> Bytes {
+        match self.factory {
+            Some(factory) => {
+                let data = self.factory_data.as_deref().unwrap_or_default();
+                [factory.as_bytes(), data].concat().into()
+            }
+            None => Bytes::default(),
+        }
+    }
+
+    /// Returns the packed `paymasterAndData`, the paymaster address followed by its 16 byte gas
+    /// limits and its data.
+    pub fn paymaster_and_data(&self) -> Bytes {
+        match self.paymaster {
+            Some(paymaster) => {
+                let verification_gas = self.paymaster_verification_gas_limit.unwrap_or_default();
+                let post_op_gas = self.paymaster_post_op_gas_limit.unwrap_or_default();
+                let data = self.paymaster_data.as_deref().unwrap_or_default();
+                [
+                    paymaster.as_bytes(),
+                    &verification_gas.low_u128().to_be_bytes(),
+                    &post_op_gas.low_u128().to_be_bytes(),
+                    data,
+                ]
+                .concat()
+                .into()
+            }
+            None => Bytes::default(),
+        }
+    }
+
+    /// Returns the packed `accountGasLimits`, the verification gas limit in the upper and the
+    /// call gas limit in the lower 16 bytes.
+    pub fn account_gas_limits(&self) -> H256 {
+        pack_u128s(self.verification_gas_limit, self.call_gas_limit)
+    }
+
+    /// Returns the packed `gasFees`, the max priority fee in the upper and the max fee in the
+    /// lower 16 bytes.
+    pub fn gas_fees(&self) -> H256 {
+        pack_u128s(self.max_priority_fee_per_gas, self.max_fee_per_gas)
+    }
+
+    fn packed_hash(&self) -> [u8; 32] {
+        keccak256(encode(&[
+            Token::Address(self.sender),
+            Token::Uint(self.nonce),
+            Token::FixedBytes(keccak256(self.init_code()).to_vec()),
+            Token::FixedBytes(keccak256(&self.call_data).to_vec()),
+            Token::FixedBytes(self.account_gas_limits().as_bytes().to_vec()),
+            Token::Uint(self.pre_verification_gas),
+            Token::FixedBytes(self.gas_fees().as_bytes().to_vec()),
+            Token::FixedBytes(keccak256(self.paymaster_and_data()).to_vec()),
+        ]))
+    }
+}
+
+fn pack_u128s(high: U256, low: U256) -> H256 {
+    let mut packed = [0u8; 32];
+    packed[..16].copy_from_slice(&high.low_u128().to_be_bytes());
+    packed[16..].copy_from_slice(&low.low_u128().to_be_bytes());
+    packed.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v06() -> UserOperationV06 {
+        UserOperationV06 {
+            sender: "0x1306b01bc3e4ad202612d3843387e94737673f53".parse().unwrap(),
+            nonce: 7.into(),
+            call_data: vec![0xb6, 0x1d, 0x27, 0xf6].into(),
+            call_gas_limit: 100_000.into(),
+            verification_gas_limit: 200_000.into(),
+            pre_verification_gas: 50_000.into(),
+            max_fee_per_gas: 3_000_000_000u64.into(),
+            max_priority_fee_per_gas: 1_000_000_000u64.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn user_op_hash_v06() {
+        let op = UserOperation::from(v06());
+        assert_eq!(
+            op.user_op_hash(ENTRY_POINT_V06, 1),
+            "0x2970f9d30aeb8fca89c5d3789395956f4f30f8896df740ff8d281f5bb543f794".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn user_op_hash_v07() {
+        let v06 = v06();
+        let op = UserOperationV07 {
+            sender: v06.sender,
+            nonce: v06.nonce,
+            factory: Some("0x9406cc6185a346906296840746125a0e44976454".parse().unwrap()),
+            factory_data: Some(vec![0x5f, 0xbf, 0xb9, 0xcf].into()),
+            call_data: v06.call_data,
+            call_gas_limit: v06.call_gas_limit,
+            verification_gas_limit: v06.verification_gas_limit,
+            pre_verification_gas: v06.pre_verification_gas,
+            max_fee_per_gas: v06.max_fee_per_gas,
+            max_priority_fee_per_gas: v06.max_priority_fee_per_gas,
+            paymaster: Some("0x0000000000325602a77416a16136fdafd04b299f".parse().unwrap()),
+            paymaster_verification_gas_limit: Some(60_000.into()),
+            paymaster_post_op_gas_limit: Some(30_000.into()),
+            paymaster_data: Some(vec![0xab, 0xcd].into()),
+            signature: Bytes::default(),
+        };
+        assert_eq!(op.paymaster_and_data().len(), 20 + 16 + 16 + 2);
+
+        let op = UserOperation::from(op);
+        assert_eq!(
+            op.user_op_hash(ENTRY_POINT_V07, 11155111),
+            "0x0925a3af64798876e8285a2c166c19a920af7c46110db32dbfa5b701dc3233e3".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_versions() {
+        let v06 = serde_json::to_string(&UserOperation::from(v06())).unwrap();
+        assert!(matches!(serde_json::from_str(&v06).unwrap(), UserOperation::V06(_)));
+
+        let v07 = serde_json::to_string(&UserOperation::from(UserOperationV07::default())).unwrap();
+        assert!(!v07.contains("initCode"));
+        assert!(matches!(serde_json::from_str(&v07).unwrap(), UserOperation::V07(_)));
+    }
+}
@@ -0,0 +1,538 @@
+//! EIP-4844 blob transactions, which carry blobs of data that are only available on the consensus
+//! layer for a limited time, see the [EIP](https://eips.ethereum.org/EIPS/eip-4844).
+//!
+//! The blobs are not part of the signed transaction, which only commits to their versioned
+//! hashes. They are sent along with it in a [`BlobTransactionSidecar`], in the network
+//! representation of the transaction accepted by `eth_sendRawTransaction`.
+//!
+//! With the `kzg` feature, sidecars can be computed from their blobs and verified with
+//! [c-kzg](https://github.com/ethereum/c-kzg-4844).
+
+use super::{eip1559::Eip1559TransactionRequest, eip2718::TypedTransaction, normalize_v};
+use crate::types::{Bytes, NameOrAddress, Signature, SignatureError, Transaction, H256, U256, U64};
+use rlp::{Decodable, DecoderError, RlpStream};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+#[cfg(feature = "kzg")]
+pub use c_kzg::KzgSettings;
+
+/// The EIP-2718 type of blob transactions
+pub const BLOB_TX_TYPE: u8 = 0x03;
+
+/// The number of field elements of a blob
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+
+/// The size of a blob in bytes
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * 32;
+
+/// The size of a KZG commitment or proof in bytes
+pub const BYTES_PER_COMMITMENT: usize = 48;
+
+/// The number of data bytes that fit into a field element, whose first byte must be zero to stay
+/// below the modulus of the BLS12-381 curve
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = 31;
+
+/// The blob gas used by each blob of a transaction
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+
+/// The maximum number of blobs of a block, and thus of a transaction
+pub const MAX_BLOBS_PER_BLOCK: usize = 6;
+
+/// The version byte of versioned hashes of KZG commitments
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// Blob transactions have 11 fields
+const NUM_TX_FIELDS: usize = 11;
+
+/// An error involving an EIP-4844 transaction request or its sidecar.
+#[derive(Debug, Error)]
+pub enum Eip4844RequestError {
+    /// When decoding a transaction request from RLP
+    #[error(transparent)]
+    DecodingError(#[from] rlp::DecoderError),
+    /// When recovering the address from a signature
+    #[error(transparent)]
+    RecoveryError(#[from] SignatureError),
+    /// A blob is not [`BYTES_PER_BLOB`] long
+    #[error("invalid blob length {0}, expected {BYTES_PER_BLOB}")]
+    InvalidBlobLength(usize),
+    /// A commitment or proof is not [`BYTES_PER_COMMITMENT`] long
+    #[error("invalid commitment or proof length {0}, expected {BYTES_PER_COMMITMENT}")]
+    InvalidCommitmentLength(usize),
+    /// The sidecar does not have a commitment and a proof for every blob
+    #[error("sidecar has {blobs} blobs, {commitments} commitments and {proofs} proofs")]
+    SidecarLengthMismatch {
+        /// The number of blobs
+        blobs: usize,
+        /// The number of commitments
+        commitments: usize,
+        /// The number of proofs
+        proofs: usize,
+    },
+    /// The transaction has no blobs or more than [`MAX_BLOBS_PER_BLOCK`]
+    #[error("invalid number of blobs {0}, expected 1 to {MAX_BLOBS_PER_BLOCK}")]
+    InvalidBlobCount(usize),
+    /// The versioned hashes of the transaction do not match the commitments of its sidecar
+    #[error("versioned hashes do not match the sidecar's commitments")]
+    VersionedHashMismatch,
+    /// Blob transactions can not create contracts
+    #[error("blob transactions require a recipient address")]
+    MissingRecipient,
+    /// When computing or verifying a commitment or proof
+    #[cfg(feature = "kzg")]
+    #[error("kzg error: {0}")]
+    Kzg(String),
+    /// The proofs of the sidecar are not valid for its blobs and commitments
+    #[cfg(feature = "kzg")]
+    #[error("invalid kzg proof")]
+    InvalidProof,
+}
+
+/// Returns the versioned hash of a KZG commitment, `0x01 || sha256(commitment)[1..]`.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash.into()
+}
+
+/// Packs `data` into as few blobs as possible, 31 bytes per field element.
+///
+/// The last blob is padded with zeros, so applications whose data may end with zeros have to
+/// encode its length themselves.
+pub fn blobs_from_data(data: &[u8]) -> Vec<Bytes> {
+    data.chunks(FIELD_ELEMENTS_PER_BLOB * USABLE_BYTES_PER_FIELD_ELEMENT)
+        .map(|chunk| {
+            let mut blob = vec![0u8; BYTES_PER_BLOB];
+            for (element, bytes) in
+                blob.chunks_mut(32).zip(chunk.chunks(USABLE_BYTES_PER_FIELD_ELEMENT))
+            {
+                element[1..=bytes.len()].copy_from_slice(bytes);
+            }
+            blob.into()
+        })
+        .collect()
+}
+
+/// The blobs of a transaction with their KZG commitments and proofs, which are sent along with the
+/// signed transaction but are not part of it.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct BlobTransactionSidecar {
+    /// The blobs, each [`BYTES_PER_BLOB`] long
+    pub blobs: Vec<Bytes>,
+    /// The KZG commitment of each blob
+    pub commitments: Vec<Bytes>,
+    /// The KZG proof of each blob
+    pub proofs: Vec<Bytes>,
+}
+
+impl BlobTransactionSidecar {
+    /// Creates a sidecar from blobs and their precomputed commitments and proofs.
+    pub fn new(blobs: Vec<Bytes>, commitments: Vec<Bytes>, proofs: Vec<Bytes>) -> Self {
+        Self { blobs, commitments, proofs }
+    }
+
+    /// Computes the commitments and proofs of `blobs`.
+    ///
+    /// `settings` are usually loaded from the trusted setup of the KZG ceremony with
+    /// [`KzgSettings::load_trusted_setup_file`].
+    #[cfg(feature = "kzg")]
+    pub fn from_blobs(
+        blobs: Vec<Bytes>,
+        settings: &KzgSettings,
+    ) -> Result<Self, Eip4844RequestError> {
+        let mut commitments = Vec::with_capacity(blobs.len());
+        let mut proofs = Vec::with_capacity(blobs.len());
+        for blob in &blobs {
+            let blob = kzg_blob(blob)?;
+            let commitment = c_kzg::KzgCommitment::blob_to_kzg_commitment(&blob, settings)
+                .map_err(kzg_error)?
+                .to_bytes();
+            let proof = c_kzg::KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings)
+                .map_err(kzg_error)?;
+            commitments.push(Bytes::from(commitment.to_vec()));
+            proofs.push(Bytes::from(proof.to_bytes().to_vec()));
+        }
+        Ok(Self { blobs, commitments, proofs })
+    }
+
+    /// Returns the versioned hashes of the commitments, as included in the transaction.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments.iter().map(|commitment| kzg_to_versioned_hash(commitment)).collect()
+    }
+
+    /// Checks that there are between 1 and [`MAX_BLOBS_PER_BLOCK`] blobs, each with a commitment
+    /// and a proof of the correct length.
+    pub fn validate(&self) -> Result<(), Eip4844RequestError> {
+        let (blobs, commitments, proofs) =
+            (self.blobs.len(), self.commitments.len(), self.proofs.len());
+        if blobs != commitments || blobs != proofs {
+            return Err(Eip4844RequestError::SidecarLengthMismatch { blobs, commitments, proofs })
+        }
+        if blobs == 0 || blobs > MAX_BLOBS_PER_BLOCK {
+            return Err(Eip4844RequestError::InvalidBlobCount(blobs))
+        }
+        if let Some(blob) = self.blobs.iter().find(|blob| blob.len() != BYTES_PER_BLOB) {
+            return Err(Eip4844RequestError::InvalidBlobLength(blob.len()))
+        }
+        if let Some(bytes) = self
+            .commitments
+            .iter()
+            .chain(&self.proofs)
+            .find(|bytes| bytes.len() != BYTES_PER_COMMITMENT)
+        {
+            return Err(Eip4844RequestError::InvalidCommitmentLength(bytes.len()))
+        }
+        Ok(())
+    }
+
+    /// Validates the sidecar and verifies its proofs.
+    #[cfg(feature = "kzg")]
+    pub fn verify(&self, settings: &KzgSettings) -> Result<(), Eip4844RequestError> {
+        self.validate()?;
+        let blobs = self.blobs.iter().map(|blob| kzg_blob(blob)).collect::<Result<Vec<_>, _>>()?;
+        let to_bytes48 = |bytes: &Bytes| c_kzg::Bytes48::from_bytes(bytes).map_err(kzg_error);
+        let commitments = self.commitments.iter().map(to_bytes48).collect::<Result<Vec<_>, _>>()?;
+        let proofs = self.proofs.iter().map(to_bytes48).collect::<Result<Vec<_>, _>>()?;
+        let valid =
+            c_kzg::KzgProof::verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs, settings)
+                .map_err(kzg_error)?;
+        if !valid {
+            return Err(Eip4844RequestError::InvalidProof)
+        }
+        Ok(())
+    }
+
+    fn rlp_append(&self, rlp: &mut RlpStream) {
+        for items in [&self.blobs, &self.commitments, &self.proofs] {
+            rlp.begin_list(items.len());
+            for item in items {
+                rlp.append(&item.as_ref());
+            }
+        }
+    }
+
+    fn decode_rlp(rlp: &rlp::Rlp, offset: usize) -> Result<Self, DecoderError> {
+        let list = |index| -> Result<Vec<Bytes>, DecoderError> {
+            rlp.at(index)?.iter().map(|item| Ok(Bytes::from(item.data()?.to_vec()))).collect()
+        };
+        Ok(Self { blobs: list(offset)?, commitments: list(offset + 1)?, proofs: list(offset + 2)? })
+    }
+}
+
+#[cfg(feature = "kzg")]
+fn kzg_blob(blob: &Bytes) -> Result<c_kzg::Blob, Eip4844RequestError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(Eip4844RequestError::InvalidBlobLength(blob.len()))
+    }
+    c_kzg::Blob::from_bytes(blob).map_err(kzg_error)
+}
+
+#[cfg(feature = "kzg")]
+fn kzg_error(err: c_kzg::Error) -> Eip4844RequestError {
+    Eip4844RequestError::Kzg(format!("{err:?}"))
+}
+
+/// An EIP-4844 blob transaction: an EIP-1559 transaction which pays for the blob gas of the blobs
+/// whose versioned hashes it includes.
+///
+/// ```
+/// use ethers_core::types::{
+///     transaction::eip4844::{blobs_from_data, BlobTransactionSidecar, Eip4844TransactionRequest},
+///     Address, Eip1559TransactionRequest,
+/// };
+///
+/// let blobs = blobs_from_data(b"rollup batch");
+/// // the commitments and proofs are computed with `BlobTransactionSidecar::from_blobs`
+/// let sidecar = BlobTransactionSidecar::new(blobs, vec![vec![0xc0; 48].into()], vec![vec![0xc0; 48].into()]);
+///
+/// let tx = Eip4844TransactionRequest::new(Eip1559TransactionRequest::new().to(Address::zero()))
+///     .max_fee_per_blob_gas(1_000_000_000u64)
+///     .sidecar(sidecar);
+/// assert_eq!(tx.blob_versioned_hashes.len(), 1);
+/// assert_eq!(tx.blob_gas(), 131_072u64.into());
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip4844TransactionRequest {
+    #[serde(flatten)]
+    pub tx: Eip1559TransactionRequest,
+
+    /// The maximum fee per unit of blob gas the sender is willing to pay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_blob_gas: Option<U256>,
+
+    /// The versioned hashes of the blobs
+    #[serde(default)]
+    pub blob_versioned_hashes: Vec<H256>,
+
+    /// The blobs with their commitments and proofs, which are only needed to broadcast the
+    /// transaction
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub sidecar: Option<BlobTransactionSidecar>,
+}
+
+impl Eip4844TransactionRequest {
+    /// Creates a blob transaction from an EIP-1559 transaction, without any blobs.
+    pub fn new(tx: Eip1559TransactionRequest) -> Self {
+        Self { tx, ..Default::default() }
+    }
+
+    /// Sets the `max_fee_per_blob_gas` field in the transaction to the provided value
+    #[must_use]
+    pub fn max_fee_per_blob_gas<T: Into<U256>>(mut self, max_fee_per_blob_gas: T) -> Self {
+        self.max_fee_per_blob_gas = Some(max_fee_per_blob_gas.into());
+        self
+    }
+
+    /// Sets the `blob_versioned_hashes` field in the transaction to the provided value
+    #[must_use]
+    pub fn blob_versioned_hashes(mut self, blob_versioned_hashes: Vec<H256>) -> Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
+    /// Sets the `sidecar` field in the transaction and the versioned hashes of its commitments
+    #[must_use]
+    pub fn sidecar(mut self, sidecar: BlobTransactionSidecar) -> Self {
+        self.blob_versioned_hashes = sidecar.versioned_hashes();
+        self.sidecar = Some(sidecar);
+        self
+    }
+
+    /// Returns the blob gas used by the transaction's blobs.
+    pub fn blob_gas(&self) -> U256 {
+        U256::from(self.blob_versioned_hashes.len()) * GAS_PER_BLOB
+    }
+
+    /// Checks that the transaction has a recipient and between 1 and [`MAX_BLOBS_PER_BLOCK`]
+    /// blobs, and that its sidecar, if any, is valid and matches the versioned hashes.
+    pub fn validate(&self) -> Result<(), Eip4844RequestError> {
+        if !matches!(self.tx.to, Some(NameOrAddress::Address(_))) {
+            return Err(Eip4844RequestError::MissingRecipient)
+        }
+        let blobs = self.blob_versioned_hashes.len();
+        if blobs == 0 || blobs > MAX_BLOBS_PER_BLOCK {
+            return Err(Eip4844RequestError::InvalidBlobCount(blobs))
+        }
+        if let Some(sidecar) = &self.sidecar {
+            sidecar.validate()?;
+            if sidecar.versioned_hashes() != self.blob_versioned_hashes {
+                return Err(Eip4844RequestError::VersionedHashMismatch)
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the unsigned transaction's RLP encoding
+    pub fn rlp(&self) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(NUM_TX_FIELDS);
+        self.rlp_base(&mut rlp);
+        rlp.out().freeze().into()
+    }
+
+    /// Produces the RLP encoding of the transaction with the provided signature, which is hashed
+    /// to the transaction hash
+    pub fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut rlp = RlpStream::new();
+        self.rlp_append_signed(&mut rlp, signature);
+        rlp.out().freeze().into()
+    }
+
+    /// Produces the network representation of the transaction with the provided signature,
+    /// `rlp([tx_payload_body, blobs, commitments, proofs])`, or the signed RLP encoding if the
+    /// transaction has no sidecar.
+    pub fn rlp_network(&self, signature: &Signature) -> Bytes {
+        let Some(sidecar) = &self.sidecar else { return self.rlp_signed(signature) };
+        let mut rlp = RlpStream::new_list(4);
+        self.rlp_append_signed(&mut rlp, signature);
+        sidecar.rlp_append(&mut rlp);
+        rlp.out().freeze().into()
+    }
+
+    fn rlp_append_signed(&self, rlp: &mut RlpStream, signature: &Signature) {
+        rlp.begin_unbounded_list();
+        self.rlp_base(rlp);
+
+        // if the chain_id is none we assume mainnet and choose one
+        let chain_id = self.tx.chain_id.unwrap_or_else(U64::one);
+
+        // append the signature
+        let v = normalize_v(signature.v, chain_id);
+        rlp.append(&v);
+        rlp.append(&signature.r);
+        rlp.append(&signature.s);
+        rlp.finalize_unbounded_list();
+    }
+
+    pub(crate) fn rlp_base(&self, rlp: &mut RlpStream) {
+        self.tx.rlp_base(rlp);
+        super::rlp_opt(rlp, &self.max_fee_per_blob_gas);
+        rlp.append_list(&self.blob_versioned_hashes);
+    }
+
+    /// Decodes fields of the request starting at the RLP offset passed. Increments the offset for
+    /// each element parsed.
+    #[inline]
+    pub fn decode_base_rlp(rlp: &rlp::Rlp, offset: &mut usize) -> Result<Self, DecoderError> {
+        let tx = Eip1559TransactionRequest::decode_base_rlp(rlp, offset)?;
+        let max_fee_per_blob_gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        let blob_versioned_hashes = rlp.list_at(*offset)?;
+        *offset += 1;
+        Ok(Self { tx, max_fee_per_blob_gas, blob_versioned_hashes, sidecar: None })
+    }
+
+    /// Decodes the given RLP into a transaction, attempting to decode its signature as well.
+    ///
+    /// Accepts both the signed transaction and its network representation with the sidecar.
+    pub fn decode_signed_rlp(rlp: &rlp::Rlp) -> Result<(Self, Signature), Eip4844RequestError> {
+        if rlp.at(0)?.is_list() {
+            let (mut txn, sig) = Self::decode_signed_rlp(&rlp.at(0)?)?;
+            txn.sidecar = Some(BlobTransactionSidecar::decode_rlp(rlp, 1)?);
+            return Ok((txn, sig))
+        }
+
+        let mut offset = 0;
+        let mut txn = Self::decode_base_rlp(rlp, &mut offset)?;
+
+        let v = rlp.val_at(offset)?;
+        offset += 1;
+        let r = rlp.val_at(offset)?;
+        offset += 1;
+        let s = rlp.val_at(offset)?;
+
+        let sig = Signature { r, s, v };
+        txn.tx.from = Some(sig.recover(TypedTransaction::Eip4844(txn.clone()).sighash())?);
+
+        Ok((txn, sig))
+    }
+}
+
+impl Decodable for Eip4844TransactionRequest {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        Self::decode_base_rlp(rlp, &mut 0)
+    }
+}
+
+impl From<Eip4844TransactionRequest> for super::request::TransactionRequest {
+    fn from(tx: Eip4844TransactionRequest) -> Self {
+        tx.tx.into()
+    }
+}
+
+impl From<&Transaction> for Eip4844TransactionRequest {
+    fn from(tx: &Transaction) -> Eip4844TransactionRequest {
+        Eip4844TransactionRequest {
+            tx: tx.into(),
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: tx.blob_versioned_hashes.clone().unwrap_or_default(),
+            sidecar: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Address;
+
+    fn sidecar() -> BlobTransactionSidecar {
+        BlobTransactionSidecar::new(
+            blobs_from_data(b"hello blobs"),
+            vec![vec![0xc0; BYTES_PER_COMMITMENT].into()],
+            vec![vec![0xc0; BYTES_PER_COMMITMENT].into()],
+        )
+    }
+
+    fn request() -> Eip4844TransactionRequest {
+        let tx = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            // decoded transactions always carry a value
+            .value(0)
+            .nonce(3)
+            .gas(21_000)
+            .max_fee_per_gas(20)
+            .max_priority_fee_per_gas(1)
+            .chain_id(1);
+        Eip4844TransactionRequest::new(tx).max_fee_per_blob_gas(7).sidecar(sidecar())
+    }
+
+    #[test]
+    fn packs_data_into_blobs() {
+        let blobs = blobs_from_data(&[0xff; FIELD_ELEMENTS_PER_BLOB * 31 + 1]);
+        assert_eq!(blobs.len(), 2);
+        assert!(blobs.iter().all(|blob| blob.len() == BYTES_PER_BLOB));
+        assert_eq!(&blobs[0][..33], &[[0u8].as_slice(), &[0xff; 31], &[0]].concat()[..]);
+        assert_eq!(&blobs[1][..3], &[0, 0xff, 0]);
+    }
+
+    #[test]
+    fn versioned_hash() {
+        let hash = kzg_to_versioned_hash(&[0xc0; BYTES_PER_COMMITMENT]);
+        assert_eq!(hash[0], VERSIONED_HASH_VERSION_KZG);
+        assert_eq!(hash[1..], Sha256::digest([0xc0; BYTES_PER_COMMITMENT])[1..]);
+    }
+
+    #[test]
+    fn validates_request() {
+        let tx = request();
+        assert_eq!(tx.blob_gas(), GAS_PER_BLOB.into());
+        tx.validate().unwrap();
+
+        let mut tx = request();
+        tx.tx.to = None;
+        assert!(matches!(tx.validate(), Err(Eip4844RequestError::MissingRecipient)));
+
+        let tx = request().blob_versioned_hashes(vec![H256::zero()]);
+        assert!(matches!(tx.validate(), Err(Eip4844RequestError::VersionedHashMismatch)));
+
+        let tx = request().blob_versioned_hashes(vec![]);
+        assert!(matches!(tx.validate(), Err(Eip4844RequestError::InvalidBlobCount(0))));
+
+        let mut sidecar = sidecar();
+        sidecar.proofs.clear();
+        assert!(matches!(
+            sidecar.validate(),
+            Err(Eip4844RequestError::SidecarLengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rlp_roundtrip() {
+        let tx = request();
+        let decoded = Eip4844TransactionRequest::decode(&rlp::Rlp::new(&tx.rlp())).unwrap();
+        assert_eq!(decoded, Eip4844TransactionRequest { sidecar: None, ..tx });
+    }
+
+    #[test]
+    fn decodes_network_representation() {
+        use k256::ecdsa::SigningKey;
+
+        let key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let from = crate::utils::secret_key_to_address(&key);
+        let tx = request();
+        let sighash = TypedTransaction::Eip4844(tx.clone()).sighash();
+        let (sig, recovery_id) = key.sign_prehash_recoverable(sighash.as_bytes()).unwrap();
+        let bytes = sig.to_bytes();
+        let signature = Signature {
+            r: U256::from_big_endian(&bytes[..32]),
+            s: U256::from_big_endian(&bytes[32..]),
+            // EIP-155 encoded like all signatures of `Wallet`, normalized to the y-parity in `rlp`
+            v: recovery_id.to_byte() as u64 + 35 + 2,
+        };
+
+        let expected = Eip4844TransactionRequest { tx: tx.tx.clone().from(from), ..tx.clone() };
+        let network = tx.rlp_network(&signature);
+        let (decoded, _) =
+            Eip4844TransactionRequest::decode_signed_rlp(&rlp::Rlp::new(&network)).unwrap();
+        assert_eq!(decoded, expected);
+
+        let signed = tx.rlp_signed(&signature);
+        let (decoded, _) =
+            Eip4844TransactionRequest::decode_signed_rlp(&rlp::Rlp::new(&signed)).unwrap();
+        assert_eq!(decoded, Eip4844TransactionRequest { sidecar: None, ..expected });
+    }
+}
@@ -0,0 +1,130 @@
+//! EIP-7702 authorizations
+use crate::{
+    types::{Address, RecoveryMessage, Signature, SignatureError, H256, U256, U64},
+    utils::keccak256,
+};
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+
+/// The magic byte prefixed to the RLP encoded authorization before hashing it.
+pub const EIP7702_MAGIC: u8 = 0x05;
+
+/// An EIP-7702 authorization, allowing the signing account to delegate its code to `address`.
+///
+/// A `chain_id` of zero makes the authorization valid on all chains.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    /// The chain the authorization is valid on, or zero for all chains
+    pub chain_id: U256,
+    /// The address of the contract whose code the account delegates to
+    pub address: Address,
+    /// The nonce of the signing account
+    pub nonce: U64,
+}
+
+impl Authorization {
+    /// Creates a new authorization.
+    pub fn new<C: Into<U256>, N: Into<U64>>(chain_id: C, address: Address, nonce: N) -> Self {
+        Self { chain_id: chain_id.into(), address, nonce: nonce.into() }
+    }
+
+    /// Returns the RLP encoding of the `[chain_id, address, nonce]` tuple.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut rlp = RlpStream::new_list(3);
+        rlp.append(&self.chain_id);
+        rlp.append(&self.address);
+        rlp.append(&self.nonce);
+        rlp.out().to_vec()
+    }
+
+    /// Returns the hash that is signed by the authorizing account,
+    /// `keccak256(0x05 || rlp([chain_id, address, nonce]))`.
+    pub fn signature_hash(&self) -> H256 {
+        let mut encoded = vec![EIP7702_MAGIC];
+        encoded.extend_from_slice(&self.rlp());
+        keccak256(encoded).into()
+    }
+
+    /// Attaches the signature of the authorizing account.
+    pub fn into_signed(self, signature: Signature) -> SignedAuthorization {
+        // the signature carries the y parity as its recovery id, with or without the legacy offset
+        let y_parity = if signature.v >= 27 { signature.v - 27 } else { signature.v };
+        SignedAuthorization {
+            inner: self,
+            y_parity: y_parity.into(),
+            r: signature.r,
+            s: signature.s,
+        }
+    }
+}
+
+/// An EIP-7702 authorization with the signature of the authorizing account, as included in the
+/// authorization list of a set code transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAuthorization {
+    /// The signed authorization
+    #[serde(flatten)]
+    pub inner: Authorization,
+    /// The y parity of the signature
+    pub y_parity: U64,
+    /// The r value of the signature
+    pub r: U256,
+    /// The s value of the signature
+    pub s: U256,
+}
+
+impl SignedAuthorization {
+    /// Returns the signature with `v` set to the y parity plus 27.
+    pub fn signature(&self) -> Signature {
+        Signature { r: self.r, s: self.s, v: self.y_parity.as_u64() + 27 }
+    }
+
+    /// Recovers the account that signed the authorization.
+    pub fn recover_authority(&self) -> Result<Address, SignatureError> {
+        if self.y_parity > U64::one() {
+            return Err(SignatureError::RecoveryError)
+        }
+        self.signature().recover(RecoveryMessage::Hash(self.inner.signature_hash()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_hash() {
+        let auth = Authorization::new(
+            1u64,
+            "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".parse().unwrap(),
+            7u64,
+        );
+        assert_eq!(
+            auth.signature_hash(),
+            "0xc2b0b9d24c515be251e7339d100eb377f32339f83d7b1bb41729d589b90c658d".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn serde_signed_authorization() {
+        let signed = Authorization::new(0u64, Address::repeat_byte(0x11), 1u64)
+            .into_signed(Signature { r: 2.into(), s: 3.into(), v: 28 });
+        assert_eq!(signed.y_parity, U64::one());
+
+        let json = serde_json::to_value(&signed).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "chainId": "0x0",
+                "address": "0x1111111111111111111111111111111111111111",
+                "nonce": "0x1",
+                "yParity": "0x1",
+                "r": "0x2",
+                "s": "0x3",
+            })
+        );
+        assert_eq!(serde_json::from_value::<SignedAuthorization>(json).unwrap(), signed);
+    }
+}
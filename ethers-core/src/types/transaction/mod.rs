@@ -1,13 +1,27 @@
 pub mod request;
 pub mod response;
 
+pub mod decoded;
+
 pub mod eip1559;
+pub mod eip2612;
 pub mod eip2718;
 pub mod eip2930;
+pub mod eip3009;
+pub mod eip4337;
+pub mod eip4844;
+pub mod eip7702;
+pub mod safe;
 
 #[cfg(feature = "optimism")]
 pub mod optimism_deposited;
 
+#[cfg(feature = "celo")]
+pub mod celo;
+
+#[cfg(feature = "zksync")]
+pub mod zksync;
+
 pub mod eip712;
 
 pub(crate) const BASE_NUM_TX_FIELDS: usize = 9;
@@ -154,6 +154,8 @@ mod test {
             source_hash: Some(H256::from_str("0xa8157ccf61bcdfbcb74a84ec1262e62644dd1e7e3614abcbd8db0c99a60049fc").unwrap()),
             mint: Some(0.into()),
             is_system_tx: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::from(0x7E)),
             access_list: None,
             max_priority_fee_per_gas: None,
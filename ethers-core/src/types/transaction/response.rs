@@ -1,9 +1,10 @@
 //! Transaction types
 use super::{
-    decode_signature, decode_to, eip2718::TypedTransaction, eip2930::AccessList, normalize_v,
-    rlp_opt, rlp_opt_list,
+    decode_signature, decode_to, decoded::DecodedReceipt, eip2718::TypedTransaction,
+    eip2930::AccessList, eip4844::BLOB_TX_TYPE, normalize_v, rlp_opt, rlp_opt_list,
 };
 use crate::{
+    abi::AbiRegistry,
     types::{
         transaction::extract_chain_id, Address, Bloom, Bytes, Log, Signature, SignatureError, H256,
         U256, U64,
@@ -128,6 +129,15 @@ pub struct Transaction {
     /// baseFeePerGas + maxPriorityFeePerGas is “refunded” to the user.
     pub max_fee_per_gas: Option<U256>,
 
+    // EIP4844
+    /// The maximum fee per unit of blob gas, only set on blob transactions
+    #[serde(rename = "maxFeePerBlobGas", default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_blob_gas: Option<U256>,
+
+    /// The versioned hashes of the blobs, only set on blob transactions
+    #[serde(rename = "blobVersionedHashes", default, skip_serializing_if = "Option::is_none")]
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+
     #[serde(rename = "chainId", default, skip_serializing_if = "Option::is_none")]
     pub chain_id: Option<U256>,
 
@@ -194,6 +204,25 @@ impl Transaction {
                 rlp.append(&self.r);
                 rlp.append(&self.s);
             }
+            // EIP-4844 (0x03)
+            Some(x) if x == U64::from(BLOB_TX_TYPE) => {
+                rlp_opt(&mut rlp, &self.chain_id);
+                rlp.append(&self.nonce);
+                rlp_opt(&mut rlp, &self.max_priority_fee_per_gas);
+                rlp_opt(&mut rlp, &self.max_fee_per_gas);
+                rlp.append(&self.gas);
+                rlp_opt(&mut rlp, &self.to);
+                rlp.append(&self.value);
+                rlp.append(&self.input.as_ref());
+                rlp_opt_list(&mut rlp, &self.access_list);
+                rlp_opt(&mut rlp, &self.max_fee_per_blob_gas);
+                rlp.append_list(self.blob_versioned_hashes.as_deref().unwrap_or_default());
+                if let Some(chain_id) = self.chain_id {
+                    rlp.append(&normalize_v(self.v.as_u64(), U64::from(chain_id.as_u64())));
+                }
+                rlp.append(&self.r);
+                rlp.append(&self.s);
+            }
             // Optimism Deposited Transaction
             #[cfg(feature = "optimism")]
             Some(x) if x == U64::from(0x7E) => {
@@ -239,6 +268,11 @@ impl Transaction {
                 encoded.extend_from_slice(rlp_bytes.as_ref());
                 encoded.into()
             }
+            Some(x) if x == U64::from(BLOB_TX_TYPE) => {
+                encoded.extend_from_slice(&[BLOB_TX_TYPE]);
+                encoded.extend_from_slice(rlp_bytes.as_ref());
+                encoded.into()
+            }
             #[cfg(feature = "optimism")]
             Some(x) if x == U64::from(0x7E) => {
                 encoded.extend_from_slice(&[0x7E]);
@@ -409,6 +443,13 @@ impl Decodable for Transaction {
                     txn.decode_base_eip1559(&rest, &mut offset)?;
                     txn.transaction_type = Some(2u64.into());
                 }
+                BLOB_TX_TYPE => {
+                    txn.decode_base_eip1559(&rest, &mut offset)?;
+                    txn.max_fee_per_blob_gas = Some(rest.val_at(offset)?);
+                    txn.blob_versioned_hashes = Some(rest.list_at(offset + 1)?);
+                    offset += 2;
+                    txn.transaction_type = Some(BLOB_TX_TYPE.into());
+                }
                 _ => return Err(DecoderError::Custom("invalid tx type")),
             }
 
@@ -486,6 +527,15 @@ impl rlp::Encodable for TransactionReceipt {
     }
 }
 
+impl TransactionReceipt {
+    /// Decodes the logs of the receipt with the events known to `registry`.
+    ///
+    /// See [`DecodedReceipt::summary`] for the tokens moved and approved by the transaction.
+    pub fn decode_logs(&self, registry: &AbiRegistry) -> DecodedReceipt {
+        DecodedReceipt::new(self, registry)
+    }
+}
+
 // Compares the transaction receipt against another receipt by checking the blocks first and then
 // the transaction index in the block
 impl Ord for TransactionReceipt {
@@ -617,6 +667,8 @@ mod tests {
             to: Some(Address::from_str("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()),
             transaction_index: None,
             value: U256::from_str_radix("0x2b40d6d551c8970c", 16).unwrap(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::from(0x2)),
             access_list: Some(AccessList::from(vec![])),
             chain_id: Some(U256::from(1)),
@@ -661,6 +713,8 @@ mod tests {
             to: Some(Address::from_str("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()),
             transaction_index: None,
             value: U256::from_str_radix("0x2b40d6d551c8970c", 16).unwrap(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::from(0x2)),
             access_list: None,
             chain_id: Some(U256::from(1)),
@@ -702,6 +756,8 @@ mod tests {
             to: Some(Address::from_str("dac17f958d2ee523a2206206994597c13d831ec7").unwrap()),
             transaction_index: None,
             value: U256::zero(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::zero()),
             v: U64::from(0x25),
             r: U256::from_str_radix("c81e70f9e49e0d3b854720143e86d172fecc9e76ef8a8666f2fdc017017c5141", 16).unwrap(),
@@ -751,6 +807,8 @@ mod tests {
                 10,
             )
             .unwrap(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(2.into()),
             access_list: Some(AccessList::default()),
             max_priority_fee_per_gas: Some(1500000000.into()),
@@ -798,6 +856,8 @@ mod tests {
                 10,
             )
             .unwrap(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(2.into()),
             access_list: Some(AccessList::default()),
             max_priority_fee_per_gas: Some(1500000000.into()),
@@ -858,6 +918,8 @@ mod tests {
             to: Some(Address::from_str("dac17f958d2ee523a2206206994597c13d831ec7").unwrap()),
             transaction_index: None,
             value: U256::zero(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::zero()),
             v: U64::from(0x25),
             r: U256::from_str_radix("c81e70f9e49e0d3b854720143e86d172fecc9e76ef8a8666f2fdc017017c5141", 16).unwrap(),
@@ -893,6 +955,8 @@ mod tests {
             to: Some(Address::from_str("dac17f958d2ee523a2206206994597c13d831ec7").unwrap()),
             transaction_index: None,
             value: U256::zero(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::zero()),
             v: U64::from(0x25),
             r: U256::from_str_radix("c81e70f9e49e0d3b854720143e86d172fecc9e76ef8a8666f2fdc017017c5141", 16).unwrap(),
@@ -962,6 +1026,8 @@ mod tests {
                 10,
             )
             .unwrap(),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(2.into()),
             access_list: Some(AccessList::default()),
             max_priority_fee_per_gas: Some(1500000000.into()),
@@ -1188,6 +1254,8 @@ mod tests {
             source_hash: Some(H256::from_str("0xa8157ccf61bcdfbcb74a84ec1262e62644dd1e7e3614abcbd8db0c99a60049fc").unwrap()),
             mint: Some(0.into()),
             is_system_tx: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
             transaction_type: Some(U64::from(126)),
             access_list: None,
             max_priority_fee_per_gas: None,
@@ -0,0 +1,345 @@
+//! Transactions of the Safe (formerly Gnosis Safe) multisig wallet, which owners approve by
+//! signing their EIP-712 hash
+use crate::{
+    abi::{encode, Token},
+    types::{
+        transaction::eip712::{EIP712Domain, Eip712, Eip712Error},
+        Address, Bytes, RecoveryMessage, Signature, SignatureError, H256, U256,
+    },
+    utils::{hash_message, keccak256},
+};
+
+/// The type hash of `SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256
+/// safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256
+/// nonce)`
+pub const SAFE_TX_TYPEHASH: [u8; 32] = [
+    187, 131, 16, 212, 134, 54, 141, 182, 189, 111, 132, 148, 2, 253, 215, 58, 213, 61, 49, 107,
+    90, 75, 38, 68, 173, 110, 254, 15, 148, 18, 134, 216,
+];
+
+/// Whether the Safe calls or delegatecalls the target of a [`SafeTx`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Operation {
+    /// A regular call
+    #[default]
+    Call = 0,
+    /// A delegatecall, executing the target's code in the context of the Safe
+    DelegateCall = 1,
+}
+
+/// A transaction executed by a Safe once enough owners signed its [`safe_tx_hash`].
+///
+/// The gas and refund parameters default to zero, so that the account executing the transaction
+/// pays for it without being refunded by the Safe.
+///
+/// [`safe_tx_hash`]: SafeTx::safe_tx_hash
+///
+/// # Example
+///
+/// ```
+/// use ethers_core::types::{transaction::safe::SafeTx, Address};
+///
+/// let safe = Address::repeat_byte(0x5a);
+/// let tx = SafeTx::new(safe, 1u64, Address::repeat_byte(1), 1_000u64, vec![], 7u64);
+/// let hash = tx.safe_tx_hash();
+/// // `tx` implements `Eip712` and can be signed with `Signer::sign_typed_data`
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SafeTx {
+    /// The Safe executing the transaction
+    pub safe: Address,
+    /// The chain id of the Safe's domain. `None` for Safes older than v1.3.0, whose domain only
+    /// contains the Safe's address
+    pub chain_id: Option<U256>,
+    /// The target of the transaction
+    pub to: Address,
+    /// The value sent with the transaction, in wei
+    pub value: U256,
+    /// The calldata of the transaction
+    pub data: Bytes,
+    /// Whether the target is called or delegatecalled
+    pub operation: Operation,
+    /// The gas forwarded to the target, zero forwards all gas
+    pub safe_tx_gas: U256,
+    /// The gas refunded on top of `safe_tx_gas`, independent of the transaction's execution
+    pub base_gas: U256,
+    /// The gas price of the refund, zero disables the refund
+    pub gas_price: U256,
+    /// The token the refund is paid in, the zero address for ether
+    pub gas_token: Address,
+    /// The receiver of the refund, the zero address for `tx.origin`
+    pub refund_receiver: Address,
+    /// The Safe's nonce the transaction is executed at
+    pub nonce: U256,
+}
+
+impl SafeTx {
+    /// Creates a call of `to` by the `safe` on `chain_id`, without a gas refund.
+    pub fn new<C, V, N>(
+        safe: Address,
+        chain_id: C,
+        to: Address,
+        value: V,
+        data: impl Into<Bytes>,
+        nonce: N,
+    ) -> Self
+    where
+        C: Into<U256>,
+        V: Into<U256>,
+        N: Into<U256>,
+    {
+        Self {
+            safe,
+            chain_id: Some(chain_id.into()),
+            to,
+            value: value.into(),
+            data: data.into(),
+            nonce: nonce.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets whether the target is called or delegatecalled
+    #[must_use]
+    pub fn operation(mut self, operation: Operation) -> Self {
+        self.operation = operation;
+        self
+    }
+
+    /// Sets the gas and the refund parameters of the transaction
+    #[must_use]
+    pub fn gas<T: Into<U256>>(
+        mut self,
+        safe_tx_gas: T,
+        base_gas: T,
+        gas_price: T,
+        gas_token: Address,
+        refund_receiver: Address,
+    ) -> Self {
+        self.safe_tx_gas = safe_tx_gas.into();
+        self.base_gas = base_gas.into();
+        self.gas_price = gas_price.into();
+        self.gas_token = gas_token;
+        self.refund_receiver = refund_receiver;
+        self
+    }
+
+    /// Uses the domain of Safes older than v1.3.0, which does not contain the chain id
+    #[must_use]
+    pub fn legacy_domain(mut self) -> Self {
+        self.chain_id = None;
+        self
+    }
+
+    /// Returns the hash which owners sign, as returned by `Safe.getTransactionHash`.
+    pub fn safe_tx_hash(&self) -> H256 {
+        H256(self.encode_eip712().expect("safe transactions always encode"))
+    }
+}
+
+impl Eip712 for SafeTx {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: None,
+            version: None,
+            chain_id: self.chain_id,
+            verifying_contract: Some(self.safe),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(SAFE_TX_TYPEHASH)
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(SAFE_TX_TYPEHASH.to_vec()),
+            Token::Address(self.to),
+            Token::Uint(self.value),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Uint((self.operation as u8).into()),
+            Token::Uint(self.safe_tx_gas),
+            Token::Uint(self.base_gas),
+            Token::Uint(self.gas_price),
+            Token::Address(self.gas_token),
+            Token::Address(self.refund_receiver),
+            Token::Uint(self.nonce),
+        ])))
+    }
+}
+
+/// The signature of a Safe owner over a [`SafeTx::safe_tx_hash`], in one of the forms the Safe
+/// accepts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SafeSignature {
+    /// An ECDSA signature of the hash itself, e.g. from `Signer::sign_typed_data`
+    Eip712(Signature),
+    /// An ECDSA signature of the hash as an EIP-191 personal message, e.g. from
+    /// `Signer::sign_message`
+    EthSign(Signature),
+    /// The owner approved the hash on-chain with `approveHash`, or is the account executing the
+    /// transaction
+    ApprovedHash(Address),
+    /// An EIP-1271 signature of the owner contract
+    Contract {
+        /// The owner contract, which validates `signature` with `isValidSignature`
+        owner: Address,
+        /// The signature passed to the owner contract
+        signature: Bytes,
+    },
+}
+
+impl SafeSignature {
+    /// Returns the owner that produced the signature, recovering it for ECDSA signatures.
+    pub fn owner(&self, safe_tx_hash: H256) -> Result<Address, SignatureError> {
+        match self {
+            SafeSignature::Eip712(signature) => {
+                signature.recover(RecoveryMessage::Hash(safe_tx_hash))
+            }
+            SafeSignature::EthSign(signature) => {
+                signature.recover(RecoveryMessage::Hash(hash_message(safe_tx_hash)))
+            }
+            SafeSignature::ApprovedHash(owner) | SafeSignature::Contract { owner, .. } => {
+                Ok(*owner)
+            }
+        }
+    }
+
+    /// Returns the 65 byte static part of the signature, with `s` set to `dynamic_offset` for
+    /// contract signatures.
+    fn static_part(&self, dynamic_offset: usize) -> [u8; 65] {
+        let mut bytes = [0; 65];
+        match self {
+            SafeSignature::Eip712(signature) | SafeSignature::EthSign(signature) => {
+                signature.r.to_big_endian(&mut bytes[..32]);
+                signature.s.to_big_endian(&mut bytes[32..64]);
+                let v = if signature.v < 27 { signature.v + 27 } else { signature.v };
+                // eth_sign signatures are marked by adding 4 to `v`
+                let v = if matches!(self, SafeSignature::EthSign(_)) { v + 4 } else { v };
+                bytes[64] = v as u8;
+            }
+            SafeSignature::ApprovedHash(owner) => {
+                bytes[12..32].copy_from_slice(owner.as_bytes());
+                bytes[64] = 1;
+            }
+            SafeSignature::Contract { owner, .. } => {
+                bytes[12..32].copy_from_slice(owner.as_bytes());
+                U256::from(dynamic_offset).to_big_endian(&mut bytes[32..64]);
+            }
+        }
+        bytes
+    }
+}
+
+/// Encodes the signatures of the owners as the `signatures` argument of `execTransaction`.
+///
+/// The Safe requires the signatures to be sorted by owner, which is why the owner of every
+/// signature has to be known, see [`SafeSignature::owner`]. The static parts are concatenated,
+/// followed by the length-prefixed data of contract signatures.
+pub fn encode_safe_signatures(signatures: &[(Address, SafeSignature)]) -> Bytes {
+    let mut signatures = signatures.iter().collect::<Vec<_>>();
+    signatures.sort_by_key(|(owner, _)| *owner);
+
+    let static_length = signatures.len() * 65;
+    let mut static_parts = Vec::with_capacity(static_length);
+    let mut dynamic_parts = Vec::new();
+    for (_, signature) in signatures {
+        let offset = static_length + dynamic_parts.len();
+        static_parts.extend_from_slice(&signature.static_part(offset));
+        if let SafeSignature::Contract { signature, .. } = signature {
+            let mut length = [0; 32];
+            U256::from(signature.len()).to_big_endian(&mut length);
+            dynamic_parts.extend_from_slice(&length);
+            dynamic_parts.extend_from_slice(signature);
+        }
+    }
+    static_parts.extend(dynamic_parts);
+    static_parts.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe_tx() -> SafeTx {
+        SafeTx::new(
+            "0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a".parse().unwrap(),
+            1u64,
+            "0x0101010101010101010101010101010101010101".parse().unwrap(),
+            1_000u64,
+            vec![0xde, 0xad, 0xbe, 0xef],
+            7u64,
+        )
+    }
+
+    #[test]
+    fn hashes_safe_tx() {
+        assert_eq!(
+            safe_tx().safe_tx_hash(),
+            "0xe82047281e3616e5562efc054783b0f5f8edfed31dcb744d40d7bfffb530d42d".parse().unwrap()
+        );
+        assert_eq!(
+            safe_tx().legacy_domain().safe_tx_hash(),
+            "0x68ddd012ce71b41cd929793533c5d75d137577885c93165e2a31d7e73462d0f2".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn recovers_owners() {
+        // signatures of the private key 0x01
+        let owner: Address = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf".parse().unwrap();
+        let hash = safe_tx().safe_tx_hash();
+        let eip712 = Signature {
+            r: U256::from_dec_str(
+                "73611180811771381461778580929049414138751661705373598364160311643875870655645",
+            )
+            .unwrap(),
+            s: U256::from_dec_str(
+                "41384326070459939240670472457904686473310793175333723780810896989047454714277",
+            )
+            .unwrap(),
+            v: 27,
+        };
+        let eth_sign = Signature {
+            r: U256::from_dec_str(
+                "92589563493741035187958476879213495298750585168255174970677012258724070393753",
+            )
+            .unwrap(),
+            s: U256::from_dec_str(
+                "22197447991511764652220799182825662521031495503132997406040948503466289237998",
+            )
+            .unwrap(),
+            v: 28,
+        };
+        assert_eq!(SafeSignature::Eip712(eip712).owner(hash).unwrap(), owner);
+        assert_eq!(SafeSignature::EthSign(eth_sign).owner(hash).unwrap(), owner);
+        assert_ne!(SafeSignature::EthSign(eip712).owner(hash).unwrap(), owner);
+    }
+
+    #[test]
+    fn encodes_signatures() {
+        let signature = Signature { r: 1.into(), s: 2.into(), v: 0 };
+        let (a, b, c) = (Address::repeat_byte(3), Address::repeat_byte(1), Address::repeat_byte(2));
+        let encoded = encode_safe_signatures(&[
+            (a, SafeSignature::EthSign(signature)),
+            (b, SafeSignature::Contract { owner: b, signature: vec![0xaa, 0xbb].into() }),
+            (c, SafeSignature::ApprovedHash(c)),
+        ]);
+        assert_eq!(encoded.len(), 3 * 65 + 32 + 2);
+
+        // contract signature of the lowest owner first, pointing at the dynamic part
+        assert_eq!(&encoded[12..32], b.as_bytes());
+        assert_eq!(U256::from_big_endian(&encoded[32..64]), U256::from(3 * 65));
+        assert_eq!(encoded[64], 0);
+        // approved hash
+        assert_eq!(&encoded[65 + 12..65 + 32], c.as_bytes());
+        assert_eq!(encoded[2 * 65 - 1], 1);
+        // eth_sign
+        assert_eq!(encoded[3 * 65 - 1], 31);
+        assert_eq!(&encoded[3 * 65..], &[&[0; 31][..], &[2, 0xaa, 0xbb]].concat()[..]);
+    }
+}
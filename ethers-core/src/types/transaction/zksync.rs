@@ -0,0 +1,474 @@
+//! zkSync Era's EIP-712 transactions, which can be sponsored by a paymaster and deploy contracts
+//! through their factory dependencies, see the
+//! [zkSync docs](https://era.zksync.io/docs/reference/concepts/transactions.html#eip-712-0x71).
+
+use super::{decode_to, eip1559::Eip1559TransactionRequest, eip712::EIP712Domain, rlp_opt};
+use crate::{
+    abi::{self, Token},
+    types::{
+        Address, Bytes, NameOrAddress, Signature, SignatureError, Transaction, H256, U256, U64,
+    },
+    utils::keccak256,
+};
+use rlp::{Decodable, DecoderError, RlpStream};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// The EIP-2718 type of zkSync Era's EIP-712 transactions
+pub const ZKSYNC_TX_TYPE: u8 = 0x71;
+
+/// The default limit of the gas paid per byte of published data
+pub const DEFAULT_GAS_PER_PUBDATA_LIMIT: u64 = 50_000;
+
+/// zkSync transactions have 16 fields
+const ZKSYNC_NUM_TX_FIELDS: usize = 16;
+
+/// The EIP-712 type of zkSync transactions
+const TRANSACTION_TYPE: &str =
+    "Transaction(uint256 txType,uint256 from,uint256 to,uint256 gasLimit,\
+uint256 gasPerPubdataByteLimit,uint256 maxFeePerGas,uint256 maxPriorityFeePerGas,uint256 paymaster,\
+uint256 nonce,uint256 value,bytes data,bytes32[] factoryDeps,bytes paymasterInput)";
+
+/// An error involving a zkSync transaction request.
+#[derive(Debug, Error)]
+pub enum ZkSyncRequestError {
+    /// When decoding a transaction request from RLP
+    #[error(transparent)]
+    DecodingError(#[from] rlp::DecoderError),
+    /// When recovering the address from a signature
+    #[error(transparent)]
+    RecoveryError(#[from] SignatureError),
+    /// When hashing a factory dependency which is not valid zkEVM bytecode
+    #[error("invalid bytecode: {0}")]
+    InvalidBytecode(&'static str),
+}
+
+/// The paymaster which pays the transaction's fees and the input it is called with
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymasterParams {
+    /// The address of the paymaster
+    pub paymaster: Address,
+    /// The input passed to the paymaster, usually encoding one of its flows
+    pub paymaster_input: Bytes,
+}
+
+impl PaymasterParams {
+    pub fn new<T: Into<Bytes>>(paymaster: Address, paymaster_input: T) -> Self {
+        Self { paymaster, paymaster_input: paymaster_input.into() }
+    }
+}
+
+/// The zkSync specific fields of a transaction
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Meta {
+    /// The maximum amount of gas paid per byte of data published to L1
+    pub gas_per_pubdata: U256,
+
+    /// The bytecode of the contracts the transaction may deploy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub factory_deps: Vec<Bytes>,
+
+    /// The signature validated by a smart contract account, used instead of the ECDSA signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_signature: Option<Bytes>,
+
+    /// The paymaster sponsoring the transaction (None if the sender pays)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster_params: Option<PaymasterParams>,
+}
+
+impl Default for Eip712Meta {
+    fn default() -> Self {
+        Self {
+            gas_per_pubdata: DEFAULT_GAS_PER_PUBDATA_LIMIT.into(),
+            factory_deps: Vec::new(),
+            custom_signature: None,
+            paymaster_params: None,
+        }
+    }
+}
+
+/// A zkSync Era transaction: an EIP-1559 transaction with zkSync's [`Eip712Meta`], which is signed
+/// as EIP-712 typed data instead of over its RLP encoding.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ZkSyncTransactionRequest {
+    #[serde(flatten)]
+    pub tx: Eip1559TransactionRequest,
+
+    /// The zkSync specific fields
+    #[serde(rename = "eip712Meta", default)]
+    pub meta: Eip712Meta,
+}
+
+impl ZkSyncTransactionRequest {
+    pub fn new(tx: Eip1559TransactionRequest, meta: Eip712Meta) -> Self {
+        Self { tx, meta }
+    }
+
+    /// Sets the `gas_per_pubdata` field in the transaction to the provided value
+    #[must_use]
+    pub fn gas_per_pubdata<T: Into<U256>>(mut self, gas_per_pubdata: T) -> Self {
+        self.meta.gas_per_pubdata = gas_per_pubdata.into();
+        self
+    }
+
+    /// Sets the `factory_deps` field in the transaction to the provided value
+    #[must_use]
+    pub fn factory_deps<T: Into<Bytes>>(
+        mut self,
+        factory_deps: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.meta.factory_deps = factory_deps.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the `custom_signature` field in the transaction to the provided value
+    #[must_use]
+    pub fn custom_signature<T: Into<Bytes>>(mut self, custom_signature: T) -> Self {
+        self.meta.custom_signature = Some(custom_signature.into());
+        self
+    }
+
+    /// Sets the `paymaster_params` field in the transaction to the provided value
+    #[must_use]
+    pub fn paymaster_params(mut self, paymaster_params: PaymasterParams) -> Self {
+        self.meta.paymaster_params = Some(paymaster_params);
+        self
+    }
+
+    /// Returns the EIP-712 hash of the transaction, which is what the sender signs.
+    ///
+    /// Factory dependencies which aren't valid bytecode are hashed as zero and rejected by the
+    /// node, check them with [`hash_bytecode`] beforehand.
+    pub fn sighash(&self) -> H256 {
+        let domain = EIP712Domain {
+            name: Some("zkSync".to_string()),
+            version: Some("2".to_string()),
+            chain_id: Some(self.tx.chain_id.unwrap_or_default().as_u64().into()),
+            verifying_contract: None,
+            salt: None,
+        };
+
+        let mut digest = [0u8; 66];
+        digest[0] = 0x19;
+        digest[1] = 0x01;
+        digest[2..34].copy_from_slice(&domain.separator());
+        digest[34..].copy_from_slice(&self.struct_hash());
+        keccak256(digest).into()
+    }
+
+    fn struct_hash(&self) -> [u8; 32] {
+        let tx = &self.tx;
+        let address =
+            |address: Option<&Address>| Token::Address(address.copied().unwrap_or_default());
+        let uint = |value: Option<&U256>| Token::Uint(value.copied().unwrap_or_default());
+
+        let factory_deps: Vec<u8> = self
+            .meta
+            .factory_deps
+            .iter()
+            .flat_map(|dep| hash_bytecode(dep).unwrap_or_default().0)
+            .collect();
+        let (paymaster, paymaster_input) = match self.meta.paymaster_params {
+            Some(ref params) => (Some(&params.paymaster), params.paymaster_input.as_ref()),
+            None => (None, &[][..]),
+        };
+
+        keccak256(abi::encode(&[
+            Token::FixedBytes(keccak256(TRANSACTION_TYPE).to_vec()),
+            Token::Uint(ZKSYNC_TX_TYPE.into()),
+            address(tx.from.as_ref()),
+            address(tx.to.as_ref().and_then(NameOrAddress::as_address)),
+            uint(tx.gas.as_ref()),
+            Token::Uint(self.meta.gas_per_pubdata),
+            uint(tx.max_fee_per_gas.as_ref()),
+            uint(tx.max_priority_fee_per_gas.as_ref()),
+            address(paymaster),
+            Token::Uint(tx.nonce.unwrap_or_default()),
+            uint(tx.value.as_ref()),
+            Token::FixedBytes(keccak256(tx.data.as_deref().unwrap_or_default()).to_vec()),
+            Token::FixedBytes(keccak256(factory_deps).to_vec()),
+            Token::FixedBytes(keccak256(paymaster_input).to_vec()),
+        ]))
+    }
+
+    /// Produces the RLP encoding of the unsigned transaction
+    pub fn rlp(&self) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(ZKSYNC_NUM_TX_FIELDS);
+        self.rlp_base(&mut rlp, None);
+        rlp.out().freeze().into()
+    }
+
+    /// Produces the RLP encoding of the transaction with the provided signature
+    pub fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(ZKSYNC_NUM_TX_FIELDS);
+        self.rlp_base(&mut rlp, Some(signature));
+        rlp.out().freeze().into()
+    }
+
+    fn rlp_base(&self, rlp: &mut RlpStream, signature: Option<&Signature>) {
+        let tx = &self.tx;
+        let chain_id = tx.chain_id.unwrap_or_else(U64::one);
+        rlp_opt(rlp, &tx.nonce);
+        rlp_opt(rlp, &tx.max_priority_fee_per_gas);
+        rlp_opt(rlp, &tx.max_fee_per_gas);
+        rlp_opt(rlp, &tx.gas);
+        rlp_opt(rlp, &tx.to.as_ref());
+        rlp_opt(rlp, &tx.value);
+        rlp_opt(rlp, &tx.data.as_ref().map(|d| d.as_ref()));
+
+        // the signature takes the place of the chain id and two empty fields
+        let mut signature_bytes = None;
+        match signature {
+            Some(signature) => {
+                let y_parity = y_parity(signature.v, chain_id);
+                rlp.append(&y_parity);
+                rlp.append(&signature.r);
+                rlp.append(&signature.s);
+                signature_bytes = Some(Signature { v: y_parity + 27, ..*signature }.to_vec());
+            }
+            None => {
+                rlp.append(&chain_id);
+                rlp.append(&"");
+                rlp.append(&"");
+            }
+        }
+
+        rlp.append(&chain_id);
+        rlp_opt(rlp, &tx.from);
+        rlp.append(&self.meta.gas_per_pubdata);
+        rlp.begin_list(self.meta.factory_deps.len());
+        for dep in &self.meta.factory_deps {
+            rlp.append(&dep.as_ref());
+        }
+
+        // the custom signature takes precedence, otherwise the ECDSA signature is repeated
+        match (&self.meta.custom_signature, signature_bytes) {
+            (Some(custom_signature), _) => rlp.append(&custom_signature.as_ref()),
+            (None, Some(signature_bytes)) => rlp.append(&signature_bytes.as_slice()),
+            (None, None) => rlp.append(&""),
+        };
+
+        match self.meta.paymaster_params {
+            Some(ref params) => {
+                rlp.begin_list(2);
+                rlp.append(&params.paymaster);
+                rlp.append(&params.paymaster_input.as_ref());
+            }
+            None => {
+                rlp.begin_list(0);
+            }
+        }
+    }
+
+    /// Decodes the fields of the request, skipping the ones the signature is encoded in.
+    pub fn decode_base_rlp(rlp: &rlp::Rlp, offset: &mut usize) -> Result<Self, DecoderError> {
+        let mut tx = Eip1559TransactionRequest::new();
+        tx.nonce = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.max_priority_fee_per_gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.max_fee_per_gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.gas = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.to = decode_to(rlp, offset)?.map(NameOrAddress::Address);
+        tx.value = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.data = decode_bytes(rlp, offset)?;
+        // the signature or the chain id and two empty fields
+        *offset += 3;
+        tx.chain_id = Some(rlp.val_at(*offset)?);
+        *offset += 1;
+        tx.from = decode_to(rlp, offset)?;
+
+        let mut meta = Eip712Meta { gas_per_pubdata: rlp.val_at(*offset)?, ..Default::default() };
+        *offset += 1;
+        meta.factory_deps = rlp
+            .at(*offset)?
+            .iter()
+            .map(|dep| dep.data().map(|data| Bytes::from(data.to_vec())))
+            .collect::<Result<_, _>>()?;
+        *offset += 1;
+        meta.custom_signature = decode_bytes(rlp, offset)?;
+        let paymaster = rlp.at(*offset)?;
+        if paymaster.item_count()? == 2 {
+            meta.paymaster_params = Some(PaymasterParams {
+                paymaster: paymaster.val_at(0)?,
+                paymaster_input: Bytes::from(paymaster.at(1)?.data()?.to_vec()),
+            });
+        }
+        *offset += 1;
+
+        Ok(Self { tx, meta })
+    }
+
+    /// Decodes the given RLP into a transaction, attempting to decode its signature as well.
+    ///
+    /// The `custom_signature` is dropped if it only repeats the ECDSA signature.
+    pub fn decode_signed_rlp(rlp: &rlp::Rlp) -> Result<(Self, Signature), ZkSyncRequestError> {
+        let mut txn = Self::decode_base_rlp(rlp, &mut 0)?;
+        let sig = Signature { v: rlp.val_at(7)?, r: rlp.val_at(8)?, s: rlp.val_at(9)? };
+
+        let ecdsa_signature = Signature { v: sig.v + 27, ..sig }.to_vec();
+        if txn.meta.custom_signature.as_deref() == Some(&ecdsa_signature[..]) {
+            txn.meta.custom_signature = None;
+        }
+        if txn.tx.from.is_none() {
+            txn.tx.from = Some(sig.recover(txn.sighash())?);
+        }
+        Ok((txn, sig))
+    }
+}
+
+impl Decodable for ZkSyncTransactionRequest {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        Self::decode_base_rlp(rlp, &mut 0)
+    }
+}
+
+/// Get a ZkSyncTransactionRequest from a Transaction
+impl From<&Transaction> for ZkSyncTransactionRequest {
+    fn from(tx: &Transaction) -> ZkSyncTransactionRequest {
+        ZkSyncTransactionRequest { tx: tx.into(), meta: Default::default() }
+    }
+}
+
+/// Returns the hash zkSync identifies the `bytecode` of a contract by, which is the bytecode's
+/// SHA-256 hash with its first four bytes replaced by a version byte, a zero byte and the length
+/// of the bytecode in 32 byte words.
+///
+/// Fails unless the bytecode consists of an odd number of words, less than 2^16.
+pub fn hash_bytecode(bytecode: &[u8]) -> Result<H256, ZkSyncRequestError> {
+    if bytecode.len() % 32 != 0 {
+        return Err(ZkSyncRequestError::InvalidBytecode("length must be divisible by 32"))
+    }
+    let words = bytecode.len() / 32;
+    if words >= 1 << 16 {
+        return Err(ZkSyncRequestError::InvalidBytecode("must be shorter than 2^16 words"))
+    }
+    if words % 2 == 0 {
+        return Err(ZkSyncRequestError::InvalidBytecode("must have an odd number of words"))
+    }
+
+    let mut hash: [u8; 32] = Sha256::digest(bytecode).into();
+    hash[0] = 1;
+    hash[1] = 0;
+    hash[2..4].copy_from_slice(&(words as u16).to_be_bytes());
+    Ok(hash.into())
+}
+
+/// Returns the y-parity of `v`, which may be EIP-155 encoded or offset by 27
+fn y_parity(v: u64, chain_id: U64) -> u64 {
+    match v {
+        0 | 1 => v,
+        27 | 28 => v - 27,
+        _ => super::normalize_v(v, chain_id),
+    }
+}
+
+fn decode_bytes(rlp: &rlp::Rlp, offset: &mut usize) -> Result<Option<Bytes>, DecoderError> {
+    let data = rlp.at(*offset)?.data()?;
+    *offset += 1;
+    Ok(match data.len() {
+        0 => None,
+        _ => Some(Bytes::from(data.to_vec())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        k256::ecdsa::SigningKey, types::transaction::eip2718::TypedTransaction,
+        utils::secret_key_to_address,
+    };
+
+    fn sign(key: &SigningKey, hash: H256, chain_id: u64) -> Signature {
+        let (signature, recovery_id) = key.sign_prehash_recoverable(hash.as_bytes()).unwrap();
+        Signature {
+            r: U256::from_big_endian(&signature.r().to_bytes()),
+            s: U256::from_big_endian(&signature.s().to_bytes()),
+            // EIP-155 encoded, like the signatures of wallets
+            v: u8::from(recovery_id) as u64 + 35 + chain_id * 2,
+        }
+    }
+
+    fn zksync_tx(from: Address) -> ZkSyncTransactionRequest {
+        let tx = Eip1559TransactionRequest::new()
+            .from(from)
+            .to(Address::repeat_byte(0x11))
+            .value(1_000u64)
+            .data(vec![0xab, 0xcd])
+            .nonce(3)
+            .gas(1_000_000)
+            .max_fee_per_gas(250_000_000u64)
+            .max_priority_fee_per_gas(0u64)
+            .chain_id(324);
+        ZkSyncTransactionRequest::new(tx, Default::default())
+    }
+
+    #[test]
+    fn hashes_bytecode() {
+        let hash = hash_bytecode(&[0u8; 32]).unwrap();
+        assert_eq!(hash[..4], [1, 0, 0, 1]);
+        assert_eq!(hash[4..], Sha256::digest([0u8; 32])[4..]);
+
+        assert!(hash_bytecode(&[0u8; 31]).is_err());
+        assert!(hash_bytecode(&[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn sighash_covers_paymaster() {
+        let from = Address::repeat_byte(0xaa);
+        let tx = zksync_tx(from);
+        let sponsored = tx.clone().paymaster_params(PaymasterParams::new(
+            Address::repeat_byte(0xbb),
+            vec![0x8c, 0x5a, 0x34, 0x45],
+        ));
+        assert_ne!(tx.sighash(), sponsored.sighash());
+        assert_ne!(tx.sighash(), tx.clone().gas_per_pubdata(800).sighash());
+        // the sighash doesn't depend on the RLP encoding of the transaction
+        assert_ne!(tx.sighash(), H256(keccak256(TypedTransaction::ZkSync(tx).rlp())));
+    }
+
+    #[test]
+    fn roundtrips_signed_zksync() {
+        let key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+        let from = secret_key_to_address(&key);
+        let tx: TypedTransaction = zksync_tx(from)
+            .factory_deps([vec![0x42u8; 96]])
+            .paymaster_params(PaymasterParams::new(Address::repeat_byte(0xbb), vec![1, 2, 3]))
+            .into();
+        assert_eq!(tx.rlp()[0], ZKSYNC_TX_TYPE);
+
+        let signature = sign(&key, tx.sighash(), 324);
+        let encoded = tx.rlp_signed(&signature);
+        let (decoded, decoded_signature) =
+            TypedTransaction::decode_signed(&rlp::Rlp::new(&encoded)).unwrap();
+
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded_signature.recover(tx.sighash()).unwrap(), from);
+        assert_eq!(decoded.rlp_signed(&decoded_signature), encoded);
+    }
+
+    #[test]
+    fn keeps_custom_signature() {
+        let key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+        let tx: TypedTransaction =
+            zksync_tx(Address::repeat_byte(0xaa)).custom_signature(vec![0xcc; 40]).into();
+
+        let signature = sign(&key, tx.sighash(), 324);
+        let encoded = tx.rlp_signed(&signature);
+        let (decoded, _) = TypedTransaction::decode_signed(&rlp::Rlp::new(&encoded)).unwrap();
+
+        let TypedTransaction::ZkSync(decoded) = decoded else { panic!("not a zkSync transaction") };
+        assert_eq!(decoded.meta.custom_signature, Some(vec![0xcc; 40].into()));
+        assert_eq!(decoded.tx.from, Some(Address::repeat_byte(0xaa)));
+    }
+}
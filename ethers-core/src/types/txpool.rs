@@ -4,6 +4,7 @@ use serde::{
     Deserialize, Serialize,
 };
 use std::{collections::BTreeMap, fmt, str::FromStr};
+use thiserror::Error;
 
 /// Transaction summary as found in the Txpool Inspection property.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,33 +42,54 @@ impl<'de> Visitor<'de> for TxpoolInspectSummaryVisitor {
     where
         E: de::Error,
     {
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Error returned when a txpool inspection summary does not have the format
+/// `to: value wei + gasLimit gas × gas_price wei`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid format for TxpoolInspectSummary: {0}")]
+pub struct ParseTxpoolInspectSummaryError(String);
+
+/// Parses a txpool inspection summary as returned by `txpool_inspect`.
+impl FromStr for TxpoolInspectSummary {
+    type Err = ParseTxpoolInspectSummaryError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let err = |field: &str| ParseTxpoolInspectSummaryError(field.to_string());
+
         let addr_split: Vec<&str> = value.split(": ").collect();
         if addr_split.len() != 2 {
-            return Err(de::Error::custom("invalid format for TxpoolInspectSummary: to"))
+            return Err(err("to"))
         }
         let value_split: Vec<&str> = addr_split[1].split(" wei + ").collect();
         if value_split.len() != 2 {
-            return Err(de::Error::custom("invalid format for TxpoolInspectSummary: gasLimit"))
+            return Err(err("gasLimit"))
         }
         let gas_split: Vec<&str> = value_split[1].split(" gas × ").collect();
         if gas_split.len() != 2 {
-            return Err(de::Error::custom("invalid format for TxpoolInspectSummary: gas"))
+            return Err(err("gas"))
         }
         let gas_price_split: Vec<&str> = gas_split[1].split(" wei").collect();
         if gas_price_split.len() != 2 {
-            return Err(de::Error::custom("invalid format for TxpoolInspectSummary: gas_price"))
+            return Err(err("gas_price"))
         }
         let addr = match addr_split[0] {
             "" => None,
             "0x" => None,
             "contract creation" => None,
-            addr => {
-                Some(Address::from_str(addr.trim_start_matches("0x")).map_err(de::Error::custom)?)
-            }
+            addr => Some(
+                Address::from_str(addr.trim_start_matches("0x"))
+                    .map_err(|e| ParseTxpoolInspectSummaryError(e.to_string()))?,
+            ),
+        };
+        let parse = |value: &str| {
+            U256::from_dec_str(value).map_err(|e| ParseTxpoolInspectSummaryError(e.to_string()))
         };
-        let value = U256::from_dec_str(value_split[0]).map_err(de::Error::custom)?;
-        let gas = U256::from_dec_str(gas_split[0]).map_err(de::Error::custom)?;
-        let gas_price = U256::from_dec_str(gas_price_split[0]).map_err(de::Error::custom)?;
+        let value = parse(value_split[0])?;
+        let gas = parse(gas_split[0])?;
+        let gas_price = parse(gas_price_split[0])?;
 
         Ok(TxpoolInspectSummary { to: addr, value, gas, gas_price })
     }
@@ -118,6 +140,37 @@ pub struct TxpoolContent {
     pub queued: BTreeMap<Address, BTreeMap<String, Transaction>>,
 }
 
+impl TxpoolContent {
+    /// Returns the pending and queued transactions of `sender`, like `txpool_contentFrom`.
+    pub fn content_from(&self, sender: &Address) -> TxpoolContentFrom {
+        TxpoolContentFrom {
+            pending: self.pending.get(sender).cloned().unwrap_or_default(),
+            queued: self.queued.get(sender).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// Transaction Pool Content From
+///
+/// Same as [`TxpoolContent`], but for the transactions of a single sender, keyed by nonce.
+///
+/// See [here](https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-contentfrom) for more details
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolContentFrom {
+    /// pending tx
+    pub pending: BTreeMap<String, Transaction>,
+    /// queued tx
+    pub queued: BTreeMap<String, Transaction>,
+}
+
+impl TxpoolContentFrom {
+    /// Returns the pending or queued transaction with the given nonce.
+    pub fn get(&self, nonce: u64) -> Option<&Transaction> {
+        let nonce = nonce.to_string();
+        self.pending.get(&nonce).or_else(|| self.queued.get(&nonce))
+    }
+}
+
 /// Transaction Pool Inspect
 ///
 /// The inspect inspection property can be queried to list a textual summary
@@ -329,6 +382,11 @@ mod tests {
         let serialized_value = serde_json::to_value(deserialized.clone()).unwrap();
         assert_eq!(origin, serialized_value);
         assert_eq!(deserialized, serde_json::from_str::<TxpoolContent>(&serialized).unwrap());
+
+        let sender: Address = "0x00000000863b56a3c1f0f1be8bc4f8b7bd78f57a".parse().unwrap();
+        let content_from = deserialized.content_from(&sender);
+        assert_eq!(content_from.get(29).map(|tx| tx.from), Some(sender));
+        assert!(deserialized.content_from(&Address::zero()).get(29).is_none());
     }
 
     #[test]
@@ -367,6 +425,21 @@ mod tests {
         assert_eq!(deserialized2, deserialized);
     }
 
+    #[test]
+    fn parse_txpool_inspect_summary() {
+        let summary: TxpoolInspectSummary =
+            "contract creation: 0 wei + 612412 gas × 6000000000 wei".parse().unwrap();
+        assert_eq!(summary.to, None);
+        assert_eq!(summary.gas, 612412.into());
+        assert_eq!(summary.gas_price, 6000000000u64.into());
+
+        let err = "0x73Aaf691bc33fe38f86260338EF88f9897eCaa4F: 1 wei"
+            .parse::<TxpoolInspectSummary>()
+            .unwrap_err();
+        assert_eq!(err, ParseTxpoolInspectSummaryError("gasLimit".to_string()));
+        assert!("0x1234: 1 wei + 21000 gas × 1 wei".parse::<TxpoolInspectSummary>().is_err());
+    }
+
     #[test]
     fn serde_txpool_status() {
         let txpool_status_json = r#"
@@ -1,4 +1,101 @@
-use ethabi::ethereum_types::U256;
+use ethabi::ethereum_types::{U256, U512};
+
+/// `1e18`, the unit of 18 decimals fixed-point numbers.
+pub const WAD: U256 = U256([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// `1e27`, the unit of 27 decimals fixed-point numbers.
+pub const RAY: U256 = U256([11_515_845_246_265_065_472, 54_210_108, 0, 0]);
+
+/// Full precision multiplication and division, and fixed-point arithmetic for [`U256`].
+///
+/// All methods compute the product with a 512-bit intermediate value, so they only fail if the
+/// final result does not fit in a [`U256`] or if the divisor is zero.
+///
+/// # Examples
+///
+/// ```
+/// use ethers_core::{
+///     types::{U256, U256Ext, WAD},
+///     utils::parse_ether,
+/// };
+///
+/// // 1.5 * 2.5 = 3.75
+/// let product = parse_ether("1.5").unwrap().wad_mul(parse_ether("2.5").unwrap());
+/// assert_eq!(product, Some(parse_ether("3.75").unwrap()));
+///
+/// // no overflow of the intermediate product
+/// assert_eq!(U256::MAX.mul_div(WAD, WAD), Some(U256::MAX));
+/// ```
+pub trait U256Ext: Sized {
+    /// Computes `self * b / denominator`, rounding down.
+    ///
+    /// Returns `None` if `denominator` is zero or the result overflows.
+    fn mul_div(self, b: U256, denominator: U256) -> Option<U256>;
+
+    /// Computes `self * b / denominator`, rounding up.
+    ///
+    /// Returns `None` if `denominator` is zero or the result overflows.
+    fn mul_div_up(self, b: U256, denominator: U256) -> Option<U256>;
+
+    /// Multiplies two [`WAD`] fixed-point numbers, rounding down.
+    fn wad_mul(self, b: U256) -> Option<U256> {
+        self.mul_div(b, WAD)
+    }
+
+    /// Multiplies two [`WAD`] fixed-point numbers, rounding up.
+    fn wad_mul_up(self, b: U256) -> Option<U256> {
+        self.mul_div_up(b, WAD)
+    }
+
+    /// Divides two [`WAD`] fixed-point numbers, rounding down.
+    fn wad_div(self, b: U256) -> Option<U256> {
+        self.mul_div(WAD, b)
+    }
+
+    /// Divides two [`WAD`] fixed-point numbers, rounding up.
+    fn wad_div_up(self, b: U256) -> Option<U256> {
+        self.mul_div_up(WAD, b)
+    }
+
+    /// Multiplies two [`RAY`] fixed-point numbers, rounding down.
+    fn ray_mul(self, b: U256) -> Option<U256> {
+        self.mul_div(b, RAY)
+    }
+
+    /// Multiplies two [`RAY`] fixed-point numbers, rounding up.
+    fn ray_mul_up(self, b: U256) -> Option<U256> {
+        self.mul_div_up(b, RAY)
+    }
+
+    /// Divides two [`RAY`] fixed-point numbers, rounding down.
+    fn ray_div(self, b: U256) -> Option<U256> {
+        self.mul_div(RAY, b)
+    }
+
+    /// Divides two [`RAY`] fixed-point numbers, rounding up.
+    fn ray_div_up(self, b: U256) -> Option<U256> {
+        self.mul_div_up(RAY, b)
+    }
+}
+
+impl U256Ext for U256 {
+    fn mul_div(self, b: U256, denominator: U256) -> Option<U256> {
+        if denominator.is_zero() {
+            return None
+        }
+        U256::try_from(self.full_mul(b) / U512::from(denominator)).ok()
+    }
+
+    fn mul_div_up(self, b: U256, denominator: U256) -> Option<U256> {
+        if denominator.is_zero() {
+            return None
+        }
+        let (quotient, remainder) = self.full_mul(b).div_mod(U512::from(denominator));
+        // the product is at most `(2^256 - 1)^2`, so this can't overflow
+        let quotient = if remainder.is_zero() { quotient } else { quotient + 1 };
+        U256::try_from(quotient).ok()
+    }
+}
 
 /// Convert a floating point value to its nearest f64 integer.
 ///
@@ -52,6 +149,47 @@ mod tests {
     use super::*;
     use std::f64;
 
+    #[test]
+    fn test_fixed_point_units() {
+        assert_eq!(WAD, U256::exp10(18));
+        assert_eq!(RAY, U256::exp10(27));
+    }
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(U256::from(7).mul_div(3.into(), 2.into()), Some(10.into()));
+        assert_eq!(U256::from(7).mul_div_up(3.into(), 2.into()), Some(11.into()));
+        assert_eq!(U256::from(6).mul_div_up(3.into(), 2.into()), Some(9.into()));
+        assert_eq!(U256::MAX.mul_div(U256::MAX, U256::MAX), Some(U256::MAX));
+        assert_eq!(U256::MAX.mul_div_up(U256::MAX, U256::MAX), Some(U256::MAX));
+        assert_eq!(U256::MAX.mul_div(2.into(), 3.into()), Some(U256::MAX / 3 * 2));
+
+        // overflow and division by zero
+        assert_eq!(U256::MAX.mul_div(2.into(), 1.into()), None);
+        assert_eq!(U256::MAX.mul_div_up(U256::MAX, U256::MAX - 1), None);
+        assert_eq!(U256::one().mul_div(1.into(), U256::zero()), None);
+        assert_eq!(U256::one().mul_div_up(1.into(), U256::zero()), None);
+    }
+
+    #[test]
+    fn test_wad_ray() {
+        let half_wad = WAD / 2;
+        assert_eq!(half_wad.wad_mul(half_wad), Some(WAD / 4));
+        assert_eq!(WAD.wad_div(3.into()), Some(WAD * WAD / 3));
+        assert_eq!(WAD.wad_div_up(U256::from(3) * WAD), Some(WAD / 3 + 1));
+        assert_eq!(U256::one().wad_mul(U256::one()), Some(U256::zero()));
+        assert_eq!(U256::one().wad_mul_up(U256::one()), Some(U256::one()));
+        assert_eq!(WAD.wad_div(U256::zero()), None);
+
+        assert_eq!(RAY.ray_mul(U256::from(3) * RAY), Some(U256::from(3) * RAY));
+        assert_eq!(RAY.ray_div(U256::from(3) * RAY), Some(RAY / 3));
+        assert_eq!(RAY.ray_div_up(U256::from(3) * RAY), Some(RAY / 3 + 1));
+        assert_eq!(U256::one().ray_mul_up(U256::one()), Some(U256::one()));
+        assert_eq!(U256::MAX.ray_mul(RAY), Some(U256::MAX));
+        assert_eq!(U256::MAX.ray_div(RAY), Some(U256::MAX));
+        assert_eq!(U256::MAX.ray_mul(RAY + 1), None);
+    }
+
     #[test]
     fn test_small_integers() {
         for i in 0..=255 {
@@ -0,0 +1,378 @@
+//! Disassembly of EVM bytecode and extraction of the Solidity metadata tail.
+
+use crate::types::{Bytes, Opcode, Selector};
+use std::{collections::BTreeSet, fmt};
+
+/// A single decoded EVM instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    /// The offset of this instruction in the bytecode.
+    pub pc: usize,
+    /// The raw opcode byte.
+    pub byte: u8,
+    /// The decoded opcode, `None` if the byte is not a known opcode.
+    pub opcode: Option<Opcode>,
+    /// The immediate data of a `PUSHn` instruction.
+    ///
+    /// This may be shorter than `n` bytes if the bytecode is truncated.
+    pub immediate: Option<Bytes>,
+}
+
+impl Instruction {
+    /// Returns the number of bytes this instruction occupies in the bytecode.
+    pub fn size(&self) -> usize {
+        1 + self.immediate.as_ref().map(|data| data.len()).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#06x} ", self.pc)?;
+        match self.opcode {
+            Some(opcode) => write!(f, "{opcode}")?,
+            None => write!(f, "INVALID({:#04x})", self.byte)?,
+        }
+        if let Some(ref immediate) = self.immediate {
+            write!(f, " {immediate}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The metadata that the Solidity compiler appends to the runtime bytecode.
+///
+/// Ref: <https://docs.soliditylang.org/en/latest/metadata.html#encoding-of-the-metadata-hash-in-the-bytecode>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BytecodeMetadata {
+    /// The IPFS multihash of the metadata file.
+    pub ipfs: Option<Bytes>,
+    /// The Swarm hash of the metadata file (legacy `bzzr0` format).
+    pub bzzr0: Option<Bytes>,
+    /// The Swarm hash of the metadata file (`bzzr1` format).
+    pub bzzr1: Option<Bytes>,
+    /// The compiler version, e.g. `0.8.17` or a prerelease string.
+    pub solc: Option<String>,
+    /// Whether experimental compiler features were used.
+    pub experimental: bool,
+}
+
+/// The result of disassembling EVM bytecode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Disassembly {
+    /// The decoded instructions, excluding the metadata tail.
+    pub instructions: Vec<Instruction>,
+    /// The offsets of all valid jump destinations.
+    pub jumpdests: BTreeSet<usize>,
+    /// The decoded metadata tail, if present.
+    pub metadata: Option<BytecodeMetadata>,
+}
+
+impl Disassembly {
+    /// Returns `true` if `pc` is a valid jump destination.
+    pub fn is_jumpdest(&self, pc: usize) -> bool {
+        self.jumpdests.contains(&pc)
+    }
+
+    /// Returns the function selectors found in the dispatch table.
+    ///
+    /// The dispatcher emitted by solc and vyper compares the selector with `PUSH4 <selector>`
+    /// followed by an `EQ` (optionally preceded by a `DUPn`), and jumps to the function body with
+    /// `PUSHn <dest> JUMPI`. Selectors are returned in the order they appear.
+    pub fn selectors(&self) -> Vec<Selector> {
+        let mut seen = BTreeSet::new();
+        let mut selectors = Vec::new();
+        for (idx, instruction) in self.instructions.iter().enumerate() {
+            if instruction.opcode != Some(Opcode::PUSH4) {
+                continue
+            }
+            let Some(ref immediate) = instruction.immediate else { continue };
+            let Ok(selector) = Selector::try_from(immediate.as_ref()) else { continue };
+
+            let mut rest = self.instructions[idx + 1..].iter().filter_map(|i| i.opcode);
+            let mut next = rest.next();
+            if matches!(next, Some(op) if is_dup(op)) {
+                next = rest.next();
+            }
+            if next != Some(Opcode::EQ) {
+                continue
+            }
+            if !matches!(rest.next(), Some(op) if push_size(op as u8) > 0) {
+                continue
+            }
+            if rest.next() != Some(Opcode::JUMPI) {
+                continue
+            }
+
+            if seen.insert(selector) {
+                selectors.push(selector);
+            }
+        }
+        selectors
+    }
+}
+
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for instruction in &self.instructions {
+            writeln!(f, "{instruction}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Disassembles the given bytecode.
+///
+/// The Solidity metadata tail is stripped before decoding and returned separately in
+/// [`Disassembly::metadata`].
+///
+/// # Example
+///
+/// ```
+/// use ethers_core::{types::Opcode, utils::disassemble};
+///
+/// let disassembly = disassemble([0x60, 0x80, 0x60, 0x40, 0x52]);
+/// assert_eq!(disassembly.instructions.len(), 3);
+/// assert_eq!(disassembly.instructions[2].opcode, Some(Opcode::MSTORE));
+/// assert_eq!(disassembly.to_string(), "0x0000 PUSH1 0x80\n0x0002 PUSH1 0x40\n0x0004 MSTORE\n");
+/// ```
+pub fn disassemble(code: impl AsRef<[u8]>) -> Disassembly {
+    let (code, metadata) = split_metadata(code.as_ref());
+
+    let mut instructions = Vec::new();
+    let mut jumpdests = BTreeSet::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let byte = code[pc];
+        let opcode = Opcode::try_from(byte).ok();
+        let size = push_size(byte);
+        let immediate = (size > 0).then(|| {
+            let end = (pc + 1 + size).min(code.len());
+            Bytes::from(code[pc + 1..end].to_vec())
+        });
+        if opcode == Some(Opcode::JUMPDEST) {
+            jumpdests.insert(pc);
+        }
+
+        let instruction = Instruction { pc, byte, opcode, immediate };
+        pc += instruction.size();
+        instructions.push(instruction);
+    }
+
+    Disassembly { instructions, jumpdests, metadata }
+}
+
+/// Splits the CBOR encoded metadata tail off the given bytecode.
+///
+/// Returns the bytecode without the metadata and the decoded metadata, or the unchanged bytecode
+/// and `None` if no valid metadata tail could be found.
+pub fn split_metadata(code: &[u8]) -> (&[u8], Option<BytecodeMetadata>) {
+    let Some(len_bytes) = code.len().checked_sub(2).map(|start| &code[start..]) else {
+        return (code, None)
+    };
+    let len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+    let Some(start) = code.len().checked_sub(2 + len) else { return (code, None) };
+
+    match decode_metadata(&code[start..code.len() - 2]) {
+        Some(metadata) => (&code[..start], Some(metadata)),
+        None => (code, None),
+    }
+}
+
+/// Returns the number of immediate bytes of the `PUSHn` opcode, 0 for all other opcodes.
+fn push_size(byte: u8) -> usize {
+    match byte {
+        0x60..=0x7f => (byte - 0x5f) as usize,
+        _ => 0,
+    }
+}
+
+fn is_dup(opcode: Opcode) -> bool {
+    (Opcode::DUP1 as u8..=Opcode::DUP16 as u8).contains(&(opcode as u8))
+}
+
+/// Decodes the subset of CBOR emitted by solc: a map of text keys to byte strings, text strings
+/// or booleans.
+fn decode_metadata(data: &[u8]) -> Option<BytecodeMetadata> {
+    let mut reader = CborReader { data, pos: 0 };
+    let (major, entries) = reader.header()?;
+    if major != 5 {
+        return None
+    }
+
+    let mut metadata = BytecodeMetadata::default();
+    for _ in 0..entries {
+        let key = reader.text()?;
+        match key {
+            "ipfs" => metadata.ipfs = Some(reader.bytes()?.to_vec().into()),
+            "bzzr0" => metadata.bzzr0 = Some(reader.bytes()?.to_vec().into()),
+            "bzzr1" => metadata.bzzr1 = Some(reader.bytes()?.to_vec().into()),
+            "solc" => metadata.solc = Some(reader.version()?),
+            "experimental" => metadata.experimental = reader.bool()?,
+            _ => reader.skip()?,
+        }
+    }
+
+    (reader.pos == data.len()).then_some(metadata)
+}
+
+struct CborReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CborReader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    /// Reads an item header, returning the major type and its argument.
+    fn header(&mut self) -> Option<(u8, usize)> {
+        let initial = self.byte()?;
+        let major = initial >> 5;
+        let arg = match initial & 0x1f {
+            n @ 0..=23 => n as usize,
+            24 => self.byte()? as usize,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().ok()?) as usize,
+            _ => return None,
+        };
+        Some((major, arg))
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        match self.header()? {
+            (2, len) => self.take(len),
+            _ => None,
+        }
+    }
+
+    fn text(&mut self) -> Option<&'a str> {
+        match self.header()? {
+            (3, len) => std::str::from_utf8(self.take(len)?).ok(),
+            _ => None,
+        }
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.byte()? {
+            0xf4 => Some(false),
+            0xf5 => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Release builds encode the version as 3 bytes, prereleases as a text string.
+    fn version(&mut self) -> Option<String> {
+        match self.header()? {
+            (2, 3) => {
+                let v = self.take(3)?;
+                Some(format!("{}.{}.{}", v[0], v[1], v[2]))
+            }
+            (3, len) => std::str::from_utf8(self.take(len)?).ok().map(str::to_string),
+            _ => None,
+        }
+    }
+
+    fn skip(&mut self) -> Option<()> {
+        if matches!(self.data.get(self.pos)?, 0xf4 | 0xf5) {
+            self.pos += 1;
+            return Some(())
+        }
+        match self.header()? {
+            (0 | 1, _) => {}
+            (2 | 3, len) => {
+                self.take(len)?;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    // `ipfs` + `solc` 0.8.17 metadata as emitted by solc
+    const METADATA: [u8; 53] = hex!(
+        "a26469706673582212202d711642b726b04401627ca9fbac32f5c8530fb1903cc4db02258717921a4881"
+        "64736f6c63430008110033"
+    );
+
+    #[test]
+    fn decodes_instructions() {
+        let disassembly = disassemble(hex!("6080604052348015600f57600080fd5b5b00"));
+        let ops: Vec<_> = disassembly.instructions.iter().map(|i| i.opcode.unwrap()).collect();
+        assert_eq!(
+            ops,
+            [
+                Opcode::PUSH1,
+                Opcode::PUSH1,
+                Opcode::MSTORE,
+                Opcode::CALLVALUE,
+                Opcode::DUP1,
+                Opcode::ISZERO,
+                Opcode::PUSH1,
+                Opcode::JUMPI,
+                Opcode::PUSH1,
+                Opcode::DUP1,
+                Opcode::REVERT,
+                Opcode::JUMPDEST,
+                Opcode::JUMPDEST,
+                Opcode::STOP,
+            ]
+        );
+        assert_eq!(disassembly.jumpdests, BTreeSet::from([15, 16]));
+        assert!(disassembly.metadata.is_none());
+    }
+
+    #[test]
+    fn ignores_jumpdest_in_push_data() {
+        let disassembly = disassemble(hex!("605b5b"));
+        assert_eq!(disassembly.instructions.len(), 2);
+        assert!(!disassembly.is_jumpdest(1));
+        assert!(disassembly.is_jumpdest(2));
+    }
+
+    #[test]
+    fn handles_truncated_push() {
+        let disassembly = disassemble(hex!("00630102"));
+        assert_eq!(disassembly.instructions[1].immediate, Some(Bytes::from(vec![1, 2])));
+    }
+
+    #[test]
+    fn extracts_metadata() {
+        let mut code = hex!("6080604052").to_vec();
+        code.extend_from_slice(&METADATA);
+
+        let (stripped, metadata) = split_metadata(&code);
+        assert_eq!(stripped, hex!("6080604052"));
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.solc.as_deref(), Some("0.8.17"));
+        assert_eq!(metadata.ipfs.unwrap().len(), 34);
+        assert!(!metadata.experimental);
+
+        let disassembly = disassemble(&code);
+        assert_eq!(disassembly.instructions.len(), 3);
+        assert!(disassembly.metadata.is_some());
+    }
+
+    #[test]
+    fn detects_dispatch_selectors() {
+        // PUSH1 0xe0 SHR DUP1 PUSH4 0xa9059cbb EQ PUSH2 0x0030 JUMPI
+        // DUP1 PUSH4 0x70a08231 EQ PUSH2 0x0040 JUMPI PUSH4 0xdeadbeef POP
+        let code = hex!(
+            "60e01c8063a9059cbb14610030578063" "70a082311461004057" "63deadbeef50"
+        );
+        let selectors = disassemble(code).selectors();
+        assert_eq!(selectors, [hex!("a9059cbb"), hex!("70a08231")]);
+    }
+}
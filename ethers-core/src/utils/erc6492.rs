@@ -0,0 +1,183 @@
+use crate::{
+    abi::{self, ParamType, Token},
+    types::{Address, Bytes, Signature, H160, H256},
+};
+
+/// The suffix of EIP-6492 wrapped signatures.
+pub const ERC6492_MAGIC_SUFFIX: [u8; 32] = [
+    0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92,
+    0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92, 0x64, 0x92,
+];
+
+/// The address of the Multicall3 contract through which signatures of counterfactual accounts are
+/// validated, see [`erc6492_validation_call`].
+pub const ERC6492_VALIDATION_MULTICALL: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
+/// The EIP-1271 `isValidSignature(bytes32,bytes)` selector, which is also the magic value returned
+/// for valid signatures.
+pub const ERC1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// The `aggregate3((address,bool,bytes)[])` selector of Multicall3.
+const AGGREGATE3_SELECTOR: [u8; 4] = [0x82, 0xad, 0x56, 0xcb];
+
+/// An EIP-6492 signature of a smart contract account that may not be deployed yet.
+///
+/// It carries the factory call that deploys the account, so the signature can be validated with
+/// EIP-1271 before the deployment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Erc6492Signature {
+    /// The factory deploying the account
+    pub factory: Address,
+    /// The calldata of the factory call deploying the account
+    pub factory_calldata: Bytes,
+    /// The signature to validate with EIP-1271 once the account is deployed
+    pub signature: Bytes,
+}
+
+impl Erc6492Signature {
+    /// Wraps `signature` with the factory call deploying the account.
+    pub fn new(
+        factory: Address,
+        factory_calldata: impl Into<Bytes>,
+        signature: impl Into<Bytes>,
+    ) -> Self {
+        Self { factory, factory_calldata: factory_calldata.into(), signature: signature.into() }
+    }
+
+    /// Encodes the wrapped signature as `abi.encode(factory, factoryCalldata, signature)` followed
+    /// by [`ERC6492_MAGIC_SUFFIX`].
+    pub fn encode(&self) -> Bytes {
+        let mut encoded = abi::encode(&[
+            Token::Address(self.factory),
+            Token::Bytes(self.factory_calldata.to_vec()),
+            Token::Bytes(self.signature.to_vec()),
+        ]);
+        encoded.extend_from_slice(&ERC6492_MAGIC_SUFFIX);
+        encoded.into()
+    }
+
+    /// Decodes a wrapped signature, returning `None` if `signature` is not an EIP-6492 signature.
+    pub fn decode(signature: &[u8]) -> Option<Self> {
+        if !is_erc6492_signature(signature) {
+            return None
+        }
+        let data = &signature[..signature.len() - ERC6492_MAGIC_SUFFIX.len()];
+        let mut tokens =
+            abi::decode(&[ParamType::Address, ParamType::Bytes, ParamType::Bytes], data)
+                .ok()?
+                .into_iter();
+        let factory = tokens.next()?.into_address()?;
+        let factory_calldata = tokens.next()?.into_bytes()?;
+        let signature = tokens.next()?.into_bytes()?;
+        Some(Self::new(factory, factory_calldata, signature))
+    }
+}
+
+/// Returns `true` if `signature` ends with [`ERC6492_MAGIC_SUFFIX`].
+pub fn is_erc6492_signature(signature: &[u8]) -> bool {
+    signature.ends_with(&ERC6492_MAGIC_SUFFIX)
+}
+
+/// Returns the calldata for an `eth_call` to [`ERC6492_VALIDATION_MULTICALL`] that validates an
+/// EIP-6492 signature of a counterfactual account.
+///
+/// Within the same call, the account is deployed through its factory, which may fail if it was
+/// already deployed, and then `isValidSignature(hash, signature)` is called on it. The result is
+/// decoded with [`decode_erc6492_validation`].
+pub fn erc6492_validation_call(signer: Address, hash: H256, signature: &Erc6492Signature) -> Bytes {
+    let call = |target: Address, allow_failure: bool, data: Vec<u8>| {
+        Token::Tuple(vec![Token::Address(target), Token::Bool(allow_failure), Token::Bytes(data)])
+    };
+    let mut is_valid_signature = ERC1271_MAGIC_VALUE.to_vec();
+    is_valid_signature.extend(abi::encode(&[
+        Token::FixedBytes(hash.as_bytes().to_vec()),
+        Token::Bytes(signature.signature.to_vec()),
+    ]));
+
+    let mut data = AGGREGATE3_SELECTOR.to_vec();
+    data.extend(abi::encode(&[Token::Array(vec![
+        call(signature.factory, true, signature.factory_calldata.to_vec()),
+        call(signer, true, is_valid_signature),
+    ])]));
+    data.into()
+}
+
+/// Decodes the output of the [`erc6492_validation_call`], returning `true` if the signature is
+/// valid.
+pub fn decode_erc6492_validation(output: &[u8]) -> Result<bool, abi::Error> {
+    let results = abi::decode(
+        &[ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes])))],
+        output,
+    )?;
+    let is_valid = results
+        .into_iter()
+        .next()
+        .and_then(Token::into_array)
+        .and_then(|results| results.into_iter().nth(1))
+        .and_then(Token::into_tuple)
+        .and_then(|result| match result.as_slice() {
+            [Token::Bool(success), Token::Bytes(data)] => {
+                Some(*success && data.starts_with(&ERC1271_MAGIC_VALUE))
+            }
+            _ => None,
+        })
+        .ok_or(abi::Error::InvalidData)?;
+    Ok(is_valid)
+}
+
+/// Returns `true` if `signature` is a valid ECDSA signature of `hash` by `signer`.
+///
+/// This is the check for signers without code that did not wrap their signature.
+pub fn is_valid_ecdsa_signature(signer: Address, hash: H256, signature: &[u8]) -> bool {
+    match Signature::try_from(signature) {
+        Ok(signature) if matches!(signature.v, 0 | 1 | 27 | 28) => {
+            signature.verify(hash, signer).is_ok()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::id;
+
+    #[test]
+    fn wraps_signature() {
+        let signature =
+            Erc6492Signature::new(Address::repeat_byte(0x11), vec![1, 2, 3], vec![4; 65]);
+        let encoded = signature.encode();
+        assert!(is_erc6492_signature(&encoded));
+        assert_eq!(Erc6492Signature::decode(&encoded), Some(signature));
+        assert_eq!(Erc6492Signature::decode(&[4; 65]), None);
+    }
+
+    #[test]
+    fn selectors() {
+        assert_eq!(id("isValidSignature(bytes32,bytes)"), ERC1271_MAGIC_VALUE);
+        assert_eq!(id("aggregate3((address,bool,bytes)[])"), AGGREGATE3_SELECTOR);
+        assert_eq!(
+            ERC6492_VALIDATION_MULTICALL,
+            "0xcA11bde05977b3631167028862bE2a173976CA11".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn decodes_validation() {
+        let result = |success, data: Vec<u8>| {
+            abi::encode(&[Token::Array(vec![
+                Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![])]),
+                Token::Tuple(vec![Token::Bool(success), Token::Bytes(data)]),
+            ])])
+        };
+        let mut magic = ERC1271_MAGIC_VALUE.to_vec();
+        magic.resize(32, 0);
+        assert!(decode_erc6492_validation(&result(true, magic.clone())).unwrap());
+        assert!(!decode_erc6492_validation(&result(false, magic)).unwrap());
+        assert!(!decode_erc6492_validation(&result(true, vec![0; 32])).unwrap());
+        assert!(decode_erc6492_validation(&[]).is_err());
+    }
+}
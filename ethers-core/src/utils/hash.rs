@@ -1,6 +1,6 @@
 //! Various utilities for manipulating Ethereum related data.
 
-use ethabi::ethereum_types::H256;
+use ethabi::ethereum_types::{Address, H256};
 use tiny_keccak::{Hasher, Keccak};
 
 /// Hash a message according to [EIP-191] (version `0x01`).
@@ -26,6 +26,24 @@ pub fn hash_message<T: AsRef<[u8]>>(message: T) -> H256 {
     H256(keccak256(&eth_message))
 }
 
+/// Hash data for an intended validator according to [EIP-191] (version `0x00`).
+///
+/// The final message is encoded as follows:
+/// `0x19 0x00 + validator address + data`
+///
+/// This message is then hashed using [Keccak-256](keccak256).
+///
+/// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+pub fn hash_intended_validator<T: AsRef<[u8]>>(validator: Address, data: T) -> H256 {
+    let data = data.as_ref();
+    let mut message = Vec::with_capacity(2 + 20 + data.len());
+    message.extend_from_slice(&[0x19, 0x00]);
+    message.extend_from_slice(validator.as_bytes());
+    message.extend_from_slice(data);
+
+    H256(keccak256(&message))
+}
+
 /// Compute the Keccak-256 hash of input bytes.
 ///
 /// Note that strings are interpreted as UTF-8 bytes,
@@ -87,6 +105,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_intended_validator() {
+        let validator = Address::repeat_byte(0xaa);
+        let hash = hash_intended_validator(validator, [0x01, 0x02]);
+
+        let mut expected = vec![0x19, 0x00];
+        expected.extend_from_slice(&[0xaa; 20]);
+        expected.extend_from_slice(&[0x01, 0x02]);
+        assert_eq!(hash, H256(keccak256(expected)));
+        assert_ne!(hash, hash_intended_validator(Address::zero(), [0x01, 0x02]));
+    }
+
     #[test]
     fn simple_function_signature() {
         // test vector retrieved from
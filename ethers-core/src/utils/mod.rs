@@ -23,8 +23,20 @@ pub use anvil::{Anvil, AnvilInstance};
 /// Moonbeam utils
 pub mod moonbeam;
 
+/// EIP-6492 signatures of counterfactual smart contract accounts
+mod erc6492;
+pub use erc6492::{
+    decode_erc6492_validation, erc6492_validation_call, is_erc6492_signature,
+    is_valid_ecdsa_signature, Erc6492Signature, ERC1271_MAGIC_VALUE, ERC6492_MAGIC_SUFFIX,
+    ERC6492_VALIDATION_MULTICALL,
+};
+
 mod hash;
-pub use hash::{hash_message, id, keccak256, serialize};
+pub use hash::{hash_intended_validator, hash_message, id, keccak256, serialize};
+
+/// EVM bytecode disassembly and metadata extraction
+mod disassembler;
+pub use disassembler::{disassemble, split_metadata, BytecodeMetadata, Disassembly, Instruction};
 
 mod units;
 use serde::{Deserialize, Deserializer};
@@ -36,7 +48,7 @@ pub use rlp;
 /// Re-export hex
 pub use hex;
 
-use crate::types::{Address, Bytes, ParseI256Error, H256, I256, U256};
+use crate::types::{Address, Bytes, FeeHistory, ParseI256Error, H256, I256, U256};
 use ethabi::ethereum_types::FromDecStrErr;
 use k256::ecdsa::SigningKey;
 use std::{
@@ -492,6 +504,37 @@ pub fn eip1559_default_estimator(base_fee_per_gas: U256, rewards: Vec<Vec<U256>>
     (max_fee_per_gas, max_priority_fee_per_gas)
 }
 
+/// Estimates the `(max_fee_per_gas, max_priority_fee_per_gas)` of a transaction from the
+/// `eth_feeHistory` of the latest blocks.
+///
+/// The `estimator` is called with the base fee of the block after the history and the rewards at
+/// the requested percentiles. Pass [`eip1559_default_estimator`] for the provider's estimation.
+///
+/// Returns `None` if the history has no base fee, i.e. the chain does not support EIP-1559.
+///
+/// ```
+/// use ethers_core::{types::FeeHistory, utils::{eip1559_default_estimator, estimate_eip1559_fees}};
+///
+/// # fn foo(history: FeeHistory) {
+/// // the default estimation
+/// let fees = estimate_eip1559_fees(&history, eip1559_default_estimator);
+/// // twice the base fee and the median of the rewards at the first requested percentile
+/// let fees = estimate_eip1559_fees(&history, |base_fee, rewards| {
+///     let mut tips: Vec<_> = rewards.iter().filter_map(|reward| reward.first()).collect();
+///     tips.sort();
+///     let tip = tips.get(tips.len() / 2).map(|tip| **tip).unwrap_or_default();
+///     (base_fee * 2 + tip, tip)
+/// });
+/// # }
+/// ```
+pub fn estimate_eip1559_fees<F>(fee_history: &FeeHistory, estimator: F) -> Option<(U256, U256)>
+where
+    F: FnOnce(U256, Vec<Vec<U256>>) -> (U256, U256),
+{
+    let base_fee_per_gas = fee_history.next_base_fee_per_gas()?;
+    Some(estimator(base_fee_per_gas, fee_history.reward.clone()))
+}
+
 /// Converts a Bytes value into a H256, accepting inputs that are less than 32 bytes long. These
 /// inputs will be left padded with zeros.
 pub fn from_bytes_to_h256<'de, D>(bytes: Bytes) -> Result<H256, D::Error>
@@ -1176,6 +1219,28 @@ mod tests {
         assert_eq!(estimate_priority_fee(rewards_overflow), overflow);
     }
 
+    #[test]
+    fn test_estimate_eip1559_fees() {
+        let history: FeeHistory = serde_json::from_value(serde_json::json!({
+            "oldestBlock": "0x1",
+            "baseFeePerGas": ["0x1", "0x2", "0x3"],
+            "gasUsedRatio": [0.5, 0.5],
+            "reward": [["0x5"], ["0x6"]]
+        }))
+        .unwrap();
+        assert_eq!(
+            estimate_eip1559_fees(&history, eip1559_default_estimator),
+            Some(eip1559_default_estimator(3.into(), history.reward.clone()))
+        );
+        let fees = estimate_eip1559_fees(&history, |base_fee, rewards| {
+            (base_fee + rewards[1][0], rewards[1][0])
+        });
+        assert_eq!(fees, Some((9.into(), 6.into())));
+
+        let history = FeeHistory { base_fee_per_gas: vec![], ..history };
+        assert_eq!(estimate_eip1559_fees(&history, eip1559_default_estimator), None);
+    }
+
     #[test]
     fn int_or_hex_combinations() {
         // make sure we can deserialize all combinations of int and hex
@@ -0,0 +1,476 @@
+//! Export of the activity of an address for accounting.
+//!
+//! [`ActivityExporter`] collects the normal and internal transactions and the ERC-20 transfers of
+//! an address from the explorer, normalizes them into [`ActivityRecord`]s with the decoded method,
+//! the fee paid and an optional fiat value, and writes them as CSV, one file per chain.
+
+use crate::{
+    account::{
+        ERC20TokenTransferEvent, InternalTransaction, InternalTxQueryOption, NormalTransaction,
+        TokenQueryOption, TxListParams,
+    },
+    Client, Result,
+};
+use ethers_core::{
+    types::{Address, BlockNumber, H256, U256},
+    utils::{format_units, hex},
+};
+use std::{fmt, io};
+
+/// The header of the CSV written by [`write_csv`]
+pub const CSV_HEADER: &str =
+    "timestamp,block_number,hash,kind,direction,from,to,method,asset,token,amount,fee,fiat_value,status";
+
+/// A source of fiat prices for the assets of an [`ActivityRecord`].
+///
+/// Implemented for closures, e.g. over a table of daily closing prices.
+pub trait PriceSource {
+    /// Returns the fiat price of one unit of `asset` at the unix `timestamp`, where `token` is
+    /// `None` for the native currency.
+    fn price(&self, asset: &str, token: Option<Address>, timestamp: u64) -> Option<f64>;
+}
+
+impl<F> PriceSource for F
+where
+    F: Fn(&str, Option<Address>, u64) -> Option<f64>,
+{
+    fn price(&self, asset: &str, token: Option<Address>, timestamp: u64) -> Option<f64> {
+        self(asset, token, timestamp)
+    }
+}
+
+/// The explorer list an [`ActivityRecord`] was read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ActivityKind {
+    /// A transaction, which transfers the native currency
+    Transaction,
+    /// An internal transfer of the native currency by a contract
+    Internal,
+    /// An ERC-20 transfer
+    Erc20,
+}
+
+impl fmt::Display for ActivityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivityKind::Transaction => write!(f, "transaction"),
+            ActivityKind::Internal => write!(f, "internal"),
+            ActivityKind::Erc20 => write!(f, "erc20"),
+        }
+    }
+}
+
+/// The direction of an [`ActivityRecord`], relative to the exported address
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The address received the assets
+    In,
+    /// The address sent the assets
+    Out,
+    /// The address sent the assets to itself
+    SelfTransfer,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::In => write!(f, "in"),
+            Direction::Out => write!(f, "out"),
+            Direction::SelfTransfer => write!(f, "self"),
+        }
+    }
+}
+
+/// A normalized entry of the activity of an address
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActivityRecord {
+    /// The unix timestamp of the block
+    pub timestamp: u64,
+    /// The number of the block
+    pub block_number: u64,
+    /// The hash of the transaction
+    pub hash: H256,
+    /// The list the entry was read from
+    pub kind: ActivityKind,
+    /// The direction of the transfer
+    pub direction: Direction,
+    /// The sender
+    pub from: Address,
+    /// The recipient, or `None` for contract deployments
+    pub to: Option<Address>,
+    /// The name of the called function, or its selector if the explorer can not decode it
+    pub method: Option<String>,
+    /// The symbol of the transferred asset
+    pub asset: String,
+    /// The token contract, or `None` for the native currency
+    pub token: Option<Address>,
+    /// The transferred amount in units of the asset, e.g. `1.5` for 1.5 ETH
+    pub amount: String,
+    /// The fee paid in the native currency, only set on transactions sent by the address
+    pub fee: Option<String>,
+    /// The fiat value of the amount, if a [`PriceSource`] knows the price of the asset
+    pub fiat_value: Option<f64>,
+    /// Whether the transaction failed
+    pub failed: bool,
+}
+
+/// Collects the activity of an address from the explorer.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_core::types::Chain;
+/// use ethers_etherscan::{export::ActivityExporter, Client};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::new(Chain::Mainnet, "<API KEY>")?;
+/// let address = "0x1f162cf730564efD2Bb96eb27486A2801d76AFB6".parse()?;
+/// let prices = |asset: &str, _token, _timestamp| (asset == "ETH").then_some(1_800.0);
+///
+/// let file = std::fs::File::create("mainnet.csv")?;
+/// ActivityExporter::new(&client, address).prices(prices).export_csv(file).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ActivityExporter<'a> {
+    client: &'a Client,
+    address: Address,
+    native_symbol: String,
+    params: TxListParams,
+    prices: Option<Box<dyn PriceSource + 'a>>,
+}
+
+impl<'a> fmt::Debug for ActivityExporter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActivityExporter")
+            .field("address", &self.address)
+            .field("native_symbol", &self.native_symbol)
+            .field("params", &self.params)
+            .field("prices", &self.prices.is_some())
+            .finish()
+    }
+}
+
+impl<'a> ActivityExporter<'a> {
+    /// Creates an exporter of the activity of `address` on the chain of `client`.
+    pub fn new(client: &'a Client, address: Address) -> Self {
+        Self {
+            client,
+            address,
+            native_symbol: "ETH".to_string(),
+            params: TxListParams::default(),
+            prices: None,
+        }
+    }
+
+    /// Sets the symbol of the native currency of the chain, `ETH` by default.
+    pub fn native_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.native_symbol = symbol.into();
+        self
+    }
+
+    /// Sets the block range and pagination of the explorer queries.
+    pub fn params(mut self, params: TxListParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Sets the source of the fiat values.
+    pub fn prices(mut self, prices: impl PriceSource + 'a) -> Self {
+        self.prices = Some(Box::new(prices));
+        self
+    }
+
+    /// Fetches the activity of the address, ordered by block.
+    pub async fn fetch(&self) -> Result<Vec<ActivityRecord>> {
+        let transactions = self.client.get_transactions(&self.address, Some(self.params)).await?;
+        let internal = self
+            .client
+            .get_internal_transactions(
+                InternalTxQueryOption::ByAddress(self.address),
+                Some(self.params),
+            )
+            .await?;
+        let transfers = self
+            .client
+            .get_erc20_token_transfer_events(
+                TokenQueryOption::ByAddress(self.address),
+                Some(self.params),
+            )
+            .await?;
+        Ok(self.records(&transactions, &internal, &transfers))
+    }
+
+    /// Fetches the activity of the address and writes it to `writer` as CSV, returning the
+    /// number of records.
+    pub async fn export_csv<W: io::Write>(&self, writer: W) -> Result<usize> {
+        let records = self.fetch().await?;
+        write_csv(&records, writer)?;
+        Ok(records.len())
+    }
+
+    /// Normalizes the explorer entries into records, ordered by block.
+    pub fn records(
+        &self,
+        transactions: &[NormalTransaction],
+        internal: &[InternalTransaction],
+        transfers: &[ERC20TokenTransferEvent],
+    ) -> Vec<ActivityRecord> {
+        let mut records = Vec::with_capacity(transactions.len() + internal.len() + transfers.len());
+
+        for tx in transactions {
+            let (Some(hash), Some(from)) = (tx.hash.value(), tx.from.value()) else {
+                // genesis allocations have no transaction
+                continue
+            };
+            let method = match (&tx.function_name, tx.input.len()) {
+                (Some(name), _) if !name.is_empty() => {
+                    Some(name.split('(').next().unwrap_or(name).to_string())
+                }
+                (_, len) if len >= 4 => Some(format!("0x{}", hex::encode(&tx.input[..4]))),
+                _ => None,
+            };
+            let fee = (*from == self.address)
+                .then(|| tx.gas_price.map(|price| native_amount(tx.gas_used * price)))
+                .flatten();
+            records.push(self.record(Entry {
+                timestamp: &tx.time_stamp,
+                block_number: &tx.block_number,
+                hash: *hash,
+                kind: ActivityKind::Transaction,
+                from: *from,
+                to: tx.to.or(tx.contract_address),
+                method,
+                token: None,
+                amount: native_amount(tx.value),
+                fee,
+                failed: tx.is_error == "1",
+            }));
+        }
+
+        for tx in internal {
+            records.push(self.record(Entry {
+                timestamp: &tx.time_stamp,
+                block_number: &tx.block_number,
+                hash: tx.hash,
+                kind: ActivityKind::Internal,
+                from: tx.from,
+                to: tx.to.value().copied().or_else(|| tx.contract_address.value().copied()),
+                method: None,
+                token: None,
+                amount: native_amount(tx.value),
+                fee: None,
+                failed: tx.is_error == "1",
+            }));
+        }
+
+        for transfer in transfers {
+            let decimals = transfer.token_decimal.parse::<u32>().unwrap_or(0);
+            let amount = format_units(transfer.value, decimals)
+                .unwrap_or_else(|_| transfer.value.to_string());
+            let mut record = self.record(Entry {
+                timestamp: &transfer.time_stamp,
+                block_number: &transfer.block_number,
+                hash: transfer.hash,
+                kind: ActivityKind::Erc20,
+                from: transfer.from,
+                to: transfer.to,
+                method: None,
+                token: Some(transfer.contract_address),
+                amount,
+                fee: None,
+                failed: false,
+            });
+            record.asset = transfer.token_symbol.clone();
+            record.fiat_value = self.fiat_value(&record);
+            records.push(record);
+        }
+
+        records.sort_by_key(|record| (record.block_number, record.hash, record.kind));
+        records
+    }
+
+    fn record(&self, entry: Entry<'_>) -> ActivityRecord {
+        let direction = match (entry.from == self.address, entry.to == Some(self.address)) {
+            (true, true) => Direction::SelfTransfer,
+            (true, false) => Direction::Out,
+            _ => Direction::In,
+        };
+        let mut record = ActivityRecord {
+            timestamp: entry.timestamp.parse().unwrap_or_default(),
+            block_number: entry.block_number.as_number().map(|n| n.as_u64()).unwrap_or_default(),
+            hash: entry.hash,
+            kind: entry.kind,
+            direction,
+            from: entry.from,
+            to: entry.to,
+            method: entry.method,
+            asset: self.native_symbol.clone(),
+            token: entry.token,
+            amount: entry.amount,
+            fee: entry.fee,
+            fiat_value: None,
+            failed: entry.failed,
+        };
+        record.fiat_value = self.fiat_value(&record);
+        record
+    }
+
+    fn fiat_value(&self, record: &ActivityRecord) -> Option<f64> {
+        let price = self.prices.as_ref()?.price(&record.asset, record.token, record.timestamp)?;
+        Some(record.amount.parse::<f64>().ok()? * price)
+    }
+}
+
+/// The fields of an explorer entry, which are shared by all lists
+struct Entry<'a> {
+    timestamp: &'a str,
+    block_number: &'a BlockNumber,
+    hash: H256,
+    kind: ActivityKind,
+    from: Address,
+    to: Option<Address>,
+    method: Option<String>,
+    token: Option<Address>,
+    amount: String,
+    fee: Option<String>,
+    failed: bool,
+}
+
+fn native_amount(wei: U256) -> String {
+    format_units(wei, 18).expect("18 decimals are valid")
+}
+
+/// Writes `records` as CSV with the [`CSV_HEADER`] to `writer`.
+pub fn write_csv<W: io::Write>(records: &[ActivityRecord], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "{CSV_HEADER}")?;
+    for record in records {
+        let fields = [
+            record.timestamp.to_string(),
+            record.block_number.to_string(),
+            format!("{:?}", record.hash),
+            record.kind.to_string(),
+            record.direction.to_string(),
+            format!("{:?}", record.from),
+            record.to.map(|to| format!("{to:?}")).unwrap_or_default(),
+            record.method.clone().unwrap_or_default(),
+            record.asset.clone(),
+            record.token.map(|token| format!("{token:?}")).unwrap_or_default(),
+            record.amount.clone(),
+            record.fee.clone().unwrap_or_default(),
+            record.fiat_value.map(|value| format!("{value:.2}")).unwrap_or_default(),
+            if record.failed { "failed" } else { "success" }.to_string(),
+        ];
+        let line = fields.iter().map(|field| escape_csv(field)).collect::<Vec<_>>().join(",");
+        writeln!(writer, "{line}")?;
+    }
+    writer.flush()
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::Chain;
+    use serde_json::json;
+
+    const ADDRESS: &str = "0x1f162cf730564efd2bb96eb27486a2801d76afb6";
+    const OTHER: &str = "0x4e83362442b8d1bec281594cea3050c8eb01311c";
+    const TOKEN: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+    fn transaction() -> NormalTransaction {
+        serde_json::from_value(json!({
+            "blockNumber": "100", "timeStamp": "1700000000",
+            "hash": format!("0x{}", "11".repeat(32)), "nonce": "1",
+            "blockHash": format!("0x{}", "22".repeat(32)), "transactionIndex": "0",
+            "from": ADDRESS, "to": TOKEN, "value": "0", "gas": "60000",
+            "gasPrice": "20000000000", "isError": "0", "txreceipt_status": "1",
+            "input": "0xa9059cbb", "contractAddress": "", "cumulativeGasUsed": "50000",
+            "gasUsed": "50000", "confirmations": "10", "methodId": "0xa9059cbb",
+            "functionName": "transfer(address _to, uint256 _value)"
+        }))
+        .unwrap()
+    }
+
+    fn internal() -> InternalTransaction {
+        serde_json::from_value(json!({
+            "blockNumber": "90", "timeStamp": "1690000000",
+            "hash": format!("0x{}", "33".repeat(32)), "from": OTHER, "to": ADDRESS,
+            "value": "1500000000000000000", "contractAddress": "", "input": "",
+            "type": "call", "gas": "2300", "gasUsed": "0", "traceId": "0",
+            "isError": "0", "errCode": ""
+        }))
+        .unwrap()
+    }
+
+    fn transfer() -> ERC20TokenTransferEvent {
+        serde_json::from_value(json!({
+            "blockNumber": "100", "timeStamp": "1700000000",
+            "hash": format!("0x{}", "11".repeat(32)), "nonce": "1",
+            "blockHash": format!("0x{}", "22".repeat(32)), "from": ADDRESS,
+            "contractAddress": TOKEN, "to": OTHER, "value": "2500000",
+            "tokenName": "USD Coin", "tokenSymbol": "USDC", "tokenDecimal": "6",
+            "transactionIndex": "0", "gas": "60000", "gasPrice": "20000000000",
+            "gasUsed": "50000", "cumulativeGasUsed": "50000", "input": "deprecated",
+            "confirmations": "10"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn normalizes_activity() {
+        let client = Client::new(Chain::Mainnet, "").unwrap();
+        let address = ADDRESS.parse().unwrap();
+        let prices = |asset: &str, _: Option<Address>, _: u64| match asset {
+            "ETH" => Some(2_000.0),
+            "USDC" => Some(1.0),
+            _ => None,
+        };
+        let exporter = ActivityExporter::new(&client, address).prices(prices);
+        let records = exporter.records(&[transaction()], &[internal()], &[transfer()]);
+        assert_eq!(records.len(), 3);
+
+        let received = &records[0];
+        assert_eq!((received.kind, received.direction), (ActivityKind::Internal, Direction::In));
+        assert_eq!(received.amount, "1.500000000000000000");
+        assert_eq!(received.fiat_value, Some(3_000.0));
+        assert_eq!(received.fee, None);
+
+        let call = &records[1];
+        assert_eq!((call.kind, call.direction), (ActivityKind::Transaction, Direction::Out));
+        assert_eq!(call.method.as_deref(), Some("transfer"));
+        assert_eq!(call.fee.as_deref(), Some("0.001000000000000000"));
+
+        let sent = &records[2];
+        assert_eq!((sent.kind, sent.direction), (ActivityKind::Erc20, Direction::Out));
+        assert_eq!((sent.asset.as_str(), sent.amount.as_str()), ("USDC", "2.500000"));
+        assert_eq!(sent.fiat_value, Some(2.5));
+
+        let mut csv = Vec::new();
+        write_csv(&records, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[3],
+            format!(
+                "1700000000,100,0x{},erc20,out,{ADDRESS},{OTHER},,USDC,{TOKEN},2.500000,,2.50,success",
+                "11".repeat(32)
+            )
+        );
+    }
+
+    #[test]
+    fn escapes_csv_fields() {
+        assert_eq!(escape_csv("transfer"), "transfer");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -23,7 +23,9 @@ pub mod account;
 pub mod blocks;
 pub mod contract;
 pub mod errors;
+pub mod export;
 pub mod gas;
+pub mod selectors;
 pub mod source_tree;
 mod transaction;
 pub mod utils;
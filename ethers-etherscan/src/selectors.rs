@@ -0,0 +1,137 @@
+//! Remote function signature lookups, used to resolve selectors of unverified contracts.
+
+use crate::{EtherscanError, Result};
+use ethers_core::{abi::LocalSignatureDatabase, types::Selector, utils::hex};
+use reqwest::{IntoUrl, Url};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::trace;
+
+/// The default signature database endpoint.
+pub const OPENCHAIN_LOOKUP_URL: &str = "https://api.openchain.xyz/signature-database/v1/lookup";
+
+/// A client for an [openchain](https://openchain.xyz/signatures) compatible signature database.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_core::abi::guess_interface;
+/// use ethers_etherscan::selectors::SignatureClient;
+/// # async fn foo(code: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+/// let selectors = ethers_core::utils::disassemble(&code).selectors();
+/// let db = SignatureClient::new().lookup_functions(&selectors).await?;
+/// let interface = guess_interface(&code, &db);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SignatureClient {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl Default for SignatureClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignatureClient {
+    /// Creates a new client for the default openchain endpoint.
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: OPENCHAIN_LOOKUP_URL.parse().expect("valid openchain url"),
+        }
+    }
+
+    /// Creates a new client for the given lookup endpoint.
+    pub fn with_url(url: impl IntoUrl) -> Result<Self> {
+        Ok(Self { client: reqwest::Client::new(), url: url.into_url()? })
+    }
+
+    /// Configures the `reqwest::Client`
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Looks up the function signatures for all `selectors`.
+    ///
+    /// The returned database only contains signatures that hash to their selector.
+    pub async fn lookup_functions(&self, selectors: &[Selector]) -> Result<LocalSignatureDatabase> {
+        let mut db = LocalSignatureDatabase::new();
+        if selectors.is_empty() {
+            return Ok(db)
+        }
+
+        let query = selectors
+            .iter()
+            .map(|selector| format!("0x{}", hex::encode(selector)))
+            .collect::<Vec<_>>();
+        trace!(target: "etherscan", "GET {} for {} selectors", self.url, query.len());
+        let response: LookupResponse = self
+            .client
+            .get(self.url.clone())
+            .query(&[("function", query.join(",")), ("filter", "true".to_string())])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let result = match response {
+            LookupResponse { ok: true, result: Some(result), .. } => result,
+            LookupResponse { error, .. } => {
+                return Err(EtherscanError::Unknown(
+                    error.unwrap_or_else(|| "signature lookup failed".to_string()),
+                ))
+            }
+        };
+
+        for (selector, entries) in result.function {
+            let Ok(selector) = hex::decode(selector.trim_start_matches("0x")) else { continue };
+            let Ok(selector) = Selector::try_from(selector.as_slice()) else { continue };
+            for entry in entries.into_iter().flatten() {
+                db.insert_with_selector(selector, entry.name);
+            }
+        }
+        Ok(db)
+    }
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    ok: bool,
+    #[serde(default)]
+    result: Option<LookupResult>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LookupResult {
+    #[serde(default)]
+    function: HashMap<String, Option<Vec<SignatureEntry>>>,
+}
+
+#[derive(Deserialize)]
+struct SignatureEntry {
+    name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_lookup_response() {
+        let s = r#"{"ok":true,"result":{"event":{},"function":{"0xa9059cbb":[{"name":"transfer(address,uint256)","filtered":false}],"0xdeadbeef":null}}}"#;
+        let response: LookupResponse = serde_json::from_str(s).unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result.function.len(), 2);
+        assert_eq!(
+            result.function["0xa9059cbb"].as_ref().unwrap()[0].name,
+            "transfer(address,uint256)"
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use ethers_core::abi::SignatureDatabase;
+use ethers_etherscan::selectors::SignatureClient;
+
+#[tokio::test]
+async fn can_lookup_functions() {
+    let selector = [0xa9, 0x05, 0x9c, 0xbb];
+    let db = SignatureClient::new().lookup_functions(&[selector]).await.unwrap();
+    let signatures = db.function_signatures(selector);
+    assert!(signatures.contains(&"transfer(address,uint256)".to_string()));
+}
@@ -1,6 +1,6 @@
 use crate::{
     gas_oracle::{GasOracle, GasOracleMiddleware},
-    NonceManagerMiddleware, SignerMiddleware,
+    NonceManagerMiddleware, PendingLimitMiddleware, SignerMiddleware,
 };
 use ethers_core::types::Address;
 use ethers_providers::Middleware;
@@ -77,6 +77,13 @@ pub trait MiddlewareBuilder: Middleware + Sized + 'static {
         NonceManagerMiddleware::new(self, address)
     }
 
+    /// Wraps `self` inside a [`PendingLimitMiddleware`](crate::PendingLimitMiddleware).
+    ///
+    /// `max_pending` is the number of concurrently pending transactions allowed per sender.
+    fn pending_limit(self, max_pending: usize) -> PendingLimitMiddleware<Self> {
+        PendingLimitMiddleware::new(self, max_pending)
+    }
+
     /// Wraps `self` inside a [`GasOracleMiddleware`](crate::gas_oracle::GasOracleMiddleware).
     ///
     /// [`GasOracle`](crate::gas_oracle::GasOracle)
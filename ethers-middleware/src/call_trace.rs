@@ -0,0 +1,333 @@
+use ethers_core::{
+    abi::{Abi, FunctionExt, Token},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, CallFrame,
+        GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingCallOptions,
+        GethDebugTracingOptions, GethTrace, GethTraceFrame, U256,
+    },
+};
+use ethers_etherscan::Client;
+use ethers_providers::Middleware;
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+use tracing::debug;
+
+/// The `Error(string)` selector of reverts with a reason string
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// The `Panic(uint256)` selector of failed assertions and arithmetic errors
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// A call of a [`CallFrame`] decoded with the ABI of the called contract.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecodedCall {
+    /// The type of the call, e.g. `CALL`, `DELEGATECALL` or `CREATE`
+    pub typ: String,
+    /// The caller
+    pub from: Address,
+    /// The called contract
+    pub to: Option<Address>,
+    /// The transferred value
+    pub value: Option<U256>,
+    /// The name of the called contract, if its ABI is known
+    pub contract: Option<String>,
+    /// The signature of the called function, if its ABI is known
+    pub function: Option<String>,
+    /// The decoded arguments of the function
+    pub args: Option<Vec<Token>>,
+    /// The decoded return values of the function
+    pub returns: Option<Vec<Token>>,
+    /// The raw input of the call
+    pub input: Bytes,
+    /// The raw output of the call
+    pub output: Option<Bytes>,
+    /// The error of the call, e.g. `execution reverted`
+    pub error: Option<String>,
+    /// The decoded revert reason, panic code or custom error
+    pub revert: Option<String>,
+    /// The calls made by this call
+    pub calls: Vec<DecodedCall>,
+}
+
+impl DecodedCall {
+    /// Returns `true` if this call failed.
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the innermost failed call, i.e. the point where the revert originated.
+    pub fn revert_point(&self) -> Option<&DecodedCall> {
+        if !self.is_error() {
+            return None
+        }
+        self.calls.iter().rev().find_map(DecodedCall::revert_point).or(Some(self))
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when tracing a call fails
+pub enum CallTraceError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// Thrown when the node returns a trace that is not a call tracer frame
+    #[error("the node did not return a call trace")]
+    UnexpectedTrace,
+}
+
+#[derive(Clone, Debug)]
+struct KnownContract {
+    name: String,
+    abi: Abi,
+}
+
+/// Runs `debug_traceCall` with the `callTracer` and decodes the resulting call tree with the ABIs
+/// of the called contracts.
+///
+/// ABIs are either registered with [`CallTraceDecoder::with_abi`] or fetched from Etherscan, in
+/// which case the metadata cache of the Etherscan [`Client`] applies.
+///
+/// ```no_run
+/// use ethers_core::types::{Chain, TransactionRequest};
+/// use ethers_etherscan::Client;
+/// use ethers_middleware::call_trace::CallTraceDecoder;
+/// use ethers_providers::{Http, Provider};
+/// use std::{convert::TryFrom, time::Duration};
+///
+/// # async fn foo(tx: TransactionRequest) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+/// let etherscan = Client::builder()
+///     .chain(Chain::Mainnet)?
+///     .with_api_key("API_KEY")
+///     .with_cache(Some("cache".into()), Duration::from_secs(24 * 60 * 60))
+///     .build()?;
+///
+/// let mut decoder = CallTraceDecoder::new().with_etherscan(etherscan);
+/// let call = decoder.trace_call(&provider, tx, None).await?;
+/// if let Some(revert) = call.revert_point() {
+///     println!("{:?}.{:?} reverted: {:?}", revert.contract, revert.function, revert.revert);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CallTraceDecoder {
+    contracts: HashMap<Address, Option<KnownContract>>,
+    etherscan: Option<Client>,
+}
+
+impl CallTraceDecoder {
+    /// Instantiates a decoder without any known ABIs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetches the ABIs of unknown contracts from Etherscan.
+    #[must_use]
+    pub fn with_etherscan(mut self, client: Client) -> Self {
+        self.etherscan = Some(client);
+        self
+    }
+
+    /// Registers the name and ABI of the contract at `address`.
+    #[must_use]
+    pub fn with_abi(mut self, address: Address, name: impl Into<String>, abi: Abi) -> Self {
+        self.contracts.insert(address, Some(KnownContract { name: name.into(), abi }));
+        self
+    }
+
+    /// Traces `tx` with `debug_traceCall` and decodes the call tree.
+    pub async fn trace_call<M, T>(
+        &mut self,
+        client: &M,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<DecodedCall, CallTraceError<M>>
+    where
+        M: Middleware,
+        T: Into<TypedTransaction> + Send + Sync,
+    {
+        let options = GethDebugTracingCallOptions {
+            tracing_options: GethDebugTracingOptions {
+                tracer: Some(GethDebugTracerType::BuiltInTracer(
+                    GethDebugBuiltInTracerType::CallTracer,
+                )),
+                ..Default::default()
+            },
+            state_overrides: None,
+        };
+        let trace = client
+            .debug_trace_call(tx, block, options)
+            .await
+            .map_err(CallTraceError::MiddlewareError)?;
+        match trace {
+            GethTrace::Known(GethTraceFrame::CallTracer(frame)) => Ok(self.decode(&frame).await),
+            _ => Err(CallTraceError::UnexpectedTrace),
+        }
+    }
+
+    /// Decodes a call tree, fetching the ABIs of unknown contracts first.
+    pub async fn decode(&mut self, frame: &CallFrame) -> DecodedCall {
+        let mut addresses = BTreeSet::new();
+        collect_addresses(frame, &mut addresses);
+        for address in addresses {
+            if !self.contracts.contains_key(&address) {
+                let contract = self.fetch(address).await;
+                self.contracts.insert(address, contract);
+            }
+        }
+        self.decode_known(frame)
+    }
+
+    async fn fetch(&self, address: Address) -> Option<KnownContract> {
+        let etherscan = self.etherscan.as_ref()?;
+        let metadata = match etherscan.contract_source_code(address).await {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                debug!(?address, %err, "could not fetch contract metadata");
+                return None
+            }
+        };
+        let item = metadata.items.into_iter().next()?;
+        let abi = item.abi().ok()?;
+        Some(KnownContract { name: item.contract_name, abi })
+    }
+
+    /// Decodes a call tree with the already known ABIs.
+    pub fn decode_known(&self, frame: &CallFrame) -> DecodedCall {
+        let to = frame.to.as_ref().and_then(|to| to.as_address()).copied();
+        let contract = to.and_then(|to| self.contracts.get(&to)).and_then(Option::as_ref);
+        let is_create = frame.typ.starts_with("CREATE");
+
+        let mut call = DecodedCall {
+            typ: frame.typ.clone(),
+            from: frame.from,
+            to,
+            value: frame.value,
+            contract: contract.map(|contract| contract.name.clone()),
+            input: frame.input.clone(),
+            output: frame.output.clone(),
+            error: frame.error.clone(),
+            calls: frame.calls.iter().flatten().map(|frame| self.decode_known(frame)).collect(),
+            ..Default::default()
+        };
+
+        if let (Some(contract), false, Some(selector)) = (contract, is_create, frame.input.get(..4))
+        {
+            let function =
+                contract.abi.functions().find(|function| function.short_signature() == selector);
+            if let Some(function) = function {
+                call.function = Some(function.abi_signature());
+                call.args = function.decode_input(&frame.input[4..]).ok();
+                if call.error.is_none() {
+                    call.returns = frame
+                        .output
+                        .as_ref()
+                        .and_then(|output| function.decode_output(output).ok());
+                }
+            }
+        }
+        if call.error.is_some() {
+            call.revert = frame
+                .output
+                .as_ref()
+                .and_then(|output| decode_revert(output, contract.map(|contract| &contract.abi)));
+        }
+        call
+    }
+}
+
+/// Collects the addresses of all called contracts
+fn collect_addresses(frame: &CallFrame, addresses: &mut BTreeSet<Address>) {
+    if let Some(to) = frame.to.as_ref().and_then(|to| to.as_address()) {
+        addresses.insert(*to);
+    }
+    for call in frame.calls.iter().flatten() {
+        collect_addresses(call, addresses);
+    }
+}
+
+/// Decodes a reason string, a panic code or a custom error of the ABI
+fn decode_revert(output: &[u8], abi: Option<&Abi>) -> Option<String> {
+    let (selector, data) = (output.get(..4)?, &output[4..]);
+    if selector == ERROR_SELECTOR {
+        let reason = ethers_core::abi::decode(&[ethers_core::abi::ParamType::String], data).ok()?;
+        return reason.into_iter().next()?.into_string()
+    }
+    if selector == PANIC_SELECTOR {
+        let code =
+            ethers_core::abi::decode(&[ethers_core::abi::ParamType::Uint(256)], data).ok()?;
+        return Some(format!("Panic({:#x})", code.into_iter().next()?.into_uint()?))
+    }
+    let error = abi?.errors().find(|error| &error.signature()[..4] == selector)?;
+    let args = error.decode(data).ok()?;
+    let args = args.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+    Some(format!("{}({args})", error.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::abi::{parse_abi, AbiEncode};
+
+    fn frame(to: Address, input: Vec<u8>, output: Vec<u8>, error: Option<&str>) -> CallFrame {
+        CallFrame {
+            typ: "CALL".to_string(),
+            to: Some(to.into()),
+            input: input.into(),
+            output: Some(output.into()),
+            error: error.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decodes_call_tree() {
+        let token = Address::repeat_byte(0x11);
+        let vault = Address::repeat_byte(0x22);
+        let token_abi = parse_abi(&[
+            "function transfer(address to, uint256 amount) returns (bool)",
+            "error InsufficientBalance(uint256 available)",
+        ])
+        .unwrap();
+        let vault_abi = parse_abi(&["function withdraw(uint256 amount)"]).unwrap();
+
+        let transfer = token_abi.function("transfer").unwrap();
+        let transfer_input =
+            transfer.encode_input(&[Token::Address(vault), Token::Uint(U256::from(100))]).unwrap();
+        let error = token_abi.error("InsufficientBalance").unwrap();
+        let mut error_output = error.signature()[..4].to_vec();
+        error_output.extend(U256::from(5).encode());
+
+        let withdraw = vault_abi.function("withdraw").unwrap();
+        let withdraw_input = withdraw.encode_input(&[Token::Uint(U256::from(100))]).unwrap();
+        let mut reason = ERROR_SELECTOR.to_vec();
+        reason.extend(ethers_core::abi::encode(&[Token::String("transfer failed".into())]));
+
+        let mut root = frame(vault, withdraw_input, reason, Some("execution reverted"));
+        root.calls =
+            Some(vec![frame(token, transfer_input, error_output, Some("execution reverted"))]);
+
+        let decoder = CallTraceDecoder::new()
+            .with_abi(token, "Token", token_abi)
+            .with_abi(vault, "Vault", vault_abi);
+        let call = decoder.decode_known(&root);
+
+        assert_eq!(call.contract.as_deref(), Some("Vault"));
+        assert_eq!(call.function.as_deref(), Some("withdraw(uint256)"));
+        assert_eq!(call.args, Some(vec![Token::Uint(U256::from(100))]));
+        assert_eq!(call.revert.as_deref(), Some("transfer failed"));
+
+        let revert = call.revert_point().unwrap();
+        assert_eq!(revert.contract.as_deref(), Some("Token"));
+        assert_eq!(revert.function.as_deref(), Some("transfer(address,uint256)"));
+        assert_eq!(revert.revert.as_deref(), Some("InsufficientBalance(5)"));
+    }
+
+    #[test]
+    fn decodes_panic() {
+        let mut output = PANIC_SELECTOR.to_vec();
+        output.extend(U256::from(0x11).encode());
+        assert_eq!(decode_revert(&output, None).as_deref(), Some("Panic(0x11)"));
+        assert_eq!(decode_revert(&[0xde, 0xad], None), None);
+    }
+}
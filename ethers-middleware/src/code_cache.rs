@@ -0,0 +1,127 @@
+use ethers_core::{
+    types::{Address, BlockId, BlockNumber, H256, U64},
+    utils::keccak256,
+};
+use ethers_providers::Middleware;
+use std::{collections::HashMap, sync::Mutex};
+
+/// The hash of empty code, which `EXTCODEHASH` returns for accounts without code.
+pub const KECCAK_EMPTY: H256 = H256([
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// The block the cached hashes were fetched at
+    block: Option<U64>,
+    hashes: HashMap<Address, H256>,
+}
+
+/// Caches the code hashes of accounts for the latest block.
+///
+/// Code hashes are fetched via `eth_getCode` and hashed locally, mirroring what `EXTCODEHASH`
+/// returns for existing accounts. The cache is cleared whenever a new block is seen, so a contract
+/// that is deployed or self-destructed is picked up on the next block.
+///
+/// ```no_run
+/// use ethers_core::types::Address;
+/// use ethers_middleware::code_cache::CodeCache;
+/// use ethers_providers::{Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+/// let cache = CodeCache::new(provider);
+/// let is_contract = cache.is_contract(Address::zero()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CodeCache<M> {
+    inner: M,
+    state: Mutex<CacheState>,
+}
+
+impl<M> CodeCache<M>
+where
+    M: Middleware,
+{
+    /// Instantiates an empty cache on top of the provided middleware.
+    pub fn new(inner: M) -> Self {
+        Self { inner, state: Default::default() }
+    }
+
+    /// Returns the hash of the code deployed at `address` as of the latest block.
+    ///
+    /// Returns [`KECCAK_EMPTY`] for accounts without code.
+    pub async fn code_hash(&self, address: Address) -> Result<H256, M::Error> {
+        let block = self.inner.get_block_number().await?;
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.block != Some(block) {
+                state.block = Some(block);
+                state.hashes.clear();
+            } else if let Some(hash) = state.hashes.get(&address) {
+                return Ok(*hash)
+            }
+        }
+
+        let code =
+            self.inner.get_code(address, Some(BlockId::Number(BlockNumber::Number(block)))).await?;
+        let hash = if code.is_empty() { KECCAK_EMPTY } else { H256(keccak256(&code)) };
+
+        let mut state = self.state.lock().unwrap();
+        if state.block == Some(block) {
+            state.hashes.insert(address, hash);
+        }
+        Ok(hash)
+    }
+
+    /// Returns `true` if code is deployed at `address` as of the latest block.
+    pub async fn is_contract(&self, address: Address) -> Result<bool, M::Error> {
+        Ok(self.code_hash(address).await? != KECCAK_EMPTY)
+    }
+
+    /// Clears all cached code hashes.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.block = None;
+        state.hashes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::Bytes;
+    use ethers_providers::Provider;
+
+    #[test]
+    fn keccak_empty_matches() {
+        assert_eq!(KECCAK_EMPTY, H256(keccak256(b"")));
+    }
+
+    #[tokio::test]
+    async fn caches_per_block() {
+        let (provider, mock) = Provider::mocked();
+        let cache = CodeCache::new(provider);
+        let address = Address::random();
+        let code = Bytes::from(vec![0x60, 0x00]);
+
+        // responses are popped in reverse order
+        mock.push(U64::from(2)).unwrap();
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        mock.push(U64::from(2)).unwrap();
+        mock.push(U64::from(1)).unwrap();
+        mock.push::<Bytes, _>(code.clone()).unwrap();
+        mock.push(U64::from(1)).unwrap();
+
+        assert_eq!(cache.code_hash(address).await.unwrap(), H256(keccak256(&code)));
+        // served from the cache for the same block
+        assert!(cache.is_contract(address).await.unwrap());
+        // refetched on a new block
+        assert!(!cache.is_contract(address).await.unwrap());
+        assert!(!cache.is_contract(address).await.unwrap());
+    }
+}
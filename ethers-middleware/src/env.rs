@@ -0,0 +1,54 @@
+use crate::{NonceManagerMiddleware, SignerMiddleware};
+use ethers_providers::{EnvError, Http, Middleware, Provider, ProviderError};
+use ethers_signers::{
+    env::{EnvSigner, EnvSignerError, CHAIN_ID},
+    Signer,
+};
+use thiserror::Error;
+
+/// The middleware stack assembled by [`SignerMiddleware::from_env`]
+pub type EnvClient = SignerMiddleware<NonceManagerMiddleware<Provider<Http>>, EnvSigner>;
+
+/// Error thrown when assembling a middleware stack from environment variables
+#[derive(Debug, Error)]
+pub enum FromEnvError {
+    /// Thrown when the provider can not be assembled
+    #[error(transparent)]
+    Provider(#[from] EnvError),
+    /// Thrown when the signer can not be assembled
+    #[error(transparent)]
+    Signer(#[from] EnvSignerError),
+    /// Thrown when the chain id can not be fetched from the provider
+    #[error("failed to fetch the chain id: {0}")]
+    ChainId(#[source] ProviderError),
+}
+
+impl SignerMiddleware<NonceManagerMiddleware<Provider<Http>>, EnvSigner> {
+    /// Assembles a signing client from environment variables: a [`Provider`] connected to
+    /// `ETH_RPC_URL` (see [`Provider::from_env`]), a [`NonceManagerMiddleware`] and the signer
+    /// configured by [`EnvSigner::from_env`].
+    ///
+    /// Unless `CHAIN_ID` is set, the signer signs for the chain id of the provider.
+    ///
+    /// ```no_run
+    /// use ethers_middleware::env::EnvClient;
+    /// use ethers_providers::Middleware;
+    ///
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// // ETH_RPC_URL=http://localhost:8545 PRIVATE_KEY=0x...
+    /// let client = EnvClient::from_env().await?;
+    /// let balance = client.get_balance(client.address(), None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_env() -> Result<Self, FromEnvError> {
+        let provider = Provider::<Http>::from_env().await?;
+        let mut signer = EnvSigner::from_env().await?;
+        if std::env::var_os(CHAIN_ID).is_none() {
+            let chain_id = provider.get_chainid().await.map_err(FromEnvError::ChainId)?;
+            signer = signer.with_chain_id(chain_id.as_u64());
+        }
+        let provider = NonceManagerMiddleware::new(provider, signer.address());
+        Ok(SignerMiddleware::new(provider, signer))
+    }
+}
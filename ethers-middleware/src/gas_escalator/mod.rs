@@ -13,11 +13,15 @@ use std::{pin::Pin, sync::Arc};
 use thiserror::Error;
 use tracing_futures::Instrument;
 
+use crate::BlockWindow;
+
 use ethers_core::types::{
     transaction::eip2718::TypedTransaction, BlockId, TransactionRequest, TxHash, U256,
 };
 use ethers_providers::{interval, Middleware, MiddlewareError, PendingTransaction, StreamExt};
 
+#[cfg(not(target_arch = "wasm32"))]
+use ethers_providers::TaskManager;
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::spawn;
 
@@ -70,6 +74,15 @@ pub enum Frequency {
     PerBlock,
     /// On a duration basis (in milliseconds)
     Duration(u64),
+    /// Once every window of blocks using the eth_newBlock filter, e.g. a
+    /// [`BlockWindow::from_duration`] for a chain-aware interval
+    Blocks(BlockWindow),
+}
+
+impl From<BlockWindow> for Frequency {
+    fn from(window: BlockWindow) -> Self {
+        Frequency::Blocks(window)
+    }
 }
 
 #[derive(Debug)]
@@ -201,13 +214,44 @@ where
 {
     /// Initializes the middleware with the provided gas escalator and the chosen
     /// escalation frequency (per block or per second)
-    #[allow(clippy::let_and_return)]
     #[cfg(not(target_arch = "wasm32"))]
     pub fn new<E>(inner: M, escalator: E, frequency: Frequency) -> Self
     where
         E: GasEscalator + 'static,
         M: 'static,
     {
+        let (this, esc) = Self::with_task(inner, escalator, frequency);
+        spawn(esc.escalate().instrument(tracing::trace_span!("gas-escalation")));
+        this
+    }
+
+    /// Same as [`GasEscalatorMiddleware::new`], but spawns the escalation task on `tasks`, so
+    /// that errors and panics of the task are reported and it is stopped on shutdown.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_with_tasks<E>(
+        inner: M,
+        escalator: E,
+        frequency: Frequency,
+        tasks: &TaskManager,
+    ) -> Self
+    where
+        E: GasEscalator + 'static,
+        M: 'static,
+    {
+        let (this, esc) = Self::with_task(inner, escalator, frequency);
+        tasks.spawn(
+            "gas-escalation",
+            esc.escalate().instrument(tracing::trace_span!("gas-escalation")),
+        );
+        this
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn with_task<E>(
+        inner: M,
+        escalator: E,
+        frequency: Frequency,
+    ) -> (Self, EscalationTask<Arc<M>, E>) {
         let (tx, rx) = oneshot::channel();
         let inner = Arc::new(inner);
 
@@ -221,11 +265,7 @@ where
 
         let esc = EscalationTask { inner, escalator, frequency, txs, shutdown: rx };
 
-        {
-            spawn(esc.escalate().instrument(tracing::trace_span!("gas-escalation")));
-        }
-
-        Self { inner: this }
+        (Self { inner: this }, esc)
     }
 }
 
@@ -262,6 +302,20 @@ impl<M, E> EscalationTask<M, E> {
                 self.inner.watch_blocks().await.map_err(MiddlewareError::from_err)?.map(|_| ()),
             ),
             Frequency::Duration(ms) => Box::pin(interval(std::time::Duration::from_millis(ms))),
+            Frequency::Blocks(window) => {
+                let every = window.blocks().max(1);
+                Box::pin(
+                    self.inner
+                        .watch_blocks()
+                        .await
+                        .map_err(MiddlewareError::from_err)?
+                        .enumerate()
+                        .filter(move |(i, _)| {
+                            futures_util::future::ready((*i as u64 + 1) % every == 0)
+                        })
+                        .map(|_| ()),
+                )
+            }
         };
 
         let mut watcher = watcher.fuse();
@@ -275,7 +329,7 @@ impl<M, E> EscalationTask<M, E> {
             opt = watcher.next() => {
                 if opt.is_none() {
                     tracing::error!("timing future has gone away");
-                    return Ok(());
+                    return Ok(())
                 }
                 let now = Instant::now();
 
@@ -1,5 +1,9 @@
 use super::{GasOracle, GasOracleError};
 use async_trait::async_trait;
+#[cfg(feature = "celo")]
+use ethers_core::types::transaction::celo::{Cip42TransactionRequest, Cip64TransactionRequest};
+#[cfg(feature = "zksync")]
+use ethers_core::types::transaction::zksync::ZkSyncTransactionRequest;
 use ethers_core::types::{transaction::eip2718::TypedTransaction, *};
 use ethers_providers::{Middleware, MiddlewareError as METrait, PendingTransaction};
 use thiserror::Error;
@@ -81,7 +85,8 @@ where
                     inner.tx.gas_price = Some(self.get_gas_price().await?);
                 }
             }
-            TypedTransaction::Eip1559(ref mut inner) => {
+            TypedTransaction::Eip1559(ref mut inner) |
+            TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) => {
                 if inner.max_priority_fee_per_gas.is_none() || inner.max_fee_per_gas.is_none() {
                     let (max_fee_per_gas, max_priority_fee_per_gas) =
                         self.estimate_eip1559_fees(None).await?;
@@ -99,6 +104,33 @@ where
                     inner.tx.gas_price = Some(self.get_gas_price().await?);
                 }
             }
+            #[cfg(feature = "celo")]
+            TypedTransaction::Cip42(Cip42TransactionRequest { tx: ref mut inner, .. }) |
+            TypedTransaction::Cip64(Cip64TransactionRequest { tx: ref mut inner, .. }) => {
+                if inner.max_priority_fee_per_gas.is_none() || inner.max_fee_per_gas.is_none() {
+                    let (max_fee_per_gas, max_priority_fee_per_gas) =
+                        self.estimate_eip1559_fees(None).await?;
+                    if inner.max_priority_fee_per_gas.is_none() {
+                        inner.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+                    }
+                    if inner.max_fee_per_gas.is_none() {
+                        inner.max_fee_per_gas = Some(max_fee_per_gas);
+                    }
+                }
+            }
+            #[cfg(feature = "zksync")]
+            TypedTransaction::ZkSync(ZkSyncTransactionRequest { tx: ref mut inner, .. }) => {
+                if inner.max_priority_fee_per_gas.is_none() || inner.max_fee_per_gas.is_none() {
+                    let (max_fee_per_gas, max_priority_fee_per_gas) =
+                        self.estimate_eip1559_fees(None).await?;
+                    if inner.max_priority_fee_per_gas.is_none() {
+                        inner.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+                    }
+                    if inner.max_fee_per_gas.is_none() {
+                        inner.max_fee_per_gas = Some(max_fee_per_gas);
+                    }
+                }
+            }
         };
 
         self.inner().fill_transaction(tx, block).await.map_err(METrait::from_err)
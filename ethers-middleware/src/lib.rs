@@ -2,6 +2,14 @@
 #![deny(unsafe_code, rustdoc::broken_intra_doc_links)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+/// The [CallTraceDecoder](crate::call_trace::CallTraceDecoder) traces calls and decodes the call
+/// tree with the ABIs of the called contracts
+pub mod call_trace;
+
+/// The [CodeCache](crate::code_cache::CodeCache) caches code hashes per block to cheaply check
+/// whether an address is a contract
+pub mod code_cache;
+
 /// The [Gas Escalator middleware](crate::gas_escalator::GasEscalatorMiddleware)
 /// is used to re-broadcast transactions with an increasing gas price to guarantee
 /// their timely inclusion.
@@ -17,6 +25,11 @@ pub mod gas_oracle;
 pub mod nonce_manager;
 pub use nonce_manager::NonceManagerMiddleware;
 
+/// The [Pending Limit](crate::PendingLimitMiddleware) is used to limit the number of concurrently
+/// pending transactions per sender
+pub mod pending_limit;
+pub use pending_limit::PendingLimitMiddleware;
+
 /// The [Transformer](crate::transformer::TransformerMiddleware) is used to intercept transactions
 /// and transform them to be sent via various supported transformers, e.g.,
 /// [DSProxy](crate::transformer::DsProxy)
@@ -32,16 +45,38 @@ pub use signer::SignerMiddleware;
 pub mod policy;
 pub use policy::PolicyMiddleware;
 
+/// The [MultiChainClient](crate::multichain::MultiChainClient) holds a middleware per chain and
+/// runs the same operation across all of them concurrently
+pub mod multichain;
+
+/// The [Scheduler](crate::scheduler::BroadcastScheduler) holds signed transactions and broadcasts
+/// them once a block number or timestamp has been reached
+pub mod scheduler;
+
+/// The [TxGraphExecutor](crate::tx_graph::TxGraphExecutor) sends transactions with declared
+/// dependencies in order, assigning nonces per sender
+pub mod tx_graph;
+
 /// The [TimeLag](crate::TimeLag) provides safety against reorgs by querying state N blocks
 /// before the chain tip
 pub mod timelag;
 pub use timelag::TimeLag;
 
+/// The [EnvClient](crate::env::EnvClient) is a signing client assembled from environment
+/// variables
+#[cfg(not(target_arch = "wasm32"))]
+pub mod env;
+
 /// The [MiddlewareBuilder](crate::MiddlewareBuilder) provides a way to compose many
 /// [`Middleware`](ethers_providers::Middleware) in a concise way
 pub mod builder;
 pub use builder::MiddlewareBuilder;
 
+/// [`BlockWindow`](crate::BlockWindow) and [`Confirmations`](crate::Confirmations) express block
+/// counts of middleware settings, optionally derived from durations
+pub mod window;
+pub use window::{BlockWindow, Confirmations};
+
 // For macro expansions only, not public API.
 // See: [#2235](https://github.com/gakonst/ethers-rs/pull/2235)
 
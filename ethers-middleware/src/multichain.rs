@@ -0,0 +1,141 @@
+use ethers_core::types::{Address, Chain, U256};
+use ethers_providers::Middleware;
+use futures_util::future::join_all;
+use std::{collections::BTreeMap, future::Future};
+
+/// Holds one middleware stack per [`Chain`] and runs the same operation across all of them.
+///
+/// ```no_run
+/// use ethers_core::types::{Address, Chain};
+/// use ethers_middleware::multichain::MultiChainClient;
+/// use ethers_providers::{Http, Middleware, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = MultiChainClient::new()
+///     .with_chain(Chain::Mainnet, Provider::<Http>::try_from("https://eth.llamarpc.com")?)
+///     .with_chain(Chain::Polygon, Provider::<Http>::try_from("https://polygon-rpc.com")?);
+///
+/// let block = client.on(Chain::Mainnet).unwrap().get_block_number().await?;
+///
+/// let balances = client.get_balances(Address::zero()).await;
+/// for (chain, balance) in balances {
+///     println!("{chain}: {:?}", balance);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiChainClient<M> {
+    clients: BTreeMap<Chain, M>,
+}
+
+impl<M> Default for MultiChainClient<M> {
+    fn default() -> Self {
+        Self { clients: BTreeMap::new() }
+    }
+}
+
+impl<M> MultiChainClient<M>
+where
+    M: Middleware,
+{
+    /// Instantiates a client without any chains.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the middleware for `chain`, replacing any previous one.
+    #[must_use]
+    pub fn with_chain(mut self, chain: Chain, client: M) -> Self {
+        self.clients.insert(chain, client);
+        self
+    }
+
+    /// Adds the middleware for `chain`, returning the previous one if any.
+    pub fn insert(&mut self, chain: Chain, client: M) -> Option<M> {
+        self.clients.insert(chain, client)
+    }
+
+    /// Removes the middleware for `chain`.
+    pub fn remove(&mut self, chain: Chain) -> Option<M> {
+        self.clients.remove(&chain)
+    }
+
+    /// Returns the middleware for `chain`, if any.
+    pub fn on(&self, chain: Chain) -> Option<&M> {
+        self.clients.get(&chain)
+    }
+
+    /// Returns all chains that have a middleware, in ascending chain id order.
+    pub fn chains(&self) -> impl Iterator<Item = Chain> + '_ {
+        self.clients.keys().copied()
+    }
+
+    /// Returns the number of chains.
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Returns `true` if there are no chains.
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Runs `f` concurrently on every chain and collects the results by chain.
+    ///
+    /// ```no_run
+    /// # use ethers_core::types::Chain;
+    /// # use ethers_middleware::multichain::MultiChainClient;
+    /// # use ethers_providers::{Http, Middleware, Provider};
+    /// # async fn foo(client: MultiChainClient<Provider<Http>>) {
+    /// let blocks = client.for_each(|_, provider| provider.get_block_number()).await;
+    /// # }
+    /// ```
+    pub async fn for_each<'a, F, Fut>(&'a self, f: F) -> BTreeMap<Chain, Fut::Output>
+    where
+        F: Fn(Chain, &'a M) -> Fut,
+        Fut: Future,
+    {
+        let futs = self.clients.iter().map(|(chain, client)| {
+            let fut = f(*chain, client);
+            async move { (*chain, fut.await) }
+        });
+        join_all(futs).await.into_iter().collect()
+    }
+
+    /// Returns the native balance of `address` on every chain.
+    pub async fn get_balances(&self, address: Address) -> BTreeMap<Chain, Result<U256, M::Error>> {
+        self.for_each(|_, client| client.get_balance(address, None)).await
+    }
+}
+
+impl<M> FromIterator<(Chain, M)> for MultiChainClient<M> {
+    fn from_iter<I: IntoIterator<Item = (Chain, M)>>(iter: I) -> Self {
+        Self { clients: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::U64;
+    use ethers_providers::Provider;
+
+    #[tokio::test]
+    async fn runs_on_all_chains() {
+        let (mainnet, mainnet_mock) = Provider::mocked();
+        let (polygon, polygon_mock) = Provider::mocked();
+        mainnet_mock.push(U64::from(1)).unwrap();
+        polygon_mock.push(U64::from(137)).unwrap();
+
+        let client: MultiChainClient<_> =
+            [(Chain::Polygon, polygon), (Chain::Mainnet, mainnet)].into_iter().collect();
+        assert_eq!(client.chains().collect::<Vec<_>>(), [Chain::Mainnet, Chain::Polygon]);
+        assert!(client.on(Chain::Goerli).is_none());
+
+        let blocks = client.for_each(|_, provider| provider.get_block_number()).await;
+        assert_eq!(blocks[&Chain::Mainnet].as_ref().unwrap(), &U64::from(1));
+        assert_eq!(blocks[&Chain::Polygon].as_ref().unwrap(), &U64::from(137));
+    }
+}
@@ -0,0 +1,306 @@
+use async_trait::async_trait;
+use ethers_core::types::{transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, TxHash};
+use ethers_providers::{
+    interval, Middleware, MiddlewareError, PendingTransaction, StreamExt, DEFAULT_POLL_INTERVAL,
+};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+use thiserror::Error;
+
+#[derive(Debug, Default)]
+struct SenderState {
+    /// Slots taken by sends that are currently in flight
+    reserved: usize,
+    /// Broadcasted transactions that have not been mined yet
+    pending: Vec<TxHash>,
+}
+
+impl SenderState {
+    fn len(&self) -> usize {
+        self.reserved + self.pending.len()
+    }
+}
+
+#[derive(Debug)]
+/// Middleware that limits the number of concurrently pending transactions per sender.
+///
+/// Once `max_pending` transactions of a sender are pending, further sends are queued until one of
+/// them is mined or dropped from the mempool. This prevents nonce runaway when upstream code keeps
+/// re-sending transactions in a loop.
+///
+/// Both [`Middleware::send_transaction`] and [`Middleware::send_raw_transaction`] are limited, so
+/// the middleware can be stacked below a `SignerMiddleware`, which broadcasts raw transactions.
+///
+/// ```no_run
+/// use ethers_middleware::PendingLimitMiddleware;
+/// use ethers_providers::{Http, Provider};
+/// use std::{convert::TryFrom, time::Duration};
+///
+/// let provider = Provider::<Http>::try_from("http://localhost:8545").unwrap();
+/// let provider = PendingLimitMiddleware::new(provider, 4).interval(Duration::from_secs(1));
+/// ```
+pub struct PendingLimitMiddleware<M> {
+    inner: M,
+    max_pending: usize,
+    interval: Duration,
+    senders: Mutex<HashMap<Address, SenderState>>,
+}
+
+impl<M> PendingLimitMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Instantiates the middleware, allowing at most `max_pending` pending transactions per
+    /// sender.
+    ///
+    /// # Panics
+    ///
+    /// If `max_pending` is 0.
+    pub fn new(inner: M, max_pending: usize) -> Self {
+        assert!(max_pending > 0, "max_pending must be at least 1");
+        Self { inner, max_pending, interval: DEFAULT_POLL_INTERVAL, senders: Default::default() }
+    }
+
+    /// Sets the interval at which pending transactions are polled while a send is queued.
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the number of transactions of `sender` that are tracked as pending, including
+    /// sends that are currently in flight.
+    pub fn pending_count(&self, sender: Address) -> usize {
+        self.senders.lock().unwrap().get(&sender).map(SenderState::len).unwrap_or_default()
+    }
+
+    /// Waits until `sender` has a free slot and reserves it until the returned [`Reservation`] is
+    /// dropped.
+    async fn reserve(&self, sender: Address) -> Result<Reservation<'_>, PendingLimitError<M>> {
+        let mut ticks = interval(self.interval);
+        loop {
+            self.prune(sender).await?;
+
+            {
+                let mut senders = self.senders.lock().unwrap();
+                let state = senders.entry(sender).or_default();
+                if state.len() < self.max_pending {
+                    state.reserved += 1;
+                    return Ok(Reservation { senders: &self.senders, sender, tx_hash: None })
+                }
+            }
+
+            tracing::trace!(?sender, max_pending = self.max_pending, "queueing transaction");
+            ticks.next().await;
+        }
+    }
+
+    /// Removes all transactions of `sender` that were mined or dropped from the mempool.
+    async fn prune(&self, sender: Address) -> Result<(), PendingLimitError<M>> {
+        let pending = self
+            .senders
+            .lock()
+            .unwrap()
+            .get(&sender)
+            .map(|state| state.pending.clone())
+            .unwrap_or_default();
+
+        let mut done = Vec::new();
+        for tx_hash in pending {
+            let mined = self
+                .inner
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(MiddlewareError::from_err)?
+                .is_some();
+            if mined ||
+                self.inner
+                    .get_transaction(tx_hash)
+                    .await
+                    .map_err(MiddlewareError::from_err)?
+                    .is_none()
+            {
+                done.push(tx_hash);
+            }
+        }
+
+        if !done.is_empty() {
+            if let Some(state) = self.senders.lock().unwrap().get_mut(&sender) {
+                state.pending.retain(|tx_hash| !done.contains(tx_hash));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the pending limit middleware
+pub enum PendingLimitError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// Thrown when the sender of a transaction can not be determined, or a raw transaction can
+    /// not be decoded
+    #[error("no sender for the transaction")]
+    MissingSender,
+}
+
+impl<M: Middleware> MiddlewareError for PendingLimitError<M> {
+    type Inner = M::Error;
+
+    fn from_err(src: M::Error) -> Self {
+        PendingLimitError::MiddlewareError(src)
+    }
+
+    fn as_inner(&self) -> Option<&Self::Inner> {
+        match self {
+            PendingLimitError::MiddlewareError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M> Middleware for PendingLimitMiddleware<M>
+where
+    M: Middleware,
+{
+    type Error = PendingLimitError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Waits until the sender has less than `max_pending` pending transactions, then delegates
+    /// the transaction to the inner middleware.
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let tx = tx.into();
+        let sender = tx
+            .from()
+            .copied()
+            .or_else(|| self.inner.default_sender())
+            .ok_or(PendingLimitError::MissingSender)?;
+
+        let mut reservation = self.reserve(sender).await?;
+        let pending =
+            self.inner.send_transaction(tx, block).await.map_err(MiddlewareError::from_err)?;
+        reservation.tx_hash = Some(*pending);
+        Ok(pending)
+    }
+
+    /// Waits until the signer of the raw transaction has less than `max_pending` pending
+    /// transactions, then delegates the transaction to the inner middleware.
+    async fn send_raw_transaction<'a>(
+        &'a self,
+        tx: Bytes,
+    ) -> Result<PendingTransaction<'a, Self::Provider>, Self::Error> {
+        let sender = TypedTransaction::decode_signed_raw(&tx)
+            .ok()
+            .and_then(|(tx, _)| tx.from().copied())
+            .ok_or(PendingLimitError::MissingSender)?;
+
+        let mut reservation = self.reserve(sender).await?;
+        let pending =
+            self.inner.send_raw_transaction(tx).await.map_err(MiddlewareError::from_err)?;
+        reservation.tx_hash = Some(*pending);
+        Ok(pending)
+    }
+}
+
+/// A slot reserved by [`PendingLimitMiddleware::reserve`].
+///
+/// The slot is released on drop, also if the send is cancelled or panics, and `tx_hash` is
+/// tracked as pending if the send succeeded.
+struct Reservation<'a> {
+    senders: &'a Mutex<HashMap<Address, SenderState>>,
+    sender: Address,
+    tx_hash: Option<TxHash>,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = senders.entry(self.sender).or_default();
+        state.reserved = state.reserved.saturating_sub(1);
+        state.pending.extend(self.tx_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::{Transaction, TransactionReceipt, TransactionRequest};
+    use ethers_providers::Provider;
+    use ethers_signers::{LocalWallet, Signer};
+
+    #[tokio::test]
+    async fn releases_reservations_on_drop() {
+        let (provider, _) = Provider::mocked();
+        let middleware = PendingLimitMiddleware::new(provider, 1);
+        let sender = Address::random();
+
+        let reservation = middleware.reserve(sender).await.unwrap();
+        assert_eq!(middleware.pending_count(sender), 1);
+        // e.g. a send cancelled by a timeout
+        drop(reservation);
+        assert_eq!(middleware.pending_count(sender), 0);
+
+        let mut reservation = middleware.reserve(sender).await.unwrap();
+        reservation.tx_hash = Some(TxHash::repeat_byte(1));
+        drop(reservation);
+        assert_eq!(middleware.pending_count(sender), 1);
+    }
+
+    #[tokio::test]
+    async fn queues_raw_transactions_until_mined_or_dropped() {
+        let (provider, mock) = Provider::mocked();
+        let middleware = PendingLimitMiddleware::new(provider, 1).interval(Duration::from_millis(1));
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let raw = |nonce: u64| {
+            let tx: TypedTransaction = TransactionRequest::new()
+                .from(wallet.address())
+                .to(Address::zero())
+                .nonce(nonce)
+                .gas(21_000)
+                .gas_price(1)
+                .chain_id(1)
+                .into();
+            let signature = wallet.sign_transaction_sync(&tx).unwrap();
+            tx.rlp_signed(&signature)
+        };
+        let (first, second) = (TxHash::repeat_byte(1), TxHash::repeat_byte(2));
+
+        mock.push(first).unwrap();
+        assert_eq!(*middleware.send_raw_transaction(raw(0)).await.unwrap(), first);
+        assert_eq!(middleware.pending_count(wallet.address()), 1);
+
+        // responses are returned last in, first out: the first transaction is still pending on
+        // the first poll and mined on the second, then the second transaction is sent
+        mock.push(second).unwrap();
+        mock.push(TransactionReceipt::default()).unwrap();
+        mock.push(Transaction::default()).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        assert_eq!(*middleware.send_raw_transaction(raw(1)).await.unwrap(), second);
+        assert_eq!(middleware.pending_count(wallet.address()), 1);
+
+        // a transaction without receipt that is unknown to the node was dropped
+        mock.push(serde_json::Value::Null).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        middleware.prune(wallet.address()).await.unwrap();
+        assert_eq!(middleware.pending_count(wallet.address()), 0);
+
+        let err = middleware.send_raw_transaction(vec![0xc0].into()).await.unwrap_err();
+        assert!(matches!(err, PendingLimitError::MissingSender));
+    }
+}
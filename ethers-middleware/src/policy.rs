@@ -1,8 +1,16 @@
-use ethers_core::types::{transaction::eip2718::TypedTransaction, BlockId};
+use crate::code_cache::CodeCache;
+use ethers_core::{
+    abi::{self, ParamType},
+    types::{transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, NameOrAddress, U256},
+};
 use ethers_providers::{Middleware, MiddlewareError, PendingTransaction};
 
 use async_trait::async_trait;
-use std::fmt::Debug;
+use futures_util::future::join_all;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+};
 use thiserror::Error;
 
 /// Basic trait to ensure that transactions about to be sent follow certain rules.
@@ -45,6 +53,442 @@ impl Policy for RejectEverything {
     }
 }
 
+/// Whether a [`RecipientCodePolicy`] requires or forbids code at the recipient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientCode {
+    /// Only allow transactions to contracts.
+    Contract,
+    /// Only allow transactions to accounts without code.
+    NoContract,
+}
+
+/// A policy that allows or denies transactions depending on whether code is deployed at the
+/// recipient. Contract deployments are always allowed.
+///
+/// Lookups go through a [`CodeCache`], so repeated checks within a block are served locally.
+#[derive(Debug)]
+pub struct RecipientCodePolicy<M> {
+    cache: CodeCache<M>,
+    inner: M,
+    rule: RecipientCode,
+}
+
+impl<M: Middleware + Clone> RecipientCodePolicy<M> {
+    /// Creates a new policy that enforces `rule` using the provided middleware.
+    pub fn new(inner: M, rule: RecipientCode) -> Self {
+        Self { cache: CodeCache::new(inner.clone()), inner, rule }
+    }
+}
+
+#[derive(Error, Debug)]
+/// Error thrown by the [`RecipientCodePolicy`]
+pub enum RecipientCodeError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error(transparent)]
+    MiddlewareError(M::Error),
+    /// Thrown when the recipient has no code but a contract was required
+    #[error("recipient {0:?} is not a contract")]
+    NotAContract(Address),
+    /// Thrown when the recipient has code but no contract was allowed
+    #[error("recipient {0:?} is a contract")]
+    IsAContract(Address),
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M: Middleware> Policy for RecipientCodePolicy<M> {
+    type Error = RecipientCodeError<M>;
+
+    async fn ensure_can_send(&self, tx: TypedTransaction) -> Result<TypedTransaction, Self::Error> {
+        let to = match tx.to().cloned() {
+            Some(NameOrAddress::Address(addr)) => addr,
+            Some(NameOrAddress::Name(ens_name)) => self
+                .inner
+                .resolve_name(&ens_name)
+                .await
+                .map_err(RecipientCodeError::MiddlewareError)?,
+            None => return Ok(tx),
+        };
+        let is_contract =
+            self.cache.is_contract(to).await.map_err(RecipientCodeError::MiddlewareError)?;
+        match (self.rule, is_contract) {
+            (RecipientCode::Contract, false) => Err(RecipientCodeError::NotAContract(to)),
+            (RecipientCode::NoContract, true) => Err(RecipientCodeError::IsAContract(to)),
+            _ => Ok(tx),
+        }
+    }
+}
+
+/// An ERC-20 operation decoded from transaction calldata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenOperation {
+    /// `transfer(address to, uint256 amount)`
+    Transfer {
+        /// The recipient
+        to: Address,
+        /// The transferred amount
+        amount: U256,
+    },
+    /// `transferFrom(address from, address to, uint256 amount)`
+    TransferFrom {
+        /// The owner of the tokens
+        from: Address,
+        /// The recipient
+        to: Address,
+        /// The transferred amount
+        amount: U256,
+    },
+    /// `approve(address spender, uint256 amount)`,
+    /// `increaseAllowance(address spender, uint256 amount)` or the EIP-2612
+    /// `permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32
+    /// r, bytes32 s)`
+    Approve {
+        /// The approved spender
+        spender: Address,
+        /// The approved amount, or the increase of the allowance
+        amount: U256,
+    },
+}
+
+impl TokenOperation {
+    /// Decodes the ERC-20 operation of the calldata.
+    ///
+    /// Returns `Ok(None)` if the calldata does not start with the selector of an ERC-20
+    /// operation, and an error if it does but the arguments can't be decoded.
+    pub fn decode(data: &[u8]) -> Result<Option<Self>, abi::Error> {
+        const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+        const TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+        const APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+        const INCREASE_ALLOWANCE: [u8; 4] = [0x39, 0x50, 0x93, 0x51];
+        const PERMIT: [u8; 4] = [0xd5, 0x05, 0xac, 0xcf];
+
+        if data.len() < 4 {
+            return Ok(None)
+        }
+        let (selector, args) = data.split_at(4);
+        let selector = [selector[0], selector[1], selector[2], selector[3]];
+        let params: &[ParamType] = match selector {
+            TRANSFER_FROM => &[ParamType::Address, ParamType::Address, ParamType::Uint(256)],
+            TRANSFER | APPROVE | INCREASE_ALLOWANCE => &[ParamType::Address, ParamType::Uint(256)],
+            PERMIT => &[
+                ParamType::Address,
+                ParamType::Address,
+                ParamType::Uint(256),
+                ParamType::Uint(256),
+                ParamType::Uint(8),
+                ParamType::FixedBytes(32),
+                ParamType::FixedBytes(32),
+            ],
+            _ => return Ok(None),
+        };
+        let tokens = abi::decode(params, args)?;
+        // the types of the tokens are guaranteed by the decoder
+        let address = |i: usize| tokens[i].clone().into_address().unwrap_or_default();
+        let amount = |i: usize| tokens[i].clone().into_uint().unwrap_or_default();
+
+        Ok(Some(match selector {
+            TRANSFER => Self::Transfer { to: address(0), amount: amount(1) },
+            TRANSFER_FROM => {
+                Self::TransferFrom { from: address(0), to: address(1), amount: amount(2) }
+            }
+            PERMIT => Self::Approve { spender: address(1), amount: amount(2) },
+            _ => Self::Approve { spender: address(0), amount: amount(1) },
+        }))
+    }
+
+    /// Returns the address receiving the tokens or the allowance.
+    pub fn recipient(&self) -> Address {
+        match self {
+            Self::Transfer { to, .. } | Self::TransferFrom { to, .. } => *to,
+            Self::Approve { spender, .. } => *spender,
+        }
+    }
+
+    /// Returns the transferred or approved amount.
+    pub fn amount(&self) -> U256 {
+        match self {
+            Self::Transfer { amount, .. } |
+            Self::TransferFrom { amount, .. } |
+            Self::Approve { amount, .. } => *amount,
+        }
+    }
+}
+
+/// The limits a [`TokenPolicy`] enforces for a token.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenLimits {
+    /// The maximum amount of a single transfer or approval
+    pub max_amount: Option<U256>,
+    /// The only recipients and spenders allowed, or `None` to allow everyone
+    pub allowed_recipients: Option<HashSet<Address>>,
+}
+
+impl TokenLimits {
+    /// Creates limits that allow everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum amount of a single transfer or approval.
+    #[must_use]
+    pub fn max_amount(mut self, max_amount: impl Into<U256>) -> Self {
+        self.max_amount = Some(max_amount.into());
+        self
+    }
+
+    /// Adds `recipient` to the allowed recipients and spenders.
+    #[must_use]
+    pub fn allow_recipient(mut self, recipient: Address) -> Self {
+        self.allowed_recipients.get_or_insert_with(Default::default).insert(recipient);
+        self
+    }
+}
+
+/// A policy that decodes the ERC-20 `transfer`, `transferFrom`, `approve` and
+/// `increaseAllowance` calldata of transactions and applies [`TokenLimits`] to the decoded
+/// recipient and amount.
+///
+/// Value based policies only see the `to` and `value` of a transaction, which for token
+/// transfers are the token contract and zero. This policy closes that gap. Transactions that are
+/// not ERC-20 operations are allowed.
+///
+/// ```
+/// use ethers_core::types::Address;
+/// use ethers_middleware::policy::{TokenLimits, TokenPolicy};
+///
+/// let usdc: Address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".parse().unwrap();
+/// let treasury = Address::random();
+///
+/// let policy = TokenPolicy::new()
+///     .with_token(usdc, TokenLimits::new().max_amount(10_000_000_000u64).allow_recipient(treasury))
+///     .deny_unknown_tokens();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TokenPolicy {
+    tokens: HashMap<Address, TokenLimits>,
+    deny_unknown_tokens: bool,
+}
+
+impl TokenPolicy {
+    /// Creates a policy without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limits for the token contract at `token`.
+    #[must_use]
+    pub fn with_token(mut self, token: Address, limits: TokenLimits) -> Self {
+        self.tokens.insert(token, limits);
+        self
+    }
+
+    /// Rejects ERC-20 operations on tokens without limits.
+    #[must_use]
+    pub fn deny_unknown_tokens(mut self) -> Self {
+        self.deny_unknown_tokens = true;
+        self
+    }
+
+    /// Checks the ERC-20 operation of `tx`, if any, against the limits.
+    ///
+    /// Calldata with the selector of an ERC-20 operation but undecodable arguments is rejected,
+    /// so that truncated or malformed calldata can't bypass the limits.
+    pub fn check(&self, tx: &TypedTransaction) -> Result<(), TokenPolicyError> {
+        let Some(data) = tx.data() else { return Ok(()) };
+        let op = match TokenOperation::decode(data) {
+            Ok(Some(op)) => op,
+            Ok(None) => return Ok(()),
+            Err(_) => return Err(TokenPolicyError::MalformedCalldata(data[..4].to_vec().into())),
+        };
+        let token = match tx.to() {
+            Some(NameOrAddress::Address(addr)) => *addr,
+            Some(NameOrAddress::Name(name)) => {
+                return Err(TokenPolicyError::UnresolvedToken(name.clone()))
+            }
+            None => return Ok(()),
+        };
+        let Some(limits) = self.tokens.get(&token) else {
+            return match self.deny_unknown_tokens {
+                true => Err(TokenPolicyError::UnknownToken(token)),
+                false => Ok(()),
+            }
+        };
+
+        if let Some(allowed) = &limits.allowed_recipients {
+            if !allowed.contains(&op.recipient()) {
+                return Err(TokenPolicyError::RecipientNotAllowed {
+                    token,
+                    recipient: op.recipient(),
+                })
+            }
+        }
+        if let Some(max) = limits.max_amount {
+            if op.amount() > max {
+                return Err(TokenPolicyError::AmountExceeded { token, amount: op.amount(), max })
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+/// Error thrown by the [`TokenPolicy`]
+pub enum TokenPolicyError {
+    /// Thrown when the recipient or spender is not allowed
+    #[error("{recipient:?} is not an allowed recipient of token {token:?}")]
+    RecipientNotAllowed {
+        /// The token contract
+        token: Address,
+        /// The decoded recipient or spender
+        recipient: Address,
+    },
+    /// Thrown when the amount exceeds the maximum
+    #[error("amount {amount} of token {token:?} exceeds the maximum of {max}")]
+    AmountExceeded {
+        /// The token contract
+        token: Address,
+        /// The decoded amount
+        amount: U256,
+        /// The maximum amount
+        max: U256,
+    },
+    /// Thrown when the token has no limits and unknown tokens are denied
+    #[error("token {0:?} has no limits")]
+    UnknownToken(Address),
+    /// Thrown when the token is an unresolved ENS name
+    #[error("token {0} must be an address")]
+    UnresolvedToken(String),
+    /// Thrown when the calldata has the selector of an ERC-20 operation but can't be decoded
+    #[error("malformed calldata for ERC-20 selector {0}")]
+    MalformedCalldata(Bytes),
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for TokenPolicy {
+    type Error = TokenPolicyError;
+
+    async fn ensure_can_send(&self, tx: TypedTransaction) -> Result<TypedTransaction, Self::Error> {
+        self.check(&tx)?;
+        Ok(tx)
+    }
+}
+
+/// The vote of a policy of an [`ApprovalQuorum`] on a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    /// The name of the policy
+    pub policy: String,
+    /// The error of the policy if it rejected the transaction, formatted with `Debug`
+    pub rejection: Option<String>,
+}
+
+impl Verdict {
+    /// Returns `true` if the policy approved the transaction.
+    pub fn is_approved(&self) -> bool {
+        self.rejection.is_none()
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+/// Error thrown by the [`ApprovalQuorum`] when too few policies approve a transaction
+#[error("{approvals} of {threshold} required approvals, verdicts: {verdicts:?}")]
+pub struct QuorumRejected {
+    /// The number of policies which approved the transaction
+    pub approvals: usize,
+    /// The number of approvals the transaction required
+    pub threshold: usize,
+    /// The votes of all policies, in the order they were added
+    pub verdicts: Vec<Verdict>,
+}
+
+/// Object safe version of [`Policy`], which formats the errors of the policy.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+trait Voter: Sync + Send + Debug {
+    async fn vote(&self, tx: TypedTransaction) -> Option<String>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: Policy> Voter for P {
+    async fn vote(&self, tx: TypedTransaction) -> Option<String> {
+        self.ensure_can_send(tx).await.err().map(|err| format!("{err:?}"))
+    }
+}
+
+/// A policy that lets several policies vote on every transaction, and allows it if at least
+/// `threshold` of them approve it.
+///
+/// All policies vote concurrently on the same transaction, so changes policies make to the
+/// transaction are discarded. When rejected, the [`QuorumRejected`] error reports the verdict
+/// of every policy.
+///
+/// ```
+/// use ethers_core::types::Address;
+/// use ethers_middleware::policy::{AllowEverything, ApprovalQuorum, TokenPolicy};
+///
+/// // two of the three org level controls have to approve
+/// let policy = ApprovalQuorum::new(2)
+///     .voter("compliance", AllowEverything)
+///     .voter("treasury", TokenPolicy::new().deny_unknown_tokens())
+///     .voter("security", AllowEverything);
+/// ```
+#[derive(Debug)]
+pub struct ApprovalQuorum {
+    voters: Vec<(String, Box<dyn Voter>)>,
+    threshold: usize,
+}
+
+impl ApprovalQuorum {
+    /// Creates a quorum without policies, which requires `threshold` approvals.
+    ///
+    /// # Panics
+    ///
+    /// If `threshold` is 0.
+    pub fn new(threshold: usize) -> Self {
+        assert!(threshold > 0, "threshold must be at least 1");
+        Self { voters: Vec::new(), threshold }
+    }
+
+    /// Adds `policy` as a voter called `name`.
+    #[must_use]
+    pub fn voter<P: Policy + 'static>(mut self, name: impl Into<String>, policy: P) -> Self {
+        self.voters.push((name.into(), Box::new(policy)));
+        self
+    }
+
+    /// Returns the number of approvals a transaction requires.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Collects the verdicts of all policies on `tx`, in the order the policies were added.
+    pub async fn vote(&self, tx: &TypedTransaction) -> Vec<Verdict> {
+        let votes = join_all(self.voters.iter().map(|(_, voter)| voter.vote(tx.clone()))).await;
+        self.voters
+            .iter()
+            .zip(votes)
+            .map(|((name, _), rejection)| Verdict { policy: name.clone(), rejection })
+            .collect()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for ApprovalQuorum {
+    type Error = QuorumRejected;
+
+    async fn ensure_can_send(&self, tx: TypedTransaction) -> Result<TypedTransaction, Self::Error> {
+        let verdicts = self.vote(&tx).await;
+        let approvals = verdicts.iter().filter(|verdict| verdict.is_approved()).count();
+        if approvals < self.threshold {
+            return Err(QuorumRejected { approvals, threshold: self.threshold, verdicts })
+        }
+        Ok(tx)
+    }
+}
+
 /// Middleware used to enforce certain policies for transactions.
 #[derive(Clone, Debug)]
 pub struct PolicyMiddleware<M, P> {
@@ -119,3 +563,129 @@ where
         self.inner.send_transaction(tx, block).await.map_err(PolicyMiddlewareError::MiddlewareError)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::{abi::Token, types::TransactionRequest};
+
+    fn calldata(selector: [u8; 4], args: &[Token]) -> Vec<u8> {
+        [&selector[..], &abi::encode(args)].concat()
+    }
+
+    #[test]
+    fn decodes_token_operations() {
+        let to = Address::random();
+        let data = calldata([0xa9, 0x05, 0x9c, 0xbb], &[Token::Address(to), Token::Uint(5.into())]);
+        assert_eq!(
+            TokenOperation::decode(&data).unwrap(),
+            Some(TokenOperation::Transfer { to, amount: 5.into() })
+        );
+
+        let data =
+            calldata([0x09, 0x5e, 0xa7, 0xb3], &[Token::Address(to), Token::Uint(U256::MAX)]);
+        assert_eq!(
+            TokenOperation::decode(&data).unwrap(),
+            Some(TokenOperation::Approve { spender: to, amount: U256::MAX })
+        );
+
+        assert!(TokenOperation::decode(&data[..20]).is_err());
+        assert_eq!(TokenOperation::decode(&[0xde, 0xad, 0xbe, 0xef]).unwrap(), None);
+        assert_eq!(TokenOperation::decode(&[0xa9, 0x05]).unwrap(), None);
+    }
+
+    #[test]
+    fn enforces_token_limits() {
+        let (token, treasury, attacker) = (Address::random(), Address::random(), Address::random());
+        let policy = TokenPolicy::new()
+            .with_token(token, TokenLimits::new().max_amount(100u64).allow_recipient(treasury))
+            .deny_unknown_tokens();
+        let transfer = |token: Address, to: Address, amount: u64| -> TypedTransaction {
+            let data = calldata(
+                [0xa9, 0x05, 0x9c, 0xbb],
+                &[Token::Address(to), Token::Uint(amount.into())],
+            );
+            TransactionRequest::new().to(token).data(data).into()
+        };
+
+        assert_eq!(policy.check(&transfer(token, treasury, 100)), Ok(()));
+        assert_eq!(
+            policy.check(&transfer(token, attacker, 1)),
+            Err(TokenPolicyError::RecipientNotAllowed { token, recipient: attacker })
+        );
+        assert_eq!(
+            policy.check(&transfer(token, treasury, 101)),
+            Err(TokenPolicyError::AmountExceeded { token, amount: 101.into(), max: 100.into() })
+        );
+
+        let other = Address::random();
+        assert_eq!(
+            policy.check(&transfer(other, attacker, 1)),
+            Err(TokenPolicyError::UnknownToken(other))
+        );
+
+        // plain value transfers are not token operations
+        let tx = TransactionRequest::pay(attacker, 1000).into();
+        assert_eq!(policy.check(&tx), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_token_calldata() {
+        let (token, attacker) = (Address::random(), Address::random());
+        let policy = TokenPolicy::new().with_token(token, TokenLimits::new().max_amount(100u64));
+
+        let data =
+            calldata([0xa9, 0x05, 0x9c, 0xbb], &[Token::Address(attacker), Token::Uint(U256::MAX)]);
+        let truncated: TypedTransaction =
+            TransactionRequest::new().to(token).data(data[..40].to_vec()).into();
+        let err = TokenPolicyError::MalformedCalldata(vec![0xa9, 0x05, 0x9c, 0xbb].into());
+        assert_eq!(policy.check(&truncated), Err(err.clone()));
+
+        // also for tokens without limits
+        let truncated: TypedTransaction =
+            TransactionRequest::new().to(Address::random()).data(data[..40].to_vec()).into();
+        assert_eq!(TokenPolicy::new().check(&truncated), Err(err));
+    }
+
+    #[tokio::test]
+    async fn requires_approval_quorum() {
+        let token = Address::random();
+        let data =
+            calldata([0xa9, 0x05, 0x9c, 0xbb], &[Token::Address(token), Token::Uint(1.into())]);
+        let tx: TypedTransaction = TransactionRequest::new().to(token).data(data).into();
+
+        let quorum = |threshold| {
+            ApprovalQuorum::new(threshold)
+                .voter("allow", AllowEverything)
+                .voter("tokens", TokenPolicy::new().deny_unknown_tokens())
+                .voter("reject", RejectEverything)
+        };
+        assert_eq!(quorum(1).ensure_can_send(tx.clone()).await.unwrap(), tx);
+
+        let err = quorum(2).ensure_can_send(tx.clone()).await.unwrap_err();
+        assert_eq!(err.approvals, 1);
+        assert_eq!(err.threshold, 2);
+        assert_eq!(
+            err.verdicts,
+            vec![
+                Verdict { policy: "allow".to_string(), rejection: None },
+                Verdict {
+                    policy: "tokens".to_string(),
+                    rejection: Some(format!("{:?}", TokenPolicyError::UnknownToken(token)))
+                },
+                Verdict { policy: "reject".to_string(), rejection: Some("()".to_string()) },
+            ]
+        );
+
+        // plain value transfers pass the token policy
+        let tx: TypedTransaction = TransactionRequest::pay(token, 1).into();
+        assert!(quorum(2).ensure_can_send(tx.clone()).await.is_ok());
+        assert!(quorum(3).ensure_can_send(tx).await.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold must be at least 1")]
+    fn rejects_zero_quorum() {
+        let _ = ApprovalQuorum::new(0);
+    }
+}
@@ -0,0 +1,296 @@
+use ethers_core::{
+    types::{BlockNumber, Bytes, TxHash, U256, U64},
+    utils::keccak256,
+};
+use ethers_providers::{interval, Middleware, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+use thiserror::Error;
+use tracing::{trace, warn};
+
+/// The condition that must be met before a scheduled transaction is broadcast.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotBefore {
+    /// Broadcast once the latest block's timestamp is at least this unix timestamp.
+    Timestamp(U256),
+    /// Broadcast once the latest block number is at least this block.
+    Block(U64),
+}
+
+impl NotBefore {
+    /// Returns `true` if the condition is met by a block with the given number and timestamp.
+    pub fn is_due(&self, number: U64, timestamp: U256) -> bool {
+        match self {
+            NotBefore::Timestamp(not_before) => timestamp >= *not_before,
+            NotBefore::Block(not_before) => number >= *not_before,
+        }
+    }
+}
+
+/// A signed transaction that is waiting to be broadcast.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTransaction {
+    /// The hash of the signed transaction.
+    pub tx_hash: TxHash,
+    /// The RLP encoded signed transaction.
+    pub raw: Bytes,
+    /// The condition that must be met before the transaction is broadcast.
+    pub not_before: NotBefore,
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the broadcast scheduler
+pub enum SchedulerError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// Thrown when the latest block could not be retrieved
+    #[error("latest block not found")]
+    BlockNotFound,
+    /// Thrown when reading or writing the store fails
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Thrown when the store can not be (de)serialized
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    /// Thrown when due transactions were rejected with "nonce too low" and removed from the
+    /// schedule, since they can never be included anymore. The other due transactions were still
+    /// broadcast.
+    #[error("scheduled transactions {0:?} were dropped: nonce too low")]
+    NonceTooLow(Vec<TxHash>),
+}
+
+/// Holds signed transactions and broadcasts them once their [`NotBefore`] condition is met.
+///
+/// Conditions are evaluated against the latest block, so timestamps refer to chain time rather
+/// than local time. If a store is configured, scheduled transactions are persisted as JSON and
+/// reloaded on restart.
+///
+/// ```no_run
+/// use ethers_core::types::{Bytes, U64};
+/// use ethers_middleware::scheduler::{BroadcastScheduler, NotBefore};
+/// use ethers_providers::{Http, Provider};
+/// use std::{convert::TryFrom, time::Duration};
+///
+/// # async fn foo(raw: Bytes) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+/// let scheduler = BroadcastScheduler::with_store(provider, "scheduled.json")?;
+/// scheduler.schedule(raw, NotBefore::Block(U64::from(17_000_000)))?;
+/// scheduler.run(Duration::from_secs(12)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BroadcastScheduler<M> {
+    inner: M,
+    store: Option<PathBuf>,
+    queue: Mutex<Vec<ScheduledTransaction>>,
+}
+
+impl<M> BroadcastScheduler<M>
+where
+    M: Middleware,
+{
+    /// Instantiates a scheduler that only keeps transactions in memory.
+    pub fn new(inner: M) -> Self {
+        Self { inner, store: None, queue: Default::default() }
+    }
+
+    /// Instantiates a scheduler that persists transactions at `path`, loading any transactions
+    /// that were previously stored there.
+    pub fn with_store(inner: M, path: impl Into<PathBuf>) -> Result<Self, SchedulerError<M>> {
+        let path = path.into();
+        let queue =
+            if path.exists() { serde_json::from_slice(&fs::read(&path)?)? } else { Vec::new() };
+        Ok(Self { inner, store: Some(path), queue: Mutex::new(queue) })
+    }
+
+    /// Returns the path of the store, if any.
+    pub fn store(&self) -> Option<&Path> {
+        self.store.as_deref()
+    }
+
+    /// Schedules the signed transaction, returning its hash.
+    pub fn schedule(
+        &self,
+        raw: impl Into<Bytes>,
+        not_before: NotBefore,
+    ) -> Result<TxHash, SchedulerError<M>> {
+        let raw = raw.into();
+        let tx_hash = TxHash(keccak256(&raw));
+        let mut queue = self.queue.lock().unwrap();
+        if !queue.iter().any(|tx| tx.tx_hash == tx_hash) {
+            queue.push(ScheduledTransaction { tx_hash, raw, not_before });
+            self.persist(&queue)?;
+        }
+        Ok(tx_hash)
+    }
+
+    /// Removes the transaction from the schedule, returning it if it was still pending.
+    pub fn cancel(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<ScheduledTransaction>, SchedulerError<M>> {
+        let mut queue = self.queue.lock().unwrap();
+        let Some(idx) = queue.iter().position(|tx| tx.tx_hash == tx_hash) else { return Ok(None) };
+        let tx = queue.remove(idx);
+        self.persist(&queue)?;
+        Ok(Some(tx))
+    }
+
+    /// Returns all transactions that have not been broadcast yet.
+    pub fn pending(&self) -> Vec<ScheduledTransaction> {
+        self.queue.lock().unwrap().clone()
+    }
+
+    /// Broadcasts all transactions whose condition is met by the latest block and returns their
+    /// hashes.
+    ///
+    /// Transactions the node already knows count as broadcast. Transactions rejected with "nonce
+    /// too low" are removed from the schedule and reported with [`SchedulerError::NonceTooLow`].
+    /// Transactions that are rejected for other reasons stay scheduled and are retried on the
+    /// next poll.
+    pub async fn poll(&self) -> Result<Vec<TxHash>, SchedulerError<M>> {
+        let block = self
+            .inner
+            .get_block(BlockNumber::Latest)
+            .await
+            .map_err(SchedulerError::MiddlewareError)?
+            .ok_or(SchedulerError::BlockNotFound)?;
+        let number = block.number.ok_or(SchedulerError::BlockNotFound)?;
+
+        let due: Vec<_> = self
+            .queue
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|tx| tx.not_before.is_due(number, block.timestamp))
+            .cloned()
+            .collect();
+
+        let mut sent = Vec::with_capacity(due.len());
+        let mut dropped = Vec::new();
+        for tx in due {
+            match self.inner.send_raw_transaction(tx.raw.clone()).await {
+                Ok(_) => {
+                    trace!(tx_hash = ?tx.tx_hash, "broadcast scheduled transaction");
+                    sent.push(tx.tx_hash);
+                }
+                Err(err) => {
+                    let message = err.to_string().to_lowercase();
+                    if message.contains("already known") || message.contains("known transaction") {
+                        trace!(tx_hash = ?tx.tx_hash, "scheduled transaction was already broadcast");
+                        sent.push(tx.tx_hash);
+                    } else if message.contains("nonce too low") {
+                        warn!(tx_hash = ?tx.tx_hash, %err, "dropping scheduled transaction");
+                        dropped.push(tx.tx_hash);
+                    } else {
+                        warn!(tx_hash = ?tx.tx_hash, %err, "failed to broadcast scheduled transaction")
+                    }
+                }
+            }
+        }
+
+        if !sent.is_empty() || !dropped.is_empty() {
+            let mut queue = self.queue.lock().unwrap();
+            queue.retain(|tx| !sent.contains(&tx.tx_hash) && !dropped.contains(&tx.tx_hash));
+            self.persist(&queue)?;
+        }
+        if !dropped.is_empty() {
+            return Err(SchedulerError::NonceTooLow(dropped))
+        }
+        Ok(sent)
+    }
+
+    /// Polls at the given interval until all scheduled transactions have been broadcast.
+    ///
+    /// Returns early with the error of [`poll`](Self::poll), e.g. if transactions were dropped.
+    pub async fn run(&self, every: Duration) -> Result<(), SchedulerError<M>> {
+        let mut ticks = interval(every);
+        loop {
+            self.poll().await?;
+            if self.queue.lock().unwrap().is_empty() {
+                return Ok(())
+            }
+            ticks.next().await;
+        }
+    }
+
+    /// Writes the queue to the store, via a temporary file so the store is never left corrupted.
+    fn persist(&self, queue: &[ScheduledTransaction]) -> Result<(), SchedulerError<M>> {
+        let Some(ref path) = self.store else { return Ok(()) };
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(queue)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::Block;
+    use ethers_providers::{JsonRpcError, MockResponse, Provider};
+
+    #[test]
+    fn not_before_is_due() {
+        let by_block = NotBefore::Block(10u64.into());
+        assert!(!by_block.is_due(9u64.into(), U256::MAX));
+        assert!(by_block.is_due(10u64.into(), U256::zero()));
+
+        let by_time = NotBefore::Timestamp(1_000u64.into());
+        assert!(!by_time.is_due(U64::MAX, 999u64.into()));
+        assert!(by_time.is_due(0u64.into(), 1_000u64.into()));
+    }
+
+    #[test]
+    fn persists_schedule() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduled.json");
+        let (provider, _) = Provider::mocked();
+
+        let scheduler = BroadcastScheduler::with_store(provider.clone(), &path).unwrap();
+        let tx_hash = scheduler.schedule(vec![1, 2, 3], NotBefore::Block(5u64.into())).unwrap();
+        scheduler.schedule(vec![4, 5, 6], NotBefore::Timestamp(5u64.into())).unwrap();
+
+        let reloaded = BroadcastScheduler::with_store(provider.clone(), &path).unwrap();
+        assert_eq!(reloaded.pending(), scheduler.pending());
+
+        reloaded.cancel(tx_hash).unwrap().unwrap();
+        let reloaded = BroadcastScheduler::with_store(provider, &path).unwrap();
+        assert_eq!(reloaded.pending().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn handles_terminal_broadcast_errors() {
+        let (provider, mock) = Provider::mocked();
+        let scheduler = BroadcastScheduler::new(provider);
+        let known = scheduler.schedule(vec![1], NotBefore::Block(1u64.into())).unwrap();
+        let stale = scheduler.schedule(vec![2], NotBefore::Block(1u64.into())).unwrap();
+        let failing = scheduler.schedule(vec![3], NotBefore::Block(1u64.into())).unwrap();
+
+        let error = |message: &str| {
+            MockResponse::Error(JsonRpcError { code: -32000, message: message.into(), data: None })
+        };
+        // responses are popped from the back
+        mock.push_response(error("insufficient funds for gas * price + value"));
+        mock.push_response(error("nonce too low"));
+        mock.push_response(error("already known"));
+        mock.push(Block::<TxHash> { number: Some(1u64.into()), ..Default::default() }).unwrap();
+
+        match scheduler.poll().await {
+            Err(SchedulerError::NonceTooLow(dropped)) => assert_eq!(dropped, vec![stale]),
+            res => panic!("unexpected poll result {res:?}"),
+        }
+        let pending: Vec<_> = scheduler.pending().into_iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(pending, vec![failing]);
+        assert_ne!(known, failing);
+    }
+}
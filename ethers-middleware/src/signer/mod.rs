@@ -1,14 +1,100 @@
-use ethers_core::types::{
-    transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
-    Address, BlockId, Bytes, Chain, Signature, TransactionRequest, U256,
+use ethers_core::{
+    abi::AbiRegistry,
+    types::{
+        transaction::{eip2612::Permit, eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
+        Address, BlockId, Bytes, Chain, PrivateTransactionOptions, Signature, TransactionRequest,
+        TxHash, U256,
+    },
+};
+use ethers_providers::{
+    erc2612_permit, maybe, Middleware, MiddlewareError, PendingTransaction, PermitError,
 };
-use ethers_providers::{maybe, Middleware, MiddlewareError, PendingTransaction};
 use ethers_signers::Signer;
-use std::convert::TryFrom;
+use std::{convert::TryFrom, error::Error, fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 use thiserror::Error;
 
+mod preview;
+pub use preview::TransactionPreview;
+
+/// The error type returned by [`SignerHooks`] to abort signing.
+pub type SignerHookError = Box<dyn Error + Send + Sync>;
+
+/// Lifecycle hooks that are invoked by the [`SignerMiddleware`] when signing and broadcasting
+/// transactions.
+///
+/// All methods have a no-op default implementation, so implementers only need to override the
+/// hooks they are interested in. This can be used for custom logging, HSM attestations or
+/// approval flows without writing a full middleware.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait SignerHooks: Send + Sync + Debug {
+    /// Called with the human-readable [`TransactionPreview`] of the transaction before it is
+    /// signed, e.g. to show it on a hardware wallet screen or an approval dialog.
+    ///
+    /// Returning an error aborts signing, the error is returned as
+    /// [`SignerMiddlewareError::HookError`].
+    async fn approve(
+        &self,
+        _tx: &TypedTransaction,
+        _preview: &TransactionPreview,
+    ) -> Result<(), SignerHookError> {
+        Ok(())
+    }
+
+    /// Called before the transaction is signed.
+    ///
+    /// Returning an error aborts signing, the error is returned as
+    /// [`SignerMiddlewareError::HookError`].
+    async fn before_sign(&self, _tx: &TypedTransaction) -> Result<(), SignerHookError> {
+        Ok(())
+    }
+
+    /// Called after the transaction was signed, before it is broadcast.
+    ///
+    /// Returning an error discards the signature, the error is returned as
+    /// [`SignerMiddlewareError::HookError`].
+    async fn after_sign(
+        &self,
+        _tx: &TypedTransaction,
+        _signature: &Signature,
+    ) -> Result<(), SignerHookError> {
+        Ok(())
+    }
+
+    /// Called after the signed transaction was accepted by the node.
+    async fn after_broadcast(&self, _tx: &TypedTransaction, _tx_hash: TxHash) {}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T: SignerHooks + ?Sized> SignerHooks for Arc<T> {
+    async fn approve(
+        &self,
+        tx: &TypedTransaction,
+        preview: &TransactionPreview,
+    ) -> Result<(), SignerHookError> {
+        (**self).approve(tx, preview).await
+    }
+
+    async fn before_sign(&self, tx: &TypedTransaction) -> Result<(), SignerHookError> {
+        (**self).before_sign(tx).await
+    }
+
+    async fn after_sign(
+        &self,
+        tx: &TypedTransaction,
+        signature: &Signature,
+    ) -> Result<(), SignerHookError> {
+        (**self).after_sign(tx, signature).await
+    }
+
+    async fn after_broadcast(&self, tx: &TypedTransaction, tx_hash: TxHash) {
+        (**self).after_broadcast(tx, tx_hash).await
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Middleware used for locally signing transactions, compatible with any implementer
 /// of the [`Signer`] trait.
@@ -65,6 +151,8 @@ pub struct SignerMiddleware<M, S> {
     pub(crate) inner: M,
     pub(crate) signer: S,
     pub(crate) address: Address,
+    pub(crate) hooks: Vec<Arc<dyn SignerHooks>>,
+    pub(crate) registry: Option<Arc<AbiRegistry>>,
 }
 
 #[derive(Error, Debug)]
@@ -93,6 +181,12 @@ pub enum SignerMiddlewareError<M: Middleware, S: Signer> {
     /// Thrown if the signer's chain_id is different than the chain_id of the transaction
     #[error("specified chain_id is different than the signer's chain_id")]
     DifferentChainID,
+    /// Thrown if one of the registered [`SignerHooks`] aborted signing
+    #[error("signer hook aborted signing: {0}")]
+    HookError(SignerHookError),
+    /// Thrown if the permit of [`SignerMiddleware::sign_permit`] could not be built
+    #[error("{0}")]
+    PermitError(PermitError<M>),
 }
 
 impl<M: Middleware, S: Signer> MiddlewareError for SignerMiddlewareError<M, S> {
@@ -127,7 +221,40 @@ where
     /// [`Signer`] ethers_signers::Signer
     pub fn new(inner: M, signer: S) -> Self {
         let address = signer.address();
-        SignerMiddleware { inner, signer, address }
+        SignerMiddleware { inner, signer, address, hooks: Vec::new(), registry: None }
+    }
+
+    /// Registers [`SignerHooks`] that are invoked when signing and broadcasting transactions.
+    ///
+    /// Hooks are invoked in the order they were added.
+    #[must_use]
+    pub fn with_hooks<H: SignerHooks + 'static>(mut self, hooks: H) -> Self {
+        self.hooks.push(Arc::new(hooks));
+        self
+    }
+
+    /// Signs the transaction with the signer, invoking the registered hooks.
+    async fn sign_with_hooks(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<Signature, SignerMiddlewareError<M, S>> {
+        if !self.hooks.is_empty() {
+            let preview = self.preview(tx);
+            for hooks in &self.hooks {
+                hooks.approve(tx, &preview).await.map_err(SignerMiddlewareError::HookError)?;
+            }
+        }
+        for hooks in &self.hooks {
+            hooks.before_sign(tx).await.map_err(SignerMiddlewareError::HookError)?;
+        }
+
+        let signature =
+            self.signer.sign_transaction(tx).await.map_err(SignerMiddlewareError::SignerError)?;
+
+        for hooks in &self.hooks {
+            hooks.after_sign(tx, &signature).await.map_err(SignerMiddlewareError::HookError)?;
+        }
+        Ok(signature)
     }
 
     /// Signs and returns the RLP encoding of the signed transaction.
@@ -150,12 +277,57 @@ where
             }
             _ => {}
         }
+        // the sender is part of the typed data zkSync transactions are signed as
+        #[cfg(feature = "zksync")]
+        if tx.from().is_none() {
+            tx.set_from(self.address);
+        }
 
-        let signature =
-            self.signer.sign_transaction(&tx).await.map_err(SignerMiddlewareError::SignerError)?;
+        let signature = self.sign_with_hooks(&tx).await?;
 
         // Return the raw rlp-encoded signed transaction
-        Ok(tx.rlp_signed(&signature))
+        Ok(tx.rlp_network(&signature))
+    }
+
+    /// Sets the [`AbiRegistry`] used to decode the called function in [`TransactionPreview`]s.
+    #[must_use]
+    pub fn with_abi_registry(mut self, registry: impl Into<Arc<AbiRegistry>>) -> Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    /// Returns the human-readable [`TransactionPreview`] of `tx` as it would be signed by this
+    /// client.
+    ///
+    /// The called function is only decoded if an [`AbiRegistry`] was set with
+    /// [`SignerMiddleware::with_abi_registry`].
+    pub fn preview(&self, tx: &TypedTransaction) -> TransactionPreview {
+        TransactionPreview::new(tx, self.signer.chain_id(), self.address, self.registry.as_deref())
+    }
+
+    /// Signs an ERC-2612 permit for `spender` to spend `value` of the client's `token`s until
+    /// `deadline`, fetching the client's nonce and the token's domain with [`erc2612_permit`].
+    ///
+    /// The permit can be submitted by any account with [`Permit::calldata`].
+    pub async fn sign_permit(
+        &self,
+        token: Address,
+        spender: Address,
+        value: U256,
+        deadline: U256,
+    ) -> Result<(Permit, Signature), SignerMiddlewareError<M, S>> {
+        let permit = erc2612_permit(&self.inner, token, self.address, spender, value, deadline)
+            .await
+            .map_err(|err| match err {
+                PermitError::MiddlewareError(err) => SignerMiddlewareError::MiddlewareError(err),
+                err => SignerMiddlewareError::PermitError(err),
+            })?;
+        let signature = self
+            .signer
+            .sign_typed_data(&permit)
+            .await
+            .map_err(SignerMiddlewareError::SignerError)?;
+        Ok((permit, signature))
     }
 
     /// Returns the client's address
@@ -196,7 +368,7 @@ where
         let chain_id =
             inner.get_chainid().await.map_err(|e| SignerMiddlewareError::MiddlewareError(e))?;
         let signer = signer.with_chain_id(chain_id.as_u64());
-        Ok(SignerMiddleware { inner, signer, address })
+        Ok(SignerMiddleware { inner, signer, address, hooks: Vec::new(), registry: None })
     }
 
     fn set_tx_from_if_none(&self, tx: &TypedTransaction) -> TypedTransaction {
@@ -238,7 +410,7 @@ where
         tx: &TypedTransaction,
         _: Address,
     ) -> Result<Signature, Self::Error> {
-        Ok(self.signer.sign_transaction(tx).await.map_err(SignerMiddlewareError::SignerError)?)
+        self.sign_with_hooks(tx).await
     }
 
     /// Helper for filling a transaction's nonce using the wallet
@@ -296,7 +468,7 @@ where
         self.fill_transaction(&mut tx, block).await?;
 
         // If the from address is set and is not our signer, delegate to inner
-        if tx.from().is_some() && tx.from() != Some(&self.address()) {
+        if tx.from().map_or(false, |from| !self.signer.is_signer_for(*from)) {
             return self
                 .inner
                 .send_transaction(tx, block)
@@ -306,13 +478,51 @@ where
 
         // if we have a nonce manager set, we should try handling the result in
         // case there was a nonce mismatch
-        let signed_tx = self.sign_transaction(tx).await?;
+        let signed_tx = self.sign_transaction(tx.clone()).await?;
 
         // Submit the raw transaction
-        self.inner
+        let pending = self
+            .inner
             .send_raw_transaction(signed_tx)
             .await
-            .map_err(SignerMiddlewareError::MiddlewareError)
+            .map_err(SignerMiddlewareError::MiddlewareError)?;
+
+        for hooks in &self.hooks {
+            hooks.after_broadcast(&tx, *pending).await;
+        }
+        Ok(pending)
+    }
+
+    /// Signs the transaction and sends it with `eth_sendPrivateTransaction` to the inner
+    /// middleware's endpoint, keeping it out of the public mempool.
+    async fn send_private_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        options: PrivateTransactionOptions,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let mut tx = tx.into();
+        self.fill_transaction(&mut tx, block).await?;
+
+        if tx.from().map_or(false, |from| !self.signer.is_signer_for(*from)) {
+            return self
+                .inner
+                .send_private_transaction(tx, options, block)
+                .await
+                .map_err(SignerMiddlewareError::MiddlewareError)
+        }
+
+        let signed_tx = self.sign_transaction(tx.clone()).await?;
+        let pending = self
+            .inner
+            .send_private_raw_transaction(signed_tx, options)
+            .await
+            .map_err(SignerMiddlewareError::MiddlewareError)?;
+
+        for hooks in &self.hooks {
+            hooks.after_broadcast(&tx, *pending).await;
+        }
+        Ok(pending)
     }
 
     /// Signs a message with the internal signer, or if none is present it will make a call to
@@ -367,6 +577,37 @@ mod tests {
     use ethers_signers::LocalWallet;
     use std::convert::TryFrom;
 
+    #[tokio::test]
+    async fn sends_private_transaction() {
+        let (provider, mock) = Provider::mocked();
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let wallet = wallet.with_chain_id(1u64);
+        let client = SignerMiddleware::new(provider, wallet.clone());
+
+        let tx: TypedTransaction = TransactionRequest::pay(Address::zero(), 100)
+            .from(wallet.address())
+            .nonce(0)
+            .gas(21000)
+            .gas_price(1)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction(&tx).await.unwrap();
+        let raw = tx.rlp_signed(&signature);
+
+        mock.push(tx.hash(&signature)).unwrap();
+        let options = PrivateTransactionOptions::new().max_block_number(100u64).fast();
+        let pending =
+            client.send_private_transaction(tx.clone(), options.clone(), None).await.unwrap();
+        assert_eq!(*pending, tx.hash(&signature));
+
+        mock.assert_request(
+            "eth_sendPrivateTransaction",
+            [ethers_core::types::PrivateTransactionRequest { tx: raw, options }],
+        )
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn signs_tx() {
         // retrieved test vector from:
@@ -596,4 +837,204 @@ mod tests {
         assert!(tx.as_legacy_ref().is_none());
         assert_eq!(tx, TypedTransaction::Eip1559(tx.as_eip1559_ref().unwrap().clone()));
     }
+
+    #[derive(Debug, Default)]
+    struct RecordingHooks {
+        calls: std::sync::Mutex<Vec<&'static str>>,
+        previews: std::sync::Mutex<Vec<TransactionPreview>>,
+        reject: bool,
+    }
+
+    #[async_trait]
+    impl SignerHooks for RecordingHooks {
+        async fn approve(
+            &self,
+            _: &TypedTransaction,
+            preview: &TransactionPreview,
+        ) -> Result<(), SignerHookError> {
+            self.calls.lock().unwrap().push("approve");
+            self.previews.lock().unwrap().push(preview.clone());
+            Ok(())
+        }
+
+        async fn before_sign(&self, _: &TypedTransaction) -> Result<(), SignerHookError> {
+            self.calls.lock().unwrap().push("before_sign");
+            if self.reject {
+                return Err("rejected".into())
+            }
+            Ok(())
+        }
+
+        async fn after_sign(
+            &self,
+            _: &TypedTransaction,
+            _: &Signature,
+        ) -> Result<(), SignerHookError> {
+            self.calls.lock().unwrap().push("after_sign");
+            Ok(())
+        }
+
+        async fn after_broadcast(&self, _: &TypedTransaction, _: TxHash) {
+            self.calls.lock().unwrap().push("after_broadcast");
+        }
+    }
+
+    #[tokio::test]
+    async fn invokes_hooks() {
+        let anvil = Anvil::new().spawn();
+        let provider = Provider::try_from(anvil.endpoint()).unwrap();
+        let key = LocalWallet::from(anvil.keys()[0].clone()).with_chain_id(anvil.chain_id());
+
+        let hooks = Arc::new(RecordingHooks::default());
+        let client = SignerMiddleware::new(provider.clone(), key.clone()).with_hooks(hooks.clone());
+        client
+            .send_transaction(TransactionRequest::pay(Address::zero(), 1u64), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            *hooks.calls.lock().unwrap(),
+            ["approve", "before_sign", "after_sign", "after_broadcast"]
+        );
+
+        let hooks = Arc::new(RecordingHooks { reject: true, ..Default::default() });
+        let client = SignerMiddleware::new(provider, key).with_hooks(hooks.clone());
+        let err = client
+            .send_transaction(TransactionRequest::pay(Address::zero(), 1u64), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SignerMiddlewareError::HookError(_)));
+        assert_eq!(*hooks.calls.lock().unwrap(), ["approve", "before_sign"]);
+    }
+
+    #[tokio::test]
+    async fn passes_preview_to_hooks() {
+        let (provider, _) = Provider::mocked();
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let wallet = wallet.with_chain_id(1u64);
+        let hooks = Arc::new(RecordingHooks::default());
+        let client = SignerMiddleware::new(provider, wallet)
+            .with_abi_registry(AbiRegistry::with_token_standards())
+            .with_hooks(hooks.clone());
+
+        let operator = Address::repeat_byte(7);
+        let data = [
+            &utils::id("setApprovalForAll(address,bool)")[..],
+            &ethers_core::abi::encode(&[
+                ethers_core::abi::Token::Address(operator),
+                ethers_core::abi::Token::Bool(true),
+            ]),
+        ]
+        .concat();
+        let tx: TypedTransaction =
+            TransactionRequest::new().to(Address::repeat_byte(1)).data(data).nonce(0).into();
+        client.sign_transaction(tx.clone()).await.unwrap();
+
+        let previews = hooks.previews.lock().unwrap();
+        assert_eq!(*previews, [client.preview(&tx)]);
+        assert_eq!(previews[0].from, client.address());
+        assert_eq!(previews[0].chain_id, 1);
+        let function = previews[0].function.as_ref().unwrap();
+        assert_eq!(function.signature, "setApprovalForAll(address,bool)");
+        assert!(previews[0].to_string().contains(&format!("operator: {operator:?}")));
+    }
+
+    #[tokio::test]
+    async fn signs_permit() {
+        use ethers_core::{
+            abi::{encode, Token},
+            types::{
+                transaction::eip712::{EIP712Domain, Eip712},
+                H256,
+            },
+        };
+
+        let (provider, mock) = Provider::mocked();
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let client = SignerMiddleware::new(provider, wallet.with_chain_id(1u64));
+
+        let token = Address::repeat_byte(0xa0);
+        let domain = EIP712Domain {
+            name: Some("Token".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(1.into()),
+            verifying_contract: Some(token),
+            salt: None,
+        };
+        // name, version, DOMAIN_SEPARATOR, chain id and nonce, last in, first out
+        let encoded = |token| Bytes::from(encode(&[token]));
+        mock.push::<Bytes, Bytes>(encoded(Token::Uint(3.into()))).unwrap();
+        mock.push(U256::one()).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::FixedBytes(domain.separator().to_vec()))).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::String("1".to_string()))).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::String("Token".to_string()))).unwrap();
+
+        let spender = Address::repeat_byte(2);
+        let (permit, signature) =
+            client.sign_permit(token, spender, 100.into(), U256::MAX).await.unwrap();
+        assert_eq!(permit.owner, client.address());
+        assert_eq!(permit.nonce, 3.into());
+        assert_eq!(permit.domain, domain);
+        let hash = permit.encode_eip712().unwrap();
+        assert_eq!(signature.recover(H256::from(hash)).unwrap(), client.address());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "celo")]
+    async fn signs_celo_fee_currency_tx() {
+        use ethers_core::types::transaction::celo::Cip64TransactionRequest;
+
+        let (provider, _) = Provider::mocked();
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let client = SignerMiddleware::new(provider, wallet.with_chain_id(44787u64));
+
+        let fee_currency = Address::repeat_byte(0xcc);
+        let tx = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(1))
+            .nonce(0)
+            .gas(21_000)
+            .max_fee_per_gas(10)
+            .max_priority_fee_per_gas(1);
+        let tx: TypedTransaction = Cip64TransactionRequest::new(tx, Some(fee_currency)).into();
+        let raw = client.sign_transaction(tx).await.unwrap();
+
+        let (decoded, _) = TypedTransaction::decode_signed(&utils::rlp::Rlp::new(&raw)).unwrap();
+        assert!(matches!(decoded, TypedTransaction::Cip64(_)));
+        assert_eq!(decoded.from(), Some(&client.address()));
+        assert_eq!(decoded.chain_id(), Some(44787u64.into()));
+        assert_eq!(decoded.fee_currency(), Some(&fee_currency));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "zksync")]
+    async fn signs_zksync_paymaster_tx() {
+        use ethers_core::types::transaction::zksync::{PaymasterParams, ZkSyncTransactionRequest};
+
+        let (provider, _) = Provider::mocked();
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let client = SignerMiddleware::new(provider, wallet.with_chain_id(324u64));
+
+        let paymaster = PaymasterParams::new(Address::repeat_byte(0xbb), vec![0x8c, 0x5a]);
+        let tx = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(1))
+            .nonce(0)
+            .gas(1_000_000)
+            .max_fee_per_gas(250_000_000)
+            .max_priority_fee_per_gas(0);
+        let tx: TypedTransaction = ZkSyncTransactionRequest::new(tx, Default::default())
+            .paymaster_params(paymaster.clone())
+            .into();
+        let raw = client.sign_transaction(tx).await.unwrap();
+
+        let (decoded, signature) =
+            TypedTransaction::decode_signed(&utils::rlp::Rlp::new(&raw)).unwrap();
+        assert_eq!(decoded.from(), Some(&client.address()));
+        assert_eq!(signature.recover(decoded.sighash()).unwrap(), client.address());
+        let decoded = decoded.as_zksync_ref().unwrap();
+        assert_eq!(decoded.meta.paymaster_params, Some(paymaster));
+        assert_eq!(decoded.tx.chain_id, Some(324u64.into()));
+    }
 }
@@ -0,0 +1,231 @@
+use ethers_core::{
+    abi::{AbiRegistry, DecodedFunction, Token},
+    types::{transaction::eip2718::TypedTransaction, Address, Chain, NameOrAddress, U256},
+    utils::{format_ether, format_units},
+};
+use std::{convert::TryFrom, fmt};
+
+/// A human-readable summary of a transaction, meant to be shown to the user before it is signed,
+/// e.g. on the screen of a hardware wallet or in an approval dialog.
+///
+/// Previews are built by [`SignerMiddleware::preview`] and passed to
+/// [`SignerHooks::approve`] before every signature. The `Display` implementation renders one
+/// `label: value` line per field:
+///
+/// ```text
+/// Chain:    mainnet (1)
+/// From:     0x7e5f4552091a69125d5dfcb7b8c2659029395bdf
+/// To:       0xdac17f958d2ee523a2206206994597c13d831ec7
+/// Function: transfer(address,uint256)
+///   to:    0x2b5ad5c4795c026514f8317c7a215e218dccd6cf
+///   value: 1000000
+/// Value:    0 ether
+/// Max fee:  0.00084 ether (40 gwei x 21000 gas)
+/// Nonce:    7
+/// ```
+///
+/// [`SignerMiddleware::preview`]: crate::SignerMiddleware::preview
+/// [`SignerHooks::approve`]: crate::signer::SignerHooks::approve
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionPreview {
+    /// The chain id the transaction is signed for
+    pub chain_id: u64,
+    /// The sender of the transaction
+    pub from: Address,
+    /// The recipient of the transaction, `None` for contract deployments
+    pub to: Option<NameOrAddress>,
+    /// The called function, if the calldata matches a function of the ABI registry
+    pub function: Option<DecodedFunction>,
+    /// The length of the calldata in bytes
+    pub data_len: usize,
+    /// The transferred value in wei
+    pub value: U256,
+    /// The gas limit of the transaction
+    pub gas: Option<U256>,
+    /// The gas price of legacy transactions or the max fee per gas of EIP-1559 transactions
+    pub gas_price: Option<U256>,
+    /// The nonce of the transaction
+    pub nonce: Option<U256>,
+}
+
+impl TransactionPreview {
+    /// Builds the preview of `tx`, decoding its calldata with `registry`.
+    ///
+    /// The `chain_id` and `from` address are used if the transaction does not specify them.
+    pub fn new(
+        tx: &TypedTransaction,
+        chain_id: u64,
+        from: Address,
+        registry: Option<&AbiRegistry>,
+    ) -> Self {
+        let data = tx.data().map(|data| data.as_ref()).unwrap_or_default();
+        let function = match (registry, tx.to()) {
+            (Some(registry), Some(to)) => {
+                registry.decode_call(to.as_address().copied().unwrap_or_default(), data)
+            }
+            _ => None,
+        };
+        Self {
+            chain_id: tx.chain_id().map(|id| id.as_u64()).unwrap_or(chain_id),
+            from: tx.from().copied().unwrap_or(from),
+            to: tx.to().cloned(),
+            function,
+            data_len: data.len(),
+            value: tx.value().copied().unwrap_or_default(),
+            gas: tx.gas().copied(),
+            gas_price: tx.gas_price(),
+            nonce: tx.nonce().copied(),
+        }
+    }
+
+    /// Returns the chain the transaction is signed for, if it is a known [`Chain`].
+    pub fn chain(&self) -> Option<Chain> {
+        Chain::try_from(self.chain_id).ok()
+    }
+
+    /// Returns the most the transaction can cost in fees, the gas limit times the gas price.
+    pub fn max_fee(&self) -> Option<U256> {
+        self.gas?.checked_mul(self.gas_price?)
+    }
+}
+
+impl fmt::Display for TransactionPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.chain() {
+            Some(chain) => writeln!(f, "Chain:    {chain} ({})", self.chain_id)?,
+            None => writeln!(f, "Chain:    {}", self.chain_id)?,
+        }
+        writeln!(f, "From:     {:?}", self.from)?;
+        match &self.to {
+            Some(NameOrAddress::Address(to)) => writeln!(f, "To:       {to:?}")?,
+            Some(NameOrAddress::Name(name)) => writeln!(f, "To:       {name}")?,
+            None => writeln!(f, "To:       contract deployment")?,
+        }
+        if let Some(function) = &self.function {
+            writeln!(f, "Function: {}", function.signature)?;
+            let width = function.params.iter().map(|param| param.name.len()).max().unwrap_or(0);
+            for param in &function.params {
+                let label = format!("{}:", param.name);
+                writeln!(f, "  {label:<width$} {}", DisplayToken(&param.value), width = width + 1)?;
+            }
+        } else if self.data_len > 0 {
+            writeln!(f, "Data:     {} bytes", self.data_len)?;
+        }
+        writeln!(f, "Value:    {} ether", trim(format_ether(self.value)))?;
+        match (self.max_fee(), self.gas, self.gas_price) {
+            (Some(max_fee), Some(gas), Some(gas_price)) => writeln!(
+                f,
+                "Max fee:  {} ether ({} gwei x {gas} gas)",
+                trim(format_ether(max_fee)),
+                trim(format_units(gas_price, "gwei").expect("gwei are valid units")),
+            )?,
+            _ => writeln!(f, "Max fee:  unknown")?,
+        }
+        match self.nonce {
+            Some(nonce) => write!(f, "Nonce:    {nonce}"),
+            None => write!(f, "Nonce:    unknown"),
+        }
+    }
+}
+
+/// Displays addresses with their `0x` prefix and all other tokens like `Token` does
+struct DisplayToken<'a>(&'a Token);
+
+impl fmt::Display for DisplayToken<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Token::Address(address) => write!(f, "{address:?}"),
+            Token::Bool(value) => write!(f, "{value}"),
+            Token::Uint(value) => write!(f, "{value}"),
+            Token::Int(value) => write!(f, "{}", ethers_core::types::I256::from_raw(*value)),
+            Token::Array(tokens) | Token::FixedArray(tokens) => {
+                f.write_str("[")?;
+                for (i, token) in tokens.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", DisplayToken(token))?;
+                }
+                f.write_str("]")
+            }
+            token => write!(f, "{token}"),
+        }
+    }
+}
+
+/// Removes trailing zeros of the fraction of a formatted decimal
+fn trim(mut amount: String) -> String {
+    if amount.contains('.') {
+        let len = amount.trim_end_matches('0').trim_end_matches('.').len();
+        amount.truncate(len);
+    }
+    amount
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::{
+        abi::encode,
+        types::{Eip1559TransactionRequest, TransactionRequest},
+        utils::id,
+    };
+
+    #[test]
+    fn renders_token_transfer() {
+        let registry = AbiRegistry::with_token_standards();
+        let (from, token, to) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        let data = [
+            &id("transfer(address,uint256)")[..],
+            &encode(&[Token::Address(to), Token::Uint(1_000_000.into())]),
+        ]
+        .concat();
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(token)
+            .data(data)
+            .gas(21_000)
+            .max_fee_per_gas(40_000_000_000u64)
+            .max_priority_fee_per_gas(1)
+            .nonce(7)
+            .into();
+
+        let preview = TransactionPreview::new(&tx, 1, from, Some(&registry));
+        assert_eq!(preview.chain(), Some(Chain::Mainnet));
+        assert_eq!(preview.function.as_ref().unwrap().name, "transfer");
+        assert_eq!(
+            preview.to_string(),
+            format!(
+                "Chain:    mainnet (1)\n\
+                 From:     {from:?}\n\
+                 To:       {token:?}\n\
+                 Function: transfer(address,uint256)\n\
+                 \x20 to:    {to:?}\n\
+                 \x20 value: 1000000\n\
+                 Value:    0 ether\n\
+                 Max fee:  0.00084 ether (40 gwei x 21000 gas)\n\
+                 Nonce:    7"
+            )
+        );
+    }
+
+    #[test]
+    fn renders_unknown_calls() {
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(Address::repeat_byte(1))
+            .data(vec![0xde, 0xad, 0xbe, 0xef, 0x00])
+            .value(1_500_000_000_000_000_000u64)
+            .chain_id(31337)
+            .into();
+
+        let preview = TransactionPreview::new(&tx, 1, Address::zero(), None);
+        assert_eq!(preview.from, Address::repeat_byte(1));
+        assert_eq!(preview.max_fee(), None);
+        let rendered = preview.to_string();
+        assert!(rendered.starts_with("Chain:    anvil-hardhat (31337)\n"));
+        assert!(rendered.contains("To:       contract deployment\n"));
+        assert!(rendered.contains("Data:     5 bytes\n"));
+        assert!(rendered.contains("Value:    1.5 ether\n"));
+        assert!(rendered.ends_with("Max fee:  unknown\nNonce:    unknown"));
+    }
+}
@@ -6,6 +6,8 @@ use ethers_core::types::{
 use std::sync::Arc;
 use thiserror::Error;
 
+use crate::BlockWindow;
+
 use ethers_providers::{Middleware, MiddlewareError};
 
 type TimeLagResult<T, M> = Result<T, TimeLagError<M>>;
@@ -43,16 +45,21 @@ impl<M: Middleware> MiddlewareError for TimeLagError<M> {
 #[derive(Debug)]
 pub struct TimeLag<M> {
     inner: Arc<M>,
-    lag: u8,
+    lag: BlockWindow,
 }
 
 impl<M> TimeLag<M>
 where
     M: Middleware,
 {
-    /// Instantiates TimeLag provider
-    pub fn new(inner: M, lag: u8) -> Self {
-        Self { inner: inner.into(), lag }
+    /// Instantiates TimeLag provider, which stays `lag` blocks behind the chain tip
+    pub fn new(inner: M, lag: impl Into<BlockWindow>) -> Self {
+        Self { inner: inner.into(), lag: lag.into() }
+    }
+
+    /// Returns the number of blocks the provider stays behind the chain tip.
+    pub fn lag(&self) -> BlockWindow {
+        self.lag
     }
 }
 
@@ -122,7 +129,7 @@ where
         self.inner()
             .get_block_number()
             .await
-            .map(|num| num - self.lag)
+            .map(|num| num.saturating_sub(self.lag.blocks().into()))
             .map_err(ethers_providers::MiddlewareError::from_err)
     }
 
@@ -0,0 +1,301 @@
+use crate::Confirmations;
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, Address, BlockNumber, TransactionReceipt, U256, U64,
+};
+use ethers_providers::{Middleware, ProviderError};
+use futures_util::future::try_join_all;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+/// The id of a transaction in a [`TxGraph`], in order of insertion.
+pub type TxId = usize;
+
+#[derive(Clone, Debug)]
+struct TxNode {
+    tx: TypedTransaction,
+    dependencies: Vec<TxId>,
+}
+
+/// A set of transactions where each transaction may only be sent once the transactions it
+/// depends on have been confirmed.
+///
+/// A transaction can only depend on transactions that were added before it, so the graph never
+/// contains cycles.
+#[derive(Clone, Debug, Default)]
+pub struct TxGraph {
+    nodes: Vec<TxNode>,
+}
+
+impl TxGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a transaction without dependencies.
+    pub fn add(&mut self, tx: impl Into<TypedTransaction>) -> TxId {
+        self.add_after(tx, std::iter::empty())
+    }
+
+    /// Adds a transaction that is sent once all `dependencies` are confirmed.
+    pub fn add_after(
+        &mut self,
+        tx: impl Into<TypedTransaction>,
+        dependencies: impl IntoIterator<Item = TxId>,
+    ) -> TxId {
+        let mut dependencies: Vec<_> = dependencies.into_iter().collect();
+        dependencies.sort_unstable();
+        dependencies.dedup();
+        self.nodes.push(TxNode { tx: tx.into(), dependencies });
+        self.nodes.len() - 1
+    }
+
+    /// Returns the number of transactions in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the graph contains no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Groups the transactions into waves, in topological order.
+    ///
+    /// All dependencies of a transaction are in earlier waves, so the transactions of a wave can
+    /// be sent concurrently once the previous waves are confirmed.
+    pub fn waves(&self) -> Result<Vec<Vec<TxId>>, TxGraphError> {
+        let mut depth = Vec::with_capacity(self.nodes.len());
+        let mut waves: Vec<Vec<TxId>> = Vec::new();
+        for (id, node) in self.nodes.iter().enumerate() {
+            let mut wave = 0;
+            for &dependency in &node.dependencies {
+                if dependency >= id {
+                    return Err(TxGraphError::InvalidDependency { tx: id, dependency })
+                }
+                wave = wave.max(depth[dependency] + 1);
+            }
+            depth.push(wave);
+            if waves.len() <= wave {
+                waves.push(Vec::new());
+            }
+            waves[wave].push(id);
+        }
+        Ok(waves)
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when the dependencies of a [`TxGraph`] are invalid
+pub enum TxGraphError {
+    /// Thrown when a transaction depends on itself or on a transaction added after it
+    #[error("transaction {tx} depends on transaction {dependency}, which was not added before it")]
+    InvalidDependency {
+        /// The dependent transaction
+        tx: TxId,
+        /// The invalid dependency
+        dependency: TxId,
+    },
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens while executing a [`TxGraph`]
+pub enum TxGraphExecutorError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// Thrown when waiting for a transaction to be confirmed fails
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+    /// Thrown when the graph is invalid
+    #[error(transparent)]
+    Graph(#[from] TxGraphError),
+    /// Thrown when the sender of a transaction can not be determined
+    #[error("no sender for transaction {0}")]
+    MissingSender(TxId),
+    /// Thrown when a transaction was mined but reverted
+    #[error("transaction {id} reverted")]
+    Reverted {
+        /// The reverted transaction
+        id: TxId,
+        /// The receipt of the reverted transaction
+        receipt: Box<TransactionReceipt>,
+    },
+    /// Thrown when a transaction was dropped from the mempool more often than allowed by the
+    /// retries
+    #[error("transaction {0} was dropped")]
+    Dropped(TxId),
+}
+
+/// Sends the transactions of a [`TxGraph`] in dependency order and waits for them to be
+/// confirmed.
+///
+/// Transactions without a nonce are assigned consecutive nonces per sender, starting at the
+/// sender's pending transaction count. Sending a transaction is retried with the same nonce if the
+/// node rejects it or if it is dropped from the mempool.
+///
+/// ```no_run
+/// use ethers_core::types::{Address, TransactionRequest};
+/// use ethers_middleware::tx_graph::{TxGraph, TxGraphExecutor};
+/// use ethers_providers::{Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(from: Address, to: Address) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+///
+/// let mut graph = TxGraph::new();
+/// let deploy = graph.add(TransactionRequest::new().from(from).data(vec![0x00]));
+/// let fund = graph.add(TransactionRequest::new().from(from).to(to).value(1));
+/// graph.add_after(TransactionRequest::new().from(from).to(to).data(vec![0x01]), [deploy, fund]);
+///
+/// let receipts = TxGraphExecutor::new(provider).confirmations(2).execute(graph).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TxGraphExecutor<M> {
+    inner: M,
+    confirmations: Confirmations,
+    retries: usize,
+}
+
+impl<M> TxGraphExecutor<M>
+where
+    M: Middleware,
+{
+    /// Instantiates an executor that waits for 1 confirmation and retries each transaction 3
+    /// times.
+    pub fn new(inner: M) -> Self {
+        Self { inner, confirmations: Confirmations::ONE, retries: 3 }
+    }
+
+    /// Sets the number of confirmations to wait for before dependent transactions are sent.
+    #[must_use]
+    pub fn confirmations(mut self, confirmations: impl Into<Confirmations>) -> Self {
+        self.confirmations = confirmations.into();
+        self
+    }
+
+    /// Sets how often sending a transaction is retried.
+    #[must_use]
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Executes the graph, returning the receipts of all transactions by id.
+    ///
+    /// Execution stops at the first wave that contains a failed transaction. Transactions of that
+    /// wave that were already sent are not cancelled.
+    pub async fn execute(
+        &self,
+        graph: TxGraph,
+    ) -> Result<BTreeMap<TxId, TransactionReceipt>, TxGraphExecutorError<M>> {
+        let waves = graph.waves()?;
+        let mut txs = graph.nodes.into_iter().map(|node| node.tx).collect::<Vec<_>>();
+        self.assign_nonces(&waves, &mut txs).await?;
+
+        let mut receipts = BTreeMap::new();
+        for (index, wave) in waves.into_iter().enumerate() {
+            debug!(wave = index, txs = ?wave, "executing transaction wave");
+            let sends = wave.into_iter().map(|id| self.drive(id, &txs[id]));
+            receipts.extend(try_join_all(sends).await?);
+        }
+        Ok(receipts)
+    }
+
+    /// Assigns consecutive nonces per sender in execution order to transactions without a nonce
+    async fn assign_nonces(
+        &self,
+        waves: &[Vec<TxId>],
+        txs: &mut [TypedTransaction],
+    ) -> Result<(), TxGraphExecutorError<M>> {
+        let mut nonces: HashMap<Address, U256> = HashMap::new();
+        for &id in waves.iter().flatten() {
+            let tx = &mut txs[id];
+            if tx.nonce().is_some() {
+                continue
+            }
+            let sender = tx
+                .from()
+                .copied()
+                .or_else(|| self.inner.default_sender())
+                .ok_or(TxGraphExecutorError::MissingSender(id))?;
+            tx.set_from(sender);
+
+            let nonce = match nonces.get(&sender) {
+                Some(nonce) => *nonce,
+                None => self
+                    .inner
+                    .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+                    .await
+                    .map_err(TxGraphExecutorError::MiddlewareError)?,
+            };
+            tx.set_nonce(nonce);
+            nonces.insert(sender, nonce + 1);
+        }
+        Ok(())
+    }
+
+    /// Sends the transaction and waits for its confirmations, retrying on failures
+    async fn drive(
+        &self,
+        id: TxId,
+        tx: &TypedTransaction,
+    ) -> Result<(TxId, TransactionReceipt), TxGraphExecutorError<M>> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let pending = match self.inner.send_transaction(tx.clone(), None).await {
+                Ok(pending) => pending,
+                Err(err) if attempt <= self.retries => {
+                    warn!(tx = id, attempt, %err, "failed to send transaction, retrying");
+                    continue
+                }
+                Err(err) => return Err(TxGraphExecutorError::MiddlewareError(err)),
+            };
+
+            match pending.confirmations(self.confirmations.get() as usize).await? {
+                Some(receipt) if receipt.status == Some(U64::zero()) => {
+                    return Err(TxGraphExecutorError::Reverted { id, receipt: Box::new(receipt) })
+                }
+                Some(receipt) => return Ok((id, receipt)),
+                None if attempt <= self.retries => {
+                    warn!(tx = id, attempt, "transaction was dropped, retrying")
+                }
+                None => return Err(TxGraphExecutorError::Dropped(id)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::TransactionRequest;
+
+    #[test]
+    fn groups_waves() {
+        let mut graph = TxGraph::new();
+        let a = graph.add(TransactionRequest::new());
+        let b = graph.add(TransactionRequest::new());
+        let c = graph.add_after(TransactionRequest::new(), [a]);
+        let d = graph.add_after(TransactionRequest::new(), [b, c, c]);
+        let e = graph.add_after(TransactionRequest::new(), [a]);
+
+        assert_eq!(graph.waves().unwrap(), vec![vec![a, b], vec![c, e], vec![d]]);
+    }
+
+    #[test]
+    fn rejects_forward_dependencies() {
+        let mut graph = TxGraph::new();
+        let a = graph.add_after(TransactionRequest::new(), [1]);
+        graph.add(TransactionRequest::new());
+
+        assert!(matches!(
+            graph.waves(),
+            Err(TxGraphError::InvalidDependency { tx, dependency: 1 }) if tx == a
+        ));
+    }
+}
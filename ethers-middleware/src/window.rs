@@ -0,0 +1,161 @@
+//! Block counts for middleware configuration, which can be derived from durations.
+//!
+//! Block times differ between chains, so a setting such as "escalate every minute" or "wait for a
+//! minute of confirmations" translates to different block counts. [`BlockWindow`] and
+//! [`Confirmations`] make the unit of these settings explicit and convert durations with the
+//! average block time of a [`Chain`].
+use ethers_core::types::Chain;
+use std::{fmt, time::Duration};
+
+/// Converts `duration` to the number of blocks of `chain` it spans, rounded up.
+fn blocks_in(duration: Duration, chain: Chain) -> Option<u64> {
+    let block_time = chain.average_blocktime_hint()?.as_millis();
+    if block_time == 0 {
+        return None
+    }
+    let blocks = (duration.as_millis() + block_time - 1) / block_time;
+    u64::try_from(blocks).ok()
+}
+
+/// Returns the expected time `chain` takes to produce `blocks`.
+fn duration_of(blocks: u64, chain: Chain) -> Option<Duration> {
+    chain.average_blocktime_hint()?.checked_mul(u32::try_from(blocks).ok()?)
+}
+
+/// A number of consecutive blocks, e.g. how often the
+/// [`GasEscalatorMiddleware`](crate::gas_escalator::GasEscalatorMiddleware) bumps fees or how far
+/// [`TimeLag`](crate::TimeLag) stays behind the chain tip.
+///
+/// ```
+/// use ethers_core::types::Chain;
+/// use ethers_middleware::BlockWindow;
+/// use std::time::Duration;
+///
+/// let minute = Duration::from_secs(60);
+/// assert_eq!(BlockWindow::from_duration(minute, Chain::Mainnet), Some(BlockWindow::new(5)));
+/// assert_eq!(BlockWindow::from_duration(minute, Chain::Optimism), Some(BlockWindow::new(30)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlockWindow(u64);
+
+impl BlockWindow {
+    /// Creates a window of `blocks` blocks.
+    pub const fn new(blocks: u64) -> Self {
+        Self(blocks)
+    }
+
+    /// Creates the window of blocks which `chain` produces in `duration`, rounded up.
+    ///
+    /// Returns `None` if the block time of the chain is unknown.
+    pub fn from_duration(duration: Duration, chain: Chain) -> Option<Self> {
+        blocks_in(duration, chain).map(Self)
+    }
+
+    /// Returns the number of blocks.
+    pub const fn blocks(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the expected time `chain` takes to produce the blocks of the window.
+    pub fn duration(&self, chain: Chain) -> Option<Duration> {
+        duration_of(self.0, chain)
+    }
+}
+
+impl From<u64> for BlockWindow {
+    fn from(blocks: u64) -> Self {
+        Self(blocks)
+    }
+}
+
+impl fmt::Display for BlockWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} blocks", self.0)
+    }
+}
+
+/// The number of blocks which have to be mined on top of the block of a transaction, including
+/// that block, before the transaction is considered final.
+///
+/// ```
+/// use ethers_core::types::Chain;
+/// use ethers_middleware::Confirmations;
+/// use std::time::Duration;
+///
+/// let confirmations = Confirmations::from_duration(Duration::from_secs(30), Chain::Mainnet);
+/// assert_eq!(confirmations, Some(Confirmations::new(3)));
+/// assert_eq!(Confirmations::new(3).duration(Chain::Mainnet), Some(Duration::from_secs(36)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Confirmations(u64);
+
+impl Confirmations {
+    /// A single confirmation, i.e. the transaction is included in a block
+    pub const ONE: Self = Self(1);
+
+    /// Creates a requirement of `confirmations` blocks.
+    pub const fn new(confirmations: u64) -> Self {
+        Self(confirmations)
+    }
+
+    /// Creates the requirement of all blocks which `chain` produces in `duration`, rounded up and
+    /// at least one.
+    ///
+    /// Returns `None` if the block time of the chain is unknown.
+    pub fn from_duration(duration: Duration, chain: Chain) -> Option<Self> {
+        blocks_in(duration, chain).map(|blocks| Self(blocks.max(1)))
+    }
+
+    /// Returns the number of confirmations.
+    pub const fn get(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the expected time `chain` takes to produce the confirmations.
+    pub fn duration(&self, chain: Chain) -> Option<Duration> {
+        duration_of(self.0, chain)
+    }
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self::ONE
+    }
+}
+
+impl From<u64> for Confirmations {
+    fn from(confirmations: u64) -> Self {
+        Self(confirmations)
+    }
+}
+
+impl From<BlockWindow> for Confirmations {
+    fn from(window: BlockWindow) -> Self {
+        Self(window.blocks())
+    }
+}
+
+impl fmt::Display for Confirmations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} confirmations", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_durations() {
+        let window = BlockWindow::from_duration(Duration::from_secs(25), Chain::Mainnet).unwrap();
+        assert_eq!(window.blocks(), 3);
+        assert_eq!(window.duration(Chain::Mainnet), Some(Duration::from_secs(36)));
+        assert_eq!(BlockWindow::from_duration(Duration::ZERO, Chain::Mainnet), Some(0.into()));
+
+        let confirmations = Confirmations::from_duration(Duration::ZERO, Chain::Mainnet);
+        assert_eq!(confirmations, Some(Confirmations::ONE));
+        assert_eq!(Confirmations::from(BlockWindow::new(4)).get(), 4);
+
+        assert_eq!(BlockWindow::from_duration(Duration::from_secs(60), Chain::Goerli), None);
+    }
+}
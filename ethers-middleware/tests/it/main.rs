@@ -18,12 +18,18 @@ mod signer;
 #[cfg(not(feature = "celo"))]
 mod nonce_manager;
 
+#[cfg(not(feature = "celo"))]
+mod pending_limit;
+
 #[cfg(not(feature = "celo"))]
 mod stack;
 
 #[cfg(not(feature = "celo"))]
 mod transformer;
 
+#[cfg(not(feature = "celo"))]
+mod tx_graph;
+
 /// Spawns Anvil and instantiates an Http provider.
 pub fn spawn_anvil() -> (Provider<Http>, AnvilInstance) {
     let anvil = Anvil::new().block_time(1u64).spawn();
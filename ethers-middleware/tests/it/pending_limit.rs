@@ -0,0 +1,24 @@
+use crate::spawn_anvil;
+use ethers_core::types::*;
+use ethers_middleware::MiddlewareBuilder;
+use ethers_providers::Middleware;
+use std::time::Duration;
+
+#[tokio::test]
+async fn pending_limit_queues_sends() {
+    let (provider, anvil) = spawn_anvil();
+    let address = anvil.addresses()[0];
+    let to = anvil.addresses()[1];
+
+    let provider = provider.pending_limit(1).interval(Duration::from_millis(50));
+
+    let tx = TransactionRequest::new().from(address).to(to).value(100u64);
+    let first = *provider.send_transaction(tx.clone(), None).await.unwrap();
+    assert_eq!(provider.pending_count(address), 1);
+
+    // the second send only goes through once the first transaction was mined
+    let second = *provider.send_transaction(tx, None).await.unwrap();
+    assert!(provider.get_transaction_receipt(first).await.unwrap().is_some());
+    assert_eq!(provider.pending_count(address), 1);
+    assert_ne!(first, second);
+}
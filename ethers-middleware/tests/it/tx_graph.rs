@@ -0,0 +1,27 @@
+use crate::spawn_anvil;
+use ethers_core::types::*;
+use ethers_middleware::tx_graph::{TxGraph, TxGraphExecutor};
+use ethers_providers::Middleware;
+
+#[tokio::test]
+async fn executes_graph_in_dependency_order() {
+    let (provider, anvil) = spawn_anvil();
+    let from = anvil.addresses()[0];
+    let to = anvil.addresses()[1];
+    let nonce = provider.get_transaction_count(from, None).await.unwrap();
+
+    let tx = TransactionRequest::new().from(from).to(to).value(100u64);
+    let mut graph = TxGraph::new();
+    let first = graph.add(tx.clone());
+    let second = graph.add(tx.clone());
+    let last = graph.add_after(tx, [first, second]);
+
+    let receipts = TxGraphExecutor::new(provider.clone()).execute(graph).await.unwrap();
+    assert_eq!(receipts.len(), 3);
+    assert!(receipts[&last].block_number > receipts[&first].block_number);
+    assert!(receipts[&last].block_number > receipts[&second].block_number);
+
+    let last_tx =
+        provider.get_transaction(receipts[&last].transaction_hash).await.unwrap().unwrap();
+    assert_eq!(last_tx.nonce, nonce + 2);
+}
@@ -0,0 +1,247 @@
+//! Benchmarks of the request throughput and latency of a transport and middleware stack.
+//!
+//! [`bench`] measures any request, [`bench_middleware`] a set of common read-only requests over
+//! a [`Middleware`], and [`bench_anvil_transports`] compares the transports enabled by the crate's
+//! features against a local Anvil node. Reports serialize to JSON, so that runs can be stored and
+//! compared.
+//!
+//! ```no_run
+//! use ethers_providers::bench::{bench_anvil_transports, BenchConfig};
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let reports = bench_anvil_transports(&BenchConfig::default()).await?;
+//! for report in &reports {
+//!     println!("{}", report.to_json());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::{Http, Middleware, Provider, ProviderError};
+use ethers_core::{
+    types::{Address, BlockNumber},
+    utils::Anvil,
+};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+/// The parameters of a benchmark run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// The number of measured requests
+    pub requests: usize,
+    /// The number of requests in flight at the same time
+    pub concurrency: usize,
+    /// The number of requests sent before measuring, e.g. to establish connections
+    pub warmup: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self { requests: 1_000, concurrency: 16, warmup: 10 }
+    }
+}
+
+/// The latency distribution of the requests of a benchmark, in microseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// The fastest request
+    pub min_us: u64,
+    /// The mean of all requests
+    pub mean_us: u64,
+    /// The median
+    pub p50_us: u64,
+    /// The 90th percentile
+    pub p90_us: u64,
+    /// The 99th percentile
+    pub p99_us: u64,
+    /// The slowest request
+    pub max_us: u64,
+}
+
+impl LatencyStats {
+    /// Computes the distribution of `samples`, which are sorted in place.
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default()
+        }
+        samples.sort_unstable();
+        let micros = |duration: Duration| duration.as_micros() as u64;
+        let percentile =
+            |p: usize| micros(samples[(samples.len() * p / 100).min(samples.len() - 1)]);
+        let total: Duration = samples.iter().sum();
+        Self {
+            min_us: micros(samples[0]),
+            mean_us: micros(total / samples.len() as u32),
+            p50_us: percentile(50),
+            p90_us: percentile(90),
+            p99_us: percentile(99),
+            max_us: micros(samples[samples.len() - 1]),
+        }
+    }
+}
+
+/// The result of a benchmark
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// The name of the measured request, e.g. `eth_blockNumber`
+    pub name: String,
+    /// The name of the transport or middleware stack, e.g. `http`
+    pub transport: String,
+    /// The number of measured requests
+    pub requests: usize,
+    /// The number of requests which failed
+    pub errors: usize,
+    /// The number of requests in flight at the same time
+    pub concurrency: usize,
+    /// The wall time of all measured requests, in milliseconds
+    pub total_ms: u64,
+    /// The number of requests per second
+    pub throughput: f64,
+    /// The latency of the requests, including failed ones
+    pub latency: LatencyStats,
+}
+
+impl BenchReport {
+    /// Serializes the report to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("report is always serializable")
+    }
+}
+
+/// Measures the requests returned by `request`, according to `config`.
+///
+/// `request` is called once per request, so that every request can be built anew.
+pub async fn bench<F, Fut, T, E>(
+    name: impl Into<String>,
+    transport: impl Into<String>,
+    config: &BenchConfig,
+    mut request: F,
+) -> BenchReport
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let concurrency = config.concurrency.max(1);
+    for _ in 0..config.warmup {
+        let _ = request().await;
+    }
+
+    let start = Instant::now();
+    let results: Vec<_> = stream::iter((0..config.requests).map(|_| {
+        let request = request();
+        async move {
+            let start = Instant::now();
+            let ok = request.await.is_ok();
+            (start.elapsed(), ok)
+        }
+    }))
+    .buffer_unordered(concurrency)
+    .collect()
+    .await;
+    let total = start.elapsed();
+
+    let errors = results.iter().filter(|(_, ok)| !ok).count();
+    let mut samples: Vec<_> = results.into_iter().map(|(latency, _)| latency).collect();
+    let throughput =
+        if total.is_zero() { 0.0 } else { config.requests as f64 / total.as_secs_f64() };
+    BenchReport {
+        name: name.into(),
+        transport: transport.into(),
+        requests: config.requests,
+        errors,
+        concurrency,
+        total_ms: total.as_millis() as u64,
+        throughput,
+        latency: LatencyStats::from_samples(&mut samples),
+    }
+}
+
+/// Measures `eth_blockNumber`, `eth_getBalance` and `eth_getBlockByNumber` requests over
+/// `client`, reporting them as sent over `transport`.
+pub async fn bench_middleware<M: Middleware>(
+    client: &M,
+    transport: &str,
+    config: &BenchConfig,
+) -> Vec<BenchReport> {
+    vec![
+        bench("eth_blockNumber", transport, config, || client.get_block_number()).await,
+        bench("eth_getBalance", transport, config, || client.get_balance(Address::zero(), None))
+            .await,
+        bench("eth_getBlockByNumber", transport, config, || client.get_block(BlockNumber::Latest))
+            .await,
+    ]
+}
+
+/// Spawns an Anvil node and runs [`bench_middleware`] over HTTP, and over WebSockets and IPC if
+/// the `ws` and `ipc` features are enabled.
+///
+/// Requires `anvil` to be installed, see [`Anvil`].
+pub async fn bench_anvil_transports(
+    config: &BenchConfig,
+) -> Result<Vec<BenchReport>, ProviderError> {
+    let anvil = Anvil::new();
+    #[cfg(feature = "ipc")]
+    let ipc_path = std::env::temp_dir().join(format!("ethers-bench-{}.ipc", std::process::id()));
+    #[cfg(feature = "ipc")]
+    let anvil = anvil.args(["--ipc".to_string(), ipc_path.display().to_string()]);
+    let anvil = anvil.spawn();
+
+    let http = Provider::<Http>::try_from(anvil.endpoint())
+        .map_err(|err| ProviderError::CustomError(err.to_string()))?;
+    #[allow(unused_mut)]
+    let mut reports = bench_middleware(&http, "http", config).await;
+
+    #[cfg(feature = "ws")]
+    {
+        let ws = Provider::<crate::Ws>::connect(anvil.ws_endpoint()).await?;
+        reports.extend(bench_middleware(&ws, "ws", config).await);
+    }
+
+    #[cfg(feature = "ipc")]
+    {
+        let ipc = Provider::connect_ipc(&ipc_path).await?;
+        reports.extend(bench_middleware(&ipc, "ipc", config).await);
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_latency_stats() {
+        let mut samples: Vec<_> = (1..=100).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_samples(&mut samples);
+        assert_eq!(stats.min_us, 1_000);
+        assert_eq!(stats.mean_us, 50_500);
+        assert_eq!(stats.p50_us, 51_000);
+        assert_eq!(stats.p99_us, 100_000);
+        assert_eq!(stats.max_us, 100_000);
+        assert_eq!(LatencyStats::from_samples(&mut []), LatencyStats::default());
+    }
+
+    #[tokio::test]
+    async fn counts_errors() {
+        let config = BenchConfig { requests: 10, concurrency: 3, warmup: 2 };
+        let mut calls = 0;
+        let report = bench("test", "mock", &config, || {
+            calls += 1;
+            futures_util::future::ready(if calls % 2 == 0 { Ok(()) } else { Err(()) })
+        })
+        .await;
+        assert_eq!(calls, 12);
+        assert_eq!(report.requests, 10);
+        assert_eq!(report.errors, 5);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["transport"], "mock");
+        assert!(json["latency"]["max_us"].is_u64());
+    }
+}
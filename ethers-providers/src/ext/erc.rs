@@ -1,8 +1,21 @@
 //! ERC related utilities. Only supporting NFTs for now.
-use ethers_core::types::{Address, Selector, U256};
+use crate::Middleware;
+use base64::{engine::general_purpose, Engine};
+use ethers_core::{
+    abi::{decode, ParamType, Token},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockNumber, Filter, Log, Selector,
+        TransactionRequest, H256, U256,
+    },
+    utils::keccak256,
+};
 
-use serde::Deserialize;
-use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+use thiserror::Error;
 use url::Url;
 
 /// ownerOf(uint256 tokenId)
@@ -102,3 +115,396 @@ pub fn http_link_ipfs(url: Url) -> Result<Url, String> {
         .join(url.to_string().trim_start_matches("ipfs://").trim_start_matches("ipfs/"))
         .map_err(|e| e.to_string())
 }
+
+/// Returns a HTTP url for an IPFS object on the given `gateway`, e.g. `https://ipfs.io/ipfs/`.
+///
+/// Accepts `ipfs://<cid>/<path>`, the non-standard `ipfs://ipfs/<cid>` and `/ipfs/<cid>` forms.
+pub fn ipfs_gateway_link(uri: &str, gateway: &Url) -> Result<Url, String> {
+    let path =
+        uri.trim_start_matches("ipfs://").trim_start_matches('/').trim_start_matches("ipfs/");
+    gateway.join(path).map_err(|e| e.to_string())
+}
+
+/// Substitutes the `{id}` placeholder of an ERC-1155 metadata URI with the hex encoded token id,
+/// as specified by the standard.
+pub fn expand_erc1155_uri(uri: &str, id: U256) -> String {
+    let mut bytes = [0u8; 32];
+    id.to_big_endian(&mut bytes);
+    let id = hex::encode(bytes);
+    uri.replace("{id}", &id).replace("%7Bid%7D", &id)
+}
+
+/// Standard ERC-721 and ERC-1155 metadata document, including the commonly used OpenSea
+/// extensions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NftMetadata {
+    /// The name of the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The description of the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The URI of the image of the asset
+    #[serde(default, alias = "image_url", skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// The URI of a multimedia attachment of the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation_url: Option<String>,
+    /// A link to the asset on an external site
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_url: Option<String>,
+    /// The number of decimals of ERC-1155 token amounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+    /// The traits of the asset
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<NftAttribute>,
+    /// Arbitrary ERC-1155 properties
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+    /// All other fields
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// A trait of an NFT
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NftAttribute {
+    /// The name of the trait
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trait_type: Option<String>,
+    /// The value of the trait, usually a string or a number
+    pub value: serde_json::Value,
+    /// How the trait should be displayed, e.g. `number` or `date`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
+}
+
+/// Errors when fetching NFT metadata
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    /// The URI has an unsupported scheme or is malformed
+    #[error("invalid metadata uri: {0}")]
+    InvalidUri(String),
+    /// The metadata could not be fetched
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// The metadata is not a valid JSON document
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The base64 payload of a `data:` URI is invalid
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+}
+
+/// Fetches and parses the metadata document at `uri`.
+///
+/// `ipfs://` URIs are resolved with `gateway`, and `data:application/json` URIs of fully
+/// on-chain tokens are decoded in place.
+pub async fn fetch_metadata(uri: &str, gateway: &Url) -> Result<NftMetadata, MetadataError> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (media_type, payload) =
+            data.split_once(',').ok_or_else(|| MetadataError::InvalidUri(uri.to_string()))?;
+        if !media_type.starts_with("application/json") {
+            return Err(MetadataError::InvalidUri(uri.to_string()))
+        }
+        let json = if media_type.ends_with(";base64") {
+            general_purpose::STANDARD.decode(payload)?
+        } else {
+            payload.as_bytes().to_vec()
+        };
+        return Ok(serde_json::from_slice(&json)?)
+    }
+
+    let url = if uri.starts_with("ipfs://") {
+        ipfs_gateway_link(uri, gateway)
+    } else {
+        Url::parse(uri).map_err(|e| e.to_string())
+    }
+    .map_err(MetadataError::InvalidUri)?;
+    match url.scheme() {
+        "https" | "http" => Ok(reqwest::get(url).await?.error_for_status()?.json().await?),
+        _ => Err(MetadataError::InvalidUri(uri.to_string())),
+    }
+}
+
+/// Errors of the NFT helpers that query contracts
+#[derive(Debug, Error)]
+pub enum NftError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// The contract returned data that could not be decoded
+    #[error(transparent)]
+    Abi(#[from] ethers_core::abi::Error),
+    /// The metadata could not be fetched
+    #[error(transparent)]
+    Metadata(#[from] MetadataError),
+}
+
+/// Returns the metadata URI of `token`, calling `tokenURI` for ERC-721 and `uri` for ERC-1155
+/// tokens.
+///
+/// The `{id}` placeholder of ERC-1155 URIs is substituted with the token id.
+pub async fn token_uri<M: Middleware>(client: &M, token: &ERCNFT) -> Result<String, NftError<M>> {
+    let selector = token.type_.resolution_selector();
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(token.contract)
+        .data([&selector[..], &token.id].concat())
+        .into();
+    let data = client.call(&tx, None).await.map_err(NftError::MiddlewareError)?;
+    let uri = decode(&[ParamType::String], &data)?
+        .pop()
+        .and_then(Token::into_string)
+        .ok_or(ethers_core::abi::Error::InvalidData)?;
+
+    Ok(match token.type_ {
+        ERCNFTType::ERC721 => uri,
+        ERCNFTType::ERC1155 => expand_erc1155_uri(&uri, U256::from_big_endian(&token.id)),
+    })
+}
+
+/// Fetches the metadata of `token`, see [`token_uri`] and [`fetch_metadata`].
+pub async fn token_metadata<M: Middleware>(
+    client: &M,
+    token: &ERCNFT,
+    gateway: &Url,
+) -> Result<NftMetadata, NftError<M>> {
+    let uri = token_uri(client, token).await?;
+    Ok(fetch_metadata(&uri, gateway).await?)
+}
+
+const ERC721_TRANSFER: &str = "Transfer(address,address,uint256)";
+const ERC1155_TRANSFER_SINGLE: &str = "TransferSingle(address,address,address,uint256,uint256)";
+const ERC1155_TRANSFER_BATCH: &str = "TransferBatch(address,address,address,uint256[],uint256[])";
+
+/// Returns the ids of the ERC-721 tokens of `contract` held by `owner`, reconstructed from the
+/// `Transfer` logs since `from_block`.
+///
+/// This works for collections that do not implement the enumerable extension. `from_block` must
+/// not be later than the first transfer to `owner`, e.g. the deployment block of the contract.
+/// Providers limit the range of `eth_getLogs`, so large collections may require a
+/// [`LogQuery`](crate::LogQuery) and [`erc721_holdings_from_logs`] instead.
+pub async fn erc721_holdings<M: Middleware>(
+    client: &M,
+    contract: Address,
+    owner: Address,
+    from_block: BlockNumber,
+) -> Result<BTreeSet<U256>, NftError<M>> {
+    let filter = Filter::new().address(contract).event(ERC721_TRANSFER).from_block(from_block);
+    let logs = transfer_logs(client, filter, owner, 1).await?;
+    Ok(erc721_holdings_from_logs(owner, &logs))
+}
+
+/// Returns the balances of the ERC-1155 tokens of `contract` held by `owner`, reconstructed from
+/// the `TransferSingle` and `TransferBatch` logs since `from_block`.
+///
+/// See [`erc721_holdings`] for the requirements on `from_block`.
+pub async fn erc1155_holdings<M: Middleware>(
+    client: &M,
+    contract: Address,
+    owner: Address,
+    from_block: BlockNumber,
+) -> Result<BTreeMap<U256, U256>, NftError<M>> {
+    let filter = Filter::new()
+        .address(contract)
+        .events([ERC1155_TRANSFER_SINGLE, ERC1155_TRANSFER_BATCH])
+        .from_block(from_block);
+    let logs = transfer_logs(client, filter, owner, 2).await?;
+    Ok(erc1155_holdings_from_logs(owner, &logs))
+}
+
+/// Fetches the logs where `owner` is the sender or the recipient, the indexed topics at
+/// `from_topic` and `from_topic + 1`, in chain order.
+async fn transfer_logs<M: Middleware>(
+    client: &M,
+    filter: Filter,
+    owner: Address,
+    from_topic: usize,
+) -> Result<Vec<Log>, NftError<M>> {
+    let mut sent = filter.clone();
+    sent.topics[from_topic] = Some(H256::from(owner).into());
+    let mut received = filter;
+    received.topics[from_topic + 1] = Some(H256::from(owner).into());
+
+    let (sent, received) =
+        futures_util::try_join!(client.get_logs(&sent), client.get_logs(&received))
+            .map_err(NftError::MiddlewareError)?;
+    let mut logs: Vec<_> = sent.into_iter().chain(received).collect();
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+    // transfers to self are returned by both queries
+    logs.dedup_by_key(|log| (log.block_number, log.log_index));
+    Ok(logs)
+}
+
+/// Replays ERC-721 `Transfer` logs, in chain order, and returns the ids of the tokens held by
+/// `owner` afterwards. Logs of other events are ignored.
+pub fn erc721_holdings_from_logs(owner: Address, logs: &[Log]) -> BTreeSet<U256> {
+    let transfer = H256::from(keccak256(ERC721_TRANSFER));
+    let owner = H256::from(owner);
+    let mut held = BTreeSet::new();
+    for log in logs {
+        // ERC-20 transfers share the signature but do not index the amount
+        if log.topics.len() != 4 || log.topics[0] != transfer {
+            continue
+        }
+        let id = U256::from_big_endian(log.topics[3].as_bytes());
+        if log.topics[1] == owner {
+            held.remove(&id);
+        }
+        if log.topics[2] == owner {
+            held.insert(id);
+        }
+    }
+    held
+}
+
+/// Replays ERC-1155 `TransferSingle` and `TransferBatch` logs, in chain order, and returns the
+/// non-zero balances of `owner` afterwards. Logs of other events are ignored.
+pub fn erc1155_holdings_from_logs(owner: Address, logs: &[Log]) -> BTreeMap<U256, U256> {
+    let single = H256::from(keccak256(ERC1155_TRANSFER_SINGLE));
+    let batch = H256::from(keccak256(ERC1155_TRANSFER_BATCH));
+    let owner = H256::from(owner);
+    let mut balances = BTreeMap::<U256, U256>::new();
+    for log in logs {
+        if log.topics.len() != 4 {
+            continue
+        }
+        let transfers = if log.topics[0] == single {
+            decode_transfer_single(&log.data)
+        } else if log.topics[0] == batch {
+            decode_transfer_batch(&log.data)
+        } else {
+            None
+        };
+
+        for (id, value) in transfers.unwrap_or_default() {
+            let balance = balances.entry(id).or_default();
+            if log.topics[2] == owner {
+                *balance = balance.saturating_sub(value);
+            }
+            if log.topics[3] == owner {
+                *balance = balance.saturating_add(value);
+            }
+        }
+    }
+    balances.retain(|_, balance| !balance.is_zero());
+    balances
+}
+
+fn decode_transfer_single(data: &[u8]) -> Option<Vec<(U256, U256)>> {
+    let mut tokens = decode(&[ParamType::Uint(256), ParamType::Uint(256)], data).ok()?.into_iter();
+    Some(vec![(tokens.next()?.into_uint()?, tokens.next()?.into_uint()?)])
+}
+
+fn decode_transfer_batch(data: &[u8]) -> Option<Vec<(U256, U256)>> {
+    let uints = ParamType::Array(Box::new(ParamType::Uint(256)));
+    let mut tokens = decode(&[uints.clone(), uints], data).ok()?.into_iter();
+    let (ids, values) = (tokens.next()?.into_array()?, tokens.next()?.into_array()?);
+    ids.into_iter()
+        .zip(values)
+        .map(|(id, value)| Some((id.into_uint()?, value.into_uint()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::abi::encode;
+
+    fn log(block: u64, topics: Vec<H256>, data: Vec<u8>) -> Log {
+        Log {
+            topics,
+            data: data.into(),
+            block_number: Some(block.into()),
+            log_index: Some(0.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn links_ipfs_uris() {
+        let gateway = Url::parse("https://gateway.example/ipfs/").unwrap();
+        for uri in ["ipfs://QmHash/1.json", "ipfs://ipfs/QmHash/1.json", "/ipfs/QmHash/1.json"] {
+            assert_eq!(
+                ipfs_gateway_link(uri, &gateway).unwrap().as_str(),
+                "https://gateway.example/ipfs/QmHash/1.json"
+            );
+        }
+    }
+
+    #[test]
+    fn expands_erc1155_uris() {
+        assert_eq!(
+            expand_erc1155_uri("https://token.example/{id}.json", 314592.into()),
+            "https://token.example/000000000000000000000000000000000000000000000000000000000004cce0.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn decodes_data_uri_metadata() {
+        let gateway = Url::parse(IPFS_GATEWAY).unwrap();
+        let json = r#"{"name":"Token","image":"ipfs://QmImage","attributes":[{"trait_type":"Level","value":5}],"seller_fee_basis_points":100}"#;
+        let uri =
+            format!("data:application/json;base64,{}", general_purpose::STANDARD.encode(json));
+
+        let metadata = fetch_metadata(&uri, &gateway).await.unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Token"));
+        assert_eq!(metadata.image.as_deref(), Some("ipfs://QmImage"));
+        assert_eq!(metadata.attributes[0].value, serde_json::json!(5));
+        assert_eq!(metadata.other["seller_fee_basis_points"], serde_json::json!(100));
+
+        let plain = format!("data:application/json;utf8,{json}");
+        assert_eq!(fetch_metadata(&plain, &gateway).await.unwrap(), metadata);
+    }
+
+    #[test]
+    fn reconstructs_erc721_holdings() {
+        let owner = Address::repeat_byte(1);
+        let other = H256::from(Address::repeat_byte(2));
+        let transfer = H256::from(keccak256(ERC721_TRANSFER));
+        let id = |id: u64| H256::from_low_u64_be(id);
+
+        let logs = vec![
+            log(1, vec![transfer, H256::zero(), owner.into(), id(1)], vec![]),
+            log(2, vec![transfer, H256::zero(), owner.into(), id(2)], vec![]),
+            log(3, vec![transfer, owner.into(), other, id(1)], vec![]),
+            // an ERC-20 transfer of the same contract
+            log(4, vec![transfer, other, owner.into()], encode(&[Token::Uint(3.into())])),
+        ];
+        assert_eq!(erc721_holdings_from_logs(owner, &logs), BTreeSet::from([U256::from(2)]));
+    }
+
+    #[test]
+    fn reconstructs_erc1155_holdings() {
+        let owner = Address::repeat_byte(1);
+        let operator = H256::from(Address::repeat_byte(9));
+        let single = H256::from(keccak256(ERC1155_TRANSFER_SINGLE));
+        let batch = H256::from(keccak256(ERC1155_TRANSFER_BATCH));
+        let uints = |values: &[u64]| {
+            Token::Array(values.iter().map(|value| Token::Uint((*value).into())).collect())
+        };
+
+        let logs = vec![
+            log(
+                1,
+                vec![batch, operator, H256::zero(), owner.into()],
+                encode(&[uints(&[1, 2]), uints(&[10, 5])]),
+            ),
+            log(
+                2,
+                vec![single, operator, owner.into(), H256::zero()],
+                encode(&[Token::Uint(2.into()), Token::Uint(5.into())]),
+            ),
+            log(
+                3,
+                vec![single, operator, owner.into(), operator],
+                encode(&[Token::Uint(1.into()), Token::Uint(4.into())]),
+            ),
+        ];
+        assert_eq!(
+            erc1155_holdings_from_logs(owner, &logs),
+            BTreeMap::from([(U256::from(1), U256::from(6))])
+        );
+    }
+}
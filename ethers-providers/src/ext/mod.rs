@@ -7,6 +7,12 @@ pub use ens::*;
 
 pub mod erc;
 
+mod permit;
+pub use permit::{erc2612_permit, permit_domain, PermitError, DEFAULT_PERMIT_VERSION};
+
+mod sender_nonce;
+pub use sender_nonce::get_transaction_by_sender_and_nonce;
+
 #[cfg(feature = "dev-rpc")]
 pub mod dev_rpc;
 #[cfg(feature = "dev-rpc")]
@@ -0,0 +1,188 @@
+//! ERC-2612 permits of ERC-20 tokens
+use crate::{Middleware, MiddlewareError};
+use ethers_core::{
+    abi::{decode, encode, ParamType, Token},
+    types::{
+        transaction::{eip2612::Permit, eip2718::TypedTransaction, eip712::EIP712Domain},
+        Address, Bytes, TransactionRequest, H256, U256,
+    },
+    utils::id,
+};
+use thiserror::Error;
+
+/// The version of the EIP-712 domain of tokens without a `version()` getter, like OpenZeppelin's
+/// `ERC20Permit` before v5
+pub const DEFAULT_PERMIT_VERSION: &str = "1";
+
+/// Errors when building a permit
+#[derive(Debug, Error)]
+pub enum PermitError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// The token returned data that could not be decoded
+    #[error(transparent)]
+    Abi(#[from] ethers_core::abi::Error),
+    /// The domain built from the token's name and version doesn't match its `DOMAIN_SEPARATOR`
+    #[error("domain separator mismatch: token has {expected:?}, built {built:?}")]
+    DomainMismatch {
+        /// The token's `DOMAIN_SEPARATOR()`
+        expected: H256,
+        /// The separator of the domain built from the token's name and version
+        built: H256,
+    },
+}
+
+/// Returns the EIP-712 domain of the ERC-2612 `token`, built from its `name()`, its `version()`
+/// and the client's chain id.
+///
+/// Tokens without a `version()` getter use [`DEFAULT_PERMIT_VERSION`]. The domain is checked
+/// against the token's `DOMAIN_SEPARATOR()`, so that no permits are signed for a domain the token
+/// would reject.
+pub async fn permit_domain<M: Middleware>(
+    client: &M,
+    token: Address,
+) -> Result<EIP712Domain, PermitError<M>> {
+    let name = call(client, token, "name()", &[]).await?;
+    let name = decode_single(ParamType::String, &name)?
+        .into_string()
+        .ok_or(ethers_core::abi::Error::InvalidData)?;
+
+    let version = match call(client, token, "version()", &[]).await {
+        Ok(version) => decode_single(ParamType::String, &version).ok().and_then(Token::into_string),
+        // the token doesn't implement the getter
+        Err(PermitError::MiddlewareError(err)) if err.as_error_response().is_some() => None,
+        Err(err) => return Err(err),
+    };
+
+    let expected = call(client, token, "DOMAIN_SEPARATOR()", &[]).await?;
+    let expected = decode_single(ParamType::FixedBytes(32), &expected)?
+        .into_fixed_bytes()
+        .map(|bytes| H256::from_slice(&bytes))
+        .ok_or(ethers_core::abi::Error::InvalidData)?;
+
+    let chain_id = client.get_chainid().await.map_err(PermitError::MiddlewareError)?;
+    let domain = EIP712Domain {
+        name: Some(name),
+        version: Some(version.unwrap_or_else(|| DEFAULT_PERMIT_VERSION.to_string())),
+        chain_id: Some(chain_id),
+        verifying_contract: Some(token),
+        salt: None,
+    };
+
+    let built = H256(domain.separator());
+    if built != expected {
+        return Err(PermitError::DomainMismatch { expected, built })
+    }
+    Ok(domain)
+}
+
+/// Returns the permit for `spender` to spend `value` of the `owner`'s `token`s until `deadline`,
+/// fetching the owner's current nonce and the token's domain, see [`permit_domain`].
+///
+/// The permit implements `Eip712` and is signed with `Signer::sign_typed_data`.
+pub async fn erc2612_permit<M: Middleware>(
+    client: &M,
+    token: Address,
+    owner: Address,
+    spender: Address,
+    value: U256,
+    deadline: U256,
+) -> Result<Permit, PermitError<M>> {
+    let domain = permit_domain(client, token).await?;
+    let nonce = call(client, token, "nonces(address)", &[Token::Address(owner)]).await?;
+    let nonce = decode_single(ParamType::Uint(256), &nonce)?
+        .into_uint()
+        .ok_or(ethers_core::abi::Error::InvalidData)?;
+
+    Ok(Permit::new(domain, owner, spender, value, nonce).deadline(deadline))
+}
+
+async fn call<M: Middleware>(
+    client: &M,
+    token: Address,
+    signature: &str,
+    args: &[Token],
+) -> Result<Bytes, PermitError<M>> {
+    let data = [&id(signature)[..], &encode(args)].concat();
+    let tx: TypedTransaction = TransactionRequest::new().to(token).data(data).into();
+    client.call(&tx, None).await.map_err(PermitError::MiddlewareError)
+}
+
+fn decode_single(kind: ParamType, data: &[u8]) -> Result<Token, ethers_core::abi::Error> {
+    decode(&[kind], data)?.pop().ok_or(ethers_core::abi::Error::InvalidData)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonRpcError, MockResponse, Provider};
+
+    fn usdc_domain(version: &str) -> EIP712Domain {
+        EIP712Domain {
+            name: Some("USD Coin".to_string()),
+            version: Some(version.to_string()),
+            chain_id: Some(1.into()),
+            verifying_contract: Some(Address::repeat_byte(0xa0)),
+            salt: None,
+        }
+    }
+
+    fn encoded(token: Token) -> Bytes {
+        encode(&[token]).into()
+    }
+
+    #[tokio::test]
+    async fn fetches_permit() {
+        let (provider, mock) = Provider::mocked();
+        let domain = usdc_domain("2");
+
+        // responses are returned last in, first out
+        mock.push::<Bytes, Bytes>(encoded(Token::Uint(7.into()))).unwrap();
+        mock.push(U256::one()).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::FixedBytes(domain.separator().to_vec()))).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::String("2".to_string()))).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::String("USD Coin".to_string()))).unwrap();
+
+        let (owner, spender) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let permit = erc2612_permit(
+            &provider,
+            Address::repeat_byte(0xa0),
+            owner,
+            spender,
+            100.into(),
+            20.into(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(permit, Permit::new(domain, owner, spender, 100, 7).deadline(20));
+    }
+
+    #[tokio::test]
+    async fn defaults_version_and_checks_separator() {
+        let (provider, mock) = Provider::mocked();
+        let reverted = || {
+            MockResponse::Error(JsonRpcError {
+                code: 3,
+                message: "execution reverted".to_string(),
+                data: None,
+            })
+        };
+
+        mock.push(U256::one()).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::FixedBytes(usdc_domain("1").separator().to_vec())))
+            .unwrap();
+        mock.push_response(reverted());
+        mock.push::<Bytes, Bytes>(encoded(Token::String("USD Coin".to_string()))).unwrap();
+        let domain = permit_domain(&provider, Address::repeat_byte(0xa0)).await.unwrap();
+        assert_eq!(domain, usdc_domain("1"));
+
+        mock.push(U256::one()).unwrap();
+        mock.push::<Bytes, Bytes>(encoded(Token::FixedBytes(usdc_domain("2").separator().to_vec())))
+            .unwrap();
+        mock.push_response(reverted());
+        mock.push::<Bytes, Bytes>(encoded(Token::String("USD Coin".to_string()))).unwrap();
+        let err = permit_domain(&provider, Address::repeat_byte(0xa0)).await.unwrap_err();
+        assert!(matches!(err, PermitError::DomainMismatch { .. }));
+    }
+}
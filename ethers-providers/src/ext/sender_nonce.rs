@@ -0,0 +1,124 @@
+//! Lookup of transactions by their sender and nonce
+use crate::{Middleware, MiddlewareError};
+use ethers_core::types::{Address, BlockId, Transaction, TxHash, U256};
+
+/// Returns the mined transaction of `sender` with `nonce`, or `None` if it was not mined yet.
+///
+/// Nodes that index transactions by sender are asked first, using
+/// `eth_getTransactionBySenderAndNonce` (reth) or `ots_getTransactionBySenderAndNonce` (Erigon and
+/// Anvil with Otterscan support). Otherwise the block that includes the transaction is found by
+/// binary searching the blocks for the first one after which the sender's nonce exceeds `nonce`,
+/// which requires an archive node and takes about `log2(block number)` requests.
+///
+/// Accounts whose nonce is incremented without a transaction, like contracts deploying other
+/// contracts, have no transaction for some nonces, which is reported as `None` as well.
+pub async fn get_transaction_by_sender_and_nonce<M: Middleware>(
+    client: &M,
+    sender: Address,
+    nonce: U256,
+) -> Result<Option<Transaction>, M::Error> {
+    let provider = client.provider();
+    let params = (sender, nonce);
+    match provider.request("eth_getTransactionBySenderAndNonce", params).await {
+        Ok(tx) => return Ok(tx),
+        Err(err) if err.as_error_response().is_none() => {
+            return Err(M::Error::from_provider_err(err))
+        }
+        // the method is not supported
+        Err(_) => {}
+    }
+    match provider.request::<_, Option<TxHash>>("ots_getTransactionBySenderAndNonce", params).await
+    {
+        Ok(Some(hash)) => return client.get_transaction(hash).await,
+        Ok(None) => return Ok(None),
+        Err(err) if err.as_error_response().is_none() => {
+            return Err(M::Error::from_provider_err(err))
+        }
+        Err(_) => {}
+    }
+
+    let latest = client.get_block_number().await?.as_u64();
+    let nonce_at = |block: u64| client.get_transaction_count(sender, Some(BlockId::from(block)));
+    if nonce_at(latest).await? <= nonce {
+        return Ok(None)
+    }
+
+    // the first block after which the sender's nonce exceeds `nonce` includes the transaction
+    let (mut low, mut high) = (0, latest);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if nonce_at(mid).await? > nonce {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    let Some(block) = client.get_block_with_txs(low).await? else { return Ok(None) };
+    Ok(block.transactions.into_iter().find(|tx| tx.from == sender && tx.nonce == nonce))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonRpcError, MockResponse, Provider};
+    use ethers_core::types::{Block, U64};
+
+    fn method_not_found() -> MockResponse {
+        MockResponse::Error(JsonRpcError {
+            code: -32601,
+            message: "the method does not exist".to_string(),
+            data: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn binary_searches_blocks() {
+        let (provider, mock) = Provider::mocked();
+        let sender = Address::repeat_byte(1);
+        let tx = Transaction { from: sender, nonce: 2.into(), ..Default::default() };
+        let other =
+            Transaction { from: Address::repeat_byte(2), nonce: 2.into(), ..Default::default() };
+
+        // responses are returned last in, first out
+        let block = Block { transactions: vec![other, tx.clone()], ..Default::default() };
+        mock.push(block).unwrap();
+        for count in [2u64, 3, 1, 3] {
+            mock.push(U256::from(count)).unwrap();
+        }
+        mock.push(U64::from(7)).unwrap();
+        mock.push_response(method_not_found());
+        mock.push_response(method_not_found());
+
+        let found = get_transaction_by_sender_and_nonce(&provider, sender, 2.into()).await.unwrap();
+        assert_eq!(found, Some(tx));
+    }
+
+    #[tokio::test]
+    async fn prefers_indexed_lookup() {
+        let (provider, mock) = Provider::mocked();
+        let tx = Transaction { nonce: 5.into(), ..Default::default() };
+        mock.push(tx.clone()).unwrap();
+        mock.push(tx.hash).unwrap();
+        mock.push_response(method_not_found());
+
+        let found = get_transaction_by_sender_and_nonce(&provider, Address::zero(), 5.into())
+            .await
+            .unwrap();
+        assert_eq!(found, Some(tx));
+    }
+
+    #[tokio::test]
+    async fn pending_transactions_are_not_found() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(3)).unwrap();
+        mock.push(U64::from(7)).unwrap();
+        mock.push_response(method_not_found());
+        mock.push_response(method_not_found());
+
+        let found = get_transaction_by_sender_and_nonce(&provider, Address::zero(), 3.into())
+            .await
+            .unwrap();
+        assert_eq!(found, None);
+    }
+}
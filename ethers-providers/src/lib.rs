@@ -15,7 +15,13 @@ pub use toolbox::*;
 
 /// Crate utilities and type aliases
 mod utils;
-pub use utils::{interval, maybe, EscalationPolicy};
+pub use utils::{maybe, EscalationPolicy};
+
+pub mod time;
+pub use time::interval;
+
+#[cfg(all(feature = "bench", not(target_arch = "wasm32")))]
+pub mod bench;
 
 /// Errors
 mod errors;
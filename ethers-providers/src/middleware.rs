@@ -452,6 +452,40 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().send_raw_transaction(tx).await.map_err(MiddlewareError::from_err)
     }
 
+    /// Signs the transaction and sends it with `eth_sendPrivateTransaction`, which keeps it out of
+    /// the public mempool. This requires a `SignerMiddleware` in the stack and an endpoint that
+    /// supports private transactions, e.g. Flashbots Protect.
+    async fn send_private_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        options: PrivateTransactionOptions,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        self.inner()
+            .send_private_transaction(tx, options, block)
+            .await
+            .map_err(MiddlewareError::from_err)
+    }
+
+    /// Sends the raw RLP encoded transaction with `eth_sendPrivateTransaction`, which keeps it out
+    /// of the public mempool.
+    async fn send_private_raw_transaction<'a>(
+        &'a self,
+        tx: Bytes,
+        options: PrivateTransactionOptions,
+    ) -> Result<PendingTransaction<'a, Self::Provider>, Self::Error> {
+        self.inner()
+            .send_private_raw_transaction(tx, options)
+            .await
+            .map_err(MiddlewareError::from_err)
+    }
+
+    /// Stops the endpoint from trying to include the private transaction with `tx_hash`, using
+    /// `eth_cancelPrivateTransaction`. Returns whether the transaction was cancelled.
+    async fn cancel_private_transaction(&self, tx_hash: TxHash) -> Result<bool, Self::Error> {
+        self.inner().cancel_private_transaction(tx_hash).await.map_err(MiddlewareError::from_err)
+    }
+
     /// This returns true if either the middleware stack contains a `SignerMiddleware`, or the
     /// JSON-RPC provider has an unlocked key that can sign using the `eth_sign` call. If none of
     /// the above conditions are met, then the middleware stack is not capable of signing data.
@@ -602,6 +636,26 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_proof(from, locations, block).await.map_err(MiddlewareError::from_err)
     }
 
+    /// Returns `true` if `signature` is a valid signature of `hash` by `signer`.
+    ///
+    /// Supports ECDSA signatures of externally owned accounts, EIP-1271 signatures of smart
+    /// contract accounts and EIP-6492 signatures of smart contract accounts that are not deployed
+    /// yet. The latter are validated with a single `eth_call` that deploys the account and calls
+    /// `isValidSignature`, see
+    /// [`erc6492_validation_call`](ethers_core::utils::erc6492_validation_call).
+    async fn verify_erc6492_signature(
+        &self,
+        signer: Address,
+        hash: H256,
+        signature: Bytes,
+        block: Option<BlockId>,
+    ) -> Result<bool, Self::Error> {
+        self.inner()
+            .verify_erc6492_signature(signer, hash, signature, block)
+            .await
+            .map_err(MiddlewareError::from_err)
+    }
+
     /// Returns an indication if this node is currently mining.
     async fn mining(&self) -> Result<bool, Self::Error> {
         self.inner().mining().await.map_err(MiddlewareError::from_err)
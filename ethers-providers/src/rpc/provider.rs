@@ -7,8 +7,9 @@ use crate::{
     rpc::pubsub::{PubsubClient, SubscriptionStream},
     stream::{FilterWatcher, DEFAULT_LOCAL_POLL_INTERVAL, DEFAULT_POLL_INTERVAL},
     utils::maybe,
-    Http as HttpProvider, JsonRpcClient, JsonRpcClientWrapper, LogQuery, MiddlewareError,
-    MockProvider, NodeInfo, PeerInfo, PendingTransaction, QuorumProvider, RwClient,
+    GasEstimation, Http as HttpProvider, JsonRpcClient, JsonRpcClientWrapper, LogQuery,
+    MiddlewareError, MockProvider, NodeInfo, PeerInfo, PendingTransaction, QuorumProvider,
+    RpcError, RwClient,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -18,20 +19,31 @@ use std::net::Ipv4Addr;
 #[cfg(feature = "celo")]
 pub use crate::CeloMiddleware;
 pub use crate::Middleware;
+#[cfg(feature = "celo")]
+use ethers_core::types::transaction::celo::{Cip42TransactionRequest, Cip64TransactionRequest};
+#[cfg(feature = "zksync")]
+use ethers_core::types::transaction::zksync::ZkSyncTransactionRequest;
 
 use async_trait::async_trait;
 
 use ethers_core::{
-    abi::{self, Detokenize, ParamType},
+    abi::{self, Detokenize, ParamType, Token},
     types::{
-        transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
-        Address, Block, BlockId, BlockNumber, BlockTrace, Bytes, Chain, EIP1186ProofResponse,
-        FeeHistory, Filter, FilterBlockOption, GethDebugTracingCallOptions,
-        GethDebugTracingOptions, GethTrace, Log, NameOrAddress, Selector, Signature, Trace,
+        transaction::{
+            eip2718::TypedTransaction, eip2930::AccessListWithGasUsed,
+            eip4844::Eip4844TransactionRequest,
+        },
+        Address, Block, BlockId, BlockNumber, BlockTrace, Bytes, CancelPrivateTransactionRequest,
+        Chain, EIP1186ProofResponse, FeeHistory, Filter, FilterBlockOption,
+        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, Log, NameOrAddress,
+        PrivateTransactionOptions, PrivateTransactionRequest, Selector, Signature, Trace,
         TraceFilter, TraceType, Transaction, TransactionReceipt, TransactionRequest, TxHash,
         TxpoolContent, TxpoolInspect, TxpoolStatus, H256, U256, U64,
     },
-    utils,
+    utils::{
+        self, decode_erc6492_validation, erc6492_validation_call, is_valid_ecdsa_signature,
+        Erc6492Signature, ERC1271_MAGIC_VALUE, ERC6492_VALIDATION_MULTICALL,
+    },
 };
 use futures_util::{lock::Mutex, try_join};
 use hex::FromHex;
@@ -100,6 +112,7 @@ pub struct Provider<P> {
     ens: Option<Address>,
     interval: Option<Duration>,
     from: Option<Address>,
+    gas_estimation: GasEstimation,
     /// Node client hasn't been checked yet = `None`
     /// Unsupported node client = `Some(None)`
     /// Supported node client = `Some(Some(NodeClient))`
@@ -134,6 +147,7 @@ impl<P: JsonRpcClient> Provider<P> {
             ens: None,
             interval: None,
             from: None,
+            gas_estimation: GasEstimation::default(),
             _node_client: Arc::new(Mutex::new(None)),
         }
     }
@@ -294,7 +308,8 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
                 let gas_price = maybe(tx.gas_price(), self.get_gas_price()).await?;
                 tx.set_gas_price(gas_price);
             }
-            TypedTransaction::Eip1559(ref mut inner) => {
+            TypedTransaction::Eip1559(ref mut inner) |
+            TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) => {
                 if inner.max_fee_per_gas.is_none() || inner.max_priority_fee_per_gas.is_none() {
                     let (max_fee_per_gas, max_priority_fee_per_gas) =
                         self.estimate_eip1559_fees(None).await?;
@@ -318,13 +333,65 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
                 let gas_price = maybe(tx.gas_price(), self.get_gas_price()).await?;
                 tx.set_gas_price(gas_price);
             }
+            #[cfg(feature = "celo")]
+            TypedTransaction::Cip42(Cip42TransactionRequest { tx: ref mut inner, .. }) |
+            TypedTransaction::Cip64(Cip64TransactionRequest { tx: ref mut inner, .. }) => {
+                // same as for EIP-1559 transactions, the fee currency only changes the unit
+                if inner.max_fee_per_gas.is_none() || inner.max_priority_fee_per_gas.is_none() {
+                    let (max_fee_per_gas, max_priority_fee_per_gas) =
+                        self.estimate_eip1559_fees(None).await?;
+                    let mfpg = inner.max_fee_per_gas.get_or_insert(max_fee_per_gas);
+                    inner.max_priority_fee_per_gas = inner
+                        .max_priority_fee_per_gas
+                        .map(|tip| std::cmp::min(tip, *mfpg))
+                        .or(Some(max_priority_fee_per_gas));
+                };
+            }
+            #[cfg(feature = "zksync")]
+            TypedTransaction::ZkSync(ZkSyncTransactionRequest { tx: ref mut inner, .. }) => {
+                if inner.max_fee_per_gas.is_none() || inner.max_priority_fee_per_gas.is_none() {
+                    let (max_fee_per_gas, max_priority_fee_per_gas) =
+                        self.estimate_eip1559_fees(None).await?;
+                    let mfpg = inner.max_fee_per_gas.get_or_insert(max_fee_per_gas);
+                    inner.max_priority_fee_per_gas = inner
+                        .max_priority_fee_per_gas
+                        .map(|tip| std::cmp::min(tip, *mfpg))
+                        .or(Some(max_priority_fee_per_gas));
+                };
+            }
+        }
+
+        // fill the blob gas price with twice the blob base fee of the latest block, which leaves
+        // room for the blob base fee to rise until the transaction is included
+        #[cfg(not(feature = "celo"))]
+        if let TypedTransaction::Eip4844(ref mut inner) = tx {
+            if inner.max_fee_per_blob_gas.is_none() {
+                let blob_base_fee = self
+                    .get_block(BlockNumber::Latest)
+                    .await?
+                    .ok_or_else(|| ProviderError::CustomError("Latest block not found".into()))?
+                    .blob_base_fee()
+                    .ok_or_else(|| ProviderError::CustomError("EIP-4844 not activated".into()))?;
+                inner.max_fee_per_blob_gas = Some(blob_base_fee * 2);
+            }
         }
 
         // Set gas to estimated value only if it was not set by the caller,
         // even if the access list has been populated and saves gas
         if tx.gas().is_none() {
+            let estimation = self.gas_estimation;
+            if estimation.simulate {
+                self.call(tx, block).await?;
+            }
             let gas_estimate = self.estimate_gas(tx, block).await?;
-            tx.set_gas(gas_estimate);
+            let block_gas_limit = if estimation.clamp_to_block_gas_limit {
+                self.get_block(block.unwrap_or_else(|| BlockNumber::Latest.into()))
+                    .await?
+                    .map(|block| block.gas_limit)
+            } else {
+                None
+            };
+            tx.set_gas(estimation.apply(gas_estimate, block_gas_limit));
         }
 
         Ok(())
@@ -569,6 +636,31 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         Ok(PendingTransaction::new(tx_hash, self))
     }
 
+    async fn send_private_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        _tx: T,
+        _options: PrivateTransactionOptions,
+        _block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, P>, ProviderError> {
+        // private transactions must be signed locally
+        Err(ProviderError::SignerUnavailable)
+    }
+
+    async fn send_private_raw_transaction<'a>(
+        &'a self,
+        tx: Bytes,
+        options: PrivateTransactionOptions,
+    ) -> Result<PendingTransaction<'a, P>, ProviderError> {
+        let request = PrivateTransactionRequest { tx, options };
+        let tx_hash = self.request("eth_sendPrivateTransaction", [request]).await?;
+        Ok(PendingTransaction::new(tx_hash, self))
+    }
+
+    async fn cancel_private_transaction(&self, tx_hash: TxHash) -> Result<bool, ProviderError> {
+        self.request("eth_cancelPrivateTransaction", [CancelPrivateTransactionRequest { tx_hash }])
+            .await
+    }
+
     async fn is_signer(&self) -> bool {
         match self.from {
             Some(sender) => self.sign(vec![], &sender).await.is_ok(),
@@ -722,6 +814,41 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("eth_getProof", [from, locations, block]).await
     }
 
+    async fn verify_erc6492_signature(
+        &self,
+        signer: Address,
+        hash: H256,
+        signature: Bytes,
+        block: Option<BlockId>,
+    ) -> Result<bool, ProviderError> {
+        if let Some(wrapped) = Erc6492Signature::decode(&signature) {
+            let tx: TypedTransaction = TransactionRequest::new()
+                .to(ERC6492_VALIDATION_MULTICALL)
+                .data(erc6492_validation_call(signer, hash, &wrapped))
+                .into();
+            let output = self.call(&tx, block).await?;
+            return decode_erc6492_validation(&output)
+                .map_err(|err| ProviderError::CustomError(err.to_string()))
+        }
+
+        if self.get_code(signer, block).await?.is_empty() {
+            return Ok(is_valid_ecdsa_signature(signer, hash, &signature))
+        }
+
+        let mut data = ERC1271_MAGIC_VALUE.to_vec();
+        data.extend(abi::encode(&[
+            Token::FixedBytes(hash.as_bytes().to_vec()),
+            Token::Bytes(signature.to_vec()),
+        ]));
+        let tx: TypedTransaction = TransactionRequest::new().to(signer).data(data).into();
+        match self.call(&tx, block).await {
+            Ok(output) => Ok(output.starts_with(&ERC1271_MAGIC_VALUE)),
+            // a reverting `isValidSignature` rejects the signature
+            Err(err) if RpcError::as_error_response(&err).is_some() => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Returns an indication if this node is currently mining.
     async fn mining(&self) -> Result<bool, Self::Error> {
         self.request("eth_mining", ()).await
@@ -1237,6 +1364,26 @@ impl<P: JsonRpcClient> Provider<P> {
         Ok(())
     }
 
+    /// Sets the strategy with which `fill_transaction` estimates the gas limit of transactions
+    /// (default: the node's estimate)
+    pub fn set_gas_estimation(&mut self, estimation: GasEstimation) -> &mut Self {
+        self.gas_estimation = estimation;
+        self
+    }
+
+    /// Sets the strategy with which `fill_transaction` estimates the gas limit of transactions
+    /// (default: the node's estimate)
+    #[must_use]
+    pub fn gas_estimation(mut self, estimation: GasEstimation) -> Self {
+        self.set_gas_estimation(estimation);
+        self
+    }
+
+    /// Gets the strategy with which `fill_transaction` estimates the gas limit of transactions
+    pub fn get_gas_estimation(&self) -> GasEstimation {
+        self.gas_estimation
+    }
+
     /// Sets the ENS Address (default: mainnet)
     #[must_use]
     pub fn ens<T: Into<Address>>(mut self, ens: T) -> Self {
@@ -1390,6 +1537,47 @@ impl Provider<RetryClient<HttpProvider>> {
     }
 }
 
+/// The environment variable holding the RPC URL read by [`Provider::from_env`]
+pub const ETH_RPC_URL: &str = "ETH_RPC_URL";
+
+/// Error thrown when assembling a provider from environment variables
+#[derive(Debug, thiserror::Error)]
+pub enum EnvError {
+    /// Thrown when a required environment variable is not set
+    #[error("environment variable {0} is not set")]
+    NotSet(&'static str),
+    /// Thrown when an environment variable can not be parsed
+    #[error("invalid environment variable {name}: {reason}")]
+    Invalid {
+        /// The name of the variable
+        name: &'static str,
+        /// Why the value is invalid
+        reason: String,
+    },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Provider<HttpProvider> {
+    /// Connects to the HTTP endpoint in the `ETH_RPC_URL` environment variable, configuring the
+    /// polling interval like [`ProviderExt::try_connect`].
+    ///
+    /// ```no_run
+    /// use ethers_providers::{Http, Provider};
+    ///
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// // ETH_RPC_URL=https://eth.llamarpc.com
+    /// let provider = Provider::<Http>::from_env().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_env() -> Result<Self, EnvError> {
+        let url = std::env::var(ETH_RPC_URL).map_err(|_| EnvError::NotSet(ETH_RPC_URL))?;
+        Self::try_connect(&url)
+            .await
+            .map_err(|err| EnvError::Invalid { name: ETH_RPC_URL, reason: err.to_string() })
+    }
+}
+
 mod sealed {
     use crate::{Http, Provider};
     /// private trait to ensure extension trait is not implement outside of this crate
@@ -1526,7 +1714,7 @@ pub fn is_local_endpoint(endpoint: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Http;
+    use crate::{GasBuffer, Http};
     use ethers_core::{
         types::{
             transaction::eip2930::AccessList, Eip1559TransactionRequest,
@@ -1878,6 +2066,32 @@ mod tests {
         assert!(matches!(res, Err(ProviderError::JsonRpcClientError(_))));
     }
 
+    #[tokio::test]
+    async fn test_fill_transaction_gas_estimation() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider
+            .gas_estimation(GasEstimation::new().simulate(true).buffer(GasBuffer::Percent(20)));
+
+        let tx = || -> TypedTransaction {
+            Eip1559TransactionRequest::new().max_fee_per_gas(25).max_priority_fee_per_gas(25).into()
+        };
+
+        // --- simulates the transaction and buffers the estimate
+        let mut tx1 = tx();
+        mock.push(U256::from(100_000)).unwrap();
+        mock.push::<Bytes, Bytes>(Bytes::default()).unwrap();
+        provider.fill_transaction(&mut tx1, None).await.unwrap();
+        assert_eq!(tx1.gas(), Some(&U256::from(120_000)));
+
+        // --- propagates the simulation's error without estimating
+        let mut tx2 = tx();
+        mock.push(U256::from(100_000)).unwrap();
+        mock.push(b'b').unwrap();
+        let res = provider.fill_transaction(&mut tx2, None).await;
+        assert!(matches!(res, Err(ProviderError::JsonRpcClientError(_))));
+        assert!(tx2.gas().is_none());
+    }
+
     #[tokio::test]
     async fn test_fill_transaction_legacy() {
         let (mut provider, mock) = Provider::mocked();
@@ -1912,6 +2126,48 @@ mod tests {
         assert!(tx.access_list().is_none());
     }
 
+    #[tokio::test]
+    async fn verifies_erc6492_signature() {
+        let (provider, mock) = Provider::mocked();
+        let signer: Address = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".parse().unwrap();
+        let hash = utils::hash_message("Some data");
+        let signature: Bytes = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c".parse().unwrap();
+
+        // an externally owned account
+        mock.push::<Bytes, Bytes>(Bytes::new()).unwrap();
+        assert!(provider
+            .verify_erc6492_signature(signer, hash, signature.clone(), None)
+            .await
+            .unwrap());
+        mock.push::<Bytes, Bytes>(Bytes::new()).unwrap();
+        assert!(!provider
+            .verify_erc6492_signature(Address::zero(), hash, signature.clone(), None)
+            .await
+            .unwrap());
+
+        // a deployed smart contract account
+        let mut magic = ERC1271_MAGIC_VALUE.to_vec();
+        magic.resize(32, 0);
+        mock.push::<Bytes, Bytes>(magic.clone().into()).unwrap();
+        mock.push::<Bytes, Bytes>(vec![0x60, 0x80].into()).unwrap();
+        assert!(provider
+            .verify_erc6492_signature(signer, hash, signature.clone(), None)
+            .await
+            .unwrap());
+
+        // a counterfactual smart contract account, validated without `eth_getCode`
+        let wrapped = Erc6492Signature::new(Address::repeat_byte(0x11), vec![1, 2, 3], signature);
+        let output = abi::encode(&[Token::Array(vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![])]),
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(magic)]),
+        ])]);
+        mock.push::<Bytes, Bytes>(output.into()).unwrap();
+        assert!(provider
+            .verify_erc6492_signature(signer, hash, wrapped.encode(), None)
+            .await
+            .unwrap());
+    }
+
     #[tokio::test]
     async fn mainnet_lookup_address_invalid_resolver() {
         let provider = crate::MAINNET.provider();
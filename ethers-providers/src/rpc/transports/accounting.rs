@@ -0,0 +1,245 @@
+//! A [JsonRpcClient] wrapper that counts requests and estimates their cost per method
+
+use crate::JsonRpcClient;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+/// The estimated cost of each RPC method, e.g. in compute units or credits.
+///
+/// The presets approximate the published pricing of the providers and may be outdated, so
+/// individual costs can be overridden with [`CostTable::with_cost`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CostTable {
+    costs: HashMap<String, u64>,
+    default_cost: u64,
+}
+
+impl CostTable {
+    /// Creates a table where every method has the cost `default_cost`.
+    pub fn new(default_cost: u64) -> Self {
+        Self { costs: HashMap::new(), default_cost }
+    }
+
+    /// Sets the cost of `method`.
+    #[must_use]
+    pub fn with_cost(mut self, method: impl Into<String>, cost: u64) -> Self {
+        self.costs.insert(method.into(), cost);
+        self
+    }
+
+    /// Returns the cost of `method`.
+    pub fn cost(&self, method: &str) -> u64 {
+        self.costs.get(method).copied().unwrap_or(self.default_cost)
+    }
+
+    /// Alchemy compute units.
+    pub fn alchemy() -> Self {
+        Self::from_costs(
+            20,
+            &[
+                ("net_version", 0),
+                ("eth_chainId", 0),
+                ("eth_blockNumber", 10),
+                ("eth_feeHistory", 10),
+                ("eth_maxPriorityFeePerGas", 10),
+                ("eth_subscribe", 10),
+                ("eth_getTransactionReceipt", 15),
+                ("eth_getBlockByNumber", 16),
+                ("eth_getStorageAt", 17),
+                ("eth_getTransactionByHash", 17),
+                ("eth_gasPrice", 19),
+                ("eth_getBalance", 19),
+                ("eth_getCode", 19),
+                ("eth_getBlockByHash", 21),
+                ("eth_getProof", 21),
+                ("eth_call", 26),
+                ("eth_getTransactionCount", 26),
+                ("eth_getLogs", 75),
+                ("eth_estimateGas", 87),
+                ("eth_sendRawTransaction", 250),
+                ("debug_traceCall", 309),
+                ("debug_traceTransaction", 309),
+                ("trace_call", 75),
+                ("trace_transaction", 26),
+            ],
+        )
+    }
+
+    /// Infura credits.
+    pub fn infura() -> Self {
+        Self::from_costs(
+            80,
+            &[
+                ("net_version", 5),
+                ("eth_chainId", 5),
+                ("eth_getLogs", 255),
+                ("eth_estimateGas", 300),
+                ("eth_sendRawTransaction", 720),
+                ("debug_traceCall", 1000),
+                ("debug_traceTransaction", 1000),
+                ("trace_call", 300),
+                ("trace_transaction", 300),
+            ],
+        )
+    }
+
+    fn from_costs(default_cost: u64, costs: &[(&str, u64)]) -> Self {
+        Self {
+            costs: costs.iter().map(|(method, cost)| (method.to_string(), *cost)).collect(),
+            default_cost,
+        }
+    }
+}
+
+/// The number of requests of a method and their estimated cost.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MethodCost {
+    /// The number of requests
+    pub requests: u64,
+    /// The estimated total cost of the requests
+    pub cost: u64,
+}
+
+/// A client that counts the requests of the wrapped client and estimates their cost per method
+/// with a [`CostTable`].
+///
+/// Failed requests are accounted for as well, since providers bill them too. Clones share the
+/// running totals.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_providers::{CostAccountingClient, CostTable, Http, Middleware, Provider};
+/// use std::str::FromStr;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let http = Http::from_str("http://localhost:8545")?;
+/// let provider = Provider::new(CostAccountingClient::new(http, CostTable::alchemy()));
+///
+/// provider.get_block_number().await?;
+/// provider.get_chainid().await?;
+///
+/// let client = provider.as_ref();
+/// println!("{} requests, {} CU", client.total_requests(), client.total_cost());
+/// for (method, cost) in client.costs() {
+///     println!("{method}: {} requests, {} CU", cost.requests, cost.cost);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CostAccountingClient<C> {
+    inner: C,
+    table: Arc<CostTable>,
+    costs: Arc<Mutex<BTreeMap<String, MethodCost>>>,
+}
+
+impl<C> CostAccountingClient<C> {
+    /// Wraps `inner`, estimating costs with `table`.
+    pub fn new(inner: C, table: CostTable) -> Self {
+        Self { inner, table: Arc::new(table), costs: Default::default() }
+    }
+
+    /// Returns the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the cost table.
+    pub fn table(&self) -> &CostTable {
+        &self.table
+    }
+
+    /// Returns the number of requests and their estimated cost per method.
+    pub fn costs(&self) -> BTreeMap<String, MethodCost> {
+        self.costs.lock().unwrap().clone()
+    }
+
+    /// Returns the total number of requests.
+    pub fn total_requests(&self) -> u64 {
+        self.costs.lock().unwrap().values().map(|cost| cost.requests).sum()
+    }
+
+    /// Returns the estimated total cost of all requests.
+    pub fn total_cost(&self) -> u64 {
+        self.costs.lock().unwrap().values().map(|cost| cost.cost).sum()
+    }
+
+    /// Resets the running totals.
+    pub fn reset(&self) {
+        self.costs.lock().unwrap().clear();
+    }
+
+    fn record(&self, method: &str) {
+        let cost = self.table.cost(method);
+        let mut costs = self.costs.lock().unwrap();
+        let entry = costs.entry(method.to_string()).or_default();
+        entry.requests += 1;
+        entry.cost += cost;
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for CostAccountingClient<C>
+where
+    C: JsonRpcClient,
+{
+    type Error = C::Error;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: std::fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.record(method);
+        self.inner.request(method, params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Middleware, MockProvider, Provider};
+    use ethers_core::types::U64;
+
+    #[tokio::test]
+    async fn accounts_costs_per_method() {
+        let mock = MockProvider::new();
+        let client = CostAccountingClient::new(
+            mock.clone(),
+            CostTable::new(1).with_cost("eth_blockNumber", 10),
+        );
+        let provider = Provider::new(client.clone());
+
+        mock.push(U64::from(1)).unwrap();
+        mock.push(U64::from(2)).unwrap();
+        mock.push(U64::from(3)).unwrap();
+        provider.get_block_number().await.unwrap();
+        provider.get_block_number().await.unwrap();
+        provider.get_chainid().await.unwrap();
+
+        let costs = client.costs();
+        assert_eq!(costs["eth_blockNumber"], MethodCost { requests: 2, cost: 20 });
+        assert_eq!(costs["eth_chainId"], MethodCost { requests: 1, cost: 1 });
+        assert_eq!(client.total_requests(), 3);
+        assert_eq!(provider.as_ref().total_cost(), 21);
+
+        // failed requests are billed too
+        provider.get_block_number().await.unwrap_err();
+        assert_eq!(client.total_cost(), 31);
+
+        client.reset();
+        assert_eq!(client.total_requests(), 0);
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(CostTable::alchemy().cost("eth_call"), 26);
+        assert_eq!(CostTable::infura().cost("eth_call"), 80);
+    }
+}
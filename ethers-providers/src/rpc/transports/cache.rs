@@ -0,0 +1,358 @@
+//! A [JsonRpcClient] wrapper that caches the responses of idempotent reads
+
+use crate::{errors::ProviderError, JsonRpcClient};
+use async_trait::async_trait;
+use instant::{Duration, Instant};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+use tracing::{trace, warn};
+
+/// How long the responses of a method are cached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CachePolicy {
+    ttl: Duration,
+    stale_while_revalidate: Duration,
+}
+
+impl CachePolicy {
+    /// Caches responses for `ttl`, after which they are fetched again before being returned.
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, stale_while_revalidate: Duration::ZERO }
+    }
+
+    /// Keeps serving expired responses for up to `window` after their `ttl`, while a fresh
+    /// response is fetched in the background.
+    ///
+    /// This trades freshness for latency: the caller never waits for a response that was cached
+    /// within `ttl + window`, at the cost of receiving a value that is up to `window` older than
+    /// the policy's `ttl`. Outside of wasm, the refresh is spawned on the current tokio runtime and
+    /// skipped when there is none.
+    #[must_use]
+    pub fn stale_while_revalidate(mut self, window: Duration) -> Self {
+        self.stale_while_revalidate = window;
+        self
+    }
+
+    /// Returns how long responses are considered fresh.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns how long expired responses are served while they are refreshed.
+    pub fn stale_window(&self) -> Duration {
+        self.stale_while_revalidate
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    fetched_at: Instant,
+    revalidating: bool,
+}
+
+type Entries = Arc<Mutex<HashMap<(String, String), Entry>>>;
+
+/// A client that caches the responses of the wrapped client for the methods it has a
+/// [`CachePolicy`] for. Requests of all other methods are passed through.
+///
+/// Responses are cached per method and parameters. Only register methods whose responses do not
+/// depend on the time of the request beyond the policy's `ttl`, e.g. `eth_chainId`,
+/// `eth_blockNumber` or `eth_gasPrice`. Failed requests are never cached, and a failed background
+/// refresh leaves the stale response in place so the next read retries it.
+///
+/// Clones share the cache.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_providers::{CachePolicy, CachingClient, Http, Middleware, Provider};
+/// use std::{str::FromStr, time::Duration};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let http = Http::from_str("http://localhost:8545")?;
+/// let client = CachingClient::new(http)
+///     .with_policy("eth_chainId", CachePolicy::new(Duration::from_secs(3600)))
+///     .with_policy(
+///         "eth_gasPrice",
+///         CachePolicy::new(Duration::from_secs(12))
+///             .stale_while_revalidate(Duration::from_secs(60)),
+///     );
+/// let provider = Provider::new(client);
+///
+/// let gas_price = provider.get_gas_price().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachingClient<C> {
+    inner: Arc<C>,
+    policies: HashMap<String, CachePolicy>,
+    entries: Entries,
+}
+
+impl<C> Clone for CachingClient<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            policies: self.policies.clone(),
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<C> CachingClient<C> {
+    /// Wraps `inner` without caching any method.
+    pub fn new(inner: C) -> Self {
+        Self { inner: Arc::new(inner), policies: HashMap::new(), entries: Default::default() }
+    }
+
+    /// Caches the responses of `method` according to `policy`.
+    #[must_use]
+    pub fn with_policy(mut self, method: impl Into<String>, policy: CachePolicy) -> Self {
+        self.policies.insert(method.into(), policy);
+        self
+    }
+
+    /// Returns the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the cache policy of `method`, if its responses are cached.
+    pub fn policy(&self, method: &str) -> Option<CachePolicy> {
+        self.policies.get(method).copied()
+    }
+
+    /// Removes all cached responses of `method`.
+    pub fn invalidate(&self, method: &str) {
+        self.entries.lock().unwrap().retain(|(cached, _), _| cached != method);
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl<C> CachingClient<C>
+where
+    C: JsonRpcClient + 'static,
+{
+    /// Refreshes the cached response of `key` in the background.
+    ///
+    /// Outside of a tokio runtime the stale response is served without being refreshed, until it
+    /// expires and is fetched again.
+    fn revalidate(&self, key: (String, String), params: Value) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            trace!(method = %key.0, "no tokio runtime, skipping revalidation");
+            if let Some(entry) = self.entries.lock().unwrap().get_mut(&key) {
+                entry.revalidating = false;
+            }
+            return
+        };
+
+        let inner = self.inner.clone();
+        let entries = self.entries.clone();
+        let fut = async move {
+            if let Err(err) = fetch(&*inner, &entries, key.clone(), params).await {
+                warn!(method = %key.0, %err, "failed to revalidate cached response");
+                if let Some(entry) = entries.lock().unwrap().get_mut(&key) {
+                    entry.revalidating = false;
+                }
+            }
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(fut);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        handle.spawn(fut);
+    }
+}
+
+async fn fetch<C: JsonRpcClient>(
+    inner: &C,
+    entries: &Entries,
+    key: (String, String),
+    params: Value,
+) -> Result<Value, CachingClientError> {
+    let value: Value = inner
+        .request(&key.0, params)
+        .await
+        .map_err(|err| CachingClientError::ProviderError(err.into()))?;
+    let entry = Entry { value: value.clone(), fetched_at: Instant::now(), revalidating: false };
+    entries.lock().unwrap().insert(key, entry);
+    Ok(value)
+}
+
+/// Error thrown by the [`CachingClient`]
+#[derive(Error, Debug)]
+pub enum CachingClientError {
+    /// Thrown if the wrapped client failed
+    #[error(transparent)]
+    ProviderError(ProviderError),
+    /// Thrown if the parameters or a cached response could not be (de)serialized
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+impl crate::RpcError for CachingClientError {
+    fn as_error_response(&self) -> Option<&super::JsonRpcError> {
+        match self {
+            CachingClientError::ProviderError(err) => err.as_error_response(),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            CachingClientError::ProviderError(err) => err.as_serde_error(),
+            CachingClientError::SerdeJson(err) => Some(err),
+        }
+    }
+}
+
+impl From<CachingClientError> for ProviderError {
+    fn from(src: CachingClientError) -> Self {
+        match src {
+            CachingClientError::ProviderError(err) => err,
+            CachingClientError::SerdeJson(err) => err.into(),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for CachingClient<C>
+where
+    C: JsonRpcClient + 'static,
+{
+    type Error = CachingClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let Some(policy) = self.policy(method) else {
+            return self
+                .inner
+                .request(method, params)
+                .await
+                .map_err(|err| CachingClientError::ProviderError(err.into()))
+        };
+
+        let params = serde_json::to_value(params)?;
+        let key = (method.to_string(), params.to_string());
+
+        let (cached, revalidate) = {
+            let mut entries = self.entries.lock().unwrap();
+            match entries.get_mut(&key) {
+                Some(entry) => {
+                    let age = entry.fetched_at.elapsed();
+                    if age < policy.ttl {
+                        (Some(entry.value.clone()), false)
+                    } else if age < policy.ttl.saturating_add(policy.stale_while_revalidate) {
+                        let revalidate = !entry.revalidating;
+                        entry.revalidating = true;
+                        (Some(entry.value.clone()), revalidate)
+                    } else {
+                        (None, false)
+                    }
+                }
+                None => (None, false),
+            }
+        };
+
+        let value = match cached {
+            Some(value) => {
+                trace!(method, revalidate, "serving cached response");
+                if revalidate {
+                    self.revalidate(key, params);
+                }
+                value
+            }
+            None => fetch(&*self.inner, &self.entries, key, params).await?,
+        };
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Middleware, MockProvider, Provider};
+    use ethers_core::types::U256;
+
+    #[tokio::test]
+    async fn caches_configured_methods() {
+        let mock = MockProvider::new();
+        let client = CachingClient::new(mock.clone())
+            .with_policy("eth_gasPrice", CachePolicy::new(Duration::from_secs(3600)));
+        let provider = Provider::new(client.clone());
+
+        mock.push(U256::from(1)).unwrap();
+        assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+        // served from the cache without a response
+        assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+
+        // other methods are passed through
+        provider.get_block_number().await.unwrap_err();
+
+        client.invalidate("eth_gasPrice");
+        mock.push(U256::from(2)).unwrap();
+        assert_eq!(provider.get_gas_price().await.unwrap(), 2.into());
+    }
+
+    #[tokio::test]
+    async fn serves_stale_while_revalidating() {
+        let mock = MockProvider::new();
+        let policy =
+            CachePolicy::new(Duration::ZERO).stale_while_revalidate(Duration::from_secs(3600));
+        let provider =
+            Provider::new(CachingClient::new(mock.clone()).with_policy("eth_gasPrice", policy));
+
+        mock.push(U256::from(1)).unwrap();
+        assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+
+        // the stale response is served while the fresh one is fetched in the background
+        mock.push(U256::from(2)).unwrap();
+        assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        // a failed refresh keeps the stale response
+        assert_eq!(provider.get_gas_price().await.unwrap(), 2.into());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(provider.get_gas_price().await.unwrap(), 2.into());
+    }
+
+    #[test]
+    fn serves_stale_outside_runtime() {
+        let mock = MockProvider::new();
+        let policy =
+            CachePolicy::new(Duration::ZERO).stale_while_revalidate(Duration::from_secs(3600));
+        let client = CachingClient::new(mock.clone()).with_policy("eth_gasPrice", policy);
+        let provider = Provider::new(client.clone());
+
+        futures_executor::block_on(async {
+            mock.push(U256::from(1)).unwrap();
+            assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+
+            // no runtime to revalidate on, the stale response is served and can be revalidated
+            // again later
+            mock.push(U256::from(2)).unwrap();
+            assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+            assert_eq!(provider.get_gas_price().await.unwrap(), 1.into());
+        });
+
+        assert!(client.entries.lock().unwrap().values().all(|entry| !entry.revalidating));
+    }
+}
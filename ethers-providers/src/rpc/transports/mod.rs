@@ -9,8 +9,16 @@ mod ipc;
 #[cfg(all(feature = "ipc", any(unix, windows)))]
 pub use ipc::{Ipc, IpcError};
 
+#[cfg(all(feature = "sse", not(target_arch = "wasm32")))]
+mod sse;
+#[cfg(all(feature = "sse", not(target_arch = "wasm32")))]
+pub use sse::{Sse, SseClientError, SseEvent, SseEventStream, SseNotifications};
+
 mod quorum;
-pub use quorum::{JsonRpcClientWrapper, Quorum, QuorumError, QuorumProvider, WeightedProvider};
+pub use quorum::{
+    ForkDetected, ForkDetection, JsonRpcClientWrapper, Quorum, QuorumError, QuorumProvider,
+    WeightedProvider,
+};
 
 mod rw;
 pub use rw::{RwClient, RwClientError};
@@ -18,6 +26,12 @@ pub use rw::{RwClient, RwClientError};
 mod retry;
 pub use retry::*;
 
+mod cache;
+pub use cache::{CachePolicy, CachingClient, CachingClientError};
+
+mod accounting;
+pub use accounting::{CostAccountingClient, CostTable, MethodCost};
+
 #[cfg(all(feature = "ws", not(feature = "legacy-ws")))]
 mod ws;
 #[cfg(all(feature = "ws", not(feature = "legacy-ws")))]
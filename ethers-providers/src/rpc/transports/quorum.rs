@@ -1,14 +1,15 @@
 use crate::{errors::ProviderError, JsonRpcClient, PubsubClient};
 use async_trait::async_trait;
-use ethers_core::types::{U256, U64};
+use ethers_core::types::{H256, U256, U64};
 use futures_core::Stream;
 use futures_util::{future::join_all, FutureExt, StreamExt};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{value::RawValue, Value};
 use std::{
-    fmt::Debug,
+    fmt::{self, Debug},
     future::Future,
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
 };
 use thiserror::Error;
@@ -78,6 +79,10 @@ pub struct QuorumProvider<T = Box<dyn JsonRpcClientWrapper>> {
     quorum_weight: u64,
     /// All the internal providers this providers runs
     providers: Vec<WeightedProvider<T>>,
+    /// How forks between the providers are handled, if they are detected
+    fork_detection: Option<ForkDetection>,
+    /// The fork detected by the last check, shared between clones
+    fork: Arc<Mutex<Option<ForkDetected>>>,
 }
 
 impl QuorumProvider<Box<dyn JsonRpcClientWrapper>> {
@@ -121,17 +126,24 @@ impl<T> QuorumProvider<T> {
         self.providers.push(provider);
         self.quorum_weight = self.quorum.weight(&self.providers)
     }
+
+    /// Returns the fork found by the last [`QuorumProvider::detect_fork`], if the providers have
+    /// not agreed again since.
+    pub fn fork(&self) -> Option<ForkDetected> {
+        self.fork.lock().unwrap().clone()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct QuorumProviderBuilder<T> {
     quorum: Quorum,
     providers: Vec<WeightedProvider<T>>,
+    fork_detection: Option<ForkDetection>,
 }
 
 impl<T> Default for QuorumProviderBuilder<T> {
     fn default() -> Self {
-        Self { quorum: Default::default(), providers: Vec::new() }
+        Self { quorum: Default::default(), providers: Vec::new(), fork_detection: None }
     }
 }
 
@@ -156,12 +168,84 @@ impl<T> QuorumProviderBuilder<T> {
         self
     }
 
+    /// Set how forks between the providers are handled
+    pub fn fork_detection(mut self, fork_detection: ForkDetection) -> Self {
+        self.fork_detection = Some(fork_detection);
+        self
+    }
+
     pub fn build(self) -> QuorumProvider<T> {
         let quorum_weight = self.quorum.weight(&self.providers);
-        QuorumProvider { quorum: self.quorum, quorum_weight, providers: self.providers }
+        QuorumProvider {
+            quorum: self.quorum,
+            quorum_weight,
+            providers: self.providers,
+            fork_detection: self.fork_detection,
+            fork: Default::default(),
+        }
     }
 }
 
+/// The methods which are refused while the providers are forked, see
+/// [`ForkDetection::pause_writes`]
+const WRITE_METHODS: &[&str] = &["eth_sendRawTransaction", "eth_sendTransaction"];
+
+/// Called with every newly detected fork
+type ForkListener = Arc<dyn Fn(&ForkDetected) + Send + Sync>;
+
+/// Configures how a [`QuorumProvider`] detects and handles forks between its providers.
+///
+/// The providers' blocks are compared `tolerance` blocks below the lowest head of all providers,
+/// so that providers which are just briefly on different branches at the tip of the chain are not
+/// reported.
+#[derive(Clone, Default)]
+pub struct ForkDetection {
+    tolerance: u64,
+    pause_writes: bool,
+    listener: Option<ForkListener>,
+}
+
+impl ForkDetection {
+    /// Compares the providers' blocks `tolerance` blocks below their lowest head
+    pub fn new(tolerance: u64) -> Self {
+        Self { tolerance, ..Default::default() }
+    }
+
+    /// Checks for forks before every transaction is sent, and refuses to send it with
+    /// [`QuorumError::ForkDetected`] while the providers are forked.
+    #[must_use]
+    pub fn pause_writes(mut self, pause_writes: bool) -> Self {
+        self.pause_writes = pause_writes;
+        self
+    }
+
+    /// Calls `listener` every time a new fork is detected
+    #[must_use]
+    pub fn on_fork(mut self, listener: impl Fn(&ForkDetected) + Send + Sync + 'static) -> Self {
+        self.listener = Some(Arc::new(listener));
+        self
+    }
+}
+
+impl Debug for ForkDetection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ForkDetection")
+            .field("tolerance", &self.tolerance)
+            .field("pause_writes", &self.pause_writes)
+            .field("listener", &self.listener.is_some())
+            .finish()
+    }
+}
+
+/// The providers of a [`QuorumProvider`] returned different blocks at the same height
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForkDetected {
+    /// The height at which the blocks were compared
+    pub block_number: U64,
+    /// The index of each provider that returned the block, with the block's hash
+    pub hashes: Vec<(usize, H256)>,
+}
+
 impl<T: JsonRpcClientWrapper> QuorumProvider<T> {
     /// Returns the block height that _all_ providers have surpassed.
     ///
@@ -182,6 +266,52 @@ impl<T: JsonRpcClientWrapper> QuorumProvider<T> {
             .ok_or_else(|| ProviderError::CustomError("No Providers".to_string()))
     }
 
+    /// Compares the blocks of all providers at the height of the [`ForkDetection`] tolerance below
+    /// their lowest head, and returns the fork if they differ.
+    ///
+    /// The listener of the [`ForkDetection`] is called if the fork is a new one. Providers which
+    /// fail to return a block are ignored.
+    pub async fn detect_fork(&self) -> Result<Option<ForkDetected>, ProviderError> {
+        let tolerance = self.fork_detection.as_ref().map_or(0, |detection| detection.tolerance);
+        let heads = join_all(self.providers.iter().map(|provider| async move {
+            let block = provider.inner.request("eth_blockNumber", QuorumParams::Zst).await.ok()?;
+            serde_json::from_value::<U64>(block).ok()
+        }))
+        .await;
+        let head = heads
+            .into_iter()
+            .flatten()
+            .min()
+            .ok_or_else(|| ProviderError::CustomError("No Providers".to_string()))?;
+        let block_number = head.saturating_sub(tolerance.into());
+
+        let params = QuorumParams::Value(serde_json::json!([block_number, false]));
+        let hashes = join_all(self.providers.iter().enumerate().map(|(idx, provider)| {
+            let params = params.clone();
+            async move {
+                let block = provider.inner.request("eth_getBlockByNumber", params).await.ok()?;
+                let hash = serde_json::from_value::<H256>(block.get("hash")?.clone()).ok()?;
+                Some((idx, hash))
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        let forked = hashes.iter().any(|(_, hash)| *hash != hashes[0].1);
+        let fork = forked.then_some(ForkDetected { block_number, hashes });
+        let previous = std::mem::replace(&mut *self.fork.lock().unwrap(), fork.clone());
+        let listener =
+            self.fork_detection.as_ref().and_then(|detection| detection.listener.as_ref());
+        if let (Some(fork), Some(listener)) = (&fork, listener) {
+            if previous.as_ref() != Some(fork) {
+                listener(fork);
+            }
+        }
+        Ok(fork)
+    }
+
     /// Normalizes the request payload depending on the call
     async fn normalize_request(&self, method: &str, q_params: &mut QuorumParams) {
         let params = if let QuorumParams::Value(v) = q_params {
@@ -364,6 +494,10 @@ pub enum QuorumError {
         /// Returned errors
         errors: Vec<ProviderError>,
     },
+    /// A transaction was refused because the providers are forked, see
+    /// [`ForkDetection::pause_writes`]
+    #[error("providers diverged at block {}", .0.block_number)]
+    ForkDetected(ForkDetected),
 }
 
 impl crate::RpcError for QuorumError {
@@ -474,6 +608,14 @@ where
         };
         self.normalize_request(method, &mut params).await;
 
+        if let Some(detection) = &self.fork_detection {
+            if detection.pause_writes && WRITE_METHODS.contains(&method) {
+                if let Some(fork) = self.detect_fork().await? {
+                    return Err(QuorumError::ForkDetected(fork).into())
+                }
+            }
+        }
+
         let requests = self
             .providers
             .iter()
@@ -602,9 +744,13 @@ pub enum QuorumParams {
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
-    use super::{Quorum, QuorumProvider, WeightedProvider};
-    use crate::{Middleware, MockProvider, Provider};
-    use ethers_core::types::U64;
+    use super::{ForkDetection, Quorum, QuorumProvider, WeightedProvider};
+    use crate::{JsonRpcClient, Middleware, MockProvider, Provider, ProviderError};
+    use ethers_core::types::{H256, U64};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
 
     async fn test_quorum(q: Quorum) {
         let num = 5u64;
@@ -672,4 +818,51 @@ mod tests {
     async fn all_quorum() {
         test_quorum(Quorum::All).await
     }
+
+    #[tokio::test]
+    async fn detects_forks() {
+        let mocks = [MockProvider::new(), MockProvider::new(), MockProvider::new()];
+        // responses are popped from the back
+        let push_blocks = |hashes: [u64; 3]| {
+            for (mock, hash) in mocks.iter().zip(hashes) {
+                mock.push(serde_json::json!({ "hash": H256::from_low_u64_be(hash) })).unwrap();
+                mock.push(U64::from(100 + hash)).unwrap();
+            }
+        };
+        let forks = Arc::new(AtomicUsize::new(0));
+        let counter = forks.clone();
+        let quorum = QuorumProvider::builder()
+            .add_providers(mocks.iter().cloned().map(WeightedProvider::new))
+            .fork_detection(ForkDetection::new(10).pause_writes(true).on_fork(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }))
+            .build();
+
+        push_blocks([1, 1, 2]);
+        let fork = quorum.detect_fork().await.unwrap().unwrap();
+        assert_eq!(fork.block_number, U64::from(91));
+        assert_eq!(fork.hashes[2], (2, H256::from_low_u64_be(2)));
+        assert_eq!(quorum.fork(), Some(fork));
+        mocks[0].assert_request("eth_blockNumber", ()).unwrap();
+        mocks[0].assert_request("eth_getBlockByNumber", ("0x5b", false)).unwrap();
+
+        // writes are paused while the fork persists, the listener is only called once
+        push_blocks([1, 1, 2]);
+        let err = JsonRpcClient::request::<_, H256>(&quorum, "eth_sendRawTransaction", ["0x00"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::JsonRpcClientError(_)));
+        assert!(err.to_string().contains("providers diverged at block 91"), "{err}");
+        assert_eq!(forks.load(Ordering::SeqCst), 1);
+
+        // and resume once the providers agree again
+        for mock in &mocks {
+            mock.push(H256::zero()).unwrap();
+        }
+        push_blocks([1, 1, 1]);
+        let hash: H256 =
+            JsonRpcClient::request(&quorum, "eth_sendRawTransaction", ["0x00"]).await.unwrap();
+        assert_eq!(hash, H256::zero());
+        assert_eq!(quorum.fork(), None);
+    }
 }
@@ -334,11 +334,7 @@ where
 
                 trace!("retrying and backing off for {:?}", next_backoff);
 
-                #[cfg(target_arch = "wasm32")]
-                futures_timer::Delay::new(next_backoff).await;
-
-                #[cfg(not(target_arch = "wasm32"))]
-                tokio::time::sleep(next_backoff).await;
+                crate::time::sleep(next_backoff).await;
             } else {
                 let err: ProviderError = err.into();
                 if timeout_retries < self.timeout_retries && maybe_connectivity(&err) {
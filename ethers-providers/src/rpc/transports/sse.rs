@@ -0,0 +1,366 @@
+//! A transport for providers that push subscription events as Server-Sent Events
+
+use super::{common::JsonRpcError, http::ClientError as HttpClientError, Http};
+use crate::{errors::ProviderError, JsonRpcClient, PubsubClient, RpcError};
+use async_trait::async_trait;
+use bytes::Bytes;
+use ethers_core::types::U256;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use reqwest::{header::ACCEPT, Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
+use thiserror::Error;
+use tracing::error;
+
+/// A single event of a Server-Sent Events stream.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The event type, if set by the server
+    pub event: Option<String>,
+    /// The event data, with multiple `data` lines joined by newlines
+    pub data: String,
+    /// The event id, if set by the server
+    pub id: Option<String>,
+    /// The reconnection time in milliseconds requested by the server
+    pub retry: Option<u64>,
+}
+
+/// Incrementally parses the `text/event-stream` format.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: SseEvent,
+    has_data: bool,
+}
+
+impl SseParser {
+    /// Feeds a chunk of the stream and returns the events it completed.
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+
+            if line.is_empty() {
+                // an empty line dispatches the event
+                let event = std::mem::take(&mut self.event);
+                if std::mem::take(&mut self.has_data) {
+                    events.push(event);
+                }
+                continue
+            }
+
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => {
+                    if self.has_data {
+                        self.event.data.push('\n');
+                    }
+                    self.event.data.push_str(value);
+                    self.has_data = true;
+                }
+                "event" => self.event.event = Some(value.to_string()),
+                "id" => self.event.id = Some(value.to_string()),
+                "retry" => self.event.retry = value.parse().ok(),
+                // comments and unknown fields are ignored
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// A stream of [`SseEvent`]s read from a `text/event-stream` response.
+///
+/// The stream ends when the server closes the connection.
+#[must_use = "streams do nothing unless polled"]
+pub struct SseEventStream {
+    body: Pin<Box<dyn Stream<Item = Result<Bytes, reqwest::Error>> + Send>>,
+    parser: SseParser,
+    pending: VecDeque<SseEvent>,
+}
+
+impl SseEventStream {
+    /// Opens the event stream at `url`.
+    pub async fn connect(client: &Client, url: Url) -> Result<Self, SseClientError> {
+        let response =
+            client.get(url).header(ACCEPT, "text/event-stream").send().await?.error_for_status()?;
+        Ok(Self::new(response.bytes_stream()))
+    }
+
+    /// Reads events from the body of a `text/event-stream` response.
+    pub fn new(body: impl Stream<Item = Result<Bytes, reqwest::Error>> + Send + 'static) -> Self {
+        Self { body: Box::pin(body), parser: SseParser::default(), pending: VecDeque::new() }
+    }
+}
+
+impl fmt::Debug for SseEventStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SseEventStream").field("pending", &self.pending.len()).finish()
+    }
+}
+
+impl Stream for SseEventStream {
+    type Item = Result<SseEvent, SseClientError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)))
+            }
+            match futures_util::ready!(self.body.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => {
+                    let events = self.parser.feed(&chunk);
+                    self.pending.extend(events);
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// The notifications of a subscription, read from an [`SseEventStream`].
+///
+/// Events carrying a JSON-RPC `eth_subscription` notification are unwrapped to their `result`,
+/// like notifications received over a websocket. Events with other JSON data are passed through
+/// as is.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct SseNotifications {
+    events: SseEventStream,
+}
+
+#[derive(Deserialize)]
+struct Notification<'a> {
+    #[serde(borrow)]
+    params: NotificationParams<'a>,
+}
+
+#[derive(Deserialize)]
+struct NotificationParams<'a> {
+    #[serde(borrow)]
+    result: &'a RawValue,
+}
+
+fn notification_payload(data: &str) -> Option<Box<RawValue>> {
+    if let Ok(notification) = serde_json::from_str::<Notification<'_>>(data) {
+        return Some(notification.params.result.to_owned())
+    }
+    RawValue::from_string(data.to_string()).ok()
+}
+
+impl Stream for SseNotifications {
+    type Item = Box<RawValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match futures_util::ready!(self.events.poll_next_unpin(cx)) {
+                Some(Ok(event)) => match notification_payload(&event.data) {
+                    Some(payload) => return Poll::Ready(Some(payload)),
+                    None => error!(data = %event.data, "event does not contain JSON"),
+                },
+                Some(Err(err)) => {
+                    error!(%err, "event stream failed");
+                    return Poll::Ready(None)
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// A client that sends requests over HTTP and receives subscription events as Server-Sent
+/// Events, for providers that offer an event stream instead of websockets.
+///
+/// `eth_subscribe` opens the event stream, whose events are then delivered through the usual
+/// [`Provider::subscribe`](crate::Provider::subscribe) streams. All other requests are sent to
+/// the JSON-RPC endpoint. The event stream is not multiplexed: every subscription opens its own
+/// connection and receives all events the server sends on it, regardless of the subscription
+/// parameters.
+///
+/// ```no_run
+/// use ethers_providers::{Middleware, Provider, Sse, StreamExt};
+/// use ethers_core::types::Block;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let sse = Sse::new("https://rpc.example.com".parse()?, "https://rpc.example.com/events".parse()?);
+/// let provider = Provider::new(sse);
+///
+/// let mut stream = provider.subscribe::<_, serde_json::Value>(["newHeads"]).await?;
+/// while let Some(event) = stream.next().await {
+///     println!("{event}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sse {
+    http: Http,
+    client: Client,
+    events_url: Url,
+    next_id: Arc<AtomicU64>,
+    streams: Arc<Mutex<HashMap<U256, SseNotifications>>>,
+}
+
+impl Sse {
+    /// Creates a client that sends requests to `rpc_url` and subscribes to `events_url`.
+    pub fn new(rpc_url: Url, events_url: Url) -> Self {
+        Self::new_with_client(rpc_url, events_url, Client::new())
+    }
+
+    /// Creates a client with a preconfigured [`reqwest::Client`], e.g. with authentication
+    /// headers.
+    pub fn new_with_client(rpc_url: Url, events_url: Url, client: Client) -> Self {
+        Self {
+            http: Http::new_with_client(rpc_url, client.clone()),
+            client,
+            events_url,
+            next_id: Arc::new(AtomicU64::new(1)),
+            streams: Default::default(),
+        }
+    }
+
+    /// Returns the URL of the event stream.
+    pub fn events_url(&self) -> &Url {
+        &self.events_url
+    }
+
+    /// Opens a new connection to the event stream and returns its raw events.
+    pub async fn events(&self) -> Result<SseEventStream, SseClientError> {
+        SseEventStream::connect(&self.client, self.events_url.clone()).await
+    }
+}
+
+/// Error thrown by the [`Sse`] client
+#[derive(Error, Debug)]
+pub enum SseClientError {
+    /// Thrown if a JSON-RPC request failed
+    #[error(transparent)]
+    Http(#[from] HttpClientError),
+    /// Thrown if the event stream could not be opened or read
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// Thrown if the subscription id could not be (de)serialized
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    /// Thrown when subscribing to an id that was not returned by `eth_subscribe`
+    #[error("unknown subscription {0}")]
+    UnknownSubscription(U256),
+}
+
+impl RpcError for SseClientError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            SseClientError::Http(err) => err.as_error_response(),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            SseClientError::Http(err) => err.as_serde_error(),
+            SseClientError::SerdeJson(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<SseClientError> for ProviderError {
+    fn from(src: SseClientError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(src))
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for Sse {
+    type Error = SseClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        match method {
+            "eth_subscribe" => {
+                let stream = SseNotifications { events: self.events().await? };
+                let id = U256::from(self.next_id.fetch_add(1, Ordering::Relaxed));
+                self.streams.lock().unwrap().insert(id, stream);
+                Ok(serde_json::from_value(serde_json::to_value(id)?)?)
+            }
+            "eth_unsubscribe" => {
+                // the connection of an active subscription is closed when its stream is dropped
+                Ok(serde_json::from_value(serde_json::Value::Bool(true))?)
+            }
+            _ => Ok(self.http.request(method, params).await?),
+        }
+    }
+}
+
+impl PubsubClient for Sse {
+    type NotificationStream = SseNotifications;
+
+    fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error> {
+        let id = id.into();
+        self.streams.lock().unwrap().remove(&id).ok_or(SseClientError::UnknownSubscription(id))
+    }
+
+    fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error> {
+        self.streams.lock().unwrap().remove(&id.into());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_event_stream() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b": keep-alive\n\nevent: tx\nid: 1\nda").is_empty());
+
+        let events = parser.feed(b"ta: {\"a\":\r\ndata: 1}\n\ndata:2\nretry: 500\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: Some("tx".to_string()),
+                    data: "{\"a\":\n1}".to_string(),
+                    id: Some("1".to_string()),
+                    retry: None,
+                },
+                SseEvent { data: "2".to_string(), retry: Some(500), ..Default::default() },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unwraps_notifications() {
+        let body = "data: {\"jsonrpc\":\"2.0\",\"method\":\"eth_subscription\",\"params\":{\"subscription\":\"0x1\",\"result\":{\"number\":\"0x1\"}}}\n\n\
+                    data: {\"hash\":\"0x00\"}\n\n\
+                    data: not json\n\n";
+        let chunks: Vec<Result<Bytes, reqwest::Error>> =
+            body.as_bytes().chunks(7).map(|chunk| Ok(Bytes::copy_from_slice(chunk))).collect();
+        let notifications =
+            SseNotifications { events: SseEventStream::new(futures_util::stream::iter(chunks)) };
+
+        let payloads: Vec<_> =
+            notifications.map(|payload| payload.get().to_string()).collect().await;
+        assert_eq!(payloads, vec!["{\"number\":\"0x1\"}", "{\"hash\":\"0x00\"}"]);
+    }
+}
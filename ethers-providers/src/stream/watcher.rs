@@ -1,7 +1,4 @@
-use crate::{
-    utils::{interval, PinBoxFut},
-    JsonRpcClient, Middleware, Provider,
-};
+use crate::{time::interval, utils::PinBoxFut, JsonRpcClient, Middleware, Provider};
 use ethers_core::types::U256;
 use futures_core::stream::Stream;
 use futures_util::StreamExt;
@@ -0,0 +1,80 @@
+//! Timers that work on native targets and in the browser.
+//!
+//! Native targets use `futures-timer`, so the futures and streams of this module work with any
+//! executor and don't require a tokio runtime. On `wasm32` they use the timers of the browser via
+//! `gloo-timers`.
+use futures_util::{
+    future::{select, Either},
+    stream, FutureExt, StreamExt,
+};
+use std::future::Future;
+use thiserror::Error;
+
+pub use instant::{Duration, Instant};
+
+/// Future returned by [`sleep`]
+#[cfg(not(target_arch = "wasm32"))]
+pub type Sleep = futures_timer::Delay;
+
+/// Future returned by [`sleep`]
+#[cfg(target_arch = "wasm32")]
+pub type Sleep = send_wrapper::SendWrapper<gloo_timers::future::TimeoutFuture>;
+
+/// Error returned by [`timeout`] if the future did not complete in time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+#[error("deadline has elapsed")]
+pub struct Elapsed;
+
+/// Returns a future that completes after `duration`.
+pub fn sleep(duration: Duration) -> Sleep {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        futures_timer::Delay::new(duration)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        send_wrapper::SendWrapper::new(gloo_timers::future::sleep(duration))
+    }
+}
+
+/// Awaits `future` for at most `duration`.
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    match select(Box::pin(future), Box::pin(sleep(duration))).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Elapsed),
+    }
+}
+
+// https://github.com/tomusdrw/rust-web3/blob/befcb2fb8f3ca0a43e3081f68886fa327e64c8e6/src/api/eth_filter.rs#L20
+/// Create a stream that emits items at a fixed interval. Used for rate control
+pub fn interval(duration: Duration) -> impl futures_core::stream::Stream<Item = ()> + Send + Unpin {
+    stream::unfold((), move |_| Box::pin(sleep(duration)).map(|_| Some(((), ())))).map(drop)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn times_out() {
+        let slow = sleep(Duration::from_millis(100));
+        assert_eq!(timeout(Duration::from_millis(10), slow).await, Err(Elapsed));
+        assert_eq!(timeout(Duration::from_millis(100), async { 1 }).await, Ok(1));
+
+        let start = Instant::now();
+        let ticks = interval(Duration::from_millis(10)).take(3).count().await;
+        assert_eq!(ticks, 3);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn works_without_tokio() {
+        futures_executor::block_on(async {
+            let start = Instant::now();
+            sleep(Duration::from_millis(10)).await;
+            assert_eq!(interval(Duration::from_millis(10)).take(2).count().await, 2);
+            assert_eq!(timeout(Duration::from_millis(100), async { 1 }).await, Ok(1));
+            assert!(start.elapsed() >= Duration::from_millis(30));
+        });
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Middleware;
+use ethers_core::types::{Bloom, BloomFilter, Filter, FilteredParams, Log, H256};
+use futures_util::stream::{self, Stream, StreamExt};
+
+/// Checks block blooms against the address and topics of a [`Filter`].
+#[derive(Clone, Debug)]
+struct BloomMatcher {
+    address: BloomFilter,
+    topics: Vec<BloomFilter>,
+}
+
+impl BloomMatcher {
+    fn new(filter: &Filter) -> Self {
+        let topics = Some(FilteredParams::new(Some(filter.clone())).flat_topics);
+        Self {
+            address: FilteredParams::address_filter(&filter.address),
+            topics: FilteredParams::topics_filter(&topics),
+        }
+    }
+
+    /// Returns `false` if the block can not contain logs matching the filter.
+    fn matches(&self, bloom: Bloom) -> bool {
+        FilteredParams::matches_address(bloom, &self.address) &&
+            FilteredParams::matches_topics(bloom, &self.topics)
+    }
+}
+
+/// Streams the logs matching `filter` from new blocks, fetching logs only for blocks whose
+/// `logsBloom` may contain a match.
+///
+/// New blocks are picked up with [`Middleware::watch_blocks`], so this also works over HTTP. For
+/// sparse events this replaces one `eth_getLogs` request per poll with a header request per block
+/// and an `eth_getLogs` request for the few blocks that match. The block range of `filter` is
+/// ignored.
+///
+/// ```no_run
+/// use ethers_core::types::{Address, Filter};
+/// use ethers_providers::{watch_logs_with_bloom, Http, Provider, StreamExt};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+/// let filter = Filter::new().address(Address::zero()).event("Transfer(address,address,uint256)");
+///
+/// let mut logs = watch_logs_with_bloom(&provider, &filter).await?;
+/// while let Some(log) = logs.next().await {
+///     println!("{:?}", log?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn watch_logs_with_bloom<'a, M: Middleware>(
+    client: &'a M,
+    filter: &Filter,
+) -> Result<impl Stream<Item = Result<Log, M::Error>> + 'a, M::Error> {
+    let matcher = BloomMatcher::new(filter);
+    let filter = filter.clone();
+    let blocks = client.watch_blocks().await?;
+
+    let logs = blocks
+        .then(move |hash| {
+            let matcher = matcher.clone();
+            let filter = filter.clone();
+            async move { fetch_matching_logs(client, &matcher, filter, hash).await }
+        })
+        .flat_map(|result| {
+            let items: Vec<_> = match result {
+                Ok(logs) => logs.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            };
+            stream::iter(items)
+        });
+    Ok(logs)
+}
+
+async fn fetch_matching_logs<M: Middleware>(
+    client: &M,
+    matcher: &BloomMatcher,
+    filter: Filter,
+    hash: H256,
+) -> Result<Vec<Log>, M::Error> {
+    let bloom = client.get_block(hash).await?.and_then(|block| block.logs_bloom);
+    if let Some(bloom) = bloom {
+        if !matcher.matches(bloom) {
+            tracing::trace!(?hash, "skipping block, bloom does not match filter");
+            return Ok(Vec::new())
+        }
+    }
+    client.get_logs(&filter.at_block_hash(hash)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::{
+        abi::ethereum_types::BloomInput,
+        types::{Address, ValueOrArray},
+    };
+
+    #[test]
+    fn matches_bloom() {
+        let address = Address::repeat_byte(0x11);
+        let topic = H256::repeat_byte(0x22);
+        let mut bloom = Bloom::default();
+        bloom.accrue(BloomInput::Raw(address.as_bytes()));
+        bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+
+        assert!(BloomMatcher::new(&Filter::new()).matches(bloom));
+        assert!(BloomMatcher::new(&Filter::new().address(address).topic0(topic)).matches(bloom));
+        assert!(BloomMatcher::new(
+            &Filter::new().address(ValueOrArray::Array(vec![Address::zero(), address]))
+        )
+        .matches(bloom));
+        assert!(!BloomMatcher::new(&Filter::new().address(Address::zero())).matches(bloom));
+        assert!(!BloomMatcher::new(&Filter::new().topic0(H256::zero())).matches(bloom));
+    }
+}
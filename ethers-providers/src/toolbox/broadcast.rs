@@ -0,0 +1,107 @@
+use crate::{Middleware, MiddlewareError, PendingTransaction};
+use ethers_core::{
+    types::{Bytes, H256},
+    utils::keccak256,
+};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use thiserror::Error;
+
+/// Thrown when none of the endpoints accepted the transaction
+#[derive(Error, Debug)]
+#[error("the transaction was rejected by all {} endpoints", .0.len())]
+pub struct BroadcastError<M: Middleware>(
+    /// The errors returned by the endpoints, in the order in which they failed
+    pub Vec<M::Error>,
+);
+
+/// Submits the signed transaction `tx` to all `endpoints` concurrently and returns as soon as one
+/// of them accepts it.
+///
+/// Sending the same transaction to several public and private endpoints speeds up its
+/// propagation, which matters for time-sensitive transactions. An endpoint that reports the
+/// transaction as already known counts as accepting it. The returned [PendingTransaction] polls
+/// the endpoint that accepted the transaction first.
+///
+/// Requests that are still in flight when one endpoint accepts the transaction are not awaited.
+///
+/// ```no_run
+/// use ethers_core::types::Bytes;
+/// use ethers_providers::{send_raw_transaction_multi, Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(signed: Bytes) -> Result<(), Box<dyn std::error::Error>> {
+/// let endpoints = [
+///     Provider::<Http>::try_from("https://rpc.ankr.com/eth")?,
+///     Provider::<Http>::try_from("https://rpc.flashbots.net")?,
+/// ];
+/// let receipt = send_raw_transaction_multi(&endpoints, signed).await?.await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn send_raw_transaction_multi<M: Middleware>(
+    endpoints: &[M],
+    tx: Bytes,
+) -> Result<PendingTransaction<'_, M::Provider>, BroadcastError<M>> {
+    let hash = H256(keccak256(&tx));
+    let mut requests: FuturesUnordered<_> = endpoints
+        .iter()
+        .map(|endpoint| {
+            let tx = tx.clone();
+            async move { (endpoint, endpoint.send_raw_transaction(tx).await) }
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    while let Some((endpoint, result)) = requests.next().await {
+        match result {
+            Ok(pending) => return Ok(pending),
+            Err(err) if is_already_known(&err) => {
+                return Ok(PendingTransaction::new(hash, endpoint.provider()))
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+    Err(BroadcastError(errors))
+}
+
+fn is_already_known<E: MiddlewareError>(err: &E) -> bool {
+    err.as_error_response().map_or(false, |err| {
+        let message = err.message.to_lowercase();
+        message.contains("already known") || message.contains("known transaction")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonRpcError, MockResponse, Provider};
+
+    #[tokio::test]
+    async fn returns_first_acceptance() {
+        let tx = Bytes::from(vec![1, 2, 3]);
+        let hash = H256(keccak256(&tx));
+
+        let (rejecting, _) = Provider::mocked();
+        let (accepting, mock) = Provider::mocked();
+        mock.push(hash).unwrap();
+
+        let endpoints = [rejecting, accepting];
+        let pending = send_raw_transaction_multi(&endpoints, tx.clone()).await.unwrap();
+        assert_eq!(pending.tx_hash(), hash);
+
+        // an endpoint that already has the transaction accepts it
+        let (known, mock) = Provider::mocked();
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code: -32000,
+            message: "already known".to_string(),
+            data: None,
+        }));
+        let endpoints = [known];
+        let pending = send_raw_transaction_multi(&endpoints, tx.clone()).await.unwrap();
+        assert_eq!(pending.tx_hash(), hash);
+
+        let endpoints = [Provider::mocked().0, Provider::mocked().0];
+        let err = send_raw_transaction_multi(&endpoints, tx).await.unwrap_err();
+        assert_eq!(err.0.len(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+use ethers_core::types::{Chain, U256};
+
+/// The margin which is added on top of the node's gas estimate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GasBuffer {
+    /// Use the node's estimate as is
+    #[default]
+    None,
+    /// Add the given percentage of the estimate, e.g. `20` for 20%
+    Percent(u64),
+    /// Add a fixed amount of gas
+    Absolute(u64),
+}
+
+impl GasBuffer {
+    /// Adds the buffer to the `estimate`
+    pub fn apply(&self, estimate: U256) -> U256 {
+        match *self {
+            GasBuffer::None => estimate,
+            GasBuffer::Percent(percent) => {
+                estimate.saturating_add(estimate.saturating_mul(percent.into()) / 100)
+            }
+            GasBuffer::Absolute(gas) => estimate.saturating_add(gas.into()),
+        }
+    }
+}
+
+/// The strategy with which [`Provider::fill_transaction`](crate::Middleware::fill_transaction)
+/// sets the gas limit of transactions which don't specify one.
+///
+/// The default uses the node's estimate as is. Use [`GasEstimation::for_chain`] for buffers which
+/// suit the chain's gas accounting.
+///
+/// # Example
+///
+/// ```
+/// use ethers_core::types::{Chain, U256};
+/// use ethers_providers::{GasBuffer, GasEstimation};
+///
+/// let estimation = GasEstimation::for_chain(Chain::Arbitrum).simulate(true);
+/// assert_eq!(estimation.buffer, GasBuffer::Percent(30));
+///
+/// let estimation = GasEstimation::default().buffer(GasBuffer::Absolute(10_000));
+/// assert_eq!(estimation.apply(100_000.into(), None), U256::from(110_000));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GasEstimation {
+    /// Simulates the transaction with `eth_call` before estimating its gas, surfacing the revert
+    /// reason of transactions which would fail
+    pub simulate: bool,
+    /// The margin added on top of the node's estimate
+    pub buffer: GasBuffer,
+    /// Whether the buffered estimate is capped at the gas limit of the latest block
+    pub clamp_to_block_gas_limit: bool,
+}
+
+impl GasEstimation {
+    /// Returns the strategy that uses the node's estimate as is
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the default strategy for the `chain`, which buffers the estimate and clamps it to
+    /// the block gas limit.
+    ///
+    /// Rollups which charge for L1 data as L2 gas, like Arbitrum, get larger buffers since the L1
+    /// component of their estimates moves with the L1 base fee.
+    pub fn for_chain(chain: Chain) -> Self {
+        let buffer = match chain {
+            Chain::Arbitrum |
+            Chain::ArbitrumNova |
+            Chain::ArbitrumGoerli |
+            Chain::ArbitrumSepolia |
+            Chain::ArbitrumTestnet => GasBuffer::Percent(30),
+            Chain::Polygon |
+            Chain::PolygonMumbai |
+            Chain::PolygonZkEvm |
+            Chain::PolygonZkEvmTestnet => GasBuffer::Percent(20),
+            _ => GasBuffer::Percent(10),
+        };
+        Self { simulate: false, buffer, clamp_to_block_gas_limit: true }
+    }
+
+    /// Sets whether the transaction is simulated before estimating its gas
+    #[must_use]
+    pub fn simulate(mut self, simulate: bool) -> Self {
+        self.simulate = simulate;
+        self
+    }
+
+    /// Sets the margin added on top of the node's estimate
+    #[must_use]
+    pub fn buffer(mut self, buffer: GasBuffer) -> Self {
+        self.buffer = buffer;
+        self
+    }
+
+    /// Sets whether the buffered estimate is capped at the gas limit of the latest block
+    #[must_use]
+    pub fn clamp_to_block_gas_limit(mut self, clamp: bool) -> Self {
+        self.clamp_to_block_gas_limit = clamp;
+        self
+    }
+
+    /// Applies the buffer to the node's `estimate`, capping it at the `block_gas_limit` if
+    /// clamping is enabled
+    pub fn apply(&self, estimate: U256, block_gas_limit: Option<U256>) -> U256 {
+        let gas = self.buffer.apply(estimate);
+        match block_gas_limit {
+            Some(limit) if self.clamp_to_block_gas_limit => gas.min(limit),
+            _ => gas,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_buffers() {
+        let estimate = U256::from(100_000);
+        assert_eq!(GasBuffer::None.apply(estimate), estimate);
+        assert_eq!(GasBuffer::Percent(25).apply(estimate), U256::from(125_000));
+        assert_eq!(GasBuffer::Absolute(21_000).apply(estimate), U256::from(121_000));
+        assert_eq!(GasBuffer::Percent(10).apply(U256::MAX), U256::MAX);
+    }
+
+    #[test]
+    fn clamps_to_block_gas_limit() {
+        let estimation = GasEstimation::for_chain(Chain::Mainnet);
+        let estimate = U256::from(29_000_000);
+        let limit = U256::from(30_000_000);
+        assert_eq!(estimation.apply(estimate, Some(limit)), limit);
+        assert_eq!(estimation.apply(estimate, None), U256::from(31_900_000));
+
+        let estimation = estimation.clamp_to_block_gas_limit(false);
+        assert_eq!(estimation.apply(estimate, Some(limit)), U256::from(31_900_000));
+    }
+
+    #[test]
+    fn chain_defaults() {
+        assert_eq!(GasEstimation::for_chain(Chain::Arbitrum).buffer, GasBuffer::Percent(30));
+        assert_eq!(GasEstimation::for_chain(Chain::Polygon).buffer, GasBuffer::Percent(20));
+        assert_eq!(GasEstimation::for_chain(Chain::Mainnet).buffer, GasBuffer::Percent(10));
+        assert_eq!(GasEstimation::default().apply(1.into(), Some(0.into())), 1.into());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{Middleware, PubsubClient};
+use ethers_core::types::{BlockNumber, Filter, Log, U256, U64};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+/// The default number of blocks requested per `eth_getLogs` call while backfilling
+pub const DEFAULT_BACKFILL_PAGE_SIZE: u64 = 10_000;
+
+/// The position of the last processed log, from which a log stream resumes.
+///
+/// Checkpoints are ordered by block number, then by the index of the log in the block.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct LogCheckpoint {
+    /// The number of the block which contains the log
+    pub block_number: U64,
+    /// The index of the log in the block
+    pub log_index: U256,
+}
+
+impl LogCheckpoint {
+    /// Returns the position of a mined `log`, or `None` if it is pending.
+    pub fn of(log: &Log) -> Option<Self> {
+        Some(Self { block_number: log.block_number?, log_index: log.log_index? })
+    }
+
+    /// Returns the checkpoint to save after processing `log`.
+    ///
+    /// This is the position of the log, or the position just before it if the log was removed by
+    /// a reorg, so that the log which replaces it is not skipped. Returns `None` if the log is
+    /// pending or there is no position before it.
+    pub fn after(log: &Log) -> Option<Self> {
+        let position = Self::of(log)?;
+        if log.removed == Some(true) {
+            position.prev()
+        } else {
+            Some(position)
+        }
+    }
+
+    /// Returns the position just before this one, if any.
+    pub fn prev(&self) -> Option<Self> {
+        if !self.log_index.is_zero() {
+            Some(Self { block_number: self.block_number, log_index: self.log_index - 1 })
+        } else if !self.block_number.is_zero() {
+            Some(Self { block_number: self.block_number - 1, log_index: U256::MAX })
+        } else {
+            None
+        }
+    }
+}
+
+/// Persists the [`LogCheckpoint`] of a log consumer across restarts.
+pub trait CheckpointStore {
+    /// The error returned when the checkpoint can't be loaded or saved
+    type Error;
+
+    /// Returns the last saved checkpoint, if any
+    fn load(&self) -> Result<Option<LogCheckpoint>, Self::Error>;
+
+    /// Saves the `checkpoint`, replacing the previous one
+    fn save(&self, checkpoint: &LogCheckpoint) -> Result<(), Self::Error>;
+}
+
+/// Stores the checkpoint as JSON in a file.
+///
+/// The file is replaced atomically, so a crash while saving leaves the previous checkpoint.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileCheckpointStore {
+    /// Stores the checkpoint at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CheckpointStore for FileCheckpointStore {
+    type Error = std::io::Error;
+
+    fn load(&self) -> Result<Option<LogCheckpoint>, Self::Error> {
+        match std::fs::read(&self.path) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save(&self, checkpoint: &LogCheckpoint) -> Result<(), Self::Error> {
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(checkpoint)?)?;
+        std::fs::rename(tmp, &self.path)
+    }
+}
+
+/// Merges the historical `backfill` and the `live` logs of the same filter into one stream,
+/// ordered by [`LogCheckpoint`] and without duplicates.
+///
+/// `backfill` is consumed first. `live` has to be subscribed to before the backfill's last block is
+/// fetched, so that no logs are missed between the two. Logs which overlap are only yielded once,
+/// as are logs at or before `checkpoint`. Logs removed by a reorg are always yielded, so that
+/// consumers can revert them, and rewind the deduplication to just before their position, so that
+/// the logs replacing them are yielded too.
+pub fn merge_backfill<'a, E: 'a>(
+    backfill: impl Stream<Item = Result<Log, E>> + 'a,
+    live: impl Stream<Item = Log> + 'a,
+    checkpoint: Option<LogCheckpoint>,
+) -> impl Stream<Item = Result<Log, E>> + 'a {
+    backfill
+        .chain(live.map(Ok))
+        .scan(checkpoint, |last, item| {
+            let item = match item {
+                Ok(log) if log.removed != Some(true) => match LogCheckpoint::of(&log) {
+                    Some(position) if last.map_or(false, |last| position <= last) => None,
+                    position => {
+                        *last = position.or(*last);
+                        Some(Ok(log))
+                    }
+                },
+                Ok(log) => {
+                    // the removed log is replaced by the logs of the new canonical block
+                    if let Some(position) = LogCheckpoint::of(&log) {
+                        let before = position.prev();
+                        if last.map_or(false, |last| before.map_or(true, |before| before < last)) {
+                            *last = before;
+                        }
+                    }
+                    Some(Ok(log))
+                }
+                item => Some(item),
+            };
+            futures_util::future::ready(Some(item))
+        })
+        .filter_map(futures_util::future::ready)
+}
+
+/// Streams the logs matching `filter`, starting after `checkpoint` or at the filter's
+/// `fromBlock`, and continuing with new logs as they are mined.
+///
+/// The historical logs are fetched with paginated `eth_getLogs` requests of `page_size` blocks,
+/// the new ones with [`Middleware::subscribe_logs`], see [`merge_backfill`]. Every log is yielded
+/// exactly once as long as the consumer saves the [`LogCheckpoint::after`] the log after
+/// processing it, and restarts from the saved checkpoint.
+///
+/// Block tags of the filter's `fromBlock`, like `earliest`, are resolved to block numbers before
+/// backfilling.
+///
+/// ```no_run
+/// use ethers_core::types::{Address, Filter};
+/// use ethers_providers::{
+///     stream_logs_from, CheckpointStore, FileCheckpointStore, LogCheckpoint, Provider, StreamExt,
+///     Ws, DEFAULT_BACKFILL_PAGE_SIZE,
+/// };
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Ws>::connect("ws://localhost:8545").await?;
+/// let filter = Filter::new().address(Address::zero()).from_block(17_000_000);
+/// let store = FileCheckpointStore::new("transfers.checkpoint");
+///
+/// let mut logs =
+///     stream_logs_from(&provider, &filter, store.load()?, DEFAULT_BACKFILL_PAGE_SIZE).await?;
+/// while let Some(log) = logs.next().await {
+///     let log = log?;
+///     // process the log, then
+///     if let Some(checkpoint) = LogCheckpoint::after(&log) {
+///         store.save(&checkpoint)?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn stream_logs_from<'a, M>(
+    client: &'a M,
+    filter: &Filter,
+    checkpoint: Option<LogCheckpoint>,
+    page_size: u64,
+) -> Result<impl Stream<Item = Result<Log, M::Error>> + 'a, M::Error>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+{
+    // subscribe first, so that logs mined during the backfill are delivered by the subscription
+    let live = client.subscribe_logs(filter).await?;
+    let last_block = client.get_block_number().await?;
+
+    let from_block = match (checkpoint, filter.block_option.get_from_block()) {
+        (Some(checkpoint), _) => checkpoint.block_number,
+        (None, Some(BlockNumber::Number(number))) => *number,
+        (None, Some(BlockNumber::Earliest)) => U64::zero(),
+        (None, Some(tag)) => {
+            client.get_block(*tag).await?.and_then(|block| block.number).unwrap_or(last_block + 1)
+        }
+        (None, None) => last_block + 1,
+    };
+    let page_size = page_size.max(1);
+    let filter = filter.clone();
+
+    let pages = stream::unfold(from_block, move |from_block| {
+        let filter = filter.clone();
+        async move {
+            if from_block > last_block {
+                return None
+            }
+            let to_block = (from_block + page_size - 1).min(last_block);
+            let logs = client.get_logs(&filter.from_block(from_block).to_block(to_block)).await;
+            Some((logs, to_block + 1))
+        }
+    });
+    let backfill = pages.flat_map(|page| {
+        let items: Vec<_> = match page {
+            Ok(logs) => logs.into_iter().map(Ok).collect(),
+            Err(err) => vec![Err(err)],
+        };
+        stream::iter(items)
+    });
+
+    Ok(merge_backfill(backfill, live, checkpoint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(block_number: u64, log_index: u64) -> Log {
+        Log {
+            block_number: Some(block_number.into()),
+            log_index: Some(log_index.into()),
+            ..Default::default()
+        }
+    }
+
+    fn positions(logs: Vec<Result<Log, ()>>) -> Vec<(u64, u64)> {
+        logs.into_iter()
+            .map(|log| {
+                let log = log.unwrap();
+                (log.block_number.unwrap().as_u64(), log.log_index.unwrap().as_u64())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn merges_without_duplicates() {
+        let backfill = stream::iter(vec![Ok(log(1, 0)), Ok(log(2, 0)), Ok(log(2, 1))]);
+        let live = stream::iter(vec![log(2, 1), log(3, 0)]);
+        let logs = merge_backfill(backfill, live, None).collect::<Vec<_>>().await;
+        assert_eq!(positions(logs), vec![(1, 0), (2, 0), (2, 1), (3, 0)]);
+    }
+
+    #[tokio::test]
+    async fn resumes_after_checkpoint() {
+        let backfill = stream::iter(vec![Ok(log(2, 0)), Ok(log(2, 1)), Ok(log(2, 2))]);
+        let mut removed = log(2, 2);
+        removed.removed = Some(true);
+        let live = stream::iter(vec![removed, log(3, 0)]);
+
+        let checkpoint = LogCheckpoint { block_number: 2.into(), log_index: 1.into() };
+        let logs = merge_backfill(backfill, live, Some(checkpoint)).collect::<Vec<_>>().await;
+        assert_eq!(positions(logs.clone()), vec![(2, 2), (2, 2), (3, 0)]);
+        assert_eq!(logs[1].as_ref().unwrap().removed, Some(true));
+    }
+
+    #[tokio::test]
+    async fn yields_logs_replacing_removed_logs() {
+        let backfill = stream::iter(vec![Ok(log(5, 0)), Ok(log(5, 1))]);
+        let mut removed = log(5, 1);
+        removed.removed = Some(true);
+        let live = stream::iter(vec![removed.clone(), log(5, 1), log(6, 0)]);
+
+        let logs = merge_backfill(backfill, live, None).collect::<Vec<_>>().await;
+        assert_eq!(positions(logs.clone()), vec![(5, 0), (5, 1), (5, 1), (5, 1), (6, 0)]);
+        assert_eq!(logs[2].as_ref().unwrap().removed, Some(true));
+        assert_eq!(logs[3].as_ref().unwrap().removed, None);
+
+        assert_eq!(
+            LogCheckpoint::after(&removed),
+            Some(LogCheckpoint { block_number: 5.into(), log_index: 0.into() })
+        );
+        assert_eq!(
+            LogCheckpoint::after(&log(5, 0)).unwrap().prev(),
+            Some(LogCheckpoint { block_number: 4.into(), log_index: U256::MAX })
+        );
+        assert_eq!(LogCheckpoint::of(&log(0, 0)).unwrap().prev(), None);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn stores_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileCheckpointStore::new(dir.path().join("logs.checkpoint"));
+        assert_eq!(store.load().unwrap(), None);
+
+        let checkpoint = LogCheckpoint { block_number: 17.into(), log_index: 3.into() };
+        store.save(&checkpoint).unwrap();
+        assert_eq!(store.load().unwrap(), Some(checkpoint));
+        assert_eq!(LogCheckpoint::of(&log(17, 3)), Some(checkpoint));
+    }
+}
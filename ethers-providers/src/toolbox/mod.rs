@@ -7,5 +7,33 @@ pub use pending_escalator::EscalatingPending;
 mod log_query;
 pub use log_query::{LogQuery, LogQueryError};
 
+mod bloom_logs;
+pub use bloom_logs::watch_logs_with_bloom;
+
+mod log_bridge;
+#[cfg(not(target_arch = "wasm32"))]
+pub use log_bridge::FileCheckpointStore;
+pub use log_bridge::{
+    merge_backfill, stream_logs_from, CheckpointStore, LogCheckpoint, DEFAULT_BACKFILL_PAGE_SIZE,
+};
+
+mod gas_estimation;
+pub use gas_estimation::{GasBuffer, GasEstimation};
+
+mod broadcast;
+pub use broadcast::{send_raw_transaction_multi, BroadcastError};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod tasks;
+#[cfg(not(target_arch = "wasm32"))]
+pub use tasks::{
+    Completion, Shutdown, ShutdownError, TaskError, TaskManager, TaskState, TaskStatus,
+};
+
+#[cfg(not(feature = "celo"))]
+mod verified_state;
+#[cfg(not(feature = "celo"))]
+pub use verified_state::{verified_account, verified_balance, VerificationError};
+
 pub mod call_raw;
 pub use call_raw::*;
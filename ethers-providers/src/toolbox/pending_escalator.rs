@@ -1,20 +1,21 @@
 #![allow(clippy::return_self_not_must_use)]
 
 use ethers_core::types::{Bytes, TransactionReceipt, H256};
-use futures_timer::Delay;
 use futures_util::{stream::FuturesUnordered, StreamExt};
 use instant::{Duration, Instant};
 use pin_project::pin_project;
 use std::{future::Future, pin::Pin, task::Poll};
 
 use crate::{
-    utils::PinBoxFut, JsonRpcClient, Middleware, PendingTransaction, Provider, ProviderError,
+    time::{self, Sleep},
+    utils::PinBoxFut,
+    JsonRpcClient, Middleware, PendingTransaction, Provider, ProviderError,
 };
 
 /// States for the EscalatingPending future
 enum EscalatorStates<'a, P> {
     Initial(PinBoxFut<'a, PendingTransaction<'a, P>>),
-    Sleeping(Pin<Box<Delay>>),
+    Sleeping(Pin<Box<Sleep>>),
     BroadcastingNew(PinBoxFut<'a, PendingTransaction<'a, P>>),
     CheckingReceipts(FuturesUnordered<PinBoxFut<'a, Option<TransactionReceipt>>>),
     Completed,
@@ -109,7 +110,7 @@ macro_rules! check_all_receipts {
 
 macro_rules! sleep {
     ($cx:ident, $this:ident) => {
-        *$this.state = EscalatorStates::Sleeping(Box::pin(Delay::new(*$this.polling_interval)));
+        *$this.state = EscalatorStates::Sleeping(Box::pin(time::sleep(*$this.polling_interval)));
         $cx.waker().wake_by_ref();
         return Poll::Pending
     };
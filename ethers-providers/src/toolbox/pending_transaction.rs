@@ -1,10 +1,10 @@
 use crate::{
-    utils::{interval, PinBoxFut},
+    time::{interval, sleep, Sleep},
+    utils::PinBoxFut,
     JsonRpcClient, Middleware, Provider, ProviderError,
 };
 use ethers_core::types::{Transaction, TransactionReceipt, TxHash, U64};
 use futures_core::stream::Stream;
-use futures_timer::Delay;
 use futures_util::stream::StreamExt;
 use instant::Duration;
 use pin_project::pin_project;
@@ -49,7 +49,7 @@ const DEFAULT_RETRIES: usize = 3;
 impl<'a, P: JsonRpcClient> PendingTransaction<'a, P> {
     /// Creates a new pending transaction poller from a hash and a provider
     pub fn new(tx_hash: TxHash, provider: &'a Provider<P>) -> Self {
-        let delay = Box::pin(Delay::new(provider.get_interval()));
+        let delay = Box::pin(sleep(provider.get_interval()));
 
         Self {
             tx_hash,
@@ -90,7 +90,7 @@ impl<'a, P: JsonRpcClient> PendingTransaction<'a, P> {
         self.interval = Box::new(interval(duration));
 
         if matches!(self.state, PendingTxState::InitialDelay(_)) {
-            self.state = PendingTxState::InitialDelay(Box::pin(Delay::new(duration)))
+            self.state = PendingTxState::InitialDelay(Box::pin(sleep(duration)))
         }
 
         self
@@ -324,7 +324,7 @@ impl<'a, P> Deref for PendingTransaction<'a, P> {
 // We box the TransactionReceipts to keep the enum small.
 enum PendingTxState<'a> {
     /// Initial delay to ensure the GettingTx loop doesn't immediately fail
-    InitialDelay(Pin<Box<Delay>>),
+    InitialDelay(Pin<Box<Sleep>>),
 
     /// Waiting for interval to elapse before calling API again
     PausedGettingTx,
@@ -0,0 +1,338 @@
+//! Supervision and graceful shutdown of background tasks
+use futures_util::{
+    future::{select, Either},
+    FutureExt,
+};
+use std::{
+    any::Any,
+    fmt::{self, Display},
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::{debug, error};
+
+/// The state of a task spawned by a [`TaskManager`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TaskState {
+    /// The task is still running
+    Running,
+    /// The task returned successfully
+    Completed,
+    /// The task was stopped by [`TaskManager::shutdown`]
+    Cancelled,
+    /// The task returned an error or panicked
+    Failed(TaskError),
+}
+
+/// The name and state of a task spawned by a [`TaskManager`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskStatus {
+    /// The name the task was spawned with
+    pub name: String,
+    /// The current state of the task
+    pub state: TaskState,
+}
+
+/// Error of a task spawned by a [`TaskManager`]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum TaskError {
+    /// The task returned an error
+    #[error("task {name} failed: {message}")]
+    Failed {
+        /// The name of the task
+        name: String,
+        /// The error returned by the task
+        message: String,
+    },
+    /// The task panicked
+    #[error("task {name} panicked: {message}")]
+    Panicked {
+        /// The name of the task
+        name: String,
+        /// The panic message
+        message: String,
+    },
+    /// The task did not stop before the shutdown deadline and was aborted
+    #[error("task {name} did not stop before the shutdown deadline")]
+    TimedOut {
+        /// The name of the task
+        name: String,
+    },
+}
+
+/// Error returned by [`TaskManager::shutdown`] if any task failed
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("{} background tasks failed", .0.len())]
+pub struct ShutdownError(pub Vec<TaskError>);
+
+/// Signal to stop a task spawned with [`TaskManager::spawn_with_shutdown`]
+#[derive(Clone, Debug)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    /// Returns `true` if the shutdown was requested.
+    pub fn is_requested(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits until the shutdown is requested.
+    pub async fn requested(&mut self) {
+        while !*self.0.borrow() {
+            // the manager was dropped, which requests the shutdown as well
+            if self.0.changed().await.is_err() {
+                return
+            }
+        }
+    }
+}
+
+struct Task {
+    name: String,
+    state: TaskState,
+    handle: JoinHandle<()>,
+}
+
+struct Inner {
+    tasks: Mutex<Vec<Task>>,
+    shutdown: watch::Sender<bool>,
+}
+
+/// Spawns background tasks on the current tokio runtime and keeps track of them, so that they can
+/// be inspected and stopped together.
+///
+/// Errors and panics of tasks are recorded as [`TaskError`]s instead of being lost with the task.
+/// Clones share the tasks.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_providers::TaskManager;
+/// use std::time::Duration;
+///
+/// # async fn foo() {
+/// let tasks = TaskManager::new();
+/// tasks.spawn_with_shutdown("poller", |mut shutdown| async move {
+///     loop {
+///         tokio::select! {
+///             _ = shutdown.requested() => return Ok::<_, std::io::Error>(()),
+///             _ = tokio::time::sleep(Duration::from_secs(1)) => { /* poll */ }
+///         }
+///     }
+/// });
+///
+/// // ask all tasks to stop and abort those still running after 5 seconds
+/// tasks.shutdown(Duration::from_secs(5)).await.unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TaskManager {
+    inner: Arc<Inner>,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        let (shutdown, _) = watch::channel(false);
+        Self { inner: Arc::new(Inner { tasks: Mutex::new(Vec::new()), shutdown }) }
+    }
+}
+
+impl fmt::Debug for TaskManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskManager").field("tasks", &self.tasks()).finish()
+    }
+}
+
+impl TaskManager {
+    /// Creates a manager without any tasks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns `task`, which is dropped at its next `.await` when the shutdown is requested.
+    pub fn spawn<F, E>(&self, name: impl Into<String>, task: F)
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.spawn_with_shutdown(name, move |mut shutdown| async move {
+            let requested = Box::pin(async move { shutdown.requested().await });
+            match select(Box::pin(task), requested).await {
+                Either::Left((res, _)) => res.map(|_| true),
+                Either::Right(_) => Ok(false),
+            }
+        })
+    }
+
+    /// Spawns the task returned by `task`, which is expected to return once the [`Shutdown`] is
+    /// requested. Tasks that did not return by the deadline of [`TaskManager::shutdown`] are
+    /// aborted.
+    pub fn spawn_with_shutdown<F, T, R, E>(&self, name: impl Into<String>, task: F)
+    where
+        F: FnOnce(Shutdown) -> T,
+        T: Future<Output = Result<R, E>> + Send + 'static,
+        R: Completion,
+        E: Display,
+    {
+        let name = name.into();
+        let future = task(Shutdown(self.inner.shutdown.subscribe()));
+
+        let mut tasks = self.inner.tasks.lock().unwrap();
+        let id = tasks.len();
+        let inner = Arc::downgrade(&self.inner);
+        let task_name = name.clone();
+        let handle = tokio::spawn(async move {
+            let state = match AssertUnwindSafe(future).catch_unwind().await {
+                Ok(Ok(res)) if res.completed() => TaskState::Completed,
+                Ok(Ok(_)) => TaskState::Cancelled,
+                Ok(Err(err)) => {
+                    let message = err.to_string();
+                    error!(task = %task_name, %message, "background task failed");
+                    TaskState::Failed(TaskError::Failed { name: task_name, message })
+                }
+                Err(panic) => {
+                    let message = panic_message(panic);
+                    error!(task = %task_name, %message, "background task panicked");
+                    TaskState::Failed(TaskError::Panicked { name: task_name, message })
+                }
+            };
+            if let Some(inner) = inner.upgrade() {
+                inner.tasks.lock().unwrap()[id].state = state;
+            }
+        });
+        tasks.push(Task { name, state: TaskState::Running, handle });
+    }
+
+    /// Returns the status of all spawned tasks, in the order they were spawned.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        let tasks = self.inner.tasks.lock().unwrap();
+        tasks
+            .iter()
+            .map(|task| TaskStatus { name: task.name.clone(), state: task.state.clone() })
+            .collect()
+    }
+
+    /// Returns the errors of all failed tasks.
+    pub fn errors(&self) -> Vec<TaskError> {
+        let tasks = self.inner.tasks.lock().unwrap();
+        tasks
+            .iter()
+            .filter_map(|task| match &task.state {
+                TaskState::Failed(err) => Some(err.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the shutdown was requested.
+    pub fn is_shutting_down(&self) -> bool {
+        *self.inner.shutdown.borrow()
+    }
+
+    /// Requests all tasks to stop and waits until they did, for at most `deadline`. Tasks still
+    /// running after the deadline are aborted.
+    ///
+    /// Returns the errors of all tasks that failed, panicked or had to be aborted.
+    pub async fn shutdown(&self, deadline: Duration) -> Result<(), ShutdownError> {
+        debug!("shutting down background tasks");
+        self.inner.shutdown.send_replace(true);
+
+        let stopped = async {
+            while self.inner.tasks.lock().unwrap().iter().any(|task| !task.handle.is_finished()) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        let _ = tokio::time::timeout(deadline, stopped).await;
+
+        {
+            let mut tasks = self.inner.tasks.lock().unwrap();
+            for task in tasks.iter_mut().filter(|task| !task.handle.is_finished()) {
+                task.handle.abort();
+                task.state = TaskState::Failed(TaskError::TimedOut { name: task.name.clone() });
+            }
+        }
+
+        let errors = self.errors();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ShutdownError(errors))
+        }
+    }
+}
+
+/// The successful output of a task spawned with [`TaskManager::spawn_with_shutdown`]
+pub trait Completion {
+    /// Returns `false` if the task stopped because the shutdown was requested before it completed.
+    fn completed(&self) -> bool;
+}
+
+impl Completion for () {
+    fn completed(&self) -> bool {
+        true
+    }
+}
+
+impl Completion for bool {
+    fn completed(&self) -> bool {
+        *self
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks_panic() -> bool {
+        true
+    }
+
+    #[tokio::test]
+    async fn surfaces_failures() {
+        let tasks = TaskManager::new();
+        tasks.spawn("ok", async { Ok::<_, String>(()) });
+        tasks.spawn("err", async { Err("boom".to_string()) });
+        tasks.spawn("panic", async {
+            if tasks_panic() {
+                panic!("oops");
+            }
+            Ok::<_, String>(())
+        });
+        tasks.spawn("forever", futures_util::future::pending::<Result<(), String>>());
+        tasks.spawn_with_shutdown("stuck", |_| {
+            futures_util::future::pending::<Result<(), String>>()
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let states: Vec<_> = tasks.tasks().into_iter().map(|task| task.state).collect();
+        assert_eq!(states[0], TaskState::Completed);
+        assert_eq!(
+            states[1],
+            TaskState::Failed(TaskError::Failed { name: "err".into(), message: "boom".into() })
+        );
+        assert_eq!(
+            states[2],
+            TaskState::Failed(TaskError::Panicked { name: "panic".into(), message: "oops".into() })
+        );
+        assert_eq!(states[3], TaskState::Running);
+        assert_eq!(tasks.errors().len(), 2);
+
+        let err = tasks.shutdown(Duration::from_millis(100)).await.unwrap_err();
+        assert_eq!(err.0.len(), 3);
+        assert_eq!(err.0[2], TaskError::TimedOut { name: "stuck".into() });
+        assert_eq!(tasks.tasks()[3].state, TaskState::Cancelled);
+    }
+}
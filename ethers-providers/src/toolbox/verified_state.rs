@@ -0,0 +1,193 @@
+use crate::Middleware;
+use ethers_core::types::{Address, ProofError, VerifiedAccount, H256, U256};
+use thiserror::Error;
+
+/// Thrown when state returned by a node can not be verified
+#[derive(Error, Debug)]
+pub enum VerificationError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    /// Thrown when the node does not know the trusted block
+    #[error("block {0:?} not found")]
+    BlockNotFound(H256),
+    /// Thrown when the header returned by the node does not hash to the trusted block hash
+    #[error("header hash mismatch: expected {expected:?}, got {actual:?}")]
+    HeaderHashMismatch {
+        /// The trusted block hash
+        expected: H256,
+        /// The hash of the returned header
+        actual: H256,
+    },
+    /// Thrown when the node returned a proof for a different account
+    #[error("proof is for {actual:?}, but {expected:?} was requested")]
+    AddressMismatch {
+        /// The requested address
+        expected: Address,
+        /// The address of the returned proof
+        actual: Address,
+    },
+    /// Thrown when the node did not return the proof of a requested storage slot
+    #[error("storage proof for slot {0:?} is missing")]
+    MissingStorageProof(H256),
+    /// Thrown when the proof is invalid
+    #[error(transparent)]
+    Proof(#[from] ProofError),
+}
+
+/// Returns the balance of `address` at the block with the trusted `block_hash`, without trusting
+/// the node it is fetched from.
+///
+/// The header of the block is fetched and hashed to check its state root, against which the
+/// `eth_getProof` account proof of `address` is then verified.
+///
+/// ```no_run
+/// use ethers_core::types::{Address, H256};
+/// use ethers_providers::{verified_balance, Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(trusted: H256) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+/// let balance = verified_balance(&provider, Address::zero(), trusted).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn verified_balance<M: Middleware>(
+    client: &M,
+    address: Address,
+    block_hash: H256,
+) -> Result<U256, VerificationError<M>> {
+    Ok(verified_account(client, address, vec![], block_hash).await?.balance)
+}
+
+/// Returns the state of `address` and the values of its storage slots `keys` at the block with
+/// the trusted `block_hash`, without trusting the node it is fetched from.
+///
+/// Like [`verified_balance`], but the storage proofs of the `eth_getProof` response are verified
+/// against the proven storage root of the account as well.
+///
+/// ```no_run
+/// use ethers_core::types::{Address, H256};
+/// use ethers_providers::{verified_account, Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(trusted: H256) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+/// let slot = H256::zero();
+/// let account = verified_account(&provider, Address::zero(), vec![slot], trusted).await?;
+/// println!("nonce {}, slot 0 {}", account.nonce, account.storage[&slot]);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn verified_account<M: Middleware>(
+    client: &M,
+    address: Address,
+    keys: Vec<H256>,
+    block_hash: H256,
+) -> Result<VerifiedAccount, VerificationError<M>> {
+    let block = client
+        .get_block(block_hash)
+        .await
+        .map_err(VerificationError::MiddlewareError)?
+        .ok_or(VerificationError::BlockNotFound(block_hash))?;
+    let actual = block.header_hash().ok_or(VerificationError::BlockNotFound(block_hash))?;
+    if actual != block_hash {
+        return Err(VerificationError::HeaderHashMismatch { expected: block_hash, actual })
+    }
+
+    let proof = client
+        .get_proof(address, keys.clone(), Some(block_hash.into()))
+        .await
+        .map_err(VerificationError::MiddlewareError)?;
+    if proof.address != address {
+        return Err(VerificationError::AddressMismatch { expected: address, actual: proof.address })
+    }
+    let account = proof.verify(block.state_root)?;
+    if let Some(key) = keys.into_iter().find(|key| !account.storage.contains_key(key)) {
+        return Err(VerificationError::MissingStorageProof(key))
+    }
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Provider;
+    use ethers_core::{
+        types::{Block, Bloom, Bytes, EIP1186ProofResponse, H64},
+        utils::{keccak256, rlp::RlpStream},
+    };
+
+    /// Returns a block whose state trie only contains the account of `proof`
+    fn single_account_state(proof: &mut EIP1186ProofResponse) -> Block<H256> {
+        let mut account = RlpStream::new_list(4);
+        account
+            .append(&proof.nonce)
+            .append(&proof.balance)
+            .append(&proof.storage_hash)
+            .append(&proof.code_hash);
+        let mut path = vec![0x20];
+        path.extend_from_slice(&keccak256(proof.address));
+        let mut leaf = RlpStream::new_list(2);
+        leaf.append(&path).append(&account.out().to_vec());
+        let leaf = Bytes::from(leaf.out().to_vec());
+
+        let state_root = H256(keccak256(&leaf));
+        proof.account_proof = vec![leaf];
+        Block {
+            author: Some(Address::zero()),
+            state_root,
+            logs_bloom: Some(Bloom::zero()),
+            number: Some(1u64.into()),
+            mix_hash: Some(H256::zero()),
+            nonce: Some(H64::zero()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn verifies_balance() {
+        let address = Address::repeat_byte(0x11);
+        let mut proof = EIP1186ProofResponse {
+            address,
+            balance: 100u64.into(),
+            code_hash: H256(keccak256(b"")),
+            storage_hash: H256(keccak256(ethers_core::utils::rlp::NULL_RLP)),
+            ..Default::default()
+        };
+        let block = single_account_state(&mut proof);
+        let trusted = block.header_hash().unwrap();
+
+        let (provider, mock) = Provider::mocked();
+        mock.push(proof.clone()).unwrap();
+        mock.push(block.clone()).unwrap();
+        assert_eq!(verified_balance(&provider, address, trusted).await.unwrap(), 100u64.into());
+
+        // a node lying about the balance
+        let mut forged = proof.clone();
+        forged.balance = 1000u64.into();
+        mock.push(forged).unwrap();
+        mock.push(block.clone()).unwrap();
+        assert!(matches!(
+            verified_balance(&provider, address, trusted).await,
+            Err(VerificationError::Proof(ProofError::ValueMismatch))
+        ));
+
+        // a node omitting a requested storage slot
+        mock.push(proof.clone()).unwrap();
+        mock.push(block.clone()).unwrap();
+        assert!(matches!(
+            verified_account(&provider, address, vec![H256::zero()], trusted).await,
+            Err(VerificationError::MissingStorageProof(_))
+        ));
+
+        // a node lying about the state root
+        let mut forged = block;
+        forged.state_root = H256::repeat_byte(0x01);
+        mock.push(forged).unwrap();
+        assert!(matches!(
+            verified_balance(&provider, address, trusted).await,
+            Err(VerificationError::HeaderHashMismatch { .. })
+        ));
+    }
+}
@@ -3,10 +3,10 @@
 use ethers_core::{
     k256::ecdsa::{Error as K256Error, Signature as KSig, VerifyingKey},
     types::{
-        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
         Address, Signature as EthSig, H256,
     },
-    utils::hash_message,
+    utils::{hash_intended_validator, hash_message},
 };
 use rusoto_core::RusotoError;
 use rusoto_kms::{
@@ -274,6 +274,38 @@ impl super::Signer for AwsSigner {
     }
 }
 
+#[async_trait::async_trait]
+impl super::AuthorizationSigner for AwsSigner {
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<EthSig, Self::Error> {
+        let digest = authorization.signature_hash();
+        let sig = self.sign_digest(digest.into()).await?;
+        let mut sig =
+            utils::sig_from_digest_bytes_trial_recovery(&sig, digest.into(), &self.pubkey);
+        sig.v += 27;
+        Ok(sig)
+    }
+}
+
+#[async_trait::async_trait]
+impl super::IntendedValidatorSigner for AwsSigner {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<EthSig, Self::Error> {
+        let digest = hash_intended_validator(validator, data);
+        let sig = self.sign_digest(digest.into()).await?;
+        let mut sig =
+            utils::sig_from_digest_bytes_trial_recovery(&sig, digest.into(), &self.pubkey);
+        // validators recover with `ecrecover`, which expects 27 or 28
+        sig.v += 27;
+        Ok(sig)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
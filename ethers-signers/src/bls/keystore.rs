@@ -0,0 +1,267 @@
+//! EIP-2335 keystores of BLS12-381 secret keys
+use super::{BlsError, BlsSigner};
+use crate::{wallet::keystore::uuid_v4, KeystoreKdf};
+use aes::cipher::{KeyIvInit, StreamCipher};
+use ethers_core::rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroize;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+const DERIVED_KEY_LENGTH: usize = 32;
+const IV_LENGTH: usize = 16;
+const SALT_LENGTH: usize = 32;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeystoreJson {
+    crypto: CryptoJson,
+    #[serde(default)]
+    description: String,
+    pubkey: String,
+    path: String,
+    uuid: String,
+    version: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CryptoJson {
+    kdf: ModuleJson<KdfParamsJson>,
+    checksum: ModuleJson<serde_json::Value>,
+    cipher: ModuleJson<CipherParamsJson>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModuleJson<P> {
+    function: String,
+    params: P,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum KdfParamsJson {
+    Scrypt { dklen: usize, n: u64, r: u32, p: u32, salt: String },
+    Pbkdf2 { dklen: usize, c: u32, prf: String, salt: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CipherParamsJson {
+    iv: String,
+}
+
+impl ModuleJson<KdfParamsJson> {
+    fn new(kdf: KeystoreKdf, salt: &[u8]) -> Result<Self, BlsError> {
+        let (dklen, salt) = (DERIVED_KEY_LENGTH, hex::encode(salt));
+        let (function, params) = match kdf {
+            KeystoreKdf::Scrypt { log_n, r, p } => {
+                ("scrypt", KdfParamsJson::Scrypt { dklen, n: 1 << log_n, r, p, salt })
+            }
+            KeystoreKdf::Pbkdf2 { c } => {
+                ("pbkdf2", KdfParamsJson::Pbkdf2 { dklen, c, prf: "hmac-sha256".to_string(), salt })
+            }
+            KeystoreKdf::Argon2id { .. } => {
+                return Err(BlsError::InvalidKeystore("EIP-2335 does not support argon2id".into()))
+            }
+        };
+        Ok(Self { function: function.to_string(), params, message: String::new() })
+    }
+
+    fn kdf(&self) -> Result<(KeystoreKdf, &str), BlsError> {
+        let (kdf, dklen, salt) = match (self.function.as_str(), &self.params) {
+            ("scrypt", KdfParamsJson::Scrypt { dklen, n, r, p, salt }) => {
+                if !n.is_power_of_two() || *n < 2 {
+                    return Err(BlsError::InvalidKeystore(format!("invalid scrypt cost {n}")))
+                }
+                let log_n = n.trailing_zeros() as u8;
+                (KeystoreKdf::Scrypt { log_n, r: *r, p: *p }, dklen, salt)
+            }
+            ("pbkdf2", KdfParamsJson::Pbkdf2 { dklen, c, prf, salt }) => {
+                if prf != "hmac-sha256" {
+                    return Err(BlsError::InvalidKeystore(format!("unsupported prf {prf}")))
+                }
+                (KeystoreKdf::Pbkdf2 { c: *c }, dklen, salt)
+            }
+            (function, _) => {
+                return Err(BlsError::InvalidKeystore(format!("unsupported kdf {function}")))
+            }
+        };
+        if *dklen != DERIVED_KEY_LENGTH {
+            return Err(BlsError::InvalidKeystore(format!("unsupported dklen {dklen}")))
+        }
+        Ok((kdf, salt))
+    }
+}
+
+/// Normalizes the password to NFKD and strips the control codes, as EIP-2335 requires.
+fn process_password(password: &str) -> Vec<u8> {
+    let is_control = |c: &char| matches!(*c as u32, 0x00..=0x1f | 0x7f..=0x9f);
+    password.nfkd().filter(|c| !is_control(c)).collect::<String>().into_bytes()
+}
+
+fn checksum(key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    Sha256::new().chain_update(&key[16..32]).chain_update(ciphertext).finalize().into()
+}
+
+impl BlsSigner {
+    /// Decrypts an [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335) keystore, as created by
+    /// the staking deposit CLI and validator clients.
+    pub fn decrypt_keystore(json: &str, password: &str) -> Result<Self, BlsError> {
+        let keystore: KeystoreJson = serde_json::from_str(json)?;
+        if keystore.version != 4 {
+            return Err(BlsError::InvalidKeystore(format!(
+                "unsupported version {}",
+                keystore.version
+            )))
+        }
+        let crypto = keystore.crypto;
+        if crypto.checksum.function != "sha256" || crypto.cipher.function != "aes-128-ctr" {
+            return Err(BlsError::InvalidKeystore(format!(
+                "unsupported checksum {} or cipher {}",
+                crypto.checksum.function, crypto.cipher.function
+            )))
+        }
+
+        let (kdf, salt) = crypto.kdf.kdf()?;
+        let mut key = kdf.derive_key(&process_password(password), &hex::decode(salt)?)?;
+
+        let mut secret = hex::decode(crypto.cipher.message)?;
+        let valid = hex::decode(crypto.checksum.message)? == checksum(&key, &secret);
+        let iv = hex::decode(crypto.cipher.params.iv)?;
+        if valid && iv.len() == IV_LENGTH {
+            Aes128Ctr::new(key[..16].into(), iv.as_slice().into()).apply_keystream(&mut secret);
+        }
+        key.zeroize();
+        if !valid {
+            return Err(BlsError::ChecksumMismatch)
+        }
+        if iv.len() != IV_LENGTH {
+            return Err(BlsError::InvalidKeystore("invalid iv".to_string()))
+        }
+
+        let signer = Self::from_bytes(&secret);
+        secret.zeroize();
+        let signer = signer?;
+        if hex::decode(keystore.pubkey.trim_start_matches("0x"))? != signer.public_key() {
+            return Err(BlsError::PublicKeyMismatch)
+        }
+        Ok(signer)
+    }
+
+    /// Encrypts the secret key with `password` into an
+    /// [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335) keystore, deriving the encryption key
+    /// with `kdf`. `path` is the EIP-2334 derivation path of the key, e.g.
+    /// `m/12381/3600/0/0/0` for the signing key of the first validator, or empty if unknown.
+    ///
+    /// Only [`KeystoreKdf::Scrypt`] and [`KeystoreKdf::Pbkdf2`] are supported.
+    pub fn encrypt_keystore<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        password: &str,
+        kdf: KeystoreKdf,
+        path: &str,
+    ) -> Result<String, BlsError> {
+        let salt: [u8; SALT_LENGTH] = rng.gen();
+        let iv: [u8; IV_LENGTH] = rng.gen();
+        let kdf_module = ModuleJson::new(kdf, &salt)?;
+        let mut key = kdf.derive_key(&process_password(password), &salt)?;
+
+        let mut ciphertext = self.to_bytes().to_vec();
+        Aes128Ctr::new(key[..16].into(), iv[..].into()).apply_keystream(&mut ciphertext);
+        let checksum = checksum(&key, &ciphertext);
+        key.zeroize();
+
+        let keystore = KeystoreJson {
+            crypto: CryptoJson {
+                kdf: kdf_module,
+                checksum: ModuleJson {
+                    function: "sha256".to_string(),
+                    params: serde_json::json!({}),
+                    message: hex::encode(checksum),
+                },
+                cipher: ModuleJson {
+                    function: "aes-128-ctr".to_string(),
+                    params: CipherParamsJson { iv: hex::encode(iv) },
+                    message: hex::encode(ciphertext),
+                },
+            },
+            description: String::new(),
+            pubkey: hex::encode(self.public_key()),
+            path: path.to_string(),
+            uuid: uuid_v4(rng),
+            version: 4,
+        };
+        Ok(serde_json::to_string(&keystore)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::rand::thread_rng;
+
+    // the PBKDF2 test vector of EIP-2335
+    const KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+            }
+        },
+        "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }"#;
+
+    const PASSWORD: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+
+    #[test]
+    fn decrypts_test_vector() {
+        let signer = BlsSigner::decrypt_keystore(KEYSTORE, PASSWORD).unwrap();
+        assert_eq!(
+            hex::encode(signer.to_bytes()),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert!(matches!(
+            BlsSigner::decrypt_keystore(KEYSTORE, "testpassword").unwrap_err(),
+            BlsError::ChecksumMismatch
+        ));
+    }
+
+    #[test]
+    fn roundtrips() {
+        let signer = BlsSigner::random(&mut thread_rng());
+        for kdf in [KeystoreKdf::Scrypt { log_n: 10, r: 8, p: 1 }, KeystoreKdf::Pbkdf2 { c: 1000 }]
+        {
+            let json =
+                signer.encrypt_keystore(&mut thread_rng(), "pass\u{7f}word", kdf, "").unwrap();
+            // control codes are stripped from the password
+            let decrypted = BlsSigner::decrypt_keystore(&json, "password").unwrap();
+            assert_eq!(decrypted.to_bytes(), signer.to_bytes());
+        }
+        assert!(signer
+            .encrypt_keystore(&mut thread_rng(), "password", KeystoreKdf::ARGON2ID, "")
+            .is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! BLS12-381 signatures of the Ethereum consensus layer
+mod keystore;
+
+use blst::{
+    min_pk::{PublicKey, SecretKey, Signature},
+    BLST_ERROR,
+};
+use ethers_core::{
+    rand::{CryptoRng, Rng},
+    types::H256,
+};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+/// The domain separation tag of the proof of possession scheme used by the consensus layer
+pub const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// The domain type of block proposals
+pub const DOMAIN_BEACON_PROPOSER: [u8; 4] = [0, 0, 0, 0];
+/// The domain type of attestations
+pub const DOMAIN_BEACON_ATTESTER: [u8; 4] = [1, 0, 0, 0];
+/// The domain type of RANDAO reveals
+pub const DOMAIN_RANDAO: [u8; 4] = [2, 0, 0, 0];
+/// The domain type of deposits
+pub const DOMAIN_DEPOSIT: [u8; 4] = [3, 0, 0, 0];
+/// The domain type of voluntary exits
+pub const DOMAIN_VOLUNTARY_EXIT: [u8; 4] = [4, 0, 0, 0];
+/// The domain type of aggregation selection proofs
+pub const DOMAIN_SELECTION_PROOF: [u8; 4] = [5, 0, 0, 0];
+/// The domain type of aggregates
+pub const DOMAIN_AGGREGATE_AND_PROOF: [u8; 4] = [6, 0, 0, 0];
+/// The domain type of builder API registrations
+pub const DOMAIN_APPLICATION_BUILDER: [u8; 4] = [0, 0, 0, 1];
+
+/// Error thrown by the [`BlsSigner`]
+#[derive(Debug, Error)]
+pub enum BlsError {
+    /// Error of the BLS library, e.g. for an invalid key or signature encoding
+    #[error("bls error: {0:?}")]
+    Bls(BLST_ERROR),
+    /// The keystore could not be parsed or uses unsupported parameters
+    #[error("invalid keystore: {0}")]
+    InvalidKeystore(String),
+    /// The keystore checksum does not match, usually because the password is wrong
+    #[error("keystore checksum mismatch")]
+    ChecksumMismatch,
+    /// The public key of the keystore does not belong to its secret key
+    #[error("keystore public key does not match its secret key")]
+    PublicKeyMismatch,
+    /// Error deriving the keystore encryption key
+    #[error(transparent)]
+    Wallet(#[from] crate::WalletError),
+    /// Error decoding hex
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    /// Error (de)serializing the keystore
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+impl From<BLST_ERROR> for BlsError {
+    fn from(err: BLST_ERROR) -> Self {
+        BlsError::Bls(err)
+    }
+}
+
+/// Signs with a BLS12-381 secret key, as validators of the consensus layer do.
+///
+/// Signatures use the proof of possession scheme with public keys in G1 (48 bytes) and signatures
+/// in G2 (96 bytes). Consensus objects are signed with [`BlsSigner::sign_root`], which mixes the
+/// [signing domain](compute_domain) into their SSZ root.
+///
+/// ```
+/// use ethers_core::{rand::thread_rng, types::H256};
+/// use ethers_signers::bls::{compute_domain, verify, BlsSigner, DOMAIN_DEPOSIT};
+///
+/// let signer = BlsSigner::random(&mut thread_rng());
+/// let domain = compute_domain(DOMAIN_DEPOSIT, [0; 4], H256::zero());
+/// let root = H256::repeat_byte(1);
+///
+/// let signature = signer.sign_root(root, domain);
+/// let message = ethers_signers::bls::compute_signing_root(root, domain);
+/// assert!(verify(&signer.public_key(), message.as_bytes(), &signature));
+/// ```
+#[derive(Clone)]
+pub struct BlsSigner {
+    secret: SecretKey,
+    public: PublicKey,
+}
+
+impl BlsSigner {
+    /// Creates a signer with a random secret key.
+    pub fn random<R: Rng + CryptoRng>(rng: &mut R) -> Self {
+        let mut ikm: [u8; 32] = rng.gen();
+        let signer = Self::from_ikm(&ikm).expect("32 bytes of key material are enough");
+        ikm.zeroize();
+        signer
+    }
+
+    /// Derives the secret key from at least 32 bytes of input key material, following `KeyGen` of
+    /// the BLS signature draft and EIP-2333.
+    pub fn from_ikm(ikm: &[u8]) -> Result<Self, BlsError> {
+        Ok(Self::from_secret(SecretKey::key_gen(ikm, &[])?))
+    }
+
+    /// Creates a signer from a big-endian encoded 32 byte secret key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        Ok(Self::from_secret(SecretKey::from_bytes(bytes)?))
+    }
+
+    fn from_secret(secret: SecretKey) -> Self {
+        let public = secret.sk_to_pk();
+        Self { secret, public }
+    }
+
+    /// Returns the big-endian encoded secret key.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.secret.to_bytes()
+    }
+
+    /// Returns the compressed public key.
+    pub fn public_key(&self) -> [u8; 48] {
+        self.public.to_bytes()
+    }
+
+    /// Signs `message` and returns the compressed signature.
+    pub fn sign(&self, message: &[u8]) -> [u8; 96] {
+        self.secret.sign(message, BLS_DST, &[]).to_bytes()
+    }
+
+    /// Signs the SSZ `object_root` of a consensus object in `domain`, see
+    /// [`compute_signing_root`].
+    pub fn sign_root(&self, object_root: H256, domain: H256) -> [u8; 96] {
+        self.sign(compute_signing_root(object_root, domain).as_bytes())
+    }
+}
+
+// do not log the secret key
+impl fmt::Debug for BlsSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlsSigner").field("public_key", &hex::encode(self.public_key())).finish()
+    }
+}
+
+/// Returns `true` if `signature` is a valid signature of `message` by `public_key`.
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) =
+        (PublicKey::key_validate(public_key), Signature::from_bytes(signature))
+    else {
+        return false
+    };
+    signature.verify(true, message, BLS_DST, &[], &public_key, false) == BLST_ERROR::BLST_SUCCESS
+}
+
+/// Returns the signing domain of `domain_type` for the fork with `fork_version` of the chain with
+/// `genesis_validators_root`, following `compute_domain` of the consensus specs.
+///
+/// Deposits are signed in the domain of the genesis fork version and a zero
+/// `genesis_validators_root`, so that they are valid before the chain exists.
+pub fn compute_domain(
+    domain_type: [u8; 4],
+    fork_version: [u8; 4],
+    genesis_validators_root: H256,
+) -> H256 {
+    // hash_tree_root(ForkData { current_version, genesis_validators_root })
+    let mut version = [0u8; 32];
+    version[..4].copy_from_slice(&fork_version);
+    let fork_data_root = sha256_pair(&version, genesis_validators_root.as_bytes());
+
+    let mut domain = [0u8; 32];
+    domain[..4].copy_from_slice(&domain_type);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    H256(domain)
+}
+
+/// Returns the root that is signed for the SSZ `object_root` in `domain`, following
+/// `compute_signing_root` of the consensus specs.
+pub fn compute_signing_root(object_root: H256, domain: H256) -> H256 {
+    // hash_tree_root(SigningData { object_root, domain })
+    H256(sha256_pair(object_root.as_bytes(), domain.as_bytes()))
+}
+
+fn sha256_pair(left: &[u8], right: &[u8]) -> [u8; 32] {
+    Sha256::new().chain_update(left).chain_update(right).finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::rand::thread_rng;
+
+    #[test]
+    fn computes_deposit_domain() {
+        let domain = compute_domain(DOMAIN_DEPOSIT, [0; 4], H256::zero());
+        assert_eq!(
+            domain,
+            "0x03000000f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn signs_and_verifies() {
+        let signer = BlsSigner::random(&mut thread_rng());
+        let signature = signer.sign(b"hello");
+        assert!(verify(&signer.public_key(), b"hello", &signature));
+        assert!(!verify(&signer.public_key(), b"world", &signature));
+
+        let restored = BlsSigner::from_bytes(&signer.to_bytes()).unwrap();
+        assert_eq!(restored.public_key(), signer.public_key());
+
+        let domain = compute_domain(DOMAIN_VOLUNTARY_EXIT, [1, 0, 0, 0], H256::repeat_byte(2));
+        let root = H256::repeat_byte(3);
+        let signature = signer.sign_root(root, domain);
+        assert!(verify(
+            &signer.public_key(),
+            compute_signing_root(root, domain).as_bytes(),
+            &signature
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+//! Signers configured by environment variables
+use crate::{
+    coins_bip39::English, AuthorizationSigner, IntendedValidatorSigner, LocalWallet,
+    MnemonicBuilder, Signer, WalletError,
+};
+use async_trait::async_trait;
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
+    Address, Signature,
+};
+use std::{env, str::FromStr};
+use thiserror::Error;
+
+#[cfg(feature = "ledger")]
+use crate::{HDPath, Ledger, LedgerError};
+
+/// A hex encoded private key
+pub const PRIVATE_KEY: &str = "PRIVATE_KEY";
+/// A BIP-39 mnemonic phrase
+pub const MNEMONIC: &str = "MNEMONIC";
+/// The account index of the `MNEMONIC`, `0` by default
+pub const MNEMONIC_INDEX: &str = "MNEMONIC_INDEX";
+/// The optional BIP-39 password of the `MNEMONIC`
+pub const MNEMONIC_PASSWORD: &str = "MNEMONIC_PASSWORD";
+/// The path of an encrypted JSON keystore
+pub const KEYSTORE: &str = "KEYSTORE";
+/// The password of the `KEYSTORE`
+pub const KEYSTORE_PASSWORD: &str = "KEYSTORE_PASSWORD";
+/// Set to `1` or `true` to sign with a Ledger
+pub const LEDGER: &str = "LEDGER";
+/// The Ledger Live account index of the `LEDGER`, `0` by default
+pub const LEDGER_INDEX: &str = "LEDGER_INDEX";
+/// The chain id to sign for, `1` by default
+pub const CHAIN_ID: &str = "CHAIN_ID";
+
+/// A signer assembled from environment variables by [`EnvSigner::from_env`]
+#[derive(Debug)]
+pub enum EnvSigner {
+    /// A private key from `PRIVATE_KEY`, `MNEMONIC` or `KEYSTORE`
+    Local(LocalWallet),
+    /// A Ledger selected with `LEDGER`
+    #[cfg(feature = "ledger")]
+    Ledger(Ledger),
+}
+
+/// Error thrown by the [`EnvSigner`]
+#[derive(Debug, Error)]
+pub enum EnvSignerError {
+    /// Thrown when none of the signer variables is set
+    #[error("no signer configured, set one of {PRIVATE_KEY}, {MNEMONIC}, {KEYSTORE} or {LEDGER}")]
+    NotSet,
+    /// Thrown when several signer variables are set
+    #[error("several signers configured ({0}), set only one of them")]
+    Ambiguous(String),
+    /// Thrown when a variable that another one requires is not set
+    #[error("environment variable {0} is not set")]
+    Missing(&'static str),
+    /// Thrown when a variable can not be parsed
+    #[error("invalid environment variable {name}: {reason}")]
+    Invalid {
+        /// The name of the variable
+        name: &'static str,
+        /// Why the value is invalid
+        reason: String,
+    },
+    /// Thrown by the local wallet
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+    /// Thrown by the Ledger
+    #[cfg(feature = "ledger")]
+    #[error(transparent)]
+    Ledger(#[from] LedgerError),
+}
+
+fn var(lookup: &impl Fn(&str) -> Option<String>, name: &'static str) -> Option<String> {
+    lookup(name).filter(|value| !value.is_empty())
+}
+
+fn parse<T: FromStr>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<T>, EnvSignerError>
+where
+    T::Err: std::fmt::Display,
+{
+    var(lookup, name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|err: T::Err| EnvSignerError::Invalid { name, reason: err.to_string() })
+        })
+        .transpose()
+}
+
+impl EnvSigner {
+    /// Assembles the signer configured by exactly one of the environment variables
+    /// [`PRIVATE_KEY`], [`MNEMONIC`] (with [`MNEMONIC_INDEX`] and [`MNEMONIC_PASSWORD`]),
+    /// [`KEYSTORE`] (with [`KEYSTORE_PASSWORD`]) or [`LEDGER`] (with [`LEDGER_INDEX`]), signing
+    /// for [`CHAIN_ID`].
+    ///
+    /// ```no_run
+    /// use ethers_signers::{EnvSigner, Signer};
+    ///
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// // MNEMONIC="test test test test test test test test test test test junk" MNEMONIC_INDEX=1
+    /// let signer = EnvSigner::from_env().await?;
+    /// println!("signing as {:?}", signer.address());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_env() -> Result<Self, EnvSignerError> {
+        Self::from_vars(|name| env::var(name).ok()).await
+    }
+
+    /// Assembles the signer like [`Self::from_env`], reading the variables with `lookup` instead
+    /// of from the process environment, e.g. from a parsed `.env` file.
+    pub async fn from_vars<F>(lookup: F) -> Result<Self, EnvSignerError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let lookup = &lookup;
+        let ledger = match var(lookup, LEDGER).as_deref() {
+            None | Some("0") | Some("false") => false,
+            Some("1") | Some("true") => true,
+            Some(value) => {
+                return Err(EnvSignerError::Invalid {
+                    name: LEDGER,
+                    reason: format!("expected 1 or 0, got {value}"),
+                })
+            }
+        };
+        let configured: Vec<_> = [PRIVATE_KEY, MNEMONIC, KEYSTORE]
+            .into_iter()
+            .filter(|name| var(lookup, name).is_some())
+            .chain(ledger.then_some(LEDGER))
+            .collect();
+        if configured.len() > 1 {
+            return Err(EnvSignerError::Ambiguous(configured.join(", ")))
+        }
+        let chain_id = parse::<u64>(lookup, CHAIN_ID)?.unwrap_or(1);
+
+        let wallet = if let Some(key) = var(lookup, PRIVATE_KEY) {
+            key.trim().parse::<LocalWallet>()?
+        } else if let Some(phrase) = var(lookup, MNEMONIC) {
+            let index = parse::<u32>(lookup, MNEMONIC_INDEX)?.unwrap_or_default();
+            let mut builder = MnemonicBuilder::<English>::default().phrase(phrase.trim());
+            if let Some(password) = var(lookup, MNEMONIC_PASSWORD) {
+                builder = builder.password(&password);
+            }
+            builder.index(index)?.build()?
+        } else if let Some(path) = var(lookup, KEYSTORE) {
+            let password = var(lookup, KEYSTORE_PASSWORD)
+                .ok_or(EnvSignerError::Missing(KEYSTORE_PASSWORD))?;
+            LocalWallet::decrypt_keystore(path, password)?
+        } else if ledger {
+            let index = parse::<usize>(lookup, LEDGER_INDEX)?.unwrap_or_default();
+            return Self::ledger(index, chain_id).await
+        } else {
+            return Err(EnvSignerError::NotSet)
+        };
+        Ok(Self::Local(wallet.with_chain_id(chain_id)))
+    }
+
+    #[cfg(feature = "ledger")]
+    async fn ledger(index: usize, chain_id: u64) -> Result<Self, EnvSignerError> {
+        Ok(Self::Ledger(Ledger::new(HDPath::LedgerLive(index), chain_id).await?))
+    }
+
+    #[cfg(not(feature = "ledger"))]
+    async fn ledger(_index: usize, _chain_id: u64) -> Result<Self, EnvSignerError> {
+        Err(EnvSignerError::Invalid {
+            name: LEDGER,
+            reason: "ethers-signers was built without the `ledger` feature".to_string(),
+        })
+    }
+}
+
+macro_rules! delegate {
+    ($self:ident, $signer:ident => $call:expr) => {
+        match $self {
+            EnvSigner::Local($signer) => $call.map_err(EnvSignerError::from),
+            #[cfg(feature = "ledger")]
+            EnvSigner::Ledger($signer) => $call.map_err(EnvSignerError::from),
+        }
+    };
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Signer for EnvSigner {
+    type Error = EnvSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        delegate!(self, signer => signer.sign_message(message.as_ref()).await)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        delegate!(self, signer => signer.sign_transaction(tx).await)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        delegate!(self, signer => signer.sign_typed_data(payload).await)
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            EnvSigner::Local(signer) => signer.address(),
+            #[cfg(feature = "ledger")]
+            EnvSigner::Ledger(signer) => signer.address(),
+        }
+    }
+
+    fn chain_id(&self) -> u64 {
+        match self {
+            EnvSigner::Local(signer) => signer.chain_id(),
+            #[cfg(feature = "ledger")]
+            EnvSigner::Ledger(signer) => signer.chain_id(),
+        }
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            EnvSigner::Local(signer) => EnvSigner::Local(signer.with_chain_id(chain_id)),
+            #[cfg(feature = "ledger")]
+            EnvSigner::Ledger(signer) => EnvSigner::Ledger(signer.with_chain_id(chain_id)),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthorizationSigner for EnvSigner {
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        delegate!(self, signer => signer.sign_authorization(authorization).await)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IntendedValidatorSigner for EnvSigner {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<Signature, Self::Error> {
+        delegate!(self, signer => signer.sign_intended_validator(validator, data.as_ref()).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    async fn from_vars(vars: &[(&str, &str)]) -> Result<EnvSigner, EnvSignerError> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        EnvSigner::from_vars(|name| vars.get(name).map(|value| value.to_string())).await
+    }
+
+    #[tokio::test]
+    async fn assembles_signer_from_vars() {
+        const PHRASE: &str = "test test test test test test test test test test test junk";
+        const KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        let address = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".parse::<Address>().unwrap();
+
+        assert!(matches!(from_vars(&[]).await.unwrap_err(), EnvSignerError::NotSet));
+        assert!(matches!(from_vars(&[(MNEMONIC, "")]).await.unwrap_err(), EnvSignerError::NotSet));
+
+        let signer =
+            from_vars(&[(MNEMONIC, PHRASE), (MNEMONIC_INDEX, "1"), (CHAIN_ID, "5")]).await.unwrap();
+        assert_eq!(signer.address(), address);
+        assert_eq!(signer.chain_id(), 5);
+
+        let err = from_vars(&[(MNEMONIC, PHRASE), (PRIVATE_KEY, KEY)]).await.unwrap_err();
+        assert!(matches!(err, EnvSignerError::Ambiguous(_)));
+
+        let signer = from_vars(&[(PRIVATE_KEY, KEY)]).await.unwrap();
+        assert_eq!(signer.address(), address);
+        assert_eq!(signer.chain_id(), 1);
+
+        let err = from_vars(&[(PRIVATE_KEY, KEY), (CHAIN_ID, "goerli")]).await.unwrap_err();
+        assert!(matches!(err, EnvSignerError::Invalid { name: CHAIN_ID, .. }));
+
+        let err = from_vars(&[(KEYSTORE, "keystore.json")]).await.unwrap_err();
+        assert!(matches!(err, EnvSignerError::Missing(KEYSTORE_PASSWORD)));
+
+        let err = from_vars(&[(LEDGER, "yes")]).await.unwrap_err();
+        assert!(matches!(err, EnvSignerError::Invalid { name: LEDGER, .. }));
+    }
+}
@@ -0,0 +1,454 @@
+//! Google Cloud KMS-based Signer
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ethers_core::{
+    k256::ecdsa::{Error as K256Error, Signature as KSig, VerifyingKey},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
+        Address, Signature as EthSig, H256, U256,
+    },
+    utils::{hash_intended_validator, hash_message, keccak256},
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, sync::Arc};
+use tracing::{debug, instrument, trace};
+
+/// The Cloud KMS API endpoint.
+pub const GCP_KMS_URL: &str = "https://cloudkms.googleapis.com/v1";
+
+/// The only Cloud KMS algorithm that produces Ethereum compatible signatures.
+const SECP256K1_ALGORITHM: &str = "EC_SIGN_SECP256K1_SHA256";
+
+/// A fully qualified Cloud KMS key version.
+///
+/// Signing requests are always made against a single, pinned key version, so rotating the primary
+/// version of the key does not change the signer's address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyVersion {
+    /// The project id
+    pub project: String,
+    /// The location of the key ring, e.g. `global` or `europe-west1`
+    pub location: String,
+    /// The key ring name
+    pub key_ring: String,
+    /// The key name
+    pub key: String,
+    /// The key version, e.g. `1`
+    pub version: String,
+}
+
+impl KeyVersion {
+    /// Creates a new key version specifier.
+    pub fn new(
+        project: impl Into<String>,
+        location: impl Into<String>,
+        key_ring: impl Into<String>,
+        key: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        Self {
+            project: project.into(),
+            location: location.into(),
+            key_ring: key_ring.into(),
+            key: key.into(),
+            version: version.into(),
+        }
+    }
+}
+
+impl fmt::Display for KeyVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "projects/{}/locations/{}/keyRings/{}/cryptoKeys/{}/cryptoKeyVersions/{}",
+            self.project, self.location, self.key_ring, self.key, self.version
+        )
+    }
+}
+
+impl FromStr for KeyVersion {
+    type Err = GcpKmsSignerError;
+
+    /// Parses a resource name of the form
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.trim_matches('/').split('/').collect();
+        match parts.as_slice() {
+            ["projects", project, "locations", location, "keyRings", key_ring, "cryptoKeys", key, "cryptoKeyVersions", version] => {
+                Ok(Self::new(*project, *location, *key_ring, *key, *version))
+            }
+            _ => Err(GcpKmsSignerError::Other(format!("invalid key version name: {s}"))),
+        }
+    }
+}
+
+/// Provides OAuth2 access tokens for the Cloud KMS API.
+///
+/// This is called before every request, so implementations are expected to cache tokens until
+/// they expire. A plain `String` can be used as a static token.
+#[async_trait::async_trait]
+pub trait AccessTokenProvider: fmt::Debug + Send + Sync {
+    /// Returns a valid access token with the `cloudkms` scope.
+    async fn access_token(&self) -> Result<String, GcpKmsSignerError>;
+}
+
+#[async_trait::async_trait]
+impl AccessTokenProvider for String {
+    async fn access_token(&self) -> Result<String, GcpKmsSignerError> {
+        Ok(self.clone())
+    }
+}
+
+/// An ethers Signer that uses keys held in Google Cloud KMS.
+///
+/// The key must be an asymmetric signing key with the `EC_SIGN_SECP256K1_SHA256` algorithm.
+/// Cloud KMS does not return the recovery id, so it is computed by trial recovery against the
+/// public key, which is retrieved on instantiation of the signer.
+///
+/// ```no_run
+/// use ethers_signers::{GcpKmsSigner, KeyVersion, Signer};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let key = KeyVersion::new("my-project", "global", "my-ring", "my-key", "1");
+/// let token = std::env::var("GCP_ACCESS_TOKEN")?;
+/// let chain_id = 1;
+///
+/// let signer = GcpKmsSigner::new(reqwest::Client::new(), key, token, chain_id).await?;
+/// let sig = signer.sign_message("hello world").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GcpKmsSigner {
+    client: reqwest::Client,
+    token: Arc<dyn AccessTokenProvider>,
+    key: KeyVersion,
+    chain_id: u64,
+    pubkey: VerifyingKey,
+    address: Address,
+}
+
+impl fmt::Debug for GcpKmsSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GcpKmsSigner")
+            .field("key", &self.key.to_string())
+            .field("chain_id", &self.chain_id)
+            .field("pubkey", &hex::encode(self.pubkey.to_sec1_bytes()))
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl fmt::Display for GcpKmsSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "GcpKmsSigner {{ address: {}, chain_id: {}, key: {} }}",
+            self.address, self.chain_id, self.key
+        )
+    }
+}
+
+/// Errors produced by the GcpKmsSigner
+#[derive(thiserror::Error, Debug)]
+pub enum GcpKmsSignerError {
+    /// Error when sending the request or decoding the response
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// Error returned by the Cloud KMS API
+    #[error("cloud kms request failed with status {status}: {body}")]
+    Api { status: reqwest::StatusCode, body: String },
+    #[error("unsupported key algorithm {0}, expected {SECP256K1_ALGORITHM}")]
+    UnsupportedAlgorithm(String),
+    #[error("{0}")]
+    K256(#[from] K256Error),
+    #[error("{0}")]
+    Spki(spki::Error),
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+    #[error("{0}")]
+    Other(String),
+    /// Error type from Eip712Error message
+    #[error("error encoding eip712 struct: {0:?}")]
+    Eip712Error(String),
+}
+
+impl From<String> for GcpKmsSignerError {
+    fn from(s: String) -> Self {
+        Self::Other(s)
+    }
+}
+
+impl From<spki::Error> for GcpKmsSignerError {
+    fn from(e: spki::Error) -> Self {
+        Self::Spki(e)
+    }
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    pem: String,
+    algorithm: String,
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    digest: Digest,
+}
+
+#[derive(Serialize)]
+struct Digest {
+    sha256: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+impl GcpKmsSigner {
+    /// Instantiate a new signer for the pinned key version.
+    ///
+    /// This function retrieves the public key from Cloud KMS and calculates the Ethereum address.
+    /// It is therefore `async`.
+    #[instrument(err, skip(client, key, token, chain_id), fields(key = %key))]
+    pub async fn new(
+        client: reqwest::Client,
+        key: KeyVersion,
+        token: impl AccessTokenProvider + 'static,
+        chain_id: u64,
+    ) -> Result<GcpKmsSigner, GcpKmsSignerError> {
+        let token: Arc<dyn AccessTokenProvider> = Arc::new(token);
+        let pubkey = request_pubkey(&client, token.as_ref(), &key).await?;
+        let address = verifying_key_to_address(&pubkey);
+
+        debug!(
+            "Instantiated GCP KMS signer with pubkey 0x{} and address 0x{}",
+            hex::encode(pubkey.to_sec1_bytes()),
+            hex::encode(address)
+        );
+
+        Ok(Self { client, token, key, chain_id, pubkey, address })
+    }
+
+    /// Returns the pinned key version of this signer
+    pub fn key(&self) -> &KeyVersion {
+        &self.key
+    }
+
+    /// Fetch the pubkey of this signer's key version
+    pub async fn get_pubkey(&self) -> Result<VerifyingKey, GcpKmsSignerError> {
+        request_pubkey(&self.client, self.token.as_ref(), &self.key).await
+    }
+
+    /// Sign a digest with this signer's key
+    #[instrument(err, skip(self, digest), fields(digest = %hex::encode(digest)))]
+    pub async fn sign_digest(&self, digest: [u8; 32]) -> Result<KSig, GcpKmsSignerError> {
+        debug!("Dispatching asymmetricSign");
+        let url = format!("{GCP_KMS_URL}/{}:asymmetricSign", self.key);
+        let req = SignRequest { digest: Digest { sha256: STANDARD.encode(digest) } };
+        let resp = self
+            .client
+            .post(url)
+            .bearer_auth(self.token.access_token().await?)
+            .json(&req)
+            .send()
+            .await?;
+        let resp: SignResponse = decode_response(resp).await?;
+        let sig = KSig::from_der(&STANDARD.decode(resp.signature)?)?;
+        Ok(sig.normalize_s().unwrap_or(sig))
+    }
+
+    /// Sign a digest with this signer's key and recover the `v` value, applying EIP-155 if a
+    /// chain id is given
+    async fn sign_digest_with_v(
+        &self,
+        digest: H256,
+        chain_id: Option<u64>,
+    ) -> Result<EthSig, GcpKmsSignerError> {
+        let sig = self.sign_digest(digest.into()).await?;
+        let mut sig = self.recover_v(&sig, digest)?;
+        if let Some(chain_id) = chain_id {
+            sig.v = crate::to_eip155_v(sig.v as u8 - 27, chain_id);
+        }
+        Ok(sig)
+    }
+
+    /// Determines the recovery id by trial recovery against this signer's address
+    fn recover_v(&self, sig: &KSig, digest: H256) -> Result<EthSig, GcpKmsSignerError> {
+        let (r, s) = sig.split_bytes();
+        let r = U256::from_big_endian(r.as_slice());
+        let s = U256::from_big_endian(s.as_slice());
+        [27, 28]
+            .into_iter()
+            .map(|v| EthSig { r, s, v })
+            .find(|sig| sig.recover(digest).map(|addr| addr == self.address).unwrap_or(false))
+            .ok_or_else(|| GcpKmsSignerError::Other("signature does not match key".to_owned()))
+    }
+}
+
+#[async_trait::async_trait]
+impl super::Signer for GcpKmsSigner {
+    type Error = GcpKmsSignerError;
+
+    #[instrument(err, skip(message))]
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<EthSig, Self::Error> {
+        let message = message.as_ref();
+        let message_hash = hash_message(message);
+        trace!("{:?}", message_hash);
+        trace!("{:?}", message);
+
+        self.sign_digest_with_v(message_hash, Some(self.chain_id)).await
+    }
+
+    #[instrument(err)]
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<EthSig, Self::Error> {
+        let mut tx_with_chain = tx.clone();
+        let chain_id = tx_with_chain.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx_with_chain.set_chain_id(chain_id);
+
+        let sighash = tx_with_chain.sighash();
+        self.sign_digest_with_v(sighash, Some(chain_id)).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<EthSig, Self::Error> {
+        let digest =
+            payload.encode_eip712().map_err(|e| Self::Error::Eip712Error(e.to_string()))?;
+
+        self.sign_digest_with_v(digest.into(), None).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the signer's chain id
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Sets the signer's chain id
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl super::AuthorizationSigner for GcpKmsSigner {
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<EthSig, Self::Error> {
+        self.sign_digest_with_v(authorization.signature_hash(), None).await
+    }
+}
+
+#[async_trait::async_trait]
+impl super::IntendedValidatorSigner for GcpKmsSigner {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<EthSig, Self::Error> {
+        self.sign_digest_with_v(hash_intended_validator(validator, data), None).await
+    }
+}
+
+#[instrument(err, skip(client, token, key), fields(key = %key))]
+async fn request_pubkey(
+    client: &reqwest::Client,
+    token: &dyn AccessTokenProvider,
+    key: &KeyVersion,
+) -> Result<VerifyingKey, GcpKmsSignerError> {
+    debug!("Dispatching getPublicKey");
+    let url = format!("{GCP_KMS_URL}/{key}/publicKey");
+    let resp = client.get(url).bearer_auth(token.access_token().await?).send().await?;
+    let resp: PublicKeyResponse = decode_response(resp).await?;
+    if resp.algorithm != SECP256K1_ALGORITHM {
+        return Err(GcpKmsSignerError::UnsupportedAlgorithm(resp.algorithm))
+    }
+    decode_pem(&resp.pem)
+}
+
+/// Decodes a successful JSON response or turns the response body into an API error
+async fn decode_response<T: serde::de::DeserializeOwned>(
+    resp: reqwest::Response,
+) -> Result<T, GcpKmsSignerError> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await?;
+        return Err(GcpKmsSignerError::Api { status, body })
+    }
+    Ok(resp.json().await?)
+}
+
+/// Decodes a PEM encoded SubjectPublicKeyInfo
+fn decode_pem(pem: &str) -> Result<VerifyingKey, GcpKmsSignerError> {
+    let body: String =
+        pem.lines().map(str::trim).filter(|line| !line.starts_with("-----")).collect();
+    let der = STANDARD.decode(body)?;
+    let spki = spki::SubjectPublicKeyInfoRef::try_from(der.as_slice())?;
+    Ok(VerifyingKey::from_sec1_bytes(spki.subject_public_key.raw_bytes())?)
+}
+
+/// Convert a verifying key to an ethereum address
+fn verifying_key_to_address(key: &VerifyingKey) -> Address {
+    let public_key = key.to_encoded_point(false);
+    let hash = keccak256(&public_key.as_bytes()[1..]);
+    Address::from_slice(&hash[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signer;
+
+    // secp256k1 public key of the private key 0x01
+    const PEM: &str = "-----BEGIN PUBLIC KEY-----
+MFYwEAYHKoZIzj0CAQYFK4EEAAoDQgAEeb5mfvncu6xVoGKVzocLBwKb/NstzijZ
+WfKBWxb4F5hIOtp3JqPEZV2k+/wOEQio/Re0SKaFVBmcR9CP+xDUuA==
+-----END PUBLIC KEY-----";
+
+    #[test]
+    fn parses_key_version() {
+        let name = "projects/p/locations/global/keyRings/r/cryptoKeys/k/cryptoKeyVersions/3";
+        let key: KeyVersion = name.parse().unwrap();
+        assert_eq!(key, KeyVersion::new("p", "global", "r", "k", "3"));
+        assert_eq!(key.to_string(), name);
+        assert!("projects/p/locations/global/keyRings/r/cryptoKeys/k"
+            .parse::<KeyVersion>()
+            .is_err());
+    }
+
+    #[test]
+    fn decodes_pem() {
+        let key = decode_pem(PEM).unwrap();
+        assert_eq!(
+            verifying_key_to_address(&key),
+            "7e5f4552091a69125d5dfcb7b8c2659029395bdf".parse::<Address>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_signs_messages() {
+        let (key, token) = match (std::env::var("GCP_KEY_VERSION"), std::env::var("GCP_TOKEN")) {
+            (Ok(key), Ok(token)) => (key, token),
+            _ => return,
+        };
+        let signer = GcpKmsSigner::new(reqwest::Client::new(), key.parse().unwrap(), token, 1)
+            .await
+            .unwrap();
+
+        let message = vec![0, 1, 2, 3];
+
+        let sig = signer.sign_message(&message).await.unwrap();
+        sig.verify(message, signer.address).expect("valid sig");
+    }
+}
@@ -0,0 +1,170 @@
+//! A common interface for hardware wallets and the derivation paths of their accounts.
+use async_trait::async_trait;
+use ethers_core::types::{transaction::eip2718::TypedTransaction, Address, Signature};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// The offset of the hardened child indices
+const HARDENED: u32 = 0x8000_0000;
+
+/// Error thrown when parsing a [`DerivationPath`]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum DerivationPathError {
+    /// The path does not start with `m/`
+    #[error("derivation path {0:?} does not start with m/")]
+    MissingRoot(String),
+    /// A component is not a child index below 2^31, optionally followed by `'` or `h`
+    #[error("invalid derivation path component {0:?}")]
+    InvalidComponent(String),
+}
+
+/// A BIP-32 derivation path, e.g. `m/44'/60'/0'/0/0`.
+///
+/// Hardened components are parsed from a trailing `'`, `h` or `H`, and displayed with a `'`.
+///
+/// ```
+/// use ethers_signers::DerivationPath;
+///
+/// let path: DerivationPath = "m/44'/60'/0'/0/3".parse().unwrap();
+/// assert_eq!(path, DerivationPath::bip44(3));
+/// assert_eq!(path.components(), &[0x8000_002c, 0x8000_003c, 0x8000_0000, 0, 3]);
+/// assert_eq!(DerivationPath::ledger_live(1).to_string(), "m/44'/60'/1'/0/0");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Creates a path from its child indices, with hardened indices offset by 2^31
+    pub fn new(components: impl Into<Vec<u32>>) -> Self {
+        Self(components.into())
+    }
+
+    /// The BIP-44 path used by most wallets, e.g. MetaMask and Trezor, `m/44'/60'/0'/0/{index}`
+    pub fn bip44(index: u32) -> Self {
+        Self(vec![44 | HARDENED, 60 | HARDENED, HARDENED, 0, index])
+    }
+
+    /// The path used by Ledger Live, `m/44'/60'/{index}'/0/0`
+    pub fn ledger_live(index: u32) -> Self {
+        Self(vec![44 | HARDENED, 60 | HARDENED, index | HARDENED, 0, 0])
+    }
+
+    /// The path used by the legacy Ledger Chrome app and MyEtherWallet, `m/44'/60'/0'/{index}`
+    pub fn ledger_legacy(index: u32) -> Self {
+        Self(vec![44 | HARDENED, 60 | HARDENED, HARDENED, index])
+    }
+
+    /// Returns the child indices of the path, with hardened indices offset by 2^31.
+    pub fn components(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = DerivationPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = match s.trim() {
+            "m" | "M" => return Ok(Self::default()),
+            path => path
+                .strip_prefix("m/")
+                .or_else(|| path.strip_prefix("M/"))
+                .ok_or_else(|| DerivationPathError::MissingRoot(s.to_string()))?,
+        };
+        path.split('/')
+            .map(|component| {
+                let (index, hardened) = match component
+                    .strip_suffix('\'')
+                    .or_else(|| component.strip_suffix('h'))
+                    .or_else(|| component.strip_suffix('H'))
+                {
+                    Some(index) => (index, HARDENED),
+                    None => (component, 0),
+                };
+                match index.parse::<u32>() {
+                    Ok(child) if child < HARDENED && index.bytes().all(|b| b.is_ascii_digit()) => {
+                        Ok(child | hardened)
+                    }
+                    _ => Err(DerivationPathError::InvalidComponent(component.to_string())),
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for component in &self.0 {
+            match component & HARDENED {
+                0 => write!(f, "/{component}")?,
+                _ => write!(f, "/{}'", component & !HARDENED)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The device and firmware of a [`HardwareSigner`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// The manufacturer of the device, e.g. `Ledger`
+    pub vendor: String,
+    /// The model of the device, if it reports one
+    pub model: Option<String>,
+    /// The version of the firmware or the Ethereum app which signs
+    pub version: String,
+}
+
+/// A hardware wallet which holds the keys of several accounts, addressed by their
+/// [`DerivationPath`].
+///
+/// Implemented by the [`Ledger`](crate::Ledger) and [`Trezor`](crate::Trezor) signers, so that
+/// applications can support any device with the same code.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait HardwareSigner: fmt::Debug + Send + Sync {
+    /// The error returned by the device
+    type Error: std::error::Error + Send + Sync;
+
+    /// Returns the address of the account at `path`.
+    async fn get_address(&self, path: &DerivationPath) -> Result<Address, Self::Error>;
+
+    /// Signs the transaction with the account at `path`, which has to be confirmed on the device.
+    async fn sign_tx(
+        &self,
+        path: &DerivationPath,
+        tx: &TypedTransaction,
+    ) -> Result<Signature, Self::Error>;
+
+    /// Returns the model and firmware version of the device.
+    async fn device_info(&self) -> Result<DeviceInfo, Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BIP44_TEMPLATE, INDEX_PLACEHOLDER, LEDGER_LEGACY_TEMPLATE, LEDGER_LIVE_TEMPLATE};
+
+    #[test]
+    fn parses_derivation_paths() {
+        for (template, preset) in [
+            (BIP44_TEMPLATE, DerivationPath::bip44 as fn(u32) -> DerivationPath),
+            (LEDGER_LIVE_TEMPLATE, DerivationPath::ledger_live),
+            (LEDGER_LEGACY_TEMPLATE, DerivationPath::ledger_legacy),
+        ] {
+            let path = template.replace(INDEX_PLACEHOLDER, "7");
+            assert_eq!(path.parse::<DerivationPath>().unwrap(), preset(7));
+            assert_eq!(preset(7).to_string(), path);
+        }
+
+        assert_eq!("m/44h/60H/0'/0/0".parse(), Ok(DerivationPath::bip44(0)));
+        assert_eq!("m".parse(), Ok(DerivationPath::default()));
+        assert_eq!(DerivationPath::default().to_string(), "m");
+
+        for invalid in ["44'/60'", "m/", "m/44''", "m/-1", "m/+1", "m/2147483648", "m/0x1"] {
+            assert!(invalid.parse::<DerivationPath>().is_err(), "{invalid}");
+        }
+    }
+}
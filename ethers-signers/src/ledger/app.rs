@@ -27,6 +27,7 @@ pub struct LedgerEthereum {
     derivation: DerivationType,
     pub(crate) chain_id: u64,
     pub(crate) address: Address,
+    blind_sign_typed_data: bool,
 }
 
 impl std::fmt::Display for LedgerEthereum {
@@ -57,7 +58,25 @@ impl LedgerEthereum {
         let transport = Ledger::init().await?;
         let address = Self::get_address_with_path_transport(&transport, &derivation).await?;
 
-        Ok(Self { transport: Mutex::new(transport), derivation, chain_id, address })
+        Ok(Self {
+            transport: Mutex::new(transport),
+            derivation,
+            chain_id,
+            address,
+            blind_sign_typed_data: false,
+        })
+    }
+
+    /// Allows signing EIP712 structs via the app's blind-signing path.
+    ///
+    /// The device does not decode the struct, it only displays the domain separator and the struct
+    /// hash for confirmation. Both are logged before signing so they can be compared with the
+    /// device screen. Signing typed data fails with [`LedgerError::BlindSigningDisabled`] unless
+    /// this is enabled.
+    #[must_use]
+    pub fn with_blind_signing(mut self, enabled: bool) -> Self {
+        self.blind_sign_typed_data = enabled;
+        self
     }
 
     /// Consume self and drop the ledger mutex
@@ -134,12 +153,28 @@ impl LedgerEthereum {
 
     /// Signs an Ethereum transaction (requires confirmation on the ledger)
     pub async fn sign_tx(&self, tx: &TypedTransaction) -> Result<Signature, LedgerError> {
+        self.sign_tx_with_path(&self.derivation, tx).await
+    }
+
+    /// Signs an Ethereum transaction with the account of the provided derivation path (requires
+    /// confirmation on the ledger)
+    pub async fn sign_tx_with_path(
+        &self,
+        derivation: &DerivationType,
+        tx: &TypedTransaction,
+    ) -> Result<Signature, LedgerError> {
+        // the app signs the RLP encoding, while zkSync transactions are signed as typed data
+        #[cfg(feature = "zksync")]
+        if let TypedTransaction::ZkSync(_) = tx {
+            return Err(LedgerError::UnsupportedSigningScheme)
+        }
+
         let mut tx_with_chain = tx.clone();
         if tx_with_chain.chain_id().is_none() {
             // in the case we don't have a chain_id, let's use the signer chain id instead
             tx_with_chain.set_chain_id(self.chain_id);
         }
-        let mut payload = Self::path_to_bytes(&self.derivation);
+        let mut payload = Self::path_to_bytes(derivation);
         payload.extend_from_slice(tx_with_chain.rlp().as_ref());
 
         let mut signature = self.sign_payload(INS::SIGN, &payload).await?;
@@ -157,12 +192,18 @@ impl LedgerEthereum {
             };
 
             signature.v = match tx {
-                TypedTransaction::Eip2930(_) | TypedTransaction::Eip1559(_) => {
-                    (ecc_parity % 2 != 1) as u64
-                }
+                TypedTransaction::Eip2930(_) |
+                TypedTransaction::Eip1559(_) |
+                TypedTransaction::Eip4844(_) => (ecc_parity % 2 != 1) as u64,
                 TypedTransaction::Legacy(_) => eip155_chain_id + ecc_parity,
                 #[cfg(feature = "optimism")]
                 TypedTransaction::OptimismDeposited(_) => 0,
+                #[cfg(feature = "celo")]
+                TypedTransaction::Cip42(_) | TypedTransaction::Cip64(_) => {
+                    (ecc_parity % 2 != 1) as u64
+                }
+                #[cfg(feature = "zksync")]
+                TypedTransaction::ZkSync(_) => (ecc_parity % 2 != 1) as u64,
             };
         }
 
@@ -181,10 +222,16 @@ impl LedgerEthereum {
     }
 
     /// Signs an EIP712 encoded domain separator and message
+    ///
+    /// Requires blind signing to be enabled with [`Self::with_blind_signing`].
     pub async fn sign_typed_struct<T>(&self, payload: &T) -> Result<Signature, LedgerError>
     where
         T: Eip712,
     {
+        if !self.blind_sign_typed_data {
+            return Err(LedgerError::BlindSigningDisabled)
+        }
+
         // See comment for v1.6.0 requirement
         // https://github.com/LedgerHQ/app-ethereum/issues/105#issuecomment-765316999
         let req = semver::VersionReq::parse(EIP712_MIN_VERSION)?;
@@ -199,6 +246,11 @@ impl LedgerEthereum {
             payload.domain_separator().map_err(|e| LedgerError::Eip712Error(e.to_string()))?;
         let struct_hash =
             payload.struct_hash().map_err(|e| LedgerError::Eip712Error(e.to_string()))?;
+        tracing::info!(
+            domain_separator = hex::encode(domain_separator),
+            struct_hash = hex::encode(struct_hash),
+            "Confirm the EIP712 hashes on the device"
+        );
 
         let mut payload = Self::path_to_bytes(&self.derivation);
         payload.extend_from_slice(&domain_separator);
@@ -1,6 +1,7 @@
 #![allow(clippy::upper_case_acronyms)]
 //! Helpers for interacting with the Ethereum Ledger App
 //! [Official Docs](https://github.com/LedgerHQ/app-ethereum/blob/master/doc/ethapp.asc)
+use crate::DerivationPath;
 use std::fmt;
 use thiserror::Error;
 
@@ -29,6 +30,12 @@ impl fmt::Display for DerivationType {
     }
 }
 
+impl From<DerivationPath> for DerivationType {
+    fn from(path: DerivationPath) -> Self {
+        DerivationType::Other(path.to_string())
+    }
+}
+
 #[derive(Error, Debug)]
 /// Error when using the Ledger transport
 pub enum LedgerError {
@@ -47,6 +54,12 @@ pub enum LedgerError {
     /// Error type from Eip712Error message
     #[error("error encoding eip712 struct: {0:?}")]
     Eip712Error(String),
+    /// Error when requesting a signature the device can not produce
+    #[error("The Ledger ethereum app does not support this signing scheme")]
+    UnsupportedSigningScheme,
+    /// Error when signing EIP712 struct without opting into blind signing
+    #[error("Signing EIP712 structs requires blind signing to be enabled")]
+    BlindSigningDisabled,
     /// Error when signing EIP712 struct with not compatible Ledger ETH app
     #[error("Ledger ethereum app requires at least version: {0:?}")]
     UnsupportedAppVersion(String),
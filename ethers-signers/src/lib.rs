@@ -3,7 +3,37 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod wallet;
-pub use wallet::{MnemonicBuilder, Wallet, WalletError};
+pub use wallet::{
+    audit_addresses, dev_addresses, AuditKey, AuditReport, DerivedAddress, HDWallet, Language,
+    MnemonicBuilder, PlatformKey, PlatformKeyError, PlatformKeystore, SecretString, Wallet,
+    WalletError, BIP44_TEMPLATE, DEV_ACCOUNTS, DEV_CHAIN_ID, DEV_MNEMONIC, INDEX_PLACEHOLDER,
+    LEDGER_LEGACY_TEMPLATE, LEDGER_LIVE_TEMPLATE, WORD_COUNTS,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use wallet::{KeystoreDir, KeystoreEntry, KeystoreKdf};
+
+mod multi;
+pub use multi::{MultiSigner, MultiSignerError};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod env;
+#[cfg(not(target_arch = "wasm32"))]
+pub use env::{EnvSigner, EnvSignerError};
+
+mod hardware;
+pub use hardware::{DerivationPath, DerivationPathError, DeviceInfo, HardwareSigner};
+
+mod link;
+pub use link::{LinkError, LinkRequest, LinkingProof, VerifiedLink, LINK_RESOURCE_PREFIX};
+
+mod watch_only;
+pub use watch_only::{WatchOnlySigner, WatchOnlySignerError};
+
+mod threshold;
+pub use threshold::{
+    PartialSigner, RoundMessage, RoundOutput, SignatureAggregator, ThresholdSigner,
+    ThresholdSignerError, DEFAULT_MAX_ROUNDS,
+};
 
 /// Re-export the BIP-32 crate so that wordlists can be accessed conveniently.
 pub use coins_bip39;
@@ -11,6 +41,9 @@ pub use coins_bip39;
 /// A wallet instantiated with a locally stored private key
 pub type LocalWallet = Wallet<ethers_core::k256::ecdsa::SigningKey>;
 
+/// A wallet whose private key is wrapped by a platform key store like the Secure Enclave or a TPM
+pub type PlatformWallet<K> = Wallet<PlatformKey<K>>;
+
 #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 /// A wallet instantiated with a YubiHSM
 pub type YubiWallet = Wallet<yubihsm::ecdsa::Signer<ethers_core::k256::Secp256k1>>;
@@ -34,18 +67,63 @@ pub use trezor::{
 #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 pub use yubihsm;
 
+#[cfg(all(feature = "piv", not(target_arch = "wasm32")))]
+mod piv;
+#[cfg(all(feature = "piv", not(target_arch = "wasm32")))]
+pub use piv::{PinCache, PinPolicy, PivError, PivKeystore, PivWallet, TouchPolicy};
+#[cfg(all(feature = "piv", not(target_arch = "wasm32")))]
+pub use yubikey;
+
+#[cfg(all(feature = "bls", not(target_arch = "wasm32")))]
+pub mod bls;
+#[cfg(all(feature = "bls", not(target_arch = "wasm32")))]
+pub use bls::{BlsError, BlsSigner};
+
+#[cfg(feature = "passkey")]
+pub mod passkey;
+#[cfg(feature = "passkey")]
+pub use passkey::{LocalPasskey, P256PublicKey, PasskeyError, PasskeySigner, WebAuthnAssertion};
+
 #[cfg(feature = "aws")]
 mod aws;
 #[cfg(feature = "aws")]
 pub use aws::{AwsSigner, AwsSignerError};
 
+#[cfg(feature = "gcp")]
+mod gcp;
+#[cfg(feature = "gcp")]
+pub use gcp::{AccessTokenProvider, GcpKmsSigner, GcpKmsSignerError, KeyVersion};
+
+#[cfg(feature = "remote")]
+mod remote;
+#[cfg(feature = "remote")]
+pub use remote::{RemoteSigner, RemoteSignerApi, RemoteSignerError};
+
+#[cfg(feature = "walletconnect")]
+pub mod walletconnect;
+#[cfg(feature = "walletconnect")]
+pub use walletconnect::{WalletConnectError, WalletConnectSigner};
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
 use async_trait::async_trait;
-use ethers_core::types::{
-    transaction::{eip2718::TypedTransaction, eip712::Eip712},
-    Address, Signature,
+use ethers_core::{
+    types::{
+        transaction::{
+            eip2718::TypedTransaction, eip4337::UserOperation, eip712::Eip712,
+            eip7702::Authorization,
+        },
+        Address, Signature, H256,
+    },
+    utils::keccak256,
 };
 use std::error::Error;
 
+/// The header that authenticates requests to the Flashbots relay and compatible builder APIs, see
+/// [`Signer::flashbots_signature`].
+pub const FLASHBOTS_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
 /// Applies [EIP155](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-155.md)
 pub fn to_eip155_v<T: Into<u8>>(recovery_id: T, chain_id: u64) -> u64 {
     (recovery_id.into() as u64) + 35 + chain_id * 2
@@ -64,6 +142,22 @@ pub trait Signer: std::fmt::Debug + Send + Sync {
         message: S,
     ) -> Result<Signature, Self::Error>;
 
+    /// Signs the hashes of the provided messages after prefixing them, like
+    /// [`Signer::sign_message`].
+    ///
+    /// Signs one message after the other by default. Signers that can sign many messages at once
+    /// more efficiently, like [`Wallet`], override it.
+    async fn sign_messages<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        messages: &[S],
+    ) -> Result<Vec<Signature>, Self::Error> {
+        let mut signatures = Vec::with_capacity(messages.len());
+        for message in messages {
+            signatures.push(self.sign_message(message).await?);
+        }
+        Ok(signatures)
+    }
+
     /// Signs the transaction
     async fn sign_transaction(&self, message: &TypedTransaction) -> Result<Signature, Self::Error>;
 
@@ -74,9 +168,39 @@ pub trait Signer: std::fmt::Debug + Send + Sync {
         payload: &T,
     ) -> Result<Signature, Self::Error>;
 
+    /// Signs the hash of the ERC-4337 user operation for the `entry_point` on the signer's chain.
+    ///
+    /// The hash is signed as an EIP-191 personal message, which is what most smart accounts
+    /// validate.
+    async fn sign_user_operation(
+        &self,
+        op: &UserOperation,
+        entry_point: Address,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_message(op.user_op_hash(entry_point, self.chain_id())).await
+    }
+
+    /// Returns the value of the [`FLASHBOTS_SIGNATURE_HEADER`] for the request `body`.
+    ///
+    /// The value is `<address>:<signature>`, where the signature is over the `0x` prefixed hex
+    /// string of the body's keccak256 hash, signed as a personal message.
+    async fn flashbots_signature<B: Send + Sync + AsRef<[u8]>>(
+        &self,
+        body: B,
+    ) -> Result<String, Self::Error> {
+        let hash = format!("{:?}", H256::from(keccak256(body.as_ref())));
+        let signature = self.sign_message(hash).await?;
+        Ok(format!("{:?}:0x{signature}", self.address()))
+    }
+
     /// Returns the signer's Ethereum Address
     fn address(&self) -> Address;
 
+    /// Returns `true` if the signer can sign transactions from `address`
+    fn is_signer_for(&self, address: Address) -> bool {
+        address == self.address()
+    }
+
     /// Returns the signer's chain id
     fn chain_id(&self) -> u64;
 
@@ -84,3 +208,35 @@ pub trait Signer: std::fmt::Debug + Send + Sync {
     #[must_use]
     fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self;
 }
+
+/// A [`Signer`] that signs data for a validator contract according to EIP-191 version `0x00`.
+///
+/// Separate from [`Signer`], so signers that predate the scheme keep implementing it unchanged.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait IntendedValidatorSigner: Signer {
+    /// Signs the data for the given validator contract according to EIP-191 version `0x00`
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<Signature, Self::Error>;
+}
+
+/// A [`Signer`] that signs EIP-7702 authorizations, delegating its account to the code at their
+/// address.
+///
+/// Not a part of [`Signer`] itself, which keeps existing implementations of it compiling.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait AuthorizationSigner: Signer {
+    /// Signs the EIP-7702 `authorization`, delegating the signer's account to the code at its
+    /// address.
+    ///
+    /// The chain id of the authorization is signed as is, a chain id of zero makes it valid on all
+    /// chains. Attach the signature with [`Authorization::into_signed`].
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, Self::Error>;
+}
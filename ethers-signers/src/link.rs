@@ -0,0 +1,323 @@
+//! Signed statements which link an Ethereum account to an off-chain account, formatted as
+//! [EIP-4361](https://eips.ethereum.org/EIPS/eip-4361) "Sign-In with Ethereum" messages.
+use crate::Signer;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use ethers_core::{
+    rand::{distributions::Alphanumeric, thread_rng, Rng},
+    types::{Address, Signature, SignatureError},
+    utils::to_checksum,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The prefix of the resource which identifies the linked account in a linking message
+pub const LINK_RESOURCE_PREFIX: &str = "urn:link:";
+
+/// The length of the random nonces of [`LinkRequest::new`]
+const NONCE_LENGTH: usize = 16;
+
+/// Error thrown when verifying a [`LinkingProof`]
+#[derive(Debug, Error)]
+pub enum LinkError {
+    /// The message is not a linking message
+    #[error("malformed linking message: {0}")]
+    Malformed(&'static str),
+    /// The message was issued for another domain
+    #[error("linking message was issued for {0}")]
+    DomainMismatch(String),
+    /// The message has expired
+    #[error("linking message expired at {0}")]
+    Expired(DateTime<Utc>),
+    /// The message was issued after the time it was verified at
+    #[error("linking message is issued at {0}, which is in the future")]
+    NotYetValid(DateTime<Utc>),
+    /// The signature was not produced by the linked address
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// A request to link the signer's Ethereum account to the account `account_id` of an application,
+/// which is signed into a [`LinkingProof`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkRequest {
+    /// The domain of the application requesting the link, e.g. `example.com`
+    pub domain: String,
+    /// The URI of the application, e.g. `https://example.com/link`
+    pub uri: String,
+    /// The id of the off-chain account, e.g. a user id or an email address
+    pub account_id: String,
+    /// The chain id the signer is connected to
+    pub chain_id: u64,
+    /// A random nonce, which the application should only accept once
+    pub nonce: String,
+    /// The time the request was issued at
+    pub issued_at: DateTime<Utc>,
+    /// The time after which the proof is no longer accepted
+    pub expiration_time: DateTime<Utc>,
+}
+
+impl LinkRequest {
+    /// Creates a request for linking to `account_id` on `domain`, which expires after `ttl`.
+    ///
+    /// The URI defaults to `https://{domain}` and the chain id to mainnet, the nonce is random.
+    pub fn new(domain: impl Into<String>, account_id: impl Into<String>, ttl: Duration) -> Self {
+        let domain = domain.into();
+        let nonce =
+            thread_rng().sample_iter(&Alphanumeric).take(NONCE_LENGTH).map(char::from).collect();
+        let issued_at = Utc::now();
+        Self {
+            uri: format!("https://{domain}"),
+            domain,
+            account_id: account_id.into(),
+            chain_id: 1,
+            nonce,
+            issued_at,
+            expiration_time: issued_at + ttl,
+        }
+    }
+
+    /// Sets the URI of the application
+    #[must_use]
+    pub fn uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = uri.into();
+        self
+    }
+
+    /// Sets the chain id
+    #[must_use]
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Returns the human readable statement of the message.
+    pub fn statement(&self) -> String {
+        format!("Link this Ethereum account to the account {} on {}.", self.account_id, self.domain)
+    }
+
+    /// Returns the EIP-4361 message which `address` signs.
+    pub fn to_message(&self, address: Address) -> String {
+        let timestamp = |time: &DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::Secs, true);
+        format!(
+            "{domain} wants you to sign in with your Ethereum account:\n\
+             {address}\n\
+             \n\
+             {statement}\n\
+             \n\
+             URI: {uri}\n\
+             Version: 1\n\
+             Chain ID: {chain_id}\n\
+             Nonce: {nonce}\n\
+             Issued At: {issued_at}\n\
+             Expiration Time: {expiration_time}\n\
+             Resources:\n\
+             - {LINK_RESOURCE_PREFIX}{account_id}",
+            domain = self.domain,
+            address = to_checksum(&address, None),
+            statement = self.statement(),
+            uri = self.uri,
+            chain_id = self.chain_id,
+            nonce = self.nonce,
+            issued_at = timestamp(&self.issued_at),
+            expiration_time = timestamp(&self.expiration_time),
+            account_id = self.account_id,
+        )
+    }
+
+    /// Parses a message produced by [`LinkRequest::to_message`], returning the request and the
+    /// address it was produced for.
+    pub fn from_message(message: &str) -> Result<(Self, Address), LinkError> {
+        let mut lines = message.split('\n');
+        let mut line = |prefix: &str, err: &'static str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(prefix))
+                .map(str::to_string)
+                .ok_or(LinkError::Malformed(err))
+        };
+        let timestamp = |time: String, err: &'static str| {
+            DateTime::parse_from_rfc3339(&time)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|_| LinkError::Malformed(err))
+        };
+
+        let domain = line("", "missing domain")?
+            .strip_suffix(" wants you to sign in with your Ethereum account:")
+            .ok_or(LinkError::Malformed("missing header"))?
+            .to_string();
+        let address =
+            line("", "missing address")?.parse().map_err(|_| LinkError::Malformed("address"))?;
+        let statement = match (line("", "missing statement")?, line("", "missing statement")?) {
+            (blank, statement) if blank.is_empty() => statement,
+            _ => return Err(LinkError::Malformed("missing blank line")),
+        };
+        if !line("", "missing statement")?.is_empty() {
+            return Err(LinkError::Malformed("missing blank line"))
+        }
+        let uri = line("URI: ", "missing URI")?;
+        if line("Version: ", "missing version")? != "1" {
+            return Err(LinkError::Malformed("unsupported version"))
+        }
+        let chain_id = line("Chain ID: ", "missing chain id")?
+            .parse()
+            .map_err(|_| LinkError::Malformed("chain id"))?;
+        let nonce = line("Nonce: ", "missing nonce")?;
+        let issued_at = timestamp(line("Issued At: ", "missing issued at")?, "issued at")?;
+        let expiration_time =
+            timestamp(line("Expiration Time: ", "missing expiration time")?, "expiration time")?;
+        if !line("Resources:", "missing resources")?.is_empty() {
+            return Err(LinkError::Malformed("missing resources"))
+        }
+        let account_id = line(&format!("- {LINK_RESOURCE_PREFIX}"), "missing linked account")?;
+        if lines.next().is_some() {
+            return Err(LinkError::Malformed("trailing lines"))
+        }
+
+        let request = Self { domain, uri, account_id, chain_id, nonce, issued_at, expiration_time };
+        if statement != request.statement() {
+            return Err(LinkError::Malformed("statement does not match the linked account"))
+        }
+        Ok((request, address))
+    }
+
+    /// Signs the request with `signer`, linking the signer's address.
+    pub async fn sign<S: Signer>(&self, signer: &S) -> Result<LinkingProof, S::Error> {
+        let message = self.to_message(signer.address());
+        let signature = signer.sign_message(&message).await?;
+        Ok(LinkingProof { message, signature })
+    }
+}
+
+/// An address and account linked by a verified [`LinkingProof`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedLink {
+    /// The Ethereum address which signed the proof
+    pub address: Address,
+    /// The signed request, which contains the linked account and the nonce
+    pub request: LinkRequest,
+}
+
+/// A signed [`LinkRequest`], which is sent to the application to prove control of an address.
+///
+/// ```
+/// use chrono::Duration;
+/// use ethers_signers::{LinkRequest, LocalWallet, Signer};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let wallet = LocalWallet::new(&mut ethers_core::rand::thread_rng());
+///
+/// let request = LinkRequest::new("example.com", "user-42", Duration::minutes(10));
+/// let proof = request.sign(&wallet).await?;
+///
+/// // on the server, which also checks that the nonce was issued by it and not used before
+/// let link = proof.verify("example.com")?;
+/// assert_eq!(link.address, wallet.address());
+/// assert_eq!(link.request.account_id, "user-42");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkingProof {
+    /// The signed EIP-4361 message
+    pub message: String,
+    /// The EIP-191 signature of the message
+    pub signature: Signature,
+}
+
+impl LinkingProof {
+    /// Verifies that the proof was issued for `domain`, has not expired and was signed by the
+    /// address it links.
+    pub fn verify(&self, domain: &str) -> Result<VerifiedLink, LinkError> {
+        self.verify_at(domain, Utc::now())
+    }
+
+    /// Verifies the proof like [`LinkingProof::verify`], at the time `now`.
+    pub fn verify_at(&self, domain: &str, now: DateTime<Utc>) -> Result<VerifiedLink, LinkError> {
+        let (request, address) = LinkRequest::from_message(&self.message)?;
+        if request.domain != domain {
+            return Err(LinkError::DomainMismatch(request.domain))
+        }
+        if now < request.issued_at {
+            return Err(LinkError::NotYetValid(request.issued_at))
+        }
+        if now >= request.expiration_time {
+            return Err(LinkError::Expired(request.expiration_time))
+        }
+        self.signature.verify(self.message.as_str(), address)?;
+        Ok(VerifiedLink { address, request })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalWallet;
+    use chrono::TimeZone;
+
+    fn request() -> LinkRequest {
+        let issued_at = Utc.with_ymd_and_hms(2023, 6, 1, 12, 0, 0).unwrap();
+        LinkRequest {
+            domain: "example.com".to_string(),
+            uri: "https://example.com/link".to_string(),
+            account_id: "user-42".to_string(),
+            chain_id: 1,
+            nonce: "32891756".to_string(),
+            issued_at,
+            expiration_time: issued_at + Duration::minutes(10),
+        }
+    }
+
+    #[test]
+    fn formats_eip4361_message() {
+        let address = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".parse().unwrap();
+        let message = request().to_message(address);
+        assert_eq!(
+            message,
+            "example.com wants you to sign in with your Ethereum account:
+0x2c7536E3605D9C16a7a3D7b1898e529396a65c23
+
+Link this Ethereum account to the account user-42 on example.com.
+
+URI: https://example.com/link
+Version: 1
+Chain ID: 1
+Nonce: 32891756
+Issued At: 2023-06-01T12:00:00Z
+Expiration Time: 2023-06-01T12:10:00Z
+Resources:
+- urn:link:user-42"
+        );
+        assert_eq!(LinkRequest::from_message(&message).unwrap(), (request(), address));
+
+        let tampered = message.replace("Link this Ethereum account to the account user-42", "Hi");
+        assert!(LinkRequest::from_message(&tampered).is_err());
+    }
+
+    #[tokio::test]
+    async fn verifies_proof() {
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let request = request();
+        let proof = request.sign(&wallet).await.unwrap();
+        let now = request.issued_at + Duration::minutes(1);
+
+        let link = proof.verify_at("example.com", now).unwrap();
+        assert_eq!(link, VerifiedLink { address: wallet.address(), request: request.clone() });
+
+        assert!(matches!(proof.verify_at("evil.com", now), Err(LinkError::DomainMismatch(_))));
+        assert!(matches!(
+            proof.verify_at("example.com", request.expiration_time),
+            Err(LinkError::Expired(_))
+        ));
+        assert!(matches!(
+            proof.verify_at("example.com", request.issued_at - Duration::seconds(1)),
+            Err(LinkError::NotYetValid(_))
+        ));
+
+        let other = LocalWallet::new(&mut thread_rng());
+        let forged = LinkingProof {
+            message: proof.message.clone(),
+            signature: other.sign_message(&proof.message).await.unwrap(),
+        };
+        assert!(matches!(forged.verify_at("example.com", now), Err(LinkError::SignatureError(_))));
+    }
+}
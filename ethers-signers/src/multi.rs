@@ -0,0 +1,429 @@
+use crate::{AuthorizationSigner, IntendedValidatorSigner, Signer};
+use async_trait::async_trait;
+use ethers_core::types::{
+    transaction::{
+        eip2718::TypedTransaction,
+        eip712::{EIP712Domain, Eip712, Eip712Error},
+        eip7702::Authorization,
+    },
+    Address, Signature,
+};
+use std::{collections::BTreeMap, error::Error, fmt::Debug};
+use thiserror::Error;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// A [`Signer`] holding several signers of possibly different types, keyed by address.
+///
+/// Transactions are signed by the signer of their `from` address, so a single
+/// `SignerMiddleware` can send transactions for all accounts. Everything else is signed by the
+/// default signer, which is the first one added unless set with [`MultiSigner::with_default`].
+///
+/// All signers use the chain id of the first signer, or the one set with
+/// [`Signer::with_chain_id`]. Signers must also implement [`IntendedValidatorSigner`] and
+/// [`AuthorizationSigner`], so the router can forward every signing scheme.
+///
+/// ```
+/// use ethers_core::rand::thread_rng;
+/// use ethers_signers::{LocalWallet, MultiSigner, Signer};
+///
+/// let hot = LocalWallet::new(&mut thread_rng());
+/// let cold = LocalWallet::new(&mut thread_rng());
+/// let signer = MultiSigner::new().with_signer(hot.clone()).with_signer(cold.clone());
+///
+/// assert_eq!(signer.address(), hot.address());
+/// assert!(signer.contains(cold.address()));
+/// ```
+#[derive(Debug, Default)]
+pub struct MultiSigner {
+    signers: BTreeMap<Address, Box<dyn DynSigner>>,
+    default: Option<Address>,
+    chain_id: Option<u64>,
+}
+
+/// Error thrown by the [`MultiSigner`]
+#[derive(Error, Debug)]
+pub enum MultiSignerError {
+    /// Thrown when there is no signer for the requested address
+    #[error("no signer for {0:?}")]
+    UnknownSigner(Address),
+    /// Thrown when signing without any signers
+    #[error("no signers")]
+    NoSigners,
+    /// Thrown when the typed data can not be encoded
+    #[error(transparent)]
+    Eip712(#[from] Eip712Error),
+    /// Thrown when the signer of the address fails
+    #[error("signer {address:?} failed: {source}")]
+    Signer {
+        /// The address of the signer
+        address: Address,
+        /// The error of the signer
+        source: BoxError,
+    },
+}
+
+impl MultiSigner {
+    /// Instantiates a signer without any signers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a signer, replacing any previous signer of the same address.
+    #[must_use]
+    pub fn with_signer<S>(mut self, signer: S) -> Self
+    where
+        S: IntendedValidatorSigner + AuthorizationSigner + 'static,
+        S::Error: 'static,
+    {
+        self.insert(signer);
+        self
+    }
+
+    /// Sets the signer of `address` as default signer.
+    ///
+    /// Has no effect if there is no signer for `address`.
+    #[must_use]
+    pub fn with_default(mut self, address: Address) -> Self {
+        if self.signers.contains_key(&address) {
+            self.default = Some(address);
+        }
+        self
+    }
+
+    /// Adds a signer, replacing any previous signer of the same address.
+    pub fn insert<S>(&mut self, signer: S)
+    where
+        S: IntendedValidatorSigner + AuthorizationSigner + 'static,
+        S::Error: 'static,
+    {
+        let signer = match self.chain_id {
+            Some(chain_id) => signer.with_chain_id(chain_id),
+            None => {
+                self.chain_id = Some(signer.chain_id());
+                signer
+            }
+        };
+        let address = signer.address();
+        self.default.get_or_insert(address);
+        self.signers.insert(address, Box::new(signer));
+    }
+
+    /// Removes the signer of `address`, returning `true` if there was one.
+    ///
+    /// If it was the default signer, the signer with the lowest address becomes the default.
+    pub fn remove(&mut self, address: Address) -> bool {
+        let removed = self.signers.remove(&address).is_some();
+        if self.default == Some(address) {
+            self.default = self.signers.keys().next().copied();
+        }
+        removed
+    }
+
+    /// Returns `true` if there is a signer for `address`.
+    pub fn contains(&self, address: Address) -> bool {
+        self.signers.contains_key(&address)
+    }
+
+    /// Returns the addresses of all signers.
+    pub fn addresses(&self) -> impl Iterator<Item = Address> + '_ {
+        self.signers.keys().copied()
+    }
+
+    /// Signs a message with the signer of `address`.
+    pub async fn sign_message_as<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        address: Address,
+        message: S,
+    ) -> Result<Signature, MultiSignerError> {
+        let signer = self.signer(address)?;
+        signer.dyn_sign_message(message.as_ref()).await.map_err(|err| signer_error(address, err))
+    }
+
+    /// Signs typed data with the signer of `address`.
+    pub async fn sign_typed_data_as<T: Eip712 + Send + Sync>(
+        &self,
+        address: Address,
+        payload: &T,
+    ) -> Result<Signature, MultiSignerError> {
+        let payload = Eip712Digest::new(payload)?;
+        let signer = self.signer(address)?;
+        signer.dyn_sign_typed_data(&payload).await.map_err(|err| signer_error(address, err))
+    }
+
+    fn signer(&self, address: Address) -> Result<&dyn DynSigner, MultiSignerError> {
+        self.signers.get(&address).map(Box::as_ref).ok_or(MultiSignerError::UnknownSigner(address))
+    }
+
+    fn default_signer(&self) -> Result<(Address, &dyn DynSigner), MultiSignerError> {
+        let address = self.default.ok_or(MultiSignerError::NoSigners)?;
+        Ok((address, self.signer(address)?))
+    }
+}
+
+fn signer_error(address: Address, source: BoxError) -> MultiSignerError {
+    MultiSignerError::Signer { address, source }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Signer for MultiSigner {
+    type Error = MultiSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let (address, signer) = self.default_signer()?;
+        signer.dyn_sign_message(message.as_ref()).await.map_err(|err| signer_error(address, err))
+    }
+
+    /// Signs the transaction with the signer of its `from` address, or the default signer if it
+    /// has none.
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let (address, signer) = match tx.from() {
+            Some(from) => (*from, self.signer(*from)?),
+            None => self.default_signer()?,
+        };
+        signer.dyn_sign_transaction(tx).await.map_err(|err| signer_error(address, err))
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let payload = Eip712Digest::new(payload)?;
+        let (address, signer) = self.default_signer()?;
+        signer.dyn_sign_typed_data(&payload).await.map_err(|err| signer_error(address, err))
+    }
+
+    /// Returns the address of the default signer, or the zero address if there are no signers.
+    fn address(&self) -> Address {
+        self.default.unwrap_or_default()
+    }
+
+    /// Returns `true` if there is a signer for `address`.
+    fn is_signer_for(&self, address: Address) -> bool {
+        self.contains(address)
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id.unwrap_or(1)
+    }
+
+    /// Sets the chain id of all signers.
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        let chain_id = chain_id.into();
+        self.chain_id = Some(chain_id);
+        self.signers = std::mem::take(&mut self.signers)
+            .into_iter()
+            .map(|(address, signer)| (address, signer.dyn_with_chain_id(chain_id)))
+            .collect();
+        self
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthorizationSigner for MultiSigner {
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        let (address, signer) = self.default_signer()?;
+        signer.dyn_sign_authorization(authorization).await.map_err(|err| signer_error(address, err))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IntendedValidatorSigner for MultiSigner {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<Signature, Self::Error> {
+        let (address, signer) = self.default_signer()?;
+        signer
+            .dyn_sign_intended_validator(validator, data.as_ref())
+            .await
+            .map_err(|err| signer_error(address, err))
+    }
+}
+
+/// An object safe version of [`Signer`], so signers of different types can be stored together.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+trait DynSigner: Debug + Send + Sync {
+    async fn dyn_sign_message(&self, message: &[u8]) -> Result<Signature, BoxError>;
+
+    async fn dyn_sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, BoxError>;
+
+    async fn dyn_sign_intended_validator(
+        &self,
+        validator: Address,
+        data: &[u8],
+    ) -> Result<Signature, BoxError>;
+
+    async fn dyn_sign_typed_data(&self, payload: &Eip712Digest) -> Result<Signature, BoxError>;
+
+    async fn dyn_sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, BoxError>;
+
+    fn dyn_with_chain_id(self: Box<Self>, chain_id: u64) -> Box<dyn DynSigner>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<S> DynSigner for S
+where
+    S: IntendedValidatorSigner + AuthorizationSigner + 'static,
+    S::Error: 'static,
+{
+    async fn dyn_sign_message(&self, message: &[u8]) -> Result<Signature, BoxError> {
+        Ok(Signer::sign_message(self, message).await?)
+    }
+
+    async fn dyn_sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, BoxError> {
+        Ok(Signer::sign_transaction(self, tx).await?)
+    }
+
+    async fn dyn_sign_intended_validator(
+        &self,
+        validator: Address,
+        data: &[u8],
+    ) -> Result<Signature, BoxError> {
+        Ok(IntendedValidatorSigner::sign_intended_validator(self, validator, data).await?)
+    }
+
+    async fn dyn_sign_typed_data(&self, payload: &Eip712Digest) -> Result<Signature, BoxError> {
+        Ok(Signer::sign_typed_data(self, payload).await?)
+    }
+
+    async fn dyn_sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, BoxError> {
+        Ok(AuthorizationSigner::sign_authorization(self, authorization).await?)
+    }
+
+    fn dyn_with_chain_id(self: Box<Self>, chain_id: u64) -> Box<dyn DynSigner> {
+        Box::new(Signer::with_chain_id(*self, chain_id))
+    }
+}
+
+/// The hashes of an EIP-712 payload, so it can be passed to signers of any type.
+#[derive(Debug)]
+struct Eip712Digest {
+    domain: EIP712Domain,
+    domain_separator: [u8; 32],
+    struct_hash: [u8; 32],
+    digest: [u8; 32],
+}
+
+impl Eip712Digest {
+    fn new<T: Eip712>(payload: &T) -> Result<Self, Eip712Error> {
+        let error = |err: T::Error| Eip712Error::Message(err.to_string());
+        Ok(Self {
+            domain: payload.domain().map_err(error)?,
+            domain_separator: payload.domain_separator().map_err(error)?,
+            struct_hash: payload.struct_hash().map_err(error)?,
+            digest: payload.encode_eip712().map_err(error)?,
+        })
+    }
+}
+
+impl Eip712 for Eip712Digest {
+    type Error = Eip712Error;
+
+    fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.domain_separator)
+    }
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(self.domain.clone())
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Err(Eip712Error::Message("type hash of precomputed payload".to_string()))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.struct_hash)
+    }
+
+    fn encode_eip712(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalWallet;
+    use ethers_core::types::{transaction::eip712::TypedData, TransactionRequest};
+
+    #[tokio::test]
+    async fn dispatches_by_sender() {
+        let first: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let second: LocalWallet =
+            "0000000000000000000000000000000000000000000000000000000000000001".parse().unwrap();
+        let first = first.with_chain_id(5u64);
+        let signer = MultiSigner::new().with_signer(first.clone()).with_signer(second.clone());
+
+        assert_eq!(signer.address(), first.address());
+        assert_eq!(signer.chain_id(), 5);
+        assert!(signer.is_signer_for(second.address()));
+
+        let tx: TypedTransaction =
+            TransactionRequest::new().from(second.address()).to(Address::zero()).chain_id(5).into();
+        let signature = signer.sign_transaction(&tx).await.unwrap();
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), second.address());
+
+        let message = signer.sign_message("hello").await.unwrap();
+        assert_eq!(message.recover("hello").unwrap(), first.address());
+        let message = signer.sign_message_as(second.address(), "hello").await.unwrap();
+        assert_eq!(message.recover("hello").unwrap(), second.address());
+
+        let unknown = TransactionRequest::new().from(Address::zero()).into();
+        assert!(matches!(
+            signer.sign_transaction(&unknown).await,
+            Err(MultiSignerError::UnknownSigner(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn signs_typed_data() {
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let signer = MultiSigner::new().with_signer(wallet.clone());
+
+        let json = serde_json::json!({
+            "types": {
+                "EIP712Domain": [{ "name": "name", "type": "string" }],
+                "Mail": [{ "name": "contents", "type": "string" }]
+            },
+            "primaryType": "Mail",
+            "domain": { "name": "Ether Mail" },
+            "message": { "contents": "Hello, Bob!" }
+        });
+        let typed_data: TypedData = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            signer.sign_typed_data(&typed_data).await.unwrap(),
+            wallet.sign_typed_data(&typed_data).await.unwrap()
+        );
+    }
+
+    #[test]
+    fn removes_default() {
+        let first: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let mut signer = MultiSigner::new().with_signer(first.clone());
+        assert!(signer.remove(first.address()));
+        assert!(!signer.remove(first.address()));
+        assert_eq!(signer.address(), Address::zero());
+    }
+}
@@ -0,0 +1,88 @@
+use super::{P256PublicKey, PasskeyError, PasskeySigner, WebAuthnAssertion};
+use async_trait::async_trait;
+use js_sys::{Array, Uint8Array};
+use p256::ecdsa::Signature;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AuthenticatorAssertionResponse, CredentialRequestOptions, PublicKeyCredential,
+    PublicKeyCredentialDescriptor, PublicKeyCredentialRequestOptions, PublicKeyCredentialType,
+    UserVerificationRequirement,
+};
+
+/// A passkey held by an authenticator of the browser, asked for assertions with
+/// `navigator.credentials.get()`.
+///
+/// The credential id and public key are returned by `navigator.credentials.create()` when the
+/// passkey is registered, and have to be stored by the application.
+#[derive(Clone, Debug)]
+pub struct BrowserPasskey {
+    credential_id: Vec<u8>,
+    public_key: P256PublicKey,
+    rp_id: Option<String>,
+}
+
+impl BrowserPasskey {
+    /// Creates a signer for the registered credential. The relying party id defaults to the
+    /// effective domain of the page.
+    pub fn new(credential_id: impl Into<Vec<u8>>, public_key: P256PublicKey) -> Self {
+        Self { credential_id: credential_id.into(), public_key, rp_id: None }
+    }
+
+    /// Sets the relying party id the credential was registered for
+    #[must_use]
+    pub fn rp_id(mut self, rp_id: impl Into<String>) -> Self {
+        self.rp_id = Some(rp_id.into());
+        self
+    }
+}
+
+fn browser_error(err: JsValue) -> PasskeyError {
+    PasskeyError::Browser(format!("{err:?}"))
+}
+
+#[async_trait(?Send)]
+impl PasskeySigner for BrowserPasskey {
+    type Error = PasskeyError;
+
+    fn public_key(&self) -> P256PublicKey {
+        self.public_key
+    }
+
+    async fn sign_challenge(&self, challenge: &[u8]) -> Result<WebAuthnAssertion, Self::Error> {
+        let window = web_sys::window().ok_or_else(|| PasskeyError::Browser("no window".into()))?;
+
+        let credential = PublicKeyCredentialDescriptor::new(
+            &Uint8Array::from(&self.credential_id[..]),
+            PublicKeyCredentialType::PublicKey,
+        );
+        let mut options = PublicKeyCredentialRequestOptions::new(&Uint8Array::from(challenge));
+        options
+            .allow_credentials(&Array::of1(&credential))
+            .user_verification(UserVerificationRequirement::Required);
+        if let Some(rp_id) = &self.rp_id {
+            options.rp_id(rp_id);
+        }
+        let mut request = CredentialRequestOptions::new();
+        request.public_key(&options);
+
+        let promise =
+            window.navigator().credentials().get_with_options(&request).map_err(browser_error)?;
+        let credential: PublicKeyCredential = JsFuture::from(promise)
+            .await
+            .map_err(browser_error)?
+            .dyn_into()
+            .map_err(browser_error)?;
+        let response: AuthenticatorAssertionResponse =
+            credential.response().dyn_into().map_err(|err| browser_error(err.into()))?;
+
+        let authenticator_data = Uint8Array::new(&response.authenticator_data()).to_vec();
+        let client_data_json =
+            String::from_utf8(Uint8Array::new(&response.client_data_json()).to_vec())
+                .map_err(|_| PasskeyError::InvalidClientData("utf-8 encoding"))?;
+        // authenticators return ASN.1 DER encoded signatures
+        let signature = Signature::from_der(&Uint8Array::new(&response.signature()).to_vec())?;
+
+        WebAuthnAssertion::new(authenticator_data, client_data_json, &signature)
+    }
+}
@@ -0,0 +1,381 @@
+//! P-256 passkeys, which sign WebAuthn assertions instead of raw hashes.
+//!
+//! Passkeys can not control an externally owned account, their public key is registered with a
+//! smart account which verifies the assertions, usually with the [RIP-7212] precompile. The
+//! assertions are encoded as the `WebAuthnAuth` struct of the Coinbase Smart Wallet and Solady's
+//! `WebAuthn` library.
+//!
+//! [RIP-7212]: https://github.com/ethereum/RIPs/blob/master/RIPS/rip-7212.md
+#[cfg(target_arch = "wasm32")]
+mod browser;
+#[cfg(target_arch = "wasm32")]
+pub use browser::BrowserPasskey;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ethers_core::{
+    abi::{encode, Token},
+    rand::{CryptoRng, RngCore},
+    types::{transaction::eip4337::UserOperation, Address, Bytes, H160, H256, U256},
+};
+use p256::{
+    ecdsa::{
+        signature::{Signer as _, Verifier as _},
+        Signature, SigningKey, VerifyingKey,
+    },
+    EncodedPoint,
+};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use thiserror::Error;
+
+/// The address of the RIP-7212 `P256VERIFY` precompile
+pub const P256_VERIFY_PRECOMPILE: Address =
+    H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x00]);
+
+/// The authenticator data flags of an assertion with user presence and user verification
+const FLAGS_UP_UV: u8 = 0x05;
+
+/// Error thrown by passkey signers
+#[derive(Debug, Error)]
+pub enum PasskeyError {
+    /// The signature or public key is not a valid P-256 encoding
+    #[error(transparent)]
+    Ecdsa(#[from] p256::ecdsa::Error),
+    /// The client data JSON lacks the `type` or `challenge` of the assertion
+    #[error("invalid client data: missing {0}")]
+    InvalidClientData(&'static str),
+    /// The browser rejected the request, e.g. because the user cancelled it
+    #[error("webauthn request failed: {0}")]
+    Browser(String),
+}
+
+/// The affine coordinates of a P-256 public key, as stored by smart accounts
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct P256PublicKey {
+    /// The x coordinate
+    pub x: H256,
+    /// The y coordinate
+    pub y: H256,
+}
+
+impl P256PublicKey {
+    /// Parses a SEC1 encoded public key, compressed or uncompressed.
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, PasskeyError> {
+        Ok(Self::from(&VerifyingKey::from_sec1_bytes(bytes)?))
+    }
+
+    /// Returns the 64 byte concatenation of the coordinates.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(self.x.as_bytes());
+        bytes[32..].copy_from_slice(self.y.as_bytes());
+        bytes
+    }
+
+    fn verifying_key(&self) -> Result<VerifyingKey, PasskeyError> {
+        let point = EncodedPoint::from_affine_coordinates(
+            self.x.as_fixed_bytes().into(),
+            self.y.as_fixed_bytes().into(),
+            false,
+        );
+        Ok(VerifyingKey::from_encoded_point(&point)?)
+    }
+}
+
+impl From<&VerifyingKey> for P256PublicKey {
+    fn from(key: &VerifyingKey) -> Self {
+        let point = key.to_encoded_point(false);
+        Self {
+            x: H256::from_slice(point.x().expect("not the identity")),
+            y: H256::from_slice(point.y().expect("uncompressed point")),
+        }
+    }
+}
+
+/// A WebAuthn assertion over a challenge, e.g. an ERC-4337 user operation hash.
+///
+/// The authenticator signs `authenticator_data || sha256(client_data_json)`, where the client data
+/// contains the base64url encoded challenge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebAuthnAssertion {
+    /// The authenticator data, starting with the hash of the relying party id
+    pub authenticator_data: Bytes,
+    /// The client data JSON as serialized by the browser
+    pub client_data_json: String,
+    /// The index of `"challenge":"` in the client data
+    pub challenge_index: usize,
+    /// The index of `"type":"` in the client data
+    pub type_index: usize,
+    /// The `r` value of the signature
+    pub r: U256,
+    /// The `s` value of the signature, normalized to the lower half of the curve order
+    pub s: U256,
+}
+
+impl WebAuthnAssertion {
+    /// Creates an assertion from the fields of an `AuthenticatorAssertionResponse`.
+    ///
+    /// The signature is normalized to a low `s`, which most on-chain verifiers require to prevent
+    /// malleability.
+    pub fn new(
+        authenticator_data: impl Into<Bytes>,
+        client_data_json: String,
+        signature: &Signature,
+    ) -> Result<Self, PasskeyError> {
+        let challenge_index = client_data_json
+            .find(r#""challenge":""#)
+            .ok_or(PasskeyError::InvalidClientData("challenge"))?;
+        let type_index =
+            client_data_json.find(r#""type":""#).ok_or(PasskeyError::InvalidClientData("type"))?;
+        let signature = signature.normalize_s().unwrap_or(*signature);
+        let (r, s) = signature.split_bytes();
+        Ok(Self {
+            authenticator_data: authenticator_data.into(),
+            client_data_json,
+            challenge_index,
+            type_index,
+            r: U256::from_big_endian(&r),
+            s: U256::from_big_endian(&s),
+        })
+    }
+
+    /// Returns the message signed by the authenticator, which is hashed with SHA-256 before
+    /// signing.
+    pub fn signed_message(&self) -> Vec<u8> {
+        let client_data_hash = Sha256::digest(self.client_data_json.as_bytes());
+        [&self.authenticator_data[..], &client_data_hash[..]].concat()
+    }
+
+    /// Returns `true` if the assertion is over `challenge` and signed by `key`.
+    pub fn verify(&self, challenge: &[u8], key: &P256PublicKey) -> bool {
+        let expected = format!(r#""challenge":"{}""#, URL_SAFE_NO_PAD.encode(challenge));
+        if !self.client_data_json[self.challenge_index..].starts_with(&expected) {
+            return false
+        }
+        let mut bytes = [0; 64];
+        self.r.to_big_endian(&mut bytes[..32]);
+        self.s.to_big_endian(&mut bytes[32..]);
+        let (Ok(signature), Ok(key)) = (Signature::from_slice(&bytes), key.verifying_key()) else {
+            return false
+        };
+        key.verify(&self.signed_message(), &signature).is_ok()
+    }
+
+    /// ABI encodes the assertion as the `WebAuthnAuth` struct
+    /// `(bytes authenticatorData, string clientDataJSON, uint256 challengeIndex, uint256
+    /// typeIndex, uint256 r, uint256 s)`.
+    pub fn abi_encode(&self) -> Bytes {
+        encode(&[Token::Tuple(vec![
+            Token::Bytes(self.authenticator_data.to_vec()),
+            Token::String(self.client_data_json.clone()),
+            Token::Uint(self.challenge_index.into()),
+            Token::Uint(self.type_index.into()),
+            Token::Uint(self.r),
+            Token::Uint(self.s),
+        ])])
+        .into()
+    }
+
+    /// Returns the 160 byte input of the [`P256_VERIFY_PRECOMPILE`], which returns `1` as a
+    /// 32 byte word if the signature is valid.
+    pub fn precompile_input(&self, key: &P256PublicKey) -> Bytes {
+        let mut input = Vec::with_capacity(160);
+        input.extend_from_slice(&Sha256::digest(self.signed_message()));
+        let mut word = [0; 32];
+        self.r.to_big_endian(&mut word);
+        input.extend_from_slice(&word);
+        self.s.to_big_endian(&mut word);
+        input.extend_from_slice(&word);
+        input.extend_from_slice(&key.to_bytes());
+        input.into()
+    }
+}
+
+/// Signs challenges with a P-256 passkey.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait PasskeySigner: fmt::Debug {
+    /// The error returned when signing fails
+    type Error: std::error::Error;
+
+    /// Returns the public key of the passkey
+    fn public_key(&self) -> P256PublicKey;
+
+    /// Asks the authenticator for an assertion over `challenge`
+    async fn sign_challenge(&self, challenge: &[u8]) -> Result<WebAuthnAssertion, Self::Error>;
+
+    /// Signs the hash of the ERC-4337 user operation for the `entry_point` on `chain_id`, returning
+    /// the ABI encoded [`WebAuthnAssertion`].
+    ///
+    /// Accounts with several owners usually expect the assertion to be wrapped with the index of
+    /// the signing owner.
+    async fn sign_user_operation(
+        &self,
+        op: &UserOperation,
+        entry_point: Address,
+        chain_id: u64,
+    ) -> Result<Bytes, Self::Error> {
+        let hash = op.user_op_hash(entry_point, chain_id);
+        Ok(self.sign_challenge(hash.as_bytes()).await?.abi_encode())
+    }
+}
+
+/// A passkey whose P-256 key is held in memory, producing the same assertions as a platform
+/// authenticator.
+///
+/// Useful for tests and for accounts which are controlled by a backend.
+///
+/// # Example
+///
+/// ```
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// use ethers_core::rand::thread_rng;
+/// use ethers_signers::passkey::{LocalPasskey, PasskeySigner};
+///
+/// let passkey = LocalPasskey::random(&mut thread_rng(), "example.com", "https://example.com");
+/// let challenge = [1u8; 32];
+/// let assertion = passkey.sign_challenge(&challenge).await?;
+/// assert!(assertion.verify(&challenge, &passkey.public_key()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LocalPasskey {
+    key: SigningKey,
+    rp_id: String,
+    origin: String,
+}
+
+impl LocalPasskey {
+    /// Creates a passkey for the relying party `rp_id` which asserts for the `origin`.
+    pub fn new(key: SigningKey, rp_id: impl Into<String>, origin: impl Into<String>) -> Self {
+        Self { key, rp_id: rp_id.into(), origin: origin.into() }
+    }
+
+    /// Creates a passkey with a random key.
+    pub fn random<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        rp_id: impl Into<String>,
+        origin: impl Into<String>,
+    ) -> Self {
+        Self::new(SigningKey::random(rng), rp_id, origin)
+    }
+
+    /// Creates a passkey from a big-endian encoded 32 byte secret key.
+    pub fn from_bytes(
+        bytes: &[u8],
+        rp_id: impl Into<String>,
+        origin: impl Into<String>,
+    ) -> Result<Self, PasskeyError> {
+        Ok(Self::new(SigningKey::from_slice(bytes)?, rp_id, origin))
+    }
+
+    fn authenticator_data(&self) -> Vec<u8> {
+        let mut data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+        data.push(FLAGS_UP_UV);
+        // a sign count of zero means the authenticator does not count signatures
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data
+    }
+}
+
+// do not log the private key
+impl fmt::Debug for LocalPasskey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalPasskey")
+            .field("public_key", &self.public_key())
+            .field("rp_id", &self.rp_id)
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl PasskeySigner for LocalPasskey {
+    type Error = PasskeyError;
+
+    fn public_key(&self) -> P256PublicKey {
+        P256PublicKey::from(self.key.verifying_key())
+    }
+
+    async fn sign_challenge(&self, challenge: &[u8]) -> Result<WebAuthnAssertion, Self::Error> {
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"{}","crossOrigin":false}}"#,
+            URL_SAFE_NO_PAD.encode(challenge),
+            self.origin
+        );
+        let authenticator_data = self.authenticator_data();
+        let client_data_hash = Sha256::digest(client_data_json.as_bytes());
+        let signature: Signature =
+            self.key.try_sign(&[&authenticator_data[..], &client_data_hash[..]].concat())?;
+        WebAuthnAssertion::new(authenticator_data, client_data_json, &signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::abi::{decode, ParamType};
+
+    fn passkey() -> LocalPasskey {
+        LocalPasskey::from_bytes(&[7; 32], "example.com", "https://example.com").unwrap()
+    }
+
+    #[tokio::test]
+    async fn signs_webauthn_assertion() {
+        let passkey = passkey();
+        let challenge = H256::repeat_byte(0xab);
+        let assertion = passkey.sign_challenge(challenge.as_bytes()).await.unwrap();
+
+        assert!(assertion.verify(challenge.as_bytes(), &passkey.public_key()));
+        assert!(!assertion.verify(&[0; 32], &passkey.public_key()));
+        assert_eq!(assertion.type_index, 1);
+        assert_eq!(
+            &assertion.client_data_json[assertion.challenge_index..][..13],
+            r#""challenge":""#
+        );
+        assert_eq!(assertion.authenticator_data.len(), 37);
+        // low s
+        let half_order = U256::from_str_radix(
+            "7fffffff800000007fffffffffffffffde737d56d38bcf4279dce5617e3192a8",
+            16,
+        )
+        .unwrap();
+        assert!(assertion.s <= half_order);
+
+        let input = assertion.precompile_input(&passkey.public_key());
+        assert_eq!(input.len(), 160);
+        assert_eq!(&input[96..], &passkey.public_key().to_bytes()[..]);
+    }
+
+    #[tokio::test]
+    async fn encodes_webauthn_auth() {
+        let assertion = passkey().sign_challenge(&[1; 32]).await.unwrap();
+        let tokens = decode(
+            &[ParamType::Tuple(vec![
+                ParamType::Bytes,
+                ParamType::String,
+                ParamType::Uint(256),
+                ParamType::Uint(256),
+                ParamType::Uint(256),
+                ParamType::Uint(256),
+            ])],
+            &assertion.abi_encode(),
+        )
+        .unwrap();
+        let fields = tokens[0].clone().into_tuple().unwrap();
+        assert_eq!(fields[1], Token::String(assertion.client_data_json.clone()));
+        assert_eq!(fields[5], Token::Uint(assertion.s));
+    }
+
+    #[test]
+    fn parses_public_key() {
+        let key = passkey().public_key();
+        let mut sec1 = vec![4];
+        sec1.extend_from_slice(&key.to_bytes());
+        assert_eq!(P256PublicKey::from_sec1_bytes(&sec1).unwrap(), key);
+        assert!(P256PublicKey::from_sec1_bytes(&[4; 65]).is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! YubiKey PIV Signer
+//!
+//! The PIV applet of a YubiKey only supports RSA and NIST curve keys, so it cannot hold the
+//! secp256k1 key of an Ethereum account itself. Instead, a P-256 key agreement key is generated on
+//! the card and used to wrap a secp256k1 key with ECIES: every signature requires an ECDH operation
+//! on the card, subject to its PIN and touch policies, to unwrap the key for the duration of the
+//! signature. The wrapped key can be stored anywhere, it is useless without the card.
+
+use crate::{PlatformKey, PlatformKeyError, PlatformKeystore, Wallet};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use ethers_core::rand::{thread_rng, CryptoRng, RngCore};
+use hkdf::Hkdf;
+use p256::{ecdh::EphemeralSecret, elliptic_curve::sec1::ToEncodedPoint, EncodedPoint, PublicKey};
+use sha2::Sha256;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use yubikey::{
+    piv::{self, AlgorithmId, SlotId},
+    Buffer, MgmKey, YubiKey,
+};
+use zeroize::Zeroize;
+
+pub use yubikey::{PinPolicy, TouchPolicy};
+
+/// A wallet whose private key is wrapped by a key of a YubiKey PIV slot
+pub type PivWallet = Wallet<PlatformKey<PivKeystore>>;
+
+/// The length of an uncompressed P-256 point
+const POINT_LENGTH: usize = 65;
+/// The length of the ChaCha20-Poly1305 nonce
+const NONCE_LENGTH: usize = 12;
+/// The HKDF info binding the wrapping key to its purpose
+const WRAPPING_KEY_INFO: &[u8] = b"ethers-rs piv secp256k1 key wrapping";
+
+/// When the PIN is sent to the card before an operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinCache {
+    /// Before every operation. Use with [`PinPolicy::Always`], which makes the card require the
+    /// PIN for every use of the key.
+    Always,
+    /// Before the first operation only, relying on the card to remember the verification for as
+    /// long as the PIV session lasts. The PIN is dropped after it was verified.
+    Once,
+    /// Before the first operation and again once the verification is older than the duration.
+    For(Duration),
+}
+
+/// Error thrown by the [`PivKeystore`]
+#[derive(Debug, thiserror::Error)]
+pub enum PivError {
+    /// The card rejected the operation, e.g. a wrong PIN or a missing touch
+    #[error(transparent)]
+    YubiKey(#[from] yubikey::Error),
+    /// The PIN is required but was already dropped by [`PinCache::Once`]
+    #[error("the PIN is no longer cached")]
+    PinRequired,
+    /// The slot does not hold a P-256 key
+    #[error("the slot does not hold a P-256 key")]
+    InvalidPublicKey,
+    /// The wrapped key is malformed or was wrapped by another key
+    #[error("failed to unwrap key")]
+    InvalidWrappedKey,
+}
+
+/// Wraps secp256k1 keys with the P-256 key of a YubiKey PIV slot.
+///
+/// Use it as the [`PlatformKeystore`] of a [`PlatformKey`], see [`PivKeystore::generate_wallet`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_signers::{PinCache, PinPolicy, PivKeystore, Signer, TouchPolicy};
+/// use yubikey::{piv::SlotId, MgmKey, YubiKey};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let keystore = PivKeystore::generate(
+///     YubiKey::open()?,
+///     SlotId::KeyManagement,
+///     MgmKey::default(),
+///     "123456",
+///     PinPolicy::Once,
+///     TouchPolicy::Cached,
+///     PinCache::Once,
+/// )?;
+/// // store the public key and the wrapped key to load the wallet again
+/// let public_key = keystore.public_key();
+/// let wallet = keystore.generate_wallet()?;
+/// let wrapped = wallet.signer().wrapped_key().to_vec();
+///
+/// // the card blinks until it is touched
+/// let signature = wallet.sign_message("hello world").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PivKeystore {
+    yubikey: Mutex<YubiKey>,
+    slot: SlotId,
+    public_key: PublicKey,
+    pin: Mutex<Pin>,
+}
+
+struct Pin {
+    pin: Option<Buffer>,
+    cache: PinCache,
+    verified_at: Option<Instant>,
+}
+
+impl Pin {
+    fn verify(&mut self, yubikey: &mut YubiKey) -> Result<(), PivError> {
+        let expired = match (self.cache, self.verified_at) {
+            (_, None) | (PinCache::Always, _) => true,
+            (PinCache::Once, Some(_)) => false,
+            (PinCache::For(duration), Some(at)) => at.elapsed() >= duration,
+        };
+        if !expired {
+            return Ok(())
+        }
+        let pin = self.pin.as_ref().ok_or(PivError::PinRequired)?;
+        yubikey.verify_pin(pin)?;
+        self.verified_at = Some(Instant::now());
+        if self.cache == PinCache::Once {
+            self.pin = None;
+        }
+        Ok(())
+    }
+}
+
+impl PivKeystore {
+    /// Generates a new P-256 key in `slot`, replacing the key of the slot.
+    ///
+    /// The `pin_policy` and `touch_policy` are enforced by the card for every signature. Changing
+    /// the key of a slot requires the management key of the card.
+    pub fn generate(
+        mut yubikey: YubiKey,
+        slot: SlotId,
+        mgm_key: MgmKey,
+        pin: &str,
+        pin_policy: PinPolicy,
+        touch_policy: TouchPolicy,
+        cache: PinCache,
+    ) -> Result<Self, PivError> {
+        yubikey.authenticate(mgm_key)?;
+        let spki =
+            piv::generate(&mut yubikey, slot, AlgorithmId::EccP256, pin_policy, touch_policy)?;
+        let public_key = PublicKey::from_sec1_bytes(spki.subject_public_key.raw_bytes())
+            .map_err(|_| PivError::InvalidPublicKey)?;
+        Ok(Self::new(yubikey, slot, public_key, pin, cache))
+    }
+
+    /// Uses the existing key of `slot`, whose public key was returned by
+    /// [`PivKeystore::public_key`] when the key was generated.
+    pub fn open(
+        yubikey: YubiKey,
+        slot: SlotId,
+        public_key: &[u8],
+        pin: &str,
+        cache: PinCache,
+    ) -> Result<Self, PivError> {
+        let public_key =
+            PublicKey::from_sec1_bytes(public_key).map_err(|_| PivError::InvalidPublicKey)?;
+        Ok(Self::new(yubikey, slot, public_key, pin, cache))
+    }
+
+    fn new(
+        yubikey: YubiKey,
+        slot: SlotId,
+        public_key: PublicKey,
+        pin: &str,
+        cache: PinCache,
+    ) -> Self {
+        let pin = Pin { pin: Some(Buffer::new(pin.as_bytes().to_vec())), cache, verified_at: None };
+        Self { yubikey: Mutex::new(yubikey), slot, public_key, pin: Mutex::new(pin) }
+    }
+
+    /// Returns the SEC1 encoded public key of the slot.
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_key.to_encoded_point(false).as_bytes().to_vec()
+    }
+
+    /// Returns the slot of the key.
+    pub fn slot(&self) -> SlotId {
+        self.slot
+    }
+
+    /// Generates a new secp256k1 key wrapped by the slot's key.
+    pub fn generate_wallet(self) -> Result<PivWallet, PlatformKeyError<PivError>> {
+        Ok(PlatformKey::generate(self, &mut thread_rng())?.into())
+    }
+
+    /// Loads a secp256k1 key that was wrapped by the slot's key.
+    ///
+    /// This requires the PIN and, depending on the touch policy, a touch.
+    pub fn load_wallet(self, wrapped: Vec<u8>) -> Result<PivWallet, PlatformKeyError<PivError>> {
+        Ok(PlatformKey::from_wrapped(self, wrapped)?.into())
+    }
+}
+
+impl PlatformKeystore for PivKeystore {
+    type Error = PivError;
+
+    fn wrap(&self, secret: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        Ok(seal(&self.public_key, secret, &mut thread_rng()))
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        if wrapped.len() <= POINT_LENGTH + NONCE_LENGTH {
+            return Err(PivError::InvalidWrappedKey)
+        }
+        let mut yubikey = self.yubikey.lock().unwrap();
+        self.pin.lock().unwrap().verify(&mut yubikey)?;
+        // ECDH with the ephemeral public key, the card returns the x coordinate of the shared point
+        let shared = piv::decrypt_data(
+            &mut yubikey,
+            &wrapped[..POINT_LENGTH],
+            AlgorithmId::EccP256,
+            self.slot,
+        )?;
+        open(&shared, wrapped)
+    }
+
+    fn attestation(&self) -> Option<Vec<u8>> {
+        let mut yubikey = self.yubikey.lock().unwrap();
+        piv::attest(&mut yubikey, self.slot).ok().map(|certificate| certificate.to_vec())
+    }
+}
+
+impl std::fmt::Debug for PivKeystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PivKeystore")
+            .field("slot", &self.slot)
+            .field("public_key", &hex::encode(self.public_key()))
+            .finish_non_exhaustive()
+    }
+}
+
+fn wrapping_key(shared: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, shared)
+        .expand(WRAPPING_KEY_INFO, &mut key)
+        .expect("32 bytes is a valid output length");
+    key
+}
+
+/// Encrypts `secret` to `public_key` as `ephemeral public key || nonce || ciphertext`.
+fn seal<R: CryptoRng + RngCore>(public_key: &PublicKey, secret: &[u8], rng: &mut R) -> Vec<u8> {
+    let ephemeral = EphemeralSecret::random(&mut *rng);
+    let shared = ephemeral.diffie_hellman(public_key);
+    let mut key = wrapping_key(shared.raw_secret_bytes());
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rng.fill_bytes(&mut nonce);
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .encrypt(Nonce::from_slice(&nonce), secret)
+        .expect("encryption with a valid key does not fail");
+    key.zeroize();
+
+    let point = EncodedPoint::from(ephemeral.public_key());
+    let mut wrapped = Vec::with_capacity(POINT_LENGTH + NONCE_LENGTH + ciphertext.len());
+    wrapped.extend_from_slice(point.as_bytes());
+    wrapped.extend_from_slice(&nonce);
+    wrapped.extend_from_slice(&ciphertext);
+    wrapped
+}
+
+/// Decrypts a key encrypted by [`seal`], given the shared secret of the ECDH with its ephemeral
+/// public key.
+fn open(shared: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, PivError> {
+    let (nonce, ciphertext) = wrapped[POINT_LENGTH..].split_at(NONCE_LENGTH);
+    let mut key = wrapping_key(shared);
+    let secret = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| PivError::InvalidWrappedKey);
+    key.zeroize();
+    secret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::{ecdh::diffie_hellman, SecretKey};
+
+    #[test]
+    fn wraps_keys() {
+        // stands in for the key of the slot
+        let card = SecretKey::random(&mut thread_rng());
+        let wrapped = seal(&card.public_key(), &[42; 32], &mut thread_rng());
+        assert_eq!(wrapped[0], 0x04);
+
+        let ephemeral = PublicKey::from_sec1_bytes(&wrapped[..POINT_LENGTH]).unwrap();
+        let shared = diffie_hellman(card.to_nonzero_scalar(), ephemeral.as_affine());
+        assert_eq!(open(shared.raw_secret_bytes(), &wrapped).unwrap(), [42; 32]);
+
+        let other = SecretKey::random(&mut thread_rng());
+        let shared = diffie_hellman(other.to_nonzero_scalar(), ephemeral.as_affine());
+        assert!(matches!(
+            open(shared.raw_secret_bytes(), &wrapped),
+            Err(PivError::InvalidWrappedKey)
+        ));
+    }
+}
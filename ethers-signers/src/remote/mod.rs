@@ -0,0 +1,370 @@
+//! Remote JSON-RPC Signer, e.g. Consensys web3signer or geth's Clef
+
+use ethers_core::{
+    types::{
+        transaction::{
+            eip2718::{TypedTransaction, TypedTransactionError},
+            eip712::{Eip712, TypedData},
+            eip7702::Authorization,
+        },
+        Address, Signature, SignatureError,
+    },
+    utils::rlp::Rlp,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION},
+    Certificate, Identity,
+};
+use serde_json::{json, Value};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tracing::{debug, instrument};
+
+/// The JSON-RPC API spoken by the remote signer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteSignerApi {
+    /// The `eth_*` signing methods of [web3signer](https://docs.web3signer.consensys.net)
+    Web3Signer,
+    /// The `account_*` methods of geth's [Clef](https://geth.ethereum.org/docs/tools/clef/introduction)
+    Clef,
+}
+
+/// Errors produced by the [RemoteSigner]
+#[derive(thiserror::Error, Debug)]
+pub enum RemoteSignerError {
+    /// Error when sending the request or decoding the response
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// Error returned by the remote signer, e.g. because the request was rejected
+    #[error("remote signer returned error {code}: {message}")]
+    Rpc {
+        /// The JSON-RPC error code
+        code: i64,
+        /// The error message
+        message: String,
+    },
+    /// The response does not have the expected shape
+    #[error("unexpected response: {0}")]
+    UnexpectedResponse(String),
+    /// The remote signer changed the transaction before signing it
+    #[error("the remote signer modified the transaction before signing it")]
+    TransactionModified,
+    /// The signing scheme is not exposed by remote signers
+    #[error("{0} is not supported by remote signers")]
+    Unsupported(&'static str),
+    /// Error when (de)serializing requests
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// Error when decoding hex data returned by the remote signer
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    /// Error when decoding a signature returned by the remote signer
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+    /// Error when decoding a transaction signed by the remote signer
+    #[error(transparent)]
+    Transaction(#[from] TypedTransactionError),
+}
+
+/// An ethers Signer that forwards signing requests to a remote JSON-RPC signer which holds the
+/// keys, such as Consensys web3signer or geth's Clef.
+///
+/// TLS, e.g. a private certificate authority or a client certificate, is configured on the
+/// [reqwest::Client] that is passed in, see [`RemoteSigner::tls_client`]. Authentication headers
+/// are added with [`RemoteSigner::with_header`] and friends.
+///
+/// ```no_run
+/// use ethers_signers::{RemoteSigner, RemoteSignerApi, Signer};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let ca = std::fs::read("ca.pem")?;
+/// let client = RemoteSigner::tls_client(&ca, None)?;
+/// let address = "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf".parse()?;
+///
+/// let signer =
+///     RemoteSigner::new(client, "https://web3signer:9000", RemoteSignerApi::Web3Signer, address, 1)
+///         .with_bearer_auth("token");
+/// let sig = signer.sign_message("hello world").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    api: RemoteSignerApi,
+    headers: HeaderMap,
+    address: Address,
+    chain_id: u64,
+    id: AtomicU64,
+}
+
+impl fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the headers are omitted since they usually contain credentials
+        f.debug_struct("RemoteSigner")
+            .field("url", &self.url)
+            .field("api", &self.api)
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    /// Instantiates a signer for the `address` managed by the remote signer at `url`.
+    pub fn new(
+        client: reqwest::Client,
+        url: impl Into<String>,
+        api: RemoteSignerApi,
+        address: Address,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            client,
+            url: url.into(),
+            api,
+            headers: HeaderMap::new(),
+            address,
+            chain_id,
+            id: AtomicU64::new(1),
+        }
+    }
+
+    /// Builds a client that only trusts the PEM encoded `ca` certificate and optionally
+    /// authenticates with the PEM encoded client certificate and private key in `identity`.
+    pub fn tls_client(
+        ca: &[u8],
+        identity: Option<&[u8]>,
+    ) -> Result<reqwest::Client, RemoteSignerError> {
+        let mut builder = reqwest::Client::builder()
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(Certificate::from_pem(ca)?);
+        if let Some(identity) = identity {
+            builder = builder.identity(Identity::from_pem(identity)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Adds a header that is sent with every request.
+    #[must_use]
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Authenticates every request with a bearer token.
+    ///
+    /// # Panics
+    ///
+    /// If the token contains characters that are not valid in a header.
+    #[must_use]
+    pub fn with_bearer_auth(self, token: impl fmt::Display) -> Self {
+        let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("token is a valid header value");
+        value.set_sensitive(true);
+        self.with_header(AUTHORIZATION, value)
+    }
+
+    /// Returns the API spoken by the remote signer
+    pub fn api(&self) -> RemoteSignerApi {
+        self.api
+    }
+
+    /// Lists the accounts managed by the remote signer.
+    pub async fn accounts(&self) -> Result<Vec<Address>, RemoteSignerError> {
+        let method = match self.api {
+            RemoteSignerApi::Web3Signer => "eth_accounts",
+            RemoteSignerApi::Clef => "account_list",
+        };
+        Ok(serde_json::from_value(self.request(method, json!([])).await?)?)
+    }
+
+    /// Signs EIP-712 typed data.
+    ///
+    /// Remote signers need the full typed data to apply their signing rules, which is why this
+    /// takes a [TypedData] rather than any [Eip712] payload.
+    pub async fn sign_typed_data_json(
+        &self,
+        payload: &TypedData,
+    ) -> Result<Signature, RemoteSignerError> {
+        let method = match self.api {
+            RemoteSignerApi::Web3Signer => "eth_signTypedData",
+            RemoteSignerApi::Clef => "account_signTypedData",
+        };
+        let signature = self.request(method, json!([self.address, payload])).await?;
+        parse_signature(&signature)
+    }
+
+    /// Sends a JSON-RPC request to the remote signer and returns its result.
+    #[instrument(err, skip(self, params))]
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, RemoteSignerError> {
+        let id = self.id.fetch_add(1, Ordering::Relaxed);
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        debug!("Dispatching {method} to remote signer");
+
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(RemoteSignerError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            })
+        }
+        match response.get_mut("result") {
+            Some(result) => Ok(result.take()),
+            None => Err(RemoteSignerError::UnexpectedResponse(response.to_string())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl super::Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let data = format!("0x{}", hex::encode(message.as_ref()));
+        let signature = match self.api {
+            RemoteSignerApi::Web3Signer => {
+                self.request("eth_sign", json!([self.address, data])).await?
+            }
+            RemoteSignerApi::Clef => {
+                self.request("account_signData", json!(["text/plain", self.address, data])).await?
+            }
+        };
+        parse_signature(&signature)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        tx.set_from(self.address);
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+
+        let method = match self.api {
+            RemoteSignerApi::Web3Signer => "eth_signTransaction",
+            RemoteSignerApi::Clef => "account_signTransaction",
+        };
+        let response = self.request(method, json!([tx])).await?;
+        decode_signed_transaction(&tx, &response)
+    }
+
+    /// Remote signers require the full typed data, use
+    /// [`RemoteSigner::sign_typed_data_json`] instead.
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("signing an Eip712 digest"))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl super::AuthorizationSigner for RemoteSigner {
+    async fn sign_authorization(
+        &self,
+        _authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("EIP-7702 authorizations"))
+    }
+}
+
+#[async_trait::async_trait]
+impl super::IntendedValidatorSigner for RemoteSigner {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        _validator: Address,
+        _data: S,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("EIP-191 version 0x00"))
+    }
+}
+
+fn parse_signature(value: &Value) -> Result<Signature, RemoteSignerError> {
+    let signature =
+        value.as_str().ok_or_else(|| RemoteSignerError::UnexpectedResponse(value.to_string()))?;
+    Ok(Signature::from_str(signature)?)
+}
+
+/// Decodes the signature from the raw transaction returned by the remote signer, which is either
+/// the raw transaction itself (web3signer) or an object with a `raw` field (Clef).
+fn decode_signed_transaction(
+    tx: &TypedTransaction,
+    response: &Value,
+) -> Result<Signature, RemoteSignerError> {
+    let raw = response
+        .as_str()
+        .or_else(|| response["raw"].as_str())
+        .ok_or_else(|| RemoteSignerError::UnexpectedResponse(response.to_string()))?;
+    let raw = hex::decode(raw.strip_prefix("0x").unwrap_or(raw))?;
+    let (signed, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw))?;
+
+    // the signature is only valid for the transaction that was actually signed
+    if signed.sighash() != tx.sighash() {
+        return Err(RemoteSignerError::TransactionModified)
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LocalWallet, Signer};
+    use ethers_core::types::TransactionRequest;
+
+    #[tokio::test]
+    async fn decodes_signed_transactions() {
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let tx: TypedTransaction = TransactionRequest::pay(Address::zero(), 100)
+            .from(wallet.address())
+            .nonce(0)
+            .gas(21000)
+            .gas_price(1)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction(&tx).await.unwrap();
+        let raw = tx.rlp_signed(&signature);
+
+        // web3signer
+        assert_eq!(decode_signed_transaction(&tx, &json!(raw)).unwrap(), signature);
+        // clef
+        let response = json!({ "raw": raw, "tx": {} });
+        assert_eq!(decode_signed_transaction(&tx, &response).unwrap(), signature);
+
+        let mut modified = tx.clone();
+        modified.set_nonce(1);
+        assert!(matches!(
+            decode_signed_transaction(&modified, &json!(raw)),
+            Err(RemoteSignerError::TransactionModified)
+        ));
+    }
+}
@@ -0,0 +1,390 @@
+//! Known-answer tests for signing transactions and typed data.
+//!
+//! The vectors are plain data, so that signers which are not built on this crate, like HSMs or
+//! MPC services, can be checked against them as well. [`Signer`] implementations can be checked
+//! against all vectors at once with [`check_signer`], which is meant to be called from their
+//! tests after importing the vector's private key:
+//!
+//! ```
+//! use ethers_signers::{test_vectors, LocalWallet};
+//!
+//! # async fn foo() {
+//! let signer: LocalWallet = test_vectors::EIP155_EXAMPLE.private_key.parse().unwrap();
+//! // panics if any vector that is signed with the signer's key produces a different signature
+//! test_vectors::assert_signer(&signer).await;
+//! # }
+//! ```
+//!
+//! Signatures are deterministic ([RFC 6979](https://datatracker.ietf.org/doc/html/rfc6979)) and
+//! have a low `s` value, signers that use random nonces can only be checked with
+//! [`TransactionVector::recovers`].
+
+use crate::{LocalWallet, Signer};
+use ethers_core::{
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::TypedData},
+        Address, Bytes, Signature, H256, U256,
+    },
+    utils::{hex, rlp::Rlp},
+};
+use thiserror::Error;
+
+/// A transaction, signed with a known private key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionVector {
+    /// The name of the vector
+    pub name: &'static str,
+    /// The hex encoded private key that signs the transaction
+    pub private_key: &'static str,
+    /// The hex encoded signing payload, the RLP encoding of the unsigned transaction prefixed
+    /// with its EIP-2718 type
+    pub unsigned: &'static str,
+    /// The hex encoded keccak256 hash of the signing payload
+    pub signing_hash: &'static str,
+    /// The `v` value of the signature, the EIP-155 `v` for legacy transactions and the y-parity
+    /// for typed transactions
+    pub v: u64,
+    /// The hex encoded `r` value of the signature
+    pub r: &'static str,
+    /// The hex encoded `s` value of the signature
+    pub s: &'static str,
+    /// The hex encoded signed transaction, as broadcast with `eth_sendRawTransaction`
+    pub signed: &'static str,
+}
+
+/// The example of [EIP-155](https://eips.ethereum.org/EIPS/eip-155#example).
+pub const EIP155_EXAMPLE: TransactionVector = TransactionVector {
+    name: "eip155-example",
+    private_key: "4646464646464646464646464646464646464646464646464646464646464646",
+    unsigned: "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080",
+    signing_hash: "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53",
+    v: 37,
+    r: "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+    s: "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+    signed: "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+};
+
+/// An EIP-2930 transfer of 1 ether on mainnet with an access list.
+pub const EIP2930_TRANSFER: TransactionVector = TransactionVector {
+    name: "eip2930-transfer",
+    private_key: "4646464646464646464646464646464646464646464646464646464646464646",
+    unsigned: "01f86401808504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080f838f7943535353535353535353535353535353535353535e1a00000000000000000000000000000000000000000000000000000000000000000",
+    signing_hash: "7db907ddeb9f4657e4ac819716fb1190cd92b9d96519a302ca0c2c3df6382232",
+    v: 1,
+    r: "c90ee30db0c4f9404e39b3d96c526f90476fd31251107856698e1742279efcf0",
+    s: "2c910017bef18a33b7d6df64abb91f1b46c6e9165a5b520013706b25eebe63ff",
+    signed: "01f8a701808504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080f838f7943535353535353535353535353535353535353535e1a0000000000000000000000000000000000000000000000000000000000000000001a0c90ee30db0c4f9404e39b3d96c526f90476fd31251107856698e1742279efcf0a02c910017bef18a33b7d6df64abb91f1b46c6e9165a5b520013706b25eebe63ff",
+};
+
+/// An EIP-1559 transfer of 1 ether on mainnet.
+pub const EIP1559_TRANSFER: TransactionVector = TransactionVector {
+    name: "eip1559-transfer",
+    private_key: "4646464646464646464646464646464646464646464646464646464646464646",
+    unsigned: "02f00180843b9aca0085174876e800825208943535353535353535353535353535353535353535880de0b6b3a764000080c0",
+    signing_hash: "bfbcb21ef85794806120d9289a00de85b28dbd68e7ff8d4f9a7d057d9b3ce032",
+    v: 1,
+    r: "84ee313a3aaca8747b7161e697dc4a30dedd03a75597e835efb756823e98f9fe",
+    s: "633e6548426a2acdd295ee425a40cc6260f3a16d836e9b845a56b9cadd0f004a",
+    signed: "02f8730180843b9aca0085174876e800825208943535353535353535353535353535353535353535880de0b6b3a764000080c001a084ee313a3aaca8747b7161e697dc4a30dedd03a75597e835efb756823e98f9fea0633e6548426a2acdd295ee425a40cc6260f3a16d836e9b845a56b9cadd0f004a",
+};
+
+/// An EIP-4844 transaction on mainnet carrying a single blob.
+///
+/// Blob transactions can not be built with [`TypedTransaction`] yet, so this vector is only
+/// checked by [`check_signer`] through its signing hash.
+pub const EIP4844_BLOB: TransactionVector = TransactionVector {
+    name: "eip4844-blob",
+    private_key: "4646464646464646464646464646464646464646464646464646464646464646",
+    unsigned: "03f84f0180843b9aca0085174876e8008252089435353535353535353535353535353535353535358080c0843b9aca00e1a00100000000000000000000000000000000000000000000000000000000000001",
+    signing_hash: "db7b67b28abaf4b2aaf4f19c85e4a5b3f7956e68283e2e3e70c3aeca078321d1",
+    v: 1,
+    r: "d83854fab9233fe3f5e6003b051955537734d29517e7aaa7cb524bc88ec7e4fe",
+    s: "20a3f0009edfacd925aec33a4ef24e5cafc30478bb10c55674adac20428ba8cc",
+    signed: "03f8920180843b9aca0085174876e8008252089435353535353535353535353535353535353535358080c0843b9aca00e1a0010000000000000000000000000000000000000000000000000000000000000101a0d83854fab9233fe3f5e6003b051955537734d29517e7aaa7cb524bc88ec7e4fea020a3f0009edfacd925aec33a4ef24e5cafc30478bb10c55674adac20428ba8cc",
+};
+
+/// All transaction vectors.
+pub const TRANSACTION_VECTORS: &[TransactionVector] =
+    &[EIP155_EXAMPLE, EIP2930_TRANSFER, EIP1559_TRANSFER, EIP4844_BLOB];
+
+impl TransactionVector {
+    /// Returns a wallet holding the vector's private key.
+    pub fn wallet(&self) -> LocalWallet {
+        self.private_key.parse().expect("test vector keys are valid")
+    }
+
+    /// Returns the address that signs the transaction.
+    pub fn address(&self) -> Address {
+        self.wallet().address()
+    }
+
+    /// Returns the hash that is signed.
+    pub fn signing_hash(&self) -> H256 {
+        H256(decode(self.signing_hash))
+    }
+
+    /// Returns the expected signature.
+    pub fn signature(&self) -> Signature {
+        Signature {
+            r: U256::from_big_endian(&decode(self.r)),
+            s: U256::from_big_endian(&decode(self.s)),
+            v: self.v,
+        }
+    }
+
+    /// Returns the signed transaction.
+    pub fn signed(&self) -> Bytes {
+        hex::decode(self.signed).expect("test vectors are valid hex").into()
+    }
+
+    /// Returns the unsigned transaction, or `None` for transaction types that can not be built
+    /// with [`TypedTransaction`].
+    pub fn transaction(&self) -> Option<TypedTransaction> {
+        let signed = self.signed();
+        TypedTransaction::decode_signed(&Rlp::new(&signed)).ok().map(|(tx, _)| tx)
+    }
+
+    /// Checks that `signature` is the expected signature.
+    ///
+    /// `v` may be given in any of the encodings [`Signature::recovery_id`] understands.
+    pub fn verify(&self, signature: &Signature) -> Result<(), Box<VectorMismatch>> {
+        verify(self.name, self.signature(), *signature)
+    }
+
+    /// Returns `true` if `signature` is a valid signature of the vector's address over the signing
+    /// hash, without requiring it to be the deterministic one.
+    pub fn recovers(&self, signature: &Signature) -> bool {
+        signature.recover(self.signing_hash()).map_or(false, |address| address == self.address())
+    }
+}
+
+/// EIP-712 typed data, signed with a known private key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypedDataVector {
+    /// The name of the vector
+    pub name: &'static str,
+    /// The hex encoded private key that signs the typed data
+    pub private_key: &'static str,
+    /// The typed data as JSON, as passed to `eth_signTypedData_v4`
+    pub typed_data: &'static str,
+    /// The hex encoded EIP-712 hash of the typed data
+    pub signing_hash: &'static str,
+    /// The `v` value of the signature
+    pub v: u64,
+    /// The hex encoded `r` value of the signature
+    pub r: &'static str,
+    /// The hex encoded `s` value of the signature
+    pub s: &'static str,
+}
+
+/// The `Mail` example of [EIP-712](https://eips.ethereum.org/EIPS/eip-712), signed with the
+/// private key `keccak256("cow")`.
+pub const EIP712_MAIL: TypedDataVector = TypedDataVector {
+    name: "eip712-mail",
+    private_key: "c85ef7d79691fe79573b1a7064c19c1a9819ebdbd1faaab1a8ec92344438aaf4",
+    typed_data: r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#,
+    signing_hash: "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+    v: 28,
+    r: "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d",
+    s: "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562",
+};
+
+/// All typed data vectors.
+pub const TYPED_DATA_VECTORS: &[TypedDataVector] = &[EIP712_MAIL];
+
+impl TypedDataVector {
+    /// Returns a wallet holding the vector's private key.
+    pub fn wallet(&self) -> LocalWallet {
+        self.private_key.parse().expect("test vector keys are valid")
+    }
+
+    /// Returns the address that signs the typed data.
+    pub fn address(&self) -> Address {
+        self.wallet().address()
+    }
+
+    /// Returns the typed data.
+    pub fn typed_data(&self) -> TypedData {
+        serde_json::from_str(self.typed_data).expect("test vector typed data is valid")
+    }
+
+    /// Returns the hash that is signed.
+    pub fn signing_hash(&self) -> H256 {
+        H256(decode(self.signing_hash))
+    }
+
+    /// Returns the expected signature.
+    pub fn signature(&self) -> Signature {
+        Signature {
+            r: U256::from_big_endian(&decode(self.r)),
+            s: U256::from_big_endian(&decode(self.s)),
+            v: self.v,
+        }
+    }
+
+    /// Checks that `signature` is the expected signature.
+    ///
+    /// `v` may be given in any of the encodings [`Signature::recovery_id`] understands.
+    pub fn verify(&self, signature: &Signature) -> Result<(), Box<VectorMismatch>> {
+        verify(self.name, self.signature(), *signature)
+    }
+}
+
+/// Thrown when a signer does not produce the signature of a test vector
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("{vector}: expected signature {expected}, got {actual}")]
+pub struct VectorMismatch {
+    /// The name of the vector
+    pub vector: &'static str,
+    /// The expected signature
+    pub expected: Signature,
+    /// The signature the signer produced
+    pub actual: Signature,
+}
+
+/// Error thrown by [`check_signer`]
+#[derive(Debug, Error)]
+pub enum ConformanceError<E> {
+    /// Thrown when the signer fails to sign a vector
+    #[error("{vector}: {error}")]
+    Signer {
+        /// The name of the vector
+        vector: &'static str,
+        /// The error of the signer
+        #[source]
+        error: E,
+    },
+    /// Thrown when the signer produces a different signature
+    #[error(transparent)]
+    Mismatch(#[from] Box<VectorMismatch>),
+}
+
+/// Signs all vectors whose private key belongs to `signer` and checks the signatures, returning
+/// the number of checked vectors.
+///
+/// Transactions are signed with [`Signer::sign_transaction`] and typed data with
+/// [`Signer::sign_typed_data`]. Transactions that can not be built with [`TypedTransaction`] are
+/// skipped, signers that sign raw hashes can check them with [`TransactionVector::verify`].
+pub async fn check_signer<S: Signer>(signer: &S) -> Result<usize, ConformanceError<S::Error>> {
+    let address = signer.address();
+    let mut checked = 0;
+    for vector in TRANSACTION_VECTORS.iter().filter(|vector| vector.address() == address) {
+        let Some(tx) = vector.transaction() else { continue };
+        let signature = signer
+            .sign_transaction(&tx)
+            .await
+            .map_err(|error| ConformanceError::Signer { vector: vector.name, error })?;
+        vector.verify(&signature)?;
+        checked += 1;
+    }
+    for vector in TYPED_DATA_VECTORS.iter().filter(|vector| vector.address() == address) {
+        let signature = signer
+            .sign_typed_data(&vector.typed_data())
+            .await
+            .map_err(|error| ConformanceError::Signer { vector: vector.name, error })?;
+        vector.verify(&signature)?;
+        checked += 1;
+    }
+    Ok(checked)
+}
+
+/// Like [`check_signer`], but panics if a vector fails or if no vector is signed with the
+/// signer's key.
+pub async fn assert_signer<S: Signer>(signer: &S) {
+    match check_signer(signer).await {
+        Ok(0) => panic!("no test vector is signed by {:?}", signer.address()),
+        Ok(_) => {}
+        Err(err) => panic!("{err}"),
+    }
+}
+
+fn verify(
+    vector: &'static str,
+    expected: Signature,
+    actual: Signature,
+) -> Result<(), Box<VectorMismatch>> {
+    let parity = |signature: &Signature| signature.recovery_id().ok();
+    if expected.r == actual.r && expected.s == actual.s && parity(&expected) == parity(&actual) {
+        Ok(())
+    } else {
+        Err(Box::new(VectorMismatch { vector, expected, actual }))
+    }
+}
+
+fn decode(value: &str) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(value, &mut bytes).expect("test vectors are valid hex");
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::utils::keccak256;
+
+    #[tokio::test]
+    async fn local_wallet_conforms() {
+        let signer: LocalWallet = EIP155_EXAMPLE.private_key.parse().unwrap();
+        let decodable = TRANSACTION_VECTORS
+            .iter()
+            .filter(|vector| vector.address() == signer.address() && vector.transaction().is_some())
+            .count();
+        let typed_data =
+            TYPED_DATA_VECTORS.iter().filter(|vector| vector.address() == signer.address()).count();
+        assert_eq!(check_signer(&signer).await.unwrap(), decodable + typed_data);
+        // every kind of typed transaction can be decoded, including blob transactions
+        assert_eq!(decodable, TRANSACTION_VECTORS.len());
+        assert_signer(&EIP712_MAIL.wallet()).await;
+
+        for vector in TRANSACTION_VECTORS {
+            let unsigned = hex::decode(vector.unsigned).unwrap();
+            assert_eq!(H256(keccak256(unsigned)), vector.signing_hash(), "{}", vector.name);
+            let signature = vector.wallet().sign_hash(vector.signing_hash()).unwrap();
+            vector.verify(&signature).unwrap();
+            assert!(vector.recovers(&signature));
+        }
+        assert_eq!(
+            EIP712_MAIL.signature().recover(EIP712_MAIL.signing_hash()).unwrap(),
+            EIP712_MAIL.address()
+        );
+    }
+
+    #[test]
+    fn detects_mismatches() {
+        let mut signature = EIP1559_TRANSFER.signature();
+        signature.v = 38;
+        assert!(EIP1559_TRANSFER.verify(&signature).is_ok());
+        signature.s = U256::one();
+        assert_eq!(EIP1559_TRANSFER.verify(&signature).unwrap_err().vector, "eip1559-transfer");
+    }
+}
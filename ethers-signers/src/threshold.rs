@@ -0,0 +1,398 @@
+use crate::to_eip155_v;
+use async_trait::async_trait;
+use ethers_core::{
+    k256::ecdsa::Signature as KSig,
+    rand::{thread_rng, Rng},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
+        Address, Signature, H256, U256,
+    },
+    utils::{hash_intended_validator, hash_message},
+};
+use std::{error::Error, fmt::Debug};
+use thiserror::Error;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// The default maximum number of message rounds of a signing session.
+pub const DEFAULT_MAX_ROUNDS: usize = 16;
+
+/// A message of a threshold signing protocol, exchanged between the parties of a session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundMessage {
+    /// The index of the sending party
+    pub from: u16,
+    /// The index of the receiving party, or `None` for a broadcast to all parties
+    pub to: Option<u16>,
+    /// The protocol round the message belongs to
+    pub round: usize,
+    /// The protocol specific payload
+    pub payload: Vec<u8>,
+}
+
+/// The result of processing the messages of a round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoundOutput {
+    /// The protocol continues with the given messages to the other parties
+    Continue(Vec<RoundMessage>),
+    /// The protocol finished with this party's share of the signature
+    Done(Vec<u8>),
+}
+
+/// One party's share of a threshold ECDSA key, e.g. a GG18, GG20 or CGGMP key share.
+///
+/// Implementations run the party's side of the multi-round signing protocol, while the
+/// [SignatureAggregator] moves messages between the parties and combines the result.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait PartialSigner: Debug + Send + Sync {
+    /// The error of the protocol implementation
+    type Error: Error + Send + Sync + 'static;
+
+    /// Returns the index of this party
+    fn party(&self) -> u16;
+
+    /// Returns the address of the joint public key
+    fn address(&self) -> Address;
+
+    /// Starts signing `digest` in the session `session` and returns the messages of the first
+    /// round.
+    async fn start(&self, session: H256, digest: H256) -> Result<Vec<RoundMessage>, Self::Error>;
+
+    /// Processes the messages of the other parties for `round` of the session.
+    async fn handle(
+        &self,
+        session: H256,
+        round: usize,
+        incoming: Vec<RoundMessage>,
+    ) -> Result<RoundOutput, Self::Error>;
+}
+
+/// Connects a [PartialSigner] to its co-signers and combines the signature shares.
+///
+/// This is usually a client of a coordination service or the co-signers themselves.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait SignatureAggregator: Debug + Send + Sync {
+    /// The error of the transport
+    type Error: Error + Send + Sync + 'static;
+
+    /// Sends this party's `outgoing` messages of `round` and returns the messages addressed to
+    /// this party by the co-signers in the same round.
+    ///
+    /// The first exchange of a session also asks the co-signers to join signing `digest`.
+    async fn exchange(
+        &self,
+        session: H256,
+        digest: H256,
+        round: usize,
+        outgoing: Vec<RoundMessage>,
+    ) -> Result<Vec<RoundMessage>, Self::Error>;
+
+    /// Combines this party's signature `share` with the shares of the co-signers.
+    async fn aggregate(
+        &self,
+        session: H256,
+        digest: H256,
+        share: Vec<u8>,
+    ) -> Result<KSig, Self::Error>;
+}
+
+/// Error thrown by the [ThresholdSigner]
+#[derive(Error, Debug)]
+pub enum ThresholdSignerError {
+    /// Thrown when the protocol implementation fails
+    #[error("partial signer failed: {0}")]
+    PartialSigner(BoxError),
+    /// Thrown when exchanging messages or aggregating the shares fails
+    #[error("aggregator failed: {0}")]
+    Aggregator(BoxError),
+    /// Thrown when the protocol does not finish within the maximum number of rounds
+    #[error("signing did not finish after {0} rounds")]
+    TooManyRounds(usize),
+    /// Thrown when the aggregated signature does not belong to the joint public key
+    #[error("aggregated signature does not match {0:?}")]
+    SignatureMismatch(Address),
+    /// Thrown when the typed data can not be encoded
+    #[error("error encoding eip712 struct: {0:?}")]
+    Eip712Error(String),
+}
+
+/// A [`Signer`](crate::Signer) whose key is shared between several parties, such that only a
+/// threshold of them together can sign.
+///
+/// Every signature runs the multi-round protocol of the [PartialSigner], exchanging messages
+/// with the co-signers through the [SignatureAggregator], so the signer can be used like any
+/// other signer, e.g. with the `SignerMiddleware`.
+///
+/// ```no_run
+/// # use ethers_signers::{PartialSigner, Signer, SignatureAggregator, ThresholdSigner};
+/// # async fn foo(share: impl PartialSigner, coordinator: impl SignatureAggregator) -> Result<(), Box<dyn std::error::Error>> {
+/// let signer = ThresholdSigner::new(share, coordinator, 1);
+/// let signature = signer.sign_message("hello world").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ThresholdSigner<P, A> {
+    partial: P,
+    aggregator: A,
+    chain_id: u64,
+    max_rounds: usize,
+}
+
+impl<P: PartialSigner, A: SignatureAggregator> ThresholdSigner<P, A> {
+    /// Creates a signer for the key share `partial`, whose co-signers are reached through
+    /// `aggregator`.
+    pub fn new(partial: P, aggregator: A, chain_id: u64) -> Self {
+        Self { partial, aggregator, chain_id, max_rounds: DEFAULT_MAX_ROUNDS }
+    }
+
+    /// Sets the maximum number of rounds after which signing is aborted.
+    #[must_use]
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Returns the key share
+    pub fn partial(&self) -> &P {
+        &self.partial
+    }
+
+    /// Returns the aggregator
+    pub fn aggregator(&self) -> &A {
+        &self.aggregator
+    }
+
+    /// Runs a signing session for `digest` and returns the aggregated signature.
+    pub async fn sign_digest(&self, digest: H256) -> Result<KSig, ThresholdSignerError> {
+        let session = H256(thread_rng().gen());
+        let mut outgoing = self.partial.start(session, digest).await.map_err(partial_error)?;
+
+        for round in 0..self.max_rounds {
+            let incoming = self
+                .aggregator
+                .exchange(session, digest, round, outgoing)
+                .await
+                .map_err(aggregator_error)?;
+            match self.partial.handle(session, round, incoming).await.map_err(partial_error)? {
+                RoundOutput::Continue(messages) => outgoing = messages,
+                RoundOutput::Done(share) => {
+                    let sig = self
+                        .aggregator
+                        .aggregate(session, digest, share)
+                        .await
+                        .map_err(aggregator_error)?;
+                    return Ok(sig.normalize_s().unwrap_or(sig))
+                }
+            }
+        }
+        Err(ThresholdSignerError::TooManyRounds(self.max_rounds))
+    }
+
+    /// Signs `digest` and recovers the `v` value, applying EIP-155 if a chain id is given
+    async fn sign_digest_with_v(
+        &self,
+        digest: H256,
+        chain_id: Option<u64>,
+    ) -> Result<Signature, ThresholdSignerError> {
+        let sig = self.sign_digest(digest).await?;
+        let (r, s) = sig.split_bytes();
+        let (r, s) = (U256::from_big_endian(r.as_slice()), U256::from_big_endian(s.as_slice()));
+        let address = self.partial.address();
+
+        let mut sig = [27, 28]
+            .into_iter()
+            .map(|v| Signature { r, s, v })
+            .find(|sig| sig.recover(digest).map(|addr| addr == address).unwrap_or(false))
+            .ok_or(ThresholdSignerError::SignatureMismatch(address))?;
+        if let Some(chain_id) = chain_id {
+            sig.v = to_eip155_v(sig.v as u8 - 27, chain_id);
+        }
+        Ok(sig)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: PartialSigner, A: SignatureAggregator> crate::Signer for ThresholdSigner<P, A> {
+    type Error = ThresholdSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest_with_v(hash_message(message), None).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx_with_chain = tx.clone();
+        let chain_id = tx_with_chain.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx_with_chain.set_chain_id(chain_id);
+
+        self.sign_digest_with_v(tx_with_chain.sighash(), Some(chain_id)).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest =
+            payload.encode_eip712().map_err(|e| Self::Error::Eip712Error(e.to_string()))?;
+        self.sign_digest_with_v(digest.into(), None).await
+    }
+
+    fn address(&self) -> Address {
+        self.partial.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: PartialSigner, A: SignatureAggregator> crate::AuthorizationSigner
+    for ThresholdSigner<P, A>
+{
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest_with_v(authorization.signature_hash(), None).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: PartialSigner, A: SignatureAggregator> crate::IntendedValidatorSigner
+    for ThresholdSigner<P, A>
+{
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest_with_v(hash_intended_validator(validator, data), None).await
+    }
+}
+
+fn partial_error<E: Error + Send + Sync + 'static>(err: E) -> ThresholdSignerError {
+    ThresholdSignerError::PartialSigner(Box::new(err))
+}
+
+fn aggregator_error<E: Error + Send + Sync + 'static>(err: E) -> ThresholdSignerError {
+    ThresholdSignerError::Aggregator(Box::new(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LocalWallet, Signer};
+    use ethers_core::k256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+    use std::{convert::Infallible, sync::Mutex};
+
+    /// Party 0 of a toy protocol that signs after one round of messages with party 1.
+    #[derive(Debug)]
+    struct ToyParty {
+        key: SigningKey,
+        digest: Mutex<Option<H256>>,
+    }
+
+    #[async_trait]
+    impl PartialSigner for ToyParty {
+        type Error = Infallible;
+
+        fn party(&self) -> u16 {
+            0
+        }
+
+        fn address(&self) -> Address {
+            LocalWallet::from(self.key.clone()).address()
+        }
+
+        async fn start(
+            &self,
+            _session: H256,
+            digest: H256,
+        ) -> Result<Vec<RoundMessage>, Infallible> {
+            *self.digest.lock().unwrap() = Some(digest);
+            Ok(vec![RoundMessage { from: 0, to: None, round: 0, payload: b"commit".to_vec() }])
+        }
+
+        async fn handle(
+            &self,
+            _session: H256,
+            round: usize,
+            incoming: Vec<RoundMessage>,
+        ) -> Result<RoundOutput, Infallible> {
+            assert_eq!(incoming[0].from, 1);
+            if round == 0 {
+                return Ok(RoundOutput::Continue(vec![RoundMessage {
+                    from: 0,
+                    to: Some(1),
+                    round: 1,
+                    payload: b"reveal".to_vec(),
+                }]))
+            }
+            let digest = self.digest.lock().unwrap().unwrap();
+            let sig: KSig = self.key.sign_prehash(digest.as_bytes()).unwrap();
+            Ok(RoundOutput::Done(sig.to_bytes().to_vec()))
+        }
+    }
+
+    /// Plays party 1 and returns party 0's share as the aggregated signature.
+    #[derive(Debug, Default)]
+    struct ToyAggregator {
+        rounds: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl SignatureAggregator for ToyAggregator {
+        type Error = Infallible;
+
+        async fn exchange(
+            &self,
+            _session: H256,
+            _digest: H256,
+            round: usize,
+            outgoing: Vec<RoundMessage>,
+        ) -> Result<Vec<RoundMessage>, Infallible> {
+            assert_eq!(outgoing[0].round, round);
+            self.rounds.lock().unwrap().push(round);
+            Ok(vec![RoundMessage { from: 1, to: Some(0), round, payload: vec![] }])
+        }
+
+        async fn aggregate(
+            &self,
+            _session: H256,
+            _digest: H256,
+            share: Vec<u8>,
+        ) -> Result<KSig, Infallible> {
+            Ok(KSig::try_from(share.as_slice()).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_signing_rounds() {
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let party = ToyParty { key: wallet.signer().clone(), digest: Mutex::new(None) };
+        let signer = ThresholdSigner::new(party, ToyAggregator::default(), 1);
+        assert_eq!(signer.address(), wallet.address());
+
+        let signature = signer.sign_message("hello").await.unwrap();
+        assert_eq!(signature, wallet.sign_message("hello").await.unwrap());
+        assert_eq!(*signer.aggregator().rounds.lock().unwrap(), vec![0, 1]);
+
+        let signer = signer.with_max_rounds(1);
+        let err = signer.sign_message("hello").await.unwrap_err();
+        assert!(matches!(err, ThresholdSignerError::TooManyRounds(1)));
+    }
+}
@@ -22,6 +22,7 @@ use std::{
 use thiserror::Error;
 
 use super::types::*;
+use crate::DeviceInfo;
 
 /// A Trezor Ethereum App.
 ///
@@ -164,9 +165,19 @@ impl TrezorEthereum {
 
     /// Signs an Ethereum transaction (requires confirmation on the Trezor)
     pub async fn sign_tx(&self, tx: &TypedTransaction) -> Result<Signature, TrezorError> {
+        self.sign_tx_with_path(&self.derivation, tx).await
+    }
+
+    /// Signs an Ethereum transaction with the account of the provided derivation path (requires
+    /// confirmation on the Trezor)
+    pub async fn sign_tx_with_path(
+        &self,
+        derivation: &DerivationType,
+        tx: &TypedTransaction,
+    ) -> Result<Signature, TrezorError> {
         let mut client = self.get_client(self.session_id.clone())?;
 
-        let arr_path = Self::convert_path(&self.derivation);
+        let arr_path = Self::convert_path(derivation);
 
         let transaction = TrezorTransaction::load(tx)?;
 
@@ -195,15 +206,38 @@ impl TrezorEthereum {
                 transaction.max_priority_fee_per_gas,
                 transaction.access_list,
             )?,
+            TypedTransaction::Eip4844(_) => return Err(TrezorError::UnsupportedSigningScheme),
             #[cfg(feature = "optimism")]
             TypedTransaction::OptimismDeposited(tx) => {
                 trezor_client::client::Signature { r: 0.into(), s: 0.into(), v: 0 }
             }
+            #[cfg(feature = "celo")]
+            TypedTransaction::Cip42(_) | TypedTransaction::Cip64(_) => {
+                return Err(TrezorError::UnsupportedSigningScheme)
+            }
+            #[cfg(feature = "zksync")]
+            TypedTransaction::ZkSync(_) => return Err(TrezorError::UnsupportedSigningScheme),
         };
 
         Ok(Signature { r: signature.r, s: signature.s, v: signature.v })
     }
 
+    /// Returns the model and firmware version of the device
+    pub async fn device_info(&self) -> Result<DeviceInfo, TrezorError> {
+        let client = self.get_client(self.session_id.clone())?;
+        let features = client.features().ok_or(TrezorError::FeaturesError)?;
+        Ok(DeviceInfo {
+            vendor: "Trezor".to_string(),
+            model: Some(features.model().to_string()).filter(|model| !model.is_empty()),
+            version: format!(
+                "{}.{}.{}",
+                features.major_version(),
+                features.minor_version(),
+                features.patch_version()
+            ),
+        })
+    }
+
     /// Signs an ethereum personal message
     pub async fn sign_message<S: AsRef<[u8]>>(&self, message: S) -> Result<Signature, TrezorError> {
         let message = message.as_ref();
@@ -216,11 +250,13 @@ impl TrezorEthereum {
     }
 
     /// Signs an EIP712 encoded domain separator and message
-    pub async fn sign_typed_struct<T>(&self, payload: &T) -> Result<Signature, TrezorError>
+    ///
+    /// The Trezor client does not support the typed hash signing flow yet, so this always errors.
+    pub async fn sign_typed_struct<T>(&self, _payload: &T) -> Result<Signature, TrezorError>
     where
         T: Eip712,
     {
-        unimplemented!()
+        Err(TrezorError::Eip712Unsupported)
     }
 
     // helper which converts a derivation path to [u32]
@@ -1,11 +1,13 @@
 pub mod app;
 pub mod types;
 
-use crate::Signer;
+use crate::{
+    AuthorizationSigner, DerivationPath, DeviceInfo, HardwareSigner, IntendedValidatorSigner, Signer,
+};
 use app::TrezorEthereum;
 use async_trait::async_trait;
 use ethers_core::types::{
-    transaction::{eip2718::TypedTransaction, eip712::Eip712},
+    transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
     Address, Signature,
 };
 use types::TrezorError;
@@ -55,3 +57,50 @@ impl Signer for TrezorEthereum {
         self.chain_id
     }
 }
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthorizationSigner for TrezorEthereum {
+    /// Not supported, the device does not sign EIP-7702 authorizations
+    async fn sign_authorization(
+        &self,
+        _authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        Err(TrezorError::UnsupportedSigningScheme)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IntendedValidatorSigner for TrezorEthereum {
+    /// Not supported, the device only signs prefixed messages, transactions and typed data
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        _validator: Address,
+        _data: S,
+    ) -> Result<Signature, Self::Error> {
+        Err(TrezorError::UnsupportedSigningScheme)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HardwareSigner for TrezorEthereum {
+    type Error = TrezorError;
+
+    async fn get_address(&self, path: &DerivationPath) -> Result<Address, Self::Error> {
+        self.get_address_with_path(&path.clone().into()).await
+    }
+
+    async fn sign_tx(
+        &self,
+        path: &DerivationPath,
+        tx: &TypedTransaction,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_tx_with_path(&path.clone().into(), tx).await
+    }
+
+    async fn device_info(&self) -> Result<DeviceInfo, Self::Error> {
+        self.device_info().await
+    }
+}
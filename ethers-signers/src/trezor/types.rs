@@ -1,6 +1,7 @@
 #![allow(clippy::upper_case_acronyms)]
 //! Helpers for interacting with the Ethereum Trezor App
 //! [Official Docs](https://github.com/TrezorHQ/app-ethereum/blob/master/doc/ethapp.asc)
+use crate::DerivationPath;
 use std::fmt;
 use thiserror::Error;
 
@@ -30,6 +31,12 @@ impl fmt::Display for DerivationType {
     }
 }
 
+impl From<DerivationPath> for DerivationType {
+    fn from(path: DerivationPath) -> Self {
+        DerivationType::Other(path.to_string())
+    }
+}
+
 #[derive(Error, Debug)]
 /// Error when using the Trezor transport
 pub enum TrezorError {
@@ -49,6 +56,12 @@ pub enum TrezorError {
     /// Error when signing EIP712 struct with not compatible Trezor ETH app
     #[error("Trezor ethereum app requires at least version: {0:?}")]
     UnsupportedFirmwareVersion(String),
+    /// Error when requesting a signature the device can not produce
+    #[error("The Trezor does not support this signing scheme")]
+    UnsupportedSigningScheme,
+    /// Error when signing EIP712 structs, which the Trezor client does not support
+    #[error("Signing EIP712 structs is not supported")]
+    Eip712Unsupported,
     #[error("Does not support ENS.")]
     NoENSSupport,
     #[error("Unable to access trezor cached session.")]
@@ -147,6 +160,16 @@ impl TrezorTransaction {
                 max_priority_fee_per_gas: vec![],
                 access_list: vec![],
             }),
+            // the Ethereum app can not sign blob transactions
+            TypedTransaction::Eip4844(_) => Err(TrezorError::UnsupportedSigningScheme),
+            // the Ethereum app can not display fee currencies
+            #[cfg(feature = "celo")]
+            TypedTransaction::Cip42(_) | TypedTransaction::Cip64(_) => {
+                Err(TrezorError::UnsupportedSigningScheme)
+            }
+            // zkSync transactions are signed as typed data, which the Ethereum app can't display
+            #[cfg(feature = "zksync")]
+            TypedTransaction::ZkSync(_) => Err(TrezorError::UnsupportedSigningScheme),
         }
     }
 }
@@ -0,0 +1,216 @@
+//! Cross-checks the addresses derived from a root key against the addresses exported by another
+//! wallet, e.g. when migrating accounts which used non-standard derivation paths.
+use crate::WalletError;
+
+use coins_bip32::{
+    enc::{MainnetEncoder, XKeyEncoder},
+    prelude::{Parent, XPriv, XPub},
+    Bip32Error, BIP32_HARDEN,
+};
+use coins_bip39::{Mnemonic, Wordlist};
+use ethers_core::{types::Address, utils::keccak256};
+use std::{collections::HashMap, fmt, ops::Range};
+
+/// The placeholder for the account index in a derivation path template
+pub const INDEX_PLACEHOLDER: &str = "{index}";
+
+/// The BIP-44 path used by most wallets, e.g. MetaMask and Trezor
+pub const BIP44_TEMPLATE: &str = "m/44'/60'/0'/0/{index}";
+
+/// The path used by Ledger Live
+pub const LEDGER_LIVE_TEMPLATE: &str = "m/44'/60'/{index}'/0/0";
+
+/// The path used by the legacy Ledger Chrome app and MyEtherWallet
+pub const LEDGER_LEGACY_TEMPLATE: &str = "m/44'/60'/0'/{index}";
+
+/// The key which addresses are derived from.
+#[derive(Clone)]
+pub enum AuditKey {
+    /// A root extended private key, e.g. derived from a mnemonic. Templates are absolute paths
+    /// starting with `m/`.
+    Root(XPriv),
+    /// An extended public key. Templates are paths relative to the key, without hardened
+    /// components, e.g. `0/{index}` for an xpub exported at `m/44'/60'/0'`.
+    Public(XPub),
+}
+
+impl AuditKey {
+    /// Returns the root key derived from the mnemonic `phrase` and `password`.
+    pub fn from_phrase<W: Wordlist>(
+        phrase: &str,
+        password: Option<&str>,
+    ) -> Result<Self, WalletError> {
+        Ok(Self::Root(Mnemonic::<W>::new_from_phrase(phrase)?.master_key(password)?))
+    }
+
+    /// Parses a base58 encoded extended public key, e.g. `xpub...`.
+    pub fn from_xpub(xpub: &str) -> Result<Self, WalletError> {
+        Ok(Self::Public(MainnetEncoder::xpub_from_base58(xpub)?))
+    }
+
+    /// Derives the address at `path`.
+    pub fn derive_address(&self, path: &str) -> Result<Address, WalletError> {
+        let xpub = match self {
+            AuditKey::Root(root) => root.derive_path(path)?.verify_key(),
+            AuditKey::Public(xpub) => {
+                let mut key = *xpub;
+                for index in path.split('/').filter(|component| !component.is_empty()) {
+                    let index = index
+                        .parse::<u32>()
+                        .map_err(|_| Bip32Error::MalformattedDerivation(path.to_string()))?;
+                    if index >= BIP32_HARDEN {
+                        return Err(Bip32Error::MalformattedDerivation(path.to_string()).into())
+                    }
+                    key = key.derive_child(index)?;
+                }
+                key
+            }
+        };
+        let key: &coins_bip32::prelude::VerifyingKey = xpub.as_ref();
+        let public_key = key.to_encoded_point(false);
+        Ok(Address::from_slice(&keccak256(&public_key.as_bytes()[1..])[12..]))
+    }
+}
+
+// do not log the extended private key
+impl fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditKey::Root(_) => f.write_str("Root(..)"),
+            AuditKey::Public(xpub) => f.debug_tuple("Public").field(xpub).finish(),
+        }
+    }
+}
+
+/// An address derived during an audit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivedAddress {
+    /// The template of the derivation path
+    pub template: String,
+    /// The account index substituted into the template
+    pub index: u32,
+    /// The derived address
+    pub address: Address,
+}
+
+impl DerivedAddress {
+    /// Returns the derivation path of the address.
+    pub fn path(&self) -> String {
+        self.template.replace(INDEX_PLACEHOLDER, &self.index.to_string())
+    }
+}
+
+/// The result of [`audit_addresses`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    /// The expected addresses which were derived, in the order they were expected
+    pub matched: Vec<DerivedAddress>,
+    /// The expected addresses which were not derived by any template and index
+    pub missing: Vec<Address>,
+}
+
+impl AuditReport {
+    /// Returns `true` if every expected address was derived.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Derives the addresses of every template in `templates` at the account indices in `indices`,
+/// and reports which of the `expected` addresses they contain.
+///
+/// Templates contain the [`INDEX_PLACEHOLDER`], e.g. [`BIP44_TEMPLATE`] or
+/// [`LEDGER_LIVE_TEMPLATE`]. An expected address matched by several templates is reported with
+/// the first one.
+///
+/// # Example
+///
+/// ```
+/// use ethers_signers::{
+///     audit_addresses, coins_bip39::English, AuditKey, BIP44_TEMPLATE, LEDGER_LIVE_TEMPLATE,
+/// };
+///
+/// # fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let key = AuditKey::from_phrase::<English>(
+///     "test test test test test test test test test test test junk",
+///     None,
+/// )?;
+/// let expected = vec!["0x70997970C51812dc3A010C7d01b50e0d17dc79C8".parse()?];
+///
+/// let report = audit_addresses(&key, &[BIP44_TEMPLATE, LEDGER_LIVE_TEMPLATE], 0..5, &expected)?;
+/// assert!(report.is_complete());
+/// assert_eq!(report.matched[0].path(), "m/44'/60'/0'/0/1");
+/// # Ok(())
+/// # }
+/// ```
+pub fn audit_addresses(
+    key: &AuditKey,
+    templates: &[&str],
+    indices: Range<u32>,
+    expected: &[Address],
+) -> Result<AuditReport, WalletError> {
+    let mut derived = HashMap::new();
+    for template in templates {
+        for index in indices.clone() {
+            let path = template.replace(INDEX_PLACEHOLDER, &index.to_string());
+            let address = key.derive_address(&path)?;
+            derived.entry(address).or_insert_with(|| DerivedAddress {
+                template: template.to_string(),
+                index,
+                address,
+            });
+        }
+    }
+
+    let mut report = AuditReport::default();
+    for address in expected {
+        match derived.get(address) {
+            Some(derived) => report.matched.push(derived.clone()),
+            None => report.missing.push(*address),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coins_bip39::English;
+
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn reports_missing_addresses() {
+        let key = AuditKey::from_phrase::<English>(PHRASE, None).unwrap();
+        let ledger_live = key.derive_address("m/44'/60'/2'/0/0").unwrap();
+        let unknown = Address::repeat_byte(0x11);
+        let expected = vec![
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".parse().unwrap(),
+            unknown,
+            ledger_live,
+        ];
+
+        let report =
+            audit_addresses(&key, &[BIP44_TEMPLATE, LEDGER_LIVE_TEMPLATE], 1..3, &expected)
+                .unwrap();
+        assert_eq!(report.missing, vec![expected[0], unknown]);
+        assert_eq!(report.matched.len(), 1);
+        assert_eq!(report.matched[0].path(), "m/44'/60'/2'/0/0");
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn derives_from_xpub() {
+        let mnemonic = Mnemonic::<English>::new_from_phrase(PHRASE).unwrap();
+        let account = mnemonic.derive_key("m/44'/60'/0'", None).unwrap();
+        let xpub = MainnetEncoder::xpub_to_base58(&account.verify_key()).unwrap();
+        let key = AuditKey::from_xpub(&xpub).unwrap();
+
+        let root = AuditKey::Root(mnemonic.master_key(None).unwrap());
+        assert_eq!(
+            key.derive_address("0/3").unwrap(),
+            root.derive_address("m/44'/60'/0'/0/3").unwrap()
+        );
+        assert!(key.derive_address("0'/3").is_err());
+    }
+}
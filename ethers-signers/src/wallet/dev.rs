@@ -0,0 +1,104 @@
+//! The well-known accounts of local development nodes
+use super::{HDWallet, Wallet, WalletError};
+use crate::Signer;
+
+use coins_bip39::English;
+use ethers_core::{k256::ecdsa::SigningKey, types::Address};
+
+/// The mnemonic of the accounts Anvil and Hardhat fund by default.
+///
+/// These keys are public, never use them outside of local development networks.
+pub const DEV_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+/// The chain id of Anvil and Hardhat
+pub const DEV_CHAIN_ID: u64 = 31337;
+
+/// The number of accounts Anvil and Hardhat fund by default
+pub const DEV_ACCOUNTS: u32 = 10;
+
+impl Wallet<SigningKey> {
+    /// Returns the funded account at `index` of an Anvil node started with the default mnemonic,
+    /// with the chain id set to [`DEV_CHAIN_ID`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ethers_signers::{LocalWallet, Signer};
+    ///
+    /// let wallet = LocalWallet::anvil_account(0);
+    /// assert_eq!(
+    ///     wallet.address(),
+    ///     "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".parse().unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not a valid non-hardened BIP-32 index, i.e. `index >= 2^31`.
+    pub fn anvil_account(index: u32) -> Self {
+        Self::dev_account(index).expect("valid account index")
+    }
+
+    /// Returns the funded account at `index` of a Hardhat network with the default accounts, which
+    /// are the same as [Anvil's](Self::anvil_account).
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not a valid non-hardened BIP-32 index, i.e. `index >= 2^31`.
+    pub fn hardhat_account(index: u32) -> Self {
+        Self::anvil_account(index)
+    }
+
+    /// Returns the `count` first accounts of the [`DEV_MNEMONIC`], see
+    /// [`anvil_account`](Self::anvil_account).
+    pub fn anvil_accounts(count: u32) -> Vec<Self> {
+        let hd = dev_hd_wallet();
+        (0..count)
+            .map(|index| hd.derive_account(index).expect("valid account index"))
+            .map(|wallet| wallet.with_chain_id(DEV_CHAIN_ID))
+            .collect()
+    }
+
+    /// Derives the account at `index` of the [`DEV_MNEMONIC`], with the chain id set to
+    /// [`DEV_CHAIN_ID`].
+    pub fn dev_account(index: u32) -> Result<Self, WalletError> {
+        Ok(dev_hd_wallet().derive_account(index)?.with_chain_id(DEV_CHAIN_ID))
+    }
+}
+
+/// Returns the addresses of the [`DEV_ACCOUNTS`] accounts funded by Anvil and Hardhat.
+pub fn dev_addresses() -> Vec<Address> {
+    dev_hd_wallet().addresses(0..DEV_ACCOUNTS).expect("valid account indices")
+}
+
+fn dev_hd_wallet() -> HDWallet {
+    HDWallet::from_phrase::<English>(DEV_MNEMONIC, None).expect("valid mnemonic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalWallet;
+
+    #[test]
+    fn derives_anvil_accounts() {
+        let wallet = LocalWallet::anvil_account(1);
+        assert_eq!(
+            wallet,
+            "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+                .parse::<LocalWallet>()
+                .unwrap()
+                .with_chain_id(DEV_CHAIN_ID)
+        );
+        assert_eq!(wallet.chain_id(), DEV_CHAIN_ID);
+        assert_eq!(LocalWallet::hardhat_account(1), wallet);
+
+        let accounts = LocalWallet::anvil_accounts(DEV_ACCOUNTS);
+        assert_eq!(accounts[1], wallet);
+        assert_eq!(accounts.iter().map(Signer::address).collect::<Vec<_>>(), dev_addresses());
+        assert_eq!(
+            accounts[9].address(),
+            "0xa0Ee7A142d267C1f36714E4a8F75612F20a79720".parse::<Address>().unwrap()
+        );
+    }
+}
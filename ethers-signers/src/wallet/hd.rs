@@ -0,0 +1,147 @@
+//! Derivation of many wallets from a single HD root key following BIP-32 and BIP-44
+use crate::{Wallet, WalletError};
+
+use coins_bip32::{
+    enc::{MainnetEncoder, XKeyEncoder},
+    prelude::{Parent, XPriv},
+};
+use coins_bip39::{Mnemonic, Wordlist};
+use ethers_core::{k256::ecdsa::SigningKey, types::Address, utils::secret_key_to_address};
+use std::{fmt, ops::Range};
+use zeroize::Zeroizing;
+
+/// The BIP-44 path of the Ethereum accounts, without the account index
+const ACCOUNTS_PATH: &str = "m/44'/60'/0'/0";
+
+/// Derives the wallets at `m/44'/60'/0'/0/{index}` of an HD root key.
+///
+/// The extended key at `m/44'/60'/0'/0` is derived once when the `HDWallet` is created, so every
+/// account only takes a single, non-hardened child derivation.
+///
+/// # Example
+///
+/// ```
+/// use ethers_signers::{coins_bip39::English, HDWallet, Signer};
+///
+/// # fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let phrase = "test test test test test test test test test test test junk";
+/// let hd = HDWallet::from_phrase::<English>(phrase, None)?;
+///
+/// let wallet = hd.derive_account(1)?;
+/// let addresses = hd.addresses(0..100)?;
+/// assert_eq!(addresses[1], wallet.address());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct HDWallet {
+    accounts: XPriv,
+}
+
+impl HDWallet {
+    /// Creates an `HDWallet` from the root key derived from `mnemonic` and `password`.
+    pub fn from_mnemonic<W: Wordlist>(
+        mnemonic: &Mnemonic<W>,
+        password: Option<&str>,
+    ) -> Result<Self, WalletError> {
+        Ok(Self { accounts: mnemonic.derive_key(ACCOUNTS_PATH, password)? })
+    }
+
+    /// Creates an `HDWallet` from the root key derived from the mnemonic `phrase` and `password`.
+    pub fn from_phrase<W: Wordlist>(
+        phrase: &str,
+        password: Option<&str>,
+    ) -> Result<Self, WalletError> {
+        Self::from_mnemonic(&Mnemonic::<W>::new_from_phrase(phrase)?, password)
+    }
+
+    /// Creates an `HDWallet` from a BIP-39 `seed`.
+    pub fn from_seed(seed: &[u8]) -> Result<Self, WalletError> {
+        Self::from_root(&XPriv::root_from_seed(seed, None)?)
+    }
+
+    /// Creates an `HDWallet` from a root extended private key.
+    pub fn from_root(root: &XPriv) -> Result<Self, WalletError> {
+        Ok(Self { accounts: root.derive_path(ACCOUNTS_PATH)? })
+    }
+
+    /// Creates an `HDWallet` from a base58 encoded root extended private key, e.g. `xprv...`.
+    pub fn from_xpriv(xpriv: &str) -> Result<Self, WalletError> {
+        Self::from_root(&MainnetEncoder::xpriv_from_base58(xpriv)?)
+    }
+
+    /// Derives the wallet at `m/44'/60'/0'/0/{index}`.
+    pub fn derive_account(&self, index: u32) -> Result<Wallet<SigningKey>, WalletError> {
+        let child = self.accounts.derive_child(index)?;
+        let key: &coins_bip32::prelude::SigningKey = child.as_ref();
+        let signer = SigningKey::from_bytes(&Zeroizing::new(key.to_bytes()))?;
+        let address = secret_key_to_address(&signer);
+        Ok(Wallet::<SigningKey> { signer, address, chain_id: 1, chain_guard: false })
+    }
+
+    /// Derives the wallets of the account indices in `range`.
+    pub fn derive_accounts(
+        &self,
+        range: Range<u32>,
+    ) -> Result<Vec<Wallet<SigningKey>>, WalletError> {
+        range.map(|index| self.derive_account(index)).collect()
+    }
+
+    /// Derives the addresses of the account indices in `range`.
+    pub fn addresses(&self, range: Range<u32>) -> Result<Vec<Address>, WalletError> {
+        range.map(|index| Ok(self.derive_account(index)?.address)).collect()
+    }
+
+    /// Returns an iterator over the wallets at the account indices `0, 1, 2, ...`.
+    pub fn accounts(&self) -> impl Iterator<Item = Result<Wallet<SigningKey>, WalletError>> + '_ {
+        (0..=u32::MAX >> 1).map(|index| self.derive_account(index))
+    }
+}
+
+// do not log the extended private key
+impl fmt::Debug for HDWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HDWallet").field("path", &ACCOUNTS_PATH).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coins_bip39::English, MnemonicBuilder};
+
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn derives_accounts() {
+        let hd = HDWallet::from_phrase::<English>(PHRASE, None).unwrap();
+        let expected: [Address; 3] = [
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".parse().unwrap(),
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".parse().unwrap(),
+            "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC".parse().unwrap(),
+        ];
+        assert_eq!(hd.addresses(0..3).unwrap(), expected);
+
+        let wallets: Vec<_> = hd.accounts().take(3).collect::<Result<_, _>>().unwrap();
+        for (index, wallet) in wallets.into_iter().enumerate() {
+            let built = MnemonicBuilder::<English>::default()
+                .phrase(PHRASE)
+                .index(index as u32)
+                .unwrap()
+                .build()
+                .unwrap();
+            assert_eq!(wallet, built);
+        }
+    }
+
+    #[test]
+    fn derives_from_xpriv() {
+        let mnemonic = Mnemonic::<English>::new_from_phrase(PHRASE).unwrap();
+        let root = mnemonic.master_key(None).unwrap();
+        let xpriv = MainnetEncoder::xpriv_to_base58(&root).unwrap();
+
+        let hd = HDWallet::from_xpriv(&xpriv).unwrap();
+        let expected = HDWallet::from_mnemonic(&mnemonic, None).unwrap();
+        assert_eq!(hd.addresses(0..2).unwrap(), expected.addresses(0..2).unwrap());
+    }
+}
@@ -0,0 +1,544 @@
+//! Encrypted JSON keystores with configurable key derivation
+use super::{Wallet, WalletError};
+use crate::LocalWallet;
+use aes::cipher::{KeyIvInit, StreamCipher};
+use eth_keystore::KeystoreError;
+use ethers_core::{
+    k256::ecdsa::SigningKey,
+    rand::{CryptoRng, Rng},
+    types::Address,
+    utils::{keccak256, secret_key_to_address},
+};
+use hmac::Hmac;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use zeroize::{Zeroize, Zeroizing};
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+const DERIVED_KEY_LENGTH: usize = 32;
+const IV_LENGTH: usize = 16;
+const SALT_LENGTH: usize = 32;
+
+/// The key derivation function of an encrypted JSON keystore, see [`Wallet::encrypt_keystore`].
+///
+/// Scrypt and PBKDF2 keystores follow the [Web3 Secret Storage Definition] and can be imported by
+/// any wallet. Argon2id keystores use the same layout with `"kdf": "argon2id"` and `m`, `t` and `p`
+/// as `kdfparams`, which only some wallets understand.
+///
+/// [Web3 Secret Storage Definition]: https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeystoreKdf {
+    /// Scrypt with a cost of `2^log_n`, a block size of `r` and a parallelization of `p`
+    Scrypt {
+        /// The base-2 logarithm of the CPU/memory cost
+        log_n: u8,
+        /// The block size
+        r: u32,
+        /// The parallelization
+        p: u32,
+    },
+    /// PBKDF2 with HMAC-SHA256 and `c` iterations
+    Pbkdf2 {
+        /// The number of iterations
+        c: u32,
+    },
+    /// Argon2id with `m` KiB of memory, `t` iterations and a parallelism of `p`
+    Argon2id {
+        /// The memory size in KiB
+        m: u32,
+        /// The number of iterations
+        t: u32,
+        /// The degree of parallelism
+        p: u32,
+    },
+}
+
+impl KeystoreKdf {
+    /// The scrypt parameters of geth's "standard" keystores, `n = 2^18, r = 8, p = 1`
+    pub const SCRYPT_STANDARD: Self = Self::Scrypt { log_n: 18, r: 8, p: 1 };
+
+    /// The scrypt parameters of geth's "light" keystores, `n = 2^12, r = 8, p = 6`
+    pub const SCRYPT_LIGHT: Self = Self::Scrypt { log_n: 12, r: 8, p: 6 };
+
+    /// The Argon2id parameters recommended by RFC 9106 for memory constrained environments,
+    /// `m = 64 MiB, t = 3, p = 4`
+    pub const ARGON2ID: Self = Self::Argon2id { m: 64 * 1024, t: 3, p: 4 };
+
+    pub(crate) fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<[u8; 32], WalletError> {
+        let mut key = [0u8; DERIVED_KEY_LENGTH];
+        match *self {
+            KeystoreKdf::Scrypt { log_n, r, p } => {
+                let params = scrypt::Params::new(log_n, r, p)
+                    .map_err(|err| WalletError::InvalidKeystore(err.to_string()))?;
+                scrypt::scrypt(password, salt, &params, &mut key)
+                    .map_err(|err| WalletError::InvalidKeystore(err.to_string()))?;
+            }
+            KeystoreKdf::Pbkdf2 { c } => {
+                pbkdf2::pbkdf2::<Hmac<Sha256>>(password, salt, c, &mut key);
+            }
+            KeystoreKdf::Argon2id { m, t, p } => {
+                let params = argon2::Params::new(m, t, p, Some(DERIVED_KEY_LENGTH))
+                    .map_err(|err| WalletError::InvalidKeystore(err.to_string()))?;
+                argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                    .hash_password_into(password, salt, &mut key)
+                    .map_err(|err| WalletError::InvalidKeystore(err.to_string()))?;
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl Default for KeystoreKdf {
+    /// The parameters used by [`Wallet::new_keystore`], `n = 2^13, r = 8, p = 1`
+    fn default() -> Self {
+        Self::Scrypt { log_n: 13, r: 8, p: 1 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeystoreJson {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    crypto: CryptoJson,
+    id: String,
+    version: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CryptoJson {
+    cipher: String,
+    cipherparams: CipherParamsJson,
+    ciphertext: String,
+    #[serde(flatten)]
+    kdf: KdfJson,
+    mac: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CipherParamsJson {
+    iv: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kdf", content = "kdfparams", rename_all = "lowercase")]
+enum KdfJson {
+    Scrypt { dklen: usize, n: u64, r: u32, p: u32, salt: String },
+    Pbkdf2 { c: u32, dklen: usize, prf: String, salt: String },
+    Argon2id { dklen: usize, m: u32, t: u32, p: u32, salt: String },
+}
+
+impl KdfJson {
+    fn new(kdf: KeystoreKdf, salt: &[u8]) -> Self {
+        let (dklen, salt) = (DERIVED_KEY_LENGTH, hex::encode(salt));
+        match kdf {
+            KeystoreKdf::Scrypt { log_n, r, p } => {
+                KdfJson::Scrypt { dklen, n: 1 << log_n, r, p, salt }
+            }
+            KeystoreKdf::Pbkdf2 { c } => {
+                KdfJson::Pbkdf2 { c, dklen, prf: "hmac-sha256".to_string(), salt }
+            }
+            KeystoreKdf::Argon2id { m, t, p } => KdfJson::Argon2id { dklen, m, t, p, salt },
+        }
+    }
+
+    fn kdf(&self) -> Result<(KeystoreKdf, &str), WalletError> {
+        let (kdf, dklen, salt) = match self {
+            KdfJson::Scrypt { dklen, n, r, p, salt } => {
+                if !n.is_power_of_two() || *n < 2 {
+                    return Err(WalletError::InvalidKeystore(format!("invalid scrypt cost {n}")))
+                }
+                let log_n = n.trailing_zeros() as u8;
+                (KeystoreKdf::Scrypt { log_n, r: *r, p: *p }, dklen, salt)
+            }
+            KdfJson::Pbkdf2 { c, dklen, prf, salt } => {
+                if prf != "hmac-sha256" {
+                    return Err(WalletError::InvalidKeystore(format!("unsupported prf {prf}")))
+                }
+                (KeystoreKdf::Pbkdf2 { c: *c }, dklen, salt)
+            }
+            KdfJson::Argon2id { dklen, m, t, p, salt } => {
+                (KeystoreKdf::Argon2id { m: *m, t: *t, p: *p }, dklen, salt)
+            }
+        };
+        if *dklen != DERIVED_KEY_LENGTH {
+            return Err(WalletError::InvalidKeystore(format!("unsupported dklen {dklen}")))
+        }
+        Ok((kdf, salt))
+    }
+}
+
+/// Returns `true` if `json` is a keystore whose key derivation function is not part of the Web3
+/// Secret Storage Definition, e.g. Argon2id.
+pub(crate) fn has_extended_kdf(json: &str) -> bool {
+    #[derive(Deserialize)]
+    struct Keystore {
+        crypto: Crypto,
+    }
+    #[derive(Deserialize)]
+    struct Crypto {
+        kdf: String,
+    }
+    serde_json::from_str::<Keystore>(json)
+        .map_or(false, |keystore| !matches!(keystore.crypto.kdf.as_str(), "scrypt" | "pbkdf2"))
+}
+
+/// Returns a random version 4 UUID.
+pub(crate) fn uuid_v4<R: Rng>(rng: &mut R) -> String {
+    let mut bytes: [u8; 16] = rng.gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+fn mac(key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    keccak256([&key[16..32], ciphertext].concat())
+}
+
+impl Wallet<SigningKey> {
+    /// Encrypts the wallet's private key with `password` into an encrypted JSON keystore in `dir`,
+    /// deriving the encryption key with `kdf`. Returns the UUID of the keystore, which is also the
+    /// file name unless a `name` is given.
+    ///
+    /// Use [`KeystoreKdf::SCRYPT_STANDARD`] or [`KeystoreKdf::ARGON2ID`] for keystores that are
+    /// exported to other wallets or stored in untrusted places.
+    pub fn encrypt_keystore<P, R, S>(
+        &self,
+        dir: P,
+        rng: &mut R,
+        password: S,
+        kdf: KeystoreKdf,
+        name: Option<&str>,
+    ) -> Result<String, WalletError>
+    where
+        P: AsRef<Path>,
+        R: Rng + CryptoRng,
+        S: AsRef<[u8]>,
+    {
+        let (json, uuid) = self.keystore_json(rng, password.as_ref(), kdf, None)?;
+        let name = name.unwrap_or(&uuid);
+        fs::write(dir.as_ref().join(name), json)?;
+        Ok(uuid)
+    }
+
+    /// Encrypts the wallet's private key with `password` into an encrypted JSON keystore, deriving
+    /// the encryption key with `kdf`.
+    pub fn encrypt_keystore_json<R, S>(
+        &self,
+        rng: &mut R,
+        password: S,
+        kdf: KeystoreKdf,
+    ) -> Result<String, WalletError>
+    where
+        R: Rng + CryptoRng,
+        S: AsRef<[u8]>,
+    {
+        Ok(self.keystore_json(rng, password.as_ref(), kdf, None)?.0)
+    }
+
+    /// Decrypts an encrypted JSON keystore with any of the key derivation functions of
+    /// [`KeystoreKdf`].
+    pub fn decrypt_keystore_json<S: AsRef<[u8]>>(
+        json: &str,
+        password: S,
+    ) -> Result<Self, WalletError> {
+        let keystore: KeystoreJson =
+            serde_json::from_str(json).map_err(|e| KeystoreError::SerdeJson(e.to_string()))?;
+        let crypto = keystore.crypto;
+        if crypto.cipher != "aes-128-ctr" {
+            return Err(WalletError::InvalidKeystore(format!(
+                "unsupported cipher {}",
+                crypto.cipher
+            )))
+        }
+        let (kdf, salt) = crypto.kdf.kdf()?;
+        let mut key = kdf.derive_key(password.as_ref(), &hex::decode(salt)?)?;
+
+        let mut secret = hex::decode(crypto.ciphertext)?;
+        let valid = hex::decode(crypto.mac)? == mac(&key, &secret);
+        let iv = hex::decode(crypto.cipherparams.iv)?;
+        if valid && iv.len() == IV_LENGTH {
+            Aes128Ctr::new(key[..16].into(), iv.as_slice().into()).apply_keystream(&mut secret);
+        }
+        key.zeroize();
+        if !valid {
+            return Err(KeystoreError::MacMismatch.into())
+        }
+        if iv.len() != IV_LENGTH {
+            return Err(WalletError::InvalidKeystore("invalid iv".to_string()))
+        }
+
+        let signer = SigningKey::from_slice(&secret);
+        secret.zeroize();
+        let signer = signer?;
+        let address = secret_key_to_address(&signer);
+        Ok(Self { signer, address, chain_id: 1, chain_guard: false })
+    }
+
+    fn keystore_json<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        password: &[u8],
+        kdf: KeystoreKdf,
+        uuid: Option<String>,
+    ) -> Result<(String, String), WalletError> {
+        let salt: [u8; SALT_LENGTH] = rng.gen();
+        let iv: [u8; IV_LENGTH] = rng.gen();
+        let mut key = kdf.derive_key(password, &salt)?;
+
+        let secret = Zeroizing::new(self.signer.to_bytes());
+        let mut ciphertext = secret.to_vec();
+        Aes128Ctr::new(key[..16].into(), iv[..].into()).apply_keystream(&mut ciphertext);
+        let mac = mac(&key, &ciphertext);
+        key.zeroize();
+
+        let uuid = uuid.unwrap_or_else(|| uuid_v4(rng));
+        let keystore = KeystoreJson {
+            address: Some(hex::encode(self.address)),
+            crypto: CryptoJson {
+                cipher: "aes-128-ctr".to_string(),
+                cipherparams: CipherParamsJson { iv: hex::encode(iv) },
+                ciphertext: hex::encode(ciphertext),
+                kdf: KdfJson::new(kdf, &salt),
+                mac: hex::encode(mac),
+            },
+            id: uuid.clone(),
+            version: 3,
+        };
+        let json = serde_json::to_string(&keystore)
+            .map_err(|e| KeystoreError::SerdeJson(e.to_string()))?;
+        Ok((json, uuid))
+    }
+}
+
+/// An encrypted JSON keystore found by [`KeystoreDir`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeystoreEntry {
+    /// The path of the keystore file
+    pub path: PathBuf,
+    /// The address stored in the keystore, if any. Some wallets omit it, in which case the address
+    /// is only known after decrypting the keystore.
+    pub address: Option<Address>,
+    /// The UUID of the keystore
+    pub uuid: String,
+}
+
+/// A directory of encrypted JSON keystores, e.g. geth's `keystore` directory.
+///
+/// Keystores are listed without decrypting them, and only decrypted when a wallet is requested.
+/// Files which are not keystores are ignored.
+///
+/// ```no_run
+/// use ethers_signers::{KeystoreDir, KeystoreKdf, Signer};
+///
+/// # fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let keystores = KeystoreDir::open("keystore")?;
+/// for entry in keystores.accounts() {
+///     println!("{:?} {}", entry.address, entry.uuid);
+/// }
+///
+/// let address = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".parse()?;
+/// let wallet = keystores.decrypt(address, "password")?;
+/// assert_eq!(wallet.address(), address);
+///
+/// keystores.rotate_password(
+///     address,
+///     "password",
+///     "new password",
+///     KeystoreKdf::SCRYPT_STANDARD,
+///     &mut ethers_core::rand::thread_rng(),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeystoreDir {
+    dir: PathBuf,
+    entries: Vec<KeystoreEntry>,
+}
+
+impl KeystoreDir {
+    /// Scans `dir` for keystores.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, WalletError> {
+        let mut keystores = Self { dir: dir.into(), entries: Vec::new() };
+        keystores.rescan()?;
+        Ok(keystores)
+    }
+
+    /// Returns the scanned directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Scans the directory again, e.g. after keystores were added by another process.
+    pub fn rescan(&mut self) -> Result<(), WalletError> {
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            // skips directories and interrupted password rotations
+            if !path.is_file() || path.extension().map_or(false, |ext| ext == "tmp") {
+                continue
+            }
+            let Ok(json) = fs::read_to_string(&path) else { continue };
+            let Ok(keystore) = serde_json::from_str::<KeystoreJson>(&json) else { continue };
+            let address = keystore.address.as_deref().and_then(parse_address);
+            entries.push(KeystoreEntry { path, address, uuid: keystore.id });
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        self.entries = entries;
+        Ok(())
+    }
+
+    /// Returns the keystores of the directory, ordered by file name.
+    pub fn accounts(&self) -> &[KeystoreEntry] {
+        &self.entries
+    }
+
+    /// Returns the keystore of `address`, if any.
+    pub fn find(&self, address: Address) -> Option<&KeystoreEntry> {
+        self.entries.iter().find(|entry| entry.address == Some(address))
+    }
+
+    /// Decrypts the keystore of `address` with `password`.
+    pub fn decrypt<S: AsRef<[u8]>>(
+        &self,
+        address: Address,
+        password: S,
+    ) -> Result<LocalWallet, WalletError> {
+        let entry = self.find(address).ok_or(WalletError::KeystoreNotFound(address))?;
+        Wallet::decrypt_keystore(&entry.path, password)
+    }
+
+    /// Re-encrypts the keystore of `address` with `new_password`, deriving the encryption key with
+    /// `kdf`. The keystore keeps its file name and UUID.
+    ///
+    /// The file is replaced atomically, so a crash while writing leaves the old keystore.
+    pub fn rotate_password<S, T, R>(
+        &self,
+        address: Address,
+        password: S,
+        new_password: T,
+        kdf: KeystoreKdf,
+        rng: &mut R,
+    ) -> Result<(), WalletError>
+    where
+        S: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        R: Rng + CryptoRng,
+    {
+        let entry = self.find(address).ok_or(WalletError::KeystoreNotFound(address))?;
+        let wallet = Wallet::decrypt_keystore(&entry.path, password)?;
+        let (json, _) =
+            wallet.keystore_json(rng, new_password.as_ref(), kdf, Some(entry.uuid.clone()))?;
+
+        let tmp = entry.path.with_extension("tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(tmp, &entry.path)?;
+        Ok(())
+    }
+}
+
+fn parse_address(address: &str) -> Option<Address> {
+    let bytes = hex::decode(address.strip_prefix("0x").unwrap_or(address)).ok()?;
+    (bytes.len() == 20).then(|| Address::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signer;
+    use tempfile::tempdir;
+
+    #[test]
+    fn roundtrips_all_kdfs() {
+        let mut rng = rand::thread_rng();
+        let wallet = Wallet::<SigningKey>::new(&mut rng);
+        for kdf in [
+            KeystoreKdf::Scrypt { log_n: 10, r: 8, p: 1 },
+            KeystoreKdf::Pbkdf2 { c: 1000 },
+            KeystoreKdf::Argon2id { m: 256, t: 1, p: 1 },
+        ] {
+            let json = wallet.encrypt_keystore_json(&mut rng, "password", kdf).unwrap();
+            let decrypted = Wallet::<SigningKey>::decrypt_keystore_json(&json, "password").unwrap();
+            assert_eq!(decrypted, wallet);
+
+            let err = Wallet::<SigningKey>::decrypt_keystore_json(&json, "wrong").unwrap_err();
+            assert!(matches!(err, WalletError::EthKeystoreError(KeystoreError::MacMismatch)));
+        }
+    }
+
+    #[test]
+    fn detects_extended_kdfs() {
+        let mut rng = rand::thread_rng();
+        let wallet = Wallet::<SigningKey>::new(&mut rng);
+        let json = |kdf| wallet.encrypt_keystore_json(&mut rand::thread_rng(), "password", kdf);
+        assert!(!has_extended_kdf(&json(KeystoreKdf::Pbkdf2 { c: 1000 }).unwrap()));
+        assert!(!has_extended_kdf(&json(KeystoreKdf::Scrypt { log_n: 10, r: 8, p: 1 }).unwrap()));
+        assert!(has_extended_kdf(&json(KeystoreKdf::Argon2id { m: 256, t: 1, p: 1 }).unwrap()));
+        assert!(!has_extended_kdf("not a keystore"));
+    }
+
+    #[test]
+    fn writes_standard_keystores() {
+        let dir = tempdir().unwrap();
+        let mut rng = rand::thread_rng();
+        let wallet = Wallet::<SigningKey>::new(&mut rng);
+
+        let kdf = KeystoreKdf::Scrypt { log_n: 10, r: 8, p: 1 };
+        let uuid = wallet.encrypt_keystore(&dir, &mut rng, "password", kdf, None).unwrap();
+        assert_eq!(uuid.len(), 36);
+        // readable by any web3 secret storage implementation
+        let secret = eth_keystore::decrypt_key(dir.path().join(&uuid), "password").unwrap();
+        assert_eq!(Wallet::<SigningKey>::from_bytes(&secret).unwrap().address(), wallet.address());
+
+        let kdf = KeystoreKdf::Argon2id { m: 256, t: 1, p: 1 };
+        wallet.encrypt_keystore(&dir, &mut rng, "password", kdf, Some("argon2id")).unwrap();
+        let decrypted =
+            Wallet::<SigningKey>::decrypt_keystore(dir.path().join("argon2id"), "password")
+                .unwrap();
+        assert_eq!(decrypted, wallet);
+    }
+
+    #[test]
+    fn manages_keystore_dir() {
+        let dir = tempdir().unwrap();
+        let mut rng = rand::thread_rng();
+        let kdf = KeystoreKdf::Pbkdf2 { c: 1000 };
+        let wallet = Wallet::<SigningKey>::new(&mut rng);
+        let uuid = wallet.encrypt_keystore(&dir, &mut rng, "password", kdf, None).unwrap();
+        let other = Wallet::<SigningKey>::new(&mut rng);
+        other.encrypt_keystore(&dir, &mut rng, "other", kdf, Some("other")).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a keystore").unwrap();
+
+        let keystores = KeystoreDir::open(dir.path()).unwrap();
+        assert_eq!(keystores.accounts().len(), 2);
+        let entry = keystores.find(wallet.address()).unwrap();
+        assert_eq!(entry.uuid, uuid);
+        assert_eq!(entry.path, dir.path().join(&uuid));
+        assert!(keystores.find(Address::zero()).is_none());
+
+        assert_eq!(keystores.decrypt(wallet.address(), "password").unwrap(), wallet);
+        assert!(keystores.decrypt(wallet.address(), "other").is_err());
+        assert!(matches!(
+            keystores.decrypt(Address::zero(), "password"),
+            Err(WalletError::KeystoreNotFound(_))
+        ));
+
+        let argon2 = KeystoreKdf::Argon2id { m: 256, t: 1, p: 1 };
+        keystores
+            .rotate_password(wallet.address(), "password", "rotated", argon2, &mut rng)
+            .unwrap();
+        assert!(keystores.decrypt(wallet.address(), "password").is_err());
+        assert_eq!(keystores.decrypt(wallet.address(), "rotated").unwrap(), wallet);
+
+        let mut keystores = keystores;
+        keystores.rescan().unwrap();
+        assert_eq!(keystores.accounts().len(), 2);
+        assert_eq!(keystores.find(wallet.address()).unwrap().uuid, uuid);
+    }
+}
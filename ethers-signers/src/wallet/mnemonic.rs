@@ -12,9 +12,139 @@ use ethers_core::{
 use rand::Rng;
 use std::{fs::File, io::Write, marker::PhantomData, path::PathBuf, str::FromStr};
 use thiserror::Error;
+use zeroize::Zeroizing;
 
 const DEFAULT_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
 
+/// The word counts of BIP-39 phrases, which encode 128, 160, 192, 224 and 256 bits of entropy
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// The languages of the BIP-39 wordlists
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// English
+    English,
+    /// Simplified Chinese
+    ChineseSimplified,
+    /// Traditional Chinese
+    ChineseTraditional,
+    /// Czech
+    Czech,
+    /// French
+    French,
+    /// Italian
+    Italian,
+    /// Japanese
+    Japanese,
+    /// Korean
+    Korean,
+    /// Portuguese
+    Portuguese,
+    /// Spanish
+    Spanish,
+}
+
+/// Evaluates `$body` with `$wordlist` bound to the wordlist type of `$language`
+macro_rules! with_wordlist {
+    ($language:expr, $wordlist:ident => $body:expr) => {
+        match $language {
+            Language::English => {
+                type $wordlist = coins_bip39::English;
+                $body
+            }
+            Language::ChineseSimplified => {
+                type $wordlist = coins_bip39::ChineseSimplified;
+                $body
+            }
+            Language::ChineseTraditional => {
+                type $wordlist = coins_bip39::ChineseTraditional;
+                $body
+            }
+            Language::Czech => {
+                type $wordlist = coins_bip39::Czech;
+                $body
+            }
+            Language::French => {
+                type $wordlist = coins_bip39::French;
+                $body
+            }
+            Language::Italian => {
+                type $wordlist = coins_bip39::Italian;
+                $body
+            }
+            Language::Japanese => {
+                type $wordlist = coins_bip39::Japanese;
+                $body
+            }
+            Language::Korean => {
+                type $wordlist = coins_bip39::Korean;
+                $body
+            }
+            Language::Portuguese => {
+                type $wordlist = coins_bip39::Portuguese;
+                $body
+            }
+            Language::Spanish => {
+                type $wordlist = coins_bip39::Spanish;
+                $body
+            }
+        }
+    };
+}
+
+impl Language {
+    /// All languages, in the order [`Language::detect`] tries them
+    pub const ALL: [Language; 10] = [
+        Language::English,
+        Language::ChineseSimplified,
+        Language::ChineseTraditional,
+        Language::Czech,
+        Language::French,
+        Language::Italian,
+        Language::Japanese,
+        Language::Korean,
+        Language::Portuguese,
+        Language::Spanish,
+    ];
+
+    /// Generates a random phrase of `word_count` words, which has to be one of [`WORD_COUNTS`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ethers_signers::Language;
+    ///
+    /// let phrase = Language::Spanish.generate_phrase(&mut rand::thread_rng(), 24).unwrap();
+    /// assert_eq!(phrase.split_whitespace().count(), 24);
+    /// assert!(Language::Spanish.validate_phrase(&phrase).is_ok());
+    /// ```
+    pub fn generate_phrase<R: Rng>(
+        &self,
+        rng: &mut R,
+        word_count: usize,
+    ) -> Result<String, WalletError> {
+        with_wordlist!(self, W => Ok(Mnemonic::<W>::new_with_count(rng, word_count)?.to_phrase()))
+    }
+
+    /// Checks that every word of `phrase` is in the wordlist, that the phrase has a valid word
+    /// count and that its checksum matches.
+    ///
+    /// Words may be separated by any whitespace, e.g. the ideographic space of Japanese phrases.
+    pub fn validate_phrase(&self, phrase: &str) -> Result<(), WalletError> {
+        let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+        with_wordlist!(self, W => Mnemonic::<W>::new_from_phrase(&phrase).map(drop)?);
+        Ok(())
+    }
+
+    /// Returns the first language in which `phrase` is valid.
+    ///
+    /// Some wordlists share words, e.g. English and French, or the Chinese ones, so a phrase may
+    /// be valid in several languages.
+    pub fn detect(phrase: &str) -> Option<Language> {
+        Self::ALL.into_iter().find(|language| language.validate_phrase(phrase).is_ok())
+    }
+}
+
 /// Represents a structure that can resolve into a `Wallet<SigningKey>`.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MnemonicBuilder<W: Wordlist> {
@@ -181,10 +311,10 @@ impl<W: Wordlist> MnemonicBuilder<W> {
         let derived_priv_key =
             mnemonic.derive_key(&self.derivation_path, self.password.as_deref())?;
         let key: &coins_bip32::prelude::SigningKey = derived_priv_key.as_ref();
-        let signer = SigningKey::from_bytes(&key.to_bytes())?;
+        let signer = SigningKey::from_bytes(&Zeroizing::new(key.to_bytes()))?;
         let address = secret_key_to_address(&signer);
 
-        Ok(Wallet::<SigningKey> { signer, address, chain_id: 1 })
+        Ok(Wallet::<SigningKey> { signer, address, chain_id: 1, chain_guard: false })
     }
 }
 
@@ -247,6 +377,37 @@ mod tests {
         })
     }
 
+    #[test]
+    fn phrase_languages() {
+        let valid = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                     abandon abandon about";
+        assert!(Language::English.validate_phrase(valid).is_ok());
+        assert_eq!(Language::detect(valid), Some(Language::English));
+
+        // wrong checksum, unknown word and invalid word count
+        let checksum = valid.replace("about", "abandon");
+        let unknown = valid.replace("about", "aboot");
+        let count = valid.replacen("abandon ", "", 1);
+        for invalid in [&checksum, &unknown, &count] {
+            assert!(Language::English.validate_phrase(invalid).is_err(), "{invalid}");
+            assert_eq!(Language::detect(invalid), None);
+        }
+
+        let mut rng = rand::thread_rng();
+        for language in Language::ALL {
+            for word_count in WORD_COUNTS {
+                let phrase = language.generate_phrase(&mut rng, word_count).unwrap();
+                assert_eq!(phrase.split_whitespace().count(), word_count);
+                language.validate_phrase(&phrase).unwrap();
+            }
+        }
+        assert!(Language::Japanese.generate_phrase(&mut rng, 13).is_err());
+
+        let japanese = Language::Japanese.generate_phrase(&mut rng, 12).unwrap();
+        assert!(Language::Japanese.validate_phrase(&japanese.replace(' ', "\u{3000}")).is_ok());
+        assert_eq!(Language::detect(&japanese), Some(Language::Japanese));
+    }
+
     #[tokio::test]
     async fn mnemonic_write_read() {
         let dir = tempdir().unwrap();
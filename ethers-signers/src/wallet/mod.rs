@@ -1,13 +1,36 @@
 mod mnemonic;
-pub use mnemonic::{MnemonicBuilder, MnemonicBuilderError};
+pub use mnemonic::{Language, MnemonicBuilder, MnemonicBuilderError, WORD_COUNTS};
+
+mod hd;
+pub use hd::HDWallet;
+
+mod audit;
+pub use audit::{
+    audit_addresses, AuditKey, AuditReport, DerivedAddress, BIP44_TEMPLATE, INDEX_PLACEHOLDER,
+    LEDGER_LEGACY_TEMPLATE, LEDGER_LIVE_TEMPLATE,
+};
+
+mod dev;
+pub use dev::{dev_addresses, DEV_ACCOUNTS, DEV_CHAIN_ID, DEV_MNEMONIC};
 
 mod private_key;
 pub use private_key::WalletError;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod keystore;
+#[cfg(not(target_arch = "wasm32"))]
+pub use keystore::{KeystoreDir, KeystoreEntry, KeystoreKdf};
+
+mod secret;
+pub use secret::SecretString;
+
+mod platform;
+pub use platform::{PlatformKey, PlatformKeyError, PlatformKeystore};
+
 #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 mod yubi;
 
-use crate::{to_eip155_v, Signer};
+use crate::{to_eip155_v, AuthorizationSigner, IntendedValidatorSigner, Signer};
 use ethers_core::{
     k256::{
         ecdsa::{signature::hazmat::PrehashSigner, RecoveryId, Signature as RecoverableSignature},
@@ -15,17 +38,26 @@ use ethers_core::{
         Secp256k1,
     },
     types::{
-        transaction::{eip2718::TypedTransaction, eip712::Eip712},
-        Address, Signature, H256, U256,
+        transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
+        Address, Bytes, Signature, H256, U256,
     },
-    utils::hash_message,
+    utils::{hash_intended_validator, hash_message},
 };
 
 use async_trait::async_trait;
 use std::fmt;
 
+/// The number of hashes from which [`Wallet::sign_hashes`] signs in parallel, below it the
+/// overhead of distributing the work outweighs the gain
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+const PARALLEL_SIGNING_THRESHOLD: usize = 16;
+
 /// An Ethereum private-public key pair which can be used for signing messages.
 ///
+/// The private key of a [`LocalWallet`](crate::LocalWallet) is zeroized when the wallet is dropped,
+/// which also holds for its clones, and is never printed by its `Debug` implementation. Use
+/// [`Wallet::from_secret`] to parse keys that are held in a [`SecretString`].
+///
 /// # Examples
 ///
 /// ## Signing and Verifying a message
@@ -68,12 +100,65 @@ pub struct Wallet<D: PrehashSigner<(RecoverableSignature, RecoveryId)>> {
     pub(crate) address: Address,
     /// The wallet's chain id (for EIP-155)
     pub(crate) chain_id: u64,
+    /// Whether the wallet only signs for its own chain, see [`Wallet::with_chain_guard`]
+    pub(crate) chain_guard: bool,
 }
 
 impl<D: PrehashSigner<(RecoverableSignature, RecoveryId)>> Wallet<D> {
     /// Construct a new wallet with an external Signer
     pub fn new_with_signer(signer: D, address: Address, chain_id: u64) -> Self {
-        Wallet { signer, address, chain_id }
+        Wallet { signer, address, chain_id, chain_guard: false }
+    }
+
+    /// Restricts the wallet to signing for its own chain id.
+    ///
+    /// A guarded wallet refuses to sign transactions, EIP-712 payloads and EIP-7702
+    /// authorizations whose chain id is missing or differs from the wallet's, instead of signing
+    /// them for the other chain or filling in its own chain id. Raw hashes can't be checked and
+    /// are refused by [`Wallet::sign_hash`]. Messages are still signed, since the EIP-191 prefix
+    /// prevents them from being replayed as transactions.
+    ///
+    /// ```
+    /// use ethers_core::{rand::thread_rng, types::TransactionRequest};
+    /// use ethers_signers::{LocalWallet, Signer};
+    ///
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let wallet = LocalWallet::new(&mut thread_rng()).with_chain_id(1u64).with_chain_guard();
+    ///
+    /// let tx = TransactionRequest::pay(wallet.address(), 100).chain_id(5);
+    /// assert!(wallet.sign_transaction(&tx.clone().into()).await.is_err());
+    ///
+    /// // signing for another chain has to be allowed explicitly
+    /// let wallet = wallet.allow_chain_override();
+    /// wallet.sign_transaction(&tx.into()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_chain_guard(mut self) -> Self {
+        self.chain_guard = true;
+        self
+    }
+
+    /// Lifts the restriction of [`Wallet::with_chain_guard`], allowing the wallet to sign for any
+    /// chain. This is the default.
+    #[must_use]
+    pub fn allow_chain_override(mut self) -> Self {
+        self.chain_guard = false;
+        self
+    }
+
+    /// Returns `true` if the wallet only signs for its own chain id.
+    pub fn is_chain_guarded(&self) -> bool {
+        self.chain_guard
+    }
+
+    /// Returns an error if the wallet is guarded and `chain_id` is not its own.
+    fn check_chain_id(&self, chain_id: Option<U256>) -> Result<(), WalletError> {
+        if self.chain_guard && chain_id != Some(self.chain_id.into()) {
+            return Err(WalletError::ChainIdMismatch { expected: self.chain_id, got: chain_id })
+        }
+        Ok(())
     }
 }
 
@@ -89,10 +174,19 @@ impl<D: Sync + Send + PrehashSigner<(RecoverableSignature, RecoveryId)>> Signer
         let message = message.as_ref();
         let message_hash = hash_message(message);
 
-        self.sign_hash(message_hash)
+        self.sign_hash_unchecked(message_hash)
+    }
+
+    async fn sign_messages<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        messages: &[S],
+    ) -> Result<Vec<Signature>, Self::Error> {
+        let hashes: Vec<_> = messages.iter().map(hash_message).collect();
+        self.sign_hashes_unchecked(&hashes)
     }
 
     async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        self.check_chain_id(tx.chain_id().map(|id| id.as_u64().into()))?;
         let mut tx_with_chain = tx.clone();
         if tx_with_chain.chain_id().is_none() {
             // in the case we don't have a chain_id, let's use the signer chain id instead
@@ -105,10 +199,14 @@ impl<D: Sync + Send + PrehashSigner<(RecoverableSignature, RecoveryId)>> Signer
         &self,
         payload: &T,
     ) -> Result<Signature, Self::Error> {
+        if self.chain_guard {
+            let domain = payload.domain().map_err(|e| Self::Error::Eip712Error(e.to_string()))?;
+            self.check_chain_id(domain.chain_id)?;
+        }
         let encoded =
             payload.encode_eip712().map_err(|e| Self::Error::Eip712Error(e.to_string()))?;
 
-        self.sign_hash(H256::from(encoded))
+        self.sign_hash_unchecked(H256::from(encoded))
     }
 
     fn address(&self) -> Address {
@@ -127,26 +225,95 @@ impl<D: Sync + Send + PrehashSigner<(RecoverableSignature, RecoveryId)>> Signer
     }
 }
 
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<D: Sync + Send + PrehashSigner<(RecoverableSignature, RecoveryId)>> AuthorizationSigner
+    for Wallet<D>
+{
+    async fn sign_authorization(
+        &self,
+        authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        // authorizations with a chain id of 0 are valid on every chain
+        self.check_chain_id(Some(authorization.chain_id))?;
+        self.sign_hash_unchecked(authorization.signature_hash())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<D: Sync + Send + PrehashSigner<(RecoverableSignature, RecoveryId)>> IntendedValidatorSigner
+    for Wallet<D>
+{
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        validator: Address,
+        data: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_hash_unchecked(hash_intended_validator(validator, data))
+    }
+}
+
 impl<D: PrehashSigner<(RecoverableSignature, RecoveryId)>> Wallet<D> {
     /// Synchronously signs the provided transaction, normalizing the signature `v` value with
     /// EIP-155 using the transaction's `chain_id`, or the signer's `chain_id` if the transaction
     /// does not specify one.
+    ///
+    /// Fails if the wallet is [guarded](Wallet::with_chain_guard) and the transaction is not for
+    /// its chain.
     pub fn sign_transaction_sync(&self, tx: &TypedTransaction) -> Result<Signature, WalletError> {
+        self.check_chain_id(tx.chain_id().map(|id| id.as_u64().into()))?;
         // rlp (for sighash) must have the same chain id as v in the signature
         let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
         let mut tx = tx.clone();
         tx.set_chain_id(chain_id);
+        // the sender is part of the typed data zkSync transactions are signed as
+        #[cfg(feature = "zksync")]
+        if tx.from().is_none() {
+            tx.set_from(self.address);
+        }
 
         let sighash = tx.sighash();
-        let mut sig = self.sign_hash(sighash)?;
+        let mut sig = self.sign_hash_unchecked(sighash)?;
 
         // sign_hash sets `v` to recid + 27, so we need to subtract 27 before normalizing
         sig.v = to_eip155_v(sig.v as u8 - 27, chain_id);
         Ok(sig)
     }
 
+    /// Synchronously signs the provided transaction like [`Wallet::sign_transaction_sync`], and
+    /// returns the signed transaction encoded for `eth_sendRawTransaction`.
+    ///
+    /// Together with a transaction whose nonce, gas and fees are set, this signs fully offline,
+    /// e.g. on an air-gapped machine.
+    pub fn sign_raw_transaction_sync(&self, tx: &TypedTransaction) -> Result<Bytes, WalletError> {
+        self.check_chain_id(tx.chain_id().map(|id| id.as_u64().into()))?;
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+        #[cfg(feature = "zksync")]
+        if tx.from().is_none() {
+            tx.set_from(self.address);
+        }
+        let signature = self.sign_transaction_sync(&tx)?;
+        Ok(tx.rlp_network(&signature))
+    }
+
     /// Signs the provided hash.
+    ///
+    /// The signature always has a low `s`, see [`Signature::normalize_s`].
+    ///
+    /// Fails if the wallet is [guarded](Wallet::with_chain_guard), since the hash may be the
+    /// sighash of a transaction for another chain.
     pub fn sign_hash(&self, hash: H256) -> Result<Signature, WalletError> {
+        if self.chain_guard {
+            return Err(WalletError::GuardedHashSigning)
+        }
+        self.sign_hash_unchecked(hash)
+    }
+
+    fn sign_hash_unchecked(&self, hash: H256) -> Result<Signature, WalletError> {
         let (recoverable_sig, recovery_id) = self.signer.sign_prehash(hash.as_ref())?;
 
         let v = u8::from(recovery_id) as u64 + 27;
@@ -156,7 +323,38 @@ impl<D: PrehashSigner<(RecoverableSignature, RecoveryId)>> Wallet<D> {
         let r = U256::from_big_endian(r_bytes.as_slice());
         let s = U256::from_big_endian(s_bytes.as_slice());
 
-        Ok(Signature { r, s, v })
+        // the signer may be an external key store, which is not bound to produce a low `s`
+        Ok(Signature { r, s, v }.normalize_s())
+    }
+
+    /// Signs the provided hashes, returning their signatures in the same order.
+    ///
+    /// With the `rayon` feature, large batches are signed in parallel on the current rayon thread
+    /// pool, which is the global one unless called within [`ThreadPool::install`].
+    ///
+    /// Like [`Wallet::sign_hash`], fails if the wallet is [guarded](Wallet::with_chain_guard).
+    ///
+    /// [`ThreadPool::install`]: https://docs.rs/rayon/latest/rayon/struct.ThreadPool.html#method.install
+    pub fn sign_hashes(&self, hashes: &[H256]) -> Result<Vec<Signature>, WalletError>
+    where
+        D: Sync,
+    {
+        if self.chain_guard {
+            return Err(WalletError::GuardedHashSigning)
+        }
+        self.sign_hashes_unchecked(hashes)
+    }
+
+    fn sign_hashes_unchecked(&self, hashes: &[H256]) -> Result<Vec<Signature>, WalletError>
+    where
+        D: Sync,
+    {
+        #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+        if hashes.len() >= PARALLEL_SIGNING_THRESHOLD {
+            use rayon::prelude::*;
+            return hashes.par_iter().map(|hash| self.sign_hash_unchecked(*hash)).collect()
+        }
+        hashes.iter().map(|hash| self.sign_hash_unchecked(*hash)).collect()
     }
 
     /// Gets the wallet's signer
@@ -171,6 +369,7 @@ impl<D: PrehashSigner<(RecoverableSignature, RecoveryId)>> fmt::Debug for Wallet
         f.debug_struct("Wallet")
             .field("address", &self.address)
             .field("chain_Id", &self.chain_id)
+            .field("chain_guard", &self.chain_guard)
             .finish()
     }
 }
@@ -0,0 +1,207 @@
+//! Helpers for creating wallets with device-bound keys of a platform key store, like the Apple
+//! Secure Enclave or a TPM 2.0
+use super::Wallet;
+use ethers_core::{
+    k256::ecdsa::{
+        signature::{hazmat::PrehashSigner, Error as SignatureError},
+        RecoveryId, Signature as RecoverableSignature, SigningKey, VerifyingKey,
+    },
+    rand::{CryptoRng, RngCore},
+    types::Address,
+    utils::keccak256,
+};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// A secure element of the platform holding a non-exportable wrapping key, e.g. the Apple Secure
+/// Enclave or a TPM 2.0.
+///
+/// Secure elements generally do not support secp256k1. The signing key of a [`PlatformKey`] is
+/// therefore kept encrypted ("wrapped") with a hardware key, e.g. an ECIES key of the Secure
+/// Enclave or a sealing key of the TPM, and only decrypted in memory for the duration of a
+/// signature. The wrapped key is useless on any other device.
+///
+/// Implementations bind to the platform APIs, e.g. `SecKeyCreateEncryptedData` on Apple platforms
+/// or `TPM2_Create`/`TPM2_Unseal` through a TSS library.
+pub trait PlatformKeystore: Send + Sync {
+    /// The error of the platform API
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Encrypts `secret` with the hardware key.
+    fn wrap(&self, secret: &[u8]) -> Result<Vec<u8>, Self::Error>;
+
+    /// Decrypts a key that was encrypted with [`PlatformKeystore::wrap`].
+    fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, Self::Error>;
+
+    /// Returns the platform's attestation of the hardware key, e.g. the certificate chain of an
+    /// App Attest key or a TPM2 quote, if the platform supports attestation.
+    fn attestation(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Error when creating or loading a [`PlatformKey`]
+#[derive(Debug, thiserror::Error)]
+pub enum PlatformKeyError<E: std::error::Error + 'static> {
+    /// The platform key store failed
+    #[error(transparent)]
+    Keystore(E),
+    /// The unwrapped key is not a valid secp256k1 key
+    #[error(transparent)]
+    InvalidKey(#[from] SignatureError),
+}
+
+/// A secp256k1 key wrapped by a [`PlatformKeystore`], which is unwrapped for every signature.
+///
+/// Persist [`PlatformKey::wrapped_key`] to load the key again with [`PlatformKey::from_wrapped`].
+pub struct PlatformKey<K> {
+    keystore: K,
+    wrapped: Vec<u8>,
+    verifying_key: VerifyingKey,
+}
+
+impl<K: PlatformKeystore> PlatformKey<K> {
+    /// Generates a new signing key and wraps it with the `keystore`.
+    ///
+    /// The key never leaves the process unencrypted.
+    pub fn generate<R: CryptoRng + RngCore>(
+        keystore: K,
+        rng: &mut R,
+    ) -> Result<Self, PlatformKeyError<K::Error>> {
+        let key = SigningKey::random(rng);
+        let mut secret = key.to_bytes();
+        let wrapped = keystore.wrap(&secret);
+        secret.zeroize();
+        let wrapped = wrapped.map_err(PlatformKeyError::Keystore)?;
+        Ok(Self { keystore, wrapped, verifying_key: *key.verifying_key() })
+    }
+
+    /// Loads a key that was wrapped by the `keystore`.
+    pub fn from_wrapped(keystore: K, wrapped: Vec<u8>) -> Result<Self, PlatformKeyError<K::Error>> {
+        let key = unwrap_key(&keystore, &wrapped)?;
+        Ok(Self { verifying_key: *key.verifying_key(), keystore, wrapped })
+    }
+
+    /// Returns the wrapped key, which can be stored and only be unwrapped by the same device.
+    pub fn wrapped_key(&self) -> &[u8] {
+        &self.wrapped
+    }
+
+    /// Returns the attestation of the hardware key that wraps the signing key, if available.
+    pub fn attestation(&self) -> Option<Vec<u8>> {
+        self.keystore.attestation()
+    }
+
+    /// Returns the public key.
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// Returns the platform key store.
+    pub fn keystore(&self) -> &K {
+        &self.keystore
+    }
+}
+
+fn unwrap_key<K: PlatformKeystore>(
+    keystore: &K,
+    wrapped: &[u8],
+) -> Result<SigningKey, PlatformKeyError<K::Error>> {
+    let mut secret = keystore.unwrap(wrapped).map_err(PlatformKeyError::Keystore)?;
+    let key = SigningKey::from_slice(&secret);
+    secret.zeroize();
+    Ok(key?)
+}
+
+impl<K: PlatformKeystore> PrehashSigner<(RecoverableSignature, RecoveryId)> for PlatformKey<K> {
+    fn sign_prehash(
+        &self,
+        prehash: &[u8],
+    ) -> Result<(RecoverableSignature, RecoveryId), SignatureError> {
+        let key = unwrap_key(&self.keystore, &self.wrapped).map_err(|err| match err {
+            PlatformKeyError::Keystore(err) => SignatureError::from_source(err),
+            PlatformKeyError::InvalidKey(err) => err,
+        })?;
+        // the key is zeroized when it is dropped
+        key.sign_prehash(prehash)
+    }
+}
+
+// do not log the wrapped key
+impl<K> fmt::Debug for PlatformKey<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlatformKey").field("verifying_key", &self.verifying_key).finish()
+    }
+}
+
+impl<K: PlatformKeystore> From<PlatformKey<K>> for Wallet<PlatformKey<K>> {
+    fn from(signer: PlatformKey<K>) -> Self {
+        let public_key = signer.verifying_key().to_encoded_point(/* compress = */ false);
+        let public_key = public_key.as_bytes();
+        debug_assert_eq!(public_key[0], 0x04);
+        let hash = keccak256(&public_key[1..]);
+        let address = Address::from_slice(&hash[12..]);
+        Self { signer, address, chain_id: 1, chain_guard: false }
+    }
+}
+
+impl<K: PlatformKeystore> Wallet<PlatformKey<K>> {
+    /// Returns the attestation of the hardware key that wraps the wallet's signing key, if the
+    /// platform supports attestation.
+    pub fn attestation(&self) -> Option<Vec<u8>> {
+        self.signer.attestation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signer;
+    use ethers_core::rand::thread_rng;
+
+    /// Stands in for a secure element, which never hands out its key
+    #[derive(Debug)]
+    struct XorKeystore([u8; 32]);
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("invalid wrapped key")]
+    struct InvalidWrappedKey;
+
+    impl PlatformKeystore for XorKeystore {
+        type Error = InvalidWrappedKey;
+
+        fn wrap(&self, secret: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            Ok(secret.iter().zip(self.0).map(|(a, b)| a ^ b).collect())
+        }
+
+        fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            if wrapped.len() != 32 {
+                return Err(InvalidWrappedKey)
+            }
+            self.wrap(wrapped)
+        }
+
+        fn attestation(&self) -> Option<Vec<u8>> {
+            Some(b"attested".to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_with_wrapped_key() {
+        let key = PlatformKey::generate(XorKeystore([7; 32]), &mut thread_rng()).unwrap();
+        let wrapped = key.wrapped_key().to_vec();
+        let wallet = Wallet::from(key);
+        assert_eq!(wallet.attestation().as_deref(), Some(&b"attested"[..]));
+
+        let signature = wallet.sign_message("hello").await.unwrap();
+        assert_eq!(signature.recover("hello").unwrap(), wallet.address());
+
+        // the same device loads the same key
+        let loaded =
+            Wallet::from(PlatformKey::from_wrapped(XorKeystore([7; 32]), wrapped).unwrap());
+        assert_eq!(loaded.address(), wallet.address());
+
+        let err = PlatformKey::from_wrapped(XorKeystore([7; 32]), vec![1]).unwrap_err();
+        assert!(matches!(err, PlatformKeyError::Keystore(InvalidWrappedKey)));
+    }
+}
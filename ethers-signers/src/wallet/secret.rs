@@ -0,0 +1,48 @@
+use std::{fmt, str::FromStr};
+use zeroize::Zeroizing;
+
+/// A string holding secret material like a hex encoded private key, which is zeroized when it is
+/// dropped and redacted when it is printed.
+///
+/// Pass secrets through a `SecretString` as soon as they are read, e.g. from a prompt or an
+/// environment variable, so that no copies are left behind in memory:
+///
+/// ```
+/// use ethers_signers::{LocalWallet, SecretString};
+///
+/// let key = SecretString::from(
+///     "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".to_string(),
+/// );
+/// assert_eq!(format!("{key:?}"), "SecretString(..)");
+///
+/// let wallet = LocalWallet::from_secret(&key).unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    /// Returns the secret. Avoid copying it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(Zeroizing::new(secret))
+    }
+}
+
+impl FromStr for SecretString {
+    type Err = std::convert::Infallible;
+
+    fn from_str(secret: &str) -> Result<Self, Self::Err> {
+        Ok(secret.to_string().into())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(..)")
+    }
+}
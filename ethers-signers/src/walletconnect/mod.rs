@@ -0,0 +1,826 @@
+//! WalletConnect v2 Signer
+//!
+//! Proxies signing requests through a WalletConnect v2 session, so that a server-side application
+//! can request signatures from an end-user's mobile wallet.
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use ethers_core::{
+    types::{
+        transaction::{
+            eip2718::{TypedTransaction, TypedTransactionError},
+            eip712::{Eip712, TypedData},
+            eip7702::Authorization,
+        },
+        Address, Signature, SignatureError, H256,
+    },
+    utils::rlp::Rlp,
+};
+use hkdf::Hkdf;
+use rand::{CryptoRng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    fmt, fs,
+    path::Path,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// The relay protocol used by the public WalletConnect relay.
+pub const RELAY_PROTOCOL: &str = "irn";
+
+/// The methods requested from the wallet when proposing a session.
+const METHODS: [&str; 3] = ["personal_sign", "eth_signTransaction", "eth_signTypedData_v4"];
+
+/// The events requested from the wallet when proposing a session.
+const EVENTS: [&str; 2] = ["chainChanged", "accountsChanged"];
+
+/// How long the relay keeps undelivered messages.
+const MESSAGE_TTL: Duration = Duration::from_secs(300);
+
+// relay message tags of the session lifecycle methods
+const TAG_SESSION_PROPOSE: u32 = 1100;
+const TAG_SESSION_SETTLE_RESPONSE: u32 = 1103;
+const TAG_SESSION_REQUEST: u32 = 1108;
+
+/// The envelope type of messages encrypted with a shared symmetric key.
+const ENVELOPE_TYPE_0: u8 = 0;
+const IV_LENGTH: usize = 12;
+
+/// Error thrown by the [WalletConnectSigner]
+#[derive(Debug, thiserror::Error)]
+pub enum WalletConnectError {
+    /// The pairing URI could not be parsed
+    #[error("invalid pairing uri: {0}")]
+    InvalidUri(String),
+    /// A relayed message is not a valid type 0 envelope
+    #[error("invalid envelope")]
+    InvalidEnvelope,
+    /// A message could not be encrypted or decrypted with the topic's symmetric key
+    #[error("failed to encrypt or decrypt message")]
+    Encryption,
+    /// Error propagated from the [Relay]
+    #[error("relay error: {0}")]
+    Relay(BoxError),
+    /// The wallet responded with a JSON-RPC error, e.g. because the user rejected the request
+    #[error("wallet returned error {code}: {message}")]
+    Rpc {
+        /// The JSON-RPC error code
+        code: i64,
+        /// The error message
+        message: String,
+    },
+    /// The wallet sent a message that does not follow the protocol
+    #[error("unexpected message: {0}")]
+    UnexpectedMessage(String),
+    /// The session does not contain any EVM account
+    #[error("the session has no eip155 accounts")]
+    NoAccounts,
+    /// The wallet ended the session
+    #[error("the session was deleted by the wallet")]
+    SessionDeleted,
+    /// The session expired and has to be re-established
+    #[error("the session has expired")]
+    SessionExpired,
+    /// The wallet changed the transaction, e.g. its nonce or gas, before signing it
+    #[error("the wallet modified the transaction before signing it")]
+    TransactionModified,
+    /// The wallet signed with a different account than the requested one
+    #[error("the signature was not produced by {0:?}")]
+    WrongSigner(Address),
+    /// The signing scheme can not be requested from WalletConnect wallets
+    #[error("{0} is not supported over WalletConnect")]
+    Unsupported(&'static str),
+    /// Error when (de)serializing messages or the session
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// Error when reading or writing the session
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Error when decoding an envelope
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+    /// Error when decoding hex data returned by the wallet
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    /// Error when decoding a signature returned by the wallet
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+    /// Error when decoding a transaction signed by the wallet
+    #[error(transparent)]
+    Transaction(#[from] TypedTransactionError),
+}
+
+/// A transport to a WalletConnect v2 relay.
+///
+/// The relay forwards end-to-end encrypted messages between the participants of a topic.
+/// Implementations take care of the relay connection and its authentication, and only need to
+/// deliver the messages of subscribed topics in order.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait Relay: fmt::Debug + Send + Sync {
+    /// Subscribes to the messages published on `topic`.
+    async fn subscribe(&self, topic: H256) -> Result<(), BoxError>;
+
+    /// Publishes `message` on `topic`.
+    async fn publish(
+        &self,
+        topic: H256,
+        message: String,
+        tag: u32,
+        ttl: Duration,
+    ) -> Result<(), BoxError>;
+
+    /// Waits for the next message published on the subscribed `topic`.
+    async fn next_message(&self, topic: H256) -> Result<String, BoxError>;
+}
+
+/// A WalletConnect v2 pairing URI, usually displayed to the user as a QR code.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PairingUri {
+    /// The pairing topic
+    pub topic: H256,
+    /// The symmetric key of the pairing topic
+    pub sym_key: [u8; 32],
+    /// The relay protocol
+    pub relay_protocol: String,
+    /// The unix timestamp after which the pairing can no longer be used
+    pub expiry: Option<u64>,
+}
+
+impl PairingUri {
+    /// Generates a new pairing with a random topic and key that expires after `ttl`.
+    pub fn generate<R: Rng + CryptoRng>(rng: &mut R, ttl: Duration) -> Self {
+        let mut sym_key = [0u8; 32];
+        rng.fill_bytes(&mut sym_key);
+        Self {
+            topic: H256::random_using(rng),
+            sym_key,
+            relay_protocol: RELAY_PROTOCOL.to_string(),
+            expiry: Some(now() + ttl.as_secs()),
+        }
+    }
+}
+
+impl fmt::Debug for PairingUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PairingUri")
+            .field("topic", &self.topic)
+            .field("relay_protocol", &self.relay_protocol)
+            .field("expiry", &self.expiry)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for PairingUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "wc:{}@2?relay-protocol={}&symKey={}",
+            hex::encode(self.topic),
+            self.relay_protocol,
+            hex::encode(self.sym_key)
+        )?;
+        if let Some(expiry) = self.expiry {
+            write!(f, "&expiryTimestamp={expiry}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PairingUri {
+    type Err = WalletConnectError;
+
+    /// Parses a URI of the form `wc:{topic}@2?relay-protocol={protocol}&symKey={key}`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WalletConnectError::InvalidUri(s.to_string());
+
+        let rest = s.strip_prefix("wc:").ok_or_else(invalid)?;
+        let (topic, rest) = rest.split_once('@').ok_or_else(invalid)?;
+        let (version, query) = rest.split_once('?').ok_or_else(invalid)?;
+        if version != "2" {
+            return Err(invalid())
+        }
+        let topic = H256::from_str(topic).map_err(|_| invalid())?;
+
+        let (mut relay_protocol, mut sym_key, mut expiry) = (None, None, None);
+        for param in query.split('&') {
+            match param.split_once('=').ok_or_else(invalid)? {
+                ("relay-protocol", value) => relay_protocol = Some(value.to_string()),
+                ("symKey", value) => {
+                    let mut key = [0u8; 32];
+                    hex::decode_to_slice(value, &mut key).map_err(|_| invalid())?;
+                    sym_key = Some(key);
+                }
+                ("expiryTimestamp", value) => expiry = Some(value.parse().map_err(|_| invalid())?),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            topic,
+            sym_key: sym_key.ok_or_else(invalid)?,
+            relay_protocol: relay_protocol.ok_or_else(invalid)?,
+            expiry,
+        })
+    }
+}
+
+/// Metadata describing a participant of a session, shown to the user by the other side.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// The application name
+    pub name: String,
+    /// A short description of the application
+    pub description: String,
+    /// The application URL
+    pub url: String,
+    /// URLs of the application's icons
+    #[serde(default)]
+    pub icons: Vec<String>,
+}
+
+/// An established WalletConnect v2 session.
+///
+/// Sessions outlive the process that created them, so they can be persisted with
+/// [`Session::save`] and restored with [`Session::load`] instead of pairing again.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// The session topic
+    pub topic: H256,
+    /// The symmetric key of the session topic
+    pub sym_key: H256,
+    /// The accounts approved by the wallet as CAIP-10 account ids, e.g. `eip155:1:0xab16...`
+    pub accounts: Vec<String>,
+    /// The unix timestamp at which the session expires
+    pub expiry: u64,
+    /// The wallet's metadata
+    #[serde(default)]
+    pub peer: Option<Metadata>,
+}
+
+impl Session {
+    /// Reads a session from the JSON file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WalletConnectError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Writes the session to the JSON file at `path`.
+    ///
+    /// The file is replaced atomically, so an interrupted write never leaves a corrupt session
+    /// behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), WalletConnectError> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Returns `true` if the session has expired.
+    pub fn is_expired(&self) -> bool {
+        self.expiry <= now()
+    }
+
+    /// Returns the chain ids and addresses of the approved EVM accounts.
+    pub fn eip155_accounts(&self) -> Vec<(u64, Address)> {
+        self.accounts
+            .iter()
+            .filter_map(|account| {
+                let mut parts = account.split(':');
+                match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                    (Some("eip155"), Some(chain_id), Some(address), None) => {
+                        Some((chain_id.parse().ok()?, address.parse().ok()?))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("topic", &self.topic)
+            .field("accounts", &self.accounts)
+            .field("expiry", &self.expiry)
+            .field("peer", &self.peer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encrypts `message` into a base64 encoded type 0 envelope.
+pub fn seal(sym_key: &[u8; 32], message: &[u8]) -> Result<String, WalletConnectError> {
+    let mut iv = [0u8; IV_LENGTH];
+    rand::thread_rng().fill_bytes(&mut iv);
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(sym_key))
+        .encrypt(Nonce::from_slice(&iv), message)
+        .map_err(|_| WalletConnectError::Encryption)?;
+
+    let mut envelope = Vec::with_capacity(1 + IV_LENGTH + ciphertext.len());
+    envelope.push(ENVELOPE_TYPE_0);
+    envelope.extend_from_slice(&iv);
+    envelope.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(envelope))
+}
+
+/// Decrypts a base64 encoded type 0 envelope.
+pub fn open(sym_key: &[u8; 32], envelope: &str) -> Result<Vec<u8>, WalletConnectError> {
+    let envelope = STANDARD.decode(envelope)?;
+    match envelope.split_first() {
+        Some((&ENVELOPE_TYPE_0, rest)) if rest.len() > IV_LENGTH => {
+            let (iv, ciphertext) = rest.split_at(IV_LENGTH);
+            ChaCha20Poly1305::new(Key::from_slice(sym_key))
+                .decrypt(Nonce::from_slice(iv), ciphertext)
+                .map_err(|_| WalletConnectError::Encryption)
+        }
+        _ => Err(WalletConnectError::InvalidEnvelope),
+    }
+}
+
+/// Derives the symmetric key and topic of a session from the X25519 key agreement between the
+/// proposer and the responder.
+pub fn derive_session_key(secret: &StaticSecret, peer: &PublicKey) -> ([u8; 32], H256) {
+    let shared = secret.diffie_hellman(peer);
+    let mut sym_key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, shared.as_bytes())
+        .expand(&[], &mut sym_key)
+        .expect("32 bytes is a valid output length");
+    let topic = H256::from_slice(&Sha256::digest(sym_key));
+    (sym_key, topic)
+}
+
+/// A signer that requests signatures from a wallet over a WalletConnect v2 session.
+///
+/// Every signing request has to be approved by the user in their wallet, so requests may take a
+/// long time or be rejected.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ethers_signers::{walletconnect::{Metadata, PairingUri, Relay, Session}, Signer, WalletConnectSigner};
+/// # use std::time::Duration;
+/// # async fn foo<R: Relay>(relay: R) -> Result<(), Box<dyn std::error::Error>> {
+/// let pairing = PairingUri::generate(&mut rand::thread_rng(), Duration::from_secs(300));
+/// // show the URI to the user, e.g. as a QR code
+/// println!("{pairing}");
+///
+/// let metadata = Metadata { name: "My App".into(), ..Default::default() };
+/// let signer = WalletConnectSigner::connect(relay, &pairing, metadata, &[1]).await?;
+/// signer.session().save("session.json")?;
+///
+/// let signature = signer.sign_message("hello world").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WalletConnectSigner<R> {
+    relay: R,
+    session: Session,
+    address: Address,
+    chain_id: u64,
+}
+
+impl<R: Relay> WalletConnectSigner<R> {
+    /// Proposes a session on `pairing` and waits until the wallet approves it.
+    ///
+    /// The pairing URI should be shown to the user before calling this, since it only returns
+    /// once the wallet has scanned it and the user approved the session.
+    pub async fn connect(
+        relay: R,
+        pairing: &PairingUri,
+        metadata: Metadata,
+        chain_ids: &[u64],
+    ) -> Result<Self, WalletConnectError> {
+        let secret = StaticSecret::random_from_rng(rand::thread_rng());
+        let public = PublicKey::from(&secret);
+
+        relay.subscribe(pairing.topic).await.map_err(WalletConnectError::Relay)?;
+        let id = request_id();
+        let proposal = json!({
+            "id": id,
+            "jsonrpc": "2.0",
+            "method": "wc_sessionPropose",
+            "params": {
+                "relays": [{ "protocol": pairing.relay_protocol }],
+                "requiredNamespaces": {
+                    "eip155": {
+                        "chains": chain_ids.iter().map(|id| format!("eip155:{id}")).collect::<Vec<_>>(),
+                        "methods": METHODS,
+                        "events": EVENTS,
+                    }
+                },
+                "proposer": { "publicKey": hex::encode(public.as_bytes()), "metadata": metadata },
+            }
+        });
+        publish(&relay, pairing.topic, &pairing.sym_key, &proposal, TAG_SESSION_PROPOSE).await?;
+
+        let response = wait_for_response(&relay, pairing.topic, &pairing.sym_key, id).await?;
+        let responder = response
+            .get("responderPublicKey")
+            .and_then(Value::as_str)
+            .ok_or_else(|| WalletConnectError::UnexpectedMessage(response.to_string()))?;
+        let mut responder_key = [0u8; 32];
+        hex::decode_to_slice(responder, &mut responder_key)?;
+
+        let (sym_key, topic) = derive_session_key(&secret, &PublicKey::from(responder_key));
+        relay.subscribe(topic).await.map_err(WalletConnectError::Relay)?;
+
+        // the wallet settles the session on the new topic
+        let settle = loop {
+            let message = next_message(&relay, topic, &sym_key).await?;
+            if message["method"] == "wc_sessionSettle" {
+                break message
+            }
+        };
+        let params = &settle["params"];
+        let session = Session {
+            topic,
+            sym_key: H256(sym_key),
+            accounts: serde_json::from_value(params["namespaces"]["eip155"]["accounts"].clone())?,
+            expiry: params["expiry"]
+                .as_u64()
+                .ok_or_else(|| WalletConnectError::UnexpectedMessage(settle.to_string()))?,
+            peer: serde_json::from_value(params["controller"]["metadata"].clone()).ok(),
+        };
+        let ack = json!({ "id": settle["id"], "jsonrpc": "2.0", "result": true });
+        publish(&relay, topic, &sym_key, &ack, TAG_SESSION_SETTLE_RESPONSE).await?;
+
+        Self::from_session(relay, session).await
+    }
+
+    /// Resumes a previously established session, e.g. one restored with [`Session::load`].
+    ///
+    /// The signer uses the first EVM account of the session.
+    pub async fn from_session(relay: R, session: Session) -> Result<Self, WalletConnectError> {
+        if session.is_expired() {
+            return Err(WalletConnectError::SessionExpired)
+        }
+        let (chain_id, address) =
+            *session.eip155_accounts().first().ok_or(WalletConnectError::NoAccounts)?;
+        relay.subscribe(session.topic).await.map_err(WalletConnectError::Relay)?;
+        Ok(Self { relay, session, address, chain_id })
+    }
+
+    /// Returns the session, e.g. to persist it with [`Session::save`].
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Returns the relay.
+    pub fn relay(&self) -> &R {
+        &self.relay
+    }
+
+    /// Signs EIP-712 typed data with `eth_signTypedData_v4`.
+    ///
+    /// Wallets need the full typed data to display it to the user, which is why this takes a
+    /// [TypedData] rather than any [Eip712] payload.
+    pub async fn sign_typed_data_v4(
+        &self,
+        payload: &TypedData,
+    ) -> Result<Signature, WalletConnectError> {
+        let params = json!([self.address, serde_json::to_string(payload)?]);
+        let signature = self.request("eth_signTypedData_v4", params).await?;
+        parse_signature(&signature)
+    }
+
+    /// Sends `method` as a session request to the wallet and waits for its result.
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, WalletConnectError> {
+        if self.session.is_expired() {
+            return Err(WalletConnectError::SessionExpired)
+        }
+        let sym_key = &self.session.sym_key.0;
+        let id = request_id();
+        let request = json!({
+            "id": id,
+            "jsonrpc": "2.0",
+            "method": "wc_sessionRequest",
+            "params": {
+                "request": { "method": method, "params": params },
+                "chainId": format!("eip155:{}", self.chain_id),
+            }
+        });
+        publish(&self.relay, self.session.topic, sym_key, &request, TAG_SESSION_REQUEST).await?;
+        wait_for_response(&self.relay, self.session.topic, sym_key, id).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R: Relay> super::Signer for WalletConnectSigner<R> {
+    type Error = WalletConnectError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let message = message.as_ref();
+        let params = json!([format!("0x{}", hex::encode(message)), self.address]);
+        let signature = parse_signature(&self.request("personal_sign", params).await?)?;
+        signature
+            .verify(message, self.address)
+            .map_err(|_| WalletConnectError::WrongSigner(self.address))?;
+        Ok(signature)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        tx.set_from(self.address);
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+
+        let raw = self.request("eth_signTransaction", json!([tx])).await?;
+        let raw =
+            raw.as_str().ok_or_else(|| WalletConnectError::UnexpectedMessage(raw.to_string()))?;
+        let raw = hex::decode(raw.strip_prefix("0x").unwrap_or(raw))?;
+        let (signed, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw))?;
+
+        // the signature is only valid for the transaction that was actually signed
+        if signed.sighash() != tx.sighash() {
+            return Err(WalletConnectError::TransactionModified)
+        }
+        Ok(signature)
+    }
+
+    /// Wallets require the full typed data, use
+    /// [`WalletConnectSigner::sign_typed_data_v4`] instead.
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        Err(WalletConnectError::Unsupported("signing an Eip712 digest"))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R: Relay> super::AuthorizationSigner for WalletConnectSigner<R> {
+    async fn sign_authorization(
+        &self,
+        _authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        Err(WalletConnectError::Unsupported("EIP-7702 authorizations"))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R: Relay> super::IntendedValidatorSigner for WalletConnectSigner<R> {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        _validator: Address,
+        _data: S,
+    ) -> Result<Signature, Self::Error> {
+        Err(WalletConnectError::Unsupported("EIP-191 version 0x00"))
+    }
+}
+
+async fn publish<R: Relay>(
+    relay: &R,
+    topic: H256,
+    sym_key: &[u8; 32],
+    message: &Value,
+    tag: u32,
+) -> Result<(), WalletConnectError> {
+    let envelope = seal(sym_key, &serde_json::to_vec(message)?)?;
+    relay.publish(topic, envelope, tag, MESSAGE_TTL).await.map_err(WalletConnectError::Relay)
+}
+
+async fn next_message<R: Relay>(
+    relay: &R,
+    topic: H256,
+    sym_key: &[u8; 32],
+) -> Result<Value, WalletConnectError> {
+    let envelope = relay.next_message(topic).await.map_err(WalletConnectError::Relay)?;
+    Ok(serde_json::from_slice(&open(sym_key, &envelope)?)?)
+}
+
+/// Waits for the response to the request `id`, skipping unrelated messages on the topic.
+async fn wait_for_response<R: Relay>(
+    relay: &R,
+    topic: H256,
+    sym_key: &[u8; 32],
+    id: u64,
+) -> Result<Value, WalletConnectError> {
+    loop {
+        let mut message = next_message(relay, topic, sym_key).await?;
+        if message["method"] == "wc_sessionDelete" {
+            return Err(WalletConnectError::SessionDeleted)
+        }
+        if message["id"].as_u64() != Some(id) || message.get("method").is_some() {
+            continue
+        }
+        if let Some(error) = message.get("error") {
+            return Err(WalletConnectError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            })
+        }
+        return Ok(message["result"].take())
+    }
+}
+
+fn parse_signature(value: &Value) -> Result<Signature, WalletConnectError> {
+    let signature =
+        value.as_str().ok_or_else(|| WalletConnectError::UnexpectedMessage(value.to_string()))?;
+    let signature = Signature::from_str(signature)?;
+    // only plain recovery ids can be recovered from without panicking
+    if !matches!(signature.v, 0 | 1 | 27 | 28) {
+        return Err(WalletConnectError::UnexpectedMessage(value.to_string()))
+    }
+    Ok(signature)
+}
+
+/// JSON-RPC ids are millisecond timestamps with random trailing digits, as in the reference
+/// implementation.
+fn request_id() -> u64 {
+    let millis =
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+    millis * 1000 + rand::thread_rng().gen_range(0..1000)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LocalWallet, Signer};
+    use ethers_core::types::TransactionRequest;
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::Mutex,
+    };
+
+    /// A relay with a wallet on the other side that approves every request.
+    #[derive(Debug)]
+    struct MockWallet {
+        wallet: LocalWallet,
+        session: Session,
+        messages: Mutex<HashMap<H256, VecDeque<String>>>,
+    }
+
+    impl MockWallet {
+        fn new() -> Self {
+            let wallet: LocalWallet =
+                "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+            let session = Session {
+                topic: H256::random(),
+                sym_key: H256::random(),
+                accounts: vec![format!("eip155:1:{:?}", wallet.address())],
+                expiry: now() + 3600,
+                peer: None,
+            };
+            Self { wallet, session, messages: Default::default() }
+        }
+
+        async fn respond(&self, request: Value) -> Value {
+            let params = &request["params"]["request"]["params"];
+            let result = match request["params"]["request"]["method"].as_str().unwrap() {
+                "personal_sign" => {
+                    let message = hex::decode(&params[0].as_str().unwrap()[2..]).unwrap();
+                    json!(self.wallet.sign_message(message).await.unwrap().to_string())
+                }
+                "eth_signTransaction" => {
+                    let tx: TypedTransaction = serde_json::from_value(params[0].clone()).unwrap();
+                    let signature = self.wallet.sign_transaction(&tx).await.unwrap();
+                    json!(tx.rlp_signed(&signature))
+                }
+                _ => {
+                    return json!({ "id": request["id"], "error": { "code": 5001, "message": "User rejected." } })
+                }
+            };
+            json!({ "id": request["id"], "jsonrpc": "2.0", "result": result })
+        }
+    }
+
+    #[async_trait]
+    impl Relay for MockWallet {
+        async fn subscribe(&self, _topic: H256) -> Result<(), BoxError> {
+            Ok(())
+        }
+
+        async fn publish(
+            &self,
+            topic: H256,
+            message: String,
+            _tag: u32,
+            _ttl: Duration,
+        ) -> Result<(), BoxError> {
+            let key = &self.session.sym_key.0;
+            let request = serde_json::from_slice(&open(key, &message)?)?;
+            let response = seal(key, &serde_json::to_vec(&self.respond(request).await)?)?;
+            // an unrelated message ahead of the response
+            let ping =
+                seal(key, br#"{"id":1,"jsonrpc":"2.0","method":"wc_sessionPing","params":{}}"#)?;
+            self.messages.lock().unwrap().entry(topic).or_default().extend([ping, response]);
+            Ok(())
+        }
+
+        async fn next_message(&self, topic: H256) -> Result<String, BoxError> {
+            self.messages
+                .lock()
+                .unwrap()
+                .get_mut(&topic)
+                .and_then(VecDeque::pop_front)
+                .ok_or_else(|| "no message".into())
+        }
+    }
+
+    #[test]
+    fn pairing_uri_roundtrip() {
+        let pairing = PairingUri::generate(&mut rand::thread_rng(), Duration::from_secs(300));
+        let uri = pairing.to_string();
+        assert!(uri.starts_with(&format!(
+            "wc:{}@2?relay-protocol=irn&symKey=",
+            hex::encode(pairing.topic)
+        )));
+        assert_eq!(uri.parse::<PairingUri>().unwrap(), pairing);
+
+        let uri = "wc:7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9@2?relay-protocol=irn&symKey=587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303";
+        let pairing: PairingUri = uri.parse().unwrap();
+        assert_eq!(pairing.expiry, None);
+        assert_eq!(pairing.to_string(), uri);
+
+        assert!("wc:00@1?relay-protocol=irn&symKey=00".parse::<PairingUri>().is_err());
+    }
+
+    #[test]
+    fn envelope_roundtrip() {
+        let key = [7u8; 32];
+        let envelope = seal(&key, b"hello").unwrap();
+        assert_eq!(open(&key, &envelope).unwrap(), b"hello");
+        assert!(matches!(open(&[8u8; 32], &envelope), Err(WalletConnectError::Encryption)));
+    }
+
+    #[test]
+    fn key_agreement() {
+        let a = StaticSecret::random_from_rng(rand::thread_rng());
+        let b = StaticSecret::random_from_rng(rand::thread_rng());
+        let (key, topic) = derive_session_key(&a, &PublicKey::from(&b));
+        assert_eq!(derive_session_key(&b, &PublicKey::from(&a)), (key, topic));
+        assert_eq!(topic, H256::from_slice(&Sha256::digest(key)));
+    }
+
+    #[test]
+    fn persists_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let session = MockWallet::new().session;
+
+        session.save(&path).unwrap();
+        assert_eq!(Session::load(&path).unwrap(), session);
+        assert_eq!(session.eip155_accounts().len(), 1);
+        assert!(!session.is_expired());
+    }
+
+    #[tokio::test]
+    async fn signs_through_session() {
+        let relay = MockWallet::new();
+        let (session, wallet) = (relay.session.clone(), relay.wallet.clone());
+        let signer = WalletConnectSigner::from_session(relay, session).await.unwrap();
+        assert_eq!(signer.address(), wallet.address());
+
+        let signature = signer.sign_message("hello").await.unwrap();
+        assert_eq!(signature, wallet.sign_message("hello").await.unwrap());
+
+        let tx: TypedTransaction =
+            TransactionRequest::pay(Address::zero(), 100).nonce(0).gas(21000).gas_price(1).into();
+        let signature = signer.sign_transaction(&tx).await.unwrap();
+        let mut expected = tx.clone();
+        expected.set_from(wallet.address());
+        expected.set_chain_id(1);
+        assert_eq!(signature, wallet.sign_transaction(&expected).await.unwrap());
+
+        let err = signer.request("eth_sign", json!([])).await.unwrap_err();
+        assert!(matches!(err, WalletConnectError::Rpc { code: 5001, .. }));
+    }
+}
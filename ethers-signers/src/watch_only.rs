@@ -0,0 +1,128 @@
+use crate::{AuthorizationSigner, IntendedValidatorSigner, Signer};
+use async_trait::async_trait;
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip712::Eip712, eip7702::Authorization},
+    Address, Signature,
+};
+use thiserror::Error;
+
+/// A [`Signer`] for an address without its key, which fails to sign anything.
+///
+/// Use it to run code that requires a `Signer`, like building transactions or estimating gas with
+/// a `SignerMiddleware`, for accounts whose keys are not available.
+///
+/// ```
+/// use ethers_core::types::Address;
+/// use ethers_signers::{Signer, WatchOnlySigner};
+///
+/// # async fn foo() {
+/// let signer = WatchOnlySigner::new(Address::repeat_byte(1)).with_chain_id(5u64);
+/// assert_eq!(signer.chain_id(), 5);
+/// assert!(signer.sign_message("hello").await.is_err());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchOnlySigner {
+    address: Address,
+    chain_id: u64,
+}
+
+/// Error thrown when a [`WatchOnlySigner`] is asked to sign
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("cannot sign for watch-only address {0:?}")]
+pub struct WatchOnlySignerError(pub Address);
+
+impl WatchOnlySigner {
+    /// Instantiates a watch-only signer for `address` on mainnet.
+    pub fn new(address: Address) -> Self {
+        Self { address, chain_id: 1 }
+    }
+
+    fn error<T>(&self) -> Result<T, WatchOnlySignerError> {
+        Err(WatchOnlySignerError(self.address))
+    }
+}
+
+impl From<Address> for WatchOnlySigner {
+    fn from(address: Address) -> Self {
+        Self::new(address)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Signer for WatchOnlySigner {
+    type Error = WatchOnlySignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        _message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.error()
+    }
+
+    async fn sign_transaction(&self, _tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        self.error()
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        self.error()
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthorizationSigner for WatchOnlySigner {
+    async fn sign_authorization(
+        &self,
+        _authorization: &Authorization,
+    ) -> Result<Signature, Self::Error> {
+        self.error()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IntendedValidatorSigner for WatchOnlySigner {
+    async fn sign_intended_validator<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        _validator: Address,
+        _data: S,
+    ) -> Result<Signature, Self::Error> {
+        self.error()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::TransactionRequest;
+
+    #[tokio::test]
+    async fn refuses_to_sign() {
+        let address = Address::repeat_byte(1);
+        let signer = WatchOnlySigner::from(address).with_chain_id(5u64);
+        assert_eq!(signer.address(), address);
+        assert_eq!(signer.chain_id(), 5);
+
+        let tx = TransactionRequest::new().from(address).into();
+        assert_eq!(signer.sign_transaction(&tx).await.unwrap_err(), WatchOnlySignerError(address));
+        assert!(signer.sign_message("hello").await.is_err());
+    }
+}
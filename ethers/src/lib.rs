@@ -44,6 +44,12 @@
 //! type is implemented which can be used with a raw private key or a YubiHSM2. Ledger and Trezor
 //! support are also provided.
 //!
+//! Hardware and remote signers are enabled with the feature of the same name on this crate, which
+//! also makes them available in the `prelude`: `ledger`, `trezor`, `yubi`, `piv`, `aws`, `gcp`,
+//! `remote` and `walletconnect`. The `bls` feature adds BLS12-381 signing and EIP-2335 keystores
+//! of the consensus layer, the `test-vectors` feature known-answer tests to check other signer
+//! implementations against.
+//!
 //! ### `contract`
 //!
 //! Interacting with Ethereum is not restricted to sending or receiving funds. It also involves
@@ -1,5 +1,6 @@
 use ethers::{
     contract::abigen,
+    middleware::NonceManagerMiddleware,
     prelude::{Provider, SignerMiddleware},
     providers::{Middleware, Ws},
     signers::Signer,
@@ -34,6 +35,7 @@ pub async fn deploy() {
     let chain_id = provider.get_chainid().await.unwrap();
     let wallet = utils::key(0).with_chain_id(chain_id.as_u64());
     log!("Wallet: {wallet:?}");
+    let provider = NonceManagerMiddleware::new(provider, wallet.address());
     let client = Arc::new(SignerMiddleware::new(provider, wallet));
 
     log!("Deploying contract...");